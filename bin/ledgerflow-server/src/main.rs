@@ -12,6 +12,10 @@ struct Cli {
     /// Path to the revocation store (JSON Lines file).
     #[arg(long, default_value = "./data/revocations.jsonl")]
     revocation_store: std::path::PathBuf,
+    /// SQLite database persisting settlements and replay state.
+    #[cfg(feature = "sqlite")]
+    #[arg(long)]
+    database: Option<std::path::PathBuf>,
 }
 
 #[tokio::main]
@@ -26,25 +30,33 @@ async fn main() -> Result<()> {
     let config = ServerConfig::from_env().wrap_err("invalid configuration (fail-fast)")?;
     println!("ledgerflow-server: bind={} mode={:?}", config.bind_addr, config.saas.mode);
 
-    // The trust anchor is derived from the configured issuer key (fail-fast
-    // guarantees it is present; never a predictable demo key — design §6.8).
-    let issuer_key_hex = config
-        .issuer_key_hex
-        .clone()
-        .ok_or_else(|| eyre::eyre!("LEDGERFLOW_ISSUER_KEY is required (fail-fast)"))?;
-    let issuer_key_bytes = hex_decode(&issuer_key_hex)
-        .ok_or_else(|| eyre::eyre!("LEDGERFLOW_ISSUER_KEY must be 32-byte hex"))?;
-    let issuer_key = ledgerflow_core::SigningKeyPair::from_bytes(&issuer_key_bytes);
+    // The trust anchor is derived from the configured issuer key or wallet
+    // key (fail-fast guarantees one is present; never a predictable demo key —
+    // design §6.8).
+    let issuer = ledgerflow_server::load_issuer(&config).wrap_err("invalid issuer")?;
 
-    let state = AppState::new(config.clone(), &cli.revocation_store, {
+    // A configured trusted-issuers file is authoritative (and reloadable via
+    // `POST /v1/admin/trust-anchors/reload`); otherwise trust the issuer key.
+    let trusted = if let Some(path) = &config.trusted_issuers_file {
+        ledgerflow_server::load_trusted_issuers(path)
+            .wrap_err("failed to load the trusted-issuers file")?
+    } else {
         let mut trusted = ledgerflow_core::TrustedIssuers::new();
-        trusted.add(ledgerflow_core::TrustedIssuer::new(
-            "issuer-1".to_string(),
-            issuer_key.signer_ref(),
-        ));
         trusted
-    })
-    .wrap_err("failed to initialize application state")?;
+            .add(ledgerflow_core::TrustedIssuer::new("issuer-1".to_string(), issuer.signer_ref()));
+        trusted
+    };
+    let state = AppState::new(config.clone(), &cli.revocation_store, trusted)
+        .wrap_err("failed to initialize application state")?;
+    #[cfg(feature = "sqlite")]
+    let state = match &cli.database {
+        Some(path) => {
+            let store = ledgerflow_server::SqliteStore::open(path)
+                .wrap_err("failed to open the SQLite database")?;
+            state.with_sqlite(&store).wrap_err("failed to attach the SQLite database")?
+        }
+        None => state,
+    };
 
     let saas_extractor = state.saas.clone();
     let app = ledgerflow_server::api::router()
@@ -56,17 +68,3 @@ async fn main() -> Result<()> {
     println!("listening on {}", config.bind_addr);
     axum::serve(listener, app).await.wrap_err("server error")
 }
-
-/// Decodes a 32-byte hex string into bytes.
-fn hex_decode(hex: &str) -> Option<[u8; 32]> {
-    let hex = hex.trim();
-    if hex.len() != 64 {
-        return None;
-    }
-    let mut out = [0_u8; 32];
-    for (i, chunk) in hex.as_bytes().chunks(2).enumerate() {
-        let text = std::str::from_utf8(chunk).ok()?;
-        out[i] = u8::from_str_radix(text, 16).ok()?;
-    }
-    Some(out)
-}
@@ -120,6 +120,8 @@ fn bench_verify_authorization_hot_path(criterion: &mut Criterion) {
                 tool_arguments: &tool_arguments,
                 revocation: &revocation,
                 payment_payload_digest: None,
+                contract_verifier: None,
+                identity_resolver: None,
             };
             let result = verify_authorization(&input);
             assert!(result.is_ok());
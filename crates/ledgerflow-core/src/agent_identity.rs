@@ -149,7 +149,7 @@ pub fn agent_id_from_warrant(warrant: &Warrant) -> Result<Option<AgentIdRef>, Ag
 ///
 /// Implemented by downstream crates over chain RPC / IPFS / caches. The core
 /// stays stateless and I/O-free.
-pub trait IdentityResolver: Send + Sync {
+pub trait IdentityResolver: std::fmt::Debug + Send + Sync {
     /// Returns the set of signer keys currently bound to the agent identity.
     ///
     /// # Errors
@@ -160,6 +160,9 @@ pub trait IdentityResolver: Send + Sync {
     fn resolve_keys(&self, agent: &AgentIdRef) -> crate::error::Result<Vec<SignerRef>>;
 }
 
+/// Shared identity resolver handle.
+pub type SharedIdentityResolver = std::sync::Arc<dyn IdentityResolver>;
+
 #[cfg(test)]
 mod tests {
     #![allow(clippy::expect_used)]
@@ -3,13 +3,16 @@
 //! A warrant may declare that certain tool calls (matching optional argument
 //! constraints) require approval from `required_approvers`, with a threshold
 //! of `min_approvals`. Approvals are single-layer signatures: they cannot be
-//! delegated, and only keys listed in `required_approvers` are accepted.
+//! delegated, and only keys listed in `required_approvers` are accepted. A
+//! warrant may instead reference a rotatable approver group
+//! ([`crate::approver_set`]), whose current members take that role.
 
 use std::collections::BTreeMap;
 
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    erc1271::{ContractSignatureVerifier, verify_signature_with},
     error::{AuthorizationError, Result},
     pop::PopTuple,
     warrant::{SignatureEnvelope, SignerRef, SigningKeyPair, sha256_prefixed},
@@ -61,7 +64,16 @@ impl SignedApproval {
 
     /// Verifies this approval signature using **strict** Ed25519 verification.
     pub fn verify_signature(&self) -> bool {
-        self.signature.verify_strict(&self.approver, &self.preimage())
+        self.verify_signature_with(None)
+    }
+
+    /// [`Self::verify_signature`], accepting a contract-account approver
+    /// through the ERC-1271 seam.
+    pub fn verify_signature_with(
+        &self,
+        contract_verifier: Option<&dyn ContractSignatureVerifier>,
+    ) -> bool {
+        verify_signature_with(&self.signature, &self.approver, &self.preimage(), contract_verifier)
     }
 
     /// CBOR-encodes this approval (used for `approvals_digest` and transport).
@@ -140,6 +152,28 @@ pub fn verify_approvals(
     request_hash: &str,
     now_ms: u64,
     pop_tuple: &PopTuple,
+) -> Result<ApprovalVerification> {
+    verify_approvals_with(
+        approvals,
+        required_approvers,
+        min_approvals,
+        request_hash,
+        now_ms,
+        pop_tuple,
+        None,
+    )
+}
+
+/// [`verify_approvals`], accepting contract-account approvers through the
+/// ERC-1271 seam.
+pub fn verify_approvals_with(
+    approvals: &[SignedApproval],
+    required_approvers: &[SignerRef],
+    min_approvals: u32,
+    request_hash: &str,
+    now_ms: u64,
+    pop_tuple: &PopTuple,
+    contract_verifier: Option<&dyn ContractSignatureVerifier>,
 ) -> Result<ApprovalVerification> {
     if approvals.is_empty() {
         return Err(AuthorizationError::ApprovalRequired);
@@ -151,34 +185,14 @@ pub fn verify_approvals(
         return Err(AuthorizationError::ApprovalsDigestMismatch);
     }
 
-    let now_secs = now_ms / 1000;
-    let mut valid: Vec<Vec<u8>> = Vec::new();
-    for approval in approvals {
-        if approval.request_hash != request_hash {
-            return Err(AuthorizationError::ApprovalRequestMismatch);
-        }
-        if approval.expires_at < now_secs {
-            return Err(AuthorizationError::ApprovalExpired);
-        }
-        if !required_approvers.contains(&approval.approver) {
-            return Err(AuthorizationError::ApproverNotAllowed);
-        }
-        if !approval.verify_signature() {
-            return Err(AuthorizationError::InvalidApprovalSignature);
-        }
-        if !valid.iter().any(|key| key == &approval.approver.public_key) {
-            valid.push(approval.approver.public_key.clone());
-        }
-    }
-
-    let valid_count = valid.len() as u32;
-    let threshold =
-        if min_approvals == 0 { required_approvers.len() as u32 } else { min_approvals };
-    if valid_count < threshold {
-        return Err(AuthorizationError::InsufficientApprovals { got: valid_count, need: threshold });
-    }
-
-    Ok(ApprovalVerification { valid_count, threshold })
+    verify_approval_threshold_with(
+        approvals,
+        required_approvers,
+        min_approvals,
+        request_hash,
+        now_ms,
+        contract_verifier,
+    )
 }
 
 /// Verifies the m-of-n threshold only (used when gates did not fire).
@@ -188,6 +202,26 @@ pub fn verify_approval_threshold(
     min_approvals: u32,
     request_hash: &str,
     now_ms: u64,
+) -> Result<ApprovalVerification> {
+    verify_approval_threshold_with(
+        approvals,
+        required_approvers,
+        min_approvals,
+        request_hash,
+        now_ms,
+        None,
+    )
+}
+
+/// [`verify_approval_threshold`], accepting contract-account approvers
+/// through the ERC-1271 seam.
+pub fn verify_approval_threshold_with(
+    approvals: &[SignedApproval],
+    required_approvers: &[SignerRef],
+    min_approvals: u32,
+    request_hash: &str,
+    now_ms: u64,
+    contract_verifier: Option<&dyn ContractSignatureVerifier>,
 ) -> Result<ApprovalVerification> {
     // Digest check is skipped here; callers that also possess the PoP tuple
     // should use [`verify_approvals`].
@@ -203,7 +237,7 @@ pub fn verify_approval_threshold(
         if !required_approvers.contains(&approval.approver) {
             return Err(AuthorizationError::ApproverNotAllowed);
         }
-        if !approval.verify_signature() {
+        if !approval.verify_signature_with(contract_verifier) {
             return Err(AuthorizationError::InvalidApprovalSignature);
         }
         if !valid.iter().any(|key| key == &approval.approver.public_key) {
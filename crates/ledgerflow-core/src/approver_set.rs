@@ -0,0 +1,328 @@
+//! Rotatable approver groups for approval gates (design §6.5).
+//!
+//! Instead of pinning `required_approvers` at issuance, a warrant may carry an
+//! [`ApproverSetRef`] under the [`APPROVER_SET_EXTENSION`] key. The reference
+//! names a versioned [`ApproverSet`] document and pins the **set authority**
+//! key that signs every version. Verifiers resolve the current version
+//! through an [`ApproverSetResolver`] and accept it when:
+//!
+//! - it is signed (strictly) by the pinned authority and has the referenced name;
+//! - its version is at least the referenced version, and the referenced version itself matches the
+//!   pinned digest.
+//!
+//! Rotating an approver therefore only needs a new signed version; warrants
+//! issued against an earlier version keep working. When a warrant references
+//! a set, the set's members replace `required_approvers`; `min_approvals`
+//! still applies (0 = every current member).
+
+use std::{collections::BTreeMap, sync::Arc};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{AuthorizationError, Result, WireResult},
+    warrant::{CborCodec, SignatureEnvelope, SignerRef, SigningKeyPair, Warrant, sha256_prefixed},
+};
+
+/// Domain-separation prefix for approver-set signatures.
+pub const APPROVER_SET_SIGN_DOMAIN: &[u8] = b"ledgerflow-approver-set-v1";
+
+/// Warrant extension key carrying a CBOR [`ApproverSetRef`].
+pub const APPROVER_SET_EXTENSION: &str = "ledgerflow.approver_set";
+
+/// One version of a named approver group.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ApproverSet {
+    pub name: String,
+    /// Monotonic version (starts at 1).
+    pub version: u64,
+    pub approvers: Vec<SignerRef>,
+    /// Digest of the version this one replaces (`None` for version 1).
+    pub previous: Option<String>,
+    /// Unix seconds when this version was published.
+    pub issued_at: u64,
+}
+
+impl CborCodec for ApproverSet {}
+
+impl ApproverSet {
+    /// Creates version 1 of a group.
+    #[must_use]
+    pub fn new(name: impl Into<String>, approvers: Vec<SignerRef>, issued_at: u64) -> Self {
+        Self { name: name.into(), version: 1, approvers, previous: None, issued_at }
+    }
+
+    /// Content digest (`sha256:` of the CBOR document).
+    pub fn digest(&self) -> WireResult<String> {
+        Ok(sha256_prefixed(self.encode_cbor()?))
+    }
+
+    /// Signs this version with the set authority key.
+    pub fn sign_with(self, authority_keys: &SigningKeyPair) -> WireResult<SignedApproverSet> {
+        let signature = authority_keys.sign(&signing_message(&self.encode_cbor()?));
+        Ok(SignedApproverSet { set: self, authority: authority_keys.signer_ref(), signature })
+    }
+}
+
+/// A signed approver-set version.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct SignedApproverSet {
+    pub set: ApproverSet,
+    /// The set authority that signed this version.
+    pub authority: SignerRef,
+    pub signature: SignatureEnvelope,
+}
+
+impl CborCodec for SignedApproverSet {}
+
+impl SignedApproverSet {
+    /// Verifies the authority signature (strict).
+    #[must_use]
+    pub fn verify_signature(&self) -> bool {
+        self.set.encode_cbor().is_ok_and(|bytes| {
+            self.signature.verify_strict(&self.authority, &signing_message(&bytes))
+        })
+    }
+
+    /// The reference a warrant embeds to pin this version.
+    pub fn reference(&self) -> WireResult<ApproverSetRef> {
+        Ok(ApproverSetRef {
+            name: self.set.name.clone(),
+            version: self.set.version,
+            digest: self.set.digest()?,
+            authority: self.authority.clone(),
+        })
+    }
+
+    /// Drafts the next version with a new member list (sign it with
+    /// [`ApproverSet::sign_with`]).
+    pub fn rotate(&self, approvers: Vec<SignerRef>, issued_at: u64) -> WireResult<ApproverSet> {
+        Ok(ApproverSet {
+            name: self.set.name.clone(),
+            version: self.set.version + 1,
+            approvers,
+            previous: Some(self.set.digest()?),
+            issued_at,
+        })
+    }
+}
+
+fn signing_message(set_cbor: &[u8]) -> Vec<u8> {
+    let mut message = Vec::with_capacity(APPROVER_SET_SIGN_DOMAIN.len() + set_cbor.len());
+    message.extend_from_slice(APPROVER_SET_SIGN_DOMAIN);
+    message.extend_from_slice(set_cbor);
+    message
+}
+
+/// A warrant's pointer to an approver group.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct ApproverSetRef {
+    pub name: String,
+    /// The version current at issuance.
+    pub version: u64,
+    /// Digest of that version.
+    pub digest: String,
+    /// The only key allowed to sign versions of this set.
+    pub authority: SignerRef,
+}
+
+impl CborCodec for ApproverSetRef {}
+
+impl ApproverSetRef {
+    /// Encodes the reference as extension bytes.
+    pub fn encode_cbor(&self) -> WireResult<Vec<u8>> {
+        <Self as CborCodec>::encode_cbor(self)
+    }
+
+    /// Checks that `current` is a valid version of the referenced set and
+    /// returns its members.
+    pub fn accept(&self, current: &SignedApproverSet) -> Result<Vec<SignerRef>> {
+        let invalid = |detail: &str| AuthorizationError::InvalidApproverSet {
+            name: self.name.clone(),
+            detail: detail.to_string(),
+        };
+        if current.set.name != self.name {
+            return Err(invalid("resolved set has a different name"));
+        }
+        if current.authority.alg != self.authority.alg ||
+            current.authority.public_key != self.authority.public_key
+        {
+            return Err(invalid("resolved set is not signed by the pinned authority"));
+        }
+        if !current.verify_signature() {
+            return Err(invalid("invalid authority signature"));
+        }
+        if current.set.version < self.version {
+            return Err(invalid("resolved version is older than the referenced version"));
+        }
+        if current.set.version == self.version &&
+            current.set.digest().map_err(|error| invalid(&error.to_string()))? != self.digest
+        {
+            return Err(invalid("resolved document does not match the pinned digest"));
+        }
+        Ok(current.set.approvers.clone())
+    }
+}
+
+impl Warrant {
+    /// Returns the approver-set reference carried by this warrant, if any.
+    ///
+    /// A present-but-undecodable reference is an error (fail closed).
+    pub fn approver_set_ref(&self) -> WireResult<Option<ApproverSetRef>> {
+        self.extensions
+            .get(APPROVER_SET_EXTENSION)
+            .map(|bytes| <ApproverSetRef as CborCodec>::decode_cbor(bytes))
+            .transpose()
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Resolution
+// ---------------------------------------------------------------------------
+
+/// Resolves the current version of a referenced approver set.
+///
+/// Implemented downstream over storage or an HTTP directory; the core stays
+/// I/O-free. Implementations return the newest version they know; the
+/// verifier checks it against the reference ([`ApproverSetRef::accept`]).
+pub trait ApproverSetResolver: std::fmt::Debug + Send + Sync {
+    /// # Errors
+    /// [`AuthorizationError::ApproverSetUnavailable`] when the set cannot be
+    /// resolved.
+    fn resolve(&self, reference: &ApproverSetRef) -> Result<SignedApproverSet>;
+}
+
+/// Shared approver-set resolver handle.
+pub type SharedApproverSetResolver = Arc<dyn ApproverSetResolver>;
+
+/// The approvers that may satisfy `warrant`'s gates: the resolved current
+/// approver set when the warrant references one, else `required_approvers`.
+///
+/// A referenced set without a resolver fails closed.
+pub fn effective_approvers(
+    warrant: &Warrant,
+    resolver: Option<&dyn ApproverSetResolver>,
+) -> Result<Vec<SignerRef>> {
+    let reference = warrant.approver_set_ref().map_err(|error| {
+        AuthorizationError::InvalidApproverSet { name: String::new(), detail: error.to_string() }
+    })?;
+    let Some(reference) = reference else {
+        return Ok(warrant.required_approvers.clone());
+    };
+    let resolver = resolver.ok_or_else(|| AuthorizationError::ApproverSetUnavailable {
+        name: reference.name.clone(),
+        detail: "no approver-set resolver configured".to_string(),
+    })?;
+    reference.accept(&resolver.resolve(&reference)?)
+}
+
+/// In-memory resolver keeping the newest published version of each set.
+/// Suitable for tests and single-process deployments.
+#[derive(Clone, Debug, Default)]
+pub struct InMemoryApproverSetResolver {
+    sets: BTreeMap<String, SignedApproverSet>,
+}
+
+impl InMemoryApproverSetResolver {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Publishes a version; older versions than the one held are ignored.
+    pub fn publish(&mut self, set: SignedApproverSet) {
+        let newer =
+            self.sets.get(&set.set.name).is_none_or(|held| held.set.version < set.set.version);
+        if newer {
+            self.sets.insert(set.set.name.clone(), set);
+        }
+    }
+}
+
+impl ApproverSetResolver for InMemoryApproverSetResolver {
+    fn resolve(&self, reference: &ApproverSetRef) -> Result<SignedApproverSet> {
+        self.sets.get(&reference.name).cloned().ok_or_else(|| {
+            AuthorizationError::ApproverSetUnavailable {
+                name: reference.name.clone(),
+                detail: "unknown approver set".to_string(),
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::expect_used)]
+
+    use super::*;
+
+    fn keys(tag: u8) -> SigningKeyPair {
+        SigningKeyPair::from_bytes(&[tag; 32])
+    }
+
+    fn first_version() -> SignedApproverSet {
+        ApproverSet::new("treasury", vec![keys(1).signer_ref(), keys(2).signer_ref()], 100)
+            .sign_with(&keys(9))
+            .expect("sign")
+    }
+
+    #[test]
+    fn rotation_keeps_outstanding_references_valid() {
+        let v1 = first_version();
+        let reference = v1.reference().expect("ref");
+        assert_eq!(reference.accept(&v1).expect("v1").len(), 2);
+
+        let v2 = v1
+            .rotate(vec![keys(2).signer_ref(), keys(3).signer_ref()], 200)
+            .expect("draft")
+            .sign_with(&keys(9))
+            .expect("sign");
+        assert_eq!(v2.set.previous, Some(v1.set.digest().expect("digest")));
+        let members = reference.accept(&v2).expect("rotated");
+        assert!(members.contains(&keys(3).signer_ref()));
+        assert!(!members.contains(&keys(1).signer_ref()));
+
+        // A reference pinned to v2 rejects a rollback to v1.
+        let pinned = v2.reference().expect("ref");
+        assert!(matches!(pinned.accept(&v1), Err(AuthorizationError::InvalidApproverSet { .. })));
+    }
+
+    #[test]
+    fn foreign_or_tampered_versions_are_rejected() {
+        let v1 = first_version();
+        let reference = v1.reference().expect("ref");
+
+        let forged = v1.rotate(vec![keys(7).signer_ref()], 200).expect("draft");
+        let forged = forged.sign_with(&keys(8)).expect("sign");
+        assert!(reference.accept(&forged).is_err());
+
+        let mut tampered = v1.clone();
+        tampered.set.approvers.push(keys(7).signer_ref());
+        assert!(reference.accept(&tampered).is_err());
+
+        let mut renamed = v1;
+        renamed.set.name = "other".to_string();
+        assert!(reference.accept(&renamed).is_err());
+    }
+
+    #[test]
+    fn in_memory_resolver_serves_the_newest_version() {
+        let v1 = first_version();
+        let v2 = v1
+            .rotate(vec![keys(3).signer_ref()], 200)
+            .expect("draft")
+            .sign_with(&keys(9))
+            .expect("sign");
+        let mut resolver = InMemoryApproverSetResolver::new();
+        resolver.publish(v2.clone());
+        resolver.publish(v1.clone());
+        let reference = v1.reference().expect("ref");
+        assert_eq!(resolver.resolve(&reference).expect("resolve"), v2);
+
+        let unknown = ApproverSetRef { name: "missing".to_string(), ..reference };
+        assert!(matches!(
+            resolver.resolve(&unknown),
+            Err(AuthorizationError::ApproverSetUnavailable { .. })
+        ));
+    }
+}
@@ -0,0 +1,248 @@
+//! Accounting-point budgets: periodic and cumulative spend limits.
+//!
+//! The v1 constraint set is purely stateless (design §1): a warrant can cap a
+//! single charge, but nothing bounds how many charges its holder makes. The
+//! accounting-point mode (design §14, P2) closes that gap. A warrant declares
+//! a [`BudgetPolicy`] under the reserved extension key [`BUDGET_EXTENSION`]
+//! (`ledgerflow.ledger`); because extensions are covered by the issuer
+//! signature, the policy is as tamper-proof as the rest of the warrant.
+//!
+//! The policy names exactly one accounting point (a Facilitator). Only that
+//! Facilitator may settle against the budget, so an agent cannot overdraw by
+//! spreading charges across accounting points. Enforcement itself is stateful
+//! and lives out of crate (`ledgerflow-facilitator`'s `BudgetLedger`); this
+//! module only defines the signed declaration and the window arithmetic.
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::WireResult,
+    warrant::{CborCodec, Warrant},
+};
+
+/// Reserved extension key carrying the budget policy.
+pub const BUDGET_EXTENSION: &str = "ledgerflow.ledger";
+
+const SECS_PER_HOUR: u64 = 3_600;
+const SECS_PER_DAY: u64 = 86_400;
+
+/// Accounting window a budget limit applies to.
+///
+/// Hour and day windows are aligned to UTC boundaries; month windows follow
+/// the UTC calendar month. The lifetime window never rolls over.
+#[derive(Clone, Copy, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BudgetPeriod {
+    Hour,
+    Day,
+    Month,
+    Lifetime,
+}
+
+impl BudgetPeriod {
+    /// All periods, shortest first.
+    pub const ALL: [Self; 4] = [Self::Hour, Self::Day, Self::Month, Self::Lifetime];
+
+    /// Returns the index of the window containing `now_secs`.
+    ///
+    /// Two timestamps fall in the same window iff their window indices are
+    /// equal, so a ledger can key its counters by `(period, window)`.
+    #[must_use]
+    pub fn window(self, now_secs: u64) -> u64 {
+        match self {
+            Self::Hour => now_secs / SECS_PER_HOUR,
+            Self::Day => now_secs / SECS_PER_DAY,
+            Self::Month => {
+                let (year, month) = civil_year_month(now_secs / SECS_PER_DAY);
+                year * 12 + (month - 1)
+            }
+            Self::Lifetime => 0,
+        }
+    }
+
+    /// Stable lowercase label (used in error messages and logs).
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Hour => "hour",
+            Self::Day => "day",
+            Self::Month => "month",
+            Self::Lifetime => "lifetime",
+        }
+    }
+}
+
+impl std::fmt::Display for BudgetPeriod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Signed budget declaration bound to one accounting point.
+///
+/// Amounts are in the same atomic units as
+/// [`PaymentConstraint::max_per_charge`](crate::PaymentConstraint). A `None`
+/// limit leaves that window unbounded.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+pub struct BudgetPolicy {
+    /// Accounting point (Facilitator identifier or URL) that enforces the
+    /// budget.
+    pub ledger: String,
+    /// Maximum spend per UTC hour.
+    pub per_hour: Option<u128>,
+    /// Maximum spend per UTC day.
+    pub per_day: Option<u128>,
+    /// Maximum spend per UTC calendar month.
+    pub per_month: Option<u128>,
+    /// Maximum cumulative spend over the warrant's lifetime.
+    pub lifetime: Option<u128>,
+}
+
+impl BudgetPolicy {
+    /// Creates a policy bound to `ledger` with no limits set.
+    #[must_use]
+    pub fn new(ledger: impl Into<String>) -> Self {
+        Self {
+            ledger: ledger.into(),
+            per_hour: None,
+            per_day: None,
+            per_month: None,
+            lifetime: None,
+        }
+    }
+
+    /// Sets the per-hour limit.
+    #[must_use]
+    pub const fn with_per_hour(mut self, limit: u128) -> Self {
+        self.per_hour = Some(limit);
+        self
+    }
+
+    /// Sets the per-day limit.
+    #[must_use]
+    pub const fn with_per_day(mut self, limit: u128) -> Self {
+        self.per_day = Some(limit);
+        self
+    }
+
+    /// Sets the per-month limit.
+    #[must_use]
+    pub const fn with_per_month(mut self, limit: u128) -> Self {
+        self.per_month = Some(limit);
+        self
+    }
+
+    /// Sets the lifetime limit.
+    #[must_use]
+    pub const fn with_lifetime(mut self, limit: u128) -> Self {
+        self.lifetime = Some(limit);
+        self
+    }
+
+    /// Returns the limit for `period`, if any.
+    #[must_use]
+    pub const fn limit(&self, period: BudgetPeriod) -> Option<u128> {
+        match period {
+            BudgetPeriod::Hour => self.per_hour,
+            BudgetPeriod::Day => self.per_day,
+            BudgetPeriod::Month => self.per_month,
+            BudgetPeriod::Lifetime => self.lifetime,
+        }
+    }
+
+    /// Iterates the configured `(period, limit)` pairs, shortest period first.
+    pub fn limits(&self) -> impl Iterator<Item = (BudgetPeriod, u128)> + '_ {
+        BudgetPeriod::ALL.into_iter().filter_map(|period| Some((period, self.limit(period)?)))
+    }
+
+    /// Encodes the policy as CBOR bytes (for embedding in `extensions`).
+    pub fn encode_cbor(&self) -> WireResult<Vec<u8>> {
+        <Self as CborCodec>::encode_cbor(self)
+    }
+
+    /// Decodes a policy from CBOR bytes.
+    pub fn decode_cbor(bytes: &[u8]) -> WireResult<Self> {
+        <Self as CborCodec>::decode_cbor(bytes)
+    }
+}
+
+impl CborCodec for BudgetPolicy {}
+
+impl Warrant {
+    /// Returns the budget policy carried by this warrant, if any.
+    ///
+    /// Unlike advisory extensions, a present-but-undecodable policy is an
+    /// error: budgets are a security control and must fail closed rather
+    /// than silently disappear.
+    pub fn budget_policy(&self) -> WireResult<Option<BudgetPolicy>> {
+        self.extensions
+            .get(BUDGET_EXTENSION)
+            .map(|bytes| BudgetPolicy::decode_cbor(bytes))
+            .transpose()
+    }
+}
+
+/// Converts days since the unix epoch to a proleptic Gregorian
+/// `(year, month)` pair (Howard Hinnant's `civil_from_days`).
+fn civil_year_month(days: u64) -> (u64, u64) {
+    let z = days + 719_468;
+    let era = z / 146_097;
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + u64::from(month <= 2);
+    (year, month)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::expect_used)]
+
+    use super::*;
+
+    #[test]
+    fn policy_roundtrips_through_cbor() {
+        let policy = BudgetPolicy::new("https://ledger.example")
+            .with_per_hour(100)
+            .with_per_day(1_000)
+            .with_lifetime(10_000);
+        let bytes = policy.encode_cbor().expect("encode");
+        assert_eq!(BudgetPolicy::decode_cbor(&bytes).expect("decode"), policy);
+        assert_eq!(
+            policy.limits().collect::<Vec<_>>(),
+            vec![
+                (BudgetPeriod::Hour, 100),
+                (BudgetPeriod::Day, 1_000),
+                (BudgetPeriod::Lifetime, 10_000)
+            ]
+        );
+    }
+
+    #[test]
+    fn hour_and_day_windows_align_to_utc() {
+        assert_eq!(BudgetPeriod::Hour.window(3_599), BudgetPeriod::Hour.window(0));
+        assert_ne!(BudgetPeriod::Hour.window(3_600), BudgetPeriod::Hour.window(3_599));
+        assert_eq!(BudgetPeriod::Day.window(86_399), 0);
+        assert_eq!(BudgetPeriod::Day.window(86_400), 1);
+        assert_eq!(BudgetPeriod::Lifetime.window(u64::MAX), 0);
+    }
+
+    #[test]
+    fn month_window_follows_calendar_months() {
+        // 2024-02-29T23:59:59Z and 2024-03-01T00:00:00Z.
+        let leap_day_end = 1_709_251_199;
+        let march_first = 1_709_251_200;
+        assert_ne!(
+            BudgetPeriod::Month.window(leap_day_end),
+            BudgetPeriod::Month.window(march_first)
+        );
+        // 2024-02-01T00:00:00Z is in the same month as the leap day.
+        assert_eq!(
+            BudgetPeriod::Month.window(1_706_745_600),
+            BudgetPeriod::Month.window(leap_day_end)
+        );
+        assert_eq!(civil_year_month(0), (1970, 1));
+    }
+}
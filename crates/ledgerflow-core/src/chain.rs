@@ -19,6 +19,7 @@ use std::collections::HashSet;
 use crate::{
     agent_identity::IdentityResolver,
     constraint::{AuthorizationContext, Verify},
+    erc1271::ContractSignatureVerifier,
     error::{AuthorizationError, Result},
     pop::{PopProof, verify_freshness},
     trust::TrustedIssuers,
@@ -115,6 +116,21 @@ pub fn verify_chain_with_resolver(
     resolver: Option<&dyn IdentityResolver>,
     proof: &PopProof,
     context: &AuthorizationContext,
+) -> Result<VerifiedChainAuthorization> {
+    verify_chain_with(chain, trusted, resolver, None, proof, context)
+}
+
+/// Verifies the chain invariants with an optional identity resolver and an
+/// optional ERC-1271 verifier, which accepts warrant and PoP signatures of
+/// contract-account (20-byte address) issuers and holders
+/// ([`crate::erc1271`]).
+pub fn verify_chain_with(
+    chain: &WarrantChain,
+    trusted: &TrustedIssuers,
+    resolver: Option<&dyn IdentityResolver>,
+    contract_verifier: Option<&dyn ContractSignatureVerifier>,
+    proof: &PopProof,
+    context: &AuthorizationContext,
 ) -> Result<VerifiedChainAuthorization> {
     if chain.is_empty() {
         return Err(AuthorizationError::EmptyChain);
@@ -141,7 +157,7 @@ pub fn verify_chain_with_resolver(
         if node.version != crate::warrant::WARRANT_VERSION_V1 {
             return Err(AuthorizationError::UnsupportedVersion(node.version));
         }
-        if !node.verify_signature() {
+        if !node.verify_signature_with(contract_verifier) {
             return Err(AuthorizationError::InvalidWarrantSignature);
         }
         if node.issued_at > context.now_ms / 1000 {
@@ -180,7 +196,7 @@ pub fn verify_chain_with_resolver(
     if proof.tuple.warrant_id != leaf.id {
         return Err(AuthorizationError::WarrantDigestMismatch);
     }
-    if !proof.verify_signature(&leaf.holder) {
+    if !proof.verify_signature_with(&leaf.holder, contract_verifier) {
         return Err(AuthorizationError::InvalidProofSignature);
     }
     if proof.tuple.challenge_id != context.challenge_id {
@@ -449,6 +465,32 @@ mod tests {
         assert!(matches!(error, AuthorizationError::WarrantExpired { .. }));
     }
 
+    #[test]
+    fn evm_issued_root_verifies_end_to_end() {
+        let eth_issuer = crate::Secp256k1WarrantSigner::eth_typed_data(
+            crate::Secp256k1KeyPair::from_bytes(&[0x5A; 32]).expect("valid key"),
+        );
+        let root = WarrantBuilder::new(2_000)
+            .warrant_id(fixed_id("evm-root-0000000"))
+            .ttl_secs(60)
+            .max_depth(1)
+            .issuer(eth_issuer.signer_ref())
+            .holder(holder_keys().signer_ref())
+            .merchant(merchant())
+            .resource(resource())
+            .payment(payment(1_000))
+            .sign_with(&eth_issuer, [0_u8; 8]);
+        let mut anchors = TrustedIssuers::new();
+        anchors.add(TrustedIssuer::new("wallet".to_string(), eth_issuer.signer_ref()));
+
+        let ctx = context(2_000, &holder_keys().signer_ref());
+        let proof = proof_for(&root, &ctx, &holder_keys());
+        let chain = WarrantChain::single(root);
+        verify_chain(&chain, &anchors, &proof, &ctx).expect("typed-data root");
+        let error = verify_chain(&chain, &trusted(), &proof, &ctx).expect_err("untrusted");
+        assert!(matches!(error, AuthorizationError::UntrustedIssuer { .. }));
+    }
+
     #[test]
     fn non_delegatable_root_with_child_is_rejected() {
         // max_depth = 0 on root means no child may follow.
@@ -25,7 +25,7 @@ use k256::{
 use sha2::{Digest as _, Sha256};
 use tiny_keccak::{Hasher, Keccak};
 
-use crate::warrant::{SignatureEnvelope, SignerRef, SigningAlgorithm};
+use crate::warrant::{SignatureEnvelope, SignerRef, SigningAlgorithm, Warrant, WarrantSigner};
 
 /// The secp256k1 group order `n`
 /// (`FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141`).
@@ -290,6 +290,64 @@ impl Secp256k1KeyPair {
     }
 }
 
+/// A secp256k1 key issuing warrants under one of the secp256k1-family
+/// algorithms, so an EVM wallet key can be a trust anchor or delegator.
+///
+/// The warrant's issuer reference carries the compressed public key; a
+/// trust anchor may instead pin the 20-byte address for the recovering
+/// algorithms.
+#[derive(Clone, Debug)]
+pub struct Secp256k1WarrantSigner {
+    keys: Secp256k1KeyPair,
+    alg: SigningAlgorithm,
+}
+
+impl Secp256k1WarrantSigner {
+    /// Raw low-s ECDSA over `SHA-256` of the signing message.
+    #[must_use]
+    pub const fn secp256k1(keys: Secp256k1KeyPair) -> Self {
+        Self { keys, alg: SigningAlgorithm::Secp256k1 }
+    }
+
+    /// EIP-191 `personal_sign` over the signing message.
+    #[must_use]
+    pub const fn eth_personal_sign(keys: Secp256k1KeyPair) -> Self {
+        Self { keys, alg: SigningAlgorithm::EthPersonalSign }
+    }
+
+    /// EIP-712 typed data ([`crate::eip712`]).
+    #[must_use]
+    pub const fn eth_typed_data(keys: Secp256k1KeyPair) -> Self {
+        Self { keys, alg: SigningAlgorithm::EthTypedData }
+    }
+
+    /// The issuer reference warrants signed by this key carry.
+    #[must_use]
+    pub fn signer_ref(&self) -> SignerRef {
+        self.keys.signer_ref(self.alg)
+    }
+}
+
+impl WarrantSigner for Secp256k1WarrantSigner {
+    fn signer_ref(&self) -> SignerRef {
+        Self::signer_ref(self)
+    }
+
+    fn sign_warrant(&self, warrant: &Warrant) -> SignatureEnvelope {
+        match self.alg {
+            SigningAlgorithm::EthTypedData => self
+                .keys
+                .sign_eth_typed_data_digest(&crate::eip712::warrant_typed_data_digest(warrant)),
+            SigningAlgorithm::EthPersonalSign => {
+                self.keys.sign_eth_personal(&warrant.signing_message())
+            }
+            SigningAlgorithm::Secp256k1 | SigningAlgorithm::Ed25519 => {
+                self.keys.sign_message_sha256(&warrant.signing_message())
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #![allow(clippy::expect_used)]
@@ -0,0 +1,265 @@
+//! EIP-712 typed-data encoding of warrants (design §6.7).
+//!
+//! An issuer holding an Ethereum wallet signs warrants with
+//! `eth_signTypedData_v4` instead of raw bytes, so the wallet can show what is
+//! being authorized. The published struct type is:
+//!
+//! ```text
+//! EIP712Domain(string name,string version)
+//! Warrant(uint8 version,bytes id,bytes holder,bytes issuer,uint64 issuedAt,
+//!         uint64 expiresAt,uint32 depth,uint8 maxDepth,bytes parentHash,
+//!         bytes32 payloadHash)
+//! ```
+//!
+//! with domain `{ name: "LedgerFlow Warrant", version: "1" }`. `holder` and
+//! `issuer` are the raw public keys (33-byte compressed or 20-byte address
+//! for EVM signers), `parentHash` is the warrant's `parent_hash` bytes (empty
+//! for roots) and `payloadHash` is `keccak256` of the CBOR payload, so the
+//! signature covers every warrant field, not only the displayed ones.
+//!
+//! A [`SigningAlgorithm::EthTypedData`](crate::warrant::SigningAlgorithm)
+//! envelope on a warrant signs [`warrant_typed_data_digest`]; every other
+//! algorithm signs [`Warrant::signing_message`].
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+
+use crate::{
+    crypto::keccak256,
+    warrant::{Warrant, hex_encode},
+};
+
+/// EIP-712 domain name for warrants.
+pub const WARRANT_EIP712_DOMAIN_NAME: &str = "LedgerFlow Warrant";
+
+/// EIP-712 domain version for warrants.
+pub const WARRANT_EIP712_DOMAIN_VERSION: &str = "1";
+
+/// Encoded `EIP712Domain` type.
+pub const EIP712_DOMAIN_TYPE: &str = "EIP712Domain(string name,string version)";
+
+/// Encoded `Warrant` struct type.
+pub const WARRANT_EIP712_TYPE: &str = concat!(
+    "Warrant(uint8 version,bytes id,bytes holder,bytes issuer,uint64 issuedAt,",
+    "uint64 expiresAt,uint32 depth,uint8 maxDepth,bytes parentHash,bytes32 payloadHash)"
+);
+
+/// `Warrant` members in type order (name, Solidity type).
+const WARRANT_FIELDS: [(&str, &str); 10] = [
+    ("version", "uint8"),
+    ("id", "bytes"),
+    ("holder", "bytes"),
+    ("issuer", "bytes"),
+    ("issuedAt", "uint64"),
+    ("expiresAt", "uint64"),
+    ("depth", "uint32"),
+    ("maxDepth", "uint8"),
+    ("parentHash", "bytes"),
+    ("payloadHash", "bytes32"),
+];
+
+/// The EIP-712 domain separator for warrants.
+#[must_use]
+pub fn warrant_domain_separator() -> [u8; 32] {
+    let mut encoded = Vec::with_capacity(3 * 32);
+    encoded.extend_from_slice(&keccak256(EIP712_DOMAIN_TYPE.as_bytes()));
+    encoded.extend_from_slice(&keccak256(WARRANT_EIP712_DOMAIN_NAME.as_bytes()));
+    encoded.extend_from_slice(&keccak256(WARRANT_EIP712_DOMAIN_VERSION.as_bytes()));
+    keccak256(&encoded)
+}
+
+/// `hashStruct(Warrant)` for `warrant`.
+#[must_use]
+pub fn warrant_struct_hash(warrant: &Warrant) -> [u8; 32] {
+    let parent_hash = warrant.parent_hash.as_deref().unwrap_or_default();
+    let mut encoded = Vec::with_capacity(11 * 32);
+    encoded.extend_from_slice(&keccak256(WARRANT_EIP712_TYPE.as_bytes()));
+    encoded.extend_from_slice(&uint_word(u64::from(warrant.version)));
+    encoded.extend_from_slice(&keccak256(&warrant.id));
+    encoded.extend_from_slice(&keccak256(&warrant.holder.public_key));
+    encoded.extend_from_slice(&keccak256(&warrant.issuer.public_key));
+    encoded.extend_from_slice(&uint_word(warrant.issued_at));
+    encoded.extend_from_slice(&uint_word(warrant.expires_at));
+    encoded.extend_from_slice(&uint_word(u64::from(warrant.depth)));
+    encoded.extend_from_slice(&uint_word(u64::from(warrant.max_depth)));
+    encoded.extend_from_slice(&keccak256(parent_hash));
+    encoded.extend_from_slice(&keccak256(&warrant.payload_bytes()));
+    keccak256(&encoded)
+}
+
+/// The digest an EIP-712 wallet signs for `warrant`:
+/// `keccak256(0x1901 || domainSeparator || hashStruct(warrant))`.
+#[must_use]
+pub fn warrant_typed_data_digest(warrant: &Warrant) -> [u8; 32] {
+    let mut encoded = Vec::with_capacity(2 + 2 * 32);
+    encoded.extend_from_slice(&[0x19, 0x01]);
+    encoded.extend_from_slice(&warrant_domain_separator());
+    encoded.extend_from_slice(&warrant_struct_hash(warrant));
+    keccak256(&encoded)
+}
+
+fn uint_word(value: u64) -> [u8; 32] {
+    let mut word = [0_u8; 32];
+    word[24..].copy_from_slice(&value.to_be_bytes());
+    word
+}
+
+// ---------------------------------------------------------------------------
+// Wallet request document
+// ---------------------------------------------------------------------------
+
+/// One member of an EIP-712 struct type.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct TypedDataField {
+    pub name: &'static str,
+    #[serde(rename = "type")]
+    pub kind: &'static str,
+}
+
+/// The EIP-712 domain values.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+pub struct TypedDataDomain {
+    pub name: &'static str,
+    pub version: &'static str,
+}
+
+/// An `eth_signTypedData_v4` request body for a warrant. Serialize it to JSON
+/// and hand it to the wallet; integers are decimal strings and byte values
+/// `0x`-prefixed hex.
+#[derive(Clone, Debug, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WarrantTypedData {
+    pub types: BTreeMap<&'static str, Vec<TypedDataField>>,
+    pub primary_type: &'static str,
+    pub domain: TypedDataDomain,
+    pub message: BTreeMap<&'static str, String>,
+}
+
+impl WarrantTypedData {
+    /// Builds the wallet request for `warrant` (its signature is ignored).
+    #[must_use]
+    pub fn new(warrant: &Warrant) -> Self {
+        let field = |(name, kind): (&'static str, &'static str)| TypedDataField { name, kind };
+        let types = BTreeMap::from([
+            ("EIP712Domain", vec![field(("name", "string")), field(("version", "string"))]),
+            ("Warrant", WARRANT_FIELDS.into_iter().map(field).collect()),
+        ]);
+        let hex = |bytes: &[u8]| format!("0x{}", hex_encode(bytes));
+        let message = BTreeMap::from([
+            ("version", warrant.version.to_string()),
+            ("id", hex(&warrant.id)),
+            ("holder", hex(&warrant.holder.public_key)),
+            ("issuer", hex(&warrant.issuer.public_key)),
+            ("issuedAt", warrant.issued_at.to_string()),
+            ("expiresAt", warrant.expires_at.to_string()),
+            ("depth", warrant.depth.to_string()),
+            ("maxDepth", warrant.max_depth.to_string()),
+            ("parentHash", hex(warrant.parent_hash.as_deref().unwrap_or_default())),
+            ("payloadHash", hex(&keccak256(&warrant.payload_bytes()))),
+        ]);
+        Self {
+            types,
+            primary_type: "Warrant",
+            domain: TypedDataDomain {
+                name: WARRANT_EIP712_DOMAIN_NAME,
+                version: WARRANT_EIP712_DOMAIN_VERSION,
+            },
+            message,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::expect_used)]
+
+    use super::*;
+    use crate::{
+        DelegatedWarrantBuilder, PaymentConstraint, Secp256k1KeyPair, Secp256k1WarrantSigner,
+        SigningAlgorithm, SigningKeyPair, WarrantBuilder,
+    };
+
+    fn eth_keys() -> Secp256k1KeyPair {
+        Secp256k1KeyPair::from_bytes(&[0x42; 32]).expect("valid key")
+    }
+
+    fn eth_root(signer: &Secp256k1WarrantSigner) -> Warrant {
+        WarrantBuilder::new(1_700_000_000_000)
+            .ttl_secs(300)
+            .max_depth(1)
+            .issuer(signer.signer_ref())
+            .holder(SigningKeyPair::from_bytes(&[0x02; 32]).signer_ref())
+            .payment(PaymentConstraint::new(1_000))
+            .sign_with(signer, [0_u8; 8])
+    }
+
+    #[test]
+    fn published_type_matches_the_field_list() {
+        let members: Vec<String> =
+            WARRANT_FIELDS.iter().map(|(name, kind)| format!("{kind} {name}")).collect();
+        assert_eq!(WARRANT_EIP712_TYPE, format!("Warrant({})", members.join(",")));
+        let document =
+            WarrantTypedData::new(&eth_root(&Secp256k1WarrantSigner::eth_typed_data(eth_keys())));
+        assert_eq!(document.types["Warrant"].len(), document.message.len());
+        assert!(document.message.keys().all(|key| WARRANT_FIELDS.iter().any(|(n, _)| n == key)));
+    }
+
+    #[test]
+    fn evm_issuers_sign_warrants_under_every_secp256k1_algorithm() {
+        for signer in [
+            Secp256k1WarrantSigner::secp256k1(eth_keys()),
+            Secp256k1WarrantSigner::eth_personal_sign(eth_keys()),
+            Secp256k1WarrantSigner::eth_typed_data(eth_keys()),
+        ] {
+            let warrant = eth_root(&signer);
+            assert!(warrant.verify_signature(), "{}", signer.signer_ref().alg);
+            let mut tampered = warrant.clone();
+            tampered.payment.max_per_charge += 1;
+            assert!(!tampered.verify_signature());
+        }
+    }
+
+    #[test]
+    fn typed_data_signature_binds_the_digest_and_accepts_address_issuers() {
+        let keys = eth_keys();
+        let signer = Secp256k1WarrantSigner::eth_typed_data(keys.clone());
+        let warrant = eth_root(&signer);
+        assert!(
+            warrant.signature.verify_strict(&warrant.issuer, &warrant_typed_data_digest(&warrant))
+        );
+        assert_ne!(warrant_typed_data_digest(&warrant), keccak256(&warrant.signing_message()));
+
+        // An external wallet signing the digest for an address-claim issuer.
+        let address =
+            crate::SignerRef::new(SigningAlgorithm::EthTypedData, keys.ethereum_address().to_vec());
+        let mut draft = eth_root(&signer);
+        draft.issuer = address;
+        let signed = draft
+            .clone()
+            .with_signature(keys.sign_eth_typed_data_digest(&warrant_typed_data_digest(&draft)));
+        assert!(signed.verify_signature());
+    }
+
+    #[test]
+    fn delegation_from_an_evm_holder_verifies() {
+        let keys = eth_keys();
+        let delegator = Secp256k1WarrantSigner::eth_typed_data(keys);
+        let issuer = SigningKeyPair::from_bytes(&[0x01; 32]);
+        let parent = WarrantBuilder::new(1_700_000_000_000)
+            .ttl_secs(300)
+            .max_depth(1)
+            .issuer(issuer.signer_ref())
+            .holder(delegator.signer_ref())
+            .payment(PaymentConstraint::new(1_000))
+            .sign_with(&issuer, [0_u8; 8]);
+        let child = DelegatedWarrantBuilder::from(parent).issue_to(
+            SigningKeyPair::from_bytes(&[0x03; 32]).signer_ref(),
+            &delegator,
+            1_700_000_000_000,
+            [1_u8; 8],
+        );
+        assert_eq!(child.signature.alg, SigningAlgorithm::EthTypedData);
+        assert!(child.verify_signature());
+    }
+}
@@ -18,6 +18,8 @@
 //! `keccak256(domain-separated LedgerFlow message)`, keeping the account-level
 //! digest unambiguous across transports.
 
+use std::sync::Arc;
+
 use crate::{
     crypto::keccak256,
     warrant::{SignatureEnvelope, SignerRef},
@@ -31,12 +33,15 @@ pub const ERC_1271_MAGIC_VALUE: [u8; 4] = [0x16, 0x26, 0xBA, 0x7E];
 /// Implementations perform the `isValidSignature(hash, signature)` call
 /// against the account at `account.public_key` (a 20-byte address) and return
 /// whether the returned bytes start with [`ERC_1271_MAGIC_VALUE`].
-pub trait ContractSignatureVerifier: Send + Sync {
+pub trait ContractSignatureVerifier: std::fmt::Debug + Send + Sync {
     /// Returns `true` when the on-chain account confirms the signature over
     /// `hash`.
     fn is_valid_signature(&self, account: &SignerRef, hash: [u8; 32], signature: &[u8]) -> bool;
 }
 
+/// Shared contract-signature verifier handle.
+pub type SharedContractSignatureVerifier = Arc<dyn ContractSignatureVerifier>;
+
 /// Returns `true` when this signer reference is shaped like an on-chain
 /// account claim (20-byte key in the secp256k1 family).
 #[must_use]
@@ -82,6 +87,7 @@ mod tests {
     use super::*;
     use crate::{Secp256k1KeyPair, SigningAlgorithm};
 
+    #[derive(Debug)]
     struct MockVerifier {
         accept: bool,
         calls: AtomicU32,
@@ -70,12 +70,26 @@ pub enum AuthorizationError {
     ParentHashMismatch,
     #[error("the root issuer is not trusted")]
     UntrustedIssuer { key_id: String },
+    #[error("approver set `{name}` could not be resolved: {detail}")]
+    ApproverSetUnavailable { name: String, detail: String },
+    #[error("approver set `{name}` is invalid: {detail}")]
+    InvalidApproverSet { name: String, detail: String },
+    #[error("trusted issuer `{key_id}` is not valid for roots issued at {issued_at}")]
+    IssuerOutsideValidity { key_id: String, issued_at: u64 },
     #[error("child constraint violates monotonic attenuation on `{dimension}`: {detail}")]
     AttenuationViolation { dimension: String, detail: String },
     #[error("the warrant has been revoked")]
     WarrantRevoked,
     #[error("the holder key has been revoked")]
     HolderRevoked,
+    #[error("the payment subject has been revoked")]
+    SubjectRevoked,
+    #[error("an issuer key in the warrant chain has been revoked")]
+    IssuerRevoked,
+    #[error("the agent `{agent_id}` has been revoked")]
+    AgentRevoked { agent_id: String },
+    #[error("revocation state is unavailable or stale")]
+    RevocationUnavailable,
     #[error("this action requires human approval")]
     ApprovalRequired,
     #[error("insufficient approvals: got {got}, need {need}")]
@@ -98,6 +112,10 @@ pub enum AuthorizationError {
     SrlVersionRegression { presented: u64, applied: u64 },
     #[error("the SRL signature is invalid")]
     InvalidSrlSignature,
+    #[error("the SRL delta applies on top of version {base} but version {applied} is applied")]
+    SrlDeltaBaseMismatch { base: u64, applied: u64 },
+    #[error("the SRL state root {actual} does not match the signed checkpoint {expected}")]
+    SrlStateRootMismatch { expected: String, actual: String },
     #[error("human presence is required for this payment but no valid approvals were presented")]
     HumanPresenceRequired,
     #[error("identity resolution failed for `{reference}`: {detail}")]
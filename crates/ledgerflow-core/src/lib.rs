@@ -4,12 +4,17 @@
 //! contains:
 //!
 //! - [`warrant`]: the signed capability token (Warrant) and its CBOR codec.
+//! - [`eip712`]: the EIP-712 `Warrant` type for EVM wallet issuers.
 //! - [`chain`]: delegation-chain verification (invariants I1-I7).
 //! - [`pop`]: proof-of-possession binding tuples.
 //! - [`constraint`]: stateless, decidable constraints.
 //! - [`approval`]: m-of-n human approval gates.
+//! - [`approver_set`]: rotatable, issuer-signed approver groups.
+//! - [`budget`]: accounting-point budget declarations (periodic/lifetime).
 //! - [`trust`]: trusted-issuer anchors.
-//! - [`revocation`]: the `RevocationCheck` seam (implemented out of crate).
+//! - [`rail_policy`]: the payment-subject to rail mapping shared by merchants and facilitators.
+//! - [`revocation`]: the `RevocationCheck` / `AsyncRevocationCheck` seams (implemented out of
+//!   crate).
 //! - [`verification`]: the type-state verification pipeline.
 //! - [`typestate`] / [`proof_builder`]: compile-time-safe builders.
 //!
@@ -21,15 +26,19 @@
 
 pub mod agent_identity;
 pub mod approval;
+pub mod approver_set;
+pub mod budget;
 pub mod chain;
 pub mod constraint;
 pub mod crypto;
+pub mod eip712;
 pub mod erc1271;
 pub mod error;
 pub mod feedback_auth;
 pub mod issue_bounds;
 pub mod pop;
 pub mod proof_builder;
+pub mod rail_policy;
 pub mod revocation;
 pub mod srl;
 pub mod trust;
@@ -40,15 +49,21 @@ pub mod warrant;
 pub use crate::{
     agent_identity::{
         AGENT_ID_EXTENSION_KEY, AgentIdParseError, AgentIdRef, IdentityResolver,
-        agent_id_from_warrant,
+        SharedIdentityResolver, agent_id_from_warrant,
     },
     approval::{
         ApprovalGate, ApprovalVerification, SignedApproval, verify_approval_threshold,
-        verify_approvals,
+        verify_approval_threshold_with, verify_approvals, verify_approvals_with,
     },
+    approver_set::{
+        APPROVER_SET_EXTENSION, ApproverSet, ApproverSetRef, ApproverSetResolver,
+        InMemoryApproverSetResolver, SharedApproverSetResolver, SignedApproverSet,
+        effective_approvers,
+    },
+    budget::{BUDGET_EXTENSION, BudgetPeriod, BudgetPolicy},
     chain::{
-        VerifiedChainAuthorization, WarrantChain, verify_chain, verify_chain_with_resolver,
-        verify_link,
+        VerifiedChainAuthorization, WarrantChain, verify_chain, verify_chain_with,
+        verify_chain_with_resolver, verify_link,
     },
     constraint::{
         AuthorizationContext, Constraint, MerchantConstraint, PaymentConstraint,
@@ -56,24 +71,35 @@ pub use crate::{
         verify_all as verify_all_constraints,
     },
     crypto::{
-        Secp256k1KeyPair, eip191_hash_of_bytes32, eip191_message_hash,
+        Secp256k1KeyPair, Secp256k1WarrantSigner, eip191_hash_of_bytes32, eip191_message_hash,
         ethereum_address_from_compressed_pubkey, keccak256,
     },
+    eip712::{
+        EIP712_DOMAIN_TYPE, WARRANT_EIP712_DOMAIN_NAME, WARRANT_EIP712_DOMAIN_VERSION,
+        WARRANT_EIP712_TYPE, WarrantTypedData, warrant_typed_data_digest,
+    },
     erc1271::{
-        ContractSignatureVerifier, ERC_1271_MAGIC_VALUE, is_contract_account_claim,
-        verify_signature_with,
+        ContractSignatureVerifier, ERC_1271_MAGIC_VALUE, SharedContractSignatureVerifier,
+        is_contract_account_claim, verify_signature_with,
     },
     error::{AuthorizationError, Result, WireError, WireResult},
     feedback_auth::FeedbackAuth,
     issue_bounds::{ISSUE_BOUNDS_EXTENSION, IssueBounds},
     pop::{POP_SIGN_DOMAIN, PopProof, PopTuple, verify_freshness},
     proof_builder::ProofBuilder,
-    revocation::{InMemoryRevocationCheck, RevocationCheck, RevocationDecision},
-    srl::{SRL_SIGN_DOMAIN, SignedRevocationList, SrlEntry, SrlState},
-    trust::{TrustedIssuer, TrustedIssuers},
+    rail_policy::{
+        DefaultSubjectRailPolicy, PrefixRailPolicy, SharedSubjectRailPolicy, SubjectRailPolicy,
+    },
+    revocation::{
+        AsyncRevocationCheck, InMemoryRevocationCheck, RevocationCheck, RevocationDecision,
+        RevocationSnapshot, subject_revocation_key, verify_chain_revocation, warrant_agent_id,
+    },
+    srl::{SRL_SIGN_DOMAIN, SignedRevocationList, SrlEntry, SrlState, srl_state_root},
+    trust::{TrustAnchorSet, TrustedIssuer, TrustedIssuers},
     typestate::{DelegatedWarrantBuilder, WarrantBuilder},
     verification::{
         AuthorizationInput, ToolArguments, VerifiedAuthorization, WarrantExt, verify_authorization,
+        verify_authorization_with_approver_sets,
     },
     warrant::{
         AssetRef, CborCodec, DEFAULT_CHALLENGE_TTL_MS, DEFAULT_CLOCK_SKEW_MS, DEFAULT_MAX_DEPTH,
@@ -81,7 +107,8 @@ pub use crate::{
         MAX_DELEGATION_DEPTH, MAX_WARRANT_CBOR_BYTES, MAX_WARRANT_TTL_SECS, PaymentRail,
         PaymentSubjectKind, PaymentSubjectRef, SignatureEnvelope, SignerRef, SigningAlgorithm,
         SigningKeyPair, WARRANT_SIGN_DOMAIN, WARRANT_VERSION_V1, Warrant, WarrantMetadata,
-        generate_warrant_id, generate_warrant_id_128, hex_encode_bytes, sha256_prefixed,
+        WarrantSigner, generate_warrant_id, generate_warrant_id_128, hex_encode_bytes,
+        is_sha256_prefixed, sha256_prefixed,
     },
 };
 
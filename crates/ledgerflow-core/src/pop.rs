@@ -9,6 +9,7 @@
 use serde::{Deserialize, Serialize};
 
 use crate::{
+    erc1271::{ContractSignatureVerifier, verify_signature_with},
     error::{AuthorizationError, Result},
     warrant::{SignatureEnvelope, SignerRef, SigningKeyPair, sha256_prefixed},
 };
@@ -145,8 +146,23 @@ impl PopProof {
     /// Verifies the PoP signature against the given signer using **strict**
     /// Ed25519 verification.
     pub fn verify_signature(&self, signer: &SignerRef) -> bool {
+        self.verify_signature_with(signer, None)
+    }
+
+    /// [`Self::verify_signature`], accepting a contract-account holder
+    /// through the ERC-1271 seam.
+    pub fn verify_signature_with(
+        &self,
+        signer: &SignerRef,
+        contract_verifier: Option<&dyn ContractSignatureVerifier>,
+    ) -> bool {
         self.signer_key == signer.public_key &&
-            self.signature.verify_strict(signer, &self.tuple.preimage())
+            verify_signature_with(
+                &self.signature,
+                signer,
+                &self.tuple.preimage(),
+                contract_verifier,
+            )
     }
 }
 
@@ -91,6 +91,14 @@ impl ProofBuilder {
         self
     }
 
+    /// Sets the tool-call arguments digest
+    /// ([`PopTuple::tool_args_digest`]).
+    #[must_use]
+    pub fn tool_args_digest(mut self, tool_args_digest: impl Into<String>) -> Self {
+        self.tool_args_digest = Some(tool_args_digest.into());
+        self
+    }
+
     /// Sets the approvals digest.
     #[must_use]
     pub fn approvals_digest(mut self, approvals_digest: impl Into<String>) -> Self {
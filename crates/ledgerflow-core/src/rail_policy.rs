@@ -0,0 +1,128 @@
+//! Payment-subject to rail mapping (design §8.2).
+//!
+//! The rail a payment is verified against (`AuthorizationContext::rail`) is
+//! derived from the presented payment subject, not chosen by the presenter.
+//! Merchants and facilitators share one [`SubjectRailPolicy`] so the rail a
+//! warrant's `allowed_rails` was checked against is the rail the payment is
+//! routed to.
+
+use std::sync::Arc;
+
+use crate::warrant::{PaymentRail, PaymentSubjectKind, PaymentSubjectRef};
+
+/// Maps a payment subject to the rail that settles it.
+pub trait SubjectRailPolicy: std::fmt::Debug + Send + Sync {
+    fn rail(&self, subject: &PaymentSubjectRef) -> PaymentRail;
+}
+
+/// Shared subject-to-rail policy handle.
+pub type SharedSubjectRailPolicy = Arc<dyn SubjectRailPolicy>;
+
+/// The built-in mapping:
+///
+/// | Subject | Rail |
+/// |---|---|
+/// | `caip10` | `onchain` |
+/// | `exchange_account`, `facilitator_account` | `exchange` |
+/// | `opaque` with a `gateway:` value | `traditional_gateway` |
+/// | other `opaque` | `custodial` |
+#[derive(Clone, Copy, Debug, Default)]
+pub struct DefaultSubjectRailPolicy;
+
+impl SubjectRailPolicy for DefaultSubjectRailPolicy {
+    fn rail(&self, subject: &PaymentSubjectRef) -> PaymentRail {
+        match subject.kind {
+            PaymentSubjectKind::Caip10 => PaymentRail::Onchain,
+            PaymentSubjectKind::ExchangeAccount | PaymentSubjectKind::FacilitatorAccount => {
+                PaymentRail::Exchange
+            }
+            PaymentSubjectKind::Opaque if subject.value.starts_with("gateway:") => {
+                PaymentRail::TraditionalGateway
+            }
+            PaymentSubjectKind::Opaque => PaymentRail::Custodial,
+        }
+    }
+}
+
+/// A rule table consulted in order, falling back to
+/// [`DefaultSubjectRailPolicy`] when no rule matches.
+///
+/// ```ignore
+/// let policy = PrefixRailPolicy::new()
+///     .route(PaymentSubjectKind::FacilitatorAccount, "stripe:", PaymentRail::TraditionalGateway)
+///     .route(PaymentSubjectKind::Opaque, "vault:", PaymentRail::Custodial);
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct PrefixRailPolicy {
+    rules: Vec<(PaymentSubjectKind, String, PaymentRail)>,
+}
+
+impl PrefixRailPolicy {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Routes subjects of `kind` whose value starts with `prefix` (empty
+    /// matches every value) to `rail`.
+    #[must_use]
+    pub fn route(
+        mut self,
+        kind: PaymentSubjectKind,
+        prefix: impl Into<String>,
+        rail: PaymentRail,
+    ) -> Self {
+        self.rules.push((kind, prefix.into(), rail));
+        self
+    }
+}
+
+impl SubjectRailPolicy for PrefixRailPolicy {
+    fn rail(&self, subject: &PaymentSubjectRef) -> PaymentRail {
+        self.rules
+            .iter()
+            .find(|(kind, prefix, _)| *kind == subject.kind && subject.value.starts_with(prefix))
+            .map_or_else(|| DefaultSubjectRailPolicy.rail(subject), |(_, _, rail)| *rail)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn subject(kind: PaymentSubjectKind, value: &str) -> PaymentSubjectRef {
+        PaymentSubjectRef::new(kind, value)
+    }
+
+    #[test]
+    fn default_policy_covers_every_subject_kind() {
+        let policy = DefaultSubjectRailPolicy;
+        let cases = [
+            (subject(PaymentSubjectKind::Caip10, "caip10:eip155:8453:0xabc"), PaymentRail::Onchain),
+            (subject(PaymentSubjectKind::ExchangeAccount, "exchange-1"), PaymentRail::Exchange),
+            (subject(PaymentSubjectKind::FacilitatorAccount, "okx:acct"), PaymentRail::Exchange),
+            (subject(PaymentSubjectKind::Opaque, "gateway:gw-1"), PaymentRail::TraditionalGateway),
+            (subject(PaymentSubjectKind::Opaque, "vault:acct"), PaymentRail::Custodial),
+        ];
+        for (subject, rail) in cases {
+            assert_eq!(policy.rail(&subject), rail, "{}", subject.value);
+        }
+    }
+
+    #[test]
+    fn prefix_rules_win_in_order_and_fall_back_to_the_default() {
+        let policy = PrefixRailPolicy::new()
+            .route(
+                PaymentSubjectKind::FacilitatorAccount,
+                "stripe:",
+                PaymentRail::TraditionalGateway,
+            )
+            .route(PaymentSubjectKind::FacilitatorAccount, "", PaymentRail::Custodial);
+        let stripe = subject(PaymentSubjectKind::FacilitatorAccount, "stripe:acct");
+        assert_eq!(policy.rail(&stripe), PaymentRail::TraditionalGateway);
+        let other = subject(PaymentSubjectKind::FacilitatorAccount, "okx:acct");
+        assert_eq!(policy.rail(&other), PaymentRail::Custodial);
+        let exchange = subject(PaymentSubjectKind::ExchangeAccount, "stripe:acct");
+        assert_eq!(policy.rail(&exchange), PaymentRail::Exchange);
+    }
+}
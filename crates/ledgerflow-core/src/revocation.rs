@@ -6,10 +6,24 @@
 //! persistent storage. Production deployments MUST persist revocation
 //! records; in-memory implementations are only permitted for demonstrations
 //! and must be explicitly acknowledged (e.g. `--insecure-revoc-memory`).
+//!
+//! Besides a warrant id and the leaf holder key, a store can revoke three
+//! wider scopes (design §6.6): a payment subject (a compromised payer
+//! account), an issuer or sub-issuer key (every chain it signed a node of
+//! dies), and a `ledgerflow.agent_id` value. [`verify_chain_revocation`]
+//! checks the scopes at every node of the chain.
+//!
+//! Stores that answer over the network implement [`AsyncRevocationCheck`]
+//! instead; async verifiers look the chain up once and hand the resulting
+//! [`RevocationSnapshot`] to the synchronous pipeline.
+
+use std::{borrow::Cow, future::Future};
 
 use crate::{
+    agent_identity::AGENT_ID_EXTENSION_KEY,
+    chain::WarrantChain,
     error::{AuthorizationError, Result},
-    warrant::SignerRef,
+    warrant::{PaymentSubjectRef, SignerRef, Warrant},
 };
 
 /// A single revocation decision.
@@ -21,6 +35,16 @@ pub enum RevocationDecision {
     RevokedWarrant,
     /// The holder key was revoked (all warrants by this holder are invalid).
     RevokedHolder,
+    /// The payment subject (payer account) was revoked.
+    RevokedSubject,
+    /// The issuer key was revoked (every chain it signed a node of is
+    /// invalid).
+    RevokedIssuer,
+    /// The `ledgerflow.agent_id` was revoked.
+    RevokedAgent,
+    /// The store cannot vouch for its revocation state (e.g. a stale SRL
+    /// replica running fail-closed).
+    Unavailable,
 }
 
 impl RevocationDecision {
@@ -29,6 +53,22 @@ impl RevocationDecision {
     pub const fn is_allowed(&self) -> bool {
         matches!(self, Self::Ok)
     }
+
+    /// Maps the decision to the matching [`AuthorizationError`];
+    /// `agent_id` names the agent in [`AuthorizationError::AgentRevoked`].
+    fn into_result(self, agent_id: Option<&str>) -> Result<()> {
+        match self {
+            Self::Ok => Ok(()),
+            Self::RevokedWarrant => Err(AuthorizationError::WarrantRevoked),
+            Self::RevokedHolder => Err(AuthorizationError::HolderRevoked),
+            Self::RevokedSubject => Err(AuthorizationError::SubjectRevoked),
+            Self::RevokedIssuer => Err(AuthorizationError::IssuerRevoked),
+            Self::RevokedAgent => Err(AuthorizationError::AgentRevoked {
+                agent_id: agent_id.unwrap_or_default().to_string(),
+            }),
+            Self::Unavailable => Err(AuthorizationError::RevocationUnavailable),
+        }
+    }
 }
 
 /// Pure seam for online revocation checks.
@@ -36,6 +76,9 @@ impl RevocationDecision {
 /// Implementations MUST be persistent in production. A best-effort check that
 /// returns `Ok` when the store is unavailable is acceptable only with an
 /// explicit availability downgrade (never silently).
+///
+/// The scoped checks default to `Ok` for stores that only record warrants
+/// and holders.
 pub trait RevocationCheck: std::fmt::Debug {
     /// Checks whether a warrant (by id) is revoked.
     fn check_warrant(&self, warrant_id: &[u8]) -> RevocationDecision;
@@ -43,27 +86,227 @@ pub trait RevocationCheck: std::fmt::Debug {
     /// Checks whether a holder key is revoked.
     fn check_holder(&self, holder: &SignerRef) -> RevocationDecision;
 
+    /// Checks whether a payment subject is revoked.
+    fn check_subject(&self, _subject: &PaymentSubjectRef) -> RevocationDecision {
+        RevocationDecision::Ok
+    }
+
+    /// Checks whether an issuer or sub-issuer key is revoked.
+    fn check_issuer(&self, _issuer: &SignerRef) -> RevocationDecision {
+        RevocationDecision::Ok
+    }
+
+    /// Checks whether a `ledgerflow.agent_id` value is revoked.
+    fn check_agent(&self, _agent_id: &str) -> RevocationDecision {
+        RevocationDecision::Ok
+    }
+
     /// Convenience: runs both checks and returns an error when revoked.
     fn verify(&self, warrant_id: &[u8], holder: &SignerRef) -> Result<()> {
-        match self.check_warrant(warrant_id) {
-            RevocationDecision::Ok => {}
-            RevocationDecision::RevokedWarrant => {
-                return Err(AuthorizationError::WarrantRevoked);
-            }
-            RevocationDecision::RevokedHolder => {
-                return Err(AuthorizationError::HolderRevoked);
-            }
+        self.check_warrant(warrant_id).into_result(None)?;
+        self.check_holder(holder).into_result(None)
+    }
+}
+
+impl<T: RevocationCheck + ?Sized> RevocationCheck for std::sync::Arc<T> {
+    fn check_warrant(&self, warrant_id: &[u8]) -> RevocationDecision {
+        self.as_ref().check_warrant(warrant_id)
+    }
+
+    fn check_holder(&self, holder: &SignerRef) -> RevocationDecision {
+        self.as_ref().check_holder(holder)
+    }
+
+    fn check_subject(&self, subject: &PaymentSubjectRef) -> RevocationDecision {
+        self.as_ref().check_subject(subject)
+    }
+
+    fn check_issuer(&self, issuer: &SignerRef) -> RevocationDecision {
+        self.as_ref().check_issuer(issuer)
+    }
+
+    fn check_agent(&self, agent_id: &str) -> RevocationDecision {
+        self.as_ref().check_agent(agent_id)
+    }
+}
+
+/// Checks every revocation scope of an authorization: the leaf warrant and
+/// holder, the payment subject, and the issuer key and agent id of every
+/// node of the chain (root first).
+pub fn verify_chain_revocation(
+    check: &(impl RevocationCheck + ?Sized),
+    chain: &WarrantChain,
+    subject: &PaymentSubjectRef,
+) -> Result<()> {
+    if let Some(leaf) = chain.leaf() {
+        check.verify(&leaf.id, &leaf.holder)?;
+    }
+    check.check_subject(subject).into_result(None)?;
+    for warrant in &chain.warrants {
+        check.check_issuer(&warrant.issuer).into_result(None)?;
+        if let Some(agent_id) = warrant_agent_id(warrant) {
+            check.check_agent(&agent_id).into_result(Some(&agent_id))?;
         }
-        match self.check_holder(holder) {
-            RevocationDecision::Ok => {}
-            RevocationDecision::RevokedWarrant => {
-                return Err(AuthorizationError::WarrantRevoked);
-            }
-            RevocationDecision::RevokedHolder => {
-                return Err(AuthorizationError::HolderRevoked);
+    }
+    Ok(())
+}
+
+/// The key a payment subject is revoked under: `{kind}:{value}` as UTF-8.
+#[must_use]
+pub fn subject_revocation_key(subject: &PaymentSubjectRef) -> Vec<u8> {
+    format!("{}:{}", subject.kind, subject.value).into_bytes()
+}
+
+/// The raw `ledgerflow.agent_id` extension value of a warrant, if any.
+///
+/// Revocation matches the value as written, so it also covers agent ids that
+/// are not EIP-8004 references.
+#[must_use]
+pub fn warrant_agent_id(warrant: &Warrant) -> Option<Cow<'_, str>> {
+    warrant.extensions.get(AGENT_ID_EXTENSION_KEY).map(|bytes| String::from_utf8_lossy(bytes))
+}
+
+/// Async seam for revocation stores reached over I/O (a database pool, a
+/// remote SRL endpoint, ...).
+///
+/// Every [`RevocationCheck`] that is `Send + Sync` is also an
+/// `AsyncRevocationCheck`, so existing stores plug into async verifiers
+/// unchanged.
+pub trait AsyncRevocationCheck: std::fmt::Debug + Send + Sync {
+    /// Looks up every revocation scope of `chain` and `subject` together
+    /// (see [`verify_chain_revocation`]).
+    fn snapshot(
+        &self,
+        chain: &WarrantChain,
+        subject: &PaymentSubjectRef,
+    ) -> impl Future<Output = RevocationSnapshot> + Send;
+}
+
+impl<T: RevocationCheck + Send + Sync + ?Sized> AsyncRevocationCheck for T {
+    async fn snapshot(
+        &self,
+        chain: &WarrantChain,
+        subject: &PaymentSubjectRef,
+    ) -> RevocationSnapshot {
+        RevocationSnapshot::capture(self, chain, subject)
+    }
+}
+
+/// Revocation decisions for one authorization, fetched ahead of
+/// verification.
+///
+/// As a [`RevocationCheck`] it answers only for what it was captured for:
+/// any other warrant, holder, subject, issuer or agent is reported revoked
+/// (fail closed).
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct RevocationSnapshot {
+    warrants: Vec<(Vec<u8>, RevocationDecision)>,
+    holders: Vec<(Vec<u8>, RevocationDecision)>,
+    subjects: Vec<(PaymentSubjectRef, RevocationDecision)>,
+    issuers: Vec<(Vec<u8>, RevocationDecision)>,
+    agents: Vec<(String, RevocationDecision)>,
+}
+
+impl RevocationSnapshot {
+    /// Records the decisions `warrant` and `holder` for the given pair.
+    #[must_use]
+    pub fn new(
+        warrant_id: &[u8],
+        holder: &SignerRef,
+        warrant: RevocationDecision,
+        holder_decision: RevocationDecision,
+    ) -> Self {
+        Self {
+            warrants: vec![(warrant_id.to_vec(), warrant)],
+            holders: vec![(holder.public_key.clone(), holder_decision)],
+            ..Self::default()
+        }
+    }
+
+    /// Records the decision for a payment subject.
+    #[must_use]
+    pub fn with_subject(
+        mut self,
+        subject: &PaymentSubjectRef,
+        decision: RevocationDecision,
+    ) -> Self {
+        self.subjects.push((subject.clone(), decision));
+        self
+    }
+
+    /// Records the decision for an issuer key.
+    #[must_use]
+    pub fn with_issuer(mut self, issuer: &SignerRef, decision: RevocationDecision) -> Self {
+        self.issuers.push((issuer.public_key.clone(), decision));
+        self
+    }
+
+    /// Records the decision for an agent id.
+    #[must_use]
+    pub fn with_agent(mut self, agent_id: &str, decision: RevocationDecision) -> Self {
+        self.agents.push((agent_id.to_string(), decision));
+        self
+    }
+
+    /// Captures the current decisions of a synchronous check for every
+    /// scope [`verify_chain_revocation`] consults.
+    #[must_use]
+    pub fn capture(
+        check: &(impl RevocationCheck + ?Sized),
+        chain: &WarrantChain,
+        subject: &PaymentSubjectRef,
+    ) -> Self {
+        let mut snapshot = match chain.leaf() {
+            Some(leaf) => Self::new(
+                &leaf.id,
+                &leaf.holder,
+                check.check_warrant(&leaf.id),
+                check.check_holder(&leaf.holder),
+            ),
+            None => Self::default(),
+        };
+        snapshot = snapshot.with_subject(subject, check.check_subject(subject));
+        for warrant in &chain.warrants {
+            snapshot = snapshot.with_issuer(&warrant.issuer, check.check_issuer(&warrant.issuer));
+            if let Some(agent_id) = warrant_agent_id(warrant) {
+                snapshot = snapshot.with_agent(&agent_id, check.check_agent(&agent_id));
             }
         }
-        Ok(())
+        snapshot
+    }
+}
+
+/// Looks `key` up in captured decisions, failing closed with `missing`.
+fn captured<K: PartialEq + ?Sized, Q: std::borrow::Borrow<K>>(
+    decisions: &[(Q, RevocationDecision)],
+    key: &K,
+    missing: RevocationDecision,
+) -> RevocationDecision {
+    decisions
+        .iter()
+        .find(|(captured, _)| captured.borrow() == key)
+        .map_or(missing, |(_, decision)| decision.clone())
+}
+
+impl RevocationCheck for RevocationSnapshot {
+    fn check_warrant(&self, warrant_id: &[u8]) -> RevocationDecision {
+        captured(&self.warrants, warrant_id, RevocationDecision::RevokedWarrant)
+    }
+
+    fn check_holder(&self, holder: &SignerRef) -> RevocationDecision {
+        captured(&self.holders, holder.public_key.as_slice(), RevocationDecision::RevokedHolder)
+    }
+
+    fn check_subject(&self, subject: &PaymentSubjectRef) -> RevocationDecision {
+        captured(&self.subjects, subject, RevocationDecision::RevokedSubject)
+    }
+
+    fn check_issuer(&self, issuer: &SignerRef) -> RevocationDecision {
+        captured(&self.issuers, issuer.public_key.as_slice(), RevocationDecision::RevokedIssuer)
+    }
+
+    fn check_agent(&self, agent_id: &str) -> RevocationDecision {
+        captured(&self.agents, agent_id, RevocationDecision::RevokedAgent)
     }
 }
 
@@ -74,34 +317,49 @@ pub trait RevocationCheck: std::fmt::Debug {
 /// implementation in `ledgerflow-facilitator` / `ledgerflow-server`.
 #[derive(Clone, Debug, Default)]
 pub struct InMemoryRevocationCheck {
-    revoked_warrants: std::collections::HashSet<Vec<u8>>,
-    revoked_holders: std::collections::HashSet<Vec<u8>>,
+    warrants: std::collections::HashSet<Vec<u8>>,
+    holders: std::collections::HashSet<Vec<u8>>,
+    subjects: std::collections::HashSet<PaymentSubjectRef>,
+    issuers: std::collections::HashSet<Vec<u8>>,
+    agents: std::collections::HashSet<String>,
 }
 
 impl InMemoryRevocationCheck {
     /// Creates an empty in-memory store.
     #[must_use]
     pub fn new() -> Self {
-        Self {
-            revoked_warrants: std::collections::HashSet::new(),
-            revoked_holders: std::collections::HashSet::new(),
-        }
+        Self::default()
     }
 
     /// Revokes a warrant by id.
     pub fn revoke_warrant(&mut self, warrant_id: &[u8]) {
-        self.revoked_warrants.insert(warrant_id.to_vec());
+        self.warrants.insert(warrant_id.to_vec());
     }
 
     /// Revokes a holder key.
     pub fn revoke_holder(&mut self, holder: &SignerRef) {
-        self.revoked_holders.insert(holder.public_key.clone());
+        self.holders.insert(holder.public_key.clone());
+    }
+
+    /// Revokes a payment subject.
+    pub fn revoke_subject(&mut self, subject: &PaymentSubjectRef) {
+        self.subjects.insert(subject.clone());
+    }
+
+    /// Revokes an issuer or sub-issuer key.
+    pub fn revoke_issuer(&mut self, issuer: &SignerRef) {
+        self.issuers.insert(issuer.public_key.clone());
+    }
+
+    /// Revokes a `ledgerflow.agent_id` value.
+    pub fn revoke_agent(&mut self, agent_id: &str) {
+        self.agents.insert(agent_id.to_string());
     }
 }
 
 impl RevocationCheck for InMemoryRevocationCheck {
     fn check_warrant(&self, warrant_id: &[u8]) -> RevocationDecision {
-        if self.revoked_warrants.contains(warrant_id) {
+        if self.warrants.contains(warrant_id) {
             RevocationDecision::RevokedWarrant
         } else {
             RevocationDecision::Ok
@@ -109,12 +367,36 @@ impl RevocationCheck for InMemoryRevocationCheck {
     }
 
     fn check_holder(&self, holder: &SignerRef) -> RevocationDecision {
-        if self.revoked_holders.contains(&holder.public_key) {
+        if self.holders.contains(&holder.public_key) {
             RevocationDecision::RevokedHolder
         } else {
             RevocationDecision::Ok
         }
     }
+
+    fn check_subject(&self, subject: &PaymentSubjectRef) -> RevocationDecision {
+        if self.subjects.contains(subject) {
+            RevocationDecision::RevokedSubject
+        } else {
+            RevocationDecision::Ok
+        }
+    }
+
+    fn check_issuer(&self, issuer: &SignerRef) -> RevocationDecision {
+        if self.issuers.contains(&issuer.public_key) {
+            RevocationDecision::RevokedIssuer
+        } else {
+            RevocationDecision::Ok
+        }
+    }
+
+    fn check_agent(&self, agent_id: &str) -> RevocationDecision {
+        if self.agents.contains(agent_id) {
+            RevocationDecision::RevokedAgent
+        } else {
+            RevocationDecision::Ok
+        }
+    }
 }
 
 #[cfg(test)]
@@ -164,6 +446,45 @@ mod tests {
         assert_eq!(error, AuthorizationError::HolderRevoked);
     }
 
+    #[test]
+    fn snapshot_answers_for_its_pair_and_fails_closed_otherwise() {
+        let mut store = InMemoryRevocationCheck::new();
+        store.revoke_holder(&holder());
+        let snapshot = RevocationSnapshot::new(
+            &[1; 16],
+            &holder(),
+            store.check_warrant(&[1; 16]),
+            store.check_holder(&holder()),
+        );
+        assert_eq!(snapshot.check_warrant(&[1; 16]), RevocationDecision::Ok);
+        assert_eq!(snapshot.verify(&[1; 16], &holder()), Err(AuthorizationError::HolderRevoked));
+        assert_eq!(snapshot.check_warrant(&[2; 16]), RevocationDecision::RevokedWarrant);
+        let other = SigningKeyPair::from_bytes(&[0x62; 32]).signer_ref();
+        assert_eq!(snapshot.check_holder(&other), RevocationDecision::RevokedHolder);
+        assert_eq!(snapshot.check_issuer(&holder()), RevocationDecision::RevokedIssuer);
+        assert_eq!(snapshot.check_agent("agent-1"), RevocationDecision::RevokedAgent);
+        let snapshot = snapshot.with_agent("agent-1", RevocationDecision::Ok);
+        assert_eq!(snapshot.check_agent("agent-1"), RevocationDecision::Ok);
+    }
+
+    #[test]
+    fn in_memory_store_revokes_subjects_issuers_and_agents() {
+        use crate::warrant::{PaymentSubjectKind, PaymentSubjectRef};
+
+        let subject = PaymentSubjectRef::new(PaymentSubjectKind::Opaque, "acct-1");
+        let mut store = InMemoryRevocationCheck::new();
+        assert_eq!(store.check_subject(&subject), RevocationDecision::Ok);
+        store.revoke_subject(&subject);
+        store.revoke_issuer(&holder());
+        store.revoke_agent("agent-1");
+        assert_eq!(store.check_subject(&subject), RevocationDecision::RevokedSubject);
+        assert_eq!(store.check_issuer(&holder()), RevocationDecision::RevokedIssuer);
+        assert_eq!(store.check_agent("agent-1"), RevocationDecision::RevokedAgent);
+        // Scopes are independent: an issuer revocation is not a holder one.
+        assert_eq!(store.check_holder(&holder()), RevocationDecision::Ok);
+        assert_eq!(store.check_agent("agent-2"), RevocationDecision::Ok);
+    }
+
     #[test]
     fn in_memory_store_clones_independently() {
         let mut store = InMemoryRevocationCheck::new();
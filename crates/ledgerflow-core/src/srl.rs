@@ -6,38 +6,97 @@
 //! list of revocations; verifier nodes fetch the latest list and apply it to
 //! their local `RevocationCheck`. The list is:
 //!
-//! - **additive**: entries are never removed from the current list (revocations are permanent for
-//!   the warrant's lifetime);
+//! - **a snapshot or a delta**: a snapshot carries the whole revocation state at `version`; a delta
+//!   carries the entries added between `base_version` and `version` and applies only on top of
+//!   `base_version`;
+//! - **compacted**: warrant entries whose warrant expired before `compacted_before` are dropped (an
+//!   expired warrant fails verification anyway); holder entries are permanent;
+//! - **checkpointed**: `state_root` is the Merkle root ([`srl_state_root`]) of the state at
+//!   `version` after compaction. A node recomputes it after every apply, so matching roots prove it
+//!   holds exactly the control plane's state;
 //! - **anti-rollback**: the `version` is a strictly increasing monotone counter; a verifier MUST
 //!   reject a list whose version is not greater than the highest it has already applied.
 //!
-//! The signature covers `SRL_SIGN_DOMAIN || version || encoded_entries`, so
-//! entries and version cannot be swapped in or replayed across lists.
+//! The signature covers `SRL_SIGN_DOMAIN || version || base_version ||
+//! compacted_before || state_root || encoded_entries`, so no field can be
+//! swapped in or replayed across lists.
+
+use std::collections::BTreeSet;
 
 use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
 
 use crate::{
     error::{AuthorizationError, Result, WireError, WireResult},
-    warrant::{CborCodec, SignerRef, SigningKeyPair, sha256_prefixed},
+    revocation::subject_revocation_key,
+    warrant::{
+        CborCodec, PaymentSubjectRef, SignerRef, SigningKeyPair, hex_encode_bytes, sha256_prefixed,
+    },
 };
 
 /// Domain-separation prefix for SRL signatures.
-pub const SRL_SIGN_DOMAIN: &[u8] = b"ledgerflow-srl-v1";
+pub const SRL_SIGN_DOMAIN: &[u8] = b"ledgerflow-srl-v2";
 
 /// A single revocation entry.
-#[derive(Clone, Debug, Deserialize, Eq, Hash, PartialEq, Serialize)]
+#[derive(Clone, Debug, Deserialize, Eq, Hash, Ord, PartialEq, PartialOrd, Serialize)]
 #[serde(tag = "kind", rename_all = "snake_case")]
 pub enum SrlEntry {
     /// A warrant (by 16-byte id) is revoked.
     Warrant {
         /// Hex-encoded 16-byte warrant id.
         id_hex: String,
+        /// The warrant's `expires_at` (Unix seconds), when known; the entry
+        /// is compacted away once it has passed.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        expires_at: Option<u64>,
     },
     /// A holder public key is revoked (all its warrants invalid).
     Holder {
         /// Hex-encoded 32-byte public key.
         key_hex: String,
     },
+    /// A payment subject is revoked.
+    Subject {
+        /// Hex-encoded [`subject_revocation_key`].
+        key_hex: String,
+    },
+    /// An issuer or sub-issuer key is revoked (every chain it signed a node
+    /// of is invalid).
+    Issuer {
+        /// Hex-encoded public key.
+        key_hex: String,
+    },
+    /// A `ledgerflow.agent_id` value is revoked.
+    Agent {
+        /// Hex-encoded UTF-8 agent id.
+        key_hex: String,
+    },
+}
+
+impl SrlEntry {
+    /// Returns `true` when compaction at `compacted_before` drops this entry.
+    #[must_use]
+    pub const fn is_expired(&self, compacted_before: u64) -> bool {
+        matches!(self, Self::Warrant { expires_at: Some(expires_at), .. } if *expires_at < compacted_before)
+    }
+
+    fn sort_key(&self) -> (u8, &str, Option<u64>) {
+        match self {
+            Self::Warrant { id_hex, expires_at } => (0, id_hex, *expires_at),
+            Self::Holder { key_hex } => (1, key_hex, None),
+            Self::Subject { key_hex } => (2, key_hex, None),
+            Self::Issuer { key_hex } => (3, key_hex, None),
+            Self::Agent { key_hex } => (4, key_hex, None),
+        }
+    }
+
+    fn encode(&self) -> Vec<u8> {
+        let mut bytes = Vec::new();
+        #[allow(clippy::expect_used)]
+        ciborium::ser::into_writer(self, &mut bytes)
+            .expect("srl entry serialization is infallible");
+        bytes
+    }
 }
 
 /// A signed, versioned revocation list.
@@ -45,25 +104,92 @@ pub enum SrlEntry {
 pub struct SignedRevocationList {
     /// Monotone version (must increase on each new list).
     pub version: u64,
-    /// All revocation entries (additive across versions).
+    /// The version this delta applies on top of; `None` for a snapshot.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub base_version: Option<u64>,
+    /// Warrant entries expired before this time (Unix seconds) are dropped.
+    #[serde(default)]
+    pub compacted_before: u64,
+    /// Snapshot: every entry at `version`. Delta: the entries added since
+    /// `base_version`.
     pub entries: Vec<SrlEntry>,
+    /// Merkle root of the compacted state at `version`.
+    pub state_root: String,
     /// The control-plane signer.
     pub signer: SignerRef,
-    /// Signature over `SRL_SIGN_DOMAIN || version || entries_cbor`.
+    /// Signature over the [module-level](self) preimage.
     pub signature: Vec<u8>,
 }
 
 impl SignedRevocationList {
-    /// Creates and signs a new SRL.
+    /// Creates and signs an uncompacted snapshot.
     #[must_use]
     pub fn sign(version: u64, entries: Vec<SrlEntry>, control_keys: &SigningKeyPair) -> Self {
-        let preimage = preimage(version, &entries);
-        Self {
+        Self::sign_snapshot(version, entries, 0, control_keys)
+    }
+
+    /// Creates and signs a snapshot of `entries` compacted at
+    /// `compacted_before`.
+    #[must_use]
+    pub fn sign_snapshot(
+        version: u64,
+        entries: Vec<SrlEntry>,
+        compacted_before: u64,
+        control_keys: &SigningKeyPair,
+    ) -> Self {
+        let entries = compact(entries, compacted_before);
+        let state_root = srl_state_root(&entries);
+        Self::signed(version, None, compacted_before, entries, state_root, control_keys)
+    }
+
+    /// Creates and signs the delta from `base_version` to `version`.
+    /// `state_root` is the root of the full state at `version` compacted at
+    /// `compacted_before`.
+    #[must_use]
+    pub fn sign_delta(
+        base_version: u64,
+        version: u64,
+        entries: Vec<SrlEntry>,
+        compacted_before: u64,
+        state_root: String,
+        control_keys: &SigningKeyPair,
+    ) -> Self {
+        let entries = compact(entries, compacted_before);
+        Self::signed(
             version,
+            Some(base_version),
+            compacted_before,
             entries,
+            state_root,
+            control_keys,
+        )
+    }
+
+    fn signed(
+        version: u64,
+        base_version: Option<u64>,
+        compacted_before: u64,
+        entries: Vec<SrlEntry>,
+        state_root: String,
+        control_keys: &SigningKeyPair,
+    ) -> Self {
+        let mut list = Self {
+            version,
+            base_version,
+            compacted_before,
+            entries,
+            state_root,
             signer: control_keys.signer_ref(),
-            signature: control_keys.sign(&preimage).value,
-        }
+            signature: Vec::new(),
+        };
+        list.signature = control_keys.sign(&list.preimage()).value;
+        list
+    }
+
+    /// Returns `true` for a delta list.
+    #[must_use]
+    pub const fn is_delta(&self) -> bool {
+        self.base_version.is_some()
     }
 
     /// Verifies the SRL signature against the control-plane signer.
@@ -76,7 +202,7 @@ impl SignedRevocationList {
             alg: crate::warrant::SigningAlgorithm::Ed25519,
             value: self.signature.clone(),
         };
-        envelope.verify_strict(signer, &preimage(self.version, &self.entries))
+        envelope.verify_strict(signer, &self.preimage())
     }
 
     /// Returns a canonical digest of the list (for audit records).
@@ -86,32 +212,80 @@ impl SignedRevocationList {
         let bytes = self.encode_cbor().expect("srl serialization is infallible");
         sha256_prefixed(bytes)
     }
-}
 
-/// Computes the domain-separated signing preimage of an SRL.
-fn preimage(version: u64, entries: &[SrlEntry]) -> Vec<u8> {
-    let mut bytes = Vec::with_capacity(SRL_SIGN_DOMAIN.len() + 16 + entries.len() * 64);
-    bytes.extend_from_slice(SRL_SIGN_DOMAIN);
-    bytes.extend_from_slice(&version.to_be_bytes());
-    // Deterministic encoding: entries are encoded in sorted order so the
-    // preimage is canonical regardless of insertion order.
-    let mut sorted = entries.to_vec();
-    sorted.sort_by_key(|e| match e {
-        SrlEntry::Warrant { id_hex } => (0_u8, id_hex.clone()),
-        SrlEntry::Holder { key_hex } => (1_u8, key_hex.clone()),
-    });
-    for entry in sorted {
-        #[allow(clippy::expect_used)]
-        ciborium::ser::into_writer(&entry, &mut bytes)
-            .expect("srl entry serialization is infallible");
+    /// Computes the domain-separated signing preimage.
+    fn preimage(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(SRL_SIGN_DOMAIN.len() + 96 + self.entries.len() * 64);
+        bytes.extend_from_slice(SRL_SIGN_DOMAIN);
+        bytes.extend_from_slice(&self.version.to_be_bytes());
+        match self.base_version {
+            Some(base_version) => {
+                bytes.push(1);
+                bytes.extend_from_slice(&base_version.to_be_bytes());
+            }
+            None => bytes.push(0),
+        }
+        bytes.extend_from_slice(&self.compacted_before.to_be_bytes());
+        bytes.extend_from_slice(&(self.state_root.len() as u64).to_be_bytes());
+        bytes.extend_from_slice(self.state_root.as_bytes());
+        // Deterministic encoding: entries are encoded in sorted order so the
+        // preimage is canonical regardless of insertion order.
+        let mut sorted: Vec<&SrlEntry> = self.entries.iter().collect();
+        sorted.sort_by(|a, b| a.sort_key().cmp(&b.sort_key()));
+        for entry in sorted {
+            bytes.extend_from_slice(&entry.encode());
+        }
+        bytes
     }
-    bytes
 }
 
 impl CborCodec for SignedRevocationList {}
 
+/// Drops the entries compaction at `compacted_before` removes.
+fn compact(mut entries: Vec<SrlEntry>, compacted_before: u64) -> Vec<SrlEntry> {
+    entries.retain(|entry| !entry.is_expired(compacted_before));
+    entries
+}
+
+/// Merkle root of a revocation state (`sha256:<hex>`), independent of entry
+/// order and duplicates.
+///
+/// Leaves are `SHA-256(0x00 || CBOR(entry))`, sorted and deduplicated;
+/// inner nodes are `SHA-256(0x01 || left || right)`, an odd node is carried
+/// up unchanged, and the empty state hashes to `SHA-256("")`.
+#[must_use]
+pub fn srl_state_root<'a>(entries: impl IntoIterator<Item = &'a SrlEntry>) -> String {
+    let mut level: Vec<[u8; 32]> = entries
+        .into_iter()
+        .map(|entry| {
+            Sha256::new().chain_update([0x00]).chain_update(entry.encode()).finalize().into()
+        })
+        .collect();
+    level.sort_unstable();
+    level.dedup();
+    if level.is_empty() {
+        return sha256_prefixed([]);
+    }
+    while level.len() > 1 {
+        level = level
+            .chunks(2)
+            .filter_map(|pair| {
+                pair.iter().copied().reduce(|left, right| {
+                    Sha256::new()
+                        .chain_update([0x01])
+                        .chain_update(left)
+                        .chain_update(right)
+                        .finalize()
+                        .into()
+                })
+            })
+            .collect();
+    }
+    format!("sha256:{}", hex_encode_bytes(&level[0]))
+}
+
 /// Incremental SRL application state: tracks the highest applied version and
-/// the union of applied entries.
+/// the applied entries.
 ///
 /// This is the pure-domain counterpart of the verifier node's local
 /// revocation store: a node applies an SRL by advancing this state, then
@@ -120,22 +294,28 @@ impl CborCodec for SignedRevocationList {}
 pub struct SrlState {
     /// Highest SRL version applied.
     pub applied_version: u64,
-    /// All entries seen so far (deduplicated).
-    pub entries: Vec<SrlEntry>,
+    /// The live entries (deduplicated, compacted).
+    pub entries: BTreeSet<SrlEntry>,
+    /// Compaction cutoff of the last applied list.
+    pub compacted_before: u64,
 }
 
 impl SrlState {
     /// Creates an empty SRL state.
     #[must_use]
     pub const fn new() -> Self {
-        Self { applied_version: 0, entries: Vec::new() }
+        Self { applied_version: 0, entries: BTreeSet::new(), compacted_before: 0 }
     }
 
-    /// Applies a signed SRL.
+    /// Applies a signed SRL: a snapshot replaces the state, a delta is
+    /// merged into it. The state is then compacted and must hash to the
+    /// list's `state_root`.
     ///
-    /// Fails when the list's version is not strictly greater than the already
-    /// applied version (anti-rollback), or when the signature does not verify
-    /// against the trusted control-plane signer.
+    /// Fails, leaving the state untouched, when the list's version is not
+    /// strictly greater than the already applied version (anti-rollback),
+    /// when the signature does not verify against the trusted control-plane
+    /// signer, when a delta's base is not the applied version, or when the
+    /// resulting state does not match the checkpoint.
     pub fn apply(&mut self, list: &SignedRevocationList, trusted_signer: &SignerRef) -> Result<()> {
         if list.version <= self.applied_version {
             return Err(AuthorizationError::SrlVersionRegression {
@@ -146,27 +326,83 @@ impl SrlState {
         if !list.verify_signature(trusted_signer) {
             return Err(AuthorizationError::InvalidSrlSignature);
         }
-        for entry in &list.entries {
-            if !self.entries.contains(entry) {
-                self.entries.push(entry.clone());
+        let mut entries = match list.base_version {
+            Some(base) if base != self.applied_version => {
+                return Err(AuthorizationError::SrlDeltaBaseMismatch {
+                    base,
+                    applied: self.applied_version,
+                });
             }
+            Some(_) => self.entries.clone(),
+            None => BTreeSet::new(),
+        };
+        entries.extend(list.entries.iter().cloned());
+        let compacted_before = self.compacted_before.max(list.compacted_before);
+        entries.retain(|entry| !entry.is_expired(compacted_before));
+        let state_root = srl_state_root(&entries);
+        if state_root != list.state_root {
+            return Err(AuthorizationError::SrlStateRootMismatch {
+                expected: list.state_root.clone(),
+                actual: state_root,
+            });
         }
+        self.entries = entries;
+        self.compacted_before = compacted_before;
         self.applied_version = list.version;
         Ok(())
     }
 
-    /// Checks whether a warrant is revoked per the applied SRL.
+    /// Drops warrant entries whose warrant expired before
+    /// `compacted_before`; returns how many were dropped.
+    pub fn compact(&mut self, compacted_before: u64) -> usize {
+        let before = self.entries.len();
+        self.entries.retain(|entry| !entry.is_expired(compacted_before));
+        self.compacted_before = self.compacted_before.max(compacted_before);
+        before - self.entries.len()
+    }
+
+    /// The checkpoint of the applied state (see [`srl_state_root`]).
+    #[must_use]
+    pub fn state_root(&self) -> String {
+        srl_state_root(&self.entries)
+    }
+
+    /// Checks whether a warrant is revoked per the applied SRL, whatever
+    /// expiry its entry carries.
     #[must_use]
     pub fn is_warrant_revoked(&self, warrant_id: &[u8]) -> bool {
-        let id_hex = crate::warrant::hex_encode_bytes(warrant_id);
-        self.entries.iter().any(|e| matches!(e, SrlEntry::Warrant { id_hex: e } if e == &id_hex))
+        let id_hex = hex_encode_bytes(warrant_id);
+        let first = SrlEntry::Warrant { id_hex: id_hex.clone(), expires_at: None };
+        let last = SrlEntry::Warrant { id_hex, expires_at: Some(u64::MAX) };
+        self.entries.range(first..=last).next().is_some()
     }
 
     /// Checks whether a holder key is revoked per the applied SRL.
     #[must_use]
     pub fn is_holder_revoked(&self, holder: &SignerRef) -> bool {
-        let key_hex = crate::warrant::hex_encode_bytes(&holder.public_key);
-        self.entries.iter().any(|e| matches!(e, SrlEntry::Holder { key_hex: e } if e == &key_hex))
+        let key_hex = hex_encode_bytes(&holder.public_key);
+        self.entries.contains(&SrlEntry::Holder { key_hex })
+    }
+
+    /// Checks whether a payment subject is revoked per the applied SRL.
+    #[must_use]
+    pub fn is_subject_revoked(&self, subject: &PaymentSubjectRef) -> bool {
+        let key_hex = hex_encode_bytes(&subject_revocation_key(subject));
+        self.entries.contains(&SrlEntry::Subject { key_hex })
+    }
+
+    /// Checks whether an issuer key is revoked per the applied SRL.
+    #[must_use]
+    pub fn is_issuer_revoked(&self, issuer: &SignerRef) -> bool {
+        let key_hex = hex_encode_bytes(&issuer.public_key);
+        self.entries.contains(&SrlEntry::Issuer { key_hex })
+    }
+
+    /// Checks whether an agent id is revoked per the applied SRL.
+    #[must_use]
+    pub fn is_agent_revoked(&self, agent_id: &str) -> bool {
+        let key_hex = hex_encode_bytes(agent_id.as_bytes());
+        self.entries.contains(&SrlEntry::Agent { key_hex })
     }
 
     /// Serializes the entries for wire transmission (as a new SRL body).
@@ -191,7 +427,7 @@ mod tests {
     fn sample_list(version: u64) -> SignedRevocationList {
         SignedRevocationList::sign(
             version,
-            vec![SrlEntry::Warrant { id_hex: "aabb".to_string() }],
+            vec![SrlEntry::Warrant { id_hex: "aabb".to_string(), expires_at: None }],
             &control_keys(),
         )
     }
@@ -204,7 +440,7 @@ mod tests {
         assert_eq!(list.digest(), sample_list(1).digest());
         let other = SignedRevocationList::sign(
             1,
-            vec![SrlEntry::Warrant { id_hex: "ccdd".to_string() }],
+            vec![SrlEntry::Warrant { id_hex: "ccdd".to_string(), expires_at: None }],
             &control_keys(),
         );
         assert_ne!(list.digest(), other.digest());
@@ -270,4 +506,131 @@ mod tests {
         let error = state.apply(&forged, &control_keys().signer_ref()).expect_err("forged");
         assert_eq!(error, AuthorizationError::InvalidSrlSignature);
     }
+
+    fn warrant(id_hex: &str, expires_at: Option<u64>) -> SrlEntry {
+        SrlEntry::Warrant { id_hex: id_hex.to_string(), expires_at }
+    }
+
+    #[test]
+    fn state_root_ignores_order_and_duplicates() {
+        let a = warrant("aa", Some(10));
+        let b = SrlEntry::Holder { key_hex: "bb".to_string() };
+        let c = warrant("cc", None);
+        let root = srl_state_root(&[a.clone(), b.clone(), c.clone()]);
+        assert!(root.starts_with("sha256:"));
+        assert_eq!(root, srl_state_root(&[c, a.clone(), b.clone(), a.clone()]));
+        assert_ne!(root, srl_state_root(&[a, b]));
+        assert_eq!(srl_state_root(&[]), sha256_prefixed([]));
+    }
+
+    #[test]
+    fn deltas_apply_on_their_base_and_match_the_checkpoint() {
+        let keys = control_keys();
+        let signer = keys.signer_ref();
+        let mut state = SrlState::new();
+        state
+            .apply(&SignedRevocationList::sign(1, vec![warrant("aa", None)], &keys), &signer)
+            .expect("v1");
+
+        let full = vec![warrant("aa", None), warrant("bb", None)];
+        let delta = SignedRevocationList::sign_delta(
+            1,
+            2,
+            vec![warrant("bb", None)],
+            0,
+            srl_state_root(&full),
+            &keys,
+        );
+        assert!(delta.is_delta());
+        // The base is signed: a delta cannot be re-based.
+        let mut rebased = delta.clone();
+        rebased.base_version = Some(0);
+        assert!(!rebased.verify_signature(&signer));
+
+        let mut behind = SrlState::new();
+        let error = behind.apply(&delta, &signer).expect_err("wrong base");
+        assert_eq!(error, AuthorizationError::SrlDeltaBaseMismatch { base: 1, applied: 0 });
+        assert_eq!(behind, SrlState::new());
+
+        state.apply(&delta, &signer).expect("v2");
+        assert_eq!(state.applied_version, 2);
+        assert_eq!(state.state_root(), srl_state_root(&full));
+        assert!(state.is_warrant_revoked(&[0xbb]));
+
+        // A delta whose checkpoint disagrees with the local state is refused.
+        let skewed = SignedRevocationList::sign_delta(
+            2,
+            3,
+            vec![warrant("cc", None)],
+            0,
+            srl_state_root(&full),
+            &keys,
+        );
+        let error = state.apply(&skewed, &signer).expect_err("root mismatch");
+        assert!(matches!(error, AuthorizationError::SrlStateRootMismatch { .. }));
+        assert_eq!(state.applied_version, 2);
+    }
+
+    #[test]
+    fn scoped_entries_answer_their_own_queries_only() {
+        use crate::warrant::PaymentSubjectKind;
+
+        let keys = control_keys();
+        let subject = PaymentSubjectRef::new(PaymentSubjectKind::Opaque, "acct-1");
+        let issuer = SigningKeyPair::from_bytes(&[0x2F; 32]).signer_ref();
+        let list = SignedRevocationList::sign(
+            1,
+            vec![
+                SrlEntry::Subject { key_hex: hex_encode_bytes(&subject_revocation_key(&subject)) },
+                SrlEntry::Issuer { key_hex: hex_encode_bytes(&issuer.public_key) },
+                SrlEntry::Agent { key_hex: hex_encode_bytes(b"agent-1") },
+            ],
+            &keys,
+        );
+        let mut state = SrlState::new();
+        state.apply(&list, &keys.signer_ref()).expect("apply");
+        assert!(state.is_subject_revoked(&subject));
+        assert!(state.is_issuer_revoked(&issuer));
+        assert!(state.is_agent_revoked("agent-1"));
+        // An issuer entry does not revoke the same key as a holder.
+        assert!(!state.is_holder_revoked(&issuer));
+        assert!(!state.is_agent_revoked("agent-2"));
+    }
+
+    #[test]
+    fn compaction_drops_only_expired_warrants() {
+        let keys = control_keys();
+        let holder = SrlEntry::Holder { key_hex: "ff".to_string() };
+        let entries = vec![
+            warrant("aa", Some(100)),
+            warrant("bb", Some(300)),
+            warrant("cc", None),
+            holder.clone(),
+        ];
+        let list = SignedRevocationList::sign_snapshot(4, entries, 200, &keys);
+        assert_eq!(list.entries, vec![warrant("bb", Some(300)), warrant("cc", None), holder]);
+
+        let mut state = SrlState::new();
+        state.apply(&list, &keys.signer_ref()).expect("apply");
+        assert!(!state.is_warrant_revoked(&[0xaa]));
+        assert!(state.is_warrant_revoked(&[0xbb]));
+        assert!(state.is_warrant_revoked(&[0xcc]));
+        assert_eq!(state.compact(400), 1);
+        assert_eq!(state.compacted_before, 400);
+        assert_eq!(state.entries.len(), 2);
+    }
+
+    #[test]
+    fn repeated_entries_are_applied_once() {
+        let keys = control_keys();
+        let holder = SrlEntry::Holder { key_hex: "ff".to_string() };
+        let entries = vec![warrant("aa", None), holder.clone(), warrant("aa", None)];
+        let root = srl_state_root(&entries);
+        let snapshot = SignedRevocationList::sign_snapshot(1, entries, 0, &keys);
+        let mut state = SrlState::new();
+        state.apply(&snapshot, &keys.signer_ref()).expect("snapshot");
+        let delta = SignedRevocationList::sign_delta(1, 2, vec![holder], 0, root, &keys);
+        state.apply(&delta, &keys.signer_ref()).expect("delta");
+        assert_eq!(state.entries.len(), 2);
+    }
 }
@@ -3,15 +3,25 @@
 //! Every verifier (merchant or Facilitator) configures a set of trusted
 //! issuer public keys. The **root** warrant of any presented chain must have
 //! been issued by one of these trusted issuers, otherwise the chain is
-//! rejected (fail-closed). Key rotation is supported via `key_id`: a rotated
-//! issuer simply replaces its entry in the set.
+//! rejected (fail-closed).
+//!
+//! Key rotation (design §6.8) uses validity windows: each entry may carry a
+//! `not_before` / `not_after` bound on the root warrant's `issued_at`. During
+//! a rotation the old and new roots are both valid; the old root's
+//! `not_after` closes the overlap window and the entry leaves the set once no
+//! warrant it signed can still be live ([`TrustedIssuers::prune_retired`]).
+//! [`TrustAnchorSet`] carries the set behind an `ArcSwap` so verifiers can
+//! reload it without restarting.
+
+use std::sync::Arc;
 
+use arc_swap::ArcSwap;
 use serde::{Deserialize, Serialize};
 
 use crate::{
     agent_identity::{AgentIdRef, IdentityResolver},
     error::{AuthorizationError, Result},
-    warrant::{SignerRef, Warrant},
+    warrant::{MAX_WARRANT_TTL_SECS, SignerRef, Warrant},
 };
 
 /// A single trusted issuer entry.
@@ -30,13 +40,20 @@ pub struct TrustedIssuer {
     /// Optional EIP-8004 agent identity anchoring this issuer.
     #[serde(default)]
     pub anchor: Option<AgentIdRef>,
+    /// Earliest root `issued_at` (Unix seconds) this entry accepts.
+    #[serde(default)]
+    pub not_before: Option<u64>,
+    /// Latest root `issued_at` (Unix seconds) this entry accepts; set when
+    /// the key is rotated out.
+    #[serde(default)]
+    pub not_after: Option<u64>,
 }
 
 impl TrustedIssuer {
     /// Creates a statically keyed trusted issuer (no anchor).
     #[must_use]
     pub const fn new(key_id: String, issuer: SignerRef) -> Self {
-        Self { key_id, issuer, anchor: None }
+        Self { key_id, issuer, anchor: None, not_before: None, not_after: None }
     }
 
     /// Creates a trusted issuer anchored to an EIP-8004 agent identity.
@@ -45,7 +62,34 @@ impl TrustedIssuer {
     /// accepted directly even before any resolution succeeds.
     #[must_use]
     pub const fn anchored(key_id: String, issuer: SignerRef, anchor: AgentIdRef) -> Self {
-        Self { key_id, issuer, anchor: Some(anchor) }
+        Self { key_id, issuer, anchor: Some(anchor), not_before: None, not_after: None }
+    }
+
+    /// Bounds the root `issued_at` values this entry accepts (inclusive).
+    #[must_use]
+    pub const fn with_validity(mut self, not_before: Option<u64>, not_after: Option<u64>) -> Self {
+        self.not_before = not_before;
+        self.not_after = not_after;
+        self
+    }
+
+    /// Returns `true` when a root issued at `issued_at` falls inside this
+    /// entry's validity window.
+    #[must_use]
+    pub const fn is_valid_at(&self, issued_at: u64) -> bool {
+        let after_start = match self.not_before {
+            Some(not_before) => issued_at >= not_before,
+            None => true,
+        };
+        let before_end = match self.not_after {
+            Some(not_after) => issued_at <= not_after,
+            None => true,
+        };
+        after_start && before_end
+    }
+
+    fn matches(&self, signer: &SignerRef) -> bool {
+        self.issuer.alg == signer.alg && self.issuer.public_key == signer.public_key
     }
 }
 
@@ -70,12 +114,50 @@ impl TrustedIssuers {
         self.issuers.push(issuer);
     }
 
-    /// Returns `true` when the signer is trusted.
+    /// Returns `true` when the signer is in the set (ignoring validity
+    /// windows).
     #[must_use]
     pub fn contains(&self, signer: &SignerRef) -> bool {
-        self.issuers.iter().any(|entry| {
-            entry.issuer.alg == signer.alg && entry.issuer.public_key == signer.public_key
-        })
+        self.issuers.iter().any(|entry| entry.matches(signer))
+    }
+
+    /// Returns `true` when the signer may sign a root issued at `issued_at`.
+    #[must_use]
+    pub fn contains_at(&self, signer: &SignerRef, issued_at: u64) -> bool {
+        self.issuers.iter().any(|entry| entry.matches(signer) && entry.is_valid_at(issued_at))
+    }
+
+    /// Starts a rotation: every entry with `retiring_key_id` stops accepting
+    /// roots issued after `now_secs + overlap_secs`, and `next` is added.
+    ///
+    /// Returns `false` (and adds nothing) when no entry has that key id.
+    pub fn rotate(
+        &mut self,
+        retiring_key_id: &str,
+        next: TrustedIssuer,
+        now_secs: u64,
+        overlap_secs: u64,
+    ) -> bool {
+        let cutoff = now_secs.saturating_add(overlap_secs);
+        let mut found = false;
+        for entry in self.issuers.iter_mut().filter(|entry| entry.key_id == retiring_key_id) {
+            entry.not_after = Some(entry.not_after.map_or(cutoff, |end| end.min(cutoff)));
+            found = true;
+        }
+        if found {
+            self.issuers.push(next);
+        }
+        found
+    }
+
+    /// Drops entries whose window closed long enough ago that no warrant
+    /// they signed can still be live (`not_after + MAX_WARRANT_TTL_SECS`).
+    pub fn prune_retired(&mut self, now_secs: u64) {
+        self.issuers.retain(|entry| {
+            entry
+                .not_after
+                .is_none_or(|not_after| not_after.saturating_add(MAX_WARRANT_TTL_SECS) >= now_secs)
+        });
     }
 
     /// Returns `true` when the set is non-empty.
@@ -95,7 +177,8 @@ impl TrustedIssuers {
     /// Verifies the root issuer against the trust set, optionally resolving
     /// EIP-8004 anchored identities through `resolver`.
     ///
-    /// Acceptance order:
+    /// Only entries whose validity window covers the root's `issued_at` are
+    /// considered. Acceptance order:
     ///
     /// 1. Direct static key match (bootstrap keys always work).
     /// 2. For each anchored entry: resolve the anchor's current keys and accept when the root
@@ -106,18 +189,20 @@ impl TrustedIssuers {
     /// [`AuthorizationError::IdentityResolutionFailed`]. When no entry
     /// accepts the key the error is
     /// [`AuthorizationError::IssuerNotBoundToIdentity`] for anchored entries
-    /// or [`AuthorizationError::UntrustedIssuer`] otherwise.
+    /// or [`AuthorizationError::UntrustedIssuer`] otherwise; a key that is
+    /// only trusted outside its window fails with
+    /// [`AuthorizationError::IssuerOutsideValidity`].
     pub fn verify_root_with_resolver(
         &self,
         root: &Warrant,
         resolver: Option<&dyn IdentityResolver>,
     ) -> Result<()> {
-        if self.contains(&root.issuer) {
+        if self.contains_at(&root.issuer, root.issued_at) {
             return Ok(());
         }
         let mut saw_anchor = false;
         let mut last_anchor: Option<&AgentIdRef> = None;
-        for entry in &self.issuers {
+        for entry in self.issuers.iter().filter(|entry| entry.is_valid_at(root.issued_at)) {
             let Some(anchor) = &entry.anchor else {
                 continue;
             };
@@ -139,7 +224,12 @@ impl TrustedIssuers {
                 return Ok(());
             }
         }
-        if saw_anchor {
+        if let Some(entry) = self.issuers.iter().find(|entry| entry.matches(&root.issuer)) {
+            Err(AuthorizationError::IssuerOutsideValidity {
+                key_id: entry.key_id.clone(),
+                issued_at: root.issued_at,
+            })
+        } else if saw_anchor {
             Err(AuthorizationError::IssuerNotBoundToIdentity {
                 reference: last_anchor.map_or_else(String::new, std::string::ToString::to_string),
             })
@@ -151,6 +241,54 @@ impl TrustedIssuers {
     }
 }
 
+// ---------------------------------------------------------------------------
+// Hot-swappable anchor set
+// ---------------------------------------------------------------------------
+
+/// A [`TrustedIssuers`] set that can be replaced atomically while verifiers
+/// keep reading it (design §6.8 hot configuration).
+///
+/// Clones share the same underlying slot, so a reload through one handle is
+/// seen by every verifier holding another.
+#[derive(Clone, Debug, Default)]
+pub struct TrustAnchorSet {
+    current: Arc<ArcSwap<TrustedIssuers>>,
+}
+
+impl TrustAnchorSet {
+    /// Creates a set holding `initial`.
+    #[must_use]
+    pub fn new(initial: TrustedIssuers) -> Self {
+        Self { current: Arc::new(ArcSwap::from_pointee(initial)) }
+    }
+
+    /// Returns the current snapshot (lock-free).
+    #[must_use]
+    pub fn load(&self) -> Arc<TrustedIssuers> {
+        self.current.load_full()
+    }
+
+    /// Replaces the whole set.
+    pub fn store(&self, next: TrustedIssuers) {
+        self.current.store(Arc::new(next));
+    }
+
+    /// Applies `update` to the current set (retrying if another writer raced
+    /// it) and returns the stored result.
+    pub fn update(
+        &self,
+        update: impl Fn(&TrustedIssuers) -> TrustedIssuers,
+    ) -> Arc<TrustedIssuers> {
+        let mut stored = None;
+        self.current.rcu(|current| {
+            let next = Arc::new(update(current));
+            stored = Some(Arc::clone(&next));
+            next
+        });
+        stored.unwrap_or_else(|| self.load())
+    }
+}
+
 #[cfg(test)]
 mod tests {
     #![allow(clippy::expect_used)]
@@ -175,6 +313,7 @@ mod tests {
     }
 
     /// Resolver returning configured keys; `fail` forces resolution errors.
+    #[derive(Debug)]
     struct MapResolver {
         keys: Vec<SignerRef>,
         fail: bool,
@@ -295,6 +434,52 @@ mod tests {
         assert!(parsed.anchor.is_none());
     }
 
+    #[test]
+    fn rotation_overlap_is_evaluated_against_issued_at() {
+        let mut set = TrustedIssuers::new();
+        set.add(TrustedIssuer::new("old".to_string(), issuer_keys().signer_ref()));
+        let next = TrustedIssuer::new("new".to_string(), rotated_keys().signer_ref())
+            .with_validity(Some(1), None);
+        assert!(set.rotate("old", next.clone(), 1, 10));
+        assert!(!set.rotate("missing", next, 1, 10));
+
+        // sample_root issues at 1 s; both roots are valid during the overlap.
+        set.verify_root(&sample_root(issuer_keys())).expect("old root in overlap");
+        set.verify_root(&sample_root(rotated_keys())).expect("new root");
+
+        let mut late = sample_root(issuer_keys());
+        late.issued_at = 12;
+        let late = late.sign_with(&issuer_keys());
+        let error = set.verify_root(&late).expect_err("old root after overlap");
+        assert!(matches!(
+            error,
+            AuthorizationError::IssuerOutsideValidity { ref key_id, issued_at: 12 } if key_id == "old"
+        ));
+
+        set.prune_retired(11 + MAX_WARRANT_TTL_SECS);
+        assert_eq!(set.issuers.len(), 2);
+        set.prune_retired(12 + MAX_WARRANT_TTL_SECS);
+        assert_eq!(set.issuers.iter().map(|e| e.key_id.as_str()).collect::<Vec<_>>(), ["new"]);
+    }
+
+    #[test]
+    fn anchor_set_swaps_are_seen_by_every_handle() {
+        let anchors = TrustAnchorSet::new(TrustedIssuers::new());
+        let reader = anchors.clone();
+        assert!(reader.load().verify_root(&sample_root(issuer_keys())).is_err());
+
+        let updated = anchors.update(|current| {
+            let mut next = current.clone();
+            next.add(TrustedIssuer::new("a".to_string(), issuer_keys().signer_ref()));
+            next
+        });
+        assert_eq!(updated.issuers.len(), 1);
+        reader.load().verify_root(&sample_root(issuer_keys())).expect("reloaded");
+
+        anchors.store(TrustedIssuers::new());
+        assert!(reader.load().is_empty());
+    }
+
     fn sample_root(keys: SigningKeyPair) -> Warrant {
         use crate::constraint::{MerchantConstraint, PaymentConstraint, ResourceConstraint};
         crate::typestate::WarrantBuilder::new(1_000)
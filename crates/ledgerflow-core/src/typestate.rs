@@ -11,7 +11,7 @@ use crate::{
     constraint::{MerchantConstraint, PaymentConstraint, ResourceConstraint, ToolConstraint},
     warrant::{
         DEFAULT_MAX_DEPTH, DEFAULT_WARRANT_TTL_SECS, MAX_DELEGATION_DEPTH, MAX_WARRANT_TTL_SECS,
-        SignerRef, SigningKeyPair, Warrant, generate_warrant_id_128,
+        SignatureEnvelope, SignerRef, Warrant, WarrantSigner, generate_warrant_id_128,
     },
 };
 
@@ -257,7 +257,9 @@ impl<I, Sig> WarrantBuilder<I, NoHolder, Sig> {
 }
 
 impl WarrantBuilder<HasIssuer, HasHolder, Unsigned> {
-    /// Signs the warrant with the issuer's key pair and returns it.
+    /// Signs the warrant with the issuer's key (Ed25519 or an EVM
+    /// [`Secp256k1WarrantSigner`](crate::crypto::Secp256k1WarrantSigner)) and
+    /// returns it.
     ///
     /// `random_bytes` supplies 8 bytes of caller randomness; the full 128-bit
     /// UUIDv7 id is derived by extending it with the timestamp's low bytes
@@ -265,9 +267,20 @@ impl WarrantBuilder<HasIssuer, HasHolder, Unsigned> {
     /// production. When an explicit id was set via [`Self::warrant_id`],
     /// `random_bytes` is ignored.
     ///
-    /// This is the only terminal transition; it is available once both issuer
-    /// and holder are configured.
-    pub fn sign_with(self, issuer_keys: &SigningKeyPair, random_bytes: [u8; 8]) -> Warrant {
+    /// Like [`Self::build_unsigned`], this terminal transition is available
+    /// once both issuer and holder are configured.
+    pub fn sign_with(self, issuer_keys: &dyn WarrantSigner, random_bytes: [u8; 8]) -> Warrant {
+        self.build_unsigned(random_bytes).sign_with(issuer_keys)
+    }
+
+    /// Builds the warrant without signing it, for issuers whose key lives
+    /// outside the process (e.g. a wallet daemon). The returned warrant
+    /// carries an empty signature; sign its [`Warrant::signed_message`] and
+    /// attach the envelope with [`Warrant::with_signature`].
+    ///
+    /// `random_bytes` is used as in [`Self::sign_with`].
+    #[must_use]
+    pub fn build_unsigned(self, random_bytes: [u8; 8]) -> Warrant {
         let mut builder = self;
         let id = builder.explicit_id.take().unwrap_or_else(|| {
             let mut random128 = [0_u8; 16];
@@ -287,8 +300,9 @@ impl WarrantBuilder<HasIssuer, HasHolder, Unsigned> {
         let issuer = builder.issuer.expect("warrant builder: issuer is required");
         #[allow(clippy::expect_used)]
         let holder = builder.holder.expect("warrant builder: holder is required");
+        let alg = issuer.alg;
 
-        let mut warrant = Warrant {
+        Warrant {
             version: crate::warrant::WARRANT_VERSION_V1,
             id: id.to_vec(),
             holder,
@@ -306,10 +320,8 @@ impl WarrantBuilder<HasIssuer, HasHolder, Unsigned> {
             required_approvers: builder.required_approvers,
             min_approvals: builder.min_approvals,
             extensions: builder.extensions,
-            signature: issuer_keys.sign(b"placeholder"),
-        };
-        warrant = warrant.sign_with(issuer_keys);
-        warrant
+            signature: SignatureEnvelope { alg, value: Vec::new() },
+        }
     }
 }
 
@@ -415,7 +427,7 @@ impl DelegatedWarrantBuilder {
     pub fn issue_to(
         self,
         new_holder: SignerRef,
-        delegator_keys: &SigningKeyPair,
+        delegator_keys: &dyn WarrantSigner,
         now_ms: u64,
         random_bytes: [u8; 8],
     ) -> Warrant {
@@ -493,7 +505,7 @@ impl DelegatedWarrantBuilder {
             required_approvers: parent.required_approvers.clone(),
             min_approvals: parent.min_approvals,
             extensions: parent.extensions.clone(),
-            signature: delegator_keys.sign(b"placeholder"),
+            signature: SignatureEnvelope { alg: parent.holder.alg, value: Vec::new() },
         };
         child = child.sign_with(delegator_keys);
         child
@@ -595,7 +607,10 @@ mod tests {
     #![allow(clippy::expect_used)]
 
     use super::*;
-    use crate::issue_bounds::{ISSUE_BOUNDS_EXTENSION, IssueBounds};
+    use crate::{
+        issue_bounds::{ISSUE_BOUNDS_EXTENSION, IssueBounds},
+        warrant::SigningKeyPair,
+    };
 
     fn issuer_keys() -> SigningKeyPair {
         SigningKeyPair::from_bytes(&[0x3A; 32])
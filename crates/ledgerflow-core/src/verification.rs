@@ -5,28 +5,43 @@
 //!
 //! 1. Chain verification (I1-I7, trust anchor) — [`crate::chain`]
 //! 2. PoP verification + freshness — [`crate::pop`]
-//! 3. Approval gates (m-of-n) — [`crate::approval`]
-//! 4. Revocation check (online seam) — [`crate::revocation`]
+//! 3. Approval gates (m-of-n) — [`crate::approval`], with approver groups resolved through
+//!    [`crate::approver_set`]
+//! 4. Revocation check (online seam) at every chain node — [`crate::revocation`]
+//!
+//! Signatures of contract-account (20-byte address) issuers, holders and
+//! approvers are accepted through the optional ERC-1271 seam
+//! ([`AuthorizationInput::contract_verifier`]), and trust entries anchored to
+//! an EIP-8004 agent identity through the optional identity seam
+//! ([`AuthorizationInput::identity_resolver`]).
 //!
 //! Online checks (revocation) are passed in as a trait object so the core
 //! stays stateless while production deployments wire in persistent storage.
 
 use crate::{
-    approval::{ApprovalGate, SignedApproval, verify_approvals},
-    chain::{VerifiedChainAuthorization, WarrantChain, verify_chain},
+    agent_identity::IdentityResolver,
+    approval::{
+        ApprovalGate, SignedApproval, verify_approval_threshold_with, verify_approvals_with,
+    },
+    approver_set::{ApproverSetResolver, effective_approvers},
+    chain::{VerifiedChainAuthorization, WarrantChain, verify_chain_with},
     constraint::{AuthorizationContext, Verify},
+    erc1271::ContractSignatureVerifier,
     error::{AuthorizationError, Result},
     pop::PopProof,
-    revocation::{RevocationCheck, RevocationDecision},
+    revocation::{RevocationCheck, verify_chain_revocation},
     trust::TrustedIssuers,
-    warrant::{SignerRef, Warrant},
+    warrant::{CborCodec, MAX_WARRANT_CBOR_BYTES, SignerRef, Warrant},
 };
 
 /// Tool-call arguments used to evaluate approval gates.
 pub type ToolArguments = std::collections::BTreeMap<String, String>;
 
 /// The result of a successful authorization.
-#[derive(Clone, Debug, Eq, PartialEq)]
+///
+/// Serializable so idempotency caches can persist it (CBOR via
+/// [`CborCodec`]).
+#[derive(Clone, Debug, Eq, PartialEq, serde::Deserialize, serde::Serialize)]
 pub struct VerifiedAuthorization {
     pub merchant_id: String,
     pub tool_name: String,
@@ -46,6 +61,13 @@ pub struct VerifiedAuthorization {
     pub warrant_digest: String,
 }
 
+impl CborCodec for VerifiedAuthorization {
+    /// Carries the leaf and root warrants, so allow two warrants' worth.
+    fn max_cbor_bytes() -> usize {
+        2 * MAX_WARRANT_CBOR_BYTES
+    }
+}
+
 /// Inputs for a full authorization check.
 #[derive(Clone, Debug)]
 pub struct AuthorizationInput<'a> {
@@ -66,12 +88,37 @@ pub struct AuthorizationInput<'a> {
     /// proof-of-possession ↔ payment binding gap (design §6.3). Callers that do
     /// not compute a bound leave this `None` (no check).
     pub payment_payload_digest: Option<String>,
+    /// Optional ERC-1271 seam for contract-account signers. `None` accepts
+    /// only directly verifiable signatures.
+    pub contract_verifier: Option<&'a dyn ContractSignatureVerifier>,
+    /// Optional EIP-8004 seam for anchored trust entries. `None` accepts only
+    /// statically trusted issuer keys.
+    pub identity_resolver: Option<&'a dyn IdentityResolver>,
 }
 
 /// Runs the full authorization pipeline.
+///
+/// Warrants that reference an approver set fail closed at the approval step;
+/// use [`verify_authorization_with_approver_sets`] to resolve them.
 pub fn verify_authorization(input: &AuthorizationInput<'_>) -> Result<VerifiedAuthorization> {
+    verify_authorization_with_approver_sets(input, None)
+}
+
+/// Runs the full authorization pipeline, resolving approver-set references
+/// through `approver_sets` (design §6.5).
+pub fn verify_authorization_with_approver_sets(
+    input: &AuthorizationInput<'_>,
+    approver_sets: Option<&dyn ApproverSetResolver>,
+) -> Result<VerifiedAuthorization> {
     // 1. Chain + PoP + trust anchor + freshness.
-    let chain_verified = verify_chain(input.chain, input.trusted, input.proof, input.context)?;
+    let chain_verified = verify_chain_with(
+        input.chain,
+        input.trusted,
+        input.identity_resolver,
+        input.contract_verifier,
+        input.proof,
+        input.context,
+    )?;
 
     // 1b. Payment-payload binding: when the caller supplies an expected digest,
     // the PoP must commit to the exact payment payload (design §6.3). This
@@ -83,42 +130,42 @@ pub fn verify_authorization(input: &AuthorizationInput<'_>) -> Result<VerifiedAu
         return Err(AuthorizationError::PaymentPayloadDigestMismatch);
     }
 
-    // 2. Revocation (online).
+    // 2. Revocation (online): the leaf warrant and holder, the payment
+    // subject, and every node's issuer key and agent id.
     let leaf = &chain_verified.leaf;
-    match input.revocation.check_warrant(&leaf.id) {
-        RevocationDecision::Ok => {}
-        RevocationDecision::RevokedWarrant => return Err(AuthorizationError::WarrantRevoked),
-        RevocationDecision::RevokedHolder => return Err(AuthorizationError::HolderRevoked),
-    }
-    match input.revocation.check_holder(&leaf.holder) {
-        RevocationDecision::Ok => {}
-        RevocationDecision::RevokedWarrant => return Err(AuthorizationError::WarrantRevoked),
-        RevocationDecision::RevokedHolder => return Err(AuthorizationError::HolderRevoked),
-    }
+    verify_chain_revocation(input.revocation, input.chain, &input.context.payment_subject)?;
 
-    // 3. Approval gates.
+    // 3. Approval gates. The approver set is resolved only when approvals
+    // are actually evaluated.
     let gate = leaf.approval_gates.get(&input.context.tool_name);
     let requires_approval =
         gate.is_some_and(|gate: &ApprovalGate| gate.fires(input.tool_arguments));
+    let approvers = if requires_approval || !input.approvals.is_empty() {
+        effective_approvers(leaf, approver_sets)?
+    } else {
+        Vec::new()
+    };
     if requires_approval {
-        verify_approvals(
+        verify_approvals_with(
             input.approvals,
-            &leaf.required_approvers,
+            &approvers,
             leaf.min_approvals,
             &input.context.request_hash,
             input.context.now_ms,
             &input.proof.tuple,
+            input.contract_verifier,
         )?;
     } else if !input.approvals.is_empty() {
         // Approvals supplied but not required: reject (fail-closed) unless
         // they still validate against the warrant's approver set.
-        if !leaf.required_approvers.is_empty() {
-            crate::approval::verify_approval_threshold(
+        if !approvers.is_empty() {
+            verify_approval_threshold_with(
                 input.approvals,
-                &leaf.required_approvers,
+                &approvers,
                 leaf.min_approvals,
                 &input.context.request_hash,
                 input.context.now_ms,
+                input.contract_verifier,
             )?;
         }
     }
@@ -134,13 +181,14 @@ pub fn verify_authorization(input: &AuthorizationInput<'_>) -> Result<VerifiedAu
         if input.approvals.is_empty() {
             return Err(AuthorizationError::HumanPresenceRequired);
         }
-        verify_approvals(
+        verify_approvals_with(
             input.approvals,
-            &leaf.required_approvers,
+            &approvers,
             leaf.min_approvals,
             &input.context.request_hash,
             input.context.now_ms,
             &input.proof.tuple,
+            input.contract_verifier,
         )?;
     }
 
@@ -343,6 +391,8 @@ mod tests {
             tool_arguments: &args,
             revocation: &revocation,
             payment_payload_digest: Some(crate::sha256_prefixed("payment-payload")),
+            contract_verifier: None,
+            identity_resolver: None,
         };
         assert!(verify_authorization(&matching).is_ok());
 
@@ -356,6 +406,8 @@ mod tests {
             tool_arguments: &args,
             revocation: &revocation,
             payment_payload_digest: Some(crate::sha256_prefixed("different-payload")),
+            contract_verifier: None,
+            identity_resolver: None,
         };
         let error = verify_authorization(&divergent).expect_err("digest mismatch");
         assert_eq!(error, AuthorizationError::PaymentPayloadDigestMismatch);
@@ -388,6 +440,8 @@ mod tests {
             tool_arguments: &args,
             revocation: &revocation,
             payment_payload_digest: None,
+            contract_verifier: None,
+            identity_resolver: None,
         });
         assert!(result.is_ok());
     }
@@ -419,11 +473,88 @@ mod tests {
             tool_arguments: &args,
             revocation: &revocation,
             payment_payload_digest: None,
+            contract_verifier: None,
+            identity_resolver: None,
         })
         .expect_err("forged approval");
         assert_eq!(error, AuthorizationError::InvalidApprovalSignature);
     }
 
+    #[test]
+    fn approver_set_rotation_keeps_gated_warrants_working() {
+        use crate::{ApproverSet, InMemoryApproverSetResolver, pop::PopTuple};
+
+        let authority = SigningKeyPair::from_bytes(&[0x1E; 32]);
+        let successor = SigningKeyPair::from_bytes(&[0x1F; 32]);
+        let v1 = ApproverSet::new("ops", vec![approver_keys().signer_ref()], 1)
+            .sign_with(&authority)
+            .expect("v1");
+        let warrant = WarrantBuilder::new(2_000)
+            .issuer(issuer_keys().signer_ref())
+            .holder(holder_keys().signer_ref())
+            .merchant(MerchantConstraint::with_ids(vec!["merchant-a".to_string()]))
+            .resource(ResourceConstraint::default())
+            .payment(PaymentConstraint::new(1_000))
+            .approval_gate("web-search", ApprovalGate::unconditional())
+            .extension(
+                crate::APPROVER_SET_EXTENSION,
+                v1.reference().expect("ref").encode_cbor().expect("cbor"),
+            )
+            .sign_with(&issuer_keys(), [0_u8; 8]);
+        let v2 = v1
+            .rotate(vec![successor.signer_ref()], 2)
+            .expect("draft")
+            .sign_with(&authority)
+            .expect("v2");
+        let mut resolver = InMemoryApproverSetResolver::new();
+        resolver.publish(v2);
+
+        let chain = WarrantChain::single(warrant.clone());
+        let ctx = context();
+        let trust = trusted();
+        let revocation = AcceptRevocation;
+        let args = BTreeMap::new();
+        let verify = |approver: &SigningKeyPair, resolver: Option<&dyn ApproverSetResolver>| {
+            let approvals =
+                vec![SignedApproval::sign(&ctx.request_hash, &approver.signer_ref(), 10, approver)];
+            let proof = ProofBuilder::new()
+                .warrant_id(warrant.id.clone())
+                .challenge_id(ctx.challenge_id.clone())
+                .method(ctx.http_method.clone())
+                .uri(format!("{}{}", ctx.merchant_host, ctx.path_and_query))
+                .request_hash(ctx.request_hash.clone())
+                .accepted_hash(ctx.accepted_hash.clone())
+                .payment_payload_digest(crate::sha256_prefixed("payment-payload"))
+                .approvals_digest(PopTuple::approvals_digest(&approvals))
+                .nonce("nonce-1".to_string())
+                .created_at_ms(ctx.now_ms)
+                .sign_with(&holder_keys());
+            let input = AuthorizationInput {
+                chain: &chain,
+                trusted: &trust,
+                proof: &proof,
+                context: &ctx,
+                approvals: &approvals,
+                tool_arguments: &args,
+                revocation: &revocation,
+                payment_payload_digest: None,
+                contract_verifier: None,
+                identity_resolver: None,
+            };
+            verify_authorization_with_approver_sets(&input, resolver)
+        };
+
+        verify(&successor, Some(&resolver)).expect("rotated-in approver");
+        assert_eq!(
+            verify(&approver_keys(), Some(&resolver)).expect_err("rotated out"),
+            AuthorizationError::ApproverNotAllowed
+        );
+        assert!(matches!(
+            verify(&successor, None).expect_err("no resolver"),
+            AuthorizationError::ApproverSetUnavailable { .. }
+        ));
+    }
+
     #[test]
     fn warrant_ext_verify_constraints_surfaces_violations() {
         let warrant = warrant(false);
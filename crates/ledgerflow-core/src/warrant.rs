@@ -62,14 +62,17 @@ pub const KNOWN_EXTENSION_KEYS: &[&str] = &[
     "ledgerflow.agent_id",
     "ledgerflow.session_id",
     "ledgerflow.client_id",
-    // Budget-accounting point (P2+; reserved now so the field is forward
-    // compatible).
-    "ledgerflow.ledger",
+    // Accounting-point budget policy (periodic / lifetime spend limits).
+    // See [`crate::budget::BUDGET_EXTENSION`].
+    crate::budget::BUDGET_EXTENSION,
     // Human-readable merchant display name (non-authoritative).
     "ledgerflow.merchant_display_name",
     // Issuance bounds constraining what the holder may delegate.
     // See [`crate::issue_bounds::ISSUE_BOUNDS_EXTENSION`].
     crate::issue_bounds::ISSUE_BOUNDS_EXTENSION,
+    // Rotatable approver group replacing `required_approvers`.
+    // See [`crate::approver_set::APPROVER_SET_EXTENSION`].
+    crate::approver_set::APPROVER_SET_EXTENSION,
 ];
 
 // ---------------------------------------------------------------------------
@@ -114,6 +117,15 @@ pub fn sha256_prefixed<T: AsRef<[u8]>>(input: T) -> String {
     format!("sha256:{encoded}")
 }
 
+/// Whether `digest` has the [`sha256_prefixed`] form: `sha256:` and 64
+/// lowercase hexadecimal digits.
+#[must_use]
+pub fn is_sha256_prefixed(digest: &str) -> bool {
+    digest.strip_prefix("sha256:").is_some_and(|hex| {
+        hex.len() == 64 && hex.bytes().all(|byte| matches!(byte, b'0'..=b'9' | b'a'..=b'f'))
+    })
+}
+
 /// Generates a 16-byte UUIDv7-style warrant identifier.
 ///
 /// Layout: 48-bit unix-epoch-milliseconds (bytes 0-5), version bits `0111`
@@ -277,6 +289,13 @@ impl SigningKeyPair {
         Self { signing_key: ed25519_dalek::SigningKey::from_bytes(secret_key) }
     }
 
+    /// Returns the raw secret key bytes, for persisting the key to
+    /// protected storage (e.g. a server's tenant keyrings).
+    #[must_use]
+    pub fn secret_key_bytes(&self) -> [u8; 32] {
+        self.signing_key.to_bytes()
+    }
+
     /// Returns the public key bytes.
     #[must_use]
     pub fn public_key_bytes(&self) -> [u8; 32] {
@@ -297,6 +316,29 @@ impl SigningKeyPair {
     }
 }
 
+/// A key that signs warrant envelopes.
+///
+/// Implemented by [`SigningKeyPair`] (Ed25519) and
+/// [`Secp256k1WarrantSigner`](crate::crypto::Secp256k1WarrantSigner) (EVM
+/// issuers); the builders accept any implementation.
+pub trait WarrantSigner {
+    /// The signer reference warrants signed by this key name as issuer.
+    fn signer_ref(&self) -> SignerRef;
+
+    /// Signs `warrant`'s [`Warrant::signed_message`].
+    fn sign_warrant(&self, warrant: &Warrant) -> SignatureEnvelope;
+}
+
+impl WarrantSigner for SigningKeyPair {
+    fn signer_ref(&self) -> SignerRef {
+        Self::signer_ref(self)
+    }
+
+    fn sign_warrant(&self, warrant: &Warrant) -> SignatureEnvelope {
+        self.sign(&warrant.signed_message())
+    }
+}
+
 /// Signature container for warrants, proofs, and approvals.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct SignatureEnvelope {
@@ -519,11 +561,19 @@ pub struct Warrant {
 impl CborCodec for Warrant {}
 
 impl Warrant {
-    /// Signs this warrant using the issuer's signing key pair.
+    /// Signs this warrant using the issuer's key.
     #[must_use]
-    pub fn sign_with(mut self, issuer_keys: &SigningKeyPair) -> Self {
-        let message = self.signing_message();
-        self.signature = issuer_keys.sign(message.as_slice());
+    pub fn sign_with(mut self, issuer_keys: &dyn WarrantSigner) -> Self {
+        self.signature = issuer_keys.sign_warrant(&self);
+        self
+    }
+
+    /// Attaches a signature produced elsewhere, e.g. by a browser wallet over
+    /// [`crate::eip712::WarrantTypedData`]. Check it with
+    /// [`Self::verify_signature`].
+    #[must_use]
+    pub fn with_signature(mut self, signature: SignatureEnvelope) -> Self {
+        self.signature = signature;
         self
     }
 
@@ -568,10 +618,39 @@ impl Warrant {
         message
     }
 
-    /// Verifies the envelope signature using **strict** Ed25519 verification.
+    /// The bytes the envelope signature covers for the issuer's algorithm:
+    /// the EIP-712 digest ([`crate::eip712::warrant_typed_data_digest`]) for
+    /// [`SigningAlgorithm::EthTypedData`], else [`Self::signing_message`].
+    #[must_use]
+    pub fn signed_message(&self) -> Vec<u8> {
+        match self.issuer.alg {
+            SigningAlgorithm::EthTypedData => {
+                crate::eip712::warrant_typed_data_digest(self).to_vec()
+            }
+            _ => self.signing_message(),
+        }
+    }
+
+    /// Verifies the envelope signature using **strict** verification for
+    /// the issuer's algorithm.
     #[must_use]
     pub fn verify_signature(&self) -> bool {
-        self.signature.verify_strict(&self.issuer, &self.signing_message())
+        self.verify_signature_with(None)
+    }
+
+    /// [`Self::verify_signature`], accepting a contract-account issuer
+    /// through the ERC-1271 seam ([`crate::erc1271::verify_signature_with`]).
+    #[must_use]
+    pub fn verify_signature_with(
+        &self,
+        contract_verifier: Option<&dyn crate::erc1271::ContractSignatureVerifier>,
+    ) -> bool {
+        crate::erc1271::verify_signature_with(
+            &self.signature,
+            &self.issuer,
+            &self.signed_message(),
+            contract_verifier,
+        )
     }
 
     /// Encodes the warrant as CBOR bytes (delegates to [`CborCodec::encode_cbor`]).
@@ -823,8 +902,10 @@ mod tests {
     #[test]
     fn digests_are_prefixed_sha256_and_content_bound() {
         let warrant = sample_warrant();
-        assert!(warrant.digest().starts_with("sha256:"));
-        assert!(warrant.payload_digest().starts_with("sha256:"));
+        assert!(is_sha256_prefixed(&warrant.digest()));
+        assert!(is_sha256_prefixed(&warrant.payload_digest()));
+        assert!(!is_sha256_prefixed(&warrant.digest().to_uppercase()));
+        assert!(!is_sha256_prefixed("sha256:../admin"));
         // Digest over the full envelope differs from the payload-only digest.
         assert_ne!(warrant.digest(), warrant.payload_digest());
         // Identical warrants share digests; tampering changes them.
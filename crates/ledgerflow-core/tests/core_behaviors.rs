@@ -147,6 +147,8 @@ fn authorize(
         tool_arguments: &std::collections::BTreeMap::new(),
         revocation,
         payment_payload_digest: None,
+        contract_verifier: None,
+        identity_resolver: None,
     };
     verify_authorization(&input)
 }
@@ -567,6 +569,8 @@ fn tenant_isolation_via_separate_trust_anchors() {
         tool_arguments: &std::collections::BTreeMap::new(),
         revocation: &InMemoryRevocationCheck::new(),
         payment_payload_digest: None,
+        contract_verifier: None,
+        identity_resolver: None,
     };
     let error = verify_authorization(&input).expect_err("cross-tenant");
     assert!(matches!(error, ledgerflow_core::AuthorizationError::UntrustedIssuer { .. }));
@@ -5,6 +5,7 @@
 //! - PoP tool-args binding
 //! - issuance-time static attenuation + issue bounds
 //! - SRL (Signed Revocation List) semantics
+//! - subject / issuer / agent revocation scopes
 
 #![allow(clippy::expect_used)]
 
@@ -318,7 +319,7 @@ fn srl_verify_signature_roundtrip() {
     let control = SigningKeyPair::from_bytes(&[0x55; 32]);
     let list = SignedRevocationList::sign(
         1,
-        vec![SrlEntry::Warrant { id_hex: hex_encode_bytes(&[0xAA; 16]) }],
+        vec![SrlEntry::Warrant { id_hex: hex_encode_bytes(&[0xAA; 16]), expires_at: None }],
         &control,
     );
     assert!(list.verify_signature(&control.signer_ref()));
@@ -334,7 +335,7 @@ fn srl_state_applies_and_detects_revocations() {
     let list = SignedRevocationList::sign(
         1,
         vec![
-            SrlEntry::Warrant { id_hex: hex_encode_bytes(&[0xAA; 16]) },
+            SrlEntry::Warrant { id_hex: hex_encode_bytes(&[0xAA; 16]), expires_at: None },
             SrlEntry::Holder { key_hex: hex_encode_bytes(&holder.public_key) },
         ],
         &control,
@@ -352,7 +353,7 @@ fn srl_anti_rollback_rejects_old_version() {
     let control = SigningKeyPair::from_bytes(&[0x55; 32]);
     let v2 = SignedRevocationList::sign(
         2,
-        vec![SrlEntry::Warrant { id_hex: hex_encode_bytes(&[0xAA; 16]) }],
+        vec![SrlEntry::Warrant { id_hex: hex_encode_bytes(&[0xAA; 16]), expires_at: None }],
         &control,
     );
     let mut state = SrlState::new();
@@ -360,7 +361,7 @@ fn srl_anti_rollback_rejects_old_version() {
 
     let v1 = SignedRevocationList::sign(
         1,
-        vec![SrlEntry::Warrant { id_hex: hex_encode_bytes(&[0xBB; 16]) }],
+        vec![SrlEntry::Warrant { id_hex: hex_encode_bytes(&[0xBB; 16]), expires_at: None }],
         &control,
     );
     let error = state.apply(&v1, &control.signer_ref()).expect_err("rollback");
@@ -373,7 +374,7 @@ fn srl_rejects_invalid_signature() {
     let attacker = SigningKeyPair::from_bytes(&[0x58; 32]);
     let list = SignedRevocationList::sign(
         1,
-        vec![SrlEntry::Warrant { id_hex: hex_encode_bytes(&[0xAA; 16]) }],
+        vec![SrlEntry::Warrant { id_hex: hex_encode_bytes(&[0xAA; 16]), expires_at: None }],
         &attacker,
     );
     let mut state = SrlState::new();
@@ -389,7 +390,7 @@ fn srl_is_additive_across_versions() {
         .apply(
             &SignedRevocationList::sign(
                 1,
-                vec![SrlEntry::Warrant { id_hex: hex_encode_bytes(&[0xAA; 16]) }],
+                vec![SrlEntry::Warrant { id_hex: hex_encode_bytes(&[0xAA; 16]), expires_at: None }],
                 &control,
             ),
             &control.signer_ref(),
@@ -400,8 +401,8 @@ fn srl_is_additive_across_versions() {
             &SignedRevocationList::sign(
                 2,
                 vec![
-                    SrlEntry::Warrant { id_hex: hex_encode_bytes(&[0xAA; 16]) },
-                    SrlEntry::Warrant { id_hex: hex_encode_bytes(&[0xBB; 16]) },
+                    SrlEntry::Warrant { id_hex: hex_encode_bytes(&[0xAA; 16]), expires_at: None },
+                    SrlEntry::Warrant { id_hex: hex_encode_bytes(&[0xBB; 16]), expires_at: None },
                 ],
                 &control,
             ),
@@ -412,3 +413,78 @@ fn srl_is_additive_across_versions() {
     assert!(state.is_warrant_revoked(&[0xAA; 16]));
     assert!(state.is_warrant_revoked(&[0xBB; 16]));
 }
+
+// ---------------------------------------------------------------------------
+// 8. Scoped revocation
+// ---------------------------------------------------------------------------
+
+/// Root (issuer -> holder, tagged `agent-7`) delegated to `delegate_keys`.
+fn agent_chain() -> WarrantChain {
+    let root = WarrantBuilder::new(2_000)
+        .warrant_id(fixed_id("root-agent-00000"))
+        .max_depth(2)
+        .issuer(issuer_keys().signer_ref())
+        .holder(holder_keys().signer_ref())
+        .merchant(merchant())
+        .resource(resource())
+        .payment(payment(1_000))
+        .extension("ledgerflow.agent_id", b"agent-7".to_vec())
+        .sign_with(&issuer_keys(), [0_u8; 8]);
+    let leaf = ledgerflow_core::typestate::DelegatedWarrantBuilder::from(root.clone()).issue_to(
+        delegate_keys().signer_ref(),
+        &holder_keys(),
+        2_000,
+        [1_u8; 8],
+    );
+    WarrantChain { warrants: vec![root, leaf] }
+}
+
+fn authorize(revocation: &ledgerflow_core::InMemoryRevocationCheck) -> ledgerflow_core::Result<()> {
+    let chain = agent_chain();
+    let ctx = context(2_000, &delegate_keys().signer_ref());
+    let proof = proof_for(chain.leaf().expect("leaf"), &ctx, &delegate_keys());
+    let input = ledgerflow_core::AuthorizationInput {
+        chain: &chain,
+        trusted: &trusted(),
+        proof: &proof,
+        context: &ctx,
+        approvals: &[],
+        tool_arguments: &ToolArguments::new(),
+        revocation,
+        payment_payload_digest: None,
+        contract_verifier: None,
+        identity_resolver: None,
+    };
+    ledgerflow_core::verify_authorization(&input).map(|_| ())
+}
+
+#[test]
+fn scoped_revocations_are_checked_at_every_chain_node() {
+    use ledgerflow_core::{AuthorizationError, InMemoryRevocationCheck};
+
+    authorize(&InMemoryRevocationCheck::new()).expect("nothing revoked");
+
+    // The sub-issuer signed the leaf: revoking it as an issuer kills the
+    // chain, while revoking it as a holder does not (it holds the root only).
+    let mut store = InMemoryRevocationCheck::new();
+    store.revoke_holder(&holder_keys().signer_ref());
+    authorize(&store).expect("holder scope is the leaf only");
+    store.revoke_issuer(&holder_keys().signer_ref());
+    assert_eq!(authorize(&store), Err(AuthorizationError::IssuerRevoked));
+
+    let mut store = InMemoryRevocationCheck::new();
+    store.revoke_issuer(&issuer_keys().signer_ref());
+    assert_eq!(authorize(&store), Err(AuthorizationError::IssuerRevoked));
+
+    // The agent id sits on the root, not the leaf.
+    let mut store = InMemoryRevocationCheck::new();
+    store.revoke_agent("agent-7");
+    assert_eq!(
+        authorize(&store),
+        Err(AuthorizationError::AgentRevoked { agent_id: "agent-7".to_string() })
+    );
+
+    let mut store = InMemoryRevocationCheck::new();
+    store.revoke_subject(&context(2_000, &delegate_keys().signer_ref()).payment_subject);
+    assert_eq!(authorize(&store), Err(AuthorizationError::SubjectRevoked));
+}
@@ -0,0 +1,341 @@
+//! Accounting-point budget enforcement (design §1, §14 P2).
+//!
+//! A warrant may declare a signed [`BudgetPolicy`] (periodic and lifetime
+//! spend limits) bound to exactly one accounting point. The Facilitator that
+//! *is* that accounting point enforces the policy through a [`BudgetLedger`]
+//! using a two-phase protocol around the rail call:
+//!
+//! 1. `reserve` — atomically check every limit in every budgeted chain node and hold the charge
+//!    amount;
+//! 2. `commit` on a successful settlement, or `release` on failure.
+//!
+//! Reservations count against the limits while the rail call is in flight,
+//! so concurrent settlements cannot jointly overdraw a window.
+//!
+//! Budgets are enforced at **every** chain node that declares one, keyed by
+//! that node's warrant id. A holder therefore cannot escape a parent's budget
+//! by delegating to fresh children: the parent node (and its counters) is
+//! still part of every presented chain.
+
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
+
+use ledgerflow_core::{BudgetPeriod, BudgetPolicy, WarrantChain};
+
+/// Errors from budget collection or enforcement.
+#[derive(Clone, Debug, Eq, PartialEq, thiserror::Error)]
+pub enum BudgetError {
+    #[error("budget exceeded for {period} window: used {used} + {amount} > limit {limit}")]
+    Exceeded { period: BudgetPeriod, limit: u128, used: u128, amount: u128 },
+    #[error("budget is bound to accounting point {declared}, this facilitator is {expected}")]
+    ForeignLedger { expected: String, declared: String },
+    #[error("warrant declares a budget but no budget ledger is configured")]
+    NoLedger,
+    #[error("malformed budget extension: {0}")]
+    Malformed(String),
+    #[error("unknown budget reservation {0}")]
+    UnknownReservation(u64),
+    #[error("budget ledger lock poisoned")]
+    Poisoned,
+}
+
+/// One budgeted chain node: the declaring warrant and its policy.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BudgetScope {
+    /// Id of the warrant that declared the policy (the counter key).
+    pub warrant_id: Vec<u8>,
+    /// The declared policy.
+    pub policy: BudgetPolicy,
+}
+
+impl BudgetScope {
+    /// Collects the budget scopes declared anywhere in `chain`, root first.
+    ///
+    /// # Errors
+    /// Returns [`BudgetError::Malformed`] when a node carries an undecodable
+    /// policy (fail-closed).
+    pub fn collect(chain: &WarrantChain) -> Result<Vec<Self>, BudgetError> {
+        let mut scopes = Vec::new();
+        for warrant in &chain.warrants {
+            let policy = warrant
+                .budget_policy()
+                .map_err(|error| BudgetError::Malformed(error.to_string()))?;
+            if let Some(policy) = policy {
+                scopes.push(Self { warrant_id: warrant.id.clone(), policy });
+            }
+        }
+        Ok(scopes)
+    }
+}
+
+/// Handle to an in-flight reservation returned by [`BudgetLedger::reserve`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BudgetReservation {
+    /// Ledger-assigned reservation id.
+    pub id: u64,
+    /// Reserved amount (atomic units).
+    pub amount: u128,
+}
+
+/// Stateful budget accounting for one accounting point.
+///
+/// Implementations MUST make `reserve` atomic across all supplied scopes and
+/// periods: either every counter is charged or none is.
+pub trait BudgetLedger: Send + Sync {
+    /// Identifier of this accounting point; compared against
+    /// [`BudgetPolicy::ledger`].
+    fn accounting_point(&self) -> &str;
+
+    /// Atomically checks every limit and reserves `amount` against it.
+    ///
+    /// # Errors
+    /// Returns [`BudgetError::Exceeded`] for the first limit that would be
+    /// crossed; nothing is reserved in that case.
+    fn reserve(
+        &self,
+        scopes: &[BudgetScope],
+        amount: u128,
+        now_secs: u64,
+    ) -> Result<BudgetReservation, BudgetError>;
+
+    /// Converts a reservation into committed spend.
+    ///
+    /// # Errors
+    /// Returns [`BudgetError::UnknownReservation`] for a stale handle.
+    fn commit(&self, reservation: &BudgetReservation) -> Result<(), BudgetError>;
+
+    /// Drops a reservation without charging it.
+    ///
+    /// # Errors
+    /// Returns [`BudgetError::UnknownReservation`] for a stale handle.
+    fn release(&self, reservation: &BudgetReservation) -> Result<(), BudgetError>;
+}
+
+/// Shared, type-erased budget ledger.
+pub type SharedBudgetLedger = Arc<dyn BudgetLedger>;
+
+// ---------------------------------------------------------------------------
+// In-memory ledger
+// ---------------------------------------------------------------------------
+
+type CounterKey = (Vec<u8>, BudgetPeriod);
+
+#[derive(Clone, Copy, Debug, Default)]
+struct Counter {
+    window: u64,
+    spent: u128,
+    reserved: u128,
+}
+
+impl Counter {
+    const fn used_in(&self, window: u64) -> u128 {
+        if self.window == window { self.spent.saturating_add(self.reserved) } else { 0 }
+    }
+}
+
+#[derive(Debug)]
+struct Pending {
+    amount: u128,
+    slots: Vec<(CounterKey, u64)>,
+}
+
+#[derive(Debug, Default)]
+struct LedgerState {
+    counters: BTreeMap<CounterKey, Counter>,
+    pending: BTreeMap<u64, Pending>,
+    next_id: u64,
+}
+
+#[derive(Debug)]
+struct InMemoryBudgetLedgerInner {
+    accounting_point: String,
+    state: Mutex<LedgerState>,
+}
+
+/// Process-local [`BudgetLedger`].
+///
+/// Each `(warrant, period)` pair keeps a single rolling counter that resets
+/// when its window index advances, so memory is bounded by the number of
+/// budgeted warrants. State is lost on restart; a persistent ledger is a
+/// deployment concern.
+#[derive(Clone, Debug)]
+pub struct InMemoryBudgetLedger {
+    inner: Arc<InMemoryBudgetLedgerInner>,
+}
+
+impl InMemoryBudgetLedger {
+    /// Creates an empty ledger for `accounting_point`.
+    #[must_use]
+    pub fn new(accounting_point: impl Into<String>) -> Self {
+        Self {
+            inner: Arc::new(InMemoryBudgetLedgerInner {
+                accounting_point: accounting_point.into(),
+                state: Mutex::new(LedgerState::default()),
+            }),
+        }
+    }
+
+    /// Returns the committed spend for `warrant_id` in the window of
+    /// `period` containing `now_secs`.
+    pub fn spent(&self, warrant_id: &[u8], period: BudgetPeriod, now_secs: u64) -> u128 {
+        let window = period.window(now_secs);
+        self.inner
+            .state
+            .lock()
+            .ok()
+            .and_then(|state| state.counters.get(&(warrant_id.to_vec(), period)).copied())
+            .filter(|counter| counter.window == window)
+            .map_or(0, |counter| counter.spent)
+    }
+
+    fn finish(&self, reservation: &BudgetReservation, commit: bool) -> Result<(), BudgetError> {
+        let mut state = self.inner.state.lock().map_err(|_| BudgetError::Poisoned)?;
+        let pending = state
+            .pending
+            .remove(&reservation.id)
+            .ok_or(BudgetError::UnknownReservation(reservation.id))?;
+        for (key, window) in pending.slots {
+            // A counter that rolled over while the charge was in flight has
+            // already dropped the reservation with its old window.
+            if let Some(counter) = state.counters.get_mut(&key).filter(|c| c.window == window) {
+                counter.reserved = counter.reserved.saturating_sub(pending.amount);
+                if commit {
+                    counter.spent = counter.spent.saturating_add(pending.amount);
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl BudgetLedger for InMemoryBudgetLedger {
+    fn accounting_point(&self) -> &str {
+        &self.inner.accounting_point
+    }
+
+    fn reserve(
+        &self,
+        scopes: &[BudgetScope],
+        amount: u128,
+        now_secs: u64,
+    ) -> Result<BudgetReservation, BudgetError> {
+        let mut state = self.inner.state.lock().map_err(|_| BudgetError::Poisoned)?;
+
+        // Check every limit before touching any counter (all-or-nothing).
+        let mut slots = Vec::new();
+        for scope in scopes {
+            for (period, limit) in scope.policy.limits() {
+                let key = (scope.warrant_id.clone(), period);
+                let window = period.window(now_secs);
+                let used = state.counters.get(&key).map_or(0, |counter| counter.used_in(window));
+                if used.checked_add(amount).is_none_or(|total| total > limit) {
+                    return Err(BudgetError::Exceeded { period, limit, used, amount });
+                }
+                slots.push((key, window));
+            }
+        }
+
+        for (key, window) in &slots {
+            let counter = state.counters.entry(key.clone()).or_default();
+            if counter.window != *window {
+                *counter = Counter { window: *window, spent: 0, reserved: 0 };
+            }
+            counter.reserved = counter.reserved.saturating_add(amount);
+        }
+        let id = state.next_id;
+        state.next_id = state.next_id.wrapping_add(1);
+        state.pending.insert(id, Pending { amount, slots });
+        Ok(BudgetReservation { id, amount })
+    }
+
+    fn commit(&self, reservation: &BudgetReservation) -> Result<(), BudgetError> {
+        self.finish(reservation, true)
+    }
+
+    fn release(&self, reservation: &BudgetReservation) -> Result<(), BudgetError> {
+        self.finish(reservation, false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::expect_used)]
+
+    use super::*;
+
+    const HOUR: u64 = 3_600;
+
+    fn scope(id: u8, policy: BudgetPolicy) -> BudgetScope {
+        BudgetScope { warrant_id: vec![id; 16], policy }
+    }
+
+    #[test]
+    fn reserve_commit_accumulates_until_limit() {
+        let ledger = InMemoryBudgetLedger::new("ledger-a");
+        let scopes = [scope(1, BudgetPolicy::new("ledger-a").with_per_hour(250))];
+
+        for _ in 0..2 {
+            let reservation = ledger.reserve(&scopes, 100, 10).expect("reserve");
+            ledger.commit(&reservation).expect("commit");
+        }
+        assert_eq!(ledger.spent(&[1; 16], BudgetPeriod::Hour, 10), 200);
+
+        let error = ledger.reserve(&scopes, 100, 20).expect_err("over hourly limit");
+        assert_eq!(
+            error,
+            BudgetError::Exceeded {
+                period: BudgetPeriod::Hour,
+                limit: 250,
+                used: 200,
+                amount: 100
+            }
+        );
+    }
+
+    #[test]
+    fn in_flight_reservations_count_and_release_restores() {
+        let ledger = InMemoryBudgetLedger::new("ledger-a");
+        let scopes = [scope(1, BudgetPolicy::new("ledger-a").with_lifetime(150))];
+
+        let first = ledger.reserve(&scopes, 100, 10).expect("reserve");
+        assert!(ledger.reserve(&scopes, 100, 10).is_err(), "pending charge must count");
+
+        ledger.release(&first).expect("release");
+        assert_eq!(ledger.spent(&[1; 16], BudgetPeriod::Lifetime, 10), 0);
+        let second = ledger.reserve(&scopes, 100, 10).expect("reserve after release");
+        ledger.commit(&second).expect("commit");
+        assert_eq!(ledger.release(&second), Err(BudgetError::UnknownReservation(second.id)));
+    }
+
+    #[test]
+    fn periodic_windows_roll_over_but_lifetime_does_not() {
+        let ledger = InMemoryBudgetLedger::new("ledger-a");
+        let scopes =
+            [scope(1, BudgetPolicy::new("ledger-a").with_per_hour(100).with_lifetime(200))];
+
+        let reservation = ledger.reserve(&scopes, 100, 0).expect("hour 0");
+        ledger.commit(&reservation).expect("commit");
+        assert!(ledger.reserve(&scopes, 1, HOUR - 1).is_err());
+
+        let reservation = ledger.reserve(&scopes, 100, HOUR).expect("hour 1");
+        ledger.commit(&reservation).expect("commit");
+        let error = ledger.reserve(&scopes, 1, 2 * HOUR).expect_err("lifetime exhausted");
+        assert!(matches!(error, BudgetError::Exceeded { period: BudgetPeriod::Lifetime, .. }));
+    }
+
+    #[test]
+    fn failing_scope_reserves_nothing() {
+        let ledger = InMemoryBudgetLedger::new("ledger-a");
+        let scopes = [
+            scope(1, BudgetPolicy::new("ledger-a").with_per_day(1_000)),
+            scope(2, BudgetPolicy::new("ledger-a").with_per_day(50)),
+        ];
+        assert!(ledger.reserve(&scopes, 100, 0).is_err());
+
+        let reservation = ledger.reserve(&scopes[..1], 1_000, 0).expect("parent untouched");
+        ledger.commit(&reservation).expect("commit");
+        assert_eq!(ledger.spent(&[1; 16], BudgetPeriod::Day, 0), 1_000);
+    }
+}
@@ -0,0 +1,286 @@
+//! ERC-1271 contract-account signature verification over Ethereum JSON-RPC.
+//!
+//! [`RpcContractSignatureVerifier`] implements the core
+//! [`ContractSignatureVerifier`] seam: it calls
+//! `isValidSignature(bytes32,bytes)` on the claimed account with `eth_call`
+//! and accepts the signature when the result starts with
+//! [`ERC_1271_MAGIC_VALUE`]. Wire it into [`crate::VerificationService`] (or
+//! the protocol merchant verifiers) with `with_contract_verifier` to accept
+//! Safe / ERC-4337 issuers, holders and approvers.
+//!
+//! Verdicts are cached per `(account, hash, signature)` for a TTL: a
+//! warrant signature is re-checked on every request that presents it, while
+//! an owner change on the account takes effect once the entry expires.
+//! A revert (JSON-RPC error code 3, or -32000 with a revert message) is the
+//! account's answer and is cached as invalid; any other failure — transport,
+//! a malformed response or a node error such as a rate limit (-32005) or an
+//! internal error (-32603) — is logged, fails closed and is not cached.
+
+use std::{
+    collections::BTreeMap,
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+use ledgerflow_core::{
+    ContractSignatureVerifier, ERC_1271_MAGIC_VALUE, SignerRef, hex_encode_bytes,
+    is_contract_account_claim,
+};
+use sha2::{Digest, Sha256};
+
+use crate::rails::{
+    evm_rpc::{address_hex, pad_calldata, parse_hex, selector, word_from_u128},
+    rpc::{JsonRpcTransport, RpcError},
+};
+
+const IS_VALID_SIGNATURE: &str = "isValidSignature(bytes32,bytes)";
+
+/// Default lifetime of a cached verdict.
+pub const DEFAULT_CONTRACT_SIGNATURE_TTL: Duration = Duration::from_mins(5);
+
+/// Upper bound on cached verdicts. A full cache prunes expired entries, then
+/// evicts the entry closest to expiry.
+const MAX_CACHED_VERDICTS: usize = 4_096;
+
+/// [`ContractSignatureVerifier`] backed by `eth_call` on one chain.
+pub struct RpcContractSignatureVerifier<T> {
+    transport: T,
+    ttl: Duration,
+    cache: Mutex<BTreeMap<[u8; 32], (bool, Instant)>>,
+}
+
+impl<T> std::fmt::Debug for RpcContractSignatureVerifier<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("RpcContractSignatureVerifier")
+            .field("ttl", &self.ttl)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> RpcContractSignatureVerifier<T>
+where
+    T: JsonRpcTransport,
+{
+    #[must_use]
+    pub const fn new(transport: T) -> Self {
+        Self { transport, ttl: DEFAULT_CONTRACT_SIGNATURE_TTL, cache: Mutex::new(BTreeMap::new()) }
+    }
+
+    /// Lifetime of a cached verdict.
+    #[must_use]
+    pub const fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Calls `isValidSignature` on `account`.
+    fn call(&self, account: [u8; 20], hash: [u8; 32], signature: &[u8]) -> Result<bool, RpcError> {
+        let mut data = selector(IS_VALID_SIGNATURE).to_vec();
+        data.extend_from_slice(&hash);
+        data.extend_from_slice(&word_from_u128(64));
+        data.extend_from_slice(&word_from_u128(signature.len() as u128));
+        data.extend_from_slice(signature);
+        pad_calldata(&mut data);
+        let result = self.transport.call(
+            "eth_call",
+            serde_json::json!([
+                {
+                    "to": address_hex(account),
+                    "data": format!("0x{}", hex_encode_bytes(&data)),
+                },
+                "latest",
+            ]),
+        )?;
+        let bytes = result
+            .as_str()
+            .and_then(parse_hex)
+            .ok_or_else(|| RpcError::InvalidResponse(format!("expected hex data, got {result}")))?;
+        Ok(bytes.starts_with(&ERC_1271_MAGIC_VALUE))
+    }
+}
+
+impl<T> ContractSignatureVerifier for RpcContractSignatureVerifier<T>
+where
+    T: JsonRpcTransport,
+{
+    fn is_valid_signature(&self, account: &SignerRef, hash: [u8; 32], signature: &[u8]) -> bool {
+        let Ok(address) = <[u8; 20]>::try_from(account.public_key.as_slice()) else {
+            return false;
+        };
+        if !is_contract_account_claim(account) {
+            return false;
+        }
+        let key: [u8; 32] = Sha256::new()
+            .chain_update(address)
+            .chain_update(hash)
+            .chain_update(signature)
+            .finalize()
+            .into();
+        let now = Instant::now();
+        if let Some((valid, _)) = self
+            .cache
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&key)
+            .filter(|(_, expires_at)| *expires_at > now)
+        {
+            return *valid;
+        }
+        let valid = match self.call(address, hash, signature) {
+            Ok(valid) => valid,
+            // A revert is the account's answer (e.g. an EOA or a rejecting
+            // wallet) and is cached like one.
+            Err(RpcError::Rpc { code, message }) if is_revert(code, &message) => false,
+            Err(error) => {
+                tracing::warn!(
+                    target: "ledgerflow::erc1271",
+                    account = %address_hex(address),
+                    error = %error,
+                    "isValidSignature call failed"
+                );
+                return false;
+            }
+        };
+        let mut cache = self.cache.lock().unwrap_or_else(PoisonError::into_inner);
+        if cache.len() >= MAX_CACHED_VERDICTS && !cache.contains_key(&key) {
+            cache.retain(|_, (_, expires_at)| *expires_at > now);
+            if cache.len() >= MAX_CACHED_VERDICTS &&
+                let Some(oldest) = cache
+                    .iter()
+                    .min_by_key(|(_, (_, expires_at))| *expires_at)
+                    .map(|(key, _)| *key)
+            {
+                cache.remove(&oldest);
+            }
+        }
+        cache.insert(key, (valid, now + self.ttl));
+        valid
+    }
+}
+
+/// Whether a JSON-RPC error object reports an `eth_call` revert: code 3
+/// (`execution reverted` with revert data), or the generic -32000 server
+/// error carrying a revert message.
+fn is_revert(code: i64, message: &str) -> bool {
+    code == 3 || (code == -32_000 && message.to_ascii_lowercase().contains("revert"))
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::expect_used)]
+
+    use std::sync::{
+        Arc,
+        atomic::{AtomicU32, Ordering},
+    };
+
+    use ledgerflow_core::SigningAlgorithm;
+
+    use super::*;
+    use crate::rails::rpc::MockJsonRpcTransport;
+
+    const ACCOUNT: [u8; 20] = [0xA5; 20];
+
+    fn account() -> SignerRef {
+        SignerRef::new(SigningAlgorithm::EthPersonalSign, ACCOUNT.to_vec())
+    }
+
+    /// A wallet accepting exactly `signature` over any hash.
+    fn wallet(calls: Arc<AtomicU32>, signature: Vec<u8>) -> MockJsonRpcTransport {
+        MockJsonRpcTransport::new(move |method, params| {
+            calls.fetch_add(1, Ordering::SeqCst);
+            assert_eq!(method, "eth_call");
+            assert_eq!(params[0]["to"], address_hex(ACCOUNT));
+            let data = parse_hex(params[0]["data"].as_str().expect("data")).expect("hex");
+            assert_eq!(data[..4], ERC_1271_MAGIC_VALUE);
+            assert_eq!(data[4 + 63], 64);
+            let len = usize::from(data[4 + 95]);
+            if data[4 + 96..4 + 96 + len] == signature[..] {
+                Ok(serde_json::json!(format!(
+                    "0x{}{}",
+                    hex_encode_bytes(&ERC_1271_MAGIC_VALUE),
+                    "00".repeat(28)
+                )))
+            } else {
+                Ok(serde_json::json!(format!("0x{}", "00".repeat(32))))
+            }
+        })
+    }
+
+    #[test]
+    fn selector_is_the_erc1271_magic_value() {
+        assert_eq!(selector(IS_VALID_SIGNATURE), ERC_1271_MAGIC_VALUE);
+    }
+
+    #[test]
+    fn verdicts_are_checked_on_chain_and_cached() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let verifier = RpcContractSignatureVerifier::new(wallet(Arc::clone(&calls), vec![7; 65]));
+        assert!(verifier.is_valid_signature(&account(), [1; 32], &[7; 65]));
+        assert!(verifier.is_valid_signature(&account(), [1; 32], &[7; 65]));
+        assert!(!verifier.is_valid_signature(&account(), [1; 32], &[8; 65]));
+        assert!(!verifier.is_valid_signature(&account(), [1; 32], &[8; 65]));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+
+        let expiring = RpcContractSignatureVerifier::new(wallet(Arc::clone(&calls), vec![7; 65]))
+            .with_ttl(Duration::ZERO);
+        assert!(expiring.is_valid_signature(&account(), [1; 32], &[7; 65]));
+        assert!(expiring.is_valid_signature(&account(), [1; 32], &[7; 65]));
+        assert_eq!(calls.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn the_cache_stays_bounded_while_every_verdict_is_live() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let verifier = RpcContractSignatureVerifier::new(wallet(Arc::clone(&calls), vec![7; 65]));
+        for i in 0..=MAX_CACHED_VERDICTS {
+            let mut hash = [0_u8; 32];
+            hash[..8].copy_from_slice(&(i as u64).to_be_bytes());
+            assert!(verifier.is_valid_signature(&account(), hash, &[7; 65]));
+        }
+        assert_eq!(verifier.cache.lock().expect("cache").len(), MAX_CACHED_VERDICTS);
+    }
+
+    #[test]
+    fn non_accounts_and_transport_failures_fail_closed() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let counter = Arc::clone(&calls);
+        let verifier = RpcContractSignatureVerifier::new(MockJsonRpcTransport::new(move |_, _| {
+            counter.fetch_add(1, Ordering::SeqCst);
+            Err(RpcError::Transport("connection refused".to_string()))
+        }));
+        let ed25519 = SignerRef::new(SigningAlgorithm::Ed25519, ACCOUNT.to_vec());
+        assert!(!verifier.is_valid_signature(&ed25519, [1; 32], &[7; 65]));
+        assert_eq!(calls.load(Ordering::SeqCst), 0);
+        assert!(!verifier.is_valid_signature(&account(), [1; 32], &[7; 65]));
+        assert!(!verifier.is_valid_signature(&account(), [1; 32], &[7; 65]));
+        // Transport failures are retried rather than cached.
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn reverts_are_cached_and_other_node_errors_are_retried() {
+        for (code, message, cached) in [
+            (3, "execution reverted", true),
+            (-32_000, "execution reverted: GS026", true),
+            (-32_000, "header not found", false),
+            (-32_005, "rate limit exceeded", false),
+            (-32_603, "internal error", false),
+        ] {
+            let calls = Arc::new(AtomicU32::new(0));
+            let counter = Arc::clone(&calls);
+            let verifier =
+                RpcContractSignatureVerifier::new(MockJsonRpcTransport::new(move |_, _| {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    Err(RpcError::Rpc { code, message: message.to_string() })
+                }));
+            assert!(!verifier.is_valid_signature(&account(), [1; 32], &[7; 65]));
+            assert!(!verifier.is_valid_signature(&account(), [1; 32], &[7; 65]));
+            assert_eq!(
+                calls.load(Ordering::SeqCst),
+                if cached { 1 } else { 2 },
+                "{code} {message}"
+            );
+        }
+    }
+}
@@ -0,0 +1,427 @@
+//! EIP-8004 identity resolution over Ethereum JSON-RPC.
+//!
+//! [`IdentityRegistryResolver`] implements the core
+//! [`IdentityResolver`] seam behind anchored trust entries
+//! ([`ledgerflow_core::TrustedIssuer::anchored`]):
+//!
+//! 1. `tokenURI(agentId)` is read from the anchor's IdentityRegistry with `eth_call`.
+//! 2. The registration file at that URI is loaded (`data:` URIs inline, anything else through a
+//!    [`RegistrationFetcher`]).
+//! 3. The file must list the agent under `registrations`; its `agentWallet` endpoint binds an
+//!    Ethereum address claim and its `did:key` endpoints bind Ed25519 / secp256k1 keys.
+//!
+//! Resolved key sets are cached for a TTL and failures for a (shorter)
+//! negative TTL, so verification does not hit the chain on every request
+//! and an unreachable registry is not hammered either. Resolution still
+//! fails closed: a cached failure is reported as a failure.
+
+use std::{
+    collections::BTreeMap,
+    sync::{Mutex, PoisonError},
+    time::{Duration, Instant},
+};
+
+use base64::Engine;
+use ledgerflow_core::{
+    AgentIdRef, AuthorizationError, IdentityResolver, SignerRef, SigningAlgorithm, hex_encode_bytes,
+};
+use serde::Deserialize;
+
+use crate::rails::{
+    evm_rpc::{parse_hex, selector, word_from_u128},
+    rpc::JsonRpcTransport,
+};
+
+const TOKEN_URI: &str = "tokenURI(uint256)";
+
+/// Default lifetime of a resolved key set.
+pub const DEFAULT_IDENTITY_TTL: Duration = Duration::from_mins(5);
+
+/// Default lifetime of a failed resolution.
+pub const DEFAULT_NEGATIVE_IDENTITY_TTL: Duration = Duration::from_secs(30);
+
+/// Loads the registration file a `tokenURI` points to (`https://`, `ipfs://`,
+/// ...). `data:` URIs never reach the fetcher.
+pub trait RegistrationFetcher: Send + Sync {
+    fn fetch(&self, uri: &str) -> Result<Vec<u8>, String>;
+}
+
+impl<F> RegistrationFetcher for F
+where
+    F: Fn(&str) -> Result<Vec<u8>, String> + Send + Sync,
+{
+    fn fetch(&self, uri: &str) -> Result<Vec<u8>, String> {
+        self(uri)
+    }
+}
+
+/// Registration-file fetcher over HTTP(S) (feature `http`; uses hpx).
+/// `ipfs://` URIs are loaded through the configured gateway.
+#[cfg(feature = "http")]
+#[derive(Clone, Debug)]
+pub struct HttpRegistrationFetcher {
+    timeout_ms: u64,
+    ipfs_gateway: String,
+}
+
+#[cfg(feature = "http")]
+impl HttpRegistrationFetcher {
+    #[must_use]
+    pub fn new(timeout_ms: u64) -> Self {
+        Self { timeout_ms, ipfs_gateway: "https://ipfs.io".to_string() }
+    }
+
+    /// Loads `ipfs://{cid}` from `{gateway}/ipfs/{cid}`.
+    #[must_use]
+    pub fn with_ipfs_gateway(mut self, gateway: impl Into<String>) -> Self {
+        self.ipfs_gateway = gateway.into().trim_end_matches('/').to_string();
+        self
+    }
+}
+
+#[cfg(feature = "http")]
+impl RegistrationFetcher for HttpRegistrationFetcher {
+    fn fetch(&self, uri: &str) -> Result<Vec<u8>, String> {
+        use crate::rails::rpc::RpcError;
+
+        let url = match uri.strip_prefix("ipfs://") {
+            Some(path) => format!("{}/ipfs/{path}", self.ipfs_gateway),
+            None if uri.starts_with("https://") || uri.starts_with("http://") => uri.to_string(),
+            None => return Err(format!("unsupported registration URI scheme in {uri}")),
+        };
+        let request = async {
+            let response = hpx::Client::new().get(url.as_str()).send().await.map_err(|error| {
+                RpcError::Transport(format!("request to {url} failed: {error}"))
+            })?;
+            if !response.status().is_success() {
+                return Err(RpcError::Transport(format!(
+                    "{url} returned HTTP {}",
+                    response.status()
+                )));
+            }
+            response
+                .bytes()
+                .await
+                .map(|bytes| bytes.to_vec())
+                .map_err(|error| RpcError::InvalidResponse(error.to_string()))
+        };
+        crate::rails::rpc::block_on_http(&url, Duration::from_millis(self.timeout_ms), request)
+            .map_err(|error| error.to_string())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Resolver
+// ---------------------------------------------------------------------------
+
+struct CacheEntry {
+    keys: Result<Vec<SignerRef>, String>,
+    expires_at: Instant,
+}
+
+/// [`IdentityResolver`] reading the EIP-8004 IdentityRegistry of one chain.
+pub struct IdentityRegistryResolver<T, F> {
+    chain_id: u64,
+    transport: T,
+    fetcher: F,
+    ttl: Duration,
+    negative_ttl: Duration,
+    cache: Mutex<BTreeMap<String, CacheEntry>>,
+}
+
+impl<T, F> std::fmt::Debug for IdentityRegistryResolver<T, F> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("IdentityRegistryResolver")
+            .field("chain_id", &self.chain_id)
+            .field("ttl", &self.ttl)
+            .field("negative_ttl", &self.negative_ttl)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T, F> IdentityRegistryResolver<T, F>
+where
+    T: JsonRpcTransport,
+    F: RegistrationFetcher,
+{
+    /// A resolver for `eip155:{chain_id}` registries, reading through
+    /// `transport` and loading registration files through `fetcher`.
+    #[must_use]
+    pub const fn new(chain_id: u64, transport: T, fetcher: F) -> Self {
+        Self {
+            chain_id,
+            transport,
+            fetcher,
+            ttl: DEFAULT_IDENTITY_TTL,
+            negative_ttl: DEFAULT_NEGATIVE_IDENTITY_TTL,
+            cache: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Lifetime of a resolved key set.
+    #[must_use]
+    pub const fn with_ttl(mut self, ttl: Duration) -> Self {
+        self.ttl = ttl;
+        self
+    }
+
+    /// Lifetime of a failed resolution.
+    #[must_use]
+    pub const fn with_negative_ttl(mut self, negative_ttl: Duration) -> Self {
+        self.negative_ttl = negative_ttl;
+        self
+    }
+
+    /// Drops every cached resolution (e.g. after a known key rotation).
+    pub fn invalidate(&self) {
+        self.cache.lock().unwrap_or_else(PoisonError::into_inner).clear();
+    }
+
+    fn lookup(&self, agent: &AgentIdRef) -> Result<Vec<SignerRef>, String> {
+        if agent.namespace != "eip155" || agent.chain_id != self.chain_id.to_string() {
+            return Err(format!("this resolver serves eip155:{} only", self.chain_id));
+        }
+        let uri = self.token_uri(agent)?;
+        let document = match data_uri(&uri) {
+            Some(inline) => inline?,
+            None => self.fetcher.fetch(&uri)?,
+        };
+        let registration: RegistrationFile = serde_json::from_slice(&document)
+            .map_err(|error| format!("invalid registration file at {uri}: {error}"))?;
+        registration.signer_keys(agent)
+    }
+
+    fn token_uri(&self, agent: &AgentIdRef) -> Result<String, String> {
+        let mut data = selector(TOKEN_URI).to_vec();
+        data.extend_from_slice(&word_from_u128(u128::from(agent.agent_id)));
+        let result = self
+            .transport
+            .call(
+                "eth_call",
+                serde_json::json!([
+                    { "to": agent.registry, "data": format!("0x{}", hex_encode_bytes(&data)) },
+                    "latest",
+                ]),
+            )
+            .map_err(|error| format!("tokenURI({}) failed: {error}", agent.agent_id))?;
+        result
+            .as_str()
+            .and_then(parse_hex)
+            .and_then(|bytes| decode_abi_string(&bytes))
+            .ok_or_else(|| format!("tokenURI({}) returned {result}", agent.agent_id))
+    }
+}
+
+impl<T, F> IdentityResolver for IdentityRegistryResolver<T, F>
+where
+    T: JsonRpcTransport,
+    F: RegistrationFetcher,
+{
+    fn resolve_keys(&self, agent: &AgentIdRef) -> ledgerflow_core::Result<Vec<SignerRef>> {
+        let key = agent.to_string();
+        let cached = self
+            .cache
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(&key)
+            .filter(|entry| entry.expires_at > Instant::now())
+            .map(|entry| entry.keys.clone());
+        let keys = if let Some(keys) = cached {
+            keys
+        } else {
+            let keys = self.lookup(agent);
+            let ttl = if keys.is_ok() { self.ttl } else { self.negative_ttl };
+            if let Err(detail) = &keys {
+                tracing::warn!(
+                    target: "ledgerflow::identity",
+                    agent = %key,
+                    detail = %detail,
+                    "failed to resolve an agent identity"
+                );
+            }
+            self.cache.lock().unwrap_or_else(PoisonError::into_inner).insert(
+                key.clone(),
+                CacheEntry { keys: keys.clone(), expires_at: Instant::now() + ttl },
+            );
+            keys
+        };
+        keys.map_err(|detail| AuthorizationError::IdentityResolutionFailed {
+            reference: key,
+            detail,
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Registration file
+// ---------------------------------------------------------------------------
+
+/// The parts of an EIP-8004 registration file that bind keys.
+#[derive(Debug, Deserialize)]
+struct RegistrationFile {
+    #[serde(default, alias = "services")]
+    endpoints: Vec<RegistrationEndpoint>,
+    #[serde(default)]
+    registrations: Vec<Registration>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RegistrationEndpoint {
+    name: String,
+    endpoint: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Registration {
+    agent_id: serde_json::Value,
+    agent_registry: String,
+}
+
+impl RegistrationFile {
+    fn signer_keys(&self, agent: &AgentIdRef) -> Result<Vec<SignerRef>, String> {
+        let registered = self.registrations.iter().any(|registration| {
+            registration.agent_registry.eq_ignore_ascii_case(&agent.agent_registry()) &&
+                registration_agent_id(&registration.agent_id) == Some(agent.agent_id)
+        });
+        if !registered {
+            return Err(format!("the registration file does not register {agent}"));
+        }
+        let mut keys = Vec::new();
+        for endpoint in &self.endpoints {
+            if endpoint.name == "agentWallet" {
+                let address = wallet_address(&endpoint.endpoint)
+                    .ok_or_else(|| format!("invalid agentWallet {}", endpoint.endpoint))?;
+                for alg in [SigningAlgorithm::EthPersonalSign, SigningAlgorithm::EthTypedData] {
+                    keys.push(SignerRef::new(alg, address.to_vec()));
+                }
+            } else if endpoint.endpoint.starts_with("did:key:") {
+                keys.extend(did_key_signers(&endpoint.endpoint)?);
+            }
+        }
+        Ok(keys)
+    }
+}
+
+/// `agentId` as a JSON number or decimal string.
+fn registration_agent_id(value: &serde_json::Value) -> Option<u64> {
+    value.as_u64().or_else(|| value.as_str().and_then(|id| id.parse().ok()))
+}
+
+/// The address of an `eip155:{chainId}:0x…` account.
+fn wallet_address(account: &str) -> Option<[u8; 20]> {
+    let mut parts = account.splitn(3, ':');
+    if parts.next()? != "eip155" {
+        return None;
+    }
+    parts.next()?;
+    parse_hex(parts.next()?).and_then(|bytes| <[u8; 20]>::try_from(bytes).ok())
+}
+
+/// The signers an Ed25519 (`0xed01`) or secp256k1 (`0xe701`) `did:key`
+/// binds. A secp256k1 key may sign in any of the secp256k1-family algorithms.
+fn did_key_signers(did: &str) -> Result<Vec<SignerRef>, String> {
+    let invalid = || format!("invalid did:key {did}");
+    let encoded = did.strip_prefix("did:key:z").ok_or_else(invalid)?;
+    let bytes = bs58::decode(encoded).into_vec().map_err(|_| invalid())?;
+    match bytes.as_slice() {
+        [0xed, 0x01, key @ ..] if key.len() == 32 => {
+            Ok(vec![SignerRef::new(SigningAlgorithm::Ed25519, key.to_vec())])
+        }
+        [0xe7, 0x01, key @ ..] if key.len() == 33 => Ok([
+            SigningAlgorithm::Secp256k1,
+            SigningAlgorithm::EthPersonalSign,
+            SigningAlgorithm::EthTypedData,
+        ]
+        .into_iter()
+        .map(|alg| SignerRef::new(alg, key.to_vec()))
+        .collect()),
+        _ => Err(invalid()),
+    }
+}
+
+/// The contents of a `data:application/json[;base64],…` URI, or `None` for
+/// any other URI.
+fn data_uri(uri: &str) -> Option<Result<Vec<u8>, String>> {
+    let (header, data) = uri.strip_prefix("data:")?.split_once(',')?;
+    Some(if header.ends_with(";base64") {
+        base64::engine::general_purpose::STANDARD
+            .decode(data)
+            .map_err(|error| format!("invalid base64 data URI: {error}"))
+    } else {
+        Ok(data.as_bytes().to_vec())
+    })
+}
+
+/// Decodes an ABI-encoded `string` return value.
+fn decode_abi_string(bytes: &[u8]) -> Option<String> {
+    let word = |offset: usize| -> Option<usize> {
+        let word = bytes.get(offset..offset.checked_add(32)?)?;
+        if word[..24].iter().any(|byte| *byte != 0) {
+            return None;
+        }
+        usize::try_from(u64::from_be_bytes(word[24..].try_into().ok()?)).ok()
+    };
+    let offset = word(0)?;
+    let len = word(offset)?;
+    let start = offset.checked_add(32)?;
+    let data = bytes.get(start..start.checked_add(len)?)?;
+    String::from_utf8(data.to_vec()).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::expect_used)]
+
+    use super::*;
+
+    fn abi_string(value: &str) -> Vec<u8> {
+        let mut bytes = word_from_u128(32).to_vec();
+        bytes.extend_from_slice(&word_from_u128(value.len() as u128));
+        bytes.extend_from_slice(value.as_bytes());
+        bytes.resize(64 + value.len().div_ceil(32) * 32, 0);
+        bytes
+    }
+
+    #[test]
+    fn abi_strings_decode() {
+        let uri = "ipfs://bafybeigdyrzt5sfp7udm7hu76uh7y26nf3efuylqabf3oclgtqy55fbzdi";
+        assert_eq!(decode_abi_string(&abi_string(uri)).as_deref(), Some(uri));
+        assert_eq!(decode_abi_string(&abi_string("")).as_deref(), Some(""));
+        let mut truncated = abi_string(uri);
+        truncated.truncate(70);
+        assert_eq!(decode_abi_string(&truncated), None);
+    }
+
+    #[test]
+    fn data_uris_decode_inline() {
+        let encoded = base64::engine::general_purpose::STANDARD.encode(br#"{"a":1}"#);
+        let uri = format!("data:application/json;base64,{encoded}");
+        assert_eq!(data_uri(&uri).expect("data").expect("decoded"), br#"{"a":1}"#.to_vec());
+        assert_eq!(
+            data_uri(r#"data:application/json,{"a":1}"#).expect("data").expect("plain"),
+            br#"{"a":1}"#.to_vec()
+        );
+        assert!(data_uri("https://agent.example/registration.json").is_none());
+    }
+
+    #[test]
+    fn wallet_and_did_key_endpoints_bind_keys() {
+        assert_eq!(
+            wallet_address("eip155:8453:0x00000000000000000000000000000000000000aa"),
+            Some({
+                let mut address = [0_u8; 20];
+                address[19] = 0xaa;
+                address
+            })
+        );
+        assert_eq!(wallet_address("solana:mainnet:0xaa"), None);
+
+        let ed25519 = ledgerflow_core::SigningKeyPair::from_bytes(&[7_u8; 32]);
+        let mut multicodec = vec![0xed, 0x01];
+        multicodec.extend_from_slice(&ed25519.public_key_bytes());
+        let did = format!("did:key:z{}", bs58::encode(multicodec).into_string());
+        assert_eq!(did_key_signers(&did).expect("did:key"), vec![ed25519.signer_ref()]);
+        assert!(did_key_signers("did:key:zinvalid0").is_err());
+    }
+}
@@ -5,18 +5,27 @@
 //!
 //! - [`verify`]: stateless authz verification + revocation pre-check.
 //! - [`settle`]: atomic re-verification (TOCTOU closing) + rail settlement.
+//! - [`session`]: MPP session intent with per-tick revocation and cap checks.
 //! - [`status`]: idempotent settlement queries.
+//! - [`budget`]: accounting-point budget reservation (periodic/lifetime).
+//! - [`contract_signature`]: ERC-1271 contract-account signatures over JSON-RPC.
+//! - [`identity`]: EIP-8004 identity resolution for anchored trust entries.
 //! - [`revocation_store`]: persistent, restart-safe revocation.
+//! - [`srl_sync`]: signed revocation list polling with a staleness heartbeat.
 //! - [`routing`] / [`subject`] / [`rails`]: rail-agnostic routing.
 
 #![allow(missing_docs)]
 #![allow(missing_debug_implementations)]
 
+pub mod budget;
+pub mod contract_signature;
+pub mod identity;
 pub mod outcome;
 pub mod rails;
 pub mod reputation;
 pub mod revocation_store;
 pub mod routing;
+pub mod session;
 pub mod settle;
 pub mod srl_sync;
 pub mod status;
@@ -24,22 +33,48 @@ pub mod subject;
 pub mod verify;
 
 pub use crate::{
+    budget::{
+        BudgetError, BudgetLedger, BudgetReservation, BudgetScope, InMemoryBudgetLedger,
+        SharedBudgetLedger,
+    },
+    contract_signature::{DEFAULT_CONTRACT_SIGNATURE_TTL, RpcContractSignatureVerifier},
+    identity::{
+        DEFAULT_IDENTITY_TTL, DEFAULT_NEGATIVE_IDENTITY_TTL, IdentityRegistryResolver,
+        RegistrationFetcher,
+    },
     outcome::{SettlementOutcome, SettlementStatus, VerifyOutcome, VerifyStatus},
     rails::{
         RailAdapter, RailError, RailQuote, SettlementReceipt, SharedRailAdapter,
-        VerificationResult, custodial::CustodialRailAdapter, evm::EvmRailAdapter,
-        exchange::ExchangeRailAdapter, gateway::GatewayRailAdapter, solana::SolanaRailAdapter,
+        VerificationResult,
+        custodial::CustodialRailAdapter,
+        evm::EvmRailAdapter,
+        evm_rpc::{EvmRpcConfig, EvmRpcRailAdapter, EvmSettlementPayload, PERMIT2_ADDRESS},
+        exchange::ExchangeRailAdapter,
+        gateway::GatewayRailAdapter,
+        rpc::{JsonRpcTransport, MockJsonRpcTransport, RpcError, SharedJsonRpcTransport},
+        solana::SolanaRailAdapter,
+        solana_rpc::{
+            SolanaCommitment, SolanaRpcConfig, SolanaRpcRailAdapter, SolanaTransaction, SplMint,
+            associated_token_address,
+        },
     },
     reputation::{
         FeedbackSink, LoggingSink, ProofOfPayment, ReputationReporter, SettlementFeedback,
     },
-    revocation_store::{FileRevocationStore, InsecureMemoryRevocationStore, RevocationStoreError},
+    revocation_store::{
+        FileRevocationStore, InsecureMemoryRevocationStore, RevocationStore, RevocationStoreError,
+        SharedRevocationCheck, SharedRevocationStore, TenantRevocationView, tenant_scoped_key,
+    },
     routing::{Facilitator, RailKind, RouteDecision, RoutingError},
+    session::{PaymentSession, SessionError, SessionManager, SessionState, SessionUpdate},
     settle::{SettleRequest, SettlementService},
-    srl_sync::{SrlSync, SrlSyncError},
-    status::{RegistryEntry, SettlementRegistry},
+    srl_sync::{
+        DEFAULT_SRL_MAX_AGE, SrlPoller, SrlSource, SrlSync, SrlSyncError, SrlUpdate, TenantSrlView,
+    },
+    status::{RegistryEntry, SettlementRegistry, SettlementStore, SharedSettlementStore},
     subject::{
-        DefaultSubjectResolver, PaymentSubjectResolver, ResolvedSubject, SubjectResolutionError,
+        DefaultSubjectResolver, PaymentSubjectResolver, PolicySubjectResolver, ResolvedSubject,
+        SubjectResolutionError,
     },
     verify::{VerificationService, VerifyRequest},
 };
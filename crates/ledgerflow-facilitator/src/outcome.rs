@@ -34,6 +34,20 @@ impl VerifyStatus {
     pub const fn is_verified(&self) -> bool {
         matches!(self, Self::Verified)
     }
+
+    /// Stable snake_case wire code (used by the hosted HTTP API).
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Verified => "verified",
+            Self::Unauthorized => "unauthorized",
+            Self::InsufficientApproval => "insufficient_approval",
+            Self::Replayed => "replayed",
+            Self::Expired => "expired",
+            Self::Revoked => "revoked",
+            Self::InvalidPayment => "invalid_payment",
+        }
+    }
 }
 
 /// Output of a `/verify` orchestration.
@@ -64,6 +78,29 @@ pub enum SettlementStatus {
     Failed,
 }
 
+impl SettlementStatus {
+    /// Stable snake_case wire code (used by the hosted HTTP API).
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Pending => "pending",
+            Self::Settled => "settled",
+            Self::Failed => "failed",
+        }
+    }
+
+    /// Parses a wire code produced by [`as_str`](Self::as_str).
+    #[must_use]
+    pub fn from_wire(value: &str) -> Option<Self> {
+        match value {
+            "pending" => Some(Self::Pending),
+            "settled" => Some(Self::Settled),
+            "failed" => Some(Self::Failed),
+            _ => None,
+        }
+    }
+}
+
 /// Output of a `/settle` orchestration.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct SettlementOutcome {
@@ -181,6 +218,15 @@ mod tests {
         assert_eq!(failed.reason.as_deref(), Some("boom"));
     }
 
+    #[test]
+    fn status_wire_codes_are_stable() {
+        assert_eq!(VerifyStatus::Verified.as_str(), "verified");
+        assert_eq!(VerifyStatus::InsufficientApproval.as_str(), "insufficient_approval");
+        assert_eq!(VerifyStatus::InvalidPayment.as_str(), "invalid_payment");
+        assert_eq!(SettlementStatus::Settled.as_str(), "settled");
+        assert_eq!(SettlementStatus::Failed.as_str(), "failed");
+    }
+
     #[test]
     fn settlement_statuses_are_distinct() {
         assert_ne!(SettlementStatus::Pending, SettlementStatus::Settled);
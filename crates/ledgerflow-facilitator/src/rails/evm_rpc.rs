@@ -0,0 +1,1200 @@
+//! EVM onchain settlement over JSON-RPC (EIP-3009 / Permit2).
+//!
+//! The agent signs a gasless token authorization and ships it as the x402
+//! `payload` (the `exact` EVM scheme shape):
+//!
+//! - **EIP-3009** (`authorization`): USDC-style `transferWithAuthorization(from, to, value,
+//!   validAfter, validBefore, nonce, v, r, s)` submitted to the token contract.
+//! - **Permit2** (`permit2Authorization`): `SignatureTransfer.permitTransferFrom` submitted to the
+//!   Permit2 contract, with the facilitator relayer as the signed `spender`.
+//!
+//! Before anything is broadcast the adapter fails closed unless the signed
+//! transfer matches the verified authorization exactly: payer (the CAIP-10
+//! payment subject), payee, token, amount, chain, and validity window. It then
+//! builds an EIP-1559 transaction from the relayer key, submits it with
+//! `eth_sendRawTransaction`, and polls `eth_getTransactionReceipt` until the
+//! configured confirmation depth is reached. [`RailAdapter::verify`] reports
+//! the live confirmation count and checks the token `Transfer` log between
+//! the payer and payee named in the transaction's own calldata, so any node
+//! can verify any settlement.
+//!
+//! Settlement blocks the calling thread while it polls (up to
+//! `max_polls × poll_interval_ms`); async callers run it on
+//! `tokio::task::spawn_blocking`.
+
+use std::{collections::BTreeMap, sync::Mutex};
+
+use ledgerflow_core::{Secp256k1KeyPair, VerifiedAuthorization, hex_encode_bytes, keccak256};
+use serde::Deserialize;
+
+use crate::{
+    rails::{
+        RailAdapter, RailError, RailQuote, SettlementReceipt, VerificationResult,
+        rpc::{JsonRpcTransport, RpcError},
+    },
+    routing::RailKind,
+    subject::ResolvedSubject,
+};
+
+/// Canonical Permit2 deployment address (same on every EVM chain).
+pub const PERMIT2_ADDRESS: [u8; 20] = [
+    0x00, 0x00, 0x00, 0x00, 0x00, 0x22, 0xd4, 0x73, 0x03, 0x0f, 0x11, 0x6d, 0xde, 0xe9, 0xf6, 0xb4,
+    0x3a, 0xc7, 0x8b, 0xa3,
+];
+
+const TRANSFER_WITH_AUTHORIZATION: &str = "transferWithAuthorization(address,address,uint256,uint256,uint256,bytes32,uint8,bytes32,bytes32)";
+const PERMIT_TRANSFER_FROM: &str =
+    "permitTransferFrom(((address,uint256),uint256,uint256),(address,uint256),address,bytes)";
+const TRANSFER_EVENT: &str = "Transfer(address,address,uint256)";
+
+// ---------------------------------------------------------------------------
+// Configuration
+// ---------------------------------------------------------------------------
+
+/// Chain configuration for [`EvmRpcRailAdapter`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EvmRpcConfig {
+    /// EIP-155 chain id; payment subjects on other chains are not supported.
+    pub chain_id: u64,
+    /// Asset symbol (as quoted, e.g. `USDC`) → token contract.
+    pub tokens: BTreeMap<String, [u8; 20]>,
+    /// Payee id (as quoted) → receiving address. Payee ids that are already
+    /// `0x` addresses need no entry.
+    pub payees: BTreeMap<String, [u8; 20]>,
+    /// Permit2 contract; Permit2 payloads are rejected when `None`.
+    pub permit2: Option<[u8; 20]>,
+    /// Confirmations required before a settlement is reported (≥ 1).
+    pub min_confirmations: u32,
+    /// Delay between receipt polls.
+    pub poll_interval_ms: u64,
+    /// Receipt polls before settlement gives up.
+    pub max_polls: u32,
+    /// Expected block time (used for quotes).
+    pub block_time_ms: u64,
+    /// Head-room added to `eth_estimateGas`, in percent.
+    pub gas_buffer_percent: u64,
+}
+
+impl EvmRpcConfig {
+    /// Creates a configuration for `chain_id` with Permit2 at its canonical
+    /// address and single-confirmation settlement.
+    #[must_use]
+    pub const fn new(chain_id: u64) -> Self {
+        Self {
+            chain_id,
+            tokens: BTreeMap::new(),
+            payees: BTreeMap::new(),
+            permit2: Some(PERMIT2_ADDRESS),
+            min_confirmations: 1,
+            poll_interval_ms: 1_000,
+            max_polls: 60,
+            block_time_ms: 2_000,
+            gas_buffer_percent: 20,
+        }
+    }
+
+    /// Maps an asset symbol to its token contract.
+    #[must_use]
+    pub fn with_token(mut self, asset: impl Into<String>, contract: [u8; 20]) -> Self {
+        self.tokens.insert(asset.into(), contract);
+        self
+    }
+
+    /// Maps a payee id to its receiving address.
+    #[must_use]
+    pub fn with_payee(mut self, payee_id: impl Into<String>, address: [u8; 20]) -> Self {
+        self.payees.insert(payee_id.into(), address);
+        self
+    }
+
+    /// Overrides (or disables) the Permit2 contract.
+    #[must_use]
+    pub const fn with_permit2(mut self, permit2: Option<[u8; 20]>) -> Self {
+        self.permit2 = permit2;
+        self
+    }
+
+    /// Sets the confirmation depth required before reporting settlement.
+    #[must_use]
+    pub fn with_min_confirmations(mut self, confirmations: u32) -> Self {
+        self.min_confirmations = confirmations.max(1);
+        self
+    }
+
+    /// Sets the receipt polling schedule.
+    #[must_use]
+    pub const fn with_polling(mut self, interval_ms: u64, max_polls: u32) -> Self {
+        self.poll_interval_ms = interval_ms;
+        self.max_polls = max_polls;
+        self
+    }
+
+    fn token(&self, asset: &str) -> Result<[u8; 20], RailError> {
+        self.tokens
+            .get(asset)
+            .copied()
+            .or_else(|| parse_address(asset))
+            .ok_or_else(|| RailError::SettlementFailed(format!("no token contract for `{asset}`")))
+    }
+
+    fn payee(&self, payee_id: &str) -> Result<[u8; 20], RailError> {
+        self.payees.get(payee_id).copied().or_else(|| parse_address(payee_id)).ok_or_else(|| {
+            RailError::SettlementFailed(format!("no address for payee `{payee_id}`"))
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Settlement payload (x402 `exact` EVM scheme)
+// ---------------------------------------------------------------------------
+
+/// Agent-signed EVM settlement payload.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct EvmSettlementPayload {
+    /// 65-byte `r || s || v` signature, `0x`-hex.
+    pub signature: String,
+    #[serde(default)]
+    pub authorization: Option<Eip3009Authorization>,
+    #[serde(default)]
+    pub permit2_authorization: Option<Permit2Authorization>,
+}
+
+/// EIP-3009 `TransferWithAuthorization` message.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Eip3009Authorization {
+    pub from: String,
+    pub to: String,
+    pub value: String,
+    pub valid_after: String,
+    pub valid_before: String,
+    /// 32-byte nonce, `0x`-hex.
+    pub nonce: String,
+}
+
+/// Permit2 `PermitTransferFrom` message.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct Permit2Authorization {
+    pub from: String,
+    pub permitted: Permit2TokenPermissions,
+    pub spender: String,
+    pub nonce: String,
+    pub deadline: String,
+}
+
+/// Permit2 `TokenPermissions`.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq)]
+pub struct Permit2TokenPermissions {
+    pub token: String,
+    pub amount: String,
+}
+
+impl EvmSettlementPayload {
+    /// Parses the x402 `payload` JSON.
+    pub fn parse(payload: &str) -> Result<Self, RailError> {
+        serde_json::from_str(payload).map_err(|error| {
+            RailError::SettlementFailed(format!("invalid EVM settlement payload: {error}"))
+        })
+    }
+}
+
+/// A validated token transfer ready to be wrapped in a transaction.
+#[derive(Clone, Debug, Eq, PartialEq)]
+struct TransferCall {
+    to: [u8; 20],
+    data: Vec<u8>,
+}
+
+/// Payer and payee of a token transfer.
+type TransferParties = ([u8; 20], [u8; 20]);
+
+// ---------------------------------------------------------------------------
+// Adapter
+// ---------------------------------------------------------------------------
+
+/// EVM rail adapter settling EIP-3009 / Permit2 authorizations through a
+/// JSON-RPC node, paying gas from a relayer key.
+///
+/// Every method blocks on the node; see the module docs.
+pub struct EvmRpcRailAdapter<T> {
+    transport: T,
+    relayer: Secp256k1KeyPair,
+    config: EvmRpcConfig,
+    /// Serializes nonce allocation → broadcast so concurrent settlements
+    /// never reuse a relayer nonce.
+    submit_lock: Mutex<()>,
+}
+
+impl<T> std::fmt::Debug for EvmRpcRailAdapter<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EvmRpcRailAdapter")
+            .field("relayer", &self.relayer)
+            .field("config", &self.config)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> EvmRpcRailAdapter<T>
+where
+    T: JsonRpcTransport,
+{
+    /// Creates an adapter submitting through `transport` with gas paid by
+    /// `relayer`.
+    #[must_use]
+    pub const fn new(transport: T, relayer: Secp256k1KeyPair, config: EvmRpcConfig) -> Self {
+        Self { transport, relayer, config, submit_lock: Mutex::new(()) }
+    }
+
+    /// The relayer address (`msg.sender` of every settlement; the required
+    /// Permit2 `spender`).
+    #[must_use]
+    pub fn relayer_address(&self) -> [u8; 20] {
+        self.relayer.ethereum_address()
+    }
+
+    /// Validates `payload` against the verified authorization and encodes the
+    /// contract call (no RPC traffic).
+    fn transfer_call(
+        &self,
+        authorization: &VerifiedAuthorization,
+        payload: &EvmSettlementPayload,
+        now_secs: u64,
+    ) -> Result<TransferCall, RailError> {
+        let reject = |reason: &str| RailError::SettlementFailed(reason.to_string());
+        let payer = payer_address(&authorization.payment_subject.value, self.config.chain_id)?;
+        let payee = self.config.payee(&authorization.payee_id)?;
+        let token = self.config.token(&authorization.asset)?;
+        let amount = word_from_u128(authorization.amount);
+        let signature = parse_hex(&payload.signature)
+            .filter(|bytes| bytes.len() == 65)
+            .ok_or_else(|| reject("signature must be 65 bytes"))?;
+
+        match (&payload.authorization, &payload.permit2_authorization) {
+            (Some(message), None) => {
+                if parse_address(&message.from) != Some(payer) {
+                    return Err(reject("authorization.from is not the payment subject"));
+                }
+                if parse_address(&message.to) != Some(payee) {
+                    return Err(reject("authorization.to is not the payee"));
+                }
+                if parse_word(&message.value) != Some(amount) {
+                    return Err(reject("authorization.value does not match the amount"));
+                }
+                let valid_after = parse_word(&message.valid_after)
+                    .ok_or_else(|| reject("invalid authorization.validAfter"))?;
+                let valid_before = parse_word(&message.valid_before)
+                    .ok_or_else(|| reject("invalid authorization.validBefore"))?;
+                let now = word_from_u128(u128::from(now_secs));
+                if valid_after >= now || valid_before <= now {
+                    return Err(reject("authorization is outside its validity window"));
+                }
+                let nonce = parse_hex(&message.nonce)
+                    .and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+                    .ok_or_else(|| reject("authorization.nonce must be 32 bytes"))?;
+                let (r, s, v) = split_signature(&signature);
+
+                let mut data = selector(TRANSFER_WITH_AUTHORIZATION).to_vec();
+                for word in [
+                    word_from_address(payer),
+                    word_from_address(payee),
+                    amount,
+                    valid_after,
+                    valid_before,
+                    nonce,
+                    word_from_u128(u128::from(v)),
+                    r,
+                    s,
+                ] {
+                    data.extend_from_slice(&word);
+                }
+                Ok(TransferCall { to: token, data })
+            }
+            (None, Some(message)) => {
+                let permit2 =
+                    self.config.permit2.ok_or_else(|| reject("Permit2 is not enabled"))?;
+                if parse_address(&message.from) != Some(payer) {
+                    return Err(reject("permit2Authorization.from is not the payment subject"));
+                }
+                if parse_address(&message.permitted.token) != Some(token) {
+                    return Err(reject("permit2Authorization token does not match the asset"));
+                }
+                if parse_address(&message.spender) != Some(self.relayer_address()) {
+                    return Err(reject("permit2Authorization.spender is not this facilitator"));
+                }
+                let permitted = parse_word(&message.permitted.amount)
+                    .ok_or_else(|| reject("invalid permit2Authorization amount"))?;
+                if permitted != amount {
+                    return Err(reject("permit2Authorization amount does not match the amount"));
+                }
+                let nonce = parse_word(&message.nonce)
+                    .ok_or_else(|| reject("invalid permit2Authorization.nonce"))?;
+                let deadline = parse_word(&message.deadline)
+                    .ok_or_else(|| reject("invalid permit2Authorization.deadline"))?;
+                if deadline < word_from_u128(u128::from(now_secs)) {
+                    return Err(reject("permit2Authorization has expired"));
+                }
+
+                // permitTransferFrom(permit, transferDetails, owner, signature):
+                // 7 static head words + the offset of the dynamic `bytes`.
+                let mut data = selector(PERMIT_TRANSFER_FROM).to_vec();
+                for word in [
+                    word_from_address(token),
+                    permitted,
+                    nonce,
+                    deadline,
+                    word_from_address(payee),
+                    amount,
+                    word_from_address(payer),
+                    word_from_u128(8 * 32),
+                    word_from_u128(signature.len() as u128),
+                ] {
+                    data.extend_from_slice(&word);
+                }
+                data.extend_from_slice(&signature);
+                pad_calldata(&mut data);
+                Ok(TransferCall { to: permit2, data })
+            }
+            _ => Err(reject("expected exactly one of authorization or permit2Authorization")),
+        }
+    }
+
+    /// Signs and broadcasts `call` from the relayer; returns the tx hash.
+    fn submit(&self, call: &TransferCall) -> Result<String, RpcError> {
+        let _guard = self
+            .submit_lock
+            .lock()
+            .map_err(|_| RpcError::Transport("submission lock poisoned".to_string()))?;
+        let from = address_hex(self.relayer_address());
+        let to = address_hex(call.to);
+        let data = format!("0x{}", hex_encode_bytes(&call.data));
+
+        let nonce =
+            quantity(&self.rpc("eth_getTransactionCount", serde_json::json!([from, "pending"]))?)?;
+        let estimate = quantity(&self.rpc(
+            "eth_estimateGas",
+            serde_json::json!([{ "from": from, "to": to, "data": data }]),
+        )?)?;
+        let gas = estimate.saturating_mul(100 + u128::from(self.config.gas_buffer_percent)) / 100;
+        let tip = quantity(&self.rpc("eth_maxPriorityFeePerGas", serde_json::json!([]))?)?;
+        let block = self.rpc("eth_getBlockByNumber", serde_json::json!(["latest", false]))?;
+        let base_fee = block.get("baseFeePerGas").map(quantity).transpose()?.unwrap_or_default();
+        let max_fee = base_fee.saturating_mul(2).saturating_add(tip);
+
+        let raw = sign_eip1559(
+            &self.relayer,
+            &Eip1559Transaction {
+                chain_id: self.config.chain_id,
+                nonce,
+                max_priority_fee_per_gas: tip,
+                max_fee_per_gas: max_fee,
+                gas_limit: gas,
+                to: call.to,
+                data: &call.data,
+            },
+        );
+        let hash = self.rpc(
+            "eth_sendRawTransaction",
+            serde_json::json!([format!("0x{}", hex_encode_bytes(&raw))]),
+        )?;
+        hash.as_str().map(str::to_string).ok_or_else(|| {
+            RpcError::InvalidResponse("transaction hash is not a string".to_string())
+        })
+    }
+
+    /// Reads the receipt of `tx_hash` and the current confirmation depth.
+    fn receipt_state(&self, tx_hash: &str) -> Result<Option<ReceiptState>, RpcError> {
+        let receipt = self.rpc("eth_getTransactionReceipt", serde_json::json!([tx_hash]))?;
+        if receipt.is_null() {
+            return Ok(None);
+        }
+        let block = receipt
+            .get("blockNumber")
+            .filter(|value| !value.is_null())
+            .map(quantity)
+            .transpose()?;
+        let Some(block) = block else {
+            return Ok(None);
+        };
+        let head = quantity(&self.rpc("eth_blockNumber", serde_json::json!([]))?)?;
+        let succeeded = receipt.get("status").map(quantity).transpose()? == Some(1);
+        let confirmations =
+            u32::try_from(head.saturating_sub(block).saturating_add(1)).unwrap_or(u32::MAX);
+        let logs =
+            receipt.get("logs").and_then(serde_json::Value::as_array).cloned().unwrap_or_default();
+        Ok(Some(ReceiptState { succeeded, confirmations, logs }))
+    }
+
+    /// Payer and payee named in the calldata of `tx_hash`, when it is a
+    /// `transferWithAuthorization` on `token` or a Permit2
+    /// `permitTransferFrom` of `token`.
+    fn transfer_parties(
+        &self,
+        tx_hash: &str,
+        token: [u8; 20],
+    ) -> Result<Option<TransferParties>, RpcError> {
+        let transaction = self.rpc("eth_getTransactionByHash", serde_json::json!([tx_hash]))?;
+        let field = |name: &str| transaction.get(name).and_then(serde_json::Value::as_str);
+        let (Some(to), Some(input)) =
+            (field("to").and_then(parse_address), field("input").and_then(parse_hex))
+        else {
+            return Ok(None);
+        };
+        let Some((function, arguments)) = input.split_at_checked(4) else {
+            return Ok(None);
+        };
+        let address = |index: usize| {
+            let (padding, address) = arguments.get(index * 32..(index + 1) * 32)?.split_at(12);
+            if padding.iter().any(|byte| *byte != 0) {
+                return None;
+            }
+            <[u8; 20]>::try_from(address).ok()
+        };
+        // transferWithAuthorization(from, to, ..) and
+        // permitTransferFrom((token, ..), (to, ..), owner, ..).
+        let parties = if to == token && function == selector(TRANSFER_WITH_AUTHORIZATION) {
+            address(0).zip(address(1))
+        } else if Some(to) == self.config.permit2 &&
+            function == selector(PERMIT_TRANSFER_FROM) &&
+            address(0) == Some(token)
+        {
+            address(6).zip(address(4))
+        } else {
+            None
+        };
+        Ok(parties)
+    }
+
+    fn rpc(&self, method: &str, params: serde_json::Value) -> Result<serde_json::Value, RpcError> {
+        self.transport.call(method, params)
+    }
+}
+
+/// Observed state of a mined transaction.
+struct ReceiptState {
+    succeeded: bool,
+    confirmations: u32,
+    logs: Vec<serde_json::Value>,
+}
+
+impl<T> RailAdapter for EvmRpcRailAdapter<T>
+where
+    T: JsonRpcTransport,
+{
+    fn kind(&self) -> RailKind {
+        RailKind::Evm
+    }
+
+    fn supports(&self, subject: &ResolvedSubject) -> bool {
+        matches!(subject.rail, RailKind::Evm) &&
+            payer_address(&subject.value, self.config.chain_id).is_ok()
+    }
+
+    fn quote(&self, authorization: &VerifiedAuthorization) -> Result<RailQuote, RailError> {
+        Ok(RailQuote {
+            rail: RailKind::Evm,
+            // Gas is paid by the relayer; the payer only moves `amount`.
+            estimated_fee: 0,
+            estimated_time_ms: self
+                .config
+                .block_time_ms
+                .saturating_mul(u64::from(self.config.min_confirmations)),
+            asset: authorization.asset.clone(),
+        })
+    }
+
+    fn settle(
+        &self,
+        _authorization: &VerifiedAuthorization,
+    ) -> Result<SettlementReceipt, RailError> {
+        Err(RailError::SettlementFailed(
+            "EVM settlement requires the agent-signed EIP-3009 or Permit2 payload".to_string(),
+        ))
+    }
+
+    fn settle_with_payload(
+        &self,
+        authorization: &VerifiedAuthorization,
+        settlement_payload: &str,
+    ) -> Result<SettlementReceipt, RailError> {
+        let payload = EvmSettlementPayload::parse(settlement_payload)?;
+        let call = self.transfer_call(authorization, &payload, unix_now_secs())?;
+        let failed = |error: RpcError| RailError::SettlementFailed(error.to_string());
+        let tx_hash = self.submit(&call).map_err(failed)?;
+
+        for attempt in 0..self.config.max_polls {
+            if attempt > 0 {
+                std::thread::sleep(std::time::Duration::from_millis(self.config.poll_interval_ms));
+            }
+            match self.receipt_state(&tx_hash).map_err(failed)? {
+                Some(state) if !state.succeeded => {
+                    return Err(RailError::SettlementFailed(format!(
+                        "transaction {tx_hash} reverted"
+                    )));
+                }
+                Some(state) if state.confirmations >= self.config.min_confirmations => {
+                    return Ok(SettlementReceipt {
+                        rail: RailKind::Evm,
+                        transaction_id: tx_hash,
+                        settled_amount: authorization.amount,
+                        asset: authorization.asset.clone(),
+                    });
+                }
+                _ => {}
+            }
+        }
+        // The transaction may still land; surface the hash for reconciliation.
+        Err(RailError::SettlementFailed(format!(
+            "transaction {tx_hash} was not confirmed after {} polls",
+            self.config.max_polls
+        )))
+    }
+
+    fn verify(&self, receipt: &SettlementReceipt) -> Result<VerificationResult, RailError> {
+        let failed = |error: RpcError| RailError::VerificationFailed(error.to_string());
+        let Some(state) = self.receipt_state(&receipt.transaction_id).map_err(failed)? else {
+            return Ok(VerificationResult { verified: false, confirmations: 0 });
+        };
+        let token = self
+            .config
+            .token(&receipt.asset)
+            .map_err(|error| RailError::VerificationFailed(error.to_string()))?;
+        // The parties come from the transaction itself; a transaction that is
+        // not a transfer of this token fails closed.
+        let parties = self.transfer_parties(&receipt.transaction_id, token).map_err(failed)?;
+        let transferred = parties.is_some_and(|(payer, payee)| {
+            let amount = word_from_u128(receipt.settled_amount);
+            state.logs.iter().any(|log| is_transfer_log(log, token, payer, payee, amount))
+        });
+        Ok(VerificationResult {
+            verified: state.succeeded &&
+                transferred &&
+                state.confirmations >= self.config.min_confirmations,
+            confirmations: state.confirmations,
+        })
+    }
+}
+
+/// Whether `log` is an ERC-20 `Transfer` of `amount` from `payer` to `payee`
+/// emitted by `token`.
+fn is_transfer_log(
+    log: &serde_json::Value,
+    token: [u8; 20],
+    payer: [u8; 20],
+    payee: [u8; 20],
+    amount: [u8; 32],
+) -> bool {
+    let emitter = log.get("address").and_then(serde_json::Value::as_str).and_then(parse_address);
+    let topic = |index: usize| {
+        log.get("topics")
+            .and_then(|topics| topics.get(index))
+            .and_then(serde_json::Value::as_str)
+            .and_then(parse_hex)
+    };
+    let data = log.get("data").and_then(serde_json::Value::as_str).and_then(parse_hex);
+    emitter == Some(token) &&
+        topic(0).as_deref() == Some(keccak256(TRANSFER_EVENT.as_bytes()).as_slice()) &&
+        topic(1).as_deref() == Some(word_from_address(payer).as_slice()) &&
+        topic(2).as_deref() == Some(word_from_address(payee).as_slice()) &&
+        data.as_deref() == Some(amount.as_slice())
+}
+
+/// Extracts the payer address from a `caip10:eip155:<chain>:<address>`
+/// subject, requiring the configured chain.
+fn payer_address(subject: &str, chain_id: u64) -> Result<[u8; 20], RailError> {
+    let unsupported = || RailError::Unsupported;
+    let rest = subject.strip_prefix("caip10:eip155:").ok_or_else(unsupported)?;
+    let (chain, address) = rest.split_once(':').ok_or_else(unsupported)?;
+    if chain.parse::<u64>().ok() != Some(chain_id) {
+        return Err(unsupported());
+    }
+    parse_address(address).ok_or_else(unsupported)
+}
+
+fn unix_now_secs() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |duration| duration.as_secs())
+}
+
+// ---------------------------------------------------------------------------
+// EIP-1559 transactions (RLP)
+// ---------------------------------------------------------------------------
+
+/// Unsigned EIP-1559 (type 2) transaction with zero value and no access list.
+struct Eip1559Transaction<'a> {
+    chain_id: u64,
+    nonce: u128,
+    max_priority_fee_per_gas: u128,
+    max_fee_per_gas: u128,
+    gas_limit: u128,
+    to: [u8; 20],
+    data: &'a [u8],
+}
+
+impl Eip1559Transaction<'_> {
+    fn fields(&self) -> Vec<Vec<u8>> {
+        vec![
+            rlp_uint(u128::from(self.chain_id)),
+            rlp_uint(self.nonce),
+            rlp_uint(self.max_priority_fee_per_gas),
+            rlp_uint(self.max_fee_per_gas),
+            rlp_uint(self.gas_limit),
+            rlp_bytes(&self.to),
+            rlp_uint(0),
+            rlp_bytes(self.data),
+            rlp_list(&[]),
+        ]
+    }
+}
+
+/// Signs a type-2 transaction, returning `0x02 || rlp([..fields, yParity, r, s])`.
+fn sign_eip1559(key: &Secp256k1KeyPair, transaction: &Eip1559Transaction<'_>) -> Vec<u8> {
+    let mut fields = transaction.fields();
+    let mut preimage = vec![0x02];
+    preimage.extend(rlp_list(&fields));
+    // The envelope is a raw recoverable signature over the prehash:
+    // `r || s || v` with `v = 27 + recovery id`.
+    let signature = key.sign_eth_typed_data_digest(&keccak256(&preimage)).value;
+    let (r, s, v) = split_signature(&signature);
+    fields.push(rlp_uint(u128::from(v.saturating_sub(27))));
+    fields.push(rlp_bytes(trim_leading_zeros(&r)));
+    fields.push(rlp_bytes(trim_leading_zeros(&s)));
+    let mut raw = vec![0x02];
+    raw.extend(rlp_list(&fields));
+    raw
+}
+
+fn rlp_bytes(bytes: &[u8]) -> Vec<u8> {
+    match bytes {
+        [single] if *single < 0x80 => vec![*single],
+        _ => {
+            let mut out = rlp_length_prefix(bytes.len(), 0x80);
+            out.extend_from_slice(bytes);
+            out
+        }
+    }
+}
+
+fn rlp_uint(value: u128) -> Vec<u8> {
+    rlp_bytes(trim_leading_zeros(&value.to_be_bytes()))
+}
+
+fn rlp_list(items: &[Vec<u8>]) -> Vec<u8> {
+    let payload: Vec<u8> = items.concat();
+    let mut out = rlp_length_prefix(payload.len(), 0xc0);
+    out.extend(payload);
+    out
+}
+
+fn rlp_length_prefix(len: usize, offset: u8) -> Vec<u8> {
+    if len < 56 {
+        return vec![offset + len as u8];
+    }
+    let len_bytes = (len as u64).to_be_bytes();
+    let len_bytes = trim_leading_zeros(&len_bytes);
+    let mut out = vec![offset + 55 + len_bytes.len() as u8];
+    out.extend_from_slice(len_bytes);
+    out
+}
+
+fn trim_leading_zeros(bytes: &[u8]) -> &[u8] {
+    let start = bytes.iter().position(|byte| *byte != 0).unwrap_or(bytes.len());
+    &bytes[start..]
+}
+
+// ---------------------------------------------------------------------------
+// ABI / hex helpers
+// ---------------------------------------------------------------------------
+
+pub(crate) fn selector(signature: &str) -> [u8; 4] {
+    let hash = keccak256(signature.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// Splits a 65-byte `r || s || v` signature (`v` normalized to 27/28).
+fn split_signature(signature: &[u8]) -> ([u8; 32], [u8; 32], u8) {
+    let mut r = [0_u8; 32];
+    let mut s = [0_u8; 32];
+    r.copy_from_slice(&signature[..32]);
+    s.copy_from_slice(&signature[32..64]);
+    let v = signature[64];
+    (r, s, if v < 27 { v + 27 } else { v })
+}
+
+/// Right-pads calldata after the selector to a whole number of words.
+pub(crate) fn pad_calldata(data: &mut Vec<u8>) {
+    let words = (data.len() - 4).div_ceil(32);
+    data.resize(4 + words * 32, 0);
+}
+
+fn word_from_address(address: [u8; 20]) -> [u8; 32] {
+    let mut word = [0_u8; 32];
+    word[12..].copy_from_slice(&address);
+    word
+}
+
+pub(crate) fn word_from_u128(value: u128) -> [u8; 32] {
+    let mut word = [0_u8; 32];
+    word[16..].copy_from_slice(&value.to_be_bytes());
+    word
+}
+
+/// Parses a uint256 from a decimal or `0x`-hex string.
+fn parse_word(value: &str) -> Option<[u8; 32]> {
+    let value = value.trim();
+    if let Some(hex) = value.strip_prefix("0x") {
+        let bytes = parse_hex_digits(hex)?;
+        if bytes.len() > 32 {
+            return None;
+        }
+        let mut word = [0_u8; 32];
+        word[32 - bytes.len()..].copy_from_slice(&bytes);
+        return Some(word);
+    }
+    if value.is_empty() {
+        return None;
+    }
+    let mut word = [0_u8; 32];
+    for digit in value.chars() {
+        let mut carry = digit.to_digit(10)?;
+        for byte in word.iter_mut().rev() {
+            let next = u32::from(*byte) * 10 + carry;
+            *byte = (next & 0xff) as u8;
+            carry = next >> 8;
+        }
+        if carry != 0 {
+            return None;
+        }
+    }
+    Some(word)
+}
+
+fn parse_address(value: &str) -> Option<[u8; 20]> {
+    parse_hex(value).and_then(|bytes| <[u8; 20]>::try_from(bytes).ok())
+}
+
+pub(crate) fn parse_hex(value: &str) -> Option<Vec<u8>> {
+    parse_hex_digits(value.trim().strip_prefix("0x")?)
+}
+
+/// Decodes hex digits, accepting an odd-length quantity (`0x1`).
+fn parse_hex_digits(hex: &str) -> Option<Vec<u8>> {
+    let padded = if hex.len() % 2 == 1 { format!("0{hex}") } else { hex.to_string() };
+    (0..padded.len())
+        .step_by(2)
+        .map(|i| padded.get(i..i + 2).and_then(|pair| u8::from_str_radix(pair, 16).ok()))
+        .collect()
+}
+
+pub(crate) fn address_hex(address: [u8; 20]) -> String {
+    format!("0x{}", hex_encode_bytes(&address))
+}
+
+/// Parses a JSON-RPC hex quantity.
+fn quantity(value: &serde_json::Value) -> Result<u128, RpcError> {
+    value
+        .as_str()
+        .and_then(|text| text.strip_prefix("0x"))
+        .and_then(|hex| u128::from_str_radix(hex, 16).ok())
+        .ok_or_else(|| RpcError::InvalidResponse(format!("expected a hex quantity, got {value}")))
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::expect_used)]
+
+    use std::sync::{Arc, Mutex};
+
+    use ledgerflow_core::{PaymentSubjectKind, PaymentSubjectRef};
+
+    use super::*;
+    use crate::rails::rpc::MockJsonRpcTransport;
+
+    const TOKEN: [u8; 20] = [0xAA; 20];
+    const PAYER: [u8; 20] = [0xBB; 20];
+    const PAYEE: [u8; 20] = [0xCC; 20];
+
+    /// Minimal in-memory node: records raw transactions, mines each one
+    /// into the next block, and emits a `Transfer` log.
+    #[derive(Default)]
+    struct MockNode {
+        head: u128,
+        sent: Vec<String>,
+        mined: BTreeMap<String, u128>,
+        revert: bool,
+        /// Overrides the `Transfer` recipient (a same-amount transfer to
+        /// someone else).
+        log_payee: Option<[u8; 20]>,
+    }
+
+    /// Splits an RLP item into its header and payload lengths.
+    fn rlp_header(item: &[u8]) -> (usize, usize) {
+        let long = |offset: u8| {
+            let len_len = usize::from(item[0] - offset);
+            let len = item[1..=len_len].iter().fold(0, |len, byte| len << 8 | usize::from(*byte));
+            (1 + len_len, len)
+        };
+        match item[0] {
+            0x00..=0x7f => (0, 1),
+            prefix @ 0x80..=0xb7 => (1, usize::from(prefix - 0x80)),
+            0xb8..=0xbf => long(0xb7),
+            prefix @ 0xc0..=0xf7 => (1, usize::from(prefix - 0xc0)),
+            _ => long(0xf7),
+        }
+    }
+
+    /// The encoded items of an RLP list.
+    fn rlp_items(list: &[u8]) -> Vec<&[u8]> {
+        let (header, len) = rlp_header(list);
+        let mut rest = &list[header..header + len];
+        let mut items = Vec::new();
+        while !rest.is_empty() {
+            let (header, len) = rlp_header(rest);
+            let (item, tail) = rest.split_at(header + len);
+            items.push(item);
+            rest = tail;
+        }
+        items
+    }
+
+    /// The payload of an RLP string item.
+    fn rlp_payload(item: &[u8]) -> &[u8] {
+        let (header, len) = rlp_header(item);
+        &item[header..header + len]
+    }
+
+    /// The signed fields of a raw type-2 transaction.
+    fn decode_eip1559(raw: &[u8]) -> Vec<Vec<u8>> {
+        assert_eq!(raw[0], 0x02, "type-2 transaction");
+        rlp_items(&raw[1..]).into_iter().map(<[u8]>::to_vec).collect()
+    }
+
+    fn node_transport(node: Arc<Mutex<MockNode>>, amount: u128) -> MockJsonRpcTransport {
+        MockJsonRpcTransport::new(move |method, params| {
+            let mut node = node.lock().expect("node");
+            let hex = |value: u128| serde_json::json!(format!("0x{value:x}"));
+            match method {
+                "eth_getTransactionCount" => Ok(hex(node.sent.len() as u128)),
+                "eth_estimateGas" => Ok(hex(60_000)),
+                "eth_maxPriorityFeePerGas" => Ok(hex(1_000_000)),
+                "eth_getBlockByNumber" => Ok(serde_json::json!({ "baseFeePerGas": "0x3b9aca00" })),
+                "eth_sendRawTransaction" => {
+                    let raw = params[0].as_str().expect("raw").to_string();
+                    assert!(raw.starts_with("0x02"), "type-2 transaction");
+                    let bytes = parse_hex(&raw).expect("raw hex");
+                    let hash = format!("0x{}", hex_encode_bytes(&keccak256(&bytes)));
+                    node.sent.push(raw);
+                    node.head += 1;
+                    let head = node.head;
+                    node.mined.insert(hash.clone(), head);
+                    Ok(serde_json::json!(hash))
+                }
+                "eth_getTransactionByHash" => {
+                    let hash = params[0].as_str().expect("hash");
+                    Ok(node
+                        .sent
+                        .iter()
+                        .map(|raw| parse_hex(raw).expect("raw hex"))
+                        .find(|raw| format!("0x{}", hex_encode_bytes(&keccak256(raw))) == hash)
+                        .map_or(serde_json::Value::Null, |raw| {
+                            let fields = decode_eip1559(&raw);
+                            serde_json::json!({
+                                "hash": hash,
+                                "to": format!("0x{}", hex_encode_bytes(rlp_payload(&fields[5]))),
+                                "input": format!("0x{}", hex_encode_bytes(rlp_payload(&fields[7]))),
+                            })
+                        }))
+                }
+                "eth_blockNumber" => {
+                    // Every poll observes one more block.
+                    node.head += 1;
+                    Ok(hex(node.head))
+                }
+                "eth_getTransactionReceipt" => {
+                    let hash = params[0].as_str().expect("hash");
+                    Ok(node.mined.get(hash).map_or(serde_json::Value::Null, |block| {
+                        serde_json::json!({
+                            "blockNumber": format!("0x{block:x}"),
+                            "status": if node.revert { "0x0" } else { "0x1" },
+                            "logs": [{
+                                "address": address_hex(TOKEN),
+                                "topics": [
+                                    format!("0x{}", hex_encode_bytes(&keccak256(TRANSFER_EVENT.as_bytes()))),
+                                    format!("0x{}", hex_encode_bytes(&word_from_address(PAYER))),
+                                    format!("0x{}", hex_encode_bytes(&word_from_address(node.log_payee.unwrap_or(PAYEE)))),
+                                ],
+                                "data": format!("0x{}", hex_encode_bytes(&word_from_u128(amount))),
+                            }],
+                        })
+                    }))
+                }
+                other => Err(RpcError::Rpc { code: -32601, message: format!("{other} not found") }),
+            }
+        })
+    }
+
+    fn relayer() -> Secp256k1KeyPair {
+        Secp256k1KeyPair::from_bytes(&[7_u8; 32]).expect("relayer key")
+    }
+
+    fn config() -> EvmRpcConfig {
+        EvmRpcConfig::new(8453)
+            .with_token("USDC", TOKEN)
+            .with_payee("merchant-a", PAYEE)
+            .with_min_confirmations(2)
+            .with_polling(0, 5)
+    }
+
+    fn authorization(amount: u128) -> VerifiedAuthorization {
+        let holder = ledgerflow_core::SignerRef::new(
+            ledgerflow_core::SigningAlgorithm::Ed25519,
+            vec![1; 32],
+        );
+        let warrant = ledgerflow_core::Warrant {
+            version: 1,
+            id: vec![0xAB; 16],
+            holder: holder.clone(),
+            issuer: holder.clone(),
+            issued_at: 1,
+            expires_at: 2,
+            depth: 0,
+            max_depth: 1,
+            parent_hash: None,
+            merchant: ledgerflow_core::MerchantConstraint::with_ids(vec!["merchant-a".to_string()]),
+            resource: ledgerflow_core::ResourceConstraint::default(),
+            payment: ledgerflow_core::PaymentConstraint::new(amount),
+            tool: None,
+            approval_gates: BTreeMap::new(),
+            required_approvers: Vec::new(),
+            min_approvals: 0,
+            extensions: BTreeMap::new(),
+            signature: ledgerflow_core::SignatureEnvelope {
+                alg: ledgerflow_core::SigningAlgorithm::Ed25519,
+                value: vec![0; 64],
+            },
+        };
+        VerifiedAuthorization {
+            merchant_id: "merchant-a".to_string(),
+            tool_name: "web-search".to_string(),
+            payment_subject: PaymentSubjectRef::new(
+                PaymentSubjectKind::Caip10,
+                format!("caip10:eip155:8453:{}", address_hex(PAYER)),
+            ),
+            holder,
+            leaf_warrant: warrant.clone(),
+            root_warrant: warrant,
+            chain_len: 1,
+            amount,
+            asset: "USDC".to_string(),
+            scheme: "exact".to_string(),
+            payee_id: "merchant-a".to_string(),
+            rail: ledgerflow_core::PaymentRail::Onchain,
+            challenge_id: "challenge-1".to_string(),
+            request_hash: "sha256:req".to_string(),
+            accepted_hash: "sha256:acc".to_string(),
+            warrant_digest: "sha256:w".to_string(),
+        }
+    }
+
+    fn eip3009_payload(value: u128, to: [u8; 20]) -> String {
+        serde_json::json!({
+            "signature": format!("0x{}", "11".repeat(64) + "1b"),
+            "authorization": {
+                "from": address_hex(PAYER),
+                "to": address_hex(to),
+                "value": value.to_string(),
+                "validAfter": "0",
+                "validBefore": "99999999999",
+                "nonce": format!("0x{}", "22".repeat(32)),
+            }
+        })
+        .to_string()
+    }
+
+    #[test]
+    fn selectors_match_the_deployed_abis() {
+        assert_eq!(selector(TRANSFER_WITH_AUTHORIZATION), [0xe3, 0xee, 0x16, 0x0e]);
+        assert_eq!(selector(PERMIT_TRANSFER_FROM), [0x30, 0xf2, 0x8b, 0x7a]);
+    }
+
+    #[test]
+    fn rlp_matches_reference_vectors() {
+        assert_eq!(rlp_bytes(b"dog"), vec![0x83, b'd', b'o', b'g']);
+        assert_eq!(
+            rlp_list(&[rlp_bytes(b"cat"), rlp_bytes(b"dog")]),
+            vec![0xc8, 0x83, b'c', b'a', b't', 0x83, b'd', b'o', b'g']
+        );
+        assert_eq!(rlp_uint(0), vec![0x80]);
+        assert_eq!(rlp_uint(15), vec![0x0f]);
+        assert_eq!(rlp_uint(1024), vec![0x82, 0x04, 0x00]);
+        assert_eq!(rlp_list(&[]), vec![0xc0]);
+        let long = rlp_bytes(&[0x61; 56]);
+        assert_eq!(&long[..2], &[0xb8, 56]);
+    }
+
+    #[test]
+    fn parse_word_accepts_decimal_and_hex() {
+        assert_eq!(parse_word("1000"), Some(word_from_u128(1_000)));
+        assert_eq!(parse_word("0x3e8"), Some(word_from_u128(1_000)));
+        let max = "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+        assert_eq!(parse_word(max), Some([0xff; 32]));
+        assert_eq!(parse_word(&format!("{max}0")), None);
+        assert_eq!(parse_word("12a"), None);
+        assert_eq!(parse_word(""), None);
+    }
+
+    #[test]
+    fn eip3009_settlement_submits_and_waits_for_confirmations() {
+        let node = Arc::new(Mutex::new(MockNode::default()));
+        let adapter =
+            EvmRpcRailAdapter::new(node_transport(Arc::clone(&node), 100), relayer(), config());
+
+        let receipt = adapter
+            .settle_with_payload(&authorization(100), &eip3009_payload(100, PAYEE))
+            .expect("settled");
+        assert_eq!(receipt.rail, RailKind::Evm);
+        assert_eq!(receipt.settled_amount, 100);
+        assert!(receipt.transaction_id.starts_with("0x"));
+
+        let raw = parse_hex(&node.lock().expect("node").sent[0]).expect("raw");
+        let calldata_selector = selector(TRANSFER_WITH_AUTHORIZATION);
+        assert!(raw.windows(4).any(|window| window == calldata_selector));
+
+        let verification = adapter.verify(&receipt).expect("verify");
+        assert!(verification.verified);
+        assert!(verification.confirmations >= 2);
+
+        // Another node (or this one after a restart) reads the parties from
+        // the chain and verifies the same settlement.
+        let other =
+            EvmRpcRailAdapter::new(node_transport(Arc::clone(&node), 100), relayer(), config());
+        assert!(other.verify(&receipt).expect("verify").verified);
+    }
+
+    #[test]
+    fn raw_transactions_are_signed_by_the_relayer() {
+        let node = Arc::new(Mutex::new(MockNode::default()));
+        let adapter =
+            EvmRpcRailAdapter::new(node_transport(Arc::clone(&node), 100), relayer(), config());
+        adapter
+            .settle_with_payload(&authorization(100), &eip3009_payload(100, PAYEE))
+            .expect("settled");
+
+        let raw = parse_hex(&node.lock().expect("node").sent[0]).expect("raw");
+        let fields = decode_eip1559(&raw);
+        assert_eq!(fields.len(), 12);
+        assert_eq!(rlp_payload(&fields[0]), rlp_payload(&rlp_uint(8453)), "chain id");
+        assert_eq!(rlp_payload(&fields[5]), TOKEN);
+
+        // Recover the sender from yParity, r, s over the unsigned envelope.
+        let mut preimage = vec![0x02];
+        preimage.extend(rlp_list(&fields[..9]));
+        let mut signature = [0_u8; 65];
+        let r = rlp_payload(&fields[10]);
+        let s = rlp_payload(&fields[11]);
+        signature[32 - r.len()..32].copy_from_slice(r);
+        signature[64 - s.len()..64].copy_from_slice(s);
+        signature[64] = 27 + rlp_payload(&fields[9]).first().copied().unwrap_or(0);
+        let sender = ledgerflow_core::SignerRef::new(
+            ledgerflow_core::SigningAlgorithm::EthTypedData,
+            adapter.relayer_address().to_vec(),
+        );
+        let envelope = ledgerflow_core::SignatureEnvelope {
+            alg: ledgerflow_core::SigningAlgorithm::EthTypedData,
+            value: signature.to_vec(),
+        };
+        assert!(envelope.verify_strict(&sender, &keccak256(&preimage)));
+        assert_eq!(adapter.relayer_address(), relayer().ethereum_address());
+    }
+
+    #[test]
+    fn verification_requires_a_transfer_between_the_settled_parties() {
+        let node =
+            Arc::new(Mutex::new(MockNode { log_payee: Some([0xDD; 20]), ..MockNode::default() }));
+        let adapter =
+            EvmRpcRailAdapter::new(node_transport(Arc::clone(&node), 100), relayer(), config());
+        let receipt = adapter
+            .settle_with_payload(&authorization(100), &eip3009_payload(100, PAYEE))
+            .expect("settled");
+        assert!(!adapter.verify(&receipt).expect("verify").verified);
+    }
+
+    #[test]
+    fn settlement_rejects_mismatched_payloads_before_broadcast() {
+        let node = Arc::new(Mutex::new(MockNode::default()));
+        let adapter =
+            EvmRpcRailAdapter::new(node_transport(Arc::clone(&node), 100), relayer(), config());
+
+        let wrong_amount =
+            adapter.settle_with_payload(&authorization(100), &eip3009_payload(101, PAYEE));
+        assert!(wrong_amount.is_err());
+        let wrong_payee =
+            adapter.settle_with_payload(&authorization(100), &eip3009_payload(100, [0xDD; 20]));
+        assert!(wrong_payee.is_err());
+        let missing_payload = adapter.settle(&authorization(100));
+        assert!(missing_payload.is_err());
+        assert!(node.lock().expect("node").sent.is_empty(), "nothing was broadcast");
+    }
+
+    #[test]
+    fn reverted_transactions_fail_settlement_and_verification() {
+        let node = Arc::new(Mutex::new(MockNode { revert: true, ..MockNode::default() }));
+        let adapter =
+            EvmRpcRailAdapter::new(node_transport(Arc::clone(&node), 100), relayer(), config());
+        let error = adapter
+            .settle_with_payload(&authorization(100), &eip3009_payload(100, PAYEE))
+            .expect_err("reverted");
+        assert!(error.to_string().contains("reverted"));
+
+        let unknown = SettlementReceipt {
+            rail: RailKind::Evm,
+            transaction_id: format!("0x{}", "00".repeat(32)),
+            settled_amount: 100,
+            asset: "USDC".to_string(),
+        };
+        assert_eq!(
+            adapter.verify(&unknown).expect("verify"),
+            VerificationResult { verified: false, confirmations: 0 }
+        );
+    }
+
+    #[test]
+    fn permit2_settlement_requires_the_relayer_as_spender() {
+        let node = Arc::new(Mutex::new(MockNode::default()));
+        let adapter =
+            EvmRpcRailAdapter::new(node_transport(Arc::clone(&node), 100), relayer(), config());
+        let payload = |spender: [u8; 20]| {
+            serde_json::json!({
+                "signature": format!("0x{}", "11".repeat(64) + "1c"),
+                "permit2Authorization": {
+                    "from": address_hex(PAYER),
+                    "permitted": { "token": address_hex(TOKEN), "amount": "100" },
+                    "spender": address_hex(spender),
+                    "nonce": "115792089237316195423570985008687907853269984665640564039457584007913129639935",
+                    "deadline": "99999999999",
+                }
+            })
+            .to_string()
+        };
+
+        assert!(adapter.settle_with_payload(&authorization(100), &payload([0xEE; 20])).is_err());
+        let receipt = adapter
+            .settle_with_payload(&authorization(100), &payload(adapter.relayer_address()))
+            .expect("settled");
+        assert_eq!(receipt.settled_amount, 100);
+        assert!(adapter.verify(&receipt).expect("verify").verified);
+
+        let call = adapter
+            .transfer_call(
+                &authorization(100),
+                &EvmSettlementPayload::parse(&payload(adapter.relayer_address())).expect("parse"),
+                1,
+            )
+            .expect("call");
+        assert_eq!(call.to, PERMIT2_ADDRESS);
+        // selector + 8 head words + length word + 65-byte signature padded to 3 words.
+        assert_eq!(call.data.len(), 4 + 12 * 32);
+    }
+}
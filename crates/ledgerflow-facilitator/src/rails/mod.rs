@@ -1,17 +1,20 @@
 //! Settlement rail adapters for the LedgerFlow Facilitator.
 //!
 //! Each rail adapter implements [`RailAdapter`] with a small trait surface:
-//! quoting, settlement, and receipt verification. Adapters are **demo-grade**
-//! in v1: they return deterministic receipts so the orchestration and
-//! TOCTOU-closing logic can be exercised end-to-end. Real chain integrations
-//! (EVM RPC, Solana, Tempo, Stripe) replace the internals without changing
-//! the trait.
+//! quoting, settlement, and receipt verification. The unit adapters are
+//! **demo-grade**: they return deterministic receipts so the orchestration and
+//! TOCTOU-closing logic can be exercised end-to-end. Chain-backed adapters
+//! ([`evm_rpc`], [`solana_rpc`]) settle the agent-signed rail payload through a JSON-RPC node
+//! ([`rpc`]) without changing the trait.
 
 pub mod custodial;
 pub mod evm;
+pub mod evm_rpc;
 pub mod exchange;
 pub mod gateway;
+pub mod rpc;
 pub mod solana;
+pub mod solana_rpc;
 
 use std::sync::Arc;
 
@@ -64,6 +67,16 @@ pub trait RailAdapter: Send + Sync {
     fn quote(&self, authorization: &VerifiedAuthorization) -> Result<RailQuote, RailError>;
     fn settle(&self, authorization: &VerifiedAuthorization)
     -> Result<SettlementReceipt, RailError>;
+    /// Settles using the agent-signed rail payload (the x402 `payload`, e.g.
+    /// an EIP-3009 authorization). Rails that settle from the authorization
+    /// alone ignore it.
+    fn settle_with_payload(
+        &self,
+        authorization: &VerifiedAuthorization,
+        _settlement_payload: &str,
+    ) -> Result<SettlementReceipt, RailError> {
+        self.settle(authorization)
+    }
     fn verify(&self, receipt: &SettlementReceipt) -> Result<VerificationResult, RailError>;
 }
 
@@ -93,6 +106,14 @@ where
         self.as_ref().settle(authorization)
     }
 
+    fn settle_with_payload(
+        &self,
+        authorization: &VerifiedAuthorization,
+        settlement_payload: &str,
+    ) -> Result<SettlementReceipt, RailError> {
+        self.as_ref().settle_with_payload(authorization, settlement_payload)
+    }
+
     fn verify(&self, receipt: &SettlementReceipt) -> Result<VerificationResult, RailError> {
         self.as_ref().verify(receipt)
     }
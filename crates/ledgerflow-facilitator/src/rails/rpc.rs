@@ -0,0 +1,229 @@
+//! JSON-RPC 2.0 transport seam for chain-backed rail adapters.
+//!
+//! Rail adapters that talk to a node (EVM `eth_*`, Solana) issue calls through
+//! [`JsonRpcTransport`], so the settlement logic can be exercised against an
+//! in-memory node ([`MockJsonRpcTransport`]) in tests. The concrete HTTP
+//! transport ([`HttpJsonRpcTransport`]) is enabled by the `http` feature.
+
+use std::sync::Arc;
+
+use thiserror::Error;
+
+/// JSON-RPC transport failures.
+#[derive(Clone, Debug, Error, Eq, PartialEq)]
+pub enum RpcError {
+    #[error("rpc transport failed: {0}")]
+    Transport(String),
+    #[error("rpc error {code}: {message}")]
+    Rpc { code: i64, message: String },
+    #[error("invalid rpc response: {0}")]
+    InvalidResponse(String),
+}
+
+/// Synchronous JSON-RPC 2.0 call seam (matches the synchronous
+/// [`RailAdapter`](crate::rails::RailAdapter) surface).
+pub trait JsonRpcTransport: Send + Sync {
+    /// Calls `method` with positional `params` and returns the `result`.
+    fn call(&self, method: &str, params: serde_json::Value) -> Result<serde_json::Value, RpcError>;
+}
+
+/// Shared transport handle.
+pub type SharedJsonRpcTransport = Arc<dyn JsonRpcTransport>;
+
+impl<T> JsonRpcTransport for Arc<T>
+where
+    T: JsonRpcTransport + ?Sized,
+{
+    fn call(&self, method: &str, params: serde_json::Value) -> Result<serde_json::Value, RpcError> {
+        self.as_ref().call(method, params)
+    }
+}
+
+/// Handler closure signature for [`MockJsonRpcTransport`].
+type RpcHandler =
+    Box<dyn Fn(&str, serde_json::Value) -> Result<serde_json::Value, RpcError> + Send + Sync>;
+
+/// In-memory transport for tests: every call is answered by a handler closure.
+pub struct MockJsonRpcTransport {
+    handler: RpcHandler,
+}
+
+impl std::fmt::Debug for MockJsonRpcTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("MockJsonRpcTransport").finish_non_exhaustive()
+    }
+}
+
+impl MockJsonRpcTransport {
+    /// Creates a mock transport with a handler closure.
+    #[must_use]
+    pub fn new(
+        handler: impl Fn(&str, serde_json::Value) -> Result<serde_json::Value, RpcError>
+        + Send
+        + Sync
+        + 'static,
+    ) -> Self {
+        Self { handler: Box::new(handler) }
+    }
+}
+
+impl JsonRpcTransport for MockJsonRpcTransport {
+    fn call(&self, method: &str, params: serde_json::Value) -> Result<serde_json::Value, RpcError> {
+        (self.handler)(method, params)
+    }
+}
+
+/// HTTP JSON-RPC transport (feature `http`; uses hpx).
+///
+/// Each call runs on a short-lived helper thread that drives a process-wide
+/// current-thread tokio runtime, so the synchronous seam stays safe to call
+/// from inside an async server handler. Calls block the calling thread until
+/// the node answers; async callers should issue them from
+/// `tokio::task::spawn_blocking`. One HTTP client (and its connection pool)
+/// is shared by every call through the transport.
+#[cfg(feature = "http")]
+#[derive(Clone, Debug)]
+pub struct HttpJsonRpcTransport {
+    url: String,
+    timeout_ms: u64,
+    client: hpx::Client,
+}
+
+#[cfg(feature = "http")]
+impl HttpJsonRpcTransport {
+    /// Creates a transport for the node at `url`.
+    #[must_use]
+    pub fn new(url: impl Into<String>, timeout_ms: u64) -> Self {
+        Self { url: url.into(), timeout_ms, client: hpx::Client::new() }
+    }
+}
+
+/// Process-wide runtime bridging the synchronous seam to hpx.
+#[cfg(feature = "http")]
+static RPC_HTTP_RUNTIME: std::sync::OnceLock<tokio::runtime::Runtime> = std::sync::OnceLock::new();
+
+#[cfg(feature = "http")]
+fn rpc_runtime() -> Result<&'static tokio::runtime::Runtime, RpcError> {
+    if let Some(runtime) = RPC_HTTP_RUNTIME.get() {
+        return Ok(runtime);
+    }
+    let runtime = tokio::runtime::Builder::new_current_thread()
+        .enable_all()
+        .build()
+        .map_err(|error| RpcError::Transport(format!("failed to build rpc runtime: {error}")))?;
+    let _ = RPC_HTTP_RUNTIME.set(runtime);
+    RPC_HTTP_RUNTIME.get().ok_or_else(|| RpcError::Transport("rpc runtime unavailable".to_string()))
+}
+
+#[cfg(feature = "http")]
+impl JsonRpcTransport for HttpJsonRpcTransport {
+    fn call(&self, method: &str, params: serde_json::Value) -> Result<serde_json::Value, RpcError> {
+        let body =
+            serde_json::json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params });
+        let url = self.url.as_str();
+        let timeout = std::time::Duration::from_millis(self.timeout_ms);
+        let client = &self.client;
+        let request = async move {
+            let response = client
+                .post(url)
+                .header("content-type", "application/json")
+                .body(body.to_string())
+                .send()
+                .await
+                .map_err(|error| {
+                    RpcError::Transport(format!("request to {url} failed: {error}"))
+                })?;
+            if !response.status().is_success() {
+                return Err(RpcError::Transport(format!(
+                    "{url} returned HTTP {}",
+                    response.status()
+                )));
+            }
+            let value: serde_json::Value = response
+                .json()
+                .await
+                .map_err(|error| RpcError::InvalidResponse(error.to_string()))?;
+            parse_response(value)
+        };
+        block_on_http(url, timeout, request)
+    }
+}
+
+/// Drives `request` to completion on the rpc runtime from a helper thread,
+/// bounded by `timeout`.
+#[cfg(feature = "http")]
+pub(crate) fn block_on_http<T: Send>(
+    url: &str,
+    timeout: std::time::Duration,
+    request: impl std::future::Future<Output = Result<T, RpcError>> + Send,
+) -> Result<T, RpcError> {
+    let runtime = rpc_runtime()?;
+    std::thread::scope(|scope| {
+        scope
+            .spawn(|| {
+                runtime.block_on(async {
+                    tokio::time::timeout(timeout, request).await.map_err(|_| {
+                        RpcError::Transport(format!(
+                            "request to {url} timed out after {} ms",
+                            timeout.as_millis()
+                        ))
+                    })?
+                })
+            })
+            .join()
+            .map_err(|_| RpcError::Transport("rpc worker thread panicked".to_string()))?
+    })
+}
+
+/// Extracts `result` from a JSON-RPC 2.0 response object.
+pub fn parse_response(value: serde_json::Value) -> Result<serde_json::Value, RpcError> {
+    if let Some(error) = value.get("error").filter(|error| !error.is_null()) {
+        return Err(RpcError::Rpc {
+            code: error.get("code").and_then(serde_json::Value::as_i64).unwrap_or_default(),
+            message: error
+                .get("message")
+                .and_then(serde_json::Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+        });
+    }
+    value
+        .get("result")
+        .cloned()
+        .ok_or_else(|| RpcError::InvalidResponse("neither result nor error".to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::expect_used)]
+
+    use super::*;
+
+    #[test]
+    fn parse_response_extracts_result_or_error() {
+        let ok = parse_response(serde_json::json!({ "jsonrpc": "2.0", "id": 1, "result": "0x1" }));
+        assert_eq!(ok.expect("result"), serde_json::json!("0x1"));
+
+        let null_result =
+            parse_response(serde_json::json!({ "jsonrpc": "2.0", "id": 1, "result": null }));
+        assert_eq!(null_result.expect("null result"), serde_json::Value::Null);
+
+        let error = parse_response(serde_json::json!({
+            "jsonrpc": "2.0", "id": 1, "error": { "code": -32000, "message": "nonce too low" }
+        }))
+        .expect_err("rpc error");
+        assert_eq!(error, RpcError::Rpc { code: -32000, message: "nonce too low".to_string() });
+
+        assert!(matches!(
+            parse_response(serde_json::json!({ "jsonrpc": "2.0", "id": 1 })),
+            Err(RpcError::InvalidResponse(_))
+        ));
+    }
+
+    #[test]
+    fn mock_transport_dispatches_to_handler() {
+        let transport: SharedJsonRpcTransport =
+            Arc::new(MockJsonRpcTransport::new(|method, _| Ok(serde_json::json!(method))));
+        assert_eq!(transport.call("eth_chainId", serde_json::json!([])), Ok("eth_chainId".into()));
+    }
+}
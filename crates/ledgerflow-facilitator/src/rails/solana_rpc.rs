@@ -0,0 +1,1076 @@
+//! Solana onchain settlement over JSON-RPC (SPL Token / Token-2022).
+//!
+//! The agent builds and partially signs an SPL `TransferChecked` transaction
+//! with the facilitator as fee payer and ships it as the x402 `payload`
+//! (`{"transaction": "<base64>"}`, the `exact` SVM scheme shape). Before
+//! co-signing, the adapter decodes the transaction and fails closed unless:
+//!
+//! - the fee payer (account 0) is this facilitator and appears in no instruction, so the agent
+//!   cannot spend facilitator funds;
+//! - the only instructions are compute-budget limits (with a capped priority fee) and exactly one
+//!   `TransferChecked` on the asset's token program;
+//! - mint, decimals, amount, and destination ATA (derived from the payee wallet) match the verified
+//!   authorization, and the transfer authority is the CAIP-10 payment subject with a valid
+//!   signature.
+//!
+//! The facilitator then co-signs as fee payer, submits with `sendTransaction`,
+//! and polls `getSignatureStatuses` up to the configured commitment.
+//! [`RailAdapter::verify`] reports the live confirmation status, and only
+//! verifies a receipt whose transaction (fetched with `getTransaction`) this
+//! facilitator sponsored and which carries a `TransferChecked` of the
+//! receipt's mint and amount into a configured payee's associated token
+//! account.
+
+use std::collections::BTreeMap;
+
+use ledgerflow_core::{
+    SignatureEnvelope, SignerRef, SigningAlgorithm, SigningKeyPair, VerifiedAuthorization,
+};
+use sha2::{Digest as _, Sha256};
+
+use crate::{
+    rails::{
+        RailAdapter, RailError, RailQuote, SettlementReceipt, VerificationResult,
+        rpc::{JsonRpcTransport, RpcError},
+    },
+    routing::RailKind,
+    subject::ResolvedSubject,
+};
+
+/// SPL Token program.
+pub const TOKEN_PROGRAM_ID: &str = "TokenkegQfeZyiNwAJbNbGKPFXCWuBvf9Ss623VQ5DA";
+/// SPL Token-2022 program.
+pub const TOKEN_2022_PROGRAM_ID: &str = "TokenzQdBNbLqP5VEhdkAS6EPFLC1PHnBqCXEpPxuEb";
+/// Associated Token Account program.
+pub const ASSOCIATED_TOKEN_PROGRAM_ID: &str = "ATokenGPvbdGVxr1b2hvZbsiqW5xWH25efTNsLJA8knL";
+/// Compute Budget program.
+pub const COMPUTE_BUDGET_PROGRAM_ID: &str = "ComputeBudget111111111111111111111111111111";
+
+/// Confirmation count reported for finalized signatures (the RPC returns
+/// `null` once a slot is rooted; 32 is the maximum vote lockout depth).
+const FINALIZED_CONFIRMATIONS: u32 = 32;
+
+/// SPL Token `TransferChecked` instruction discriminator.
+const TRANSFER_CHECKED: u8 = 12;
+/// Compute Budget `SetComputeUnitLimit` / `SetComputeUnitPrice`.
+const SET_COMPUTE_UNIT_LIMIT: u8 = 2;
+const SET_COMPUTE_UNIT_PRICE: u8 = 3;
+
+// ---------------------------------------------------------------------------
+// Configuration
+// ---------------------------------------------------------------------------
+
+/// Commitment level a settlement must reach.
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum SolanaCommitment {
+    Processed,
+    Confirmed,
+    Finalized,
+}
+
+impl SolanaCommitment {
+    /// JSON-RPC commitment name.
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Processed => "processed",
+            Self::Confirmed => "confirmed",
+            Self::Finalized => "finalized",
+        }
+    }
+
+    fn parse(value: &str) -> Option<Self> {
+        match value {
+            "processed" => Some(Self::Processed),
+            "confirmed" => Some(Self::Confirmed),
+            "finalized" => Some(Self::Finalized),
+            _ => None,
+        }
+    }
+}
+
+/// An SPL mint accepted for settlement.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub struct SplMint {
+    pub mint: [u8; 32],
+    /// Owning token program (SPL Token or Token-2022).
+    pub token_program: [u8; 32],
+    pub decimals: u8,
+}
+
+/// Cluster configuration for [`SolanaRpcRailAdapter`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SolanaRpcConfig {
+    /// CAIP-2 reference of the cluster; payment subjects on other clusters
+    /// are not supported.
+    pub cluster: String,
+    /// Asset symbol (as quoted, e.g. `USDC`) → mint.
+    pub mints: BTreeMap<String, SplMint>,
+    /// Payee id (as quoted) → owner wallet; the destination must be the
+    /// owner's associated token account. Base58 payee ids need no entry to
+    /// settle, but [`RailAdapter::verify`] only recognizes transfers into
+    /// the accounts of payees listed here.
+    pub payees: BTreeMap<String, [u8; 32]>,
+    /// Highest `SetComputeUnitPrice` (micro-lamports per CU) the fee payer
+    /// will sponsor.
+    pub max_compute_unit_price: u64,
+    /// Commitment required before a settlement is reported.
+    pub commitment: SolanaCommitment,
+    /// Delay between status polls.
+    pub poll_interval_ms: u64,
+    /// Status polls before settlement gives up.
+    pub max_polls: u32,
+}
+
+impl SolanaRpcConfig {
+    /// Creates a configuration for `cluster` requiring `confirmed`
+    /// commitment.
+    #[must_use]
+    pub fn new(cluster: impl Into<String>) -> Self {
+        Self {
+            cluster: cluster.into(),
+            mints: BTreeMap::new(),
+            payees: BTreeMap::new(),
+            max_compute_unit_price: 5_000_000,
+            commitment: SolanaCommitment::Confirmed,
+            poll_interval_ms: 500,
+            max_polls: 60,
+        }
+    }
+
+    /// Accepts `asset` as `mint`.
+    #[must_use]
+    pub fn with_mint(mut self, asset: impl Into<String>, mint: SplMint) -> Self {
+        self.mints.insert(asset.into(), mint);
+        self
+    }
+
+    /// Maps a payee id to its owner wallet.
+    #[must_use]
+    pub fn with_payee(mut self, payee_id: impl Into<String>, owner: [u8; 32]) -> Self {
+        self.payees.insert(payee_id.into(), owner);
+        self
+    }
+
+    /// Sets the commitment required before reporting settlement.
+    #[must_use]
+    pub const fn with_commitment(mut self, commitment: SolanaCommitment) -> Self {
+        self.commitment = commitment;
+        self
+    }
+
+    /// Sets the status polling schedule.
+    #[must_use]
+    pub const fn with_polling(mut self, interval_ms: u64, max_polls: u32) -> Self {
+        self.poll_interval_ms = interval_ms;
+        self.max_polls = max_polls;
+        self
+    }
+
+    fn mint(&self, asset: &str) -> Result<SplMint, RailError> {
+        self.mints
+            .get(asset)
+            .copied()
+            .ok_or_else(|| RailError::SettlementFailed(format!("no SPL mint for `{asset}`")))
+    }
+
+    fn payee(&self, payee_id: &str) -> Result<[u8; 32], RailError> {
+        self.payees
+            .get(payee_id)
+            .copied()
+            .or_else(|| decode_pubkey(payee_id))
+            .ok_or_else(|| RailError::SettlementFailed(format!("no wallet for payee `{payee_id}`")))
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Transaction wire format
+// ---------------------------------------------------------------------------
+
+/// A decoded (legacy or v0) Solana transaction.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SolanaTransaction {
+    pub signatures: Vec<[u8; 64]>,
+    /// Serialized message (the bytes every signature covers).
+    pub message: Vec<u8>,
+    pub num_required_signatures: u8,
+    pub account_keys: Vec<[u8; 32]>,
+    pub instructions: Vec<CompiledInstruction>,
+    /// Whether the v0 message loads accounts from lookup tables.
+    pub uses_lookup_tables: bool,
+}
+
+/// A compiled instruction (indices into `account_keys`).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CompiledInstruction {
+    pub program_id_index: u8,
+    pub accounts: Vec<u8>,
+    pub data: Vec<u8>,
+}
+
+impl SolanaTransaction {
+    /// Decodes a wire-format transaction.
+    pub fn decode(bytes: &[u8]) -> Result<Self, RailError> {
+        let mut reader = Reader { bytes, offset: 0 };
+        let signature_count = reader.compact_u16()?;
+        let signatures =
+            (0..signature_count).map(|_| reader.array::<64>()).collect::<Result<Vec<_>, _>>()?;
+        let message = reader.rest().to_vec();
+
+        let mut reader = Reader { bytes: &message, offset: 0 };
+        let first = reader.byte()?;
+        let (versioned, num_required_signatures) = if first & 0x80 == 0 {
+            (false, first)
+        } else if first == 0x80 {
+            (true, reader.byte()?)
+        } else {
+            return Err(malformed("unsupported message version"));
+        };
+        let _readonly_signed = reader.byte()?;
+        let _readonly_unsigned = reader.byte()?;
+        let key_count = reader.compact_u16()?;
+        let account_keys =
+            (0..key_count).map(|_| reader.array::<32>()).collect::<Result<Vec<_>, _>>()?;
+        let _recent_blockhash = reader.array::<32>()?;
+        let instruction_count = reader.compact_u16()?;
+        let instructions = (0..instruction_count)
+            .map(|_| {
+                let program_id_index = reader.byte()?;
+                let account_count = reader.compact_u16()?;
+                let accounts = reader.take(usize::from(account_count))?.to_vec();
+                let data_len = reader.compact_u16()?;
+                let data = reader.take(usize::from(data_len))?.to_vec();
+                Ok(CompiledInstruction { program_id_index, accounts, data })
+            })
+            .collect::<Result<Vec<_>, RailError>>()?;
+        let uses_lookup_tables = versioned && reader.compact_u16()? > 0;
+
+        if signatures.len() != usize::from(num_required_signatures) {
+            return Err(malformed("signature count does not match the message header"));
+        }
+        Ok(Self {
+            signatures,
+            message,
+            num_required_signatures,
+            account_keys,
+            instructions,
+            uses_lookup_tables,
+        })
+    }
+
+    /// Serializes the transaction back to wire format.
+    #[must_use]
+    pub fn encode(&self) -> Vec<u8> {
+        let mut out = compact_u16(self.signatures.len() as u16);
+        for signature in &self.signatures {
+            out.extend_from_slice(signature);
+        }
+        out.extend_from_slice(&self.message);
+        out
+    }
+
+    fn key(&self, index: u8) -> Result<[u8; 32], RailError> {
+        self.account_keys
+            .get(usize::from(index))
+            .copied()
+            .ok_or_else(|| malformed("account index out of range"))
+    }
+}
+
+/// Cursor over wire bytes.
+struct Reader<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], RailError> {
+        let end = self.offset.checked_add(len).ok_or_else(|| malformed("length overflow"))?;
+        let slice = self.bytes.get(self.offset..end).ok_or_else(|| malformed("truncated"))?;
+        self.offset = end;
+        Ok(slice)
+    }
+
+    fn byte(&mut self) -> Result<u8, RailError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N], RailError> {
+        self.take(N).and_then(|slice| <[u8; N]>::try_from(slice).map_err(|_| malformed("short")))
+    }
+
+    fn compact_u16(&mut self) -> Result<u16, RailError> {
+        let mut value = 0_u32;
+        for shift in [0, 7, 14] {
+            let byte = self.byte()?;
+            value |= u32::from(byte & 0x7f) << shift;
+            if byte & 0x80 == 0 {
+                return u16::try_from(value).map_err(|_| malformed("compact-u16 overflow"));
+            }
+        }
+        Err(malformed("compact-u16 overflow"))
+    }
+
+    fn rest(&self) -> &'a [u8] {
+        &self.bytes[self.offset..]
+    }
+}
+
+fn compact_u16(mut value: u16) -> Vec<u8> {
+    let mut out = Vec::with_capacity(3);
+    loop {
+        let byte = (value & 0x7f) as u8;
+        value >>= 7;
+        if value == 0 {
+            out.push(byte);
+            return out;
+        }
+        out.push(byte | 0x80);
+    }
+}
+
+fn malformed(reason: &str) -> RailError {
+    RailError::SettlementFailed(format!("malformed Solana transaction: {reason}"))
+}
+
+// ---------------------------------------------------------------------------
+// Addresses
+// ---------------------------------------------------------------------------
+
+fn decode_pubkey(value: &str) -> Option<[u8; 32]> {
+    bs58::decode(value).into_vec().ok().and_then(|bytes| <[u8; 32]>::try_from(bytes).ok())
+}
+
+fn program_id(value: &str) -> [u8; 32] {
+    decode_pubkey(value).unwrap_or_default()
+}
+
+/// Derives a program address (`find_program_address`): the first bump from
+/// 255 down whose hash is off the ed25519 curve.
+fn find_program_address(seeds: &[&[u8]], program: &[u8; 32]) -> Option<[u8; 32]> {
+    (0..=u8::MAX).rev().find_map(|bump| {
+        let mut hasher = Sha256::new();
+        for seed in seeds {
+            hasher.update(seed);
+        }
+        hasher.update([bump]);
+        hasher.update(program);
+        hasher.update(b"ProgramDerivedAddress");
+        let candidate: [u8; 32] = hasher.finalize().into();
+        ed25519_dalek::VerifyingKey::from_bytes(&candidate).is_err().then_some(candidate)
+    })
+}
+
+/// The associated token account of `owner` for `mint` under `token_program`.
+#[must_use]
+pub fn associated_token_address(
+    owner: &[u8; 32],
+    mint: &[u8; 32],
+    token_program: &[u8; 32],
+) -> Option<[u8; 32]> {
+    find_program_address(&[owner, token_program, mint], &program_id(ASSOCIATED_TOKEN_PROGRAM_ID))
+}
+
+/// Extracts the payer wallet from a `caip10:solana:<cluster>:<address>`
+/// subject, requiring the configured cluster.
+fn payer_pubkey(subject: &str, cluster: &str) -> Result<[u8; 32], RailError> {
+    let rest = subject.strip_prefix("caip10:solana:").ok_or(RailError::Unsupported)?;
+    let (reference, address) = rest.split_once(':').ok_or(RailError::Unsupported)?;
+    if reference != cluster {
+        return Err(RailError::Unsupported);
+    }
+    decode_pubkey(address).ok_or(RailError::Unsupported)
+}
+
+// ---------------------------------------------------------------------------
+// Adapter
+// ---------------------------------------------------------------------------
+
+/// Solana rail adapter co-signing agent SPL transfers as fee payer and
+/// submitting them through a JSON-RPC node.
+pub struct SolanaRpcRailAdapter<T> {
+    transport: T,
+    fee_payer: SigningKeyPair,
+    config: SolanaRpcConfig,
+}
+
+impl<T> std::fmt::Debug for SolanaRpcRailAdapter<T> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SolanaRpcRailAdapter")
+            .field("fee_payer", &bs58::encode(self.fee_payer.public_key_bytes()).into_string())
+            .field("config", &self.config)
+            .finish_non_exhaustive()
+    }
+}
+
+impl<T> SolanaRpcRailAdapter<T>
+where
+    T: JsonRpcTransport,
+{
+    /// Creates an adapter submitting through `transport` with fees paid by
+    /// `fee_payer`.
+    #[must_use]
+    pub const fn new(transport: T, fee_payer: SigningKeyPair, config: SolanaRpcConfig) -> Self {
+        Self { transport, fee_payer, config }
+    }
+
+    /// The fee payer the agent must name as account 0.
+    #[must_use]
+    pub fn fee_payer(&self) -> [u8; 32] {
+        self.fee_payer.public_key_bytes()
+    }
+
+    /// Checks the decoded transaction against the verified authorization
+    /// (no RPC traffic).
+    fn check_transfer(
+        &self,
+        authorization: &VerifiedAuthorization,
+        transaction: &SolanaTransaction,
+    ) -> Result<(), RailError> {
+        let reject = |reason: &str| RailError::SettlementFailed(reason.to_string());
+        let payer = payer_pubkey(&authorization.payment_subject.value, &self.config.cluster)?;
+        let mint = self.config.mint(&authorization.asset)?;
+        let owner = self.config.payee(&authorization.payee_id)?;
+        let destination = associated_token_address(&owner, &mint.mint, &mint.token_program)
+            .ok_or_else(|| reject("cannot derive the payee token account"))?;
+        let fee_payer = self.fee_payer();
+
+        if transaction.uses_lookup_tables {
+            return Err(reject("address lookup tables are not accepted"));
+        }
+        if transaction.account_keys.first() != Some(&fee_payer) {
+            return Err(reject("the fee payer is not this facilitator"));
+        }
+
+        let compute_budget = program_id(COMPUTE_BUDGET_PROGRAM_ID);
+        let mut transfer = None;
+        for instruction in &transaction.instructions {
+            if instruction.accounts.contains(&0) {
+                return Err(reject("the fee payer may not appear in an instruction"));
+            }
+            let program = transaction.key(instruction.program_id_index)?;
+            if program == compute_budget {
+                match instruction.data.split_first() {
+                    Some((&SET_COMPUTE_UNIT_LIMIT, rest)) if rest.len() == 4 => {}
+                    Some((&SET_COMPUTE_UNIT_PRICE, rest)) => {
+                        let price = <[u8; 8]>::try_from(rest)
+                            .map(u64::from_le_bytes)
+                            .map_err(|_| malformed("compute unit price"))?;
+                        if price > self.config.max_compute_unit_price {
+                            return Err(reject("compute unit price exceeds the sponsor cap"));
+                        }
+                    }
+                    _ => return Err(reject("unsupported compute budget instruction")),
+                }
+            } else if program == mint.token_program && transfer.is_none() {
+                transfer = Some(instruction);
+            } else {
+                return Err(reject("unexpected instruction in settlement transaction"));
+            }
+        }
+
+        let transfer = transfer.ok_or_else(|| reject("no TransferChecked instruction"))?;
+        let [source, mint_index, destination_index, authority] = transfer.accounts[..] else {
+            return Err(reject("TransferChecked expects four accounts"));
+        };
+        let _source = transaction.key(source)?;
+        let (&discriminator, rest) =
+            transfer.data.split_first().ok_or_else(|| malformed("empty instruction"))?;
+        if discriminator != TRANSFER_CHECKED || rest.len() != 9 {
+            return Err(reject("the token instruction is not TransferChecked"));
+        }
+        let amount = u64::from_le_bytes(
+            <[u8; 8]>::try_from(&rest[..8]).map_err(|_| malformed("transfer amount"))?,
+        );
+        if transaction.key(mint_index)? != mint.mint || rest[8] != mint.decimals {
+            return Err(reject("the transfer mint does not match the asset"));
+        }
+        if u128::from(amount) != authorization.amount {
+            return Err(reject("the transfer amount does not match the amount"));
+        }
+        if transaction.key(destination_index)? != destination {
+            return Err(reject("the destination is not the payee token account"));
+        }
+        if transaction.key(authority)? != payer {
+            return Err(reject("the transfer authority is not the payment subject"));
+        }
+
+        // The authority must have signed this exact message.
+        let signature = transaction
+            .signatures
+            .get(usize::from(authority))
+            .filter(|_| authority < transaction.num_required_signatures)
+            .ok_or_else(|| reject("the transfer authority did not sign"))?;
+        let envelope =
+            SignatureEnvelope { alg: SigningAlgorithm::Ed25519, value: signature.to_vec() };
+        if !envelope.verify_strict(
+            &SignerRef::new(SigningAlgorithm::Ed25519, payer.to_vec()),
+            &transaction.message,
+        ) {
+            return Err(reject("invalid transfer authority signature"));
+        }
+        Ok(())
+    }
+
+    /// Reads the status of `signature`: `None` while unknown to the cluster.
+    fn signature_status(&self, signature: &str) -> Result<Option<SignatureStatus>, RpcError> {
+        let result = self.transport.call(
+            "getSignatureStatuses",
+            serde_json::json!([[signature], { "searchTransactionHistory": true }]),
+        )?;
+        let status = result
+            .get("value")
+            .and_then(|value| value.get(0))
+            .ok_or_else(|| RpcError::InvalidResponse("missing status value".to_string()))?;
+        if status.is_null() {
+            return Ok(None);
+        }
+        let commitment = status
+            .get("confirmationStatus")
+            .and_then(serde_json::Value::as_str)
+            .and_then(SolanaCommitment::parse)
+            .unwrap_or(SolanaCommitment::Processed);
+        let confirmations = if commitment == SolanaCommitment::Finalized {
+            FINALIZED_CONFIRMATIONS
+        } else {
+            status
+                .get("confirmations")
+                .and_then(serde_json::Value::as_u64)
+                .map_or(0, |count| u32::try_from(count).unwrap_or(u32::MAX))
+        };
+        let failed = status.get("err").is_some_and(|error| !error.is_null());
+        Ok(Some(SignatureStatus { commitment, confirmations, failed }))
+    }
+
+    /// Fetches and decodes the transaction `signature` at (at least)
+    /// `confirmed` commitment: `None` while the cluster does not return it.
+    fn transaction(&self, signature: &str) -> Result<Option<SolanaTransaction>, RpcError> {
+        let commitment = self.config.commitment.max(SolanaCommitment::Confirmed);
+        let result = self.transport.call(
+            "getTransaction",
+            serde_json::json!([signature, {
+                "encoding": "base64",
+                "commitment": commitment.as_str(),
+                "maxSupportedTransactionVersion": 0,
+            }]),
+        )?;
+        if result.is_null() {
+            return Ok(None);
+        }
+        let invalid = |reason: String| RpcError::InvalidResponse(reason);
+        let encoded = result
+            .get("transaction")
+            .and_then(|transaction| transaction.get(0))
+            .and_then(serde_json::Value::as_str)
+            .ok_or_else(|| invalid("missing base64 transaction".to_string()))?;
+        decode_payload(encoded)
+            .and_then(|bytes| SolanaTransaction::decode(&bytes))
+            .map(Some)
+            .map_err(|error| invalid(error.to_string()))
+    }
+
+    /// Whether `transaction` is the settlement `receipt` records: sponsored
+    /// by this facilitator and carrying a `TransferChecked` of the receipt's
+    /// mint and amount into a configured payee's associated token account.
+    fn is_settlement_transfer(
+        &self,
+        transaction: &SolanaTransaction,
+        receipt: &SettlementReceipt,
+        mint: SplMint,
+    ) -> bool {
+        let Ok(amount) = u64::try_from(receipt.settled_amount) else {
+            return false;
+        };
+        // The fee-payer signature is the transaction id.
+        let transaction_id =
+            transaction.signatures.first().map(|signature| bs58::encode(signature).into_string());
+        if transaction.uses_lookup_tables ||
+            transaction.account_keys.first() != Some(&self.fee_payer()) ||
+            transaction_id.as_deref() != Some(receipt.transaction_id.as_str())
+        {
+            return false;
+        }
+        let destinations: Vec<[u8; 32]> = self
+            .config
+            .payees
+            .values()
+            .filter_map(|owner| associated_token_address(owner, &mint.mint, &mint.token_program))
+            .collect();
+        let mut data = vec![TRANSFER_CHECKED];
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.push(mint.decimals);
+        transaction.instructions.iter().any(|instruction| {
+            let [_source, mint_index, destination, _authority] = instruction.accounts[..] else {
+                return false;
+            };
+            transaction.key(instruction.program_id_index).ok() == Some(mint.token_program) &&
+                instruction.data == data &&
+                transaction.key(mint_index).ok() == Some(mint.mint) &&
+                transaction.key(destination).is_ok_and(|key| destinations.contains(&key))
+        })
+    }
+}
+
+/// Observed status of a submitted signature.
+struct SignatureStatus {
+    commitment: SolanaCommitment,
+    confirmations: u32,
+    failed: bool,
+}
+
+impl<T> RailAdapter for SolanaRpcRailAdapter<T>
+where
+    T: JsonRpcTransport,
+{
+    fn kind(&self) -> RailKind {
+        RailKind::Solana
+    }
+
+    fn supports(&self, subject: &ResolvedSubject) -> bool {
+        matches!(subject.rail, RailKind::Solana) &&
+            payer_pubkey(&subject.value, &self.config.cluster).is_ok()
+    }
+
+    fn quote(&self, authorization: &VerifiedAuthorization) -> Result<RailQuote, RailError> {
+        Ok(RailQuote {
+            rail: RailKind::Solana,
+            // The facilitator sponsors the network fee.
+            estimated_fee: 0,
+            estimated_time_ms: match self.config.commitment {
+                SolanaCommitment::Processed => 400,
+                SolanaCommitment::Confirmed => 1_000,
+                SolanaCommitment::Finalized => 13_000,
+            },
+            asset: authorization.asset.clone(),
+        })
+    }
+
+    fn settle(
+        &self,
+        _authorization: &VerifiedAuthorization,
+    ) -> Result<SettlementReceipt, RailError> {
+        Err(RailError::SettlementFailed(
+            "Solana settlement requires the agent-signed transfer transaction".to_string(),
+        ))
+    }
+
+    fn settle_with_payload(
+        &self,
+        authorization: &VerifiedAuthorization,
+        settlement_payload: &str,
+    ) -> Result<SettlementReceipt, RailError> {
+        let bytes = decode_payload(settlement_payload)?;
+        let mut transaction = SolanaTransaction::decode(&bytes)?;
+        self.check_transfer(authorization, &transaction)?;
+
+        // Co-sign as fee payer (signature slot 0); that signature is the
+        // transaction id.
+        let signature = self.fee_payer.sign(&transaction.message).value;
+        let fee_payer_signature =
+            <[u8; 64]>::try_from(signature).map_err(|_| malformed("fee payer signature"))?;
+        transaction.signatures[0] = fee_payer_signature;
+        let transaction_id = bs58::encode(fee_payer_signature).into_string();
+
+        let failed = |error: RpcError| RailError::SettlementFailed(error.to_string());
+        let submitted = self
+            .transport
+            .call(
+                "sendTransaction",
+                serde_json::json!([base64_encode(&transaction.encode()), {
+                    "encoding": "base64",
+                    "preflightCommitment": self.config.commitment.as_str(),
+                }]),
+            )
+            .map_err(failed)?;
+        if submitted.as_str() != Some(transaction_id.as_str()) {
+            return Err(RailError::SettlementFailed(format!(
+                "node returned an unexpected signature for {transaction_id}"
+            )));
+        }
+
+        for attempt in 0..self.config.max_polls {
+            if attempt > 0 {
+                std::thread::sleep(std::time::Duration::from_millis(self.config.poll_interval_ms));
+            }
+            match self.signature_status(&transaction_id).map_err(failed)? {
+                Some(status) if status.failed => {
+                    return Err(RailError::SettlementFailed(format!(
+                        "transaction {transaction_id} failed"
+                    )));
+                }
+                Some(status) if status.commitment >= self.config.commitment => {
+                    return Ok(SettlementReceipt {
+                        rail: RailKind::Solana,
+                        transaction_id,
+                        settled_amount: authorization.amount,
+                        asset: authorization.asset.clone(),
+                    });
+                }
+                _ => {}
+            }
+        }
+        // The transaction may still land; surface the id for reconciliation.
+        Err(RailError::SettlementFailed(format!(
+            "transaction {transaction_id} did not reach `{}` after {} polls",
+            self.config.commitment.as_str(),
+            self.config.max_polls
+        )))
+    }
+
+    fn verify(&self, receipt: &SettlementReceipt) -> Result<VerificationResult, RailError> {
+        let failed = |error: RpcError| RailError::VerificationFailed(error.to_string());
+        let Some(status) = self.signature_status(&receipt.transaction_id).map_err(failed)? else {
+            return Ok(VerificationResult { verified: false, confirmations: 0 });
+        };
+        let mint = self
+            .config
+            .mint(&receipt.asset)
+            .map_err(|error| RailError::VerificationFailed(error.to_string()))?;
+        // A successful signature proves nothing by itself; the transfer is
+        // checked against the transaction the cluster actually recorded.
+        let transferred = self
+            .transaction(&receipt.transaction_id)
+            .map_err(failed)?
+            .is_some_and(|transaction| self.is_settlement_transfer(&transaction, receipt, mint));
+        Ok(VerificationResult {
+            verified: !status.failed &&
+                transferred &&
+                status.commitment >= self.config.commitment,
+            confirmations: status.confirmations,
+        })
+    }
+}
+
+/// Accepts `{"transaction": "<base64>"}` or a bare base64 transaction.
+fn decode_payload(payload: &str) -> Result<Vec<u8>, RailError> {
+    let payload = payload.trim();
+    let encoded = if payload.starts_with('{') {
+        serde_json::from_str::<serde_json::Value>(payload)
+            .ok()
+            .and_then(|value| value.get("transaction")?.as_str().map(str::to_string))
+            .ok_or_else(|| malformed("payload has no `transaction`"))?
+    } else {
+        payload.to_string()
+    };
+    use base64::Engine as _;
+    base64::engine::general_purpose::STANDARD
+        .decode(encoded)
+        .map_err(|error| malformed(&format!("invalid base64: {error}")))
+}
+
+fn base64_encode(bytes: &[u8]) -> String {
+    use base64::Engine as _;
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::expect_used)]
+
+    use std::sync::{Arc, Mutex};
+
+    use ledgerflow_core::{PaymentSubjectKind, PaymentSubjectRef};
+
+    use super::*;
+    use crate::rails::rpc::MockJsonRpcTransport;
+
+    const MINT: [u8; 32] = [0x11; 32];
+    const MERCHANT: [u8; 32] = [0x22; 32];
+    const SOURCE: [u8; 32] = [0x33; 32];
+
+    fn fee_payer() -> SigningKeyPair {
+        SigningKeyPair::from_bytes(&[0x41; 32])
+    }
+
+    fn agent() -> SigningKeyPair {
+        SigningKeyPair::from_bytes(&[0x42; 32])
+    }
+
+    fn usdc() -> SplMint {
+        SplMint { mint: MINT, token_program: program_id(TOKEN_PROGRAM_ID), decimals: 6 }
+    }
+
+    fn config() -> SolanaRpcConfig {
+        SolanaRpcConfig::new("mainnet")
+            .with_mint("USDC", usdc())
+            .with_payee("merchant-a", MERCHANT)
+            .with_polling(0, 5)
+    }
+
+    fn authorization(amount: u128) -> VerifiedAuthorization {
+        let holder = ledgerflow_core::SignerRef::new(SigningAlgorithm::Ed25519, vec![1; 32]);
+        let warrant = ledgerflow_core::Warrant {
+            version: 1,
+            id: vec![0xAB; 16],
+            holder: holder.clone(),
+            issuer: holder.clone(),
+            issued_at: 1,
+            expires_at: 2,
+            depth: 0,
+            max_depth: 1,
+            parent_hash: None,
+            merchant: ledgerflow_core::MerchantConstraint::with_ids(vec!["merchant-a".to_string()]),
+            resource: ledgerflow_core::ResourceConstraint::default(),
+            payment: ledgerflow_core::PaymentConstraint::new(amount),
+            tool: None,
+            approval_gates: BTreeMap::new(),
+            required_approvers: Vec::new(),
+            min_approvals: 0,
+            extensions: BTreeMap::new(),
+            signature: SignatureEnvelope { alg: SigningAlgorithm::Ed25519, value: vec![0; 64] },
+        };
+        VerifiedAuthorization {
+            merchant_id: "merchant-a".to_string(),
+            tool_name: "web-search".to_string(),
+            payment_subject: PaymentSubjectRef::new(
+                PaymentSubjectKind::Caip10,
+                format!(
+                    "caip10:solana:mainnet:{}",
+                    bs58::encode(agent().public_key_bytes()).into_string()
+                ),
+            ),
+            holder,
+            leaf_warrant: warrant.clone(),
+            root_warrant: warrant,
+            chain_len: 1,
+            amount,
+            asset: "USDC".to_string(),
+            scheme: "exact".to_string(),
+            payee_id: "merchant-a".to_string(),
+            rail: ledgerflow_core::PaymentRail::Onchain,
+            challenge_id: "challenge-1".to_string(),
+            request_hash: "sha256:req".to_string(),
+            accepted_hash: "sha256:acc".to_string(),
+            warrant_digest: "sha256:w".to_string(),
+        }
+    }
+
+    /// Builds a legacy transaction `[compute price?, TransferChecked]` signed
+    /// by the agent, with an empty fee-payer slot.
+    fn agent_transaction(amount: u64, destination: [u8; 32], unit_price: Option<u64>) -> String {
+        let token_program = program_id(TOKEN_PROGRAM_ID);
+        let compute_budget = program_id(COMPUTE_BUDGET_PROGRAM_ID);
+        // Writable signers, readonly signers, writable, readonly.
+        let keys = [
+            fee_payer().public_key_bytes(),
+            agent().public_key_bytes(),
+            SOURCE,
+            destination,
+            MINT,
+            token_program,
+            compute_budget,
+        ];
+        let mut message = vec![2, 1, 3];
+        message.extend(compact_u16(keys.len() as u16));
+        for key in &keys {
+            message.extend_from_slice(key);
+        }
+        message.extend_from_slice(&[0x77; 32]);
+        let mut instructions = Vec::new();
+        if let Some(price) = unit_price {
+            let mut data = vec![SET_COMPUTE_UNIT_PRICE];
+            data.extend_from_slice(&price.to_le_bytes());
+            instructions.push((6_u8, Vec::new(), data));
+        }
+        let mut data = vec![TRANSFER_CHECKED];
+        data.extend_from_slice(&amount.to_le_bytes());
+        data.push(6);
+        instructions.push((5, vec![2, 4, 3, 1], data));
+        message.extend(compact_u16(instructions.len() as u16));
+        for (program, accounts, data) in instructions {
+            message.push(program);
+            message.extend(compact_u16(accounts.len() as u16));
+            message.extend(accounts);
+            message.extend(compact_u16(data.len() as u16));
+            message.extend(data);
+        }
+        let transaction = SolanaTransaction {
+            signatures: vec![
+                [0; 64],
+                <[u8; 64]>::try_from(agent().sign(&message).value).expect("signature"),
+            ],
+            message,
+            num_required_signatures: 2,
+            account_keys: keys.to_vec(),
+            instructions: Vec::new(),
+            uses_lookup_tables: false,
+        };
+        serde_json::json!({ "transaction": base64_encode(&transaction.encode()) }).to_string()
+    }
+
+    fn merchant_ata() -> [u8; 32] {
+        associated_token_address(&MERCHANT, &MINT, &program_id(TOKEN_PROGRAM_ID)).expect("ata")
+    }
+
+    /// In-memory cluster: accepts transactions and reports them `confirmed`.
+    fn cluster(sent: Arc<Mutex<Vec<Vec<u8>>>>, error: bool) -> MockJsonRpcTransport {
+        MockJsonRpcTransport::new(move |method, params| match method {
+            "sendTransaction" => {
+                let bytes = decode_payload(params[0].as_str().expect("tx")).expect("base64");
+                let transaction = SolanaTransaction::decode(&bytes).expect("decode");
+                sent.lock().expect("sent").push(bytes);
+                Ok(serde_json::json!(bs58::encode(transaction.signatures[0]).into_string()))
+            }
+            "getSignatureStatuses" => {
+                let known = !sent.lock().expect("sent").is_empty();
+                Ok(serde_json::json!({
+                    "context": { "slot": 10 },
+                    "value": [known.then(|| serde_json::json!({
+                        "slot": 9,
+                        "confirmations": 3,
+                        "err": if error { serde_json::json!({ "InstructionError": [0, "Custom"] }) } else { serde_json::Value::Null },
+                        "confirmationStatus": "confirmed",
+                    }))],
+                }))
+            }
+            "getTransaction" => {
+                let signature = params[0].as_str().expect("signature");
+                let sent = sent.lock().expect("sent");
+                let landed = sent.iter().find(|bytes| {
+                    let transaction = SolanaTransaction::decode(bytes).expect("decode");
+                    bs58::encode(transaction.signatures[0]).into_string() == signature
+                });
+                Ok(landed.map_or(serde_json::Value::Null, |bytes| {
+                    serde_json::json!({
+                        "slot": 9,
+                        "transaction": [base64_encode(bytes), "base64"],
+                        "meta": { "err": null },
+                    })
+                }))
+            }
+            other => Err(RpcError::Rpc { code: -32601, message: format!("{other} not found") }),
+        })
+    }
+
+    #[test]
+    fn compact_u16_round_trips() {
+        for value in [0_u16, 1, 127, 128, 16_383, 16_384, u16::MAX] {
+            let encoded = compact_u16(value);
+            let mut reader = Reader { bytes: &encoded, offset: 0 };
+            assert_eq!(reader.compact_u16().expect("decode"), value);
+        }
+        assert_eq!(compact_u16(128), vec![0x80, 0x01]);
+    }
+
+    #[test]
+    fn associated_token_address_is_an_off_curve_program_address() {
+        let ata = merchant_ata();
+        assert!(ed25519_dalek::VerifyingKey::from_bytes(&ata).is_err());
+        let token_2022 =
+            associated_token_address(&MERCHANT, &MINT, &program_id(TOKEN_2022_PROGRAM_ID));
+        assert_ne!(Some(ata), token_2022, "the token program is part of the seed");
+    }
+
+    #[test]
+    fn spl_transfer_is_cosigned_submitted_and_confirmed() {
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let adapter =
+            SolanaRpcRailAdapter::new(cluster(Arc::clone(&sent), false), fee_payer(), config());
+        let payload = agent_transaction(100, merchant_ata(), Some(1_000));
+
+        let receipt = adapter.settle_with_payload(&authorization(100), &payload).expect("settled");
+        assert_eq!(receipt.rail, RailKind::Solana);
+        assert_eq!(receipt.settled_amount, 100);
+
+        let submitted =
+            SolanaTransaction::decode(&sent.lock().expect("sent")[0]).expect("submitted");
+        let fee_payer_signature = SignatureEnvelope {
+            alg: SigningAlgorithm::Ed25519,
+            value: submitted.signatures[0].to_vec(),
+        };
+        assert!(fee_payer_signature.verify_strict(&fee_payer().signer_ref(), &submitted.message));
+        assert_eq!(receipt.transaction_id, bs58::encode(submitted.signatures[0]).into_string());
+
+        let verification = adapter.verify(&receipt).expect("verify");
+        assert_eq!(verification, VerificationResult { verified: true, confirmations: 3 });
+    }
+
+    #[test]
+    fn mismatched_transfers_are_rejected_before_submission() {
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let adapter =
+            SolanaRpcRailAdapter::new(cluster(Arc::clone(&sent), false), fee_payer(), config());
+
+        for payload in [
+            agent_transaction(101, merchant_ata(), None),
+            agent_transaction(100, [0x55; 32], None),
+            agent_transaction(100, merchant_ata(), Some(u64::MAX)),
+        ] {
+            assert!(adapter.settle_with_payload(&authorization(100), &payload).is_err());
+        }
+
+        // A tampered message invalidates the agent's signature.
+        let mut bytes = decode_payload(&agent_transaction(100, merchant_ata(), None)).expect("tx");
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0x01;
+        let tampered = base64_encode(&bytes);
+        assert!(adapter.settle_with_payload(&authorization(100), &tampered).is_err());
+
+        // A different fee payer is refused.
+        let other = SolanaRpcRailAdapter::new(
+            cluster(Arc::clone(&sent), false),
+            SigningKeyPair::from_bytes(&[0x43; 32]),
+            config(),
+        );
+        let payload = agent_transaction(100, merchant_ata(), None);
+        assert!(other.settle_with_payload(&authorization(100), &payload).is_err());
+        assert!(sent.lock().expect("sent").is_empty(), "nothing was submitted");
+    }
+
+    #[test]
+    fn failed_transactions_fail_settlement_and_verification() {
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let adapter =
+            SolanaRpcRailAdapter::new(cluster(Arc::clone(&sent), true), fee_payer(), config());
+        let unknown = SettlementReceipt {
+            rail: RailKind::Solana,
+            transaction_id: "unknown".to_string(),
+            settled_amount: 100,
+            asset: "USDC".to_string(),
+        };
+        assert_eq!(
+            adapter.verify(&unknown).expect("verify"),
+            VerificationResult { verified: false, confirmations: 0 }
+        );
+
+        let payload = agent_transaction(100, merchant_ata(), None);
+        let error = adapter.settle_with_payload(&authorization(100), &payload).expect_err("failed");
+        assert!(error.to_string().contains("failed"));
+    }
+
+    #[test]
+    fn verification_requires_the_recorded_transfer() {
+        let sent = Arc::new(Mutex::new(Vec::new()));
+        let adapter =
+            SolanaRpcRailAdapter::new(cluster(Arc::clone(&sent), false), fee_payer(), config());
+        let payload = agent_transaction(100, merchant_ata(), None);
+        let receipt = adapter.settle_with_payload(&authorization(100), &payload).expect("settled");
+        assert!(adapter.verify(&receipt).expect("verify").verified);
+
+        // The same signature does not verify a different amount or asset.
+        let inflated = SettlementReceipt { settled_amount: 1_000, ..receipt.clone() };
+        assert!(!adapter.verify(&inflated).expect("verify").verified);
+        let mut other_asset = config();
+        other_asset.mints.insert("USDT".to_string(), SplMint { mint: [0x12; 32], ..usdc() });
+        let adapter_usdt =
+            SolanaRpcRailAdapter::new(cluster(Arc::clone(&sent), false), fee_payer(), other_asset);
+        let relabelled = SettlementReceipt { asset: "USDT".to_string(), ..receipt.clone() };
+        assert!(!adapter_usdt.verify(&relabelled).expect("verify").verified);
+
+        // A successful transaction that paid someone else is not a settlement.
+        let unlisted = SolanaRpcRailAdapter::new(
+            cluster(Arc::clone(&sent), false),
+            fee_payer(),
+            SolanaRpcConfig::new("mainnet").with_mint("USDC", usdc()),
+        );
+        assert!(!unlisted.verify(&receipt).expect("verify").verified);
+
+        // Nor is a transaction another fee payer sponsored.
+        let other = SolanaRpcRailAdapter::new(
+            cluster(Arc::clone(&sent), false),
+            SigningKeyPair::from_bytes(&[0x43; 32]),
+            config(),
+        );
+        assert!(!other.verify(&receipt).expect("verify").verified);
+    }
+}
@@ -6,23 +6,92 @@
 //!
 //! The in-memory variant is only permitted for demonstrations and must be
 //! explicitly acknowledged by the operator (e.g. `--insecure-revoc-memory`).
+//!
+//! Besides warrants and holder keys, the store records the scoped
+//! revocations of design §6.6: payment subjects, issuer keys and
+//! `ledgerflow.agent_id` values.
+//!
+//! The file is an append-only log, so its record count is a monotone version
+//! and [`FileRevocationStore::srl_entries`] replays it in order: a control
+//! plane publishes the log as a Signed Revocation List (design §6.6).
+//!
+//! [`RevocationStore`] is the storage seam a control plane administers
+//! through; [`FileRevocationStore`] is the default implementation, and
+//! `ledgerflow-server` plugs in its SQLite backend.
 
 use std::{
     collections::HashSet,
     fs::{File, OpenOptions},
     io::{BufRead, BufReader, Write},
     path::{Path, PathBuf},
-    sync::Mutex,
+    sync::{Arc, Mutex},
 };
 
-use ledgerflow_core::{RevocationCheck, RevocationDecision, SignerRef};
+use ledgerflow_core::{
+    PaymentSubjectRef, RevocationCheck, RevocationDecision, SignerRef, SrlEntry,
+    subject_revocation_key,
+};
 
 /// A revocation record (JSON Lines).
 #[derive(Clone, Debug, serde::Serialize, serde::Deserialize)]
 #[serde(tag = "kind", rename_all = "snake_case")]
 enum RevocationRecord {
-    Warrant { id_hex: String },
-    Holder { key_hex: String },
+    Warrant {
+        id_hex: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        expires_at: Option<u64>,
+    },
+    Holder {
+        key_hex: String,
+    },
+    Subject {
+        key_hex: String,
+    },
+    Issuer {
+        key_hex: String,
+    },
+    Agent {
+        key_hex: String,
+    },
+}
+
+impl RevocationRecord {
+    /// The record's decoded key, with the set it belongs to.
+    fn key(&self) -> Result<(Scope, Vec<u8>), RevocationStoreError> {
+        let (scope, key_hex) = match self {
+            Self::Warrant { id_hex, .. } => (Scope::Warrant, id_hex),
+            Self::Holder { key_hex } => (Scope::Holder, key_hex),
+            Self::Subject { key_hex } => (Scope::Subject, key_hex),
+            Self::Issuer { key_hex } => (Scope::Issuer, key_hex),
+            Self::Agent { key_hex } => (Scope::Agent, key_hex),
+        };
+        let key = hex_decode(key_hex).map_err(|()| {
+            RevocationStoreError::Corrupt(format!("invalid {} hex", scope.as_str()))
+        })?;
+        Ok((scope, key))
+    }
+}
+
+/// The revocation scopes a record can belong to.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Scope {
+    Warrant,
+    Holder,
+    Subject,
+    Issuer,
+    Agent,
+}
+
+impl Scope {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Warrant => "warrant id",
+            Self::Holder => "holder key",
+            Self::Subject => "subject key",
+            Self::Issuer => "issuer key",
+            Self::Agent => "agent id",
+        }
+    }
 }
 
 /// File-backed, restart-safe revocation store.
@@ -40,18 +109,32 @@ struct FileRevocationStoreInner {
     path: PathBuf,
     revoked_warrants: Mutex<HashSet<Vec<u8>>>,
     revoked_holders: Mutex<HashSet<Vec<u8>>>,
+    revoked_subjects: Mutex<HashSet<Vec<u8>>>,
+    revoked_issuers: Mutex<HashSet<Vec<u8>>>,
+    revoked_agents: Mutex<HashSet<Vec<u8>>>,
+    /// Every record in append order.
+    log: Mutex<Vec<RevocationRecord>>,
 }
 
 impl FileRevocationStore {
     /// Opens (and loads) a revocation store at `path`.
     pub fn open(path: impl AsRef<Path>) -> Result<Self, RevocationStoreError> {
-        let path = path.as_ref().to_path_buf();
-        let mut revoked_warrants = HashSet::new();
-        let mut revoked_holders = HashSet::new();
+        let store = Self {
+            inner: std::sync::Arc::new(FileRevocationStoreInner {
+                path: path.as_ref().to_path_buf(),
+                revoked_warrants: Mutex::new(HashSet::new()),
+                revoked_holders: Mutex::new(HashSet::new()),
+                revoked_subjects: Mutex::new(HashSet::new()),
+                revoked_issuers: Mutex::new(HashSet::new()),
+                revoked_agents: Mutex::new(HashSet::new()),
+                log: Mutex::new(Vec::new()),
+            }),
+        };
 
-        if path.exists() {
-            let file = File::open(&path).map_err(RevocationStoreError::Io)?;
+        if store.inner.path.exists() {
+            let file = File::open(&store.inner.path).map_err(RevocationStoreError::Io)?;
             let reader = BufReader::new(file);
+            let mut log = Vec::new();
             for line in reader.lines() {
                 let line = line.map_err(RevocationStoreError::Io)?;
                 let trimmed = line.trim();
@@ -60,48 +143,52 @@ impl FileRevocationStore {
                 }
                 let record: RevocationRecord = serde_json::from_str(trimmed)
                     .map_err(|error| RevocationStoreError::Corrupt(error.to_string()))?;
-                match record {
-                    RevocationRecord::Warrant { id_hex } => {
-                        revoked_warrants.insert(hex_decode(&id_hex).map_err(|()| {
-                            RevocationStoreError::Corrupt("invalid warrant id hex".to_string())
-                        })?);
-                    }
-                    RevocationRecord::Holder { key_hex } => {
-                        revoked_holders.insert(hex_decode(&key_hex).map_err(|()| {
-                            RevocationStoreError::Corrupt("invalid holder key hex".to_string())
-                        })?);
-                    }
-                }
+                let (scope, key) = record.key()?;
+                store.insert(scope, key);
+                log.push(record);
             }
+            *store.inner.log.lock().unwrap_or_else(std::sync::PoisonError::into_inner) = log;
         }
 
-        Ok(Self {
-            inner: std::sync::Arc::new(FileRevocationStoreInner {
-                path,
-                revoked_warrants: Mutex::new(revoked_warrants),
-                revoked_holders: Mutex::new(revoked_holders),
-            }),
-        })
+        Ok(store)
     }
 
     /// Revokes a warrant by id (persisted immediately).
     pub fn revoke_warrant(&self, warrant_id: &[u8]) -> Result<(), RevocationStoreError> {
-        let record = RevocationRecord::Warrant { id_hex: hex_encode(warrant_id) };
-        self.append(&record)?;
-        if let Ok(mut set) = self.inner.revoked_warrants.lock() {
-            set.insert(warrant_id.to_vec());
-        }
-        Ok(())
+        self.revoke_warrant_until(warrant_id, None)
+    }
+
+    /// Revokes a warrant by id, recording the warrant's `expires_at` so a
+    /// published SRL can compact the entry away once it has passed.
+    pub fn revoke_warrant_until(
+        &self,
+        warrant_id: &[u8],
+        expires_at: Option<u64>,
+    ) -> Result<(), RevocationStoreError> {
+        self.record(RevocationRecord::Warrant { id_hex: hex_encode(warrant_id), expires_at })
     }
 
     /// Revokes a holder key (persisted immediately).
     pub fn revoke_holder(&self, holder: &SignerRef) -> Result<(), RevocationStoreError> {
-        let record = RevocationRecord::Holder { key_hex: hex_encode(&holder.public_key) };
-        self.append(&record)?;
-        if let Ok(mut set) = self.inner.revoked_holders.lock() {
-            set.insert(holder.public_key.clone());
-        }
-        Ok(())
+        self.record(RevocationRecord::Holder { key_hex: hex_encode(&holder.public_key) })
+    }
+
+    /// Revokes a payment subject (persisted immediately).
+    pub fn revoke_subject(&self, subject: &PaymentSubjectRef) -> Result<(), RevocationStoreError> {
+        self.record(RevocationRecord::Subject {
+            key_hex: hex_encode(&subject_revocation_key(subject)),
+        })
+    }
+
+    /// Revokes an issuer or sub-issuer key (persisted immediately): every
+    /// chain with a node it signed is rejected.
+    pub fn revoke_issuer(&self, issuer: &SignerRef) -> Result<(), RevocationStoreError> {
+        self.record(RevocationRecord::Issuer { key_hex: hex_encode(&issuer.public_key) })
+    }
+
+    /// Revokes a `ledgerflow.agent_id` value (persisted immediately).
+    pub fn revoke_agent(&self, agent_id: &str) -> Result<(), RevocationStoreError> {
+        self.record(RevocationRecord::Agent { key_hex: hex_encode(agent_id.as_bytes()) })
     }
 
     /// Tenant-scoped revocation of a warrant (design §10.2 tenant isolation).
@@ -113,14 +200,19 @@ impl FileRevocationStore {
         &self,
         tenant_id: &str,
         warrant_id: &[u8],
+    ) -> Result<(), RevocationStoreError> {
+        self.revoke_warrant_for_until(tenant_id, warrant_id, None)
+    }
+
+    /// Tenant-scoped [`revoke_warrant_until`](Self::revoke_warrant_until).
+    pub fn revoke_warrant_for_until(
+        &self,
+        tenant_id: &str,
+        warrant_id: &[u8],
+        expires_at: Option<u64>,
     ) -> Result<(), RevocationStoreError> {
         let scoped = tenant_scoped_key(tenant_id, warrant_id);
-        let record = RevocationRecord::Warrant { id_hex: hex_encode(&scoped) };
-        self.append(&record)?;
-        if let Ok(mut set) = self.inner.revoked_warrants.lock() {
-            set.insert(scoped);
-        }
-        Ok(())
+        self.record(RevocationRecord::Warrant { id_hex: hex_encode(&scoped), expires_at })
     }
 
     /// Tenant-scoped revocation of a holder key (design §10.2).
@@ -130,12 +222,37 @@ impl FileRevocationStore {
         holder: &SignerRef,
     ) -> Result<(), RevocationStoreError> {
         let scoped = tenant_scoped_key(tenant_id, &holder.public_key);
-        let record = RevocationRecord::Holder { key_hex: hex_encode(&scoped) };
-        self.append(&record)?;
-        if let Ok(mut set) = self.inner.revoked_holders.lock() {
-            set.insert(scoped);
-        }
-        Ok(())
+        self.record(RevocationRecord::Holder { key_hex: hex_encode(&scoped) })
+    }
+
+    /// Tenant-scoped revocation of a payment subject (design §10.2).
+    pub fn revoke_subject_for(
+        &self,
+        tenant_id: &str,
+        subject: &PaymentSubjectRef,
+    ) -> Result<(), RevocationStoreError> {
+        let scoped = tenant_scoped_key(tenant_id, &subject_revocation_key(subject));
+        self.record(RevocationRecord::Subject { key_hex: hex_encode(&scoped) })
+    }
+
+    /// Tenant-scoped revocation of an issuer key (design §10.2).
+    pub fn revoke_issuer_for(
+        &self,
+        tenant_id: &str,
+        issuer: &SignerRef,
+    ) -> Result<(), RevocationStoreError> {
+        let scoped = tenant_scoped_key(tenant_id, &issuer.public_key);
+        self.record(RevocationRecord::Issuer { key_hex: hex_encode(&scoped) })
+    }
+
+    /// Tenant-scoped revocation of an agent id (design §10.2).
+    pub fn revoke_agent_for(
+        &self,
+        tenant_id: &str,
+        agent_id: &str,
+    ) -> Result<(), RevocationStoreError> {
+        let scoped = tenant_scoped_key(tenant_id, agent_id.as_bytes());
+        self.record(RevocationRecord::Agent { key_hex: hex_encode(&scoped) })
     }
 
     /// Tenant-scoped warrant revocation check (design §10.2).
@@ -152,48 +269,343 @@ impl FileRevocationStore {
         self.check_holder_key(&scoped)
     }
 
+    /// Tenant-scoped payment subject revocation check (design §10.2).
+    #[must_use]
+    pub fn check_subject_for(
+        &self,
+        tenant_id: &str,
+        subject: &PaymentSubjectRef,
+    ) -> RevocationDecision {
+        let scoped = tenant_scoped_key(tenant_id, &subject_revocation_key(subject));
+        self.lookup(Scope::Subject, &scoped)
+    }
+
+    /// Tenant-scoped issuer revocation check (design §10.2).
+    #[must_use]
+    pub fn check_issuer_for(&self, tenant_id: &str, issuer: &SignerRef) -> RevocationDecision {
+        let scoped = tenant_scoped_key(tenant_id, &issuer.public_key);
+        self.lookup(Scope::Issuer, &scoped)
+    }
+
+    /// Tenant-scoped agent id revocation check (design §10.2).
+    #[must_use]
+    pub fn check_agent_for(&self, tenant_id: &str, agent_id: &str) -> RevocationDecision {
+        let scoped = tenant_scoped_key(tenant_id, agent_id.as_bytes());
+        self.lookup(Scope::Agent, &scoped)
+    }
+
+    /// Returns a [`RevocationCheck`] view that honors both global and
+    /// `tenant_id`-scoped revocations.
+    ///
+    /// Hosted verify/settle endpoints use this so that revocations recorded
+    /// through the tenant-scoped admin API take effect for that tenant only
+    /// (design §10.2).
+    #[must_use]
+    pub fn for_tenant(&self, tenant_id: impl Into<String>) -> TenantRevocationView {
+        TenantRevocationView { store: self.clone(), tenant_id: tenant_id.into() }
+    }
+
     /// Checks whether a raw (possibly tenant-scoped) holder key is revoked.
     #[must_use]
     pub fn check_holder_key(&self, holder_key: &[u8]) -> RevocationDecision {
-        let revoked = self.inner.revoked_holders.lock().is_ok_and(|set| set.contains(holder_key));
-        if revoked { RevocationDecision::RevokedHolder } else { RevocationDecision::Ok }
+        self.lookup(Scope::Holder, holder_key)
     }
 
-    fn append(&self, record: &RevocationRecord) -> Result<(), RevocationStoreError> {
+    /// Every record in append order, tenant-scoped keys included: entry `n`
+    /// is the `n+1`-th revocation, so `srl_entries().len()` is the store's
+    /// version and `srl_entries()[v..]` the delta since version `v`.
+    #[must_use]
+    pub fn srl_entries(&self) -> Vec<SrlEntry> {
+        self.inner
+            .log
+            .lock()
+            .unwrap_or_else(std::sync::PoisonError::into_inner)
+            .iter()
+            .map(|record| match record {
+                RevocationRecord::Warrant { id_hex, expires_at } => {
+                    SrlEntry::Warrant { id_hex: id_hex.clone(), expires_at: *expires_at }
+                }
+                RevocationRecord::Holder { key_hex } => {
+                    SrlEntry::Holder { key_hex: key_hex.clone() }
+                }
+                RevocationRecord::Subject { key_hex } => {
+                    SrlEntry::Subject { key_hex: key_hex.clone() }
+                }
+                RevocationRecord::Issuer { key_hex } => {
+                    SrlEntry::Issuer { key_hex: key_hex.clone() }
+                }
+                RevocationRecord::Agent { key_hex } => SrlEntry::Agent { key_hex: key_hex.clone() },
+            })
+            .collect()
+    }
+
+    fn set(&self, scope: Scope) -> &Mutex<HashSet<Vec<u8>>> {
+        match scope {
+            Scope::Warrant => &self.inner.revoked_warrants,
+            Scope::Holder => &self.inner.revoked_holders,
+            Scope::Subject => &self.inner.revoked_subjects,
+            Scope::Issuer => &self.inner.revoked_issuers,
+            Scope::Agent => &self.inner.revoked_agents,
+        }
+    }
+
+    fn insert(&self, scope: Scope, key: Vec<u8>) {
+        if let Ok(mut set) = self.set(scope).lock() {
+            set.insert(key);
+        }
+    }
+
+    fn lookup(&self, scope: Scope, key: &[u8]) -> RevocationDecision {
+        if !self.set(scope).lock().is_ok_and(|set| set.contains(key)) {
+            return RevocationDecision::Ok;
+        }
+        match scope {
+            Scope::Warrant => RevocationDecision::RevokedWarrant,
+            Scope::Holder => RevocationDecision::RevokedHolder,
+            Scope::Subject => RevocationDecision::RevokedSubject,
+            Scope::Issuer => RevocationDecision::RevokedIssuer,
+            Scope::Agent => RevocationDecision::RevokedAgent,
+        }
+    }
+
+    /// Records a replicated SRL entry under its raw (possibly tenant-scoped)
+    /// key, unless that key is already revoked.
+    pub(crate) fn record_srl_entry(&self, entry: &SrlEntry) -> Result<(), RevocationStoreError> {
+        let record = match entry {
+            SrlEntry::Warrant { id_hex, expires_at } => {
+                RevocationRecord::Warrant { id_hex: id_hex.clone(), expires_at: *expires_at }
+            }
+            SrlEntry::Holder { key_hex } => RevocationRecord::Holder { key_hex: key_hex.clone() },
+            SrlEntry::Subject { key_hex } => RevocationRecord::Subject { key_hex: key_hex.clone() },
+            SrlEntry::Issuer { key_hex } => RevocationRecord::Issuer { key_hex: key_hex.clone() },
+            SrlEntry::Agent { key_hex } => RevocationRecord::Agent { key_hex: key_hex.clone() },
+        };
+        self.record(record)
+    }
+
+    /// Persists `record`, then makes it visible to checks; a key that is
+    /// already revoked is skipped, so the log holds each key once.
+    ///
+    /// The log lock is held from the duplicate check until the record is
+    /// visible, so the file and the log (and hence published SRL versions)
+    /// always agree on the append order.
+    fn record(&self, record: RevocationRecord) -> Result<(), RevocationStoreError> {
+        let (scope, key) = record.key()?;
+        let mut log = self.inner.log.lock().unwrap_or_else(std::sync::PoisonError::into_inner);
+        if self.lookup(scope, &key) != RevocationDecision::Ok {
+            return Ok(());
+        }
         let mut file = OpenOptions::new()
             .create(true)
             .append(true)
             .open(&self.inner.path)
             .map_err(RevocationStoreError::Io)?;
-        let line = serde_json::to_string(record)
+        let line = serde_json::to_string(&record)
             .map_err(|error| RevocationStoreError::Corrupt(error.to_string()))?;
         writeln!(file, "{line}").map_err(RevocationStoreError::Io)?;
         file.flush().map_err(RevocationStoreError::Io)?;
         file.sync_all().map_err(RevocationStoreError::Io)?;
+        log.push(record);
+        self.insert(scope, key);
         Ok(())
     }
 }
 
 impl RevocationCheck for FileRevocationStore {
     fn check_warrant(&self, warrant_id: &[u8]) -> RevocationDecision {
-        let revoked = self.inner.revoked_warrants.lock().is_ok_and(|set| set.contains(warrant_id));
-        if revoked { RevocationDecision::RevokedWarrant } else { RevocationDecision::Ok }
+        self.lookup(Scope::Warrant, warrant_id)
+    }
+
+    fn check_holder(&self, holder: &SignerRef) -> RevocationDecision {
+        self.lookup(Scope::Holder, &holder.public_key)
+    }
+
+    fn check_subject(&self, subject: &PaymentSubjectRef) -> RevocationDecision {
+        self.lookup(Scope::Subject, &subject_revocation_key(subject))
+    }
+
+    fn check_issuer(&self, issuer: &SignerRef) -> RevocationDecision {
+        self.lookup(Scope::Issuer, &issuer.public_key)
+    }
+
+    fn check_agent(&self, agent_id: &str) -> RevocationDecision {
+        self.lookup(Scope::Agent, agent_id.as_bytes())
+    }
+}
+
+/// Tenant-scoped view over a [`FileRevocationStore`].
+///
+/// A warrant or holder is revoked for the tenant when it was revoked
+/// globally (e.g. via SRL sync) or within the tenant's namespace.
+#[derive(Clone, Debug)]
+pub struct TenantRevocationView {
+    store: FileRevocationStore,
+    tenant_id: String,
+}
+
+impl RevocationCheck for TenantRevocationView {
+    fn check_warrant(&self, warrant_id: &[u8]) -> RevocationDecision {
+        match self.store.check_warrant(warrant_id) {
+            RevocationDecision::Ok => self.store.check_warrant_for(&self.tenant_id, warrant_id),
+            decision => decision,
+        }
     }
 
     fn check_holder(&self, holder: &SignerRef) -> RevocationDecision {
-        let revoked =
-            self.inner.revoked_holders.lock().is_ok_and(|set| set.contains(&holder.public_key));
-        if revoked { RevocationDecision::RevokedHolder } else { RevocationDecision::Ok }
+        match self.store.check_holder(holder) {
+            RevocationDecision::Ok => self.store.check_holder_for(&self.tenant_id, holder),
+            decision => decision,
+        }
+    }
+
+    fn check_subject(&self, subject: &PaymentSubjectRef) -> RevocationDecision {
+        match self.store.check_subject(subject) {
+            RevocationDecision::Ok => self.store.check_subject_for(&self.tenant_id, subject),
+            decision => decision,
+        }
+    }
+
+    fn check_issuer(&self, issuer: &SignerRef) -> RevocationDecision {
+        match self.store.check_issuer(issuer) {
+            RevocationDecision::Ok => self.store.check_issuer_for(&self.tenant_id, issuer),
+            decision => decision,
+        }
+    }
+
+    fn check_agent(&self, agent_id: &str) -> RevocationDecision {
+        match self.store.check_agent(agent_id) {
+            RevocationDecision::Ok => self.store.check_agent_for(&self.tenant_id, agent_id),
+            decision => decision,
+        }
+    }
+}
+
+/// Storage seam for an administered revocation store: tenant-scoped writes
+/// (design §10.2), tenant views for hosted verification, and the ordered log
+/// published as a Signed Revocation List.
+///
+/// The inherent [`RevocationCheck`] answers global revocations only.
+pub trait RevocationStore: RevocationCheck + Send + Sync {
+    /// Revokes a warrant for `tenant_id`, recording its `expires_at` for SRL
+    /// compaction.
+    fn revoke_warrant_for_until(
+        &self,
+        tenant_id: &str,
+        warrant_id: &[u8],
+        expires_at: Option<u64>,
+    ) -> Result<(), RevocationStoreError>;
+
+    /// Revokes a holder key for `tenant_id`.
+    fn revoke_holder_for(
+        &self,
+        tenant_id: &str,
+        holder: &SignerRef,
+    ) -> Result<(), RevocationStoreError>;
+
+    /// Revokes a payment subject for `tenant_id`.
+    fn revoke_subject_for(
+        &self,
+        tenant_id: &str,
+        subject: &PaymentSubjectRef,
+    ) -> Result<(), RevocationStoreError>;
+
+    /// Revokes an issuer or sub-issuer key for `tenant_id`.
+    fn revoke_issuer_for(
+        &self,
+        tenant_id: &str,
+        issuer: &SignerRef,
+    ) -> Result<(), RevocationStoreError>;
+
+    /// Revokes a `ledgerflow.agent_id` value for `tenant_id`.
+    fn revoke_agent_for(&self, tenant_id: &str, agent_id: &str)
+    -> Result<(), RevocationStoreError>;
+
+    /// A [`RevocationCheck`] honoring both global and `tenant_id`-scoped
+    /// revocations.
+    fn for_tenant(&self, tenant_id: &str) -> SharedRevocationCheck;
+
+    /// Every revocation in append order, tenant-scoped ones under
+    /// [`tenant_scoped_key`]s (see [`FileRevocationStore::srl_entries`]).
+    fn srl_entries(&self) -> Vec<SrlEntry>;
+
+    /// The number of revocations, i.e. `srl_entries().len()` (the published
+    /// list version), without materializing the log.
+    fn srl_version(&self) -> u64 {
+        self.srl_entries().len() as u64
+    }
+}
+
+/// Shared revocation store handle.
+pub type SharedRevocationStore = Arc<dyn RevocationStore>;
+
+/// Shared revocation check handle (e.g. a tenant view).
+pub type SharedRevocationCheck = Arc<dyn RevocationCheck + Send + Sync>;
+
+impl RevocationStore for FileRevocationStore {
+    fn revoke_warrant_for_until(
+        &self,
+        tenant_id: &str,
+        warrant_id: &[u8],
+        expires_at: Option<u64>,
+    ) -> Result<(), RevocationStoreError> {
+        Self::revoke_warrant_for_until(self, tenant_id, warrant_id, expires_at)
+    }
+
+    fn revoke_holder_for(
+        &self,
+        tenant_id: &str,
+        holder: &SignerRef,
+    ) -> Result<(), RevocationStoreError> {
+        Self::revoke_holder_for(self, tenant_id, holder)
+    }
+
+    fn revoke_subject_for(
+        &self,
+        tenant_id: &str,
+        subject: &PaymentSubjectRef,
+    ) -> Result<(), RevocationStoreError> {
+        Self::revoke_subject_for(self, tenant_id, subject)
+    }
+
+    fn revoke_issuer_for(
+        &self,
+        tenant_id: &str,
+        issuer: &SignerRef,
+    ) -> Result<(), RevocationStoreError> {
+        Self::revoke_issuer_for(self, tenant_id, issuer)
+    }
+
+    fn revoke_agent_for(
+        &self,
+        tenant_id: &str,
+        agent_id: &str,
+    ) -> Result<(), RevocationStoreError> {
+        Self::revoke_agent_for(self, tenant_id, agent_id)
+    }
+
+    fn for_tenant(&self, tenant_id: &str) -> SharedRevocationCheck {
+        Arc::new(Self::for_tenant(self, tenant_id))
+    }
+
+    fn srl_entries(&self) -> Vec<SrlEntry> {
+        Self::srl_entries(self)
+    }
+
+    fn srl_version(&self) -> u64 {
+        self.inner.log.lock().unwrap_or_else(std::sync::PoisonError::into_inner).len() as u64
     }
 }
 
-/// File revocation store failures.
+/// Revocation store failures.
 #[derive(Debug, thiserror::Error)]
 pub enum RevocationStoreError {
     #[error("I/O error on the revocation store: {0}")]
     Io(#[from] std::io::Error),
     #[error("corrupt revocation record: {0}")]
     Corrupt(String),
+    /// A pluggable backend (e.g. a database) failed.
+    #[error("revocation backend error: {0}")]
+    Backend(String),
 }
 
 /// An in-memory revocation check for demos and tests.
@@ -221,6 +633,21 @@ impl InsecureMemoryRevocationStore {
     pub fn revoke_holder(&mut self, holder: &SignerRef) {
         self.inner.revoke_holder(holder);
     }
+
+    /// Revokes a payment subject.
+    pub fn revoke_subject(&mut self, subject: &PaymentSubjectRef) {
+        self.inner.revoke_subject(subject);
+    }
+
+    /// Revokes an issuer or sub-issuer key.
+    pub fn revoke_issuer(&mut self, issuer: &SignerRef) {
+        self.inner.revoke_issuer(issuer);
+    }
+
+    /// Revokes a `ledgerflow.agent_id` value.
+    pub fn revoke_agent(&mut self, agent_id: &str) {
+        self.inner.revoke_agent(agent_id);
+    }
 }
 
 impl RevocationCheck for InsecureMemoryRevocationStore {
@@ -231,6 +658,18 @@ impl RevocationCheck for InsecureMemoryRevocationStore {
     fn check_holder(&self, holder: &SignerRef) -> RevocationDecision {
         self.inner.check_holder(holder)
     }
+
+    fn check_subject(&self, subject: &PaymentSubjectRef) -> RevocationDecision {
+        self.inner.check_subject(subject)
+    }
+
+    fn check_issuer(&self, issuer: &SignerRef) -> RevocationDecision {
+        self.inner.check_issuer(issuer)
+    }
+
+    fn check_agent(&self, agent_id: &str) -> RevocationDecision {
+        self.inner.check_agent(agent_id)
+    }
 }
 
 fn hex_encode(bytes: &[u8]) -> String {
@@ -257,8 +696,10 @@ fn hex_decode(hex: &str) -> Result<Vec<u8>, ()> {
 ///
 /// Tenant isolation (design §10.2) requires that one tenant's revocation cannot
 /// match another's; we prefix the key with the tenant id and a separator that
-/// cannot appear in a hex key.
-fn tenant_scoped_key(tenant_id: &str, key: &[u8]) -> Vec<u8> {
+/// cannot appear in a hex key. Other [`RevocationStore`]s publish their
+/// tenant-scoped SRL entries under the same keys, so any replica enforces them.
+#[must_use]
+pub fn tenant_scoped_key(tenant_id: &str, key: &[u8]) -> Vec<u8> {
     let mut scoped = Vec::with_capacity(tenant_id.len() + 1 + key.len());
     scoped.extend_from_slice(tenant_id.as_bytes());
     scoped.push(0xFF);
@@ -328,6 +769,31 @@ mod tests {
         let _ = std::fs::remove_dir(&dir);
     }
 
+    #[test]
+    fn tenant_view_sees_global_and_own_revocations_only() {
+        let dir = std::env::temp_dir().join(format!("ledgerflow-tview-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("create dir");
+        let path = dir.join("revocations.jsonl");
+        let _ = std::fs::remove_file(&path);
+
+        let store = FileRevocationStore::open(&path).expect("open");
+        store.revoke_warrant(&[1_u8; 16]).expect("global revoke");
+        store.revoke_warrant_for("tenant-a", &[2_u8; 16]).expect("tenant revoke");
+        store.revoke_holder_for("tenant-a", &holder()).expect("tenant holder revoke");
+
+        let tenant_a = store.for_tenant("tenant-a");
+        let tenant_b = store.for_tenant("tenant-b");
+        assert_eq!(tenant_a.check_warrant(&[1_u8; 16]), RevocationDecision::RevokedWarrant);
+        assert_eq!(tenant_b.check_warrant(&[1_u8; 16]), RevocationDecision::RevokedWarrant);
+        assert_eq!(tenant_a.check_warrant(&[2_u8; 16]), RevocationDecision::RevokedWarrant);
+        assert_eq!(tenant_b.check_warrant(&[2_u8; 16]), RevocationDecision::Ok);
+        assert_eq!(tenant_a.check_holder(&holder()), RevocationDecision::RevokedHolder);
+        assert_eq!(tenant_b.check_holder(&holder()), RevocationDecision::Ok);
+
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_dir(&dir);
+    }
+
     #[test]
     fn insecure_memory_store_revokes_warrant_and_holder() {
         let mut store = InsecureMemoryRevocationStore::new();
@@ -343,4 +809,94 @@ mod tests {
     fn signing_algorithm_is_ed25519_by_default() {
         assert_eq!(SigningAlgorithm::Ed25519.as_str(), "ed25519");
     }
+
+    #[test]
+    fn file_store_replays_its_log_in_append_order() {
+        let dir = std::env::temp_dir().join(format!("ledgerflow-srl-log-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("create dir");
+        let path = dir.join("revocations.jsonl");
+        let _ = std::fs::remove_file(&path);
+
+        let store = FileRevocationStore::open(&path).expect("open");
+        store.revoke_holder(&holder()).expect("revoke holder");
+        store.revoke_warrant(&[3_u8; 16]).expect("revoke warrant");
+        store.revoke_warrant_until(&[4_u8; 16], Some(1_700_000_000)).expect("revoke until");
+        let expected = vec![
+            SrlEntry::Holder { key_hex: hex_encode(&holder().public_key) },
+            SrlEntry::Warrant { id_hex: hex_encode(&[3_u8; 16]), expires_at: None },
+            SrlEntry::Warrant { id_hex: hex_encode(&[4_u8; 16]), expires_at: Some(1_700_000_000) },
+        ];
+        assert_eq!(store.srl_entries(), expected);
+        assert_eq!(FileRevocationStore::open(&path).expect("reopen").srl_entries(), expected);
+
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_dir(&dir);
+    }
+
+    #[test]
+    fn file_store_logs_concurrent_revocations_once_and_in_file_order() {
+        let dir = std::env::temp_dir().join(format!("ledgerflow-srl-race-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("create dir");
+        let path = dir.join("revocations.jsonl");
+        let _ = std::fs::remove_file(&path);
+
+        let store = FileRevocationStore::open(&path).expect("open");
+        std::thread::scope(|scope| {
+            for thread in 0..4_u8 {
+                let store = store.clone();
+                scope.spawn(move || {
+                    for id in 0..16_u8 {
+                        // Every thread revokes its own ids and a shared one.
+                        store.revoke_warrant(&[thread, id]).expect("revoke");
+                        store.revoke_warrant(&[0xff, id]).expect("revoke shared");
+                    }
+                });
+            }
+        });
+        let entries = store.srl_entries();
+        assert_eq!(entries.len(), 4 * 16 + 16);
+        assert_eq!(FileRevocationStore::open(&path).expect("reopen").srl_entries(), entries);
+
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_dir(&dir);
+    }
+
+    #[test]
+    fn file_store_persists_scoped_revocations_and_replays_them() {
+        use ledgerflow_core::PaymentSubjectKind;
+
+        let dir = std::env::temp_dir().join(format!("ledgerflow-scopes-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("create dir");
+        let path = dir.join("revocations.jsonl");
+        let _ = std::fs::remove_file(&path);
+
+        let subject = PaymentSubjectRef::new(PaymentSubjectKind::Opaque, "acct-1");
+        {
+            let store = FileRevocationStore::open(&path).expect("open");
+            store.revoke_subject(&subject).expect("revoke subject");
+            store.revoke_issuer(&holder()).expect("revoke issuer");
+            store.revoke_agent_for("tenant-a", "agent-1").expect("revoke agent");
+        }
+        let reloaded = FileRevocationStore::open(&path).expect("reopen");
+        assert_eq!(reloaded.check_subject(&subject), RevocationDecision::RevokedSubject);
+        assert_eq!(reloaded.check_issuer(&holder()), RevocationDecision::RevokedIssuer);
+        // An issuer revocation does not revoke the key as a holder.
+        assert_eq!(reloaded.check_holder(&holder()), RevocationDecision::Ok);
+        assert_eq!(reloaded.check_agent("agent-1"), RevocationDecision::Ok);
+        assert_eq!(
+            reloaded.for_tenant("tenant-a").check_agent("agent-1"),
+            RevocationDecision::RevokedAgent
+        );
+        assert_eq!(reloaded.for_tenant("tenant-b").check_agent("agent-1"), RevocationDecision::Ok);
+        assert_eq!(
+            reloaded.srl_entries()[..2],
+            [
+                SrlEntry::Subject { key_hex: hex_encode(&subject_revocation_key(&subject)) },
+                SrlEntry::Issuer { key_hex: hex_encode(&holder().public_key) },
+            ]
+        );
+
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_dir(&dir);
+    }
 }
@@ -23,6 +23,33 @@ pub enum RailKind {
     Gateway,
 }
 
+impl RailKind {
+    /// Stable lowercase wire name.
+    #[must_use]
+    pub const fn as_str(&self) -> &'static str {
+        match self {
+            Self::Evm => "evm",
+            Self::Solana => "solana",
+            Self::Exchange => "exchange",
+            Self::Custodial => "custodial",
+            Self::Gateway => "gateway",
+        }
+    }
+
+    /// Parses a wire name produced by [`as_str`](Self::as_str).
+    #[must_use]
+    pub fn from_wire(value: &str) -> Option<Self> {
+        match value {
+            "evm" => Some(Self::Evm),
+            "solana" => Some(Self::Solana),
+            "exchange" => Some(Self::Exchange),
+            "custodial" => Some(Self::Custodial),
+            "gateway" => Some(Self::Gateway),
+            _ => None,
+        }
+    }
+}
+
 /// Final routing decision returned by the Facilitator.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct RouteDecision {
@@ -0,0 +1,717 @@
+//! MPP session intent: streaming payments under one warrant (design §6.6).
+//!
+//! A session is opened once against a verified authorization and then
+//! advanced by ticks: an MPP voucher carrying the new cumulative total, or a
+//! charge adding to it. Either way the tick's rail payload must authorize
+//! the new cumulative total, since close redeems only the latest payload
+//! (the EVM and Solana adapters reject a payload whose amount differs).
+//! Every tick re-checks revocation across the chain (issuers, agent ids, the
+//! leaf and its holder, the payment subject), the leaf's TTL, and the
+//! cumulative amount against the leaf's `max_per_charge` cap. A revocation
+//! or an expired warrant terminates the session for good; an over-cap
+//! update is rejected and leaves the session open.
+//!
+//! When the chain declares accounting-point budgets, every
+//! [`SessionManager`] tick reserves its increment against them before it is
+//! accepted, so a stream cannot run past a budget it would only hit at close.
+//! Closing settles the cumulative amount once, through
+//! [`SettlementService::settle_session`], which commits the reservations (or
+//! releases them when settlement fails); a terminated session releases them.
+//! Revocation therefore takes effect at the next tick at the latest;
+//! [`SessionManager::terminate_revoked`] closes affected streams proactively
+//! (e.g. after recording a revocation or applying an SRL), so the acceptance
+//! window stays at most one tick.
+
+use std::{
+    collections::BTreeMap,
+    sync::{Mutex, PoisonError},
+};
+
+use ledgerflow_core::{
+    AuthorizationError, RevocationCheck, VerifiedAuthorization, WarrantChain,
+    verify_chain_revocation,
+};
+use thiserror::Error;
+
+use crate::{
+    budget::{BudgetError, BudgetReservation},
+    outcome::SettlementOutcome,
+    rails::RailAdapter,
+    settle::SettlementService,
+    subject::PaymentSubjectResolver,
+};
+
+/// One incremental session update.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SessionUpdate {
+    /// An MPP voucher: the new cumulative total, which must not decrease.
+    Voucher { cumulative_amount: u128, payload: String },
+    /// A charge adding `amount` to the cumulative total. Its `payload` must
+    /// authorize the new cumulative total, not the increment alone.
+    Charge { amount: u128, payload: String },
+}
+
+/// Lifecycle state of a [`PaymentSession`].
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum SessionState {
+    Open,
+    /// Closed by the payer or merchant; the total was handed to settlement.
+    Closed,
+    /// Closed by the Facilitator (revocation or expiry); never settles.
+    Terminated {
+        reason: String,
+    },
+}
+
+/// Errors surfaced by session ticks and lifecycle calls.
+#[derive(Debug, Error)]
+pub enum SessionError {
+    #[error("unknown session {0}")]
+    UnknownSession(String),
+    #[error("session {0} already exists")]
+    DuplicateSession(String),
+    #[error("the session is closed")]
+    Closed,
+    #[error("the session was terminated: {0}")]
+    Terminated(String),
+    #[error("voucher total {cumulative} is below the accepted total {accepted}")]
+    VoucherRegressed { cumulative: u128, accepted: u128 },
+    #[error("cumulative amount {cumulative} exceeds the warrant cap {cap}")]
+    CapExceeded { cumulative: u128, cap: u128 },
+    #[error("the warrant chain must not be empty")]
+    EmptyChain,
+    #[error(transparent)]
+    Budget(#[from] BudgetError),
+}
+
+/// A streaming payment authorized by one warrant chain.
+#[derive(Clone, Debug)]
+pub struct PaymentSession {
+    id: String,
+    authorization: VerifiedAuthorization,
+    chain: WarrantChain,
+    cumulative: u128,
+    ticks: u64,
+    latest_payload: String,
+    state: SessionState,
+    /// Budget held for the accepted ticks, one reservation per increment.
+    reservations: Vec<BudgetReservation>,
+}
+
+impl PaymentSession {
+    /// Opens a session with a zero cumulative total.
+    ///
+    /// `authorization` is the result of the initial `/verify`; revocation is
+    /// re-checked on every tick from here on.
+    pub fn open(
+        id: impl Into<String>,
+        authorization: VerifiedAuthorization,
+        chain: WarrantChain,
+    ) -> Result<Self, SessionError> {
+        if chain.leaf().is_none() {
+            return Err(SessionError::EmptyChain);
+        }
+        Ok(Self {
+            id: id.into(),
+            authorization,
+            chain,
+            cumulative: 0,
+            ticks: 0,
+            latest_payload: String::new(),
+            state: SessionState::Open,
+            reservations: Vec::new(),
+        })
+    }
+
+    #[must_use]
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    #[must_use]
+    pub const fn authorization(&self) -> &VerifiedAuthorization {
+        &self.authorization
+    }
+
+    #[must_use]
+    pub const fn chain(&self) -> &WarrantChain {
+        &self.chain
+    }
+
+    /// The accepted cumulative total.
+    #[must_use]
+    pub const fn cumulative(&self) -> u128 {
+        self.cumulative
+    }
+
+    /// Number of accepted ticks.
+    #[must_use]
+    pub const fn ticks(&self) -> u64 {
+        self.ticks
+    }
+
+    /// The rail payload of the latest accepted tick: the voucher to redeem,
+    /// authorizing the whole cumulative total.
+    #[must_use]
+    pub fn latest_payload(&self) -> &str {
+        &self.latest_payload
+    }
+
+    #[must_use]
+    pub const fn state(&self) -> &SessionState {
+        &self.state
+    }
+
+    /// Budget reservations held for the accepted ticks (empty when the chain
+    /// declares no budget, or outside a [`SessionManager`]).
+    #[must_use]
+    pub fn reservations(&self) -> &[BudgetReservation] {
+        &self.reservations
+    }
+
+    /// The cap every cumulative total is checked against: the leaf's
+    /// `max_per_charge`, since the session settles as a single charge.
+    #[must_use]
+    pub fn cap(&self) -> u128 {
+        self.chain.leaf().map_or(0, |leaf| leaf.payment.max_per_charge)
+    }
+
+    /// The authorization settled on close: the opening authorization with
+    /// the cumulative total as its amount.
+    #[must_use]
+    pub fn settlement_authorization(&self) -> VerifiedAuthorization {
+        VerifiedAuthorization { amount: self.cumulative, ..self.authorization.clone() }
+    }
+
+    /// Re-checks revocation across the chain (issuers, agent ids, the leaf
+    /// and its holder, the payment subject) and the leaf's TTL, terminating
+    /// the session on failure.
+    pub fn check(
+        &mut self,
+        revocation: &dyn RevocationCheck,
+        now_ms: u64,
+    ) -> Result<(), SessionError> {
+        self.ensure_open()?;
+        let leaf = self.chain.leaf().ok_or(SessionError::EmptyChain)?;
+        let result =
+            verify_chain_revocation(revocation, &self.chain, &self.authorization.payment_subject)
+                .and(if leaf.expires_at < now_ms / 1000 {
+                    Err(AuthorizationError::WarrantExpired { expires_at: leaf.expires_at })
+                } else {
+                    Ok(())
+                });
+        result.map_err(|error| {
+            let reason = error.to_string();
+            self.state = SessionState::Terminated { reason: reason.clone() };
+            SessionError::Terminated(reason)
+        })
+    }
+
+    /// Applies one update after [`check`](Self::check) and the cap check.
+    /// Returns the new cumulative total.
+    pub fn tick(
+        &mut self,
+        update: SessionUpdate,
+        revocation: &dyn RevocationCheck,
+        now_ms: u64,
+    ) -> Result<u128, SessionError> {
+        let (cumulative, payload) = self.next_total(update, revocation, now_ms)?;
+        self.accept(cumulative, payload);
+        Ok(cumulative)
+    }
+
+    /// Checks one update and returns the cumulative total and payload it
+    /// would move the session to.
+    fn next_total(
+        &mut self,
+        update: SessionUpdate,
+        revocation: &dyn RevocationCheck,
+        now_ms: u64,
+    ) -> Result<(u128, String), SessionError> {
+        self.check(revocation, now_ms)?;
+        let (cumulative, payload) = match update {
+            SessionUpdate::Voucher { cumulative_amount, payload } => {
+                if cumulative_amount < self.cumulative {
+                    return Err(SessionError::VoucherRegressed {
+                        cumulative: cumulative_amount,
+                        accepted: self.cumulative,
+                    });
+                }
+                (cumulative_amount, payload)
+            }
+            SessionUpdate::Charge { amount, payload } => {
+                (self.cumulative.saturating_add(amount), payload)
+            }
+        };
+        let cap = self.cap();
+        if cumulative > cap {
+            return Err(SessionError::CapExceeded { cumulative, cap });
+        }
+        Ok((cumulative, payload))
+    }
+
+    fn accept(&mut self, cumulative: u128, payload: String) {
+        self.cumulative = cumulative;
+        self.latest_payload = payload;
+        self.ticks += 1;
+    }
+
+    fn ensure_open(&self) -> Result<(), SessionError> {
+        match &self.state {
+            SessionState::Open => Ok(()),
+            SessionState::Closed => Err(SessionError::Closed),
+            SessionState::Terminated { reason } => Err(SessionError::Terminated(reason.clone())),
+        }
+    }
+}
+
+/// The Facilitator's open sessions, ticked and closed against one
+/// [`SettlementService`].
+pub struct SessionManager<R, P, A> {
+    settlement: SettlementService<R, P, A>,
+    sessions: Mutex<BTreeMap<String, PaymentSession>>,
+}
+
+impl<R, P, A> SessionManager<R, P, A>
+where
+    R: RevocationCheck,
+    P: PaymentSubjectResolver,
+    A: RailAdapter,
+{
+    #[must_use]
+    pub const fn new(settlement: SettlementService<R, P, A>) -> Self {
+        Self { settlement, sessions: Mutex::new(BTreeMap::new()) }
+    }
+
+    #[must_use]
+    pub const fn settlement(&self) -> &SettlementService<R, P, A> {
+        &self.settlement
+    }
+
+    /// Opens a session after an initial revocation and TTL check.
+    pub fn open(
+        &self,
+        id: impl Into<String>,
+        authorization: VerifiedAuthorization,
+        chain: WarrantChain,
+        now_ms: u64,
+    ) -> Result<(), SessionError> {
+        let mut session = PaymentSession::open(id, authorization, chain)?;
+        session.check(&self.settlement.revocation, now_ms)?;
+        let mut sessions = self.sessions.lock().unwrap_or_else(PoisonError::into_inner);
+        if sessions.contains_key(session.id()) {
+            return Err(SessionError::DuplicateSession(session.id.clone()));
+        }
+        sessions.insert(session.id.clone(), session);
+        Ok(())
+    }
+
+    /// Applies one tick, reserving its increment against the chain's
+    /// budgets first; an update the budget cannot cover is rejected and
+    /// leaves the session open. A terminated session is dropped (releasing
+    /// its reservations), so later ticks report it as unknown.
+    pub fn tick(&self, id: &str, update: SessionUpdate, now_ms: u64) -> Result<u128, SessionError> {
+        let mut sessions = self.sessions.lock().unwrap_or_else(PoisonError::into_inner);
+        let session =
+            sessions.get_mut(id).ok_or_else(|| SessionError::UnknownSession(id.to_string()))?;
+        let (cumulative, payload) =
+            match session.next_total(update, &self.settlement.revocation, now_ms) {
+                Ok(next) => next,
+                Err(error) => {
+                    if matches!(error, SessionError::Terminated(_)) &&
+                        let Some(session) = sessions.remove(id)
+                    {
+                        self.settlement.finish_budget(session.reservations(), false);
+                    }
+                    return Err(error);
+                }
+            };
+        let increment = cumulative - session.cumulative;
+        if increment > 0 &&
+            let Some(reservation) =
+                self.settlement.reserve_budget(session.chain(), increment, now_ms)?
+        {
+            session.reservations.push(reservation);
+        }
+        session.accept(cumulative, payload);
+        Ok(cumulative)
+    }
+
+    /// Closes a session and settles its cumulative total. Returns `None`
+    /// when nothing was consumed.
+    pub fn close(&self, id: &str, now_ms: u64) -> Result<Option<SettlementOutcome>, SessionError> {
+        let mut session = self
+            .sessions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(id)
+            .ok_or_else(|| SessionError::UnknownSession(id.to_string()))?;
+        if let Err(error) = session.check(&self.settlement.revocation, now_ms) {
+            self.settlement.finish_budget(session.reservations(), false);
+            return Err(error);
+        }
+        session.state = SessionState::Closed;
+        if session.cumulative == 0 {
+            return Ok(None);
+        }
+        Ok(Some(self.settlement.settle_session(&session, now_ms)))
+    }
+
+    /// Terminates every session whose leaf is revoked or expired and returns
+    /// the terminated sessions.
+    pub fn terminate_revoked(&self, now_ms: u64) -> Vec<PaymentSession> {
+        let mut sessions = self.sessions.lock().unwrap_or_else(PoisonError::into_inner);
+        let mut terminated = Vec::new();
+        sessions.retain(|_, session| {
+            if session.check(&self.settlement.revocation, now_ms).is_ok() {
+                return true;
+            }
+            self.settlement.finish_budget(session.reservations(), false);
+            terminated.push(session.clone());
+            false
+        });
+        terminated
+    }
+
+    /// A snapshot of an open session.
+    #[must_use]
+    pub fn session(&self, id: &str) -> Option<PaymentSession> {
+        self.sessions.lock().unwrap_or_else(PoisonError::into_inner).get(id).cloned()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::expect_used)]
+
+    use std::sync::{Arc, RwLock};
+
+    use ledgerflow_core::{
+        MerchantConstraint, PaymentConstraint, PaymentRail, PaymentSubjectKind, PaymentSubjectRef,
+        RevocationDecision, SignerRef, SigningKeyPair, WarrantBuilder,
+    };
+
+    use super::*;
+    use crate::{
+        RailKind, SubjectResolutionError,
+        rails::{RailError, RailQuote, SettlementReceipt, VerificationResult},
+        subject::ResolvedSubject,
+    };
+
+    /// Revoked warrant ids and issuer keys, mutable while the manager holds
+    /// the store.
+    #[derive(Clone, Debug, Default)]
+    struct SharedRevocations(Arc<RwLock<Vec<Vec<u8>>>>);
+
+    impl SharedRevocations {
+        fn revoke(&self, warrant_id_or_issuer_key: &[u8]) {
+            self.0.write().expect("lock").push(warrant_id_or_issuer_key.to_vec());
+        }
+    }
+
+    impl RevocationCheck for SharedRevocations {
+        fn check_warrant(&self, warrant_id: &[u8]) -> RevocationDecision {
+            if self.0.read().expect("lock").iter().any(|id| id == warrant_id) {
+                RevocationDecision::RevokedWarrant
+            } else {
+                RevocationDecision::Ok
+            }
+        }
+
+        fn check_holder(&self, _holder: &SignerRef) -> RevocationDecision {
+            RevocationDecision::Ok
+        }
+
+        fn check_issuer(&self, issuer: &SignerRef) -> RevocationDecision {
+            if self.0.read().expect("lock").contains(&issuer.public_key) {
+                RevocationDecision::RevokedIssuer
+            } else {
+                RevocationDecision::Ok
+            }
+        }
+    }
+
+    struct AcceptAllResolver;
+
+    impl PaymentSubjectResolver for AcceptAllResolver {
+        fn resolve(
+            &self,
+            _authorization: &VerifiedAuthorization,
+        ) -> Result<ResolvedSubject, SubjectResolutionError> {
+            Ok(ResolvedSubject { rail: RailKind::Evm, value: "0xpayee".to_string() })
+        }
+    }
+
+    /// Echoes the settled amount and payload back in the receipt. Like the
+    /// EVM and Solana adapters, it refuses a payload that does not authorize
+    /// the settled amount.
+    struct EchoAdapter;
+
+    impl RailAdapter for EchoAdapter {
+        fn kind(&self) -> RailKind {
+            RailKind::Evm
+        }
+
+        fn supports(&self, _subject: &ResolvedSubject) -> bool {
+            true
+        }
+
+        fn quote(&self, _authorization: &VerifiedAuthorization) -> Result<RailQuote, RailError> {
+            Err(RailError::SettlementFailed("unused".to_string()))
+        }
+
+        fn settle(
+            &self,
+            authorization: &VerifiedAuthorization,
+        ) -> Result<SettlementReceipt, RailError> {
+            self.settle_with_payload(authorization, "")
+        }
+
+        fn settle_with_payload(
+            &self,
+            authorization: &VerifiedAuthorization,
+            payload: &str,
+        ) -> Result<SettlementReceipt, RailError> {
+            if payload != format!("voucher-{}", authorization.amount) {
+                return Err(RailError::SettlementFailed(format!(
+                    "payload {payload} does not authorize {}",
+                    authorization.amount
+                )));
+            }
+            Ok(SettlementReceipt {
+                rail: RailKind::Evm,
+                transaction_id: payload.to_string(),
+                settled_amount: authorization.amount,
+                asset: authorization.asset.clone(),
+            })
+        }
+
+        fn verify(&self, _receipt: &SettlementReceipt) -> Result<VerificationResult, RailError> {
+            Ok(VerificationResult { verified: true, confirmations: 1 })
+        }
+    }
+
+    fn chain() -> WarrantChain {
+        let issuer = SigningKeyPair::from_bytes(&[0xa1; 32]);
+        let holder = SigningKeyPair::from_bytes(&[0xa2; 32]);
+        WarrantChain::single(
+            WarrantBuilder::new(1_000)
+                .ttl_secs(60)
+                .issuer(issuer.signer_ref())
+                .holder(holder.signer_ref())
+                .merchant(MerchantConstraint::with_ids(vec!["merchant-a".to_string()]))
+                .payment(PaymentConstraint::new(500))
+                .sign_with(&issuer, [0_u8; 8]),
+        )
+    }
+
+    /// A chain whose leaf declares a lifetime budget on `ledger-a`.
+    fn budgeted_chain(lifetime: u128) -> WarrantChain {
+        let issuer = SigningKeyPair::from_bytes(&[0xa1; 32]);
+        let holder = SigningKeyPair::from_bytes(&[0xa3; 32]);
+        let policy = ledgerflow_core::BudgetPolicy::new("ledger-a").with_lifetime(lifetime);
+        WarrantChain::single(
+            WarrantBuilder::new(1_000)
+                .ttl_secs(60)
+                .issuer(issuer.signer_ref())
+                .holder(holder.signer_ref())
+                .merchant(MerchantConstraint::with_ids(vec!["merchant-a".to_string()]))
+                .payment(PaymentConstraint::new(500))
+                .extension(
+                    ledgerflow_core::BUDGET_EXTENSION,
+                    policy.encode_cbor().expect("encode budget"),
+                )
+                .sign_with(&issuer, [0_u8; 8]),
+        )
+    }
+
+    fn authorization(chain: &WarrantChain) -> VerifiedAuthorization {
+        let leaf = chain.leaf().expect("leaf").clone();
+        VerifiedAuthorization {
+            merchant_id: "merchant-a".to_string(),
+            tool_name: "stream".to_string(),
+            payment_subject: PaymentSubjectRef::new(
+                PaymentSubjectKind::Caip10,
+                "caip10:eip155:8453:0xabc123",
+            ),
+            holder: leaf.holder.clone(),
+            root_warrant: leaf.clone(),
+            leaf_warrant: leaf,
+            chain_len: 1,
+            amount: 10,
+            asset: "USDC".to_string(),
+            scheme: "exact".to_string(),
+            payee_id: "merchant-a".to_string(),
+            rail: PaymentRail::Onchain,
+            challenge_id: "challenge-1".to_string(),
+            request_hash: "sha256:req".to_string(),
+            accepted_hash: "sha256:acc".to_string(),
+            warrant_digest: "sha256:w".to_string(),
+        }
+    }
+
+    fn manager(
+        revocations: &SharedRevocations,
+    ) -> SessionManager<SharedRevocations, AcceptAllResolver, EchoAdapter> {
+        SessionManager::new(SettlementService::new(
+            revocations.clone(),
+            AcceptAllResolver,
+            vec![EchoAdapter],
+        ))
+    }
+
+    fn voucher(cumulative_amount: u128) -> SessionUpdate {
+        SessionUpdate::Voucher {
+            cumulative_amount,
+            payload: format!("voucher-{cumulative_amount}"),
+        }
+    }
+
+    #[test]
+    fn ticks_accumulate_and_close_settles_the_total() {
+        let revocations = SharedRevocations::default();
+        let manager = manager(&revocations);
+        let chain = chain();
+        manager.open("s-1", authorization(&chain), chain, 1_000).expect("open");
+
+        assert_eq!(manager.tick("s-1", voucher(100), 2_000).expect("tick"), 100);
+        let charge = SessionUpdate::Charge { amount: 50, payload: "voucher-150".to_string() };
+        assert_eq!(manager.tick("s-1", charge, 3_000).expect("tick"), 150);
+        assert!(matches!(
+            manager.tick("s-1", voucher(120), 4_000),
+            Err(SessionError::VoucherRegressed { cumulative: 120, accepted: 150 })
+        ));
+        assert!(matches!(
+            manager.tick("s-1", voucher(501), 4_000),
+            Err(SessionError::CapExceeded { cumulative: 501, cap: 500 })
+        ));
+        assert_eq!(manager.session("s-1").expect("session").ticks(), 2);
+
+        let outcome = manager.close("s-1", 5_000).expect("close").expect("outcome");
+        let receipt = outcome.receipt.expect("receipt");
+        assert_eq!(receipt.settled_amount, 150);
+        assert_eq!(receipt.transaction_id, "voucher-150");
+        assert!(matches!(
+            manager.tick("s-1", voucher(200), 6_000),
+            Err(SessionError::UnknownSession(_))
+        ));
+    }
+
+    #[test]
+    fn charges_carry_payloads_for_the_cumulative_total() {
+        let revocations = SharedRevocations::default();
+        let manager = manager(&revocations);
+        let chain = chain();
+        manager.open("s-1", authorization(&chain), chain.clone(), 1_000).expect("open");
+        manager.open("s-2", authorization(&chain), chain, 1_000).expect("open");
+        for (amount, payload) in [(40, "voucher-40"), (60, "voucher-100"), (25, "voucher-125")] {
+            let charge = SessionUpdate::Charge { amount, payload: payload.to_string() };
+            manager.tick("s-1", charge, 2_000).expect("tick");
+        }
+        for amount in [40, 60] {
+            // A payload covering only the increment cannot redeem the total.
+            let charge = SessionUpdate::Charge { amount, payload: format!("voucher-{amount}") };
+            manager.tick("s-2", charge, 2_000).expect("tick");
+        }
+
+        let outcome = manager.close("s-1", 3_000).expect("close").expect("outcome");
+        let receipt = outcome.receipt.expect("receipt");
+        assert_eq!(receipt.settled_amount, 125);
+        assert_eq!(receipt.transaction_id, "voucher-125");
+        let outcome = manager.close("s-2", 3_000).expect("close").expect("outcome");
+        assert!(outcome.receipt.is_none());
+    }
+
+    #[test]
+    fn revocation_takes_effect_at_the_next_tick() {
+        let revocations = SharedRevocations::default();
+        let manager = manager(&revocations);
+        let chain = chain();
+        let warrant_id = chain.leaf().expect("leaf").id.clone();
+        manager.open("s-1", authorization(&chain), chain.clone(), 1_000).expect("open");
+        manager.open("s-2", authorization(&chain), chain, 1_000).expect("open");
+        manager.tick("s-1", voucher(100), 2_000).expect("tick");
+
+        revocations.revoke(&warrant_id);
+        assert!(matches!(
+            manager.tick("s-1", voucher(200), 3_000),
+            Err(SessionError::Terminated(_))
+        ));
+        assert!(manager.session("s-1").is_none());
+
+        // The facilitator closes the remaining stream without waiting for a tick.
+        let terminated = manager.terminate_revoked(3_000);
+        assert_eq!(terminated.len(), 1);
+        assert_eq!(terminated[0].id(), "s-2");
+        assert!(matches!(terminated[0].state(), SessionState::Terminated { .. }));
+        assert!(manager.close("s-2", 4_000).is_err());
+    }
+
+    #[test]
+    fn issuer_revocation_terminates_sessions() {
+        let revocations = SharedRevocations::default();
+        let manager = manager(&revocations);
+        let chain = chain();
+        let issuer = chain.leaf().expect("leaf").issuer.public_key.clone();
+        manager.open("s-1", authorization(&chain), chain, 1_000).expect("open");
+        manager.tick("s-1", voucher(100), 2_000).expect("tick");
+
+        revocations.revoke(&issuer);
+        assert!(matches!(
+            manager.tick("s-1", voucher(200), 3_000),
+            Err(SessionError::Terminated(_))
+        ));
+    }
+
+    #[test]
+    fn expired_warrants_cannot_open_or_close_sessions() {
+        let revocations = SharedRevocations::default();
+        let manager = manager(&revocations);
+        let chain = chain();
+        assert!(matches!(
+            manager.open("s-1", authorization(&chain), chain.clone(), 70_000_000),
+            Err(SessionError::Terminated(_))
+        ));
+
+        manager.open("s-2", authorization(&chain), chain, 1_000).expect("open");
+        manager.tick("s-2", voucher(100), 2_000).expect("tick");
+        assert!(matches!(manager.close("s-2", 70_000_000), Err(SessionError::Terminated(_))));
+    }
+
+    #[test]
+    fn ticks_reserve_the_budget_that_close_commits() {
+        use crate::budget::{BudgetLedger, BudgetScope, InMemoryBudgetLedger};
+
+        let ledger = InMemoryBudgetLedger::new("ledger-a");
+        let manager = SessionManager::new(
+            SettlementService::new(
+                SharedRevocations::default(),
+                AcceptAllResolver,
+                vec![EchoAdapter],
+            )
+            .with_budget_ledger(Arc::new(ledger.clone())),
+        );
+        let chain = budgeted_chain(150);
+        let warrant_id = chain.leaf().expect("leaf").id.clone();
+        manager.open("s-1", authorization(&chain), chain.clone(), 1_000).expect("open");
+        manager.open("s-2", authorization(&chain), chain.clone(), 1_000).expect("open");
+
+        // s-1's open stream holds its total against the budget.
+        manager.tick("s-1", voucher(100), 2_000).expect("tick");
+        assert!(matches!(
+            manager.tick("s-2", voucher(100), 2_000),
+            Err(SessionError::Budget(BudgetError::Exceeded { .. }))
+        ));
+        assert_eq!(manager.session("s-2").expect("still open").cumulative(), 0);
+        manager.tick("s-2", voucher(50), 2_000).expect("within budget");
+
+        manager.close("s-1", 3_000).expect("close").expect("outcome");
+        assert_eq!(ledger.spent(&warrant_id, ledgerflow_core::BudgetPeriod::Lifetime, 3), 100);
+
+        // Terminating s-2 releases its reservation without charging it.
+        assert_eq!(manager.terminate_revoked(70_000_000).len(), 1);
+        assert_eq!(ledger.spent(&warrant_id, ledgerflow_core::BudgetPeriod::Lifetime, 3), 100);
+        let scopes = BudgetScope::collect(&chain).expect("scopes");
+        ledger.reserve(&scopes, 50, 3).expect("released budget is available");
+    }
+}
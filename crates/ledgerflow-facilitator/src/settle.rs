@@ -3,17 +3,24 @@
 //! The verify step is only a pre-check. Settlement MUST re-verify revocation,
 //! TTL, PoP freshness, and the payment cap atomically before touching any
 //! rail, closing the verify→settle TOCTOU window (design §8.1).
+//!
+//! When the presented chain declares an accounting-point budget, the charge
+//! is reserved against the configured
+//! [`BudgetLedger`](crate::budget::BudgetLedger) before the rail call
+//! and committed or released afterwards (reserve → settle → commit/release).
 
 use ledgerflow_core::{
     AuthorizationContext, AuthorizationError, PopProof, RevocationCheck, VerifiedAuthorization,
-    WarrantChain, verify_freshness,
+    WarrantChain, verify_chain_revocation, verify_freshness,
 };
 
 use crate::{
+    budget::{BudgetError, BudgetReservation, BudgetScope, SharedBudgetLedger},
     outcome::SettlementOutcome,
     rails::RailAdapter,
     reputation::ReputationReporter,
     routing::RoutingError,
+    session::PaymentSession,
     subject::{PaymentSubjectResolver, ResolvedSubject},
 };
 
@@ -28,6 +35,9 @@ pub struct SettleRequest<'a> {
     pub proof: &'a PopProof,
     /// The request context.
     pub context: &'a AuthorizationContext,
+    /// The agent-signed rail payload (x402 `payload`); empty when the rail
+    /// settles from the authorization alone.
+    pub settlement_payload: &'a str,
     /// Verification timestamp (unix milliseconds).
     pub now_ms: u64,
 }
@@ -41,6 +51,9 @@ pub struct SettlementService<R, P, A> {
     /// Optional EIP-8004 reputation reporter invoked after successful
     /// settlement. Reporting never affects settlement outcomes.
     pub reputation: Option<ReputationReporter>,
+    /// Optional accounting-point budget ledger. Chains declaring a budget
+    /// are rejected (fail-closed) when none is configured.
+    pub budget: Option<SharedBudgetLedger>,
 }
 
 impl<R, P, A> SettlementService<R, P, A>
@@ -52,7 +65,7 @@ where
     /// Creates a new settlement service without reputation reporting.
     #[must_use]
     pub const fn new(revocation: R, resolver: P, adapters: Vec<A>) -> Self {
-        Self { revocation, resolver, adapters, reputation: None }
+        Self { revocation, resolver, adapters, reputation: None, budget: None }
     }
 
     /// Attaches an EIP-8004 reputation reporter (builder style).
@@ -66,6 +79,37 @@ where
         self
     }
 
+    /// Attaches the accounting-point budget ledger (builder style).
+    ///
+    /// Only budgets whose [`BudgetPolicy::ledger`] names this ledger's
+    /// accounting point are settled; any other budget fails closed.
+    ///
+    /// [`BudgetPolicy::ledger`]: ledgerflow_core::BudgetPolicy::ledger
+    #[must_use]
+    pub fn with_budget_ledger(mut self, ledger: SharedBudgetLedger) -> Self {
+        self.budget = Some(ledger);
+        self
+    }
+
+    /// Returns a copy of this service that re-verifies revocation against
+    /// `revocation` instead (e.g. a tenant-scoped view), sharing the
+    /// resolver, adapters, reputation reporter, and budget ledger.
+    #[must_use]
+    pub fn with_revocation<R2>(&self, revocation: R2) -> SettlementService<R2, P, A>
+    where
+        R2: RevocationCheck,
+        P: Clone,
+        A: Clone,
+    {
+        SettlementService {
+            revocation,
+            resolver: self.resolver.clone(),
+            adapters: self.adapters.clone(),
+            reputation: self.reputation.clone(),
+            budget: self.budget.clone(),
+        }
+    }
+
     /// Settles a verified authorization after atomic re-verification.
     pub fn settle(&self, request: &SettleRequest<'_>) -> SettlementOutcome {
         // 1. Atomic re-verify: revocation + TTL + PoP freshness + amount cap.
@@ -99,22 +143,165 @@ where
             return SettlementOutcome::failed(error.to_string());
         }
 
-        // 4. Settle.
-        match adapter.settle(request.authorization) {
+        // 4–5. Reserve, settle, then commit or release.
+        self.settle_on(
+            adapter,
+            request.authorization,
+            request.chain,
+            request.settlement_payload,
+            request.now_ms,
+        )
+    }
+
+    /// Settles the cumulative amount of a closed payment session as one
+    /// charge (design §6.6), redeeming the session's latest payload, which
+    /// authorizes that total.
+    ///
+    /// Revocation is re-checked across the chain, TTL and the cap against
+    /// the leaf; PoP freshness is not, since the session was authorized once
+    /// at open and every tick since re-checked revocation. The budget was
+    /// reserved tick by tick ([`PaymentSession::reservations`]); those
+    /// reservations are committed when the rail settles and released
+    /// otherwise.
+    pub fn settle_session(&self, session: &PaymentSession, now_ms: u64) -> SettlementOutcome {
+        let authorization = session.settlement_authorization();
+        let failed = |reason: String| {
+            self.finish_budget(session.reservations(), false);
+            SettlementOutcome::failed(reason)
+        };
+        let reverify = || -> Result<(), AuthorizationError> {
+            let leaf = session.chain().leaf().ok_or(AuthorizationError::EmptyChain)?;
+            verify_chain_revocation(
+                &self.revocation,
+                session.chain(),
+                &authorization.payment_subject,
+            )?;
+            if leaf.expires_at < now_ms / 1000 {
+                return Err(AuthorizationError::WarrantExpired { expires_at: leaf.expires_at });
+            }
+            if authorization.amount > leaf.payment.max_per_charge {
+                return Err(AuthorizationError::PaymentAmountExceeded {
+                    amount: authorization.amount,
+                    limit: leaf.payment.max_per_charge,
+                });
+            }
+            Ok(())
+        };
+        if let Err(error) = reverify() {
+            return failed(error.to_string());
+        }
+
+        let resolved = match self.resolve(&authorization) {
+            Ok(subject) => subject,
+            Err(error) => return failed(error.to_string()),
+        };
+        let Some(adapter) = self.adapters.iter().find(|adapter| adapter.supports(&resolved)) else {
+            return failed(RoutingError::NoCompatibleRail.to_string());
+        };
+        if let Err(error) = reverify() {
+            return failed(error.to_string());
+        }
+        self.settle_reserved(
+            adapter,
+            &authorization,
+            session.latest_payload(),
+            session.reservations(),
+        )
+    }
+
+    fn settle_on(
+        &self,
+        adapter: &A,
+        authorization: &VerifiedAuthorization,
+        chain: &WarrantChain,
+        settlement_payload: &str,
+        now_ms: u64,
+    ) -> SettlementOutcome {
+        let reservation = match self.reserve_budget(chain, authorization.amount, now_ms) {
+            Ok(reservation) => reservation,
+            Err(error) => return SettlementOutcome::failed(error.to_string()),
+        };
+        self.settle_reserved(adapter, authorization, settlement_payload, reservation.as_slice())
+    }
+
+    /// Settles on `adapter`, then commits `reservations` on success or
+    /// releases them on failure.
+    fn settle_reserved(
+        &self,
+        adapter: &A,
+        authorization: &VerifiedAuthorization,
+        settlement_payload: &str,
+        reservations: &[BudgetReservation],
+    ) -> SettlementOutcome {
+        match adapter.settle_with_payload(authorization, settlement_payload) {
             Ok(receipt) => {
+                self.finish_budget(reservations, true);
                 if let Some(reporter) = &self.reputation {
-                    reporter.report_settlement(request.authorization, &receipt);
+                    reporter.report_settlement(authorization, &receipt);
                 }
                 SettlementOutcome::settled(receipt)
             }
-            Err(error) => SettlementOutcome::failed(error.to_string()),
+            Err(error) => {
+                self.finish_budget(reservations, false);
+                SettlementOutcome::failed(error.to_string())
+            }
+        }
+    }
+
+    /// Reserves `amount` against every budget declared in `chain`; `None`
+    /// when the chain declares none.
+    pub(crate) fn reserve_budget(
+        &self,
+        chain: &WarrantChain,
+        amount: u128,
+        now_ms: u64,
+    ) -> Result<Option<BudgetReservation>, BudgetError> {
+        let scopes = BudgetScope::collect(chain)?;
+        if scopes.is_empty() {
+            return Ok(None);
+        }
+        let ledger = self.budget.as_ref().ok_or(BudgetError::NoLedger)?;
+        let accounting_point = ledger.accounting_point();
+        if let Some(foreign) = scopes.iter().find(|scope| scope.policy.ledger != accounting_point) {
+            return Err(BudgetError::ForeignLedger {
+                expected: accounting_point.to_string(),
+                declared: foreign.policy.ledger.clone(),
+            });
+        }
+        ledger.reserve(&scopes, amount, now_ms / 1000).map(Some)
+    }
+
+    /// Commits or releases `reservations`.
+    pub(crate) fn finish_budget(&self, reservations: &[BudgetReservation], commit: bool) {
+        let Some(ledger) = &self.budget else {
+            return;
+        };
+        for reservation in reservations {
+            let result =
+                if commit { ledger.commit(reservation) } else { ledger.release(reservation) };
+            // The rail outcome is authoritative; a bookkeeping failure here is
+            // surfaced to operators but does not rewrite the settlement result.
+            if let Err(error) = result {
+                tracing::warn!(
+                    target: "ledgerflow::budget",
+                    error = %error,
+                    reservation = reservation.id,
+                    commit,
+                    "failed to finalize budget reservation"
+                );
+            }
         }
     }
 
     fn reverify(&self, request: &SettleRequest<'_>) -> Result<(), AuthorizationError> {
         let leaf = request.chain.leaf().ok_or(AuthorizationError::EmptyChain)?;
-        // Revocation (online, persistent).
-        self.revocation.verify(&leaf.id, &leaf.holder)?;
+        // Revocation (online, persistent): every node's issuer and agent
+        // id, the leaf warrant and holder, and the payment subject.
+        verify_chain_revocation(
+            &self.revocation,
+            request.chain,
+            &request.authorization.payment_subject,
+        )?;
         // TTL.
         let now_secs = request.now_ms / 1000;
         if leaf.expires_at < now_secs {
@@ -281,7 +468,14 @@ mod tests {
         proof: &'a PopProof,
         context: &'a AuthorizationContext,
     ) -> SettleRequest<'a> {
-        SettleRequest { authorization, chain, proof, context, now_ms: 5_000 }
+        SettleRequest {
+            authorization,
+            chain,
+            proof,
+            context,
+            settlement_payload: "",
+            now_ms: 5_000,
+        }
     }
 
     #[test]
@@ -324,6 +518,108 @@ mod tests {
         assert!(sink.0.lock().expect("lock").is_empty());
     }
 
+    fn budgeted_authorization(ledger: &str, lifetime: u128) -> VerifiedAuthorization {
+        let issuer = SigningKeyPair::from_bytes(&[0x91; 32]);
+        let policy = ledgerflow_core::BudgetPolicy::new(ledger).with_lifetime(lifetime);
+        let leaf = ledgerflow_core::WarrantBuilder::new(1_000)
+            .issuer(issuer.signer_ref())
+            .holder(SigningKeyPair::from_bytes(&[0x92; 32]).signer_ref())
+            .merchant(MerchantConstraint::with_ids(vec!["merchant-a".to_string()]))
+            .resource(ResourceConstraint::default())
+            .payment(PaymentConstraint::new(1_000))
+            .extension(
+                ledgerflow_core::BUDGET_EXTENSION,
+                policy.encode_cbor().expect("encode budget"),
+            )
+            .sign_with(&issuer, [0_u8; 8]);
+        let mut authorization = authorization(false);
+        authorization.leaf_warrant = leaf.clone();
+        authorization.root_warrant = leaf;
+        authorization
+    }
+
+    #[test]
+    fn budgeted_settlement_commits_and_then_exhausts() {
+        let ledger = crate::budget::InMemoryBudgetLedger::new("ledger-a");
+        let service = SettlementService::new(
+            InMemoryRevocationCheck::new(),
+            AcceptAllResolver,
+            vec![ScriptedAdapter { fail: false }],
+        )
+        .with_budget_ledger(std::sync::Arc::new(ledger.clone()));
+
+        let authorization = budgeted_authorization("ledger-a", 150);
+        let chain = WarrantChain::single(authorization.leaf_warrant.clone());
+        let proof = sample_proof();
+        let context = sample_context();
+        let first = service.settle(&request(&authorization, &chain, &proof, &context));
+        assert_eq!(first.status, crate::outcome::SettlementStatus::Settled);
+        assert_eq!(
+            ledger.spent(
+                &authorization.leaf_warrant.id,
+                ledgerflow_core::BudgetPeriod::Lifetime,
+                5
+            ),
+            100
+        );
+
+        let second = service.settle(&request(&authorization, &chain, &proof, &context));
+        assert_eq!(second.status, crate::outcome::SettlementStatus::Failed);
+        assert!(second.reason.as_deref().is_some_and(|reason| reason.contains("lifetime")));
+    }
+
+    #[test]
+    fn failed_rail_releases_budget_reservation() {
+        let ledger = crate::budget::InMemoryBudgetLedger::new("ledger-a");
+        let service = SettlementService::new(
+            InMemoryRevocationCheck::new(),
+            AcceptAllResolver,
+            vec![ScriptedAdapter { fail: true }],
+        )
+        .with_budget_ledger(std::sync::Arc::new(ledger.clone()));
+
+        let authorization = budgeted_authorization("ledger-a", 100);
+        let chain = WarrantChain::single(authorization.leaf_warrant.clone());
+        let proof = sample_proof();
+        let context = sample_context();
+        for _ in 0..2 {
+            let outcome = service.settle(&request(&authorization, &chain, &proof, &context));
+            assert_eq!(outcome.status, crate::outcome::SettlementStatus::Failed);
+            assert_eq!(outcome.reason.as_deref(), Some("rail settlement failed: forced"));
+        }
+        assert_eq!(
+            ledger.spent(
+                &authorization.leaf_warrant.id,
+                ledgerflow_core::BudgetPeriod::Lifetime,
+                5
+            ),
+            0
+        );
+    }
+
+    #[test]
+    fn budget_without_matching_ledger_fails_closed() {
+        let authorization = budgeted_authorization("ledger-b", 1_000);
+        let chain = WarrantChain::single(authorization.leaf_warrant.clone());
+        let proof = sample_proof();
+        let context = sample_context();
+
+        let unconfigured = SettlementService::new(
+            InMemoryRevocationCheck::new(),
+            AcceptAllResolver,
+            vec![ScriptedAdapter { fail: false }],
+        );
+        let outcome = unconfigured.settle(&request(&authorization, &chain, &proof, &context));
+        assert_eq!(outcome.status, crate::outcome::SettlementStatus::Failed);
+
+        let foreign = unconfigured.with_budget_ledger(std::sync::Arc::new(
+            crate::budget::InMemoryBudgetLedger::new("ledger-a"),
+        ));
+        let outcome = foreign.settle(&request(&authorization, &chain, &proof, &context));
+        assert_eq!(outcome.status, crate::outcome::SettlementStatus::Failed);
+        assert!(outcome.reason.as_deref().is_some_and(|reason| reason.contains("ledger-b")));
+    }
+
     // Minimal PoP/context fixtures; settle re-verification only checks
     // freshness bounds and the amount cap.
     fn sample_proof() -> PopProof {
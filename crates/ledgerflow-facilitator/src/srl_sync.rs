@@ -6,28 +6,202 @@
 //! [`SignedRevocationList`] / [`SrlState`] domain (in `ledgerflow-core`) with
 //! the persistent [`FileRevocationStore`] used at verification time:
 //!
-//! - [`SrlSync`] tracks the highest applied version (anti-rollback) and the union of entries.
-//! - [`SrlSync::apply`] validates signature + version, then persists the new entries into the store
-//!   (each entry becomes an ordinary revocation record, so it survives restarts through the
-//!   existing JSON-Lines store).
+//! - [`SrlSync`] tracks the highest applied version (anti-rollback) and the compacted state.
+//! - [`SrlSync::apply`] validates signature, version, delta base and state root, then persists the
+//!   new entries into the store (each entry becomes an ordinary revocation record, so it survives
+//!   restarts through the existing JSON-Lines store).
+//! - [`SrlSync::sync_once`] asks an [`SrlSource`] (e.g. [`HttpSrlSource`] against
+//!   ledgerflow-server's `GET /v1/srl`) for the delta since the applied version, falling back to a
+//!   full snapshot when the delta does not fit; [`SrlSync::spawn_poller`] does so on an interval in
+//!   a background thread.
+//! - [`SrlSync::checkpoint`] exposes the applied version and state root, which match the control
+//!   plane's exactly after every successful apply.
 //!
-//! The HTTP transport of SRLs (a control-plane endpoint that verifiers poll)
-//! is a deployment concern left to `ledgerflow-server` (P4); this module owns
-//! the application semantics.
+//! Every successful exchange with the control plane is a heartbeat. A node
+//! that has not heartbeated yet, or whose last heartbeat is older than its
+//! max age, is stale: it raises an alarm on every poll, and with
+//! [`SrlSync::with_fail_closed`] it also answers
+//! [`RevocationDecision::Unavailable`] as a [`RevocationCheck`], so a node
+//! that starts partitioned, or becomes so, stops accepting payments instead
+//! of missing revocations.
+//!
+//! The control plane publishes a tenant's revocations under
+//! [`tenant_scoped_key`](crate::tenant_scoped_key)s, so a node verifying for
+//! a tenant checks through [`SrlSync::for_tenant`], which sees both the
+//! global and that tenant's records; [`SrlSync`] itself sees global ones only.
+//!
+//! ```ignore
+//! let sync = SrlSync::new(store, control_plane_signer)
+//!     .with_max_age(Duration::from_mins(2))
+//!     .with_fail_closed(true);
+//! let _poller = sync.spawn_poller(HttpSrlSource::new("https://ledgerflow.example"), Duration::from_secs(15))?;
+//! let verification = VerificationService::new(sync.for_tenant("tenant-a"));
+//! ```
+
+use std::{
+    sync::{Arc, Mutex, PoisonError, mpsc},
+    time::{Duration, Instant},
+};
+
+use ledgerflow_core::{
+    AuthorizationError, PaymentSubjectRef, RevocationCheck, RevocationDecision,
+    SignedRevocationList, SignerRef, SrlState,
+};
+
+use crate::revocation_store::{FileRevocationStore, RevocationStoreError, TenantRevocationView};
+
+/// Default age beyond which an unrefreshed SRL replica is stale.
+pub const DEFAULT_SRL_MAX_AGE: Duration = Duration::from_mins(5);
+
+// ---------------------------------------------------------------------------
+// Sources
+// ---------------------------------------------------------------------------
+
+/// The control plane's answer to "what changed since version `since`".
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SrlUpdate {
+    /// The control plane's current version.
+    pub version: u64,
+    /// The signed snapshot or delta; `None` when the caller is already at
+    /// `version`.
+    pub list: Option<SignedRevocationList>,
+}
+
+/// Fetches SRL updates from a control plane.
+pub trait SrlSource: Send + Sync {
+    fn fetch(&self, since: u64) -> Result<SrlUpdate, String>;
+}
+
+impl<F> SrlSource for F
+where
+    F: Fn(u64) -> Result<SrlUpdate, String> + Send + Sync,
+{
+    fn fetch(&self, since: u64) -> Result<SrlUpdate, String> {
+        self(since)
+    }
+}
+
+/// [`SrlSource`] polling ledgerflow-server's `GET /v1/srl?since=` (feature
+/// `http`; uses hpx).
+#[cfg(feature = "http")]
+#[derive(Clone, Debug)]
+pub struct HttpSrlSource {
+    base_url: String,
+    headers: Vec<(String, String)>,
+    timeout_ms: u64,
+}
+
+#[cfg(feature = "http")]
+impl HttpSrlSource {
+    /// A source polling the server at `base_url` (e.g.
+    /// `https://ledgerflow.example`).
+    #[must_use]
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            headers: Vec::new(),
+            timeout_ms: 10_000,
+        }
+    }
+
+    /// Authenticates with a service token (`Authorization: Bearer ...`).
+    #[must_use]
+    pub fn with_bearer_token(self, token: impl AsRef<str>) -> Self {
+        self.with_header("authorization", format!("Bearer {}", token.as_ref()))
+    }
+
+    /// Sends `name: value` with every poll (e.g. a gateway tenant header).
+    #[must_use]
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    #[must_use]
+    pub const fn with_timeout_ms(mut self, timeout_ms: u64) -> Self {
+        self.timeout_ms = timeout_ms;
+        self
+    }
+}
+
+#[cfg(feature = "http")]
+impl SrlSource for HttpSrlSource {
+    fn fetch(&self, since: u64) -> Result<SrlUpdate, String> {
+        use base64::Engine as _;
+        use ledgerflow_core::CborCodec as _;
+
+        use crate::rails::rpc::RpcError;
+
+        /// `ApiResponse<SrlResponse>` envelope of `GET /v1/srl`.
+        #[derive(serde::Deserialize)]
+        struct Envelope {
+            data: Option<Body>,
+            error: Option<String>,
+        }
+
+        #[derive(serde::Deserialize)]
+        struct Body {
+            version: u64,
+            /// base64url CBOR of the signed list.
+            srl: Option<String>,
+        }
 
-use ledgerflow_core::{RevocationCheck, SignedRevocationList, SignerRef, SrlEntry, SrlState};
+        let url = format!("{}/v1/srl?since={since}", self.base_url);
+        let request = async {
+            let mut request = hpx::Client::new().get(url.as_str());
+            for (name, value) in &self.headers {
+                request = request.header(name.as_str(), value.as_str());
+            }
+            let response = request.send().await.map_err(|error| {
+                RpcError::Transport(format!("request to {url} failed: {error}"))
+            })?;
+            if !response.status().is_success() {
+                return Err(RpcError::Transport(format!(
+                    "{url} returned HTTP {}",
+                    response.status()
+                )));
+            }
+            response
+                .json::<Envelope>()
+                .await
+                .map_err(|error| RpcError::InvalidResponse(error.to_string()))
+        };
+        let envelope =
+            crate::rails::rpc::block_on_http(&url, Duration::from_millis(self.timeout_ms), request)
+                .map_err(|error| error.to_string())?;
+        let body = envelope.data.ok_or_else(|| envelope.error.unwrap_or_default())?;
+        let list = body
+            .srl
+            .map(|encoded| {
+                let bytes = base64::engine::general_purpose::URL_SAFE_NO_PAD
+                    .decode(encoded)
+                    .map_err(|error| error.to_string())?;
+                SignedRevocationList::decode_cbor(&bytes).map_err(|error| error.to_string())
+            })
+            .transpose()?;
+        Ok(SrlUpdate { version: body.version, list })
+    }
+}
 
-use crate::revocation_store::{FileRevocationStore, RevocationStoreError};
+// ---------------------------------------------------------------------------
+// Sync
+// ---------------------------------------------------------------------------
 
 /// Bridges SRL application onto a persistent revocation store.
 ///
-/// Cheap to clone: the state and the store are both shared internally.
+/// Cheap to clone: the state, the heartbeat and the store are all shared
+/// internally.
 #[derive(Clone, Debug)]
 pub struct SrlSync {
-    state: std::sync::Arc<std::sync::Mutex<SrlState>>,
+    state: Arc<Mutex<SrlState>>,
     store: FileRevocationStore,
     /// The trusted control-plane signer that SRLs must verify against.
     trusted_control_plane: SignerRef,
+    /// Last successful exchange with the control plane (`None` until the
+    /// first one, which counts as stale).
+    heartbeat: Arc<Mutex<Option<Instant>>>,
+    max_age: Duration,
+    fail_closed: bool,
 }
 
 impl SrlSync {
@@ -36,62 +210,294 @@ impl SrlSync {
     #[must_use]
     pub fn new(store: FileRevocationStore, trusted_control_plane: SignerRef) -> Self {
         Self {
-            state: std::sync::Arc::new(std::sync::Mutex::new(SrlState::new())),
+            state: Arc::new(Mutex::new(SrlState::new())),
             store,
             trusted_control_plane,
+            heartbeat: Arc::new(Mutex::new(None)),
+            max_age: DEFAULT_SRL_MAX_AGE,
+            fail_closed: false,
         }
     }
 
+    /// Age beyond which the replica is stale (default
+    /// [`DEFAULT_SRL_MAX_AGE`]).
+    #[must_use]
+    pub const fn with_max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = max_age;
+        self
+    }
+
+    /// When set, a stale replica answers [`RevocationDecision::Unavailable`]
+    /// for every check.
+    #[must_use]
+    pub const fn with_fail_closed(mut self, fail_closed: bool) -> Self {
+        self.fail_closed = fail_closed;
+        self
+    }
+
     /// Returns the highest SRL version applied so far.
     #[must_use]
     pub fn applied_version(&self) -> u64 {
         self.state.lock().map_or(0, |s| s.applied_version)
     }
 
+    /// Time since the last successful exchange with the control plane
+    /// (`None` before the first one).
+    #[must_use]
+    pub fn heartbeat_age(&self) -> Option<Duration> {
+        self.heartbeat.lock().unwrap_or_else(PoisonError::into_inner).map(|at| at.elapsed())
+    }
+
+    /// Returns `true` before the first heartbeat and when the last one is
+    /// older than the max age.
+    #[must_use]
+    pub fn is_stale(&self) -> bool {
+        self.heartbeat_age().is_none_or(|age| age > self.max_age)
+    }
+
+    /// A [`RevocationCheck`] for one tenant: global records and the tenant's
+    /// own, under the same fail-closed staleness rule as the replica.
+    #[must_use]
+    pub fn for_tenant(&self, tenant_id: impl Into<String>) -> TenantSrlView {
+        TenantSrlView { sync: self.clone(), view: self.store.for_tenant(tenant_id) }
+    }
+
+    /// `true` when checks must answer [`RevocationDecision::Unavailable`].
+    fn fails_closed(&self) -> bool {
+        self.fail_closed && self.is_stale()
+    }
+
+    /// The applied version and the Merkle root of the applied state
+    /// ([`ledgerflow_core::srl_state_root`]); equal to the control plane's
+    /// `(version, state_root)` when this replica holds exactly its state.
+    #[must_use]
+    pub fn checkpoint(&self) -> (u64, String) {
+        let state = self.state.lock().unwrap_or_else(PoisonError::into_inner);
+        (state.applied_version, state.state_root())
+    }
+
     /// Applies a signed SRL: verifies the signature, enforces anti-rollback,
-    /// and persists every new entry into the store.
+    /// the delta base and the state root, and persists every new entry into
+    /// the store.
     pub fn apply(&self, list: &SignedRevocationList) -> Result<(), SrlSyncError> {
         let mut state = self.state.lock().map_err(|_| SrlSyncError::Poisoned)?;
-        // Validate before mutating anything.
-        state.apply(list, &self.trusted_control_plane).map_err(SrlSyncError::Core)?;
+        // Validate on a copy: persistence failures are surfaced but do NOT
+        // advance the applied version (so the node retries on the next poll
+        // and never silently skips a revocation).
+        let mut next = state.clone();
+        next.apply(list, &self.trusted_control_plane).map_err(SrlSyncError::Core)?;
+        self.persist(list)?;
+        *state = next;
+        Ok(())
+    }
+
+    /// Replaces the applied state with a snapshot, bypassing the delta base
+    /// and root of the local state (which no longer line up with the control
+    /// plane's). The snapshot must still not roll the version back.
+    fn resync(&self, snapshot: &SignedRevocationList) -> Result<(), SrlSyncError> {
+        let mut state = self.state.lock().map_err(|_| SrlSyncError::Poisoned)?;
+        if snapshot.is_delta() || snapshot.version < state.applied_version {
+            return Err(SrlSyncError::Core(AuthorizationError::SrlVersionRegression {
+                presented: snapshot.version,
+                applied: state.applied_version,
+            }));
+        }
+        let mut next = SrlState::new();
+        next.apply(snapshot, &self.trusted_control_plane).map_err(SrlSyncError::Core)?;
+        self.persist(snapshot)?;
+        *state = next;
+        Ok(())
+    }
 
-        // Persist entries that are new to the store. Persistence failures are
-        // surfaced but do NOT advance the applied version (so the node retries
-        // on the next poll and never silently skips a revocation).
-        let mut store_updated = false;
+    /// Records the list's entries in the store. Revocation in the store is
+    /// monotone: entries compacted away upstream stay (they only match
+    /// expired warrants).
+    fn persist(&self, list: &SignedRevocationList) -> Result<(), SrlSyncError> {
         for entry in &list.entries {
-            match entry {
-                SrlEntry::Warrant { id_hex } => {
-                    let id = hex_decode(id_hex).ok_or_else(|| {
-                        SrlSyncError::Store(RevocationStoreError::Corrupt(format!(
-                            "invalid warrant id hex `{id_hex}`"
-                        )))
-                    })?;
-                    if self.store.check_warrant(&id) == ledgerflow_core::RevocationDecision::Ok {
-                        self.store.revoke_warrant(&id)?;
-                        store_updated = true;
-                    }
+            self.store.record_srl_entry(entry)?;
+        }
+        Ok(())
+    }
+
+    /// Fetches and applies what changed since the applied version, and
+    /// records a heartbeat on success. Returns the applied version.
+    ///
+    /// When the delta does not apply on top of the local state (another
+    /// base, or a state root that disagrees with the checkpoint), the full
+    /// snapshot is fetched and replaces the local state.
+    pub fn sync_once(&self, source: &dyn SrlSource) -> Result<u64, SrlSyncError> {
+        let applied = self.applied_version();
+        let update = source.fetch(applied).map_err(SrlSyncError::Fetch)?;
+        match &update.list {
+            Some(list) => match self.apply(list) {
+                Err(SrlSyncError::Core(
+                    AuthorizationError::SrlDeltaBaseMismatch { .. } |
+                    AuthorizationError::SrlStateRootMismatch { .. },
+                )) => {
+                    let snapshot =
+                        source.fetch(0).map_err(SrlSyncError::Fetch)?.list.ok_or_else(|| {
+                            SrlSyncError::Fetch("no snapshot to resync from".to_string())
+                        })?;
+                    self.resync(&snapshot)?;
                 }
-                SrlEntry::Holder { key_hex } => {
-                    let key = hex_decode(key_hex).ok_or_else(|| {
-                        SrlSyncError::Store(RevocationStoreError::Corrupt(format!(
-                            "invalid holder key hex `{key_hex}`"
-                        )))
-                    })?;
-                    let holder = SignerRef::new(ledgerflow_core::SigningAlgorithm::Ed25519, key);
-                    if self.store.check_holder(&holder) == ledgerflow_core::RevocationDecision::Ok {
-                        self.store.revoke_holder(&holder)?;
-                        store_updated = true;
+                result => result?,
+            },
+            None if update.version < applied => {
+                return Err(SrlSyncError::Core(AuthorizationError::SrlVersionRegression {
+                    presented: update.version,
+                    applied,
+                }));
+            }
+            None if update.version > applied => {
+                return Err(SrlSyncError::Fetch(format!(
+                    "the control plane is at version {} but sent no list",
+                    update.version
+                )));
+            }
+            None => {}
+        }
+        *self.heartbeat.lock().unwrap_or_else(PoisonError::into_inner) = Some(Instant::now());
+        Ok(self.applied_version())
+    }
+
+    /// Polls `source` every `interval` on a background thread, alarming
+    /// (`tracing::error!`) on every poll while the replica is stale. The
+    /// thread stops when the returned [`SrlPoller`] is stopped or dropped.
+    pub fn spawn_poller(
+        &self,
+        source: impl SrlSource + 'static,
+        interval: Duration,
+    ) -> std::io::Result<SrlPoller> {
+        let sync = self.clone();
+        let (stop, stopped) = mpsc::channel::<()>();
+        let handle = std::thread::Builder::new().name("ledgerflow-srl-sync".to_string()).spawn(
+            move || {
+                loop {
+                    if let Err(error) = sync.sync_once(&source) {
+                        tracing::warn!(
+                            target: "ledgerflow::srl",
+                            applied_version = sync.applied_version(),
+                            error = %error,
+                            "SRL sync failed"
+                        );
+                    }
+                    if sync.is_stale() {
+                        tracing::error!(
+                            target: "ledgerflow::srl",
+                            heartbeat_age_ms =
+                                ?sync.heartbeat_age().map(|age| age.as_millis() as u64),
+                            max_age_ms = sync.max_age.as_millis() as u64,
+                            fail_closed = sync.fail_closed,
+                            "revocation list is stale"
+                        );
+                    }
+                    if !matches!(
+                        stopped.recv_timeout(interval),
+                        Err(mpsc::RecvTimeoutError::Timeout)
+                    ) {
+                        break;
                     }
                 }
-            }
+            },
+        )?;
+        Ok(SrlPoller { stop, handle })
+    }
+}
+
+impl RevocationCheck for SrlSync {
+    fn check_warrant(&self, warrant_id: &[u8]) -> RevocationDecision {
+        if self.fails_closed() {
+            return RevocationDecision::Unavailable;
         }
+        self.store.check_warrant(warrant_id)
+    }
 
-        // Only now advance the version (after all entries persisted).
-        if store_updated || !list.entries.is_empty() {
-            state.applied_version = list.version;
+    fn check_holder(&self, holder: &SignerRef) -> RevocationDecision {
+        if self.fails_closed() {
+            return RevocationDecision::Unavailable;
         }
-        Ok(())
+        self.store.check_holder(holder)
+    }
+
+    fn check_subject(&self, subject: &PaymentSubjectRef) -> RevocationDecision {
+        if self.fails_closed() {
+            return RevocationDecision::Unavailable;
+        }
+        self.store.check_subject(subject)
+    }
+
+    fn check_issuer(&self, issuer: &SignerRef) -> RevocationDecision {
+        if self.fails_closed() {
+            return RevocationDecision::Unavailable;
+        }
+        self.store.check_issuer(issuer)
+    }
+
+    fn check_agent(&self, agent_id: &str) -> RevocationDecision {
+        if self.fails_closed() {
+            return RevocationDecision::Unavailable;
+        }
+        self.store.check_agent(agent_id)
+    }
+}
+
+/// Tenant-scoped view over an [`SrlSync`] replica (see
+/// [`SrlSync::for_tenant`]).
+#[derive(Clone, Debug)]
+pub struct TenantSrlView {
+    sync: SrlSync,
+    view: TenantRevocationView,
+}
+
+impl RevocationCheck for TenantSrlView {
+    fn check_warrant(&self, warrant_id: &[u8]) -> RevocationDecision {
+        if self.sync.fails_closed() {
+            return RevocationDecision::Unavailable;
+        }
+        self.view.check_warrant(warrant_id)
+    }
+
+    fn check_holder(&self, holder: &SignerRef) -> RevocationDecision {
+        if self.sync.fails_closed() {
+            return RevocationDecision::Unavailable;
+        }
+        self.view.check_holder(holder)
+    }
+
+    fn check_subject(&self, subject: &PaymentSubjectRef) -> RevocationDecision {
+        if self.sync.fails_closed() {
+            return RevocationDecision::Unavailable;
+        }
+        self.view.check_subject(subject)
+    }
+
+    fn check_issuer(&self, issuer: &SignerRef) -> RevocationDecision {
+        if self.sync.fails_closed() {
+            return RevocationDecision::Unavailable;
+        }
+        self.view.check_issuer(issuer)
+    }
+
+    fn check_agent(&self, agent_id: &str) -> RevocationDecision {
+        if self.sync.fails_closed() {
+            return RevocationDecision::Unavailable;
+        }
+        self.view.check_agent(agent_id)
+    }
+}
+
+/// Handle of a background SRL poller (see [`SrlSync::spawn_poller`]).
+#[derive(Debug)]
+pub struct SrlPoller {
+    stop: mpsc::Sender<()>,
+    handle: std::thread::JoinHandle<()>,
+}
+
+impl SrlPoller {
+    /// Stops the poller and waits for its thread to exit.
+    pub fn stop(self) {
+        drop(self.stop);
+        let _ = self.handle.join();
     }
 }
 
@@ -104,11 +510,6 @@ pub enum SrlSyncError {
     Core(#[from] ledgerflow_core::AuthorizationError),
     #[error("revocation store failure: {0}")]
     Store(#[from] RevocationStoreError),
-}
-
-fn hex_decode(hex: &str) -> Option<Vec<u8>> {
-    if !hex.len().is_multiple_of(2) {
-        return None;
-    }
-    (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(&hex[i..i + 2], 16).ok()).collect()
+    #[error("failed to fetch the SRL: {0}")]
+    Fetch(String),
 }
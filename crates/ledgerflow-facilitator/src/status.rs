@@ -1,6 +1,13 @@
 //! Settlement status registry (idempotent `/status` queries).
+//!
+//! [`SettlementStore`] is the storage seam; [`SettlementRegistry`] is the
+//! in-memory implementation. Deployments that must survive restarts plug in a
+//! persistent store (e.g. the `sqlite` backend in `ledgerflow-server`).
 
-use std::{collections::BTreeMap, sync::Mutex};
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex},
+};
 
 use crate::{outcome::SettlementStatus, rails::SettlementReceipt};
 
@@ -11,25 +18,49 @@ pub struct RegistryEntry {
     pub status: SettlementStatus,
 }
 
+/// Storage seam for settlement outcomes.
+///
+/// Outcomes are keyed by tenant: a lookup never returns another tenant's
+/// settlements (design §10.2).
+pub trait SettlementStore: std::fmt::Debug + Send + Sync {
+    /// Records a settlement outcome (idempotent by transaction id).
+    fn record(
+        &self,
+        tenant_id: &str,
+        warrant_digest: &str,
+        receipt: SettlementReceipt,
+        status: SettlementStatus,
+    );
+
+    /// Queries a single settlement by transaction id.
+    fn query(&self, tenant_id: &str, transaction_id: &str) -> Option<RegistryEntry>;
+
+    /// Queries all settlements for a warrant digest, in recording order.
+    fn query_by_warrant(&self, tenant_id: &str, warrant_digest: &str) -> Vec<RegistryEntry>;
+}
+
+/// Shared settlement store handle.
+pub type SharedSettlementStore = Arc<dyn SettlementStore>;
+
 /// In-memory settlement registry with idempotent lookups.
 ///
-/// The registry is intentionally in-memory in v1; a persistent registry is a
-/// deployment concern handled by `ledgerflow-server` (P3).
+/// Not restart-safe; persistent registries implement [`SettlementStore`]
+/// (design §11.3).
 #[derive(Debug, Default)]
 pub struct SettlementRegistry {
     inner: std::sync::Arc<SettlementRegistryInner>,
 }
 
-/// Shared registry storage.
+/// Shared registry storage, keyed by `(tenant_id, …)`.
 #[derive(Debug, Default)]
 struct SettlementRegistryInner {
-    by_transaction: Mutex<BTreeMap<String, RegistryEntry>>,
-    by_warrant: Mutex<BTreeMap<String, Vec<String>>>,
+    by_transaction: Mutex<BTreeMap<(String, String), RegistryEntry>>,
+    by_warrant: Mutex<BTreeMap<(String, String), Vec<String>>>,
 }
 
 impl Clone for SettlementRegistry {
     fn clone(&self) -> Self {
-        Self { inner: std::sync::Arc::clone(&self.inner) }
+        Self { inner: Arc::clone(&self.inner) }
     }
 }
 
@@ -37,22 +68,26 @@ impl SettlementRegistry {
     /// Creates an empty registry.
     #[must_use]
     pub fn new() -> Self {
-        Self { inner: std::sync::Arc::new(SettlementRegistryInner::default()) }
+        Self { inner: Arc::new(SettlementRegistryInner::default()) }
     }
 
     /// Records a settlement outcome (idempotent by transaction id).
     pub fn record(
         &self,
+        tenant_id: &str,
         warrant_digest: &str,
         receipt: SettlementReceipt,
         status: SettlementStatus,
     ) {
         let transaction_id = receipt.transaction_id.clone();
         if let Ok(mut map) = self.inner.by_transaction.lock() {
-            map.insert(transaction_id.clone(), RegistryEntry { receipt, status });
+            map.insert(
+                (tenant_id.to_string(), transaction_id.clone()),
+                RegistryEntry { receipt, status },
+            );
         }
         if let Ok(mut map) = self.inner.by_warrant.lock() {
-            let ids = map.entry(warrant_digest.to_string()).or_default();
+            let ids = map.entry((tenant_id.to_string(), warrant_digest.to_string())).or_default();
             if !ids.contains(&transaction_id) {
                 ids.push(transaction_id);
             }
@@ -60,23 +95,49 @@ impl SettlementRegistry {
     }
 
     /// Queries a single settlement by transaction id.
-    pub fn query(&self, transaction_id: &str) -> Option<RegistryEntry> {
-        self.inner.by_transaction.lock().ok().and_then(|map| map.get(transaction_id).cloned())
+    pub fn query(&self, tenant_id: &str, transaction_id: &str) -> Option<RegistryEntry> {
+        self.inner
+            .by_transaction
+            .lock()
+            .ok()
+            .and_then(|map| map.get(&(tenant_id.to_string(), transaction_id.to_string())).cloned())
     }
 
     /// Queries all settlements for a warrant digest.
-    pub fn query_by_warrant(&self, warrant_digest: &str) -> Vec<RegistryEntry> {
+    pub fn query_by_warrant(&self, tenant_id: &str, warrant_digest: &str) -> Vec<RegistryEntry> {
         let transaction_ids = self
             .inner
             .by_warrant
             .lock()
             .ok()
-            .and_then(|map| map.get(warrant_digest).cloned())
+            .and_then(|map| map.get(&(tenant_id.to_string(), warrant_digest.to_string())).cloned())
             .unwrap_or_default();
         let map = self.inner.by_transaction.lock().ok();
         transaction_ids
             .into_iter()
-            .filter_map(|id| map.as_ref().and_then(|m| m.get(&id).cloned()))
+            .filter_map(|id| {
+                map.as_ref().and_then(|m| m.get(&(tenant_id.to_string(), id)).cloned())
+            })
             .collect()
     }
 }
+
+impl SettlementStore for SettlementRegistry {
+    fn record(
+        &self,
+        tenant_id: &str,
+        warrant_digest: &str,
+        receipt: SettlementReceipt,
+        status: SettlementStatus,
+    ) {
+        Self::record(self, tenant_id, warrant_digest, receipt, status);
+    }
+
+    fn query(&self, tenant_id: &str, transaction_id: &str) -> Option<RegistryEntry> {
+        Self::query(self, tenant_id, transaction_id)
+    }
+
+    fn query_by_warrant(&self, tenant_id: &str, warrant_digest: &str) -> Vec<RegistryEntry> {
+        Self::query_by_warrant(self, tenant_id, warrant_digest)
+    }
+}
@@ -1,6 +1,14 @@
 //! Payment-subject resolution for Facilitator routing.
-
-use ledgerflow_core::{PaymentSubjectKind, VerifiedAuthorization};
+//!
+//! The rail family comes from a core
+//! [`SubjectRailPolicy`](ledgerflow_core::SubjectRailPolicy), the same policy
+//! merchants verify `allowed_rails` against; on-chain subjects are then
+//! narrowed to a chain family from their CAIP-10 namespace.
+
+use ledgerflow_core::{
+    DefaultSubjectRailPolicy, PaymentRail, PaymentSubjectRef, SharedSubjectRailPolicy,
+    SubjectRailPolicy, VerifiedAuthorization,
+};
 use thiserror::Error;
 
 use crate::routing::RailKind;
@@ -27,7 +35,8 @@ pub trait PaymentSubjectResolver {
     ) -> Result<ResolvedSubject, SubjectResolutionError>;
 }
 
-/// Default subject resolver for the onchain, exchange, custodial, and gateway rails.
+/// Default subject resolver for the onchain, exchange, custodial, and gateway
+/// rails ([`DefaultSubjectRailPolicy`]).
 #[derive(Clone, Debug, Default)]
 pub struct DefaultSubjectResolver;
 
@@ -36,41 +45,54 @@ impl PaymentSubjectResolver for DefaultSubjectResolver {
         &self,
         authorization: &VerifiedAuthorization,
     ) -> Result<ResolvedSubject, SubjectResolutionError> {
-        let subject = &authorization.payment_subject;
-        let rail = match subject.kind {
-            PaymentSubjectKind::Caip10 if subject.value.starts_with("caip10:solana:") => {
-                RailKind::Solana
-            }
-            PaymentSubjectKind::Caip10 if subject.value.starts_with("caip10:eip155:") => {
-                RailKind::Evm
-            }
-            PaymentSubjectKind::ExchangeAccount => RailKind::Exchange,
-            PaymentSubjectKind::FacilitatorAccount if subject.value.starts_with("binance:") => {
-                RailKind::Exchange
-            }
-            PaymentSubjectKind::FacilitatorAccount if subject.value.starts_with("okx:") => {
-                RailKind::Exchange
-            }
-            PaymentSubjectKind::Opaque if subject.value.starts_with("gateway:") => {
-                RailKind::Gateway
-            }
-            PaymentSubjectKind::Opaque => RailKind::Custodial,
-            PaymentSubjectKind::FacilitatorAccount => RailKind::Exchange,
-            _ => {
-                return Err(SubjectResolutionError::UnsupportedSubject {
-                    value: subject.value.clone(),
-                });
-            }
-        };
+        resolve_with(&DefaultSubjectRailPolicy, &authorization.payment_subject)
+    }
+}
+
+/// Subject resolver over a shared [`SubjectRailPolicy`]; hand it the policy
+/// the merchant's verification context uses.
+#[derive(Clone, Debug)]
+pub struct PolicySubjectResolver {
+    policy: SharedSubjectRailPolicy,
+}
 
-        Ok(ResolvedSubject { rail, value: subject.value.clone() })
+impl PolicySubjectResolver {
+    #[must_use]
+    pub fn new(policy: SharedSubjectRailPolicy) -> Self {
+        Self { policy }
     }
 }
 
+impl PaymentSubjectResolver for PolicySubjectResolver {
+    fn resolve(
+        &self,
+        authorization: &VerifiedAuthorization,
+    ) -> Result<ResolvedSubject, SubjectResolutionError> {
+        resolve_with(self.policy.as_ref(), &authorization.payment_subject)
+    }
+}
+
+fn resolve_with(
+    policy: &dyn SubjectRailPolicy,
+    subject: &PaymentSubjectRef,
+) -> Result<ResolvedSubject, SubjectResolutionError> {
+    let rail = match policy.rail(subject) {
+        PaymentRail::Onchain if subject.value.starts_with("caip10:solana:") => RailKind::Solana,
+        PaymentRail::Onchain if subject.value.starts_with("caip10:eip155:") => RailKind::Evm,
+        PaymentRail::Exchange => RailKind::Exchange,
+        PaymentRail::Custodial => RailKind::Custodial,
+        PaymentRail::TraditionalGateway => RailKind::Gateway,
+        _ => {
+            return Err(SubjectResolutionError::UnsupportedSubject { value: subject.value.clone() });
+        }
+    };
+    Ok(ResolvedSubject { rail, value: subject.value.clone() })
+}
+
 #[cfg(test)]
 mod tests {
     #![allow(clippy::expect_used)]
-    use ledgerflow_core::PaymentSubjectRef;
+    use ledgerflow_core::{PaymentSubjectKind, PrefixRailPolicy};
 
     use super::*;
 
@@ -202,4 +224,18 @@ mod tests {
         let resolved = DefaultSubjectResolver.resolve(&authz(near_miss)).expect("falls through");
         assert_eq!(resolved.rail, RailKind::Exchange);
     }
+
+    #[test]
+    fn policy_resolver_routes_on_the_shared_policy() {
+        let policy = std::sync::Arc::new(PrefixRailPolicy::new().route(
+            PaymentSubjectKind::FacilitatorAccount,
+            "stripe:",
+            PaymentRail::TraditionalGateway,
+        ));
+        let resolver = PolicySubjectResolver::new(policy);
+        let stripe = PaymentSubjectRef::new(PaymentSubjectKind::FacilitatorAccount, "stripe:acct");
+        assert_eq!(resolver.resolve(&authz(stripe)).expect("resolved").rail, RailKind::Gateway);
+        let okx = PaymentSubjectRef::new(PaymentSubjectKind::FacilitatorAccount, "okx:acct");
+        assert_eq!(resolver.resolve(&authz(okx)).expect("resolved").rail, RailKind::Exchange);
+    }
 }
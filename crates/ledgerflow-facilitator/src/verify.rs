@@ -7,8 +7,10 @@
 //! close the verify→settle TOCTOU window.
 
 use ledgerflow_core::{
-    AuthorizationContext, AuthorizationError, PopProof, SignedApproval, ToolArguments,
-    TrustedIssuers, WarrantChain, revocation::RevocationCheck, verify_authorization,
+    AuthorizationContext, AuthorizationError, PopProof, SharedApproverSetResolver,
+    SharedContractSignatureVerifier, SharedIdentityResolver, SignedApproval, ToolArguments,
+    TrustedIssuers, WarrantChain, revocation::RevocationCheck,
+    verify_authorization_with_approver_sets,
 };
 
 use crate::outcome::{VerifyOutcome, VerifyStatus};
@@ -28,6 +30,15 @@ pub struct VerifyRequest<'a> {
 #[derive(Clone, Debug)]
 pub struct VerificationService<R> {
     pub revocation: R,
+    /// Resolves approver-group references (warrants carrying one fail closed
+    /// without it).
+    pub approver_sets: Option<SharedApproverSetResolver>,
+    /// Checks contract-account (ERC-1271) signatures; without it only
+    /// directly verifiable signatures are accepted.
+    pub contract_verifier: Option<SharedContractSignatureVerifier>,
+    /// Resolves the EIP-8004 identities of anchored trust entries; without
+    /// it only statically trusted issuer keys are accepted.
+    pub identity_resolver: Option<SharedIdentityResolver>,
 }
 
 impl<R> VerificationService<R>
@@ -37,7 +48,30 @@ where
     /// Creates a new verification service over the given revocation store.
     #[must_use]
     pub const fn new(revocation: R) -> Self {
-        Self { revocation }
+        Self { revocation, approver_sets: None, contract_verifier: None, identity_resolver: None }
+    }
+
+    /// Resolves approver-set references through `resolver`.
+    #[must_use]
+    pub fn with_approver_sets(mut self, resolver: SharedApproverSetResolver) -> Self {
+        self.approver_sets = Some(resolver);
+        self
+    }
+
+    /// Accepts contract-account issuers, holders and approvers, checked
+    /// through `verifier`.
+    #[must_use]
+    pub fn with_contract_verifier(mut self, verifier: SharedContractSignatureVerifier) -> Self {
+        self.contract_verifier = Some(verifier);
+        self
+    }
+
+    /// Accepts roots issued by keys bound to an anchored trust entry's
+    /// EIP-8004 identity, resolved through `resolver`.
+    #[must_use]
+    pub fn with_identity_resolver(mut self, resolver: SharedIdentityResolver) -> Self {
+        self.identity_resolver = Some(resolver);
+        self
     }
 
     /// Runs the verify orchestration.
@@ -46,6 +80,31 @@ where
     /// re-verifies. All authorization failures are mapped to a
     /// [`VerifyStatus`].
     pub fn verify(&self, request: &VerifyRequest<'_>) -> VerifyOutcome {
+        // The Facilitator pre-check has no payment-payload context of its
+        // own; the binding is enforced by the merchant verifier which sets
+        // this. Leaving it `None` performs no digest check here.
+        self.run(request, None)
+    }
+
+    /// Runs the verify orchestration for a concrete x402 payment, also
+    /// checking that the PoP commits to `payment_payload_digest`.
+    ///
+    /// Hosted facilitators receive the payment payload itself, so unlike
+    /// [`Self::verify`] they can (and must) enforce the payload binding
+    /// (design §6.3).
+    pub fn verify_payment(
+        &self,
+        request: &VerifyRequest<'_>,
+        payment_payload_digest: String,
+    ) -> VerifyOutcome {
+        self.run(request, Some(payment_payload_digest))
+    }
+
+    fn run(
+        &self,
+        request: &VerifyRequest<'_>,
+        payment_payload_digest: Option<String>,
+    ) -> VerifyOutcome {
         let input = ledgerflow_core::AuthorizationInput {
             chain: request.chain,
             trusted: request.trusted,
@@ -54,12 +113,11 @@ where
             approvals: request.approvals,
             tool_arguments: request.tool_arguments,
             revocation: &self.revocation,
-            // The Facilitator pre-check has no payment-payload context of its
-            // own; the binding is enforced by the merchant verifier which sets
-            // this. Leaving it `None` performs no digest check here.
-            payment_payload_digest: None,
+            payment_payload_digest,
+            contract_verifier: self.contract_verifier.as_deref(),
+            identity_resolver: self.identity_resolver.as_deref(),
         };
-        match verify_authorization(&input) {
+        match verify_authorization_with_approver_sets(&input, self.approver_sets.as_deref()) {
             Ok(authorization) => VerifyOutcome::ok(authorization),
             Err(error) => VerifyOutcome::error(map_error(&error), error.to_string()),
         }
@@ -76,9 +134,11 @@ pub const fn map_error(error: &AuthorizationError) -> VerifyStatus {
         AuthorizationError::InvalidApprovalSignature |
         AuthorizationError::ApprovalsDigestMismatch |
         AuthorizationError::ApprovalRequestMismatch => VerifyStatus::InsufficientApproval,
-        AuthorizationError::WarrantRevoked | AuthorizationError::HolderRevoked => {
-            VerifyStatus::Revoked
-        }
+        AuthorizationError::WarrantRevoked |
+        AuthorizationError::HolderRevoked |
+        AuthorizationError::SubjectRevoked |
+        AuthorizationError::IssuerRevoked |
+        AuthorizationError::AgentRevoked { .. } => VerifyStatus::Revoked,
         AuthorizationError::WarrantExpired { .. } |
         AuthorizationError::WarrantNotYetValid { .. } |
         AuthorizationError::ProofOutsideFreshnessWindow { .. } => VerifyStatus::Expired,
@@ -9,16 +9,18 @@
 use std::sync::Arc;
 
 use ledgerflow_core::{
-    AssetRef, AuthorizationContext, AuthorizationInput, InMemoryRevocationCheck,
-    MerchantConstraint, PaymentConstraint, PaymentRail, PaymentSubjectKind, PaymentSubjectRef,
-    PopProof, ProofBuilder, ResourceConstraint, RevocationCheck, SignedApproval, SignerRef,
-    SigningKeyPair, TrustedIssuer, TrustedIssuers, Warrant, WarrantBuilder, WarrantChain,
+    AgentIdRef, AssetRef, AuthorizationContext, AuthorizationInput, BUDGET_EXTENSION, BudgetPeriod,
+    BudgetPolicy, DelegatedWarrantBuilder, ERC_1271_MAGIC_VALUE, IdentityResolver,
+    InMemoryRevocationCheck, MerchantConstraint, PaymentConstraint, PaymentRail,
+    PaymentSubjectKind, PaymentSubjectRef, PopProof, ProofBuilder, ResourceConstraint,
+    RevocationCheck, Secp256k1KeyPair, SignedApproval, SignerRef, SigningAlgorithm, SigningKeyPair,
+    TrustedIssuer, TrustedIssuers, Warrant, WarrantBuilder, WarrantChain, hex_encode_bytes,
     sha256_prefixed, verify_authorization,
 };
 use ledgerflow_facilitator::{
-    DefaultSubjectResolver, EvmRailAdapter, FileRevocationStore, SettlementRegistry,
-    SettlementService, SharedRailAdapter, SolanaRailAdapter, VerificationService, VerifyRequest,
-    VerifyStatus,
+    DefaultSubjectResolver, EvmRailAdapter, FileRevocationStore, InMemoryBudgetLedger,
+    MockJsonRpcTransport, RpcContractSignatureVerifier, SettlementRegistry, SettlementService,
+    SharedRailAdapter, SolanaRailAdapter, VerificationService, VerifyRequest, VerifyStatus,
 };
 
 fn issuer_keys() -> SigningKeyPair {
@@ -297,6 +299,7 @@ fn settle_succeeds_when_reverification_passes() {
         chain: &chain,
         proof: &proof,
         context: &ctx,
+        settlement_payload: "",
         now_ms,
     };
     let result = settlement.settle(&settle_request);
@@ -335,6 +338,7 @@ fn settle_rejects_when_revoked_after_verify_then_settle() {
         chain: &chain,
         proof: &proof,
         context: &ctx,
+        settlement_payload: "",
         now_ms,
     };
     let result = settlement.settle(&settle_request);
@@ -342,6 +346,114 @@ fn settle_rejects_when_revoked_after_verify_then_settle() {
     assert!(result.reason.is_some());
 }
 
+#[test]
+fn settle_rejects_when_the_issuer_is_revoked_after_verify() {
+    let now_ms = 5_000;
+    let chain = WarrantChain::single(root_warrant(now_ms));
+    let ctx = context(now_ms, 100);
+    let proof = proof(chain.leaf().expect("leaf"), &ctx);
+    let verify_request = VerifyRequest {
+        chain: &chain,
+        trusted: &trusted(),
+        proof: &proof,
+        context: &ctx,
+        approvals: &[],
+        tool_arguments: &tool_arguments(),
+    };
+    let outcome = VerificationService::new(InMemoryRevocationCheck::new()).verify(&verify_request);
+    let authorization = outcome.authorization.expect("authorized");
+
+    // The root issuer's key is revoked between verify and settle; the leaf
+    // warrant and holder are untouched.
+    let mut revocation = InMemoryRevocationCheck::new();
+    revocation.revoke_issuer(&issuer_keys().signer_ref());
+    let settlement =
+        SettlementService::new(revocation, DefaultSubjectResolver, vec![EvmRailAdapter]);
+    let result = settlement.settle(&ledgerflow_facilitator::SettleRequest {
+        authorization: &authorization,
+        chain: &chain,
+        proof: &proof,
+        context: &ctx,
+        settlement_payload: "",
+        now_ms,
+    });
+    assert_eq!(result.status, ledgerflow_facilitator::SettlementStatus::Failed);
+    assert!(result.reason.expect("reason").contains("issuer"));
+}
+
+// ---------------------------------------------------------------------------
+// Accounting-point budget
+// ---------------------------------------------------------------------------
+
+#[test]
+fn settle_enforces_budget_across_charges_and_delegation() {
+    let now_ms = 5_000;
+    let issuer = issuer_keys();
+    let policy = BudgetPolicy::new("ledger-a").with_per_day(250);
+    let root = WarrantBuilder::new(now_ms)
+        .warrant_id(*b"root-budget-0000")
+        .ttl_secs(60)
+        .max_depth(1)
+        .issuer(issuer.signer_ref())
+        .holder(holder_keys().signer_ref())
+        .merchant(merchant_constraint())
+        .resource(resource_constraint())
+        .payment(payment_constraint(1_000))
+        .extension(BUDGET_EXTENSION, policy.encode_cbor().expect("encode budget"))
+        .sign_with(&issuer, [2_u8; 8]);
+    let chain = WarrantChain::single(root.clone());
+    let ctx = context(now_ms, 100);
+    let proof = proof(&root, &ctx);
+    let outcome = VerificationService::new(InMemoryRevocationCheck::new()).verify(&VerifyRequest {
+        chain: &chain,
+        trusted: &trusted(),
+        proof: &proof,
+        context: &ctx,
+        approvals: &[],
+        tool_arguments: &tool_arguments(),
+    });
+    let authorization = outcome.authorization.expect("authorized");
+
+    let ledger = InMemoryBudgetLedger::new("ledger-a");
+    let settlement = SettlementService::new(
+        InMemoryRevocationCheck::new(),
+        DefaultSubjectResolver,
+        vec![EvmRailAdapter],
+    )
+    .with_budget_ledger(Arc::new(ledger.clone()));
+    let settle = |chain: &WarrantChain| {
+        settlement.settle(&ledgerflow_facilitator::SettleRequest {
+            authorization: &authorization,
+            chain,
+            proof: &proof,
+            context: &ctx,
+            settlement_payload: "",
+            now_ms,
+        })
+    };
+
+    // Two individually-capped charges fit the daily budget.
+    for _ in 0..2 {
+        assert_eq!(settle(&chain).status, ledgerflow_facilitator::SettlementStatus::Settled);
+    }
+    assert_eq!(ledger.spent(&root.id, BudgetPeriod::Day, now_ms / 1000), 200);
+
+    // A third charge would overdraw the day window.
+    assert_eq!(settle(&chain).status, ledgerflow_facilitator::SettlementStatus::Failed);
+
+    // Delegating to a fresh child does not reset the root's counters.
+    let child = DelegatedWarrantBuilder::from(root.clone()).issue_to(
+        SigningKeyPair::from_bytes(&[54u8; 32]).signer_ref(),
+        &holder_keys(),
+        now_ms,
+        [3_u8; 8],
+    );
+    let delegated = WarrantChain { warrants: vec![root, child] };
+    let result = settle(&delegated);
+    assert_eq!(result.status, ledgerflow_facilitator::SettlementStatus::Failed);
+    assert!(result.reason.expect("reason").contains("day"));
+}
+
 // ---------------------------------------------------------------------------
 // Persistent revocation store
 // ---------------------------------------------------------------------------
@@ -378,16 +490,25 @@ fn settlement_registry_is_idempotent() {
         asset: "USDC".to_string(),
     };
     registry.record(
+        "tenant-a",
         "sha256:warrant",
         receipt.clone(),
         ledgerflow_facilitator::SettlementStatus::Settled,
     );
-    registry.record("sha256:warrant", receipt, ledgerflow_facilitator::SettlementStatus::Settled);
+    registry.record(
+        "tenant-a",
+        "sha256:warrant",
+        receipt,
+        ledgerflow_facilitator::SettlementStatus::Settled,
+    );
 
-    let query = registry.query("tx-1").expect("found");
+    let query = registry.query("tenant-a", "tx-1").expect("found");
     assert_eq!(query.receipt.settled_amount, 100);
-    let by_warrant = registry.query_by_warrant("sha256:warrant");
+    let by_warrant = registry.query_by_warrant("tenant-a", "sha256:warrant");
     assert_eq!(by_warrant.len(), 1);
+    // Another tenant never sees the settlement.
+    assert!(registry.query("tenant-b", "tx-1").is_none());
+    assert!(registry.query_by_warrant("tenant-b", "sha256:warrant").is_empty());
 }
 
 #[test]
@@ -418,6 +539,7 @@ fn settle_rejects_when_warrant_expired_between_verify_and_settle() {
         chain: &chain,
         proof: &proof,
         context: &ctx,
+        settlement_payload: "",
         now_ms: 66_000,
     };
     let result = settlement.settle(&settle_request);
@@ -480,6 +602,7 @@ fn settle_accepts_expiry_exactly_at_now() {
         chain: &chain,
         proof: &proof,
         context: &ctx,
+        settlement_payload: "",
         now_ms: 65_000,
     });
     assert_eq!(result.status, ledgerflow_facilitator::SettlementStatus::Settled);
@@ -517,6 +640,7 @@ fn settle_accepts_amount_exactly_at_cap() {
         chain: &chain,
         proof: &proof,
         context: &ctx,
+        settlement_payload: "",
         now_ms,
     });
     assert_eq!(result.status, ledgerflow_facilitator::SettlementStatus::Settled);
@@ -552,6 +676,7 @@ fn settle_routes_to_matching_rail_and_fails_when_none() {
         chain: &chain,
         proof: &proof,
         context: &ctx,
+        settlement_payload: "",
         now_ms,
     });
     assert_eq!(ok.status, ledgerflow_facilitator::SettlementStatus::Settled);
@@ -567,6 +692,7 @@ fn settle_routes_to_matching_rail_and_fails_when_none() {
         chain: &chain,
         proof: &proof,
         context: &ctx,
+        settlement_payload: "",
         now_ms,
     });
     assert_eq!(failed.status, ledgerflow_facilitator::SettlementStatus::Failed);
@@ -605,6 +731,7 @@ fn settle_routes_solana_subjects_to_solana_adapter() {
         chain: &chain,
         proof: &proof,
         context: &ctx,
+        settlement_payload: "",
         now_ms,
     });
 
@@ -614,6 +741,113 @@ fn settle_routes_solana_subjects_to_solana_adapter() {
     assert!(receipt.transaction_id.starts_with("solana-tx-"));
 }
 
+// ---------------------------------------------------------------------------
+// ERC-1271 contract-account issuers
+// ---------------------------------------------------------------------------
+
+#[test]
+fn verify_accepts_a_contract_account_issuer_through_erc1271() {
+    const SAFE: [u8; 20] = [0x5A; 20];
+    let owner = Secp256k1KeyPair::from_bytes(&[0x42; 32]).expect("owner key");
+    let safe = SignerRef::new(SigningAlgorithm::EthPersonalSign, SAFE.to_vec());
+    let now_ms = 5_000;
+    let unsigned = WarrantBuilder::new(now_ms)
+        .warrant_id(*b"root-safe-000000")
+        .ttl_secs(60)
+        .max_depth(1)
+        .issuer(safe.clone())
+        .holder(holder_keys().signer_ref())
+        .merchant(merchant_constraint())
+        .resource(resource_constraint())
+        .payment(payment_constraint(1_000))
+        .build_unsigned([0_u8; 8]);
+    let signature = owner.sign_eth_personal(&unsigned.signing_message());
+    let owner_signature = signature.value.clone();
+    let warrant = unsigned.with_signature(signature);
+
+    let mut anchors = TrustedIssuers::new();
+    anchors.add(TrustedIssuer::new("treasury-safe".to_string(), safe));
+    let ctx = context(now_ms, 100);
+    let proof = proof(&warrant, &ctx);
+    let chain = WarrantChain::single(warrant);
+    let request = VerifyRequest {
+        chain: &chain,
+        trusted: &anchors,
+        proof: &proof,
+        context: &ctx,
+        approvals: &[],
+        tool_arguments: &tool_arguments(),
+    };
+
+    // The Safe accepts its owner's signature and nothing else.
+    let wallet = MockJsonRpcTransport::new(move |_, params| {
+        let data = params[0]["data"].as_str().unwrap_or_default();
+        let magic = if data.contains(&hex_encode_bytes(&owner_signature)) {
+            format!("0x{}{}", hex_encode_bytes(&ERC_1271_MAGIC_VALUE), "00".repeat(28))
+        } else {
+            format!("0x{}", "00".repeat(32))
+        };
+        Ok(serde_json::json!(magic))
+    });
+    let service = VerificationService::new(InMemoryRevocationCheck::new())
+        .with_contract_verifier(Arc::new(RpcContractSignatureVerifier::new(wallet)));
+    let outcome = service.verify(&request);
+    assert_eq!(outcome.status, VerifyStatus::Verified);
+
+    let outcome = VerificationService::new(InMemoryRevocationCheck::new()).verify(&request);
+    assert_ne!(outcome.status, VerifyStatus::Verified);
+}
+
+// ---------------------------------------------------------------------------
+// EIP-8004 anchored issuers
+// ---------------------------------------------------------------------------
+
+/// Resolves every agent identity to `keys`.
+#[derive(Debug)]
+struct FixedIdentity {
+    keys: Vec<SignerRef>,
+}
+
+impl IdentityResolver for FixedIdentity {
+    fn resolve_keys(&self, _agent: &AgentIdRef) -> ledgerflow_core::Result<Vec<SignerRef>> {
+        Ok(self.keys.clone())
+    }
+}
+
+#[test]
+fn verify_accepts_an_anchored_issuer_through_the_identity_resolver() {
+    let now_ms = 5_000;
+    let warrant = root_warrant(now_ms);
+    let ctx = context(now_ms, 100);
+    let proof = proof(&warrant, &ctx);
+    let chain = WarrantChain::single(warrant);
+    // Only the bootstrap key is trusted statically; the issuer key is bound
+    // through the agent's registration.
+    let mut anchors = TrustedIssuers::new();
+    anchors.add(TrustedIssuer::anchored(
+        "agent-7".to_string(),
+        SigningKeyPair::from_bytes(&[50u8; 32]).signer_ref(),
+        AgentIdRef::parse("eip155:8453:0x8004a169fb4a3325136eb29fa0ceb6d2e539a432/7")
+            .expect("agent"),
+    ));
+    let request = VerifyRequest {
+        chain: &chain,
+        trusted: &anchors,
+        proof: &proof,
+        context: &ctx,
+        approvals: &[],
+        tool_arguments: &tool_arguments(),
+    };
+
+    let service = VerificationService::new(InMemoryRevocationCheck::new())
+        .with_identity_resolver(Arc::new(FixedIdentity { keys: vec![issuer_keys().signer_ref()] }));
+    let outcome = service.verify(&request);
+    assert_eq!(outcome.status, VerifyStatus::Verified, "{:?}", outcome.reason);
+
+    let outcome = VerificationService::new(InMemoryRevocationCheck::new()).verify(&request);
+    assert_ne!(outcome.status, VerifyStatus::Verified);
+}
+
 // ---------------------------------------------------------------------------
 // Helper: prove the verify_authorization core path is reachable from here
 // ---------------------------------------------------------------------------
@@ -635,6 +869,8 @@ fn _core_path_is_reachable(
         tool_arguments: &tool_arguments(),
         revocation: &InMemoryRevocationCheck::new(),
         payment_payload_digest: None,
+        contract_verifier: None,
+        identity_resolver: None,
     };
     let _ = holder;
     let verified = verify_authorization(&input)?;
@@ -0,0 +1,140 @@
+//! Integration tests for EIP-8004 identity resolution against a mock
+//! JSON-RPC node and a registration-file fixture.
+
+#![allow(clippy::expect_used)]
+
+use std::{
+    sync::{
+        Arc,
+        atomic::{AtomicU32, Ordering},
+    },
+    time::Duration,
+};
+
+use ledgerflow_core::{
+    AgentIdRef, AuthorizationError, IdentityResolver, MerchantConstraint, PaymentConstraint,
+    SignerRef, SigningAlgorithm, SigningKeyPair, TrustedIssuer, TrustedIssuers, WarrantBuilder,
+    hex_encode_bytes,
+};
+use ledgerflow_facilitator::{IdentityRegistryResolver, MockJsonRpcTransport, RpcError};
+
+const REGISTRY: &str = "0x8004a169fb4a3325136eb29fa0ceb6d2e539a432";
+const REGISTRATION_URI: &str = "https://agent.example/registration.json";
+const REGISTRATION: &[u8] = include_bytes!("fixtures/eip8004-registration.json");
+
+fn agent(agent_id: u64) -> AgentIdRef {
+    AgentIdRef::parse(&format!("eip155:8453:{REGISTRY}/{agent_id}")).expect("agent")
+}
+
+fn issuer_keys() -> SigningKeyPair {
+    SigningKeyPair::from_bytes(&[81_u8; 32])
+}
+
+/// ABI-encodes `value` as a `string` return value.
+fn abi_string(value: &str) -> String {
+    let mut bytes = vec![0_u8; 64];
+    bytes[31] = 32;
+    bytes[56..64].copy_from_slice(&(value.len() as u64).to_be_bytes());
+    bytes.extend_from_slice(value.as_bytes());
+    bytes.resize(64 + value.len().div_ceil(32) * 32, 0);
+    format!("0x{}", hex_encode_bytes(&bytes))
+}
+
+/// A node whose registry serves `REGISTRATION_URI` for every token, counting
+/// `eth_call`s.
+fn node(calls: Arc<AtomicU32>) -> MockJsonRpcTransport {
+    MockJsonRpcTransport::new(move |method, params| {
+        calls.fetch_add(1, Ordering::SeqCst);
+        assert_eq!(method, "eth_call");
+        assert_eq!(params[0]["to"], REGISTRY);
+        // tokenURI(uint256)
+        let data = params[0]["data"].as_str().expect("data");
+        if !data.starts_with("0xc87b56dd") {
+            return Err(RpcError::Rpc { code: 3, message: "execution reverted".to_string() });
+        }
+        Ok(serde_json::Value::String(abi_string(REGISTRATION_URI)))
+    })
+}
+
+fn fixture(uri: &str) -> Result<Vec<u8>, String> {
+    if uri == REGISTRATION_URI { Ok(REGISTRATION.to_vec()) } else { Err(format!("404 {uri}")) }
+}
+
+#[test]
+fn anchored_trust_entries_accept_keys_bound_by_the_registration_file() {
+    let calls = Arc::new(AtomicU32::new(0));
+    let resolver = IdentityRegistryResolver::new(8453, node(Arc::clone(&calls)), fixture);
+
+    let keys = resolver.resolve_keys(&agent(22)).expect("resolved");
+    assert!(keys.contains(&issuer_keys().signer_ref()));
+    let mut wallet = [0_u8; 20];
+    wallet.copy_from_slice(
+        &ledgerflow_core::Secp256k1KeyPair::from_bytes(&[82_u8; 32])
+            .expect("secp256k1")
+            .ethereum_address(),
+    );
+    assert!(keys.contains(&SignerRef::new(SigningAlgorithm::EthPersonalSign, wallet.to_vec())));
+
+    let bootstrap = SigningKeyPair::from_bytes(&[80_u8; 32]);
+    let mut trusted = TrustedIssuers::new();
+    trusted.add(TrustedIssuer::anchored("agent-22".to_string(), bootstrap.signer_ref(), agent(22)));
+    let root = WarrantBuilder::new(2_000)
+        .ttl_secs(60)
+        .issuer(issuer_keys().signer_ref())
+        .holder(SigningKeyPair::from_bytes(&[83_u8; 32]).signer_ref())
+        .merchant(MerchantConstraint::with_ids(vec!["merchant-a".to_string()]))
+        .payment(PaymentConstraint::new(1_000))
+        .sign_with(&issuer_keys(), [0_u8; 8]);
+    trusted.verify_root_with_resolver(&root, Some(&resolver)).expect("anchored issuer");
+    // Served from the cache.
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    let stranger = WarrantBuilder::new(2_000)
+        .ttl_secs(60)
+        .issuer(SigningKeyPair::from_bytes(&[84_u8; 32]).signer_ref())
+        .holder(SigningKeyPair::from_bytes(&[83_u8; 32]).signer_ref())
+        .merchant(MerchantConstraint::with_ids(vec!["merchant-a".to_string()]))
+        .payment(PaymentConstraint::new(1_000))
+        .sign_with(&SigningKeyPair::from_bytes(&[84_u8; 32]), [0_u8; 8]);
+    let error =
+        trusted.verify_root_with_resolver(&stranger, Some(&resolver)).expect_err("unbound key");
+    assert!(matches!(error, AuthorizationError::IssuerNotBoundToIdentity { .. }));
+}
+
+#[test]
+fn failed_resolutions_are_negatively_cached() {
+    let calls = Arc::new(AtomicU32::new(0));
+    let resolver = IdentityRegistryResolver::new(8453, node(Arc::clone(&calls)), fixture);
+
+    // The fixture registers agent 22 only.
+    for _ in 0..3 {
+        let error = resolver.resolve_keys(&agent(23)).expect_err("not registered");
+        assert!(matches!(error, AuthorizationError::IdentityResolutionFailed { .. }));
+    }
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+    // Other chains are never resolved against this registry.
+    let mainnet = AgentIdRef::parse(&format!("eip155:1:{REGISTRY}/22")).expect("agent");
+    assert!(resolver.resolve_keys(&mainnet).is_err());
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn expired_entries_are_resolved_again() {
+    let calls = Arc::new(AtomicU32::new(0));
+    let resolver = IdentityRegistryResolver::new(8453, node(Arc::clone(&calls)), fixture)
+        .with_ttl(Duration::ZERO)
+        .with_negative_ttl(Duration::ZERO);
+
+    resolver.resolve_keys(&agent(22)).expect("first");
+    resolver.resolve_keys(&agent(22)).expect("second");
+    assert!(resolver.resolve_keys(&agent(23)).is_err());
+    assert!(resolver.resolve_keys(&agent(23)).is_err());
+    assert_eq!(calls.load(Ordering::SeqCst), 4);
+
+    let cached = IdentityRegistryResolver::new(8453, node(Arc::clone(&calls)), fixture);
+    cached.resolve_keys(&agent(22)).expect("cached");
+    cached.invalidate();
+    cached.resolve_keys(&agent(22)).expect("refetched");
+    assert_eq!(calls.load(Ordering::SeqCst), 6);
+}
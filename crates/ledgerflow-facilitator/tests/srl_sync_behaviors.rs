@@ -3,8 +3,11 @@
 
 #![allow(clippy::expect_used)]
 
+use std::time::Duration;
+
 use ledgerflow_core::{
     RevocationCheck, RevocationDecision, SignedRevocationList, SrlEntry, hex_encode_bytes,
+    srl_state_root,
 };
 use ledgerflow_facilitator::{FileRevocationStore, SrlSync};
 
@@ -30,7 +33,7 @@ fn srl_apply_persists_revocations_to_store() {
     let warrant_id = [0xAA; 16];
     let list = SignedRevocationList::sign(
         1,
-        vec![SrlEntry::Warrant { id_hex: hex_encode_bytes(&warrant_id) }],
+        vec![SrlEntry::Warrant { id_hex: hex_encode_bytes(&warrant_id), expires_at: None }],
         &control_keys(),
     );
     sync.apply(&list).expect("apply");
@@ -57,7 +60,7 @@ fn srl_apply_rejects_rollback_and_bad_sig() {
 
     sync.apply(&SignedRevocationList::sign(
         2,
-        vec![SrlEntry::Warrant { id_hex: hex_encode_bytes(&[0xAA; 16]) }],
+        vec![SrlEntry::Warrant { id_hex: hex_encode_bytes(&[0xAA; 16]), expires_at: None }],
         &control_keys(),
     ))
     .expect("v2");
@@ -65,7 +68,7 @@ fn srl_apply_rejects_rollback_and_bad_sig() {
     // Rollback to v1 rejected.
     let rollback = sync.apply(&SignedRevocationList::sign(
         1,
-        vec![SrlEntry::Warrant { id_hex: hex_encode_bytes(&[0xBB; 16]) }],
+        vec![SrlEntry::Warrant { id_hex: hex_encode_bytes(&[0xBB; 16]), expires_at: None }],
         &control_keys(),
     ));
     assert!(rollback.is_err(), "anti-rollback must reject older version");
@@ -74,7 +77,7 @@ fn srl_apply_rejects_rollback_and_bad_sig() {
     let attacker = ledgerflow_core::SigningKeyPair::from_bytes(&[0x77; 32]);
     let forged = sync.apply(&SignedRevocationList::sign(
         3,
-        vec![SrlEntry::Warrant { id_hex: hex_encode_bytes(&[0xCC; 16]) }],
+        vec![SrlEntry::Warrant { id_hex: hex_encode_bytes(&[0xCC; 16]), expires_at: None }],
         &attacker,
     ));
     assert!(forged.is_err(), "forged signature must be rejected");
@@ -108,3 +111,174 @@ fn srl_apply_holder_revocation_persists() {
     let _ = std::fs::remove_file(&path);
     let _ = std::fs::remove_dir(&dir);
 }
+
+#[test]
+fn srl_apply_replicates_subject_issuer_and_agent_scopes() {
+    use ledgerflow_core::{PaymentSubjectKind, PaymentSubjectRef, subject_revocation_key};
+
+    let dir = std::env::temp_dir().join(format!("ledgerflow-srl-scopes-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).expect("create dir");
+    let path = dir.join("revocations.jsonl");
+    let _ = std::fs::remove_file(&path);
+
+    let store = FileRevocationStore::open(&path).expect("open");
+    let sync = SrlSync::new(store, control_keys().signer_ref());
+    let subject = PaymentSubjectRef::new(PaymentSubjectKind::Opaque, "acct-1");
+    let issuer = holder_keys().signer_ref();
+    sync.apply(&SignedRevocationList::sign(
+        1,
+        vec![
+            SrlEntry::Subject { key_hex: hex_encode_bytes(&subject_revocation_key(&subject)) },
+            SrlEntry::Issuer { key_hex: hex_encode_bytes(&issuer.public_key) },
+            SrlEntry::Agent { key_hex: hex_encode_bytes(b"agent-1") },
+        ],
+        &control_keys(),
+    ))
+    .expect("apply scopes");
+
+    assert_eq!(sync.check_subject(&subject), RevocationDecision::RevokedSubject);
+    assert_eq!(sync.check_issuer(&issuer), RevocationDecision::RevokedIssuer);
+    assert_eq!(sync.check_agent("agent-1"), RevocationDecision::RevokedAgent);
+    let reloaded = FileRevocationStore::open(&path).expect("reopen");
+    assert_eq!(reloaded.check_issuer(&issuer), RevocationDecision::RevokedIssuer);
+    assert_eq!(reloaded.check_holder(&issuer), RevocationDecision::Ok);
+
+    let _ = std::fs::remove_file(&path);
+    let _ = std::fs::remove_dir(&dir);
+}
+
+#[test]
+fn srl_sync_applies_deltas_on_their_base_only_and_heartbeats() {
+    use std::sync::Mutex;
+
+    use ledgerflow_facilitator::SrlUpdate;
+
+    let dir = std::env::temp_dir().join(format!("ledgerflow-srl-poll-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).expect("create dir");
+    let path = dir.join("revocations.jsonl");
+    let _ = std::fs::remove_file(&path);
+
+    // A control plane at version 2 serving snapshots and deltas.
+    let log = [
+        SrlEntry::Warrant { id_hex: hex_encode_bytes(&[0xA1; 16]), expires_at: None },
+        SrlEntry::Warrant { id_hex: hex_encode_bytes(&[0xA2; 16]), expires_at: None },
+    ];
+    let requested = Mutex::new(Vec::new());
+    let source = |since: u64| {
+        requested.lock().expect("lock").push(since);
+        let version = log.len() as u64;
+        let list = match since {
+            0 => Some(SignedRevocationList::sign(version, log.to_vec(), &control_keys())),
+            since if since < version => Some(SignedRevocationList::sign_delta(
+                since,
+                version,
+                log[usize::try_from(since).expect("since")..].to_vec(),
+                0,
+                srl_state_root(&log),
+                &control_keys(),
+            )),
+            _ => None,
+        };
+        Ok(SrlUpdate { version, list })
+    };
+
+    let store = FileRevocationStore::open(&path).expect("open");
+    let sync = SrlSync::new(store.clone(), control_keys().signer_ref())
+        .with_max_age(Duration::from_mins(1));
+    assert_eq!(sync.sync_once(&source).expect("full"), 2);
+    assert_eq!(sync.sync_once(&source).expect("up to date"), 2);
+    assert_eq!(*requested.lock().expect("lock"), vec![0, 2]);
+    assert_eq!(store.check_warrant(&[0xA2; 16]), RevocationDecision::RevokedWarrant);
+    assert_eq!(sync.checkpoint(), (2, srl_state_root(&log)));
+    assert!(!sync.is_stale());
+
+    // A delta computed from another base is refused; the replica resyncs
+    // from the snapshot and ends up on the control plane's checkpoint.
+    let ahead = [log[0].clone(), log[1].clone(), SrlEntry::Holder { key_hex: "aa".to_string() }];
+    let rebased = |since: u64| {
+        let list = if since == 0 {
+            SignedRevocationList::sign(4, ahead.to_vec(), &control_keys())
+        } else {
+            SignedRevocationList::sign_delta(
+                3,
+                4,
+                ahead[2..].to_vec(),
+                0,
+                srl_state_root(&ahead),
+                &control_keys(),
+            )
+        };
+        Ok(SrlUpdate { version: 4, list: Some(list) })
+    };
+    assert_eq!(sync.sync_once(&rebased).expect("resync"), 4);
+    assert_eq!(sync.checkpoint(), (4, srl_state_root(&ahead)));
+
+    // A snapshot behind the applied version is still a rollback.
+    let behind = |_: u64| {
+        Ok(SrlUpdate {
+            version: 3,
+            list: Some(SignedRevocationList::sign(3, log.to_vec(), &control_keys())),
+        })
+    };
+    let error = sync.sync_once(&behind).expect_err("rollback");
+    assert!(matches!(
+        error,
+        ledgerflow_facilitator::SrlSyncError::Core(
+            ledgerflow_core::AuthorizationError::SrlVersionRegression { presented: 3, applied: 4 }
+        )
+    ));
+    let unreachable = |_: u64| Err("connection refused".to_string());
+    assert!(sync.sync_once(&unreachable).is_err());
+    assert_eq!(sync.applied_version(), 4);
+
+    let _ = std::fs::remove_file(&path);
+    let _ = std::fs::remove_dir(&dir);
+}
+
+#[test]
+fn stale_srl_replica_fails_closed_only_when_configured() {
+    let dir = std::env::temp_dir().join(format!("ledgerflow-srl-stale-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).expect("create dir");
+    let path = dir.join("revocations.jsonl");
+    let _ = std::fs::remove_file(&path);
+
+    let store = FileRevocationStore::open(&path).expect("open");
+    let holder = holder_keys().signer_ref();
+    let lenient =
+        SrlSync::new(store.clone(), control_keys().signer_ref()).with_max_age(Duration::ZERO);
+    let strict = lenient.clone().with_fail_closed(true);
+    std::thread::sleep(Duration::from_millis(5));
+    assert!(strict.is_stale());
+    assert_eq!(lenient.check_warrant(&[0xA1; 16]), RevocationDecision::Ok);
+    assert_eq!(strict.check_warrant(&[0xA1; 16]), RevocationDecision::Unavailable);
+    assert_eq!(strict.check_holder(&holder), RevocationDecision::Unavailable);
+    assert_eq!(strict.check_issuer(&holder), RevocationDecision::Unavailable);
+
+    // A node that has not reached the control plane yet is stale too.
+    let fresh = SrlSync::new(store, control_keys().signer_ref())
+        .with_max_age(Duration::from_mins(1))
+        .with_fail_closed(true);
+    assert!(fresh.heartbeat_age().is_none());
+    assert_eq!(fresh.check_warrant(&[0xA1; 16]), RevocationDecision::Unavailable);
+
+    // The poller heartbeats in the background until stopped.
+    let polls = std::sync::Arc::new(std::sync::atomic::AtomicU32::new(0));
+    let counter = std::sync::Arc::clone(&polls);
+    let poller = fresh
+        .spawn_poller(
+            move |_: u64| {
+                counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                Ok(ledgerflow_facilitator::SrlUpdate { version: 0, list: None })
+            },
+            Duration::from_millis(1),
+        )
+        .expect("spawn");
+    while polls.load(std::sync::atomic::Ordering::SeqCst) < 3 {
+        std::thread::sleep(Duration::from_millis(1));
+    }
+    poller.stop();
+    assert_eq!(fresh.check_warrant(&[0xA1; 16]), RevocationDecision::Ok);
+
+    let _ = std::fs::remove_file(&path);
+    let _ = std::fs::remove_dir(&dir);
+}
@@ -0,0 +1,331 @@
+//! RFC 9110 §11 authentication header grammar.
+//!
+//! Parses and serializes `WWW-Authenticate` challenge lists and
+//! `Authorization` credentials:
+//!
+//! ```text
+//! challenge   = auth-scheme [ 1*SP ( token68 / #auth-param ) ]
+//! credentials = auth-scheme [ 1*SP ( token68 / #auth-param ) ]
+//! auth-param  = token BWS "=" BWS ( token / quoted-string )
+//! ```
+//!
+//! Scheme and parameter names compare case-insensitively; a parameter may
+//! occur at most once per challenge. The Payment scheme on top of this lives
+//! in [`crate::mpp`].
+
+use crate::error::ProtocolError;
+
+/// One challenge (or the credentials) of an authentication header.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct AuthChallenge {
+    pub scheme: String,
+    /// The `token68` form (mutually exclusive with `params`).
+    pub token68: Option<String>,
+    /// Auth-params in header order, values unquoted.
+    pub params: Vec<(String, String)>,
+}
+
+impl AuthChallenge {
+    #[must_use]
+    pub fn new(scheme: impl Into<String>) -> Self {
+        Self { scheme: scheme.into(), ..Self::default() }
+    }
+
+    /// Appends an auth-param.
+    #[must_use]
+    pub fn param(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.params.push((name.into(), value.into()));
+        self
+    }
+
+    /// Whether this challenge uses `scheme` (case-insensitive).
+    #[must_use]
+    pub fn is_scheme(&self, scheme: &str) -> bool {
+        self.scheme.eq_ignore_ascii_case(scheme)
+    }
+
+    /// The value of parameter `name` (case-insensitive).
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(param, _)| param.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Serializes the challenge. Parameter values are always emitted as
+    /// quoted-strings.
+    pub fn to_header_value(&self) -> Result<String, ProtocolError> {
+        if !is_token(&self.scheme) {
+            return Err(invalid(format!("invalid auth-scheme {:?}", self.scheme)));
+        }
+        let mut value = self.scheme.clone();
+        if let Some(token68) = &self.token68 {
+            if !is_token68(token68) {
+                return Err(invalid("invalid token68"));
+            }
+            value.push(' ');
+            value.push_str(token68);
+            return Ok(value);
+        }
+        for (index, (name, param)) in self.params.iter().enumerate() {
+            if !is_token(name) {
+                return Err(invalid(format!("invalid auth-param name {name:?}")));
+            }
+            if param.chars().any(|c| c.is_control() && c != '\t') {
+                return Err(invalid(format!("auth-param {name} contains a control character")));
+            }
+            value.push_str(if index == 0 { " " } else { ", " });
+            value.push_str(name);
+            value.push_str("=\"");
+            for c in param.chars() {
+                if c == '"' || c == '\\' {
+                    value.push('\\');
+                }
+                value.push(c);
+            }
+            value.push('"');
+        }
+        Ok(value)
+    }
+}
+
+/// Parses a `WWW-Authenticate` value (a comma-separated challenge list).
+pub fn parse_challenges(value: &str) -> Result<Vec<AuthChallenge>, ProtocolError> {
+    let mut cursor = Cursor::new(value);
+    let mut challenges = Vec::new();
+    loop {
+        cursor.skip_list_separators();
+        if cursor.at_end() {
+            return Ok(challenges);
+        }
+        challenges.push(cursor.challenge()?);
+        cursor.skip_ows();
+        if !cursor.at_end() && cursor.peek() != Some(b',') {
+            return Err(invalid(format!("unexpected input at byte {}", cursor.pos)));
+        }
+    }
+}
+
+/// Parses an `Authorization` value (exactly one set of credentials).
+pub fn parse_credentials(value: &str) -> Result<AuthChallenge, ProtocolError> {
+    let mut cursor = Cursor::new(value);
+    cursor.skip_ows();
+    let credentials = cursor.challenge()?;
+    cursor.skip_ows();
+    if !cursor.at_end() {
+        return Err(invalid(format!("unexpected input at byte {}", cursor.pos)));
+    }
+    Ok(credentials)
+}
+
+fn invalid(reason: impl Into<String>) -> ProtocolError {
+    ProtocolError::InvalidAuthHeader(reason.into())
+}
+
+fn is_tchar(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&byte)
+}
+
+const fn is_token68_char(byte: u8) -> bool {
+    byte.is_ascii_alphanumeric() || matches!(byte, b'-' | b'.' | b'_' | b'~' | b'+' | b'/')
+}
+
+fn is_token(value: &str) -> bool {
+    !value.is_empty() && value.bytes().all(is_tchar)
+}
+
+fn is_token68(value: &str) -> bool {
+    let body = value.trim_end_matches('=');
+    !body.is_empty() && body.bytes().all(is_token68_char)
+}
+
+/// Byte cursor over a header value.
+struct Cursor<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl<'a> Cursor<'a> {
+    const fn new(input: &'a str) -> Self {
+        Self { input, pos: 0 }
+    }
+
+    fn peek(&self) -> Option<u8> {
+        self.input.as_bytes().get(self.pos).copied()
+    }
+
+    const fn at_end(&self) -> bool {
+        self.pos >= self.input.len()
+    }
+
+    fn skip_ows(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t')) {
+            self.pos += 1;
+        }
+    }
+
+    /// Skips OWS and empty list elements (`#rule` allows `, ,`).
+    fn skip_list_separators(&mut self) {
+        while matches!(self.peek(), Some(b' ' | b'\t' | b',')) {
+            self.pos += 1;
+        }
+    }
+
+    fn take_while(&mut self, accept: impl Fn(u8) -> bool) -> &'a str {
+        let start = self.pos;
+        while self.peek().is_some_and(&accept) {
+            self.pos += 1;
+        }
+        &self.input[start..self.pos]
+    }
+
+    fn token(&mut self) -> Option<&'a str> {
+        Some(self.take_while(is_tchar)).filter(|token| !token.is_empty())
+    }
+
+    fn quoted_string(&mut self) -> Result<String, ProtocolError> {
+        // Caller has checked the opening quote.
+        self.pos += 1;
+        let mut value = String::new();
+        let mut chars = self.input[self.pos..].char_indices();
+        while let Some((offset, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.pos += offset + 1;
+                    return Ok(value);
+                }
+                '\\' => match chars.next() {
+                    Some((_, escaped)) => value.push(escaped),
+                    None => break,
+                },
+                c if c.is_control() && c != '\t' => {
+                    return Err(invalid("control character in quoted-string"));
+                }
+                c => value.push(c),
+            }
+        }
+        Err(invalid("unterminated quoted-string"))
+    }
+
+    /// `token BWS "=" BWS ( token / quoted-string )`, or `None` (cursor
+    /// unchanged) when the input is not an auth-param.
+    fn auth_param(&mut self) -> Result<Option<(String, String)>, ProtocolError> {
+        let start = self.pos;
+        let Some(name) = self.token() else {
+            return Ok(None);
+        };
+        self.skip_ows();
+        if self.peek() != Some(b'=') {
+            self.pos = start;
+            return Ok(None);
+        }
+        self.pos += 1;
+        self.skip_ows();
+        let value = if self.peek() == Some(b'"') {
+            self.quoted_string()?
+        } else if let Some(token) = self.token() {
+            token.to_string()
+        } else {
+            // `name=` followed by `=`, `,` or the end is token68 padding.
+            self.pos = start;
+            return Ok(None);
+        };
+        Ok(Some((name.to_string(), value)))
+    }
+
+    fn challenge(&mut self) -> Result<AuthChallenge, ProtocolError> {
+        let scheme =
+            self.token().ok_or_else(|| invalid(format!("expected auth-scheme at {}", self.pos)))?;
+        let mut challenge = AuthChallenge::new(scheme);
+        let after_scheme = self.pos;
+        self.skip_ows();
+        if self.pos == after_scheme || self.at_end() || self.peek() == Some(b',') {
+            self.pos = after_scheme;
+            return Ok(challenge);
+        }
+
+        if let Some(param) = self.auth_param()? {
+            challenge.params.push(param);
+            loop {
+                let before_comma = self.pos;
+                self.skip_ows();
+                if self.peek() != Some(b',') {
+                    self.pos = before_comma;
+                    break;
+                }
+                self.skip_list_separators();
+                // Anything but an auth-param starts the next challenge.
+                let Some((name, value)) = self.auth_param()? else {
+                    self.pos = before_comma;
+                    break;
+                };
+                if challenge.get(&name).is_some() {
+                    return Err(invalid(format!("duplicate auth-param {name}")));
+                }
+                challenge.params.push((name, value));
+            }
+            return Ok(challenge);
+        }
+
+        let token68 = self.take_while(is_token68_char);
+        if token68.is_empty() {
+            return Err(invalid(format!("expected token68 or auth-param at {}", self.pos)));
+        }
+        let padding = self.take_while(|byte| byte == b'=');
+        challenge.token68 = Some(format!("{token68}{padding}"));
+        Ok(challenge)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::expect_used)]
+
+    use super::*;
+
+    #[test]
+    fn parses_challenge_lists_with_mixed_forms() {
+        let challenges = parse_challenges(
+            r#"Basic realm="simple", Newauth realm="apps", type=1, title="Login to \"apps\"", Bearer abc.def==, Payment"#,
+        )
+        .expect("parse");
+        assert_eq!(challenges.len(), 4);
+        assert_eq!(challenges[0].get("REALM"), Some("simple"));
+        assert!(challenges[1].is_scheme("newauth"));
+        assert_eq!(challenges[1].get("type"), Some("1"));
+        assert_eq!(challenges[1].get("title"), Some(r#"Login to "apps""#));
+        assert_eq!(challenges[2].token68.as_deref(), Some("abc.def=="));
+        assert_eq!(challenges[3], AuthChallenge::new("Payment"));
+    }
+
+    #[test]
+    fn serialization_round_trips() {
+        let challenge = AuthChallenge::new("Payment")
+            .param("id", "c-1")
+            .param("realm", "api \"v1\" \\ x")
+            .param("intent", "charge");
+        let header = challenge.to_header_value().expect("serialize");
+        assert_eq!(header, r#"Payment id="c-1", realm="api \"v1\" \\ x", intent="charge""#);
+        assert_eq!(parse_credentials(&header).expect("parse"), challenge);
+        assert_eq!(
+            parse_challenges(&format!("{header}, Basic realm=x")).expect("list")[0],
+            challenge
+        );
+    }
+
+    #[test]
+    fn malformed_headers_are_rejected() {
+        for value in [
+            r#"Payment id="unterminated"#,
+            "Payment id=a, id=b",
+            "Payment id=a extra",
+            "Payment, =x",
+            "Payment id=\"a\u{1}\"",
+        ] {
+            assert!(parse_challenges(value).is_err(), "{value}");
+        }
+        assert!(parse_credentials("Payment id=a, Basic realm=x").is_err());
+        assert!(AuthChallenge::new("Pay ment").to_header_value().is_err());
+    }
+}
@@ -12,8 +12,14 @@ pub enum ProtocolError {
     Serialization(String),
     #[error("failed to decode the payload from CBOR: {0}")]
     Deserialization(String),
+    #[error("invalid x402 JSON: {0}")]
+    Json(String),
     #[error("invalid base64: {0}")]
     InvalidBase64(String),
+    #[error("invalid authentication header: {0}")]
+    InvalidAuthHeader(String),
+    #[error("the body does not match the ledgerflow-digest reference")]
+    DigestMismatch,
     #[error("the warrant chain must not be empty")]
     EmptyChain,
     #[error("the carrier cannot carry {size} bytes (limit {max})")]
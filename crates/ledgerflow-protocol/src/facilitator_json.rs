@@ -0,0 +1,197 @@
+//! JSON bodies of the hosted facilitator's `POST /v1/verify` and
+//! `POST /v1/settle` (design §8).
+//!
+//! `ledgerflow-server` deserializes [`FacilitatorRequest`]; merchants that
+//! settle through a hosted facilitator (e.g. the paywall's
+//! `HostedFacilitator`) serialize the same type, so both ends share one wire
+//! shape.
+//!
+//! The payment payload is a JSON object or base64 of that object (the x402
+//! `X-PAYMENT` header form); the LedgerFlow extension inside it is base64url
+//! CBOR, exactly as carried on the wire. The challenge is a JSON object or
+//! its MPP `ledgerflow` parameter form.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::ProtocolError,
+    mpp::encode_challenge_param,
+    wire::base64url_encode,
+    x402::{
+        AcceptedQuote, HttpRequest, LedgerFlowAuthorizationExtension, LedgerFlowChallenge,
+        PaymentPayload,
+    },
+};
+
+/// x402 `accepted` quote. The amount is a decimal string (x402 convention).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct AcceptedQuoteBody {
+    pub scheme: String,
+    pub asset: String,
+    pub amount: String,
+    pub payee_id: String,
+    #[serde(default)]
+    pub network: Option<String>,
+}
+
+impl From<&AcceptedQuote> for AcceptedQuoteBody {
+    fn from(quote: &AcceptedQuote) -> Self {
+        Self {
+            scheme: quote.scheme.clone(),
+            asset: quote.asset.clone(),
+            amount: quote.amount.to_string(),
+            payee_id: quote.payee_id.clone(),
+            network: quote.network.clone(),
+        }
+    }
+}
+
+/// x402 payment payload with the LedgerFlow extension.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct PaymentPayloadBody {
+    pub accepted: AcceptedQuoteBody,
+    pub settlement_payload: String,
+    #[serde(default)]
+    pub payment_identifier: Option<String>,
+    /// base64url(CBOR `LedgerFlowAuthorizationExtension`).
+    pub ledgerflow: String,
+}
+
+impl PaymentPayloadBody {
+    /// The body of `payload`, carrying `extension` (the payload's LedgerFlow
+    /// authorization).
+    ///
+    /// # Errors
+    /// [`ProtocolError::Serialization`] when the extension does not encode.
+    pub fn new(
+        payload: &PaymentPayload,
+        extension: &LedgerFlowAuthorizationExtension,
+    ) -> Result<Self, ProtocolError> {
+        Ok(Self {
+            accepted: AcceptedQuoteBody::from(&payload.accepted),
+            settlement_payload: payload.settlement_payload.clone(),
+            payment_identifier: payload.payment_identifier.clone(),
+            ledgerflow: base64url_encode(&extension.encode_cbor()?),
+        })
+    }
+}
+
+/// A payment payload as a JSON object or base64 of that JSON.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum PaymentPayloadWire {
+    Encoded(String),
+    Json(PaymentPayloadBody),
+}
+
+/// The merchant's LedgerFlow challenge as a JSON object or base64url CBOR
+/// (the MPP `ledgerflow` parameter form).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum ChallengeWire {
+    Encoded(String),
+    Json(LedgerFlowChallenge),
+}
+
+/// The HTTP request being paid for (canonical request binding).
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct RequestContextBody {
+    pub method: String,
+    pub authority: String,
+    pub path_and_query: String,
+    /// base64url request body (empty when absent).
+    #[serde(default)]
+    pub body: String,
+}
+
+impl From<&HttpRequest> for RequestContextBody {
+    fn from(request: &HttpRequest) -> Self {
+        Self {
+            method: request.method.clone(),
+            authority: request.authority.clone(),
+            path_and_query: request.path_and_query.clone(),
+            body: base64url_encode(&request.body),
+        }
+    }
+}
+
+/// Body of `POST /v1/verify` and `POST /v1/settle`.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct FacilitatorRequest {
+    #[cfg_attr(feature = "utoipa", schema(value_type = Object))]
+    pub payment_payload: PaymentPayloadWire,
+    #[cfg_attr(feature = "utoipa", schema(value_type = Object))]
+    pub challenge: ChallengeWire,
+    pub request: RequestContextBody,
+    /// Tool being invoked (selects approval gates).
+    #[serde(default)]
+    pub tool_name: String,
+    /// Model provider driving the call (tool constraints).
+    #[serde(default)]
+    pub model_provider: String,
+    /// Action label of the call (tool constraints).
+    #[serde(default)]
+    pub action_label: String,
+    /// Tool-call arguments (evaluated by approval gates).
+    #[serde(default)]
+    pub tool_arguments: BTreeMap<String, String>,
+}
+
+impl FacilitatorRequest {
+    /// Posts `payment_payload`, paying for `request` under `challenge` (sent
+    /// in its MPP parameter form).
+    ///
+    /// # Errors
+    /// [`ProtocolError::Serialization`] when the challenge does not encode.
+    pub fn new(
+        payment_payload: PaymentPayloadBody,
+        challenge: &LedgerFlowChallenge,
+        request: &HttpRequest,
+    ) -> Result<Self, ProtocolError> {
+        Ok(Self {
+            payment_payload: PaymentPayloadWire::Json(payment_payload),
+            challenge: ChallengeWire::Encoded(encode_challenge_param(challenge)?),
+            request: RequestContextBody::from(request),
+            tool_name: String::new(),
+            model_provider: String::new(),
+            action_label: String::new(),
+            tool_arguments: BTreeMap::new(),
+        })
+    }
+
+    /// The tool being invoked.
+    #[must_use]
+    pub fn with_tool_name(mut self, tool_name: impl Into<String>) -> Self {
+        self.tool_name = tool_name.into();
+        self
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::expect_used)]
+
+    use super::*;
+
+    #[test]
+    fn payment_payload_wire_accepts_object_or_string() {
+        let object: PaymentPayloadWire = serde_json::from_value(serde_json::json!({
+            "accepted": {
+                "scheme": "exact", "asset": "USDC", "amount": "100", "payee_id": "merchant-a"
+            },
+            "settlement_payload": "0xabc",
+            "ledgerflow": "AA"
+        }))
+        .expect("object");
+        assert!(matches!(object, PaymentPayloadWire::Json(_)));
+        let encoded: PaymentPayloadWire =
+            serde_json::from_value(serde_json::json!("eyJ9")).expect("string");
+        assert!(matches!(encoded, PaymentPayloadWire::Encoded(_)));
+    }
+}
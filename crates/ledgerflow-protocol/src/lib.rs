@@ -4,34 +4,65 @@
 //! types to concrete protocols:
 //!
 //! - [`x402`]: x402 v2 extensions (challenge + payment payload).
-//! - [`mpp`]: MPP Payment HTTP authentication scheme parameters.
-//! - [`middleware`]: merchant-side verification (trust anchor, revocation, replay, approvals).
+//! - [`x402_json`]: x402 v2 JSON documents and `PAYMENT-*` header codecs.
+//! - [`mpp`]: MPP Payment HTTP authentication scheme headers, with carrier selection.
+//! - [`facilitator_json`]: JSON bodies of the hosted facilitator's verify / settle endpoints.
+//! - [`mcp`]: MCP `tools/call` binding (`params._meta.ledgerflow`) and payment-required tool
+//!   results.
+//! - [`auth_param`]: RFC 9110 authentication header grammar.
+//! - [`middleware`]: merchant-side verification (trust anchor, revocation, replay, approvals),
+//!   synchronous or shared across async handlers.
+//! - `paywall` (feature `paywall`): tower / axum layer running the whole merchant 402 flow.
+//! - `remote_warrants` (feature `remote-warrants`): resolves digest-referenced parents from a
+//!   remote `GET /v1/warrants/{digest}` endpoint.
 //! - [`replay`]: nonce replay protection and payment-id idempotency.
 //! - [`carrier`]: transport carrier size policy.
 
 #![allow(missing_docs)]
 
+pub mod auth_param;
 pub mod carrier;
 pub mod error;
+pub mod facilitator_json;
+pub mod mcp;
 pub mod middleware;
 pub mod mpp;
+#[cfg(feature = "paywall")]
+pub mod paywall;
+#[cfg(feature = "remote-warrants")]
+pub mod remote_warrants;
 pub mod replay;
 pub mod vc;
 pub mod wire;
 pub mod x402;
+pub mod x402_json;
 
 pub use crate::{
     carrier::{LedgerFlowCarrier, MAX_HEADER_CBOR_BYTES},
     error::ProtocolError,
+    facilitator_json::{
+        AcceptedQuoteBody, ChallengeWire, FacilitatorRequest, PaymentPayloadBody,
+        PaymentPayloadWire, RequestContextBody,
+    },
+    mcp::{
+        MCP_METHOD, MCP_TOOLS_CALL, McpToolCall, build_tool_call_payment,
+        payment_required_from_result, payment_required_result,
+    },
     middleware::{
-        InMemoryWarrantRepository, MerchantVerificationError, MerchantVerificationOutcome,
-        MerchantVerifier, WarrantRepository,
+        AsyncMerchantVerifier, AsyncWarrantRepository, InMemoryWarrantRepository,
+        MerchantVerificationError, MerchantVerificationOutcome, MerchantVerifier,
+        VerificationContextBuilder, WarrantRepository, authorization_context,
     },
     mpp::{
-        LEDGERFLOW_PARAM, SlimAuthorization, decode_authorization_param, decode_challenge_param,
+        EmittedCredential, LEDGERFLOW_BODY_CONTENT_TYPE, LEDGERFLOW_DIGEST_PARAM, LEDGERFLOW_PARAM,
+        LedgerFlowReference, PAYMENT_SCHEME, PaymentChallenge, PaymentCredential,
+        SlimAuthorization, decode_authorization_param, decode_challenge_param,
         encode_authorization_param, encode_challenge_param,
     },
-    replay::{InMemoryReplayStore, NonceClaim, ReplayConflict, ReplayFingerprint, ReplayStore},
+    replay::{
+        AsyncReplayStore, InMemoryReplayStore, NonceClaim, ReplayConflict, ReplayFingerprint,
+        ReplayStore,
+    },
     vc::{
         CREDENTIAL_TYPE_WARRANT, CredentialSubject, LedgerFlowAlg, Proof, VC_CONTEXT_WARRANT_V1,
         WarrantCredential, credential_from_json, credential_to_json, did_key_ed25519,
@@ -44,4 +75,10 @@ pub use crate::{
         PaymentRequiredResponse, build_payment_payload, canonical_accepted_hash,
         canonical_request_hash, merchant_payment_required, merchant_payment_required_with,
     },
+    x402_json::{
+        LEDGERFLOW_EXTENSION_KEY, PAYMENT_REQUIRED_HEADER, PAYMENT_RESPONSE_HEADER,
+        PAYMENT_SIGNATURE_HEADER, X402_VERSION, X402PaymentPayload, X402PaymentRequired,
+        X402PaymentRequirements, X402ResourceInfo, X402SettlementResponse,
+        decode_payment_signature, encode_payment_signature,
+    },
 };
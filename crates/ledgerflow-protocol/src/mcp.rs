@@ -0,0 +1,259 @@
+//! MCP carrier binding for LedgerFlow authorization (design §7.3).
+//!
+//! A paid MCP tool call carries its x402 payment, LedgerFlow extension
+//! included, in `params._meta.ledgerflow` of the `tools/call` request, with
+//! the full chain inline. A server refusing an unpaid call answers with an
+//! error tool result carrying the x402 `PaymentRequired` document in
+//! `_meta.ledgerflow`, so no HTTP 402 round trip is needed.
+//!
+//! The PoP binds to the call through a synthetic request: method
+//! [`MCP_METHOD`], the server name as authority, `/tools/{name}` as path and
+//! the canonical JSON arguments as body; `tool_args_digest` commits to the
+//! arguments as [`ToolArguments`]. Warrant resource constraints therefore
+//! name `MCP` and `/tools/...` prefixes.
+//!
+//! ```ignore
+//! // Agent: answer the challenge in the tool result.
+//! let challenge = payment_required_from_result(&result)?.and_then(|r| r.ledgerflow);
+//! let call = call.with_payment(&build_tool_call_payment(&challenge, "search.example", &call, quote, chain, seed)?)?;
+//!
+//! // Server: verify the call.
+//! verifier.verify_tool_call(&challenge, "search.example", &call, &trusted, &context, now_ms)?;
+//! ```
+
+use ledgerflow_core::{PopTuple, ToolArguments, WarrantChain};
+use serde_json::{Map, Value, json};
+
+use crate::{
+    error::ProtocolError,
+    x402::{
+        AcceptedQuote, HttpRequest, LedgerFlowChallenge, PaymentPayload, PaymentPayloadSeed,
+        PaymentRequiredResponse, build_payment_payload,
+    },
+    x402_json::{LEDGERFLOW_EXTENSION_KEY, X402PaymentPayload, X402PaymentRequired},
+};
+
+/// The JSON-RPC method of an MCP tool invocation.
+pub const MCP_TOOLS_CALL: &str = "tools/call";
+
+/// The method of the synthetic request a tool call binds to.
+pub const MCP_METHOD: &str = "MCP";
+
+/// The `params` of an MCP `tools/call` request.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct McpToolCall {
+    pub name: String,
+    pub arguments: Map<String, Value>,
+    /// `params._meta`, LedgerFlow payment included.
+    pub meta: Map<String, Value>,
+}
+
+impl McpToolCall {
+    #[must_use]
+    pub fn new(name: impl Into<String>, arguments: Map<String, Value>) -> Self {
+        Self { name: name.into(), arguments, meta: Map::new() }
+    }
+
+    /// Parses the `params` of a `tools/call` request.
+    pub fn from_params(params: &Value) -> Result<Self, ProtocolError> {
+        let name = params
+            .get("name")
+            .and_then(Value::as_str)
+            .ok_or_else(|| invalid("tools/call params.name must be a string"))?;
+        Ok(Self {
+            name: name.to_string(),
+            arguments: object_member(params, "arguments")?,
+            meta: object_member(params, "_meta")?,
+        })
+    }
+
+    /// The `params` of a `tools/call` request.
+    #[must_use]
+    pub fn to_params(&self) -> Value {
+        let mut params = Map::new();
+        params.insert("name".to_string(), Value::String(self.name.clone()));
+        params.insert("arguments".to_string(), Value::Object(self.arguments.clone()));
+        if !self.meta.is_empty() {
+            params.insert("_meta".to_string(), Value::Object(self.meta.clone()));
+        }
+        Value::Object(params)
+    }
+
+    /// The arguments as [`ToolArguments`] for approval gates: string values
+    /// verbatim, anything else as compact JSON.
+    #[must_use]
+    pub fn tool_arguments(&self) -> ToolArguments {
+        self.arguments
+            .iter()
+            .map(|(key, value)| {
+                let value = match value {
+                    Value::String(value) => value.clone(),
+                    other => other.to_string(),
+                };
+                (key.clone(), value)
+            })
+            .collect()
+    }
+
+    /// The `tool_args_digest` a PoP for this call must carry.
+    #[must_use]
+    pub fn tool_args_digest(&self) -> Option<String> {
+        PopTuple::tool_args_digest(&self.tool_arguments())
+    }
+
+    /// The synthetic request the PoP binds to for this call on `server`.
+    #[must_use]
+    pub fn binding_request(&self, server: &str) -> HttpRequest {
+        HttpRequest::new(
+            MCP_METHOD,
+            server,
+            format!("/tools/{}", self.name),
+            Value::Object(self.arguments.clone()).to_string().into_bytes(),
+        )
+    }
+
+    /// Attaches `payment` as `_meta.ledgerflow`.
+    pub fn with_payment(mut self, payment: &PaymentPayload) -> Result<Self, ProtocolError> {
+        let document = X402PaymentPayload::from_payload(payment)?;
+        self.meta.insert(LEDGERFLOW_EXTENSION_KEY.to_string(), to_json(&document)?);
+        Ok(self)
+    }
+
+    /// The payment in `_meta.ledgerflow`, if any.
+    pub fn payment(&self) -> Result<Option<PaymentPayload>, ProtocolError> {
+        self.meta
+            .get(LEDGERFLOW_EXTENSION_KEY)
+            .map(|document| {
+                serde_json::from_value::<X402PaymentPayload>(document.clone())
+                    .map_err(|error| invalid(error.to_string()))?
+                    .to_payload()
+            })
+            .transpose()
+    }
+}
+
+/// Builds the payment for `call` on `server`: the PoP binds to the call's
+/// [`McpToolCall::binding_request`] and its arguments, overriding
+/// `seed.tool_args`.
+pub fn build_tool_call_payment(
+    challenge: &LedgerFlowChallenge,
+    server: &str,
+    call: &McpToolCall,
+    accepted: AcceptedQuote,
+    chain: WarrantChain,
+    seed: PaymentPayloadSeed,
+) -> Result<PaymentPayload, ProtocolError> {
+    build_payment_payload(
+        challenge,
+        &call.binding_request(server),
+        accepted,
+        chain,
+        PaymentPayloadSeed { tool_args: call.tool_arguments(), ..seed },
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Payment-required tool results
+// ---------------------------------------------------------------------------
+
+/// The error tool result refusing an unpaid call: a text content block and
+/// the x402 `PaymentRequired` document in `_meta.ledgerflow`.
+pub fn payment_required_result(response: &PaymentRequiredResponse) -> Result<Value, ProtocolError> {
+    let document = X402PaymentRequired::from_response(response)?;
+    Ok(json!({
+        "content": [{ "type": "text", "text": "payment required" }],
+        "isError": true,
+        "_meta": { LEDGERFLOW_EXTENSION_KEY: to_json(&document)? },
+    }))
+}
+
+/// The payment challenge of a tool result, or `None` for any other result.
+pub fn payment_required_from_result(
+    result: &Value,
+) -> Result<Option<PaymentRequiredResponse>, ProtocolError> {
+    result
+        .pointer(&format!("/_meta/{LEDGERFLOW_EXTENSION_KEY}"))
+        .map(|document| {
+            serde_json::from_value::<X402PaymentRequired>(document.clone())
+                .map_err(|error| invalid(error.to_string()))?
+                .to_response()
+        })
+        .transpose()
+}
+
+fn object_member(params: &Value, key: &str) -> Result<Map<String, Value>, ProtocolError> {
+    match params.get(key) {
+        None | Some(Value::Null) => Ok(Map::new()),
+        Some(Value::Object(object)) => Ok(object.clone()),
+        Some(_) => Err(invalid(format!("tools/call params.{key} must be an object"))),
+    }
+}
+
+fn to_json(value: &impl serde::Serialize) -> Result<Value, ProtocolError> {
+    serde_json::to_value(value).map_err(|error| invalid(error.to_string()))
+}
+
+fn invalid(reason: impl Into<String>) -> ProtocolError {
+    ProtocolError::Json(reason.into())
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::expect_used)]
+
+    use super::*;
+    use crate::x402::merchant_payment_required;
+
+    fn call() -> McpToolCall {
+        McpToolCall::from_params(&json!({
+            "name": "web-search",
+            "arguments": { "query": "rust", "limit": 5, "filters": { "lang": "en" } },
+        }))
+        .expect("params")
+    }
+
+    #[test]
+    fn tool_arguments_keep_strings_and_encode_other_values() {
+        let arguments = call().tool_arguments();
+        assert_eq!(arguments["query"], "rust");
+        assert_eq!(arguments["limit"], "5");
+        assert_eq!(arguments["filters"], r#"{"lang":"en"}"#);
+        assert!(call().tool_args_digest().is_some());
+        assert_eq!(McpToolCall::new("noop", Map::new()).tool_args_digest(), None);
+    }
+
+    #[test]
+    fn binding_request_is_canonical() {
+        let request = call().binding_request("search.example");
+        assert_eq!(request.method, MCP_METHOD);
+        assert_eq!(request.path_and_query, "/tools/web-search");
+        assert_eq!(request.body, br#"{"filters":{"lang":"en"},"limit":5,"query":"rust"}"#.to_vec());
+    }
+
+    #[test]
+    fn malformed_params_are_rejected() {
+        assert!(McpToolCall::from_params(&json!({ "arguments": {} })).is_err());
+        assert!(McpToolCall::from_params(&json!({ "name": "x", "arguments": [1] })).is_err());
+        assert!(McpToolCall::from_params(&json!({ "name": "x", "_meta": "x" })).is_err());
+        let bare = McpToolCall::from_params(&json!({ "name": "x" })).expect("bare");
+        assert_eq!(bare.payment().expect("payment"), None);
+        assert_eq!(bare.to_params(), json!({ "name": "x", "arguments": {} }));
+    }
+
+    #[test]
+    fn payment_required_result_round_trips() {
+        let response = merchant_payment_required(
+            "challenge-1",
+            "merchant-a",
+            "mcp://search.example/tools/web-search",
+            vec![AcceptedQuote::exact("USDC", 100, "merchant-a", Some("base".to_string()))],
+            60_000,
+        );
+        let result = payment_required_result(&response).expect("result");
+        assert_eq!(result["isError"], true);
+        let parsed = payment_required_from_result(&result).expect("parse").expect("challenge");
+        assert_eq!(parsed.ledgerflow, response.ledgerflow);
+        assert_eq!(parsed.accepted, response.accepted);
+        assert!(payment_required_from_result(&json!({ "content": [] })).expect("plain").is_none());
+    }
+}
@@ -6,18 +6,34 @@
 //! replay protection, trust-anchor verification, revocation pre-check, and
 //! approval-gate evaluation — all through the core `verify_authorization`
 //! pipeline.
+//!
+//! [`MerchantVerifier`] owns synchronous stores and verifies through
+//! `&mut self`. [`AsyncMerchantVerifier`] runs the same checks through
+//! `&self` over the async seams ([`AsyncReplayStore`],
+//! [`AsyncWarrantRepository`], [`AsyncRevocationCheck`]) and is
+//! `Clone + Send + Sync`, so async merchants share one instance across
+//! handlers instead of serializing verification behind a lock.
 
-use std::collections::BTreeMap;
+use std::{
+    collections::BTreeMap,
+    future::Future,
+    sync::{Arc, PoisonError, RwLock},
+};
 
 use ledgerflow_core::{
-    AuthorizationContext, AuthorizationInput, DEFAULT_PROOF_FRESHNESS_MS, PaymentRail,
-    RevocationCheck, ToolArguments, TrustedIssuers, VerifiedAuthorization, Warrant, WarrantChain,
-    sha256_prefixed,
+    ApproverSetResolver, AsyncRevocationCheck, AuthorizationContext, AuthorizationInput,
+    ContractSignatureVerifier, DEFAULT_PROOF_FRESHNESS_MS, DefaultSubjectRailPolicy,
+    IdentityResolver, MAX_DELEGATION_DEPTH, PaymentRail, PaymentSubjectRef, RevocationCheck,
+    SharedApproverSetResolver, SharedContractSignatureVerifier, SharedIdentityResolver,
+    SharedSubjectRailPolicy, SubjectRailPolicy, ToolArguments, TrustedIssuers,
+    VerifiedAuthorization, Warrant, WarrantChain, is_sha256_prefixed, sha256_prefixed,
 };
 use thiserror::Error;
 
 use crate::{
-    replay::{ReplayConflict, ReplayFingerprint, ReplayStore},
+    error::ProtocolError,
+    mcp::McpToolCall,
+    replay::{AsyncReplayStore, ReplayConflict, ReplayFingerprint, ReplayStore},
     x402::{
         HttpRequest, LedgerFlowAuthorizationExtension, LedgerFlowChallenge, PaymentPayload,
         canonical_accepted_hash, canonical_request_hash,
@@ -28,6 +44,12 @@ use crate::{
 pub trait WarrantRepository {
     fn load(&self, digest: &str) -> Option<Warrant>;
     fn store(&mut self, warrant: Warrant);
+
+    /// A stored warrant with id `warrant_id`. Repositories that do not index
+    /// ids find none.
+    fn load_by_id(&self, _warrant_id: &[u8]) -> Option<Warrant> {
+        None
+    }
 }
 
 /// In-memory warrant repository for tests and local development flows.
@@ -44,6 +66,27 @@ impl WarrantRepository for InMemoryWarrantRepository {
     fn store(&mut self, warrant: Warrant) {
         self.warrants.insert(warrant.digest(), warrant);
     }
+
+    fn load_by_id(&self, warrant_id: &[u8]) -> Option<Warrant> {
+        self.warrants.values().find(|warrant| warrant.id == warrant_id).cloned()
+    }
+}
+
+/// Async, shareable counterpart of [`WarrantRepository`]. Any
+/// `WarrantRepository` behind an [`RwLock`] implements it.
+pub trait AsyncWarrantRepository: Send + Sync {
+    fn load(&self, digest: &str) -> impl Future<Output = Option<Warrant>> + Send;
+    fn store(&self, warrant: Warrant) -> impl Future<Output = ()> + Send;
+}
+
+impl<W: WarrantRepository + Send + Sync> AsyncWarrantRepository for RwLock<W> {
+    async fn load(&self, digest: &str) -> Option<Warrant> {
+        self.read().unwrap_or_else(PoisonError::into_inner).load(digest)
+    }
+
+    async fn store(&self, warrant: Warrant) {
+        self.write().unwrap_or_else(PoisonError::into_inner).store(warrant);
+    }
 }
 
 /// Result of merchant verification, including whether settlement work was reused.
@@ -64,26 +107,232 @@ pub enum MerchantVerificationError {
     ExtensionSignerMismatch,
     #[error("the warrant chain is empty")]
     EmptyChain,
-    #[error("the warrant digest `{digest}` was not present in merchant cache")]
+    #[error("the warrant digest `{digest}` was not present in the warrant repository")]
     UnknownWarrantDigest { digest: String },
     #[error("the proof replay key was already used for a different request")]
     ReplayDetected,
+    #[error("the proof does not bind the presented tool-call arguments")]
+    ToolArgumentsMismatch,
+    #[error(transparent)]
+    Protocol(#[from] ProtocolError),
     #[error(transparent)]
     Core(#[from] ledgerflow_core::AuthorizationError),
 }
 
+// ---------------------------------------------------------------------------
+// Verification context
+// ---------------------------------------------------------------------------
+
+/// Merchant-supplied inputs to the [`AuthorizationContext`] that the wire
+/// payload does not carry: the tool context (tool name, model provider,
+/// action label, arguments) and the subject-to-rail policy.
+///
+/// ```ignore
+/// let context = VerificationContextBuilder::new()
+///     .with_tool_name("transfer")
+///     .with_model_provider("openai")
+///     .with_action_label("pay-invoice")
+///     .with_rail_policy(Arc::new(PrefixRailPolicy::new().route(
+///         PaymentSubjectKind::Opaque,
+///         "vault:",
+///         PaymentRail::Custodial,
+///     )));
+/// verifier.verify_payment_with_context(&challenge, &request, &payload, &trusted, &context, now_ms)?;
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct VerificationContextBuilder {
+    tool_name: String,
+    model_provider: String,
+    action_label: String,
+    tool_arguments: ToolArguments,
+    rail_policy: Option<SharedSubjectRailPolicy>,
+}
+
+impl VerificationContextBuilder {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The tool being invoked (tool constraints and approval gates).
+    #[must_use]
+    pub fn with_tool_name(mut self, tool_name: impl Into<String>) -> Self {
+        self.tool_name = tool_name.into();
+        self
+    }
+
+    /// The model provider driving the call (tool constraints).
+    #[must_use]
+    pub fn with_model_provider(mut self, model_provider: impl Into<String>) -> Self {
+        self.model_provider = model_provider.into();
+        self
+    }
+
+    /// The action label of the call (tool constraints).
+    #[must_use]
+    pub fn with_action_label(mut self, action_label: impl Into<String>) -> Self {
+        self.action_label = action_label.into();
+        self
+    }
+
+    /// The tool-call arguments (approval gates).
+    #[must_use]
+    pub fn with_tool_arguments(mut self, tool_arguments: ToolArguments) -> Self {
+        self.tool_arguments = tool_arguments;
+        self
+    }
+
+    /// Maps payment subjects to rails through `policy` instead of
+    /// [`DefaultSubjectRailPolicy`]. Share the same policy with the
+    /// facilitator's subject resolver.
+    #[must_use]
+    pub fn with_rail_policy(mut self, policy: SharedSubjectRailPolicy) -> Self {
+        self.rail_policy = Some(policy);
+        self
+    }
+
+    #[must_use]
+    pub const fn tool_arguments(&self) -> &ToolArguments {
+        &self.tool_arguments
+    }
+
+    /// The rail `subject` settles on under the configured policy.
+    #[must_use]
+    pub fn rail(&self, subject: &PaymentSubjectRef) -> PaymentRail {
+        self.rail_policy
+            .as_deref()
+            .map_or_else(|| DefaultSubjectRailPolicy.rail(subject), |policy| policy.rail(subject))
+    }
+
+    /// Builds the [`AuthorizationContext`] for an x402 payment presented
+    /// against `challenge` on `request`.
+    ///
+    /// `request_hash` and `accepted_hash` are the canonical hashes of
+    /// `request` and `payload.accepted` (callers usually already computed
+    /// them).
+    #[allow(clippy::too_many_arguments)]
+    #[must_use]
+    pub fn build(
+        &self,
+        challenge: &LedgerFlowChallenge,
+        request: &HttpRequest,
+        payload: &PaymentPayload,
+        extension: &LedgerFlowAuthorizationExtension,
+        request_hash: &str,
+        accepted_hash: &str,
+        now_ms: u64,
+    ) -> AuthorizationContext {
+        let proof_freshness_ms = if challenge.proof_freshness_ms == 0 {
+            DEFAULT_PROOF_FRESHNESS_MS
+        } else {
+            challenge.proof_freshness_ms
+        };
+        AuthorizationContext {
+            merchant_id: challenge.merchant_id.clone(),
+            merchant_host: request.authority.clone(),
+            tool_name: self.tool_name.clone(),
+            model_provider: self.model_provider.clone(),
+            action_label: self.action_label.clone(),
+            http_method: request.method.clone(),
+            path_and_query: request.path_and_query.clone(),
+            selected_amount: payload.accepted.amount,
+            asset: payload.accepted.asset.clone(),
+            asset_network: payload.accepted.network.clone(),
+            scheme: payload.accepted.scheme.clone(),
+            payee_id: payload.accepted.payee_id.clone(),
+            rail: self.rail(&extension.payment_subject),
+            challenge_id: challenge.challenge_id.clone(),
+            request_hash: request_hash.to_string(),
+            accepted_hash: accepted_hash.to_string(),
+            now_ms,
+            freshness_window_ms: proof_freshness_ms,
+            clock_skew_ms: challenge.clock_skew_ms,
+            payment_subject: extension.payment_subject.clone(),
+            presenter: extension.signer.clone(),
+            human_present: challenge.human_present,
+        }
+    }
+}
+
+/// Builds the [`AuthorizationContext`] for an x402 payment presented against
+/// `challenge` on `request` with only a tool name and the default rail policy
+/// (see [`VerificationContextBuilder`]).
+///
+/// Shared by the in-process [`MerchantVerifier`] and hosted facilitators so
+/// both derive identical contexts from the same wire inputs.
+#[allow(clippy::too_many_arguments)]
+#[must_use]
+pub fn authorization_context(
+    challenge: &LedgerFlowChallenge,
+    request: &HttpRequest,
+    payload: &PaymentPayload,
+    extension: &LedgerFlowAuthorizationExtension,
+    tool_name: &str,
+    request_hash: &str,
+    accepted_hash: &str,
+    now_ms: u64,
+) -> AuthorizationContext {
+    VerificationContextBuilder::new().with_tool_name(tool_name).build(
+        challenge,
+        request,
+        payload,
+        extension,
+        request_hash,
+        accepted_hash,
+        now_ms,
+    )
+}
+
+// ---------------------------------------------------------------------------
+// Merchant verifier
+// ---------------------------------------------------------------------------
+
 /// Merchant-side verifier that preserves x402 semantics while adding LedgerFlow checks.
 #[derive(Clone, Debug)]
 pub struct MerchantVerifier<R, W, Rev> {
     replay_store: R,
     warrant_repository: W,
     revocation: Rev,
+    approver_sets: Option<SharedApproverSetResolver>,
+    contract_verifier: Option<SharedContractSignatureVerifier>,
+    identity_resolver: Option<SharedIdentityResolver>,
 }
 
 impl<R, W, Rev> MerchantVerifier<R, W, Rev> {
     #[must_use]
     pub const fn new(replay_store: R, warrant_repository: W, revocation: Rev) -> Self {
-        Self { replay_store, warrant_repository, revocation }
+        Self {
+            replay_store,
+            warrant_repository,
+            revocation,
+            approver_sets: None,
+            contract_verifier: None,
+            identity_resolver: None,
+        }
+    }
+
+    /// Resolves approver-set references through `resolver` (warrants
+    /// carrying one fail closed without it).
+    #[must_use]
+    pub fn with_approver_sets(mut self, resolver: SharedApproverSetResolver) -> Self {
+        self.approver_sets = Some(resolver);
+        self
+    }
+
+    /// Accepts contract-account (ERC-1271) issuers, holders and approvers,
+    /// checked through `verifier`.
+    #[must_use]
+    pub fn with_contract_verifier(mut self, verifier: SharedContractSignatureVerifier) -> Self {
+        self.contract_verifier = Some(verifier);
+        self
+    }
+
+    /// Accepts roots issued by keys bound to an anchored trust entry's
+    /// EIP-8004 identity, resolved through `resolver`.
+    #[must_use]
+    pub fn with_identity_resolver(mut self, resolver: SharedIdentityResolver) -> Self {
+        self.identity_resolver = Some(resolver);
+        self
     }
 
     pub const fn replay_store_mut(&mut self) -> &mut R {
@@ -109,6 +358,9 @@ where
     /// - `tool_name`: the tool being invoked (for approval gates).
     /// - `tool_arguments`: the tool-call arguments (for approval gates).
     /// - `now_ms`: verification timestamp.
+    ///
+    /// Use [`Self::verify_payment_with_context`] to supply a model provider,
+    /// action label, or rail policy.
     #[allow(clippy::too_many_arguments)]
     pub fn verify_payment(
         &mut self,
@@ -120,18 +372,28 @@ where
         tool_arguments: &ToolArguments,
         now_ms: u64,
     ) -> Result<MerchantVerificationOutcome, MerchantVerificationError> {
-        let Some(extension) = &payload.ledgerflow else {
-            return Err(MerchantVerificationError::MissingLedgerFlowExtension);
-        };
-
-        if extension.challenge_id != challenge.challenge_id {
-            return Err(MerchantVerificationError::ChallengeMismatch);
-        }
-
-        if extension.signer.public_key != extension.proof.signer_key {
-            return Err(MerchantVerificationError::ExtensionSignerMismatch);
-        }
+        let context = VerificationContextBuilder::new()
+            .with_tool_name(tool_name)
+            .with_tool_arguments(tool_arguments.clone());
+        self.verify_payment_with_context(challenge, request, payload, trusted, &context, now_ms)
+    }
 
+    /// Verifies a payment payload against the active challenge and request,
+    /// taking the tool context and rail policy from `context`.
+    ///
+    /// Every link of the presented chain is verified (signatures, attenuation,
+    /// trust anchor, revocation), and the leaf's constraints are checked
+    /// against the context built by `context`.
+    pub fn verify_payment_with_context(
+        &mut self,
+        challenge: &LedgerFlowChallenge,
+        request: &HttpRequest,
+        payload: &PaymentPayload,
+        trusted: &TrustedIssuers,
+        context: &VerificationContextBuilder,
+        now_ms: u64,
+    ) -> Result<MerchantVerificationOutcome, MerchantVerificationError> {
+        let extension = presented_extension(challenge, payload)?;
         let accepted_hash = canonical_accepted_hash(&payload.accepted);
         let request_hash = canonical_request_hash(request);
 
@@ -145,112 +407,496 @@ where
             return Ok(MerchantVerificationOutcome { authorization, settlement_reused: true });
         }
 
-        self.claim_replay(challenge, extension, &request_hash, &accepted_hash, now_ms)?;
+        self.replay_store
+            .claim_nonce(
+                replay_fingerprint(challenge, extension, &request_hash, &accepted_hash),
+                now_ms,
+            )
+            .map_err(replay_detected)?;
 
         let chain = self.resolve_chain(extension)?;
-        let proof_freshness_ms = if challenge.proof_freshness_ms == 0 {
-            DEFAULT_PROOF_FRESHNESS_MS
-        } else {
-            challenge.proof_freshness_ms
-        };
-        let context = AuthorizationContext {
-            merchant_id: challenge.merchant_id.clone(),
-            merchant_host: request.authority.clone(),
-            tool_name: tool_name.to_string(),
-            model_provider: String::new(),
-            action_label: String::new(),
-            http_method: request.method.clone(),
-            path_and_query: request.path_and_query.clone(),
-            selected_amount: payload.accepted.amount,
-            asset: payload.accepted.asset.clone(),
-            asset_network: payload.accepted.network.clone(),
-            scheme: payload.accepted.scheme.clone(),
-            payee_id: payload.accepted.payee_id.clone(),
-            rail: match extension.payment_subject.kind {
-                ledgerflow_core::PaymentSubjectKind::ExchangeAccount |
-                ledgerflow_core::PaymentSubjectKind::FacilitatorAccount => PaymentRail::Exchange,
-                _ => PaymentRail::Onchain,
-            },
-            challenge_id: challenge.challenge_id.clone(),
-            request_hash: request_hash.clone(),
-            accepted_hash: accepted_hash.clone(),
+        let verification_context = context.build(
+            challenge,
+            request,
+            payload,
+            extension,
+            &request_hash,
+            &accepted_hash,
             now_ms,
-            freshness_window_ms: proof_freshness_ms,
-            clock_skew_ms: challenge.clock_skew_ms,
-            payment_subject: extension.payment_subject.clone(),
-            presenter: extension.signer.clone(),
-            human_present: challenge.human_present,
-        };
-        let input = AuthorizationInput {
-            chain: &chain,
+        );
+        let authorization = verify_presented(
+            &chain,
             trusted,
-            proof: &extension.proof,
-            context: &context,
-            approvals: &extension.approvals,
-            tool_arguments,
-            revocation: &self.revocation,
-            // Bind the PoP to the concrete accepted quote (design §6.3).
-            payment_payload_digest: Some(sha256_prefixed(payload.accepted.canonical())),
-        };
-        let authorization = ledgerflow_core::verify_authorization(&input)?;
+            payload,
+            extension,
+            &verification_context,
+            context.tool_arguments(),
+            &self.revocation,
+            self.approver_sets.as_deref(),
+            self.contract_verifier.as_deref(),
+            self.identity_resolver.as_deref(),
+        )?;
 
         if let Some(payment_identifier) = payload.payment_identifier() {
             self.replay_store.cache_payment(
                 payment_identifier.to_string(),
                 authorization.clone(),
-                request_hash.clone(),
-                accepted_hash.clone(),
+                request_hash,
+                accepted_hash,
             );
         }
 
         Ok(MerchantVerificationOutcome { authorization, settlement_reused: false })
     }
 
-    fn claim_replay(
+    /// Verifies an MCP `tools/call` on `server` paid through
+    /// `params._meta.ledgerflow` (design §7.3).
+    ///
+    /// The tool name and arguments of `call` are added to `context`, so
+    /// tool constraints and approval gates apply per call; the PoP must bind
+    /// the call's [`McpToolCall::binding_request`] and `tool_args_digest`.
+    pub fn verify_tool_call(
         &mut self,
         challenge: &LedgerFlowChallenge,
-        extension: &LedgerFlowAuthorizationExtension,
-        request_hash: &str,
-        accepted_hash: &str,
+        server: &str,
+        call: &McpToolCall,
+        trusted: &TrustedIssuers,
+        context: &VerificationContextBuilder,
         now_ms: u64,
-    ) -> Result<(), MerchantVerificationError> {
-        let fingerprint = ReplayFingerprint {
-            challenge_id: challenge.challenge_id.clone(),
-            nonce: extension.proof.tuple.nonce.clone(),
-            request_hash: request_hash.to_string(),
-            accepted_hash: accepted_hash.to_string(),
-        };
-
-        match self.replay_store.claim_nonce(fingerprint, now_ms) {
-            Ok(()) => Ok(()),
-            // Any nonce conflict is a replay: the nonce key (challenge_id,
-            // nonce) is globally unique per challenge, so a conflict means the
-            // exact same proof was already observed.
-            Err(ReplayConflict { .. }) => Err(MerchantVerificationError::ReplayDetected),
-        }
+    ) -> Result<MerchantVerificationOutcome, MerchantVerificationError> {
+        let (payload, context) = tool_call_payment(call, context)?;
+        self.verify_payment_with_context(
+            challenge,
+            &call.binding_request(server),
+            &payload,
+            trusted,
+            &context,
+            now_ms,
+        )
     }
 
     /// Resolves the presented warrant chain.
     ///
-    /// v1 rule: the chain is transmitted inline. If the extension carries an
-    /// empty chain (digest-only mode), the merchant cache is consulted; a
-    /// digest-only submission must have been cached by a prior inline one.
+    /// The chain is transmitted inline, or in digest-reference mode as the
+    /// leaf plus parent digests. Referenced parents are loaded from the
+    /// warrant repository (a prior inline submission, issuance, or a remote
+    /// repository); an unknown digest fails verification.
     fn resolve_chain(
         &mut self,
         extension: &LedgerFlowAuthorizationExtension,
     ) -> Result<WarrantChain, MerchantVerificationError> {
-        if extension.warrant_chain.is_empty() {
-            return Err(MerchantVerificationError::EmptyChain);
-        }
-        let mut chain = WarrantChain::default();
+        check_presentation(extension)?;
+        let parents = extension
+            .parent_digests
+            .iter()
+            .map(|digest| referenced_parent(digest, self.warrant_repository.load(digest)))
+            .collect::<Result<Vec<_>, _>>()?;
+        let chain = presented_chain(parents, extension)?;
         for warrant in &extension.warrant_chain {
             self.warrant_repository.store(warrant.clone());
-            chain.push(warrant.clone());
         }
         Ok(chain)
     }
 }
 
+// ---------------------------------------------------------------------------
+// Async merchant verifier
+// ---------------------------------------------------------------------------
+
+/// [`MerchantVerifier`] counterpart for async merchants.
+///
+/// Verification takes `&self`; replay claims, warrant caching and revocation
+/// lookups go through the async seams, and the CPU-bound chain verification
+/// runs between them without holding any store lock. Clones share the same
+/// stores, so a single verifier can live in handler state:
+///
+/// ```ignore
+/// let verifier = AsyncMerchantVerifier::new(
+///     RwLock::new(InMemoryReplayStore::default()),
+///     RwLock::new(InMemoryWarrantRepository::default()),
+///     FileRevocationStore::open("revocations.jsonl")?,
+/// );
+/// let app = Router::new().route("/pay", post(pay)).with_state(verifier);
+/// ```
+#[derive(Debug)]
+pub struct AsyncMerchantVerifier<R, W, Rev> {
+    stores: Arc<VerifierStores<R, W, Rev>>,
+    approver_sets: Option<SharedApproverSetResolver>,
+    contract_verifier: Option<SharedContractSignatureVerifier>,
+    identity_resolver: Option<SharedIdentityResolver>,
+}
+
+#[derive(Debug)]
+struct VerifierStores<R, W, Rev> {
+    replay_store: R,
+    warrant_repository: W,
+    revocation: Rev,
+}
+
+impl<R, W, Rev> Clone for AsyncMerchantVerifier<R, W, Rev> {
+    fn clone(&self) -> Self {
+        Self {
+            stores: Arc::clone(&self.stores),
+            approver_sets: self.approver_sets.clone(),
+            contract_verifier: self.contract_verifier.clone(),
+            identity_resolver: self.identity_resolver.clone(),
+        }
+    }
+}
+
+impl<R, W, Rev> AsyncMerchantVerifier<R, W, Rev> {
+    #[must_use]
+    pub fn new(replay_store: R, warrant_repository: W, revocation: Rev) -> Self {
+        Self {
+            stores: Arc::new(VerifierStores { replay_store, warrant_repository, revocation }),
+            approver_sets: None,
+            contract_verifier: None,
+            identity_resolver: None,
+        }
+    }
+
+    /// Resolves approver-set references through `resolver` (warrants
+    /// carrying one fail closed without it).
+    #[must_use]
+    pub fn with_approver_sets(mut self, resolver: SharedApproverSetResolver) -> Self {
+        self.approver_sets = Some(resolver);
+        self
+    }
+
+    /// Accepts contract-account (ERC-1271) issuers, holders and approvers,
+    /// checked through `verifier`.
+    ///
+    /// The seam is synchronous and runs inline on the task polling
+    /// [`verify_payment`](Self::verify_payment): an RPC-backed verifier
+    /// (the facilitator's `RpcContractSignatureVerifier`) blocks the executor
+    /// thread on every uncached lookup. Keep such verifiers off latency-bound
+    /// executors, or run verification on a blocking pool.
+    #[must_use]
+    pub fn with_contract_verifier(mut self, verifier: SharedContractSignatureVerifier) -> Self {
+        self.contract_verifier = Some(verifier);
+        self
+    }
+
+    /// Accepts roots issued by keys bound to an anchored trust entry's
+    /// EIP-8004 identity, resolved through `resolver`.
+    ///
+    /// Like [`with_contract_verifier`](Self::with_contract_verifier), the
+    /// resolver runs inline: an RPC-backed one (the facilitator's
+    /// `IdentityRegistryResolver`) blocks the executor thread while it
+    /// queries the registry.
+    #[must_use]
+    pub fn with_identity_resolver(mut self, resolver: SharedIdentityResolver) -> Self {
+        self.identity_resolver = Some(resolver);
+        self
+    }
+
+    pub fn replay_store(&self) -> &R {
+        &self.stores.replay_store
+    }
+
+    pub fn warrant_repository(&self) -> &W {
+        &self.stores.warrant_repository
+    }
+}
+
+/// Moves a synchronous verifier's stores behind locks.
+impl<R, W, Rev> From<MerchantVerifier<R, W, Rev>>
+    for AsyncMerchantVerifier<RwLock<R>, RwLock<W>, Rev>
+{
+    fn from(verifier: MerchantVerifier<R, W, Rev>) -> Self {
+        Self {
+            stores: Arc::new(VerifierStores {
+                replay_store: RwLock::new(verifier.replay_store),
+                warrant_repository: RwLock::new(verifier.warrant_repository),
+                revocation: verifier.revocation,
+            }),
+            approver_sets: verifier.approver_sets,
+            contract_verifier: verifier.contract_verifier,
+            identity_resolver: verifier.identity_resolver,
+        }
+    }
+}
+
+impl<R, W, Rev> AsyncMerchantVerifier<R, W, Rev>
+where
+    R: AsyncReplayStore,
+    W: AsyncWarrantRepository,
+    Rev: AsyncRevocationCheck,
+{
+    /// Async [`MerchantVerifier::verify_payment`].
+    #[allow(clippy::too_many_arguments)]
+    pub async fn verify_payment(
+        &self,
+        challenge: &LedgerFlowChallenge,
+        request: &HttpRequest,
+        payload: &PaymentPayload,
+        trusted: &TrustedIssuers,
+        tool_name: &str,
+        tool_arguments: &ToolArguments,
+        now_ms: u64,
+    ) -> Result<MerchantVerificationOutcome, MerchantVerificationError> {
+        let context = VerificationContextBuilder::new()
+            .with_tool_name(tool_name)
+            .with_tool_arguments(tool_arguments.clone());
+        self.verify_payment_with_context(challenge, request, payload, trusted, &context, now_ms)
+            .await
+    }
+
+    /// Async [`MerchantVerifier::verify_payment_with_context`].
+    pub async fn verify_payment_with_context(
+        &self,
+        challenge: &LedgerFlowChallenge,
+        request: &HttpRequest,
+        payload: &PaymentPayload,
+        trusted: &TrustedIssuers,
+        context: &VerificationContextBuilder,
+        now_ms: u64,
+    ) -> Result<MerchantVerificationOutcome, MerchantVerificationError> {
+        let stores = &self.stores;
+        let extension = presented_extension(challenge, payload)?;
+        let accepted_hash = canonical_accepted_hash(&payload.accepted);
+        let request_hash = canonical_request_hash(request);
+
+        if let Some(payment_identifier) = payload.payment_identifier() &&
+            let Some(authorization) = stores
+                .replay_store
+                .cached_payment(payment_identifier, &request_hash, &accepted_hash)
+                .await
+        {
+            return Ok(MerchantVerificationOutcome { authorization, settlement_reused: true });
+        }
+
+        stores
+            .replay_store
+            .claim_nonce(
+                replay_fingerprint(challenge, extension, &request_hash, &accepted_hash),
+                now_ms,
+            )
+            .await
+            .map_err(replay_detected)?;
+
+        check_presentation(extension)?;
+        let mut parents = Vec::with_capacity(extension.parent_digests.len());
+        for digest in &extension.parent_digests {
+            parents.push(referenced_parent(digest, stores.warrant_repository.load(digest).await)?);
+        }
+        let chain = presented_chain(parents, extension)?;
+        for warrant in &extension.warrant_chain {
+            stores.warrant_repository.store(warrant.clone()).await;
+        }
+        if chain.leaf().is_none() {
+            return Err(MerchantVerificationError::EmptyChain);
+        }
+        let verification_context = context.build(
+            challenge,
+            request,
+            payload,
+            extension,
+            &request_hash,
+            &accepted_hash,
+            now_ms,
+        );
+        // Fetch every revocation scope the core pipeline checks up front so
+        // the synchronous verification below does no store I/O. Contract
+        // verifiers and identity resolvers still run inline (and block when
+        // RPC-backed; see `with_contract_verifier`).
+        let revocation =
+            stores.revocation.snapshot(&chain, &verification_context.payment_subject).await;
+        let authorization = verify_presented(
+            &chain,
+            trusted,
+            payload,
+            extension,
+            &verification_context,
+            context.tool_arguments(),
+            &revocation,
+            self.approver_sets.as_deref(),
+            self.contract_verifier.as_deref(),
+            self.identity_resolver.as_deref(),
+        )?;
+
+        if let Some(payment_identifier) = payload.payment_identifier() {
+            stores
+                .replay_store
+                .cache_payment(
+                    payment_identifier.to_string(),
+                    authorization.clone(),
+                    request_hash,
+                    accepted_hash,
+                )
+                .await;
+        }
+
+        Ok(MerchantVerificationOutcome { authorization, settlement_reused: false })
+    }
+
+    /// Async [`MerchantVerifier::verify_tool_call`].
+    pub async fn verify_tool_call(
+        &self,
+        challenge: &LedgerFlowChallenge,
+        server: &str,
+        call: &McpToolCall,
+        trusted: &TrustedIssuers,
+        context: &VerificationContextBuilder,
+        now_ms: u64,
+    ) -> Result<MerchantVerificationOutcome, MerchantVerificationError> {
+        let (payload, context) = tool_call_payment(call, context)?;
+        self.verify_payment_with_context(
+            challenge,
+            &call.binding_request(server),
+            &payload,
+            trusted,
+            &context,
+            now_ms,
+        )
+        .await
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Shared verification steps
+// ---------------------------------------------------------------------------
+
+/// The LedgerFlow extension of `payload`, checked against the active
+/// challenge and the proof signer.
+fn presented_extension<'a>(
+    challenge: &LedgerFlowChallenge,
+    payload: &'a PaymentPayload,
+) -> Result<&'a LedgerFlowAuthorizationExtension, MerchantVerificationError> {
+    let Some(extension) = &payload.ledgerflow else {
+        return Err(MerchantVerificationError::MissingLedgerFlowExtension);
+    };
+
+    if extension.challenge_id != challenge.challenge_id {
+        return Err(MerchantVerificationError::ChallengeMismatch);
+    }
+
+    if extension.signer.public_key != extension.proof.signer_key {
+        return Err(MerchantVerificationError::ExtensionSignerMismatch);
+    }
+
+    Ok(extension)
+}
+
+/// The payment in `call._meta.ledgerflow`, with `context` extended by the
+/// call's tool name and arguments. The PoP must commit to those arguments.
+fn tool_call_payment(
+    call: &McpToolCall,
+    context: &VerificationContextBuilder,
+) -> Result<(PaymentPayload, VerificationContextBuilder), MerchantVerificationError> {
+    let payload = call.payment()?.ok_or(MerchantVerificationError::MissingLedgerFlowExtension)?;
+    let extension =
+        payload.ledgerflow.as_ref().ok_or(MerchantVerificationError::MissingLedgerFlowExtension)?;
+    if extension.proof.tuple.tool_args_digest != call.tool_args_digest() {
+        return Err(MerchantVerificationError::ToolArgumentsMismatch);
+    }
+    let context = context
+        .clone()
+        .with_tool_name(call.name.clone())
+        .with_tool_arguments(call.tool_arguments());
+    Ok((payload, context))
+}
+
+fn replay_fingerprint(
+    challenge: &LedgerFlowChallenge,
+    extension: &LedgerFlowAuthorizationExtension,
+    request_hash: &str,
+    accepted_hash: &str,
+) -> ReplayFingerprint {
+    ReplayFingerprint {
+        challenge_id: challenge.challenge_id.clone(),
+        nonce: extension.proof.tuple.nonce.clone(),
+        request_hash: request_hash.to_string(),
+        accepted_hash: accepted_hash.to_string(),
+    }
+}
+
+/// Any nonce conflict is a replay: the nonce key (challenge_id, nonce) is
+/// globally unique per challenge, so a conflict means the exact same proof
+/// was already observed.
+fn replay_detected(_: ReplayConflict) -> MerchantVerificationError {
+    MerchantVerificationError::ReplayDetected
+}
+
+/// A repository hit for a referenced parent. The digest is re-checked so a
+/// remote repository cannot substitute a different warrant.
+fn referenced_parent(
+    digest: &str,
+    loaded: Option<Warrant>,
+) -> Result<Warrant, MerchantVerificationError> {
+    loaded.filter(|warrant| warrant.digest() == digest).ok_or_else(|| {
+        MerchantVerificationError::UnknownWarrantDigest { digest: digest.to_string() }
+    })
+}
+
+/// Rejects a presentation longer than the delegation-depth ceiling allows,
+/// or one referencing a parent by anything but a well-formed warrant digest,
+/// before any referenced parent is resolved, so neither an oversized digest
+/// list nor a crafted digest reaches the warrant repository (and, through a
+/// remote repository, an authenticated URL).
+fn check_presentation(
+    extension: &LedgerFlowAuthorizationExtension,
+) -> Result<(), MerchantVerificationError> {
+    let presented = extension.parent_digests.len() + extension.warrant_chain.len();
+    if presented > usize::from(MAX_DELEGATION_DEPTH) + 1 {
+        return Err(ledgerflow_core::AuthorizationError::DelegationDepthExceeded {
+            presented: u8::try_from(presented - 1).unwrap_or(u8::MAX),
+            allowed: MAX_DELEGATION_DEPTH,
+        }
+        .into());
+    }
+    if let Some(digest) =
+        extension.parent_digests.iter().find(|digest| !is_sha256_prefixed(digest))
+    {
+        return Err(MerchantVerificationError::UnknownWarrantDigest { digest: digest.clone() });
+    }
+    Ok(())
+}
+
+/// Root-first chain: the resolved `parents`, then the inline warrants.
+fn presented_chain(
+    parents: Vec<Warrant>,
+    extension: &LedgerFlowAuthorizationExtension,
+) -> Result<WarrantChain, MerchantVerificationError> {
+    if extension.warrant_chain.is_empty() {
+        return Err(MerchantVerificationError::EmptyChain);
+    }
+    let mut chain = WarrantChain { warrants: parents };
+    for warrant in &extension.warrant_chain {
+        chain.push(warrant.clone());
+    }
+    Ok(chain)
+}
+
+#[allow(clippy::too_many_arguments)]
+fn verify_presented(
+    chain: &WarrantChain,
+    trusted: &TrustedIssuers,
+    payload: &PaymentPayload,
+    extension: &LedgerFlowAuthorizationExtension,
+    context: &AuthorizationContext,
+    tool_arguments: &ToolArguments,
+    revocation: &dyn RevocationCheck,
+    approver_sets: Option<&dyn ApproverSetResolver>,
+    contract_verifier: Option<&dyn ContractSignatureVerifier>,
+    identity_resolver: Option<&dyn IdentityResolver>,
+) -> Result<VerifiedAuthorization, MerchantVerificationError> {
+    let input = AuthorizationInput {
+        chain,
+        trusted,
+        proof: &extension.proof,
+        context,
+        approvals: &extension.approvals,
+        tool_arguments,
+        revocation,
+        // Bind the PoP to the concrete accepted quote (design §6.3).
+        payment_payload_digest: Some(sha256_prefixed(payload.accepted.canonical())),
+        contract_verifier,
+        identity_resolver,
+    };
+    Ok(ledgerflow_core::verify_authorization_with_approver_sets(&input, approver_sets)?)
+}
+
 #[cfg(test)]
 mod tests {
     #![allow(clippy::expect_used)]
@@ -312,7 +958,10 @@ mod tests {
     }
 
     fn extension() -> LedgerFlowAuthorizationExtension {
-        let w = warrant();
+        extension_for(warrant())
+    }
+
+    fn extension_for(w: Warrant) -> LedgerFlowAuthorizationExtension {
         let req = request();
         let quote =
             crate::x402::AcceptedQuote::exact("USDC", 100, "merchant-a", Some("base".to_string()));
@@ -365,6 +1014,7 @@ mod tests {
             signer: holder_keys().signer_ref(),
             payment_subject: ctx.payment_subject,
             approvals: Vec::new(),
+            parent_digests: Vec::new(),
         }
     }
 
@@ -378,6 +1028,100 @@ mod tests {
         assert_eq!(loaded.digest(), w.digest());
     }
 
+    /// Counts lookups; resolves nothing.
+    #[derive(Debug, Default)]
+    struct CountingWarrantRepository {
+        loads: std::cell::Cell<usize>,
+    }
+
+    impl WarrantRepository for CountingWarrantRepository {
+        fn load(&self, _digest: &str) -> Option<Warrant> {
+            self.loads.set(self.loads.get() + 1);
+            None
+        }
+
+        fn store(&mut self, _warrant: Warrant) {}
+    }
+
+    #[test]
+    fn oversized_presentations_are_rejected_before_parents_resolve() {
+        let mut verifier = MerchantVerifier::new(
+            InMemoryReplayStore::default(),
+            CountingWarrantRepository::default(),
+            ledgerflow_core::InMemoryRevocationCheck::new(),
+        );
+        let mut ext = extension();
+        ext.parent_digests =
+            (0..=MAX_DELEGATION_DEPTH).map(|i| format!("sha256:{i:02x}")).collect();
+        let payload = crate::x402::PaymentPayload {
+            accepted: crate::x402::AcceptedQuote::exact(
+                "USDC",
+                100,
+                "merchant-a",
+                Some("base".to_string()),
+            ),
+            settlement_payload: "0xabc".to_string(),
+            payment_identifier: None,
+            ledgerflow: Some(ext),
+        };
+        let error = verifier
+            .verify_payment(
+                &challenge(),
+                &request(),
+                &payload,
+                &trusted(),
+                "web-search",
+                &BTreeMap::new(),
+                2_000,
+            )
+            .expect_err("too deep");
+        assert!(matches!(
+            error,
+            MerchantVerificationError::Core(
+                ledgerflow_core::AuthorizationError::DelegationDepthExceeded { .. }
+            )
+        ));
+        assert_eq!(verifier.warrant_repository.loads.get(), 0);
+    }
+
+    #[test]
+    fn malformed_parent_digests_are_rejected_before_parents_resolve() {
+        let upper_hex = format!("sha256:{}", "AB".repeat(32));
+        for digest in ["../admin/tenants", "sha256:x?y", "sha256:00", upper_hex.as_str()] {
+            let mut verifier = MerchantVerifier::new(
+                InMemoryReplayStore::default(),
+                CountingWarrantRepository::default(),
+                ledgerflow_core::InMemoryRevocationCheck::new(),
+            );
+            let mut ext = extension();
+            ext.parent_digests = vec![digest.to_string()];
+            let payload = crate::x402::PaymentPayload {
+                accepted: crate::x402::AcceptedQuote::exact(
+                    "USDC",
+                    100,
+                    "merchant-a",
+                    Some("base".to_string()),
+                ),
+                settlement_payload: "0xabc".to_string(),
+                payment_identifier: None,
+                ledgerflow: Some(ext),
+            };
+            let error = verifier
+                .verify_payment(
+                    &challenge(),
+                    &request(),
+                    &payload,
+                    &trusted(),
+                    "web-search",
+                    &BTreeMap::new(),
+                    2_000,
+                )
+                .expect_err("malformed digest");
+            assert!(matches!(error, MerchantVerificationError::UnknownWarrantDigest { .. }));
+            assert_eq!(verifier.warrant_repository.loads.get(), 0);
+        }
+    }
+
     #[test]
     fn merchant_verifier_full_payment_flow_passes() {
         let mut verifier = MerchantVerifier::new(
@@ -554,30 +1298,212 @@ mod tests {
     }
 
     #[test]
-    fn rail_mapping_exchange_account_uses_exchange_rail() {
-        let subject = ledgerflow_core::PaymentSubjectRef::new(
+    fn context_builder_maps_subjects_through_the_rail_policy() {
+        let exchange = ledgerflow_core::PaymentSubjectRef::new(
             ledgerflow_core::PaymentSubjectKind::ExchangeAccount,
             "exchange-1",
         );
-        assert_eq!(
-            match subject.kind {
-                ledgerflow_core::PaymentSubjectKind::ExchangeAccount |
-                ledgerflow_core::PaymentSubjectKind::FacilitatorAccount => PaymentRail::Exchange,
-                _ => PaymentRail::Onchain,
-            },
-            PaymentRail::Exchange
-        );
-        let opaque = ledgerflow_core::PaymentSubjectRef::new(
+        let gateway = ledgerflow_core::PaymentSubjectRef::new(
             ledgerflow_core::PaymentSubjectKind::Opaque,
             "gateway:gw",
         );
-        assert_eq!(
-            match opaque.kind {
-                ledgerflow_core::PaymentSubjectKind::ExchangeAccount |
-                ledgerflow_core::PaymentSubjectKind::FacilitatorAccount => PaymentRail::Exchange,
-                _ => PaymentRail::Onchain,
-            },
-            PaymentRail::Onchain
+        let default = VerificationContextBuilder::new();
+        assert_eq!(default.rail(&exchange), PaymentRail::Exchange);
+        assert_eq!(default.rail(&gateway), PaymentRail::TraditionalGateway);
+
+        let custom = default.with_rail_policy(std::sync::Arc::new(
+            ledgerflow_core::PrefixRailPolicy::new().route(
+                ledgerflow_core::PaymentSubjectKind::ExchangeAccount,
+                "",
+                PaymentRail::Custodial,
+            ),
+        ));
+        assert_eq!(custom.rail(&exchange), PaymentRail::Custodial);
+        assert_eq!(custom.rail(&gateway), PaymentRail::TraditionalGateway);
+    }
+
+    #[test]
+    fn merchant_context_exercises_custodial_rails_and_tool_constraints() {
+        let w = WarrantBuilder::new(2_000)
+            .warrant_id(*b"mid-root-0000000")
+            .ttl_secs(3600)
+            .issuer(issuer_keys().signer_ref())
+            .holder(holder_keys().signer_ref())
+            .merchant(MerchantConstraint::with_ids(vec!["merchant-a".to_string()]))
+            .payment(PaymentConstraint {
+                allowed_rails: vec![PaymentRail::Custodial],
+                ..PaymentConstraint::new(1_000)
+            })
+            .tool(ledgerflow_core::ToolConstraint {
+                tool_names: vec!["web-search".to_string()],
+                model_providers: vec!["openai".to_string()],
+                action_labels: vec!["research".to_string()],
+            })
+            .sign_with(&issuer_keys(), [0_u8; 8]);
+        let mut ext = extension_for(w);
+        ext.payment_subject = ledgerflow_core::PaymentSubjectRef::new(
+            ledgerflow_core::PaymentSubjectKind::Opaque,
+            "vault:acct-1",
         );
+        let payload = crate::x402::PaymentPayload {
+            accepted: crate::x402::AcceptedQuote::exact(
+                "USDC",
+                100,
+                "merchant-a",
+                Some("base".to_string()),
+            ),
+            settlement_payload: "0xabc".to_string(),
+            payment_identifier: None,
+            ledgerflow: Some(ext),
+        };
+        let verify = |context: &VerificationContextBuilder| {
+            MerchantVerifier::new(
+                InMemoryReplayStore::default(),
+                InMemoryWarrantRepository::default(),
+                ledgerflow_core::InMemoryRevocationCheck::new(),
+            )
+            .verify_payment_with_context(
+                &challenge(),
+                &request(),
+                &payload,
+                &trusted(),
+                context,
+                2_000,
+            )
+        };
+
+        // Without a model provider and action label the tool constraint fails.
+        let bare = VerificationContextBuilder::new().with_tool_name("web-search");
+        assert!(matches!(verify(&bare), Err(MerchantVerificationError::Core(_))));
+
+        let context = bare.with_model_provider("openai").with_action_label("research");
+        let outcome = verify(&context).expect("custodial payment verified");
+        assert_eq!(outcome.authorization.rail, PaymentRail::Custodial);
+    }
+
+    fn payload(payment_identifier: Option<&str>) -> crate::x402::PaymentPayload {
+        crate::x402::PaymentPayload {
+            accepted: crate::x402::AcceptedQuote::exact(
+                "USDC",
+                100,
+                "merchant-a",
+                Some("base".to_string()),
+            ),
+            settlement_payload: "0xabc".to_string(),
+            payment_identifier: payment_identifier.map(str::to_string),
+            ledgerflow: Some(extension()),
+        }
+    }
+
+    /// A revocation store reached over I/O that only implements the async seam.
+    #[derive(Debug)]
+    struct RemoteRevocation {
+        revoked_holder: Vec<u8>,
+    }
+
+    impl AsyncRevocationCheck for RemoteRevocation {
+        async fn snapshot(
+            &self,
+            chain: &ledgerflow_core::WarrantChain,
+            subject: &ledgerflow_core::PaymentSubjectRef,
+        ) -> ledgerflow_core::RevocationSnapshot {
+            use ledgerflow_core::RevocationDecision;
+
+            tokio::task::yield_now().await;
+            let leaf = chain.leaf().expect("leaf");
+            let holder_decision = if leaf.holder.public_key == self.revoked_holder {
+                RevocationDecision::RevokedHolder
+            } else {
+                RevocationDecision::Ok
+            };
+            let mut snapshot = ledgerflow_core::RevocationSnapshot::new(
+                &leaf.id,
+                &leaf.holder,
+                RevocationDecision::Ok,
+                holder_decision,
+            )
+            .with_subject(subject, RevocationDecision::Ok);
+            for warrant in &chain.warrants {
+                snapshot = snapshot.with_issuer(&warrant.issuer, RevocationDecision::Ok);
+            }
+            snapshot
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+    async fn async_verifier_admits_exactly_one_of_concurrent_replays() {
+        let verifier: AsyncMerchantVerifier<_, _, _> = MerchantVerifier::new(
+            InMemoryReplayStore::default(),
+            InMemoryWarrantRepository::default(),
+            ledgerflow_core::InMemoryRevocationCheck::new(),
+        )
+        .into();
+        let handles: Vec<_> = (0..8)
+            .map(|_| {
+                let verifier = verifier.clone();
+                tokio::spawn(async move {
+                    verifier
+                        .verify_payment(
+                            &challenge(),
+                            &request(),
+                            &payload(None),
+                            &trusted(),
+                            "web-search",
+                            &BTreeMap::new(),
+                            2_000,
+                        )
+                        .await
+                })
+            })
+            .collect();
+        let mut verified = 0;
+        for handle in handles {
+            match handle.await.expect("task") {
+                Ok(_) => verified += 1,
+                Err(error) => {
+                    assert!(matches!(error, MerchantVerificationError::ReplayDetected));
+                }
+            }
+        }
+        assert_eq!(verified, 1);
+        let stored = verifier.warrant_repository().read().expect("lock").load(&warrant().digest());
+        assert!(stored.is_some());
+    }
+
+    #[tokio::test]
+    async fn async_verifier_reuses_cached_payments_and_awaits_revocation() {
+        let verifier = AsyncMerchantVerifier::new(
+            RwLock::new(InMemoryReplayStore::default()),
+            RwLock::new(InMemoryWarrantRepository::default()),
+            RemoteRevocation { revoked_holder: Vec::new() },
+        );
+        let verify = |verifier: AsyncMerchantVerifier<_, _, _>, payload| async move {
+            verifier
+                .verify_payment(
+                    &challenge(),
+                    &request(),
+                    &payload,
+                    &trusted(),
+                    "web-search",
+                    &BTreeMap::new(),
+                    2_000,
+                )
+                .await
+        };
+        let first = verify(verifier.clone(), payload(Some("payment-1"))).await.expect("first");
+        assert!(!first.settlement_reused);
+        let second = verify(verifier, payload(Some("payment-1"))).await.expect("second");
+        assert!(second.settlement_reused);
+
+        let revoked = AsyncMerchantVerifier::new(
+            RwLock::new(InMemoryReplayStore::default()),
+            RwLock::new(InMemoryWarrantRepository::default()),
+            RemoteRevocation { revoked_holder: holder_keys().signer_ref().public_key },
+        );
+        let error = verify(revoked, payload(None)).await.expect_err("holder revoked");
+        assert!(matches!(
+            error,
+            MerchantVerificationError::Core(ledgerflow_core::AuthorizationError::HolderRevoked)
+        ));
     }
 }
@@ -9,11 +9,20 @@
 //! Header size policy (design §7.2): the header parameter carries at most a
 //! single-node or digest reference; the full chain travels in the body. See
 //! [`crate::carrier`].
+//!
+//! [`PaymentChallenge`] and [`PaymentCredential`] are the whole
+//! `WWW-Authenticate: Payment` / `Authorization: Payment` headers (RFC 9110
+//! grammar in [`crate::auth_param`]). A credential whose authorization does
+//! not fit [`MAX_HEADER_CBOR_BYTES`] is emitted with a `ledgerflow-digest`
+//! reference in the header and the CBOR authorization as the body.
 
-use ledgerflow_core::{PaymentSubjectRef, PopProof, SignedApproval, SignerRef, Warrant};
+use ledgerflow_core::{
+    PaymentSubjectRef, PopProof, SignedApproval, SignerRef, Warrant, sha256_prefixed,
+};
 
 use crate::{
-    carrier::MAX_HEADER_CBOR_BYTES,
+    auth_param::{AuthChallenge, parse_challenges, parse_credentials},
+    carrier::{LedgerFlowCarrier, MAX_HEADER_CBOR_BYTES},
     error::ProtocolError,
     wire::{base64url_decode, base64url_encode, cbor_decode, cbor_encode},
     x402::{LedgerFlowAuthorizationExtension, LedgerFlowChallenge},
@@ -22,6 +31,15 @@ use crate::{
 /// LedgerFlow MPP parameter key.
 pub const LEDGERFLOW_PARAM: &str = "ledgerflow";
 
+/// Parameter referencing an authorization carried in the body.
+pub const LEDGERFLOW_DIGEST_PARAM: &str = "ledgerflow-digest";
+
+/// The Payment HTTP authentication scheme name.
+pub const PAYMENT_SCHEME: &str = "Payment";
+
+/// Content type of a body-carried authorization.
+pub const LEDGERFLOW_BODY_CONTENT_TYPE: &str = "application/cbor";
+
 /// Encodes a challenge as a base64url header parameter value.
 pub fn encode_challenge_param(challenge: &LedgerFlowChallenge) -> Result<String, ProtocolError> {
     let bytes = cbor_encode(challenge, MAX_HEADER_CBOR_BYTES)?;
@@ -34,7 +52,8 @@ pub fn decode_challenge_param(value: &str) -> Result<LedgerFlowChallenge, Protoc
     cbor_decode(&bytes, MAX_HEADER_CBOR_BYTES)
 }
 
-/// Encodes an authorization (header-slim: single warrant + proof + signer).
+/// Encodes an authorization (header-slim: the leaf warrant, the digests of
+/// its parents, proof + signer).
 pub fn encode_authorization_param(
     chain: &[Warrant],
     proof: &PopProof,
@@ -42,13 +61,14 @@ pub fn encode_authorization_param(
     payment_subject: &PaymentSubjectRef,
     approvals: &[SignedApproval],
 ) -> Result<String, ProtocolError> {
-    let leaf = chain.last().ok_or(ProtocolError::EmptyChain)?;
+    let (leaf, parents) = chain.split_last().ok_or(ProtocolError::EmptyChain)?;
     let slim = SlimAuthorization {
         leaf: leaf.clone(),
         proof: proof.clone(),
         signer: signer.clone(),
         payment_subject: payment_subject.clone(),
         approvals: approvals.to_vec(),
+        parent_digests: parents.iter().map(Warrant::digest).collect(),
     };
     let bytes = cbor_encode(&slim, MAX_HEADER_CBOR_BYTES)?;
     Ok(base64url_encode(&bytes))
@@ -62,8 +82,9 @@ pub fn decode_authorization_param(value: &str) -> Result<SlimAuthorization, Prot
 
 /// The header-slim authorization payload carried in MPP `ledgerflow` params.
 ///
-/// Contains only the leaf warrant (plus proof/signer); the full chain is
-/// fetched from the body or an established cache.
+/// Contains only the leaf warrant (plus proof/signer) and the root-first
+/// digests of its parents; the parents are fetched from the body, an
+/// established cache, or a remote `WarrantRepository`.
 #[derive(Clone, Debug, serde::Deserialize, serde::Serialize)]
 pub struct SlimAuthorization {
     pub leaf: Warrant,
@@ -71,6 +92,8 @@ pub struct SlimAuthorization {
     pub signer: SignerRef,
     pub payment_subject: PaymentSubjectRef,
     pub approvals: Vec<SignedApproval>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parent_digests: Vec<String>,
 }
 
 impl SlimAuthorization {
@@ -88,6 +111,376 @@ impl SlimAuthorization {
             signer: self.signer,
             payment_subject: self.payment_subject,
             approvals: self.approvals,
+            parent_digests: Vec::new(),
+        }
+    }
+
+    /// Converts to a digest-referenced extension (the leaf inline, parents
+    /// by digest) for a verifier that resolves them itself.
+    #[must_use]
+    pub fn into_referenced_extension(mut self) -> LedgerFlowAuthorizationExtension {
+        let parent_digests = std::mem::take(&mut self.parent_digests);
+        LedgerFlowAuthorizationExtension { parent_digests, ..self.into_extension(Vec::new()) }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Payment auth-scheme headers
+// ---------------------------------------------------------------------------
+
+/// Auth-params the Payment scheme defines; others are kept in `extra`.
+const PAYMENT_PARAMS: [&str; 8] = [
+    "id",
+    "realm",
+    "method",
+    "intent",
+    "request",
+    "expires",
+    LEDGERFLOW_PARAM,
+    LEDGERFLOW_DIGEST_PARAM,
+];
+
+/// A `WWW-Authenticate: Payment` challenge.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PaymentChallenge {
+    /// Challenge id, echoed by the credential.
+    pub id: String,
+    pub realm: String,
+    /// Payment method (e.g. `tempo`, `stripe`).
+    pub method: String,
+    /// Payment intent (e.g. `charge`, `session`).
+    pub intent: String,
+    /// Method-specific payment request (base64url JSON).
+    pub request: String,
+    /// RFC 3339 expiry of the challenge.
+    pub expires: Option<String>,
+    pub ledgerflow: Option<LedgerFlowChallenge>,
+    /// Auth-params this binding does not interpret, in header order.
+    pub extra: Vec<(String, String)>,
+}
+
+impl PaymentChallenge {
+    /// Serializes the challenge as a `WWW-Authenticate` value.
+    pub fn to_header_value(&self) -> Result<String, ProtocolError> {
+        let mut challenge = AuthChallenge::new(PAYMENT_SCHEME)
+            .param("id", &self.id)
+            .param("realm", &self.realm)
+            .param("method", &self.method)
+            .param("intent", &self.intent)
+            .param("request", &self.request);
+        if let Some(expires) = &self.expires {
+            challenge = challenge.param("expires", expires);
+        }
+        if let Some(ledgerflow) = &self.ledgerflow {
+            challenge = challenge.param(LEDGERFLOW_PARAM, encode_challenge_param(ledgerflow)?);
         }
+        challenge.params.extend(self.extra.iter().cloned());
+        challenge.to_header_value()
+    }
+
+    /// Parses the first Payment challenge of a `WWW-Authenticate` value.
+    pub fn parse(value: &str) -> Result<Self, ProtocolError> {
+        let challenge = parse_challenges(value)?
+            .into_iter()
+            .find(|challenge| challenge.is_scheme(PAYMENT_SCHEME))
+            .ok_or_else(|| ProtocolError::InvalidAuthHeader("no Payment challenge".to_string()))?;
+        Self::from_auth(&challenge)
+    }
+
+    fn from_auth(challenge: &AuthChallenge) -> Result<Self, ProtocolError> {
+        let required = |name: &str| {
+            challenge.get(name).map(str::to_string).ok_or_else(|| {
+                ProtocolError::InvalidAuthHeader(format!("Payment challenge lacks {name}"))
+            })
+        };
+        Ok(Self {
+            id: required("id")?,
+            realm: required("realm")?,
+            method: required("method")?,
+            intent: required("intent")?,
+            request: required("request")?,
+            expires: challenge.get("expires").map(str::to_string),
+            ledgerflow: challenge.get(LEDGERFLOW_PARAM).map(decode_challenge_param).transpose()?,
+            extra: extra_params(challenge),
+        })
+    }
+}
+
+/// Where a credential's LedgerFlow authorization travels.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum LedgerFlowReference {
+    /// Inline in the `ledgerflow` parameter.
+    Header(Box<LedgerFlowAuthorizationExtension>),
+    /// In the body; the `ledgerflow-digest` parameter is
+    /// `sha256:<hex>` of the CBOR body.
+    Body { digest: String },
+}
+
+/// An `Authorization: Payment` credential.
+#[derive(Clone, Debug, Default, Eq, PartialEq)]
+pub struct PaymentCredential {
+    /// The id of the challenge being answered.
+    pub id: String,
+    pub realm: Option<String>,
+    pub method: Option<String>,
+    pub intent: Option<String>,
+    pub request: Option<String>,
+    pub expires: Option<String>,
+    pub ledgerflow: Option<LedgerFlowReference>,
+    /// Auth-params this binding does not interpret (e.g. the method's
+    /// payment proof), in header order.
+    pub extra: Vec<(String, String)>,
+}
+
+/// A credential ready to send.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct EmittedCredential {
+    /// The `Authorization` header value.
+    pub header: String,
+    /// Carrier the authorization ended up in.
+    pub carrier: LedgerFlowCarrier,
+    /// CBOR authorization to send as the body
+    /// ([`LEDGERFLOW_BODY_CONTENT_TYPE`]) when `carrier` is `HttpBody`.
+    pub body: Option<Vec<u8>>,
+}
+
+impl PaymentCredential {
+    /// A credential echoing `challenge`'s parameters.
+    #[must_use]
+    pub fn answering(challenge: &PaymentChallenge) -> Self {
+        Self {
+            id: challenge.id.clone(),
+            realm: Some(challenge.realm.clone()),
+            method: Some(challenge.method.clone()),
+            intent: Some(challenge.intent.clone()),
+            request: Some(challenge.request.clone()),
+            expires: challenge.expires.clone(),
+            ledgerflow: None,
+            extra: Vec::new(),
+        }
+    }
+
+    /// Attaches `authorization` and serializes the credential, choosing the
+    /// carrier: inline when its CBOR fits [`MAX_HEADER_CBOR_BYTES`],
+    /// otherwise a digest reference in the header and the CBOR as the body.
+    pub fn emit(
+        mut self,
+        authorization: &LedgerFlowAuthorizationExtension,
+    ) -> Result<EmittedCredential, ProtocolError> {
+        let bytes = authorization.encode_cbor()?;
+        let (carrier, body) = if LedgerFlowCarrier::HttpHeader.validate(bytes.len()).is_ok() {
+            self.ledgerflow = Some(LedgerFlowReference::Header(Box::new(authorization.clone())));
+            (LedgerFlowCarrier::HttpHeader, None)
+        } else {
+            self.ledgerflow = Some(LedgerFlowReference::Body { digest: sha256_prefixed(&bytes) });
+            (LedgerFlowCarrier::HttpBody, Some(bytes))
+        };
+        Ok(EmittedCredential { header: self.to_header_value()?, carrier, body })
+    }
+
+    /// Serializes the credential as an `Authorization` value.
+    pub fn to_header_value(&self) -> Result<String, ProtocolError> {
+        let mut credential = AuthChallenge::new(PAYMENT_SCHEME).param("id", &self.id);
+        for (name, value) in [
+            ("realm", &self.realm),
+            ("method", &self.method),
+            ("intent", &self.intent),
+            ("request", &self.request),
+            ("expires", &self.expires),
+        ] {
+            if let Some(value) = value {
+                credential = credential.param(name, value);
+            }
+        }
+        match &self.ledgerflow {
+            Some(LedgerFlowReference::Header(authorization)) => {
+                let bytes = cbor_encode(authorization.as_ref(), MAX_HEADER_CBOR_BYTES)?;
+                credential = credential.param(LEDGERFLOW_PARAM, base64url_encode(&bytes));
+            }
+            Some(LedgerFlowReference::Body { digest }) => {
+                credential = credential.param(LEDGERFLOW_DIGEST_PARAM, digest);
+            }
+            None => {}
+        }
+        credential.params.extend(self.extra.iter().cloned());
+        credential.to_header_value()
+    }
+
+    /// Parses an `Authorization: Payment` value.
+    pub fn parse(value: &str) -> Result<Self, ProtocolError> {
+        let credential = parse_credentials(value)?;
+        if !credential.is_scheme(PAYMENT_SCHEME) {
+            return Err(ProtocolError::InvalidAuthHeader(format!(
+                "expected the Payment scheme, got {}",
+                credential.scheme
+            )));
+        }
+        let param = |name: &str| credential.get(name).map(str::to_string);
+        let ledgerflow = match (param(LEDGERFLOW_PARAM), param(LEDGERFLOW_DIGEST_PARAM)) {
+            (Some(_), Some(_)) => {
+                return Err(ProtocolError::InvalidAuthHeader(
+                    "ledgerflow and ledgerflow-digest are exclusive".to_string(),
+                ));
+            }
+            (Some(inline), None) => Some(LedgerFlowReference::Header(Box::new(cbor_decode(
+                &base64url_decode(&inline)?,
+                MAX_HEADER_CBOR_BYTES,
+            )?))),
+            (None, Some(digest)) => Some(LedgerFlowReference::Body { digest }),
+            (None, None) => None,
+        };
+        Ok(Self {
+            id: param("id").ok_or_else(|| {
+                ProtocolError::InvalidAuthHeader("Payment credential lacks id".to_string())
+            })?,
+            realm: param("realm"),
+            method: param("method"),
+            intent: param("intent"),
+            request: param("request"),
+            expires: param("expires"),
+            ledgerflow,
+            extra: extra_params(&credential),
+        })
+    }
+
+    /// Resolves the LedgerFlow authorization, reading it from `body` when
+    /// the header carries a digest reference. `None` when the credential
+    /// carries no LedgerFlow data.
+    pub fn authorization(
+        &self,
+        body: Option<&[u8]>,
+    ) -> Result<Option<LedgerFlowAuthorizationExtension>, ProtocolError> {
+        match &self.ledgerflow {
+            None => Ok(None),
+            Some(LedgerFlowReference::Header(authorization)) => Ok(Some(*authorization.clone())),
+            Some(LedgerFlowReference::Body { digest }) => {
+                let body = body.ok_or(ProtocolError::DigestMismatch)?;
+                if sha256_prefixed(body) != *digest {
+                    return Err(ProtocolError::DigestMismatch);
+                }
+                LedgerFlowAuthorizationExtension::decode_cbor(body).map(Some)
+            }
+        }
+    }
+}
+
+fn extra_params(challenge: &AuthChallenge) -> Vec<(String, String)> {
+    challenge
+        .params
+        .iter()
+        .filter(|(name, _)| !PAYMENT_PARAMS.iter().any(|known| known.eq_ignore_ascii_case(name)))
+        .cloned()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::expect_used)]
+
+    use std::collections::BTreeMap;
+
+    use ledgerflow_core::{
+        PaymentConstraint, PaymentSubjectKind, SigningKeyPair, WarrantBuilder, WarrantChain,
+    };
+
+    use super::*;
+    use crate::x402::{
+        AcceptedQuote, HttpRequest, PaymentPayloadSeed, build_payment_payload,
+        merchant_payment_required,
+    };
+
+    fn challenge() -> PaymentChallenge {
+        PaymentChallenge {
+            id: "c-1".to_string(),
+            realm: "api.merchant-a.example".to_string(),
+            method: "tempo".to_string(),
+            intent: "charge".to_string(),
+            request: "eyJhbW91bnQiOiIxMDAifQ".to_string(),
+            expires: Some("2026-01-01T00:00:00Z".to_string()),
+            ledgerflow: merchant_payment_required("c-1", "merchant-a", "/search", vec![], 60_000)
+                .ledgerflow,
+            extra: vec![("description".to_string(), "Search \"pro\"".to_string())],
+        }
+    }
+
+    /// An authorization presenting `depth` copies of one warrant (the carrier
+    /// only cares about size).
+    fn authorization(depth: usize) -> LedgerFlowAuthorizationExtension {
+        let issuer = SigningKeyPair::from_bytes(&[0x41; 32]);
+        let holder = SigningKeyPair::from_bytes(&[0x42; 32]);
+        let warrant = WarrantBuilder::new(2_000)
+            .ttl_secs(60)
+            .issuer(issuer.signer_ref())
+            .holder(holder.signer_ref())
+            .payment(PaymentConstraint::new(1_000))
+            .sign_with(&issuer, [0_u8; 8]);
+        let payload = build_payment_payload(
+            challenge().ledgerflow.as_ref().expect("challenge"),
+            &HttpRequest::new("POST", "merchant-a.example", "/search", Vec::new()),
+            AcceptedQuote::exact("USDC", 100, "merchant-a", None),
+            WarrantChain { warrants: vec![warrant; depth] },
+            PaymentPayloadSeed {
+                payment_subject: PaymentSubjectRef::new(PaymentSubjectKind::Opaque, "acct-1"),
+                signer: holder,
+                created_at_ms: 2_000,
+                nonce: "nonce-1".to_string(),
+                payment_identifier: None,
+                tool_args: BTreeMap::new(),
+                approvals: Vec::new(),
+            },
+        )
+        .expect("payload");
+        payload.ledgerflow.expect("extension")
+    }
+
+    #[test]
+    fn challenges_round_trip_and_are_found_in_lists() {
+        let header = challenge().to_header_value().expect("serialize");
+        assert!(header.starts_with(r#"Payment id="c-1", realm="api.merchant-a.example""#));
+        assert_eq!(PaymentChallenge::parse(&header).expect("parse"), challenge());
+        let listed = format!(r#"Bearer realm="oauth", {header}"#);
+        assert_eq!(PaymentChallenge::parse(&listed).expect("listed"), challenge());
+        assert!(PaymentChallenge::parse(r#"Payment id="c-1", realm="r""#).is_err());
+        assert!(PaymentChallenge::parse(r#"Bearer realm="oauth""#).is_err());
+    }
+
+    #[test]
+    fn small_authorizations_stay_in_the_header() {
+        let authorization = authorization(1);
+        let emitted =
+            PaymentCredential::answering(&challenge()).emit(&authorization).expect("emit");
+        assert_eq!(emitted.carrier, LedgerFlowCarrier::HttpHeader);
+        assert!(emitted.body.is_none());
+
+        let credential = PaymentCredential::parse(&emitted.header).expect("parse");
+        assert_eq!(credential.id, "c-1");
+        assert_eq!(credential.intent.as_deref(), Some("charge"));
+        assert_eq!(credential.authorization(None).expect("inline"), Some(authorization));
+    }
+
+    #[test]
+    fn long_chains_move_to_the_body_behind_a_digest() {
+        let authorization = authorization(8);
+        assert!(authorization.encode_cbor().expect("cbor").len() > MAX_HEADER_CBOR_BYTES);
+        let emitted =
+            PaymentCredential::answering(&challenge()).emit(&authorization).expect("emit");
+        assert_eq!(emitted.carrier, LedgerFlowCarrier::HttpBody);
+        assert!(emitted.header.len() < MAX_HEADER_CBOR_BYTES);
+        let body = emitted.body.expect("body");
+
+        let credential = PaymentCredential::parse(&emitted.header).expect("parse");
+        assert!(matches!(credential.ledgerflow, Some(LedgerFlowReference::Body { .. })));
+        assert_eq!(credential.authorization(Some(&body)).expect("body"), Some(authorization));
+
+        let mut tampered = body;
+        tampered[0] ^= 1;
+        assert!(matches!(
+            credential.authorization(Some(&tampered)),
+            Err(ProtocolError::DigestMismatch)
+        ));
+        assert!(credential.authorization(None).is_err());
+        assert!(
+            PaymentCredential::parse(&format!(r#"{}, ledgerflow="AA""#, emitted.header)).is_err()
+        );
     }
 }
@@ -0,0 +1,943 @@
+//! Drop-in x402 paywall for axum / tower merchants (feature `paywall`;
+//! design §7.1).
+//!
+//! [`PaywallLayer`] runs the whole merchant side of the 402 flow in front of
+//! the priced routes:
+//!
+//! 1. A request without a `PAYMENT-SIGNATURE` header gets `402 Payment Required` with the route's
+//!    [`AcceptedQuote`]s and a fresh [`LedgerFlowChallenge`] as an x402 v2 `PaymentRequired`
+//!    document (JSON body and `PAYMENT-REQUIRED` header). The paywall remembers the challenge id
+//!    until the challenge TTL runs out.
+//! 2. A retry carrying `PAYMENT-SIGNATURE` is decoded, checked against a quote the route offered
+//!    and a challenge the paywall issued, and verified through an [`AsyncMerchantVerifier`].
+//! 3. An optional [`PaymentSettler`] (e.g. [`HostedFacilitator`]) settles the payment. The
+//!    challenge is burned once the payment is settled (or verified, without a settler); a failed
+//!    settlement leaves it live for another attempt. A retry of a settled payment with the same
+//!    payment identifier replays the recorded settlement instead of settling twice.
+//! 4. The inner service sees the [`VerifiedAuthorization`] in the request extensions, and the
+//!    response carries the settlement in `PAYMENT-RESPONSE`.
+//!
+//! Every failure is answered with a new 402 whose `error` names the reason.
+//!
+//! ```ignore
+//! let paywall = Paywall::new("merchant-a", verifier, trusted)
+//!     .price("/search", PaywallRoute::new(vec![quote]).with_tool_name("web-search"))
+//!     .with_settler(Arc::new(HostedFacilitator::new("https://facilitator.example")));
+//! let app = Router::new()
+//!     .route("/search", post(search))
+//!     .layer(PaywallLayer::new(paywall));
+//!
+//! async fn search(Extension(auth): Extension<VerifiedAuthorization>) -> String { ... }
+//! ```
+//!
+//! Headers use the x402 v2 JSON wire ([`crate::x402_json`]); clients build
+//! `PAYMENT-SIGNATURE` with [`encode_payment_signature`](crate::encode_payment_signature).
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    future::Future,
+    pin::Pin,
+    sync::{Arc, PoisonError, RwLock},
+    task::{Context, Poll},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use axum::{
+    Json,
+    body::Body,
+    http::{HeaderValue, Request, Response, StatusCode},
+    response::IntoResponse,
+};
+use ledgerflow_core::{
+    AsyncRevocationCheck, TrustedIssuers, VerifiedAuthorization, generate_warrant_id_128,
+    hex_encode_bytes,
+};
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::{
+    error::ProtocolError,
+    facilitator_json::{FacilitatorRequest, PaymentPayloadBody},
+    middleware::{
+        AsyncMerchantVerifier, AsyncWarrantRepository, MerchantVerificationError,
+        VerificationContextBuilder,
+    },
+    replay::AsyncReplayStore,
+    x402::{
+        AcceptedQuote, HttpRequest, LedgerFlowChallenge, PaymentPayload,
+        merchant_payment_required_with,
+    },
+    x402_json::{
+        PAYMENT_REQUIRED_HEADER, PAYMENT_RESPONSE_HEADER, PAYMENT_SIGNATURE_HEADER,
+        X402PaymentRequired, X402SettlementResponse, decode_payment_signature,
+    },
+};
+
+/// Default cap on a buffered request body (the body is hashed into the PoP).
+pub const DEFAULT_MAX_BODY_BYTES: usize = 1024 * 1024;
+
+// ---------------------------------------------------------------------------
+// Settlement seam
+// ---------------------------------------------------------------------------
+
+/// A payment the paywall verified, handed to a [`PaymentSettler`].
+#[derive(Clone, Copy, Debug)]
+pub struct VerifiedPayment<'a> {
+    pub challenge: &'a LedgerFlowChallenge,
+    pub request: &'a HttpRequest,
+    pub payload: &'a PaymentPayload,
+    pub authorization: &'a VerifiedAuthorization,
+}
+
+/// Boxed future returned by [`PaymentSettler::settle`].
+pub type SettleFuture<'a> =
+    Pin<Box<dyn Future<Output = Result<X402SettlementResponse, PaywallError>> + Send + 'a>>;
+
+/// Settles verified payments (usually through a facilitator).
+pub trait PaymentSettler: fmt::Debug + Send + Sync {
+    fn settle<'a>(&'a self, payment: VerifiedPayment<'a>) -> SettleFuture<'a>;
+}
+
+/// Shared settler handle.
+pub type SharedPaymentSettler = Arc<dyn PaymentSettler>;
+
+/// Reasons the paywall refuses a payment (each answered with a 402).
+#[derive(Debug, Error)]
+pub enum PaywallError {
+    #[error("invalid payment header: {0}")]
+    InvalidPaymentHeader(#[from] ProtocolError),
+    #[error("the payment does not echo a challenge issued by this paywall")]
+    UnknownChallenge,
+    #[error("the accepted quote was not offered for this route")]
+    QuoteNotOffered,
+    #[error("the request body could not be read: {0}")]
+    Body(String),
+    #[error(transparent)]
+    Verification(#[from] MerchantVerificationError),
+    #[error("settlement failed: {0}")]
+    Settlement(String),
+}
+
+// ---------------------------------------------------------------------------
+// Configuration
+// ---------------------------------------------------------------------------
+
+/// Pricing of one paid route.
+#[derive(Clone, Debug)]
+pub struct PaywallRoute {
+    accepted: Vec<AcceptedQuote>,
+    tool_name: String,
+    human_present: bool,
+}
+
+impl PaywallRoute {
+    /// Charges one of `accepted` per request.
+    #[must_use]
+    pub const fn new(accepted: Vec<AcceptedQuote>) -> Self {
+        Self { accepted, tool_name: String::new(), human_present: false }
+    }
+
+    /// The tool name warrants' tool constraints and approval gates see.
+    #[must_use]
+    pub fn with_tool_name(mut self, tool_name: impl Into<String>) -> Self {
+        self.tool_name = tool_name.into();
+        self
+    }
+
+    /// Requires human-present approvals (design §6.5).
+    #[must_use]
+    pub const fn with_human_present(mut self, human_present: bool) -> Self {
+        self.human_present = human_present;
+        self
+    }
+}
+
+/// A challenge the paywall issued and still accepts.
+#[derive(Clone, Debug)]
+struct IssuedChallenge {
+    challenge: LedgerFlowChallenge,
+    path: String,
+    expires_at_ms: u64,
+}
+
+/// A settled payment, kept (until its challenge would have expired) so a
+/// retry carrying the same payment identifier gets the same settlement.
+#[derive(Clone, Debug)]
+struct SettledPayment {
+    issued: IssuedChallenge,
+    settlement: X402SettlementResponse,
+}
+
+/// Paywall configuration: the verifier, trust anchors, per-route pricing,
+/// the issued-challenge table and an optional settler.
+#[derive(Debug)]
+pub struct Paywall<R, W, Rev> {
+    verifier: AsyncMerchantVerifier<R, W, Rev>,
+    trusted: Arc<TrustedIssuers>,
+    merchant_id: String,
+    proof_freshness_ms: u64,
+    ledger: Option<String>,
+    routes: BTreeMap<String, PaywallRoute>,
+    challenges: RwLock<BTreeMap<String, IssuedChallenge>>,
+    /// Settled payments by payment identifier.
+    settled: RwLock<BTreeMap<String, SettledPayment>>,
+    settler: Option<SharedPaymentSettler>,
+    max_body_bytes: usize,
+}
+
+impl<R, W, Rev> Paywall<R, W, Rev> {
+    #[must_use]
+    pub fn new(
+        merchant_id: impl Into<String>,
+        verifier: AsyncMerchantVerifier<R, W, Rev>,
+        trusted: Arc<TrustedIssuers>,
+    ) -> Self {
+        Self {
+            verifier,
+            trusted,
+            merchant_id: merchant_id.into(),
+            proof_freshness_ms: ledgerflow_core::DEFAULT_PROOF_FRESHNESS_MS,
+            ledger: None,
+            routes: BTreeMap::new(),
+            challenges: RwLock::new(BTreeMap::new()),
+            settled: RwLock::new(BTreeMap::new()),
+            settler: None,
+            max_body_bytes: DEFAULT_MAX_BODY_BYTES,
+        }
+    }
+
+    /// Prices requests whose path is exactly `path`; other paths pass
+    /// through unpaid.
+    #[must_use]
+    pub fn price(mut self, path: impl Into<String>, route: PaywallRoute) -> Self {
+        self.routes.insert(path.into(), route);
+        self
+    }
+
+    /// Settles every newly verified payment through `settler`.
+    #[must_use]
+    pub fn with_settler(mut self, settler: SharedPaymentSettler) -> Self {
+        self.settler = Some(settler);
+        self
+    }
+
+    /// Advertises the accounting point enforcing warrant budgets.
+    #[must_use]
+    pub fn with_ledger(mut self, ledger: impl Into<String>) -> Self {
+        self.ledger = Some(ledger.into());
+        self
+    }
+
+    /// Sets the PoP freshness window advertised in challenges.
+    #[must_use]
+    pub const fn with_proof_freshness_ms(mut self, proof_freshness_ms: u64) -> Self {
+        self.proof_freshness_ms = proof_freshness_ms;
+        self
+    }
+
+    /// Caps the buffered request body.
+    #[must_use]
+    pub const fn with_max_body_bytes(mut self, max_body_bytes: usize) -> Self {
+        self.max_body_bytes = max_body_bytes;
+        self
+    }
+
+    pub const fn verifier(&self) -> &AsyncMerchantVerifier<R, W, Rev> {
+        &self.verifier
+    }
+
+    /// Issues and remembers a challenge for `route` at `path`.
+    fn issue_challenge(
+        &self,
+        path: &str,
+        route: &PaywallRoute,
+        now_ms: u64,
+    ) -> Result<X402PaymentRequired, ProtocolError> {
+        let challenge_id =
+            format!("ch-{}", hex_encode_bytes(&generate_warrant_id_128(now_ms, rand::random())));
+        let mut response = merchant_payment_required_with(
+            challenge_id,
+            self.merchant_id.clone(),
+            path,
+            route.accepted.clone(),
+            self.proof_freshness_ms,
+            route.human_present,
+        );
+        if let Some(ledger) = &self.ledger {
+            response = response.with_ledger(ledger.clone());
+        }
+        let document = X402PaymentRequired::from_response(&response)?;
+        if let Some(challenge) = response.ledgerflow {
+            let mut challenges = self.challenges.write().unwrap_or_else(PoisonError::into_inner);
+            challenges.retain(|_, issued| issued.expires_at_ms > now_ms);
+            challenges.insert(
+                challenge.challenge_id.clone(),
+                IssuedChallenge {
+                    expires_at_ms: now_ms.saturating_add(challenge.challenge_ttl_ms),
+                    challenge,
+                    path: path.to_string(),
+                },
+            );
+        }
+        Ok(document)
+    }
+
+    /// A `402 Payment Required` response with a fresh challenge, naming
+    /// `error` when a presented payment was refused.
+    fn payment_required(
+        &self,
+        path: &str,
+        route: &PaywallRoute,
+        now_ms: u64,
+        error: Option<&PaywallError>,
+    ) -> Response<Body> {
+        let rendered = self.issue_challenge(path, route, now_ms).and_then(|mut document| {
+            document.error = error.map(ToString::to_string);
+            document.encode_header().map(|header| (document, header))
+        });
+        match rendered {
+            Ok((document, header)) => {
+                let mut response = (StatusCode::PAYMENT_REQUIRED, Json(document)).into_response();
+                if let Ok(value) = HeaderValue::from_str(&header) {
+                    response.headers_mut().insert(PAYMENT_REQUIRED_HEADER, value);
+                }
+                response
+            }
+            Err(error) => {
+                tracing::error!(%error, "failed to issue a payment challenge");
+                StatusCode::INTERNAL_SERVER_ERROR.into_response()
+            }
+        }
+    }
+
+    /// The live challenge `challenge_id` issued for `path`.
+    fn issued_challenge(
+        &self,
+        challenge_id: &str,
+        path: &str,
+        now_ms: u64,
+    ) -> Option<IssuedChallenge> {
+        let challenges = self.challenges.read().unwrap_or_else(PoisonError::into_inner);
+        challenges
+            .get(challenge_id)
+            .filter(|issued| issued.path == path && issued.expires_at_ms > now_ms)
+            .cloned()
+    }
+
+    /// The recorded settlement of `payment_identifier`, made under
+    /// `challenge_id` for `path`.
+    fn settled_payment(
+        &self,
+        payment_identifier: &str,
+        challenge_id: &str,
+        path: &str,
+        now_ms: u64,
+    ) -> Option<SettledPayment> {
+        let settled = self.settled.read().unwrap_or_else(PoisonError::into_inner);
+        settled
+            .get(payment_identifier)
+            .filter(|settled| {
+                settled.issued.challenge.challenge_id == challenge_id &&
+                    settled.issued.path == path &&
+                    settled.issued.expires_at_ms > now_ms
+            })
+            .cloned()
+    }
+
+    /// Burns a challenge after a successful payment, recording the
+    /// settlement under the payment identifier when there is one.
+    fn complete_payment(
+        &self,
+        issued: &IssuedChallenge,
+        payment_identifier: Option<&str>,
+        settlement: Option<&X402SettlementResponse>,
+        now_ms: u64,
+    ) {
+        if let (Some(payment_identifier), Some(settlement)) = (payment_identifier, settlement) {
+            let mut settled = self.settled.write().unwrap_or_else(PoisonError::into_inner);
+            settled.retain(|_, held| held.issued.expires_at_ms > now_ms);
+            settled.insert(
+                payment_identifier.to_string(),
+                SettledPayment { issued: issued.clone(), settlement: settlement.clone() },
+            );
+        }
+        self.challenges
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .remove(&issued.challenge.challenge_id);
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Tower layer
+// ---------------------------------------------------------------------------
+
+/// Tower layer wrapping services in a [`PaywallService`].
+#[derive(Debug)]
+pub struct PaywallLayer<R, W, Rev> {
+    paywall: Arc<Paywall<R, W, Rev>>,
+}
+
+impl<R, W, Rev> PaywallLayer<R, W, Rev> {
+    #[must_use]
+    pub fn new(paywall: Paywall<R, W, Rev>) -> Self {
+        Self { paywall: Arc::new(paywall) }
+    }
+}
+
+impl<R, W, Rev> Clone for PaywallLayer<R, W, Rev> {
+    fn clone(&self) -> Self {
+        Self { paywall: Arc::clone(&self.paywall) }
+    }
+}
+
+impl<S, R, W, Rev> tower::Layer<S> for PaywallLayer<R, W, Rev> {
+    type Service = PaywallService<S, R, W, Rev>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        PaywallService { inner, paywall: Arc::clone(&self.paywall) }
+    }
+}
+
+/// Service enforcing a [`Paywall`] in front of `S`.
+#[derive(Debug)]
+pub struct PaywallService<S, R, W, Rev> {
+    inner: S,
+    paywall: Arc<Paywall<R, W, Rev>>,
+}
+
+impl<S: Clone, R, W, Rev> Clone for PaywallService<S, R, W, Rev> {
+    fn clone(&self) -> Self {
+        Self { inner: self.inner.clone(), paywall: Arc::clone(&self.paywall) }
+    }
+}
+
+impl<S, R, W, Rev> tower::Service<Request<Body>> for PaywallService<S, R, W, Rev>
+where
+    S: tower::Service<Request<Body>, Response = Response<Body>> + Clone + Send + 'static,
+    S::Future: Send,
+    R: AsyncReplayStore + 'static,
+    W: AsyncWarrantRepository + 'static,
+    Rev: AsyncRevocationCheck + 'static,
+{
+    type Response = Response<Body>;
+    type Error = S::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Response<Body>, S::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, request: Request<Body>) -> Self::Future {
+        // Take the service that was driven to readiness; leave a clone.
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let paywall = Arc::clone(&self.paywall);
+        Box::pin(async move {
+            let path = request.uri().path().to_string();
+            let Some(route) = paywall.routes.get(&path) else {
+                return inner.call(request).await;
+            };
+            let now_ms = now_ms();
+            let Some(header) = request.headers().get(PAYMENT_SIGNATURE_HEADER).cloned() else {
+                return Ok(paywall.payment_required(&path, route, now_ms, None));
+            };
+            match paywall.accept(request, &header, &path, route, now_ms).await {
+                Ok((request, settlement)) => {
+                    let mut response = inner.call(request).await?;
+                    if let Some(value) = settlement
+                        .and_then(|settlement| settlement.encode_header().ok())
+                        .and_then(|value| HeaderValue::from_str(&value).ok())
+                    {
+                        response.headers_mut().insert(PAYMENT_RESPONSE_HEADER, value);
+                    }
+                    Ok(response)
+                }
+                Err(error) => Ok(paywall.payment_required(&path, route, now_ms, Some(&error))),
+            }
+        })
+    }
+}
+
+impl<R, W, Rev> Paywall<R, W, Rev>
+where
+    R: AsyncReplayStore,
+    W: AsyncWarrantRepository,
+    Rev: AsyncRevocationCheck,
+{
+    /// Verifies (and settles) the payment in `header`, returning the request
+    /// to forward with its [`VerifiedAuthorization`] extension.
+    async fn accept(
+        &self,
+        request: Request<Body>,
+        header: &HeaderValue,
+        path: &str,
+        route: &PaywallRoute,
+        now_ms: u64,
+    ) -> Result<(Request<Body>, Option<X402SettlementResponse>), PaywallError> {
+        let payload = decode_payment_signature(header.to_str().map_err(|error| {
+            PaywallError::InvalidPaymentHeader(ProtocolError::Deserialization(error.to_string()))
+        })?)?;
+        if !route.accepted.contains(&payload.accepted) {
+            return Err(PaywallError::QuoteNotOffered);
+        }
+        let challenge_id = payload
+            .ledgerflow
+            .as_ref()
+            .map(|extension| extension.challenge_id.as_str())
+            .unwrap_or_default();
+        let payment_identifier = payload.payment_identifier();
+        // A settled payment's challenge is burned; its retries are answered
+        // from the settlement record.
+        let settled =
+            payment_identifier.and_then(|id| self.settled_payment(id, challenge_id, path, now_ms));
+        let issued = match &settled {
+            Some(settled) => settled.issued.clone(),
+            None => self
+                .issued_challenge(challenge_id, path, now_ms)
+                .ok_or(PaywallError::UnknownChallenge)?,
+        };
+        let challenge = &issued.challenge;
+
+        let (parts, body) = request.into_parts();
+        let bytes = axum::body::to_bytes(body, self.max_body_bytes)
+            .await
+            .map_err(|error| PaywallError::Body(error.to_string()))?;
+        let authority = parts
+            .uri
+            .authority()
+            .map(ToString::to_string)
+            .or_else(|| {
+                parts.headers.get("host").and_then(|host| host.to_str().ok()).map(str::to_string)
+            })
+            .unwrap_or_default();
+        let path_and_query =
+            parts.uri.path_and_query().map_or_else(|| path.to_string(), ToString::to_string);
+        let http_request =
+            HttpRequest::new(parts.method.as_str(), authority, path_and_query, bytes.to_vec());
+
+        let context = VerificationContextBuilder::new().with_tool_name(route.tool_name.clone());
+        let outcome = self
+            .verifier
+            .verify_payment_with_context(
+                challenge,
+                &http_request,
+                &payload,
+                &self.trusted,
+                &context,
+                now_ms,
+            )
+            .await?;
+
+        // The verifier caches a payment identifier's authorization as soon as
+        // it verifies, so `settlement_reused` alone does not mean the payment
+        // was settled: only a recorded settlement is replayed, and a retry
+        // after a failed settlement settles again.
+        let settlement = match (&self.settler, settled) {
+            (None, _) => None,
+            (Some(_), Some(settled)) if outcome.settlement_reused => {
+                return Ok((forward(parts, bytes, outcome.authorization), Some(settled.settlement)));
+            }
+            (Some(_), Some(_)) => return Err(PaywallError::UnknownChallenge),
+            (Some(settler), None) => Some(
+                settler
+                    .settle(VerifiedPayment {
+                        challenge,
+                        request: &http_request,
+                        payload: &payload,
+                        authorization: &outcome.authorization,
+                    })
+                    .await?,
+            ),
+        };
+        self.complete_payment(&issued, payment_identifier, settlement.as_ref(), now_ms);
+        Ok((forward(parts, bytes, outcome.authorization), settlement))
+    }
+}
+
+/// The buffered request, carrying its [`VerifiedAuthorization`] extension.
+fn forward(
+    parts: axum::http::request::Parts,
+    bytes: axum::body::Bytes,
+    authorization: VerifiedAuthorization,
+) -> Request<Body> {
+    let mut request = Request::from_parts(parts, Body::from(bytes));
+    request.extensions_mut().insert(authorization);
+    request
+}
+
+fn now_ms() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| u64::try_from(elapsed.as_millis()).unwrap_or(u64::MAX))
+}
+
+// ---------------------------------------------------------------------------
+// Hosted facilitator
+// ---------------------------------------------------------------------------
+
+/// Settles through a ledgerflow-server facilitator's `POST /v1/settle`.
+///
+/// The facilitator re-verifies the payment against its own trust anchors and
+/// revocation view, burns the PoP nonce and settles on the payment's rail.
+#[derive(Clone, Debug)]
+pub struct HostedFacilitator {
+    base_url: String,
+    bearer_token: Option<String>,
+    client: hpx::Client,
+}
+
+impl HostedFacilitator {
+    /// A facilitator at `base_url` (e.g. `https://facilitator.example`).
+    #[must_use]
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            bearer_token: None,
+            client: hpx::Client::new(),
+        }
+    }
+
+    /// Authenticates with a service token (`Authorization: Bearer ...`).
+    #[must_use]
+    pub fn with_bearer_token(mut self, token: impl Into<String>) -> Self {
+        self.bearer_token = Some(token.into());
+        self
+    }
+
+    async fn post_settle(
+        &self,
+        payment: VerifiedPayment<'_>,
+    ) -> Result<X402SettlementResponse, PaywallError> {
+        let settlement_error = |error: String| PaywallError::Settlement(error);
+        let payload = payment.payload;
+        let extension = payload.ledgerflow.as_ref().ok_or_else(|| {
+            settlement_error("the payment carries no LedgerFlow authorization".to_string())
+        })?;
+        // The facilitator's JSON payment payload form (not the x402 document).
+        let body = FacilitatorRequest::new(
+            PaymentPayloadBody::new(payload, extension)?,
+            payment.challenge,
+            payment.request,
+        )?
+        .with_tool_name(payment.authorization.tool_name.clone());
+        let body =
+            serde_json::to_string(&body).map_err(|error| settlement_error(error.to_string()))?;
+        let mut request = self
+            .client
+            .post(format!("{}/v1/settle", self.base_url))
+            .header("content-type", "application/json")
+            .body(body);
+        if let Some(token) = &self.bearer_token {
+            request = request.header("authorization", format!("Bearer {token}"));
+        }
+        let response = request.send().await.map_err(|error| settlement_error(error.to_string()))?;
+        if !response.status().is_success() {
+            return Err(settlement_error(format!("facilitator returned {}", response.status())));
+        }
+        let envelope: HostedSettleEnvelope =
+            response.json().await.map_err(|error| settlement_error(error.to_string()))?;
+        let data =
+            envelope.data.ok_or_else(|| settlement_error(envelope.error.unwrap_or_default()))?;
+        match (data.success, data.transaction_id) {
+            (true, Some(transaction)) => Ok(X402SettlementResponse {
+                success: true,
+                error_reason: None,
+                transaction,
+                network: payload.accepted.network.clone().unwrap_or_default(),
+                payer: data.payer,
+            }),
+            _ => Err(settlement_error(data.error_reason.unwrap_or(data.status))),
+        }
+    }
+}
+
+impl PaymentSettler for HostedFacilitator {
+    fn settle<'a>(&'a self, payment: VerifiedPayment<'a>) -> SettleFuture<'a> {
+        Box::pin(self.post_settle(payment))
+    }
+}
+
+/// `ApiResponse<SettleResponse>` envelope of `POST /v1/settle`.
+#[derive(Debug, Deserialize)]
+struct HostedSettleEnvelope {
+    data: Option<HostedSettleResponse>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HostedSettleResponse {
+    success: bool,
+    status: String,
+    error_reason: Option<String>,
+    payer: Option<String>,
+    transaction_id: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::expect_used)]
+
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use axum::{Extension, Router, routing::post};
+    use ledgerflow_core::{
+        InMemoryRevocationCheck, MerchantConstraint, PaymentConstraint, PaymentSubjectKind,
+        PaymentSubjectRef, SigningKeyPair, TrustedIssuer, WarrantBuilder, WarrantChain,
+    };
+    use tower::ServiceExt as _;
+
+    use super::*;
+    use crate::{
+        middleware::InMemoryWarrantRepository,
+        replay::InMemoryReplayStore,
+        x402::{PaymentPayloadSeed, build_payment_payload},
+    };
+
+    type Verifier = AsyncMerchantVerifier<
+        RwLock<InMemoryReplayStore>,
+        RwLock<InMemoryWarrantRepository>,
+        InMemoryRevocationCheck,
+    >;
+
+    fn issuer_keys() -> SigningKeyPair {
+        SigningKeyPair::from_bytes(&[0x31; 32])
+    }
+
+    fn holder_keys() -> SigningKeyPair {
+        SigningKeyPair::from_bytes(&[0x32; 32])
+    }
+
+    fn quote() -> AcceptedQuote {
+        AcceptedQuote::exact("USDC", 100, "merchant-a", Some("base".to_string()))
+    }
+
+    /// Counts settlement attempts; the first `failures` attempts fail.
+    #[derive(Debug, Default)]
+    struct CountingSettler {
+        attempts: AtomicUsize,
+        failures: usize,
+    }
+
+    impl CountingSettler {
+        fn failing(failures: usize) -> Self {
+            Self { attempts: AtomicUsize::new(0), failures }
+        }
+    }
+
+    impl PaymentSettler for CountingSettler {
+        fn settle<'a>(&'a self, payment: VerifiedPayment<'a>) -> SettleFuture<'a> {
+            let count = self.attempts.fetch_add(1, Ordering::SeqCst) + 1;
+            Box::pin(async move {
+                if count <= self.failures {
+                    return Err(PaywallError::Settlement("rail unavailable".to_string()));
+                }
+                Ok(X402SettlementResponse {
+                    success: true,
+                    error_reason: None,
+                    transaction: format!("tx-{count}"),
+                    network: payment.payload.accepted.network.clone().unwrap_or_default(),
+                    payer: Some(payment.authorization.payment_subject.value.clone()),
+                })
+            })
+        }
+    }
+
+    fn app(settler: Arc<CountingSettler>) -> Router {
+        let mut trusted = TrustedIssuers::new();
+        trusted.add(TrustedIssuer::new("issuer-1".to_string(), issuer_keys().signer_ref()));
+        let verifier: Verifier = AsyncMerchantVerifier::new(
+            RwLock::new(InMemoryReplayStore::default()),
+            RwLock::new(InMemoryWarrantRepository::default()),
+            InMemoryRevocationCheck::new(),
+        );
+        let paywall = Paywall::new("merchant-a", verifier, Arc::new(trusted))
+            .price("/search", PaywallRoute::new(vec![quote()]).with_tool_name("web-search"))
+            .with_settler(settler);
+        Router::new()
+            .route(
+                "/search",
+                post(|Extension(authorization): Extension<VerifiedAuthorization>| async move {
+                    format!("paid {} by {}", authorization.amount, authorization.tool_name)
+                }),
+            )
+            .route("/free", post(|| async { "free" }))
+            .layer(PaywallLayer::new(paywall))
+    }
+
+    fn search(payment: Option<&str>) -> Request<Body> {
+        let mut builder =
+            Request::builder().method("POST").uri("/search").header("host", "merchant-a.example");
+        if let Some(payment) = payment {
+            builder = builder.header(PAYMENT_SIGNATURE_HEADER, payment);
+        }
+        builder.body(Body::from("{\"q\":\"rust\"}")).expect("request")
+    }
+
+    async fn payment_required(response: Response<Body>) -> X402PaymentRequired {
+        assert_eq!(response.status(), StatusCode::PAYMENT_REQUIRED);
+        let header = X402PaymentRequired::decode_header(
+            response.headers()[PAYMENT_REQUIRED_HEADER].to_str().expect("ascii"),
+        )
+        .expect("header");
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.expect("body");
+        let body: X402PaymentRequired = serde_json::from_slice(&bytes).expect("json");
+        assert_eq!(body, header);
+        body
+    }
+
+    fn pay(challenge: &LedgerFlowChallenge, accepted: AcceptedQuote, nonce: &str) -> String {
+        pay_with_identifier(challenge, accepted, nonce, None)
+    }
+
+    fn pay_with_identifier(
+        challenge: &LedgerFlowChallenge,
+        accepted: AcceptedQuote,
+        nonce: &str,
+        payment_identifier: Option<&str>,
+    ) -> String {
+        let now = now_ms();
+        let warrant = WarrantBuilder::new(now)
+            .ttl_secs(300)
+            .issuer(issuer_keys().signer_ref())
+            .holder(holder_keys().signer_ref())
+            .merchant(MerchantConstraint::with_ids(vec!["merchant-a".to_string()]))
+            .payment(PaymentConstraint::new(1_000))
+            .sign_with(&issuer_keys(), [0_u8; 8]);
+        let request =
+            HttpRequest::new("POST", "merchant-a.example", "/search", b"{\"q\":\"rust\"}".to_vec());
+        let payload = build_payment_payload(
+            challenge,
+            &request,
+            accepted,
+            WarrantChain::single(warrant),
+            PaymentPayloadSeed {
+                payment_subject: PaymentSubjectRef::new(
+                    PaymentSubjectKind::Caip10,
+                    "caip10:eip155:8453:0xabc123",
+                ),
+                signer: holder_keys(),
+                created_at_ms: now,
+                nonce: nonce.to_string(),
+                payment_identifier: payment_identifier.map(str::to_string),
+                tool_args: BTreeMap::new(),
+                approvals: Vec::new(),
+            },
+        )
+        .expect("payload");
+        crate::encode_payment_signature(&payload).expect("header")
+    }
+
+    #[tokio::test]
+    async fn unpaid_requests_get_a_challenge_and_paid_retries_reach_the_handler() {
+        let settler = Arc::new(CountingSettler::default());
+        let app = app(Arc::clone(&settler));
+
+        let free = app
+            .clone()
+            .oneshot(
+                Request::builder().method("POST").uri("/free").body(Body::empty()).expect("req"),
+            )
+            .await
+            .expect("free");
+        assert_eq!(free.status(), StatusCode::OK);
+
+        let body = payment_required(app.clone().oneshot(search(None)).await.expect("402")).await;
+        assert!(body.error.is_none());
+        assert_eq!(body.accepted_quotes().expect("quotes"), vec![quote()]);
+        let challenge = body.ledgerflow_challenge().expect("decode").expect("challenge");
+        assert_eq!(challenge.resource, "/search");
+
+        let header = pay(&challenge, quote(), "nonce-1");
+        let response = app.clone().oneshot(search(Some(&header))).await.expect("paid");
+        assert_eq!(response.status(), StatusCode::OK);
+        let settlement = X402SettlementResponse::decode_header(
+            response.headers()[PAYMENT_RESPONSE_HEADER].to_str().expect("ascii"),
+        )
+        .expect("settlement");
+        assert_eq!(settlement.transaction, "tx-1");
+        assert_eq!(settlement.network, "base");
+        assert_eq!(settlement.payer.as_deref(), Some("caip10:eip155:8453:0xabc123"));
+        let bytes = axum::body::to_bytes(response.into_body(), usize::MAX).await.expect("body");
+        assert_eq!(bytes.as_ref(), b"paid 100 by web-search");
+
+        // The challenge is burned: the same payment is refused with a new 402.
+        let body =
+            payment_required(app.oneshot(search(Some(&header))).await.expect("replay")).await;
+        assert_eq!(
+            body.error.as_deref(),
+            Some(PaywallError::UnknownChallenge.to_string().as_str())
+        );
+        assert_eq!(settler.attempts.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn payments_must_use_an_offered_quote_and_an_issued_challenge() {
+        let settler = Arc::new(CountingSettler::default());
+        let app = app(Arc::clone(&settler));
+        let body = payment_required(app.clone().oneshot(search(None)).await.expect("402")).await;
+        let challenge = body.ledgerflow_challenge().expect("decode").expect("challenge");
+
+        let cheaper = AcceptedQuote::exact("USDC", 1, "merchant-a", Some("base".to_string()));
+        let refused = app.clone().oneshot(search(Some(&pay(&challenge, cheaper, "n-1")))).await;
+        let body = payment_required(refused.expect("refused")).await;
+        assert_eq!(body.error.as_deref(), Some(PaywallError::QuoteNotOffered.to_string().as_str()));
+
+        let mut forged = challenge;
+        forged.challenge_id = "ch-forged".to_string();
+        let refused = app.clone().oneshot(search(Some(&pay(&forged, quote(), "n-2")))).await;
+        let body = payment_required(refused.expect("refused")).await;
+        assert_eq!(
+            body.error.as_deref(),
+            Some(PaywallError::UnknownChallenge.to_string().as_str())
+        );
+
+        let garbage = app.oneshot(search(Some("not base64!"))).await.expect("garbage");
+        let body = payment_required(garbage).await;
+        assert!(body.error.is_some_and(|error| error.starts_with("invalid payment header")));
+        assert_eq!(settler.attempts.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn failed_settlements_are_retried_and_settled_payments_replayed() {
+        let settler = Arc::new(CountingSettler::failing(2));
+        let app = app(Arc::clone(&settler));
+        let body = payment_required(app.clone().oneshot(search(None)).await.expect("402")).await;
+        let challenge = body.ledgerflow_challenge().expect("decode").expect("challenge");
+        let header = pay_with_identifier(&challenge, quote(), "nonce-1", Some("pay-1"));
+
+        // The verifier caches the payment when it verifies; a retry after a
+        // failed settlement must settle again rather than be served unpaid.
+        for _ in 0..2 {
+            let refused = app.clone().oneshot(search(Some(&header))).await.expect("refused");
+            let body = payment_required(refused).await;
+            assert!(body.error.is_some_and(|error| error.starts_with("settlement failed")));
+        }
+
+        let settlement = |response: &Response<Body>| {
+            X402SettlementResponse::decode_header(
+                response.headers()[PAYMENT_RESPONSE_HEADER].to_str().expect("ascii"),
+            )
+            .expect("settlement")
+            .transaction
+        };
+        let paid = app.clone().oneshot(search(Some(&header))).await.expect("paid");
+        assert_eq!(paid.status(), StatusCode::OK);
+        assert_eq!(settlement(&paid), "tx-3");
+
+        // Retrying the settled payment replays its settlement.
+        let replayed = app.clone().oneshot(search(Some(&header))).await.expect("replayed");
+        assert_eq!(replayed.status(), StatusCode::OK);
+        assert_eq!(settlement(&replayed), "tx-3");
+        assert_eq!(settler.attempts.load(Ordering::SeqCst), 3);
+
+        // A different payment on the burned challenge is refused.
+        let other = pay_with_identifier(&challenge, quote(), "nonce-2", Some("pay-2"));
+        let body = payment_required(app.oneshot(search(Some(&other))).await.expect("other")).await;
+        assert_eq!(
+            body.error.as_deref(),
+            Some(PaywallError::UnknownChallenge.to_string().as_str())
+        );
+    }
+}
@@ -0,0 +1,178 @@
+//! Remote warrant repository for digest-referenced chains (design §7.2).
+//!
+//! A digest-referenced authorization carries the leaf inline and its parents
+//! as [`Warrant::digest`]s. [`RemoteWarrantRepository`] resolves those
+//! digests against a ledgerflow-server `GET /v1/warrants/{digest}` endpoint
+//! and caches what it fetches; warrants are content-addressed and immutable,
+//! so a cached entry never goes stale. Only well-formed `sha256:` digests are
+//! requested, and a fetched warrant whose digest does not match the request
+//! is discarded.
+//!
+//! Each fetch is bounded by a request timeout (10 s by default), and a digest
+//! that failed to resolve is not fetched again for a short window (5 s by
+//! default), so a flood of unknown digests cannot pin verifiers on the
+//! endpoint.
+//!
+//! ```ignore
+//! let verifier = AsyncMerchantVerifier::new(
+//!     RwLock::new(InMemoryReplayStore::default()),
+//!     RemoteWarrantRepository::new("https://ledgerflow.example"),
+//!     FileRevocationStore::open("revocations.jsonl")?,
+//! );
+//! ```
+
+use std::{
+    collections::BTreeMap,
+    sync::{PoisonError, RwLock},
+    time::{Duration, Instant},
+};
+
+use ledgerflow_core::{Warrant, is_sha256_prefixed};
+use serde::Deserialize;
+
+use crate::{middleware::AsyncWarrantRepository, wire::base64url_decode};
+
+/// [`AsyncWarrantRepository`] backed by a remote warrant-resolution endpoint.
+#[derive(Debug)]
+pub struct RemoteWarrantRepository {
+    base_url: String,
+    bearer_token: Option<String>,
+    client: hpx::Client,
+    timeout_ms: u64,
+    miss_ttl_ms: u64,
+    cache: RwLock<BTreeMap<String, Warrant>>,
+    /// Digests that failed to resolve, with when they may be fetched again.
+    misses: RwLock<BTreeMap<String, Instant>>,
+}
+
+impl RemoteWarrantRepository {
+    /// A repository resolving against the server at `base_url` (e.g.
+    /// `https://ledgerflow.example`).
+    #[must_use]
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            bearer_token: None,
+            client: hpx::Client::new(),
+            timeout_ms: 10_000,
+            miss_ttl_ms: 5_000,
+            cache: RwLock::new(BTreeMap::new()),
+            misses: RwLock::new(BTreeMap::new()),
+        }
+    }
+
+    /// Authenticates with a service token (`Authorization: Bearer ...`).
+    #[must_use]
+    pub fn with_bearer_token(mut self, token: impl Into<String>) -> Self {
+        self.bearer_token = Some(token.into());
+        self
+    }
+
+    /// Bounds each fetch, from connecting until the body is read.
+    #[must_use]
+    pub const fn with_timeout_ms(mut self, timeout_ms: u64) -> Self {
+        self.timeout_ms = timeout_ms;
+        self
+    }
+
+    /// How long a digest that failed to resolve is answered from the
+    /// negative cache before it is fetched again (0 disables the cache).
+    #[must_use]
+    pub const fn with_miss_ttl_ms(mut self, miss_ttl_ms: u64) -> Self {
+        self.miss_ttl_ms = miss_ttl_ms;
+        self
+    }
+
+    /// Number of cached warrants.
+    #[must_use]
+    pub fn cached(&self) -> usize {
+        self.cache.read().unwrap_or_else(PoisonError::into_inner).len()
+    }
+
+    async fn fetch(&self, digest: &str) -> Result<Warrant, String> {
+        // The digest is caller-supplied and lands in an authenticated URL.
+        if !is_sha256_prefixed(digest) {
+            return Err(format!("`{digest}` is not a warrant digest"));
+        }
+        let mut request = self
+            .client
+            .get(format!("{}/v1/warrants/{digest}", self.base_url))
+            .timeout(Duration::from_millis(self.timeout_ms));
+        if let Some(token) = &self.bearer_token {
+            request = request.header("authorization", format!("Bearer {token}"));
+        }
+        let response = request.send().await.map_err(|error| error.to_string())?;
+        if !response.status().is_success() {
+            return Err(format!("warrant endpoint returned {}", response.status()));
+        }
+        let envelope: WarrantEnvelope = response.json().await.map_err(|error| error.to_string())?;
+        let data = envelope.data.ok_or_else(|| envelope.error.unwrap_or_default())?;
+        let bytes = base64url_decode(&data.warrant).map_err(|error| error.to_string())?;
+        let warrant = Warrant::decode_cbor(&bytes).map_err(|error| error.to_string())?;
+        if warrant.digest() != digest {
+            return Err(format!("the endpoint returned a warrant with digest {}", warrant.digest()));
+        }
+        Ok(warrant)
+    }
+}
+
+impl AsyncWarrantRepository for RemoteWarrantRepository {
+    async fn load(&self, digest: &str) -> Option<Warrant> {
+        if let Some(warrant) = self.cache.read().unwrap_or_else(PoisonError::into_inner).get(digest)
+        {
+            return Some(warrant.clone());
+        }
+        let now = Instant::now();
+        if self
+            .misses
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(digest)
+            .is_some_and(|retry_at| *retry_at > now)
+        {
+            return None;
+        }
+        match self.fetch(digest).await {
+            Ok(warrant) => {
+                self.cache
+                    .write()
+                    .unwrap_or_else(PoisonError::into_inner)
+                    .insert(digest.to_string(), warrant.clone());
+                Some(warrant)
+            }
+            Err(error) => {
+                tracing::warn!(
+                    target: "ledgerflow::warrants",
+                    digest,
+                    error = %error,
+                    "failed to resolve a referenced warrant"
+                );
+                let mut misses = self.misses.write().unwrap_or_else(PoisonError::into_inner);
+                let now = Instant::now();
+                misses.retain(|_, retry_at| *retry_at > now);
+                misses.insert(digest.to_string(), now + Duration::from_millis(self.miss_ttl_ms));
+                None
+            }
+        }
+    }
+
+    async fn store(&self, warrant: Warrant) {
+        self.cache
+            .write()
+            .unwrap_or_else(PoisonError::into_inner)
+            .insert(warrant.digest(), warrant);
+    }
+}
+
+/// `ApiResponse<IssueWarrantResponse>` envelope of `GET /v1/warrants/{digest}`.
+#[derive(Debug, Deserialize)]
+struct WarrantEnvelope {
+    data: Option<WarrantBody>,
+    error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct WarrantBody {
+    /// base64url CBOR of the signed warrant.
+    warrant: String,
+}
@@ -1,6 +1,15 @@
 //! Replay protection and idempotency helpers for merchant verification.
+//!
+//! [`ReplayStore`] is the synchronous seam used by [`MerchantVerifier`](crate::MerchantVerifier);
+//! [`AsyncReplayStore`] is its `&self` counterpart for verifiers shared
+//! across async handlers. Any `ReplayStore` behind an [`RwLock`] is an
+//! `AsyncReplayStore`.
 
-use std::collections::BTreeMap;
+use std::{
+    collections::BTreeMap,
+    future::Future,
+    sync::{PoisonError, RwLock},
+};
 
 use ledgerflow_core::VerifiedAuthorization;
 
@@ -40,6 +49,10 @@ pub trait ReplayStore {
         fingerprint: ReplayFingerprint,
         now_ms: u64,
     ) -> std::result::Result<(), ReplayConflict>;
+    /// Drops the claim taken for exactly `fingerprint`, so a proof whose
+    /// settlement failed can be presented again. Claims by other requests
+    /// for the same nonce are kept.
+    fn release_nonce(&mut self, fingerprint: &ReplayFingerprint);
     fn cached_payment(
         &self,
         payment_identifier: &str,
@@ -55,6 +68,74 @@ pub trait ReplayStore {
     );
 }
 
+/// Async, shareable storage seam for nonce-based replay protection and
+/// payment-id idempotency.
+///
+/// Methods take `&self`: implementations synchronize internally, and
+/// [`Self::claim_nonce`] must be atomic — of concurrent claims for the same
+/// `(challenge_id, nonce)` exactly one succeeds.
+pub trait AsyncReplayStore: Send + Sync {
+    fn claim_nonce(
+        &self,
+        fingerprint: ReplayFingerprint,
+        now_ms: u64,
+    ) -> impl Future<Output = std::result::Result<(), ReplayConflict>> + Send;
+    fn cached_payment(
+        &self,
+        payment_identifier: &str,
+        request_hash: &str,
+        accepted_hash: &str,
+    ) -> impl Future<Output = Option<VerifiedAuthorization>> + Send;
+    fn cache_payment(
+        &self,
+        payment_identifier: String,
+        authorization: VerifiedAuthorization,
+        request_hash: String,
+        accepted_hash: String,
+    ) -> impl Future<Output = ()> + Send;
+}
+
+/// Adapts a synchronous store: the claim runs under the write lock, so it is
+/// atomic. The lock is never held across an `.await`. A poisoned lock is
+/// recovered, since every store operation leaves the maps consistent.
+impl<S: ReplayStore + Send + Sync> AsyncReplayStore for RwLock<S> {
+    async fn claim_nonce(
+        &self,
+        fingerprint: ReplayFingerprint,
+        now_ms: u64,
+    ) -> std::result::Result<(), ReplayConflict> {
+        self.write().unwrap_or_else(PoisonError::into_inner).claim_nonce(fingerprint, now_ms)
+    }
+
+    async fn cached_payment(
+        &self,
+        payment_identifier: &str,
+        request_hash: &str,
+        accepted_hash: &str,
+    ) -> Option<VerifiedAuthorization> {
+        self.read().unwrap_or_else(PoisonError::into_inner).cached_payment(
+            payment_identifier,
+            request_hash,
+            accepted_hash,
+        )
+    }
+
+    async fn cache_payment(
+        &self,
+        payment_identifier: String,
+        authorization: VerifiedAuthorization,
+        request_hash: String,
+        accepted_hash: String,
+    ) {
+        self.write().unwrap_or_else(PoisonError::into_inner).cache_payment(
+            payment_identifier,
+            authorization,
+            request_hash,
+            accepted_hash,
+        );
+    }
+}
+
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct CachedPayment {
     pub authorization: VerifiedAuthorization,
@@ -104,6 +185,13 @@ impl ReplayStore for InMemoryReplayStore {
         Ok(())
     }
 
+    fn release_nonce(&mut self, fingerprint: &ReplayFingerprint) {
+        let key = fingerprint.key();
+        if self.nonce_claims.get(&key).is_some_and(|claim| claim.fingerprint == *fingerprint) {
+            self.nonce_claims.remove(&key);
+        }
+    }
+
     fn cached_payment(
         &self,
         payment_identifier: &str,
@@ -192,6 +280,20 @@ mod tests {
         store.claim_nonce(fingerprint("c1", "n1"), 2_000).expect("exactly ttl is expired");
     }
 
+    #[test]
+    fn released_nonces_can_be_claimed_again() {
+        let mut store = InMemoryReplayStore::default();
+        store.claim_nonce(fingerprint("c1", "n1"), 1_000).expect("first");
+        // Another request's fingerprint does not release the claim.
+        let mut other = fingerprint("c1", "n1");
+        other.request_hash = "sha256:other".to_string();
+        store.release_nonce(&other);
+        assert!(store.claim_nonce(fingerprint("c1", "n1"), 1_500).is_err());
+
+        store.release_nonce(&fingerprint("c1", "n1"));
+        store.claim_nonce(fingerprint("c1", "n1"), 2_000).expect("released");
+    }
+
     #[test]
     fn payment_cache_round_trips() {
         let mut store = InMemoryReplayStore::default();
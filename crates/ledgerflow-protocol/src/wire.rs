@@ -42,6 +42,27 @@ pub fn base64url_decode(encoded: &str) -> Result<Vec<u8>, ProtocolError> {
         .map_err(|error| ProtocolError::InvalidBase64(error.to_string()))
 }
 
+/// Base64-encodes bytes (standard alphabet, padded), as x402 headers do.
+pub fn base64_encode(bytes: &[u8]) -> String {
+    use base64::Engine as _;
+    base64::engine::general_purpose::STANDARD.encode(bytes)
+}
+
+/// Decodes standard or URL-safe base64, padded or not.
+pub fn base64_decode_lenient(encoded: &str) -> Result<Vec<u8>, ProtocolError> {
+    let normalized: String = encoded
+        .trim()
+        .trim_end_matches('=')
+        .chars()
+        .map(|c| match c {
+            '+' => '-',
+            '/' => '_',
+            other => other,
+        })
+        .collect();
+    base64url_decode(&normalized)
+}
+
 /// Validates that a payload fits the given carrier's size budget.
 ///
 /// Header carriers are size-constrained (single-node/digest references only);
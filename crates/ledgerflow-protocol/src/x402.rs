@@ -98,7 +98,9 @@ pub struct LedgerFlowChallenge {
     pub clock_skew_ms: u64,
     pub challenge_ttl_ms: u64,
     pub required_subject_kinds: Vec<String>,
-    /// Accounting point for budget execution (P2+; null in v1).
+    /// Accounting point that enforces warrant budgets for this merchant
+    /// (the Facilitator named by a warrant's `ledgerflow.ledger` policy).
+    /// `None` when the merchant does not settle through an accounting point.
     pub ledger: Option<String>,
     /// Whether this resource requires human presence (AP2-style
     /// human-in-the-loop). When `true`, the presented authorization must
@@ -124,12 +126,18 @@ impl LedgerFlowChallenge {
 pub struct LedgerFlowAuthorizationExtension {
     pub version: String,
     pub challenge_id: String,
-    /// Root-first warrant chain, transmitted **inline** (v1 rule).
+    /// Root-first warrant chain, transmitted **inline** (v1 rule) — or, in
+    /// digest-reference mode, only its tail (at least the leaf).
     pub warrant_chain: Vec<Warrant>,
     pub proof: PopProof,
     pub signer: SignerRef,
     pub payment_subject: PaymentSubjectRef,
     pub approvals: Vec<ledgerflow_core::SignedApproval>,
+    /// Digest-reference mode: root-first [`Warrant::digest`]s of the parents
+    /// omitted from `warrant_chain`, resolved by the verifier's
+    /// `WarrantRepository`. Empty when the chain is inline.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub parent_digests: Vec<String>,
 }
 
 impl LedgerFlowAuthorizationExtension {
@@ -143,11 +151,24 @@ impl LedgerFlowAuthorizationExtension {
         cbor_decode(bytes, MAX_LEDGERFLOW_EXTENSION_BYTES)
     }
 
-    /// Assembles the presented chain into a [`WarrantChain`].
+    /// Assembles the presented chain into a [`WarrantChain`]. In
+    /// digest-reference mode this is only the inline tail; verifiers resolve
+    /// [`parent_digests`](Self::parent_digests) first.
     #[must_use]
     pub fn chain(&self) -> WarrantChain {
         WarrantChain { warrants: self.warrant_chain.clone() }
     }
+
+    /// Switches to digest-reference mode: every warrant but the leaf is
+    /// replaced by its digest, so deep chains fit a header carrier.
+    #[must_use]
+    pub fn into_digest_referenced(mut self) -> Self {
+        let tail = self.warrant_chain.len().saturating_sub(1);
+        let parents: Vec<Warrant> = self.warrant_chain.drain(..tail).collect();
+        let mut digests: Vec<String> = parents.iter().map(Warrant::digest).collect();
+        self.parent_digests.append(&mut digests);
+        self
+    }
 }
 
 /// An x402 `402 Payment Required` response with a LedgerFlow challenge.
@@ -159,6 +180,18 @@ pub struct PaymentRequiredResponse {
     pub ledgerflow: Option<LedgerFlowChallenge>,
 }
 
+impl PaymentRequiredResponse {
+    /// Advertises the accounting point enforcing warrant budgets (builder
+    /// style). No-op when the response carries no LedgerFlow challenge.
+    #[must_use]
+    pub fn with_ledger(mut self, ledger: impl Into<String>) -> Self {
+        if let Some(challenge) = self.ledgerflow.as_mut() {
+            challenge.ledger = Some(ledger.into());
+        }
+        self
+    }
+}
+
 /// x402 payment payload that echoes the quote and adds LedgerFlow authz data.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct PaymentPayload {
@@ -285,7 +318,7 @@ pub fn build_payment_payload(
         nonce: seed.nonce.clone(),
         created_at_ms: seed.created_at_ms,
     };
-    let proof = ProofBuilder::new()
+    let mut proof = ProofBuilder::new()
         .warrant_id(tuple.warrant_id.clone())
         .challenge_id(tuple.challenge_id.clone())
         .method(tuple.method.clone())
@@ -295,8 +328,11 @@ pub fn build_payment_payload(
         .payment_payload_digest(tuple.payment_payload_digest.clone())
         .approvals_digest(tuple.approvals_digest.clone().unwrap_or_default())
         .nonce(tuple.nonce.clone())
-        .created_at_ms(tuple.created_at_ms)
-        .sign_with(&seed.signer);
+        .created_at_ms(tuple.created_at_ms);
+    if let Some(tool_args_digest) = tuple.tool_args_digest {
+        proof = proof.tool_args_digest(tool_args_digest);
+    }
+    let proof = proof.sign_with(&seed.signer);
 
     Ok(PaymentPayload {
         accepted: accepted.clone(),
@@ -312,6 +348,7 @@ pub fn build_payment_payload(
             signer: seed.signer.signer_ref(),
             payment_subject: seed.payment_subject,
             approvals: seed.approvals,
+            parent_digests: Vec::new(),
         }),
     })
 }
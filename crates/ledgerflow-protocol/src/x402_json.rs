@@ -0,0 +1,423 @@
+//! x402 v2 JSON wire encoding (design §7.1).
+//!
+//! [`crate::x402`] models the 402 flow as plain Rust values; this module maps
+//! them to the JSON documents stock x402 v2 clients and facilitators
+//! exchange, and to the three standard headers:
+//!
+//! | Header | Document | Direction |
+//! |---|---|---|
+//! | `PAYMENT-REQUIRED` | [`X402PaymentRequired`] | merchant → client (402) |
+//! | `PAYMENT-SIGNATURE` | [`X402PaymentPayload`] | client → merchant (retry) |
+//! | `PAYMENT-RESPONSE` | [`X402SettlementResponse`] | merchant → client (200) |
+//!
+//! Header values are base64 of the JSON document; decoders also accept the
+//! URL-safe alphabet and missing padding.
+//!
+//! LedgerFlow occupies the `extensions.ledgerflow` slot. The 402 carries the
+//! [`LedgerFlowChallenge`] as `info` plus its JSON Schema as `schema`; the
+//! payment echoes `info` with the challenge id and the
+//! [`LedgerFlowAuthorizationExtension`] as base64url CBOR (the warrant chain
+//! stays binary, as in the MPP carrier). A payment identifier rides in the
+//! `payment-identifier` extension.
+//!
+//! x402's scheme `payload` is a JSON object; [`PaymentPayload`] keeps it as a
+//! string, so object payloads are carried as their compact JSON text and any
+//! other settlement payload as a JSON string.
+
+use std::collections::BTreeMap;
+
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::{Value, json};
+
+use crate::{
+    error::ProtocolError,
+    wire::{base64_decode_lenient, base64_encode, base64url_decode, base64url_encode},
+    x402::{
+        AcceptedQuote, LEDGERFLOW_EXTENSION_VERSION, LedgerFlowAuthorizationExtension,
+        LedgerFlowChallenge, PaymentPayload, PaymentRequiredResponse,
+    },
+};
+
+/// x402 protocol version emitted by this module.
+pub const X402_VERSION: u32 = 2;
+
+/// 402 response header carrying [`X402PaymentRequired`].
+pub const PAYMENT_REQUIRED_HEADER: &str = "payment-required";
+
+/// Request header carrying [`X402PaymentPayload`].
+pub const PAYMENT_SIGNATURE_HEADER: &str = "payment-signature";
+
+/// Response header carrying [`X402SettlementResponse`].
+pub const PAYMENT_RESPONSE_HEADER: &str = "payment-response";
+
+/// Extension key LedgerFlow occupies.
+pub const LEDGERFLOW_EXTENSION_KEY: &str = "ledgerflow";
+
+/// Extension key carrying the payment identifier (idempotency key).
+pub const PAYMENT_IDENTIFIER_EXTENSION_KEY: &str = "payment-identifier";
+
+// ---------------------------------------------------------------------------
+// Documents
+// ---------------------------------------------------------------------------
+
+/// The resource being paid for.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct X402ResourceInfo {
+    pub url: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+}
+
+/// One entry of `accepts[]` (and the `accepted` echo).
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct X402PaymentRequirements {
+    pub scheme: String,
+    /// Omitted when the quote names no network.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub network: Option<String>,
+    /// Decimal amount in the asset's base units.
+    pub max_amount_required: String,
+    pub asset: String,
+    pub pay_to: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_timeout_seconds: Option<u64>,
+    /// Scheme-specific data (e.g. the EIP-712 domain of an EIP-3009 token).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extra: Option<Value>,
+}
+
+impl From<&AcceptedQuote> for X402PaymentRequirements {
+    fn from(quote: &AcceptedQuote) -> Self {
+        Self {
+            scheme: quote.scheme.clone(),
+            network: quote.network.clone(),
+            max_amount_required: quote.amount.to_string(),
+            asset: quote.asset.clone(),
+            pay_to: quote.payee_id.clone(),
+            max_timeout_seconds: None,
+            extra: None,
+        }
+    }
+}
+
+impl TryFrom<&X402PaymentRequirements> for AcceptedQuote {
+    type Error = ProtocolError;
+
+    fn try_from(requirements: &X402PaymentRequirements) -> Result<Self, Self::Error> {
+        let amount = requirements.max_amount_required.parse::<u128>().map_err(|_| {
+            ProtocolError::Json("maxAmountRequired must be a decimal integer".to_string())
+        })?;
+        Ok(Self {
+            scheme: requirements.scheme.clone(),
+            asset: requirements.asset.clone(),
+            amount,
+            payee_id: requirements.pay_to.clone(),
+            network: requirements.network.clone().filter(|network| !network.is_empty()),
+        })
+    }
+}
+
+/// The x402 v2 `PaymentRequired` document.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct X402PaymentRequired {
+    pub x402_version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource: Option<X402ResourceInfo>,
+    pub accepts: Vec<X402PaymentRequirements>,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub extensions: BTreeMap<String, Value>,
+}
+
+impl X402PaymentRequired {
+    /// Maps a 402 response; its LedgerFlow challenge becomes
+    /// `extensions.ledgerflow` and sets the resource and timeout.
+    pub fn from_response(response: &PaymentRequiredResponse) -> Result<Self, ProtocolError> {
+        let challenge = response.ledgerflow.as_ref();
+        // Rounded up, so a sub-second TTL never advertises a zero timeout.
+        let max_timeout_seconds = challenge
+            .map(|challenge| challenge.challenge_ttl_ms.div_ceil(1_000))
+            .filter(|seconds| *seconds > 0);
+        let accepts = response
+            .accepted
+            .iter()
+            .map(|quote| X402PaymentRequirements {
+                max_timeout_seconds,
+                ..X402PaymentRequirements::from(quote)
+            })
+            .collect();
+        let mut extensions = BTreeMap::new();
+        if let Some(challenge) = challenge {
+            extensions.insert(
+                LEDGERFLOW_EXTENSION_KEY.to_string(),
+                json!({ "info": to_value(challenge)?, "schema": ledgerflow_challenge_schema() }),
+            );
+        }
+        Ok(Self {
+            x402_version: X402_VERSION,
+            error: None,
+            resource: challenge.map(|challenge| X402ResourceInfo {
+                url: challenge.resource.clone(),
+                description: None,
+                mime_type: None,
+            }),
+            accepts,
+            extensions,
+        })
+    }
+
+    /// Sets the `error` member (why a presented payment was refused).
+    #[must_use]
+    pub fn with_error(mut self, error: impl Into<String>) -> Self {
+        self.error = Some(error.into());
+        self
+    }
+
+    /// The quotes in `accepts[]`.
+    pub fn accepted_quotes(&self) -> Result<Vec<AcceptedQuote>, ProtocolError> {
+        self.accepts.iter().map(AcceptedQuote::try_from).collect()
+    }
+
+    /// The LedgerFlow challenge in `extensions.ledgerflow.info`, if any.
+    pub fn ledgerflow_challenge(&self) -> Result<Option<LedgerFlowChallenge>, ProtocolError> {
+        self.extensions
+            .get(LEDGERFLOW_EXTENSION_KEY)
+            .map(|extension| from_value(extension.get("info").cloned().unwrap_or_default()))
+            .transpose()
+    }
+
+    /// Maps back to a 402 response carrying this document in the
+    /// `PAYMENT-REQUIRED` header.
+    pub fn to_response(&self) -> Result<PaymentRequiredResponse, ProtocolError> {
+        Ok(PaymentRequiredResponse {
+            status_code: 402,
+            headers: vec![
+                ("content-type".to_string(), "application/json".to_string()),
+                (PAYMENT_REQUIRED_HEADER.to_string(), self.encode_header()?),
+            ],
+            accepted: self.accepted_quotes()?,
+            ledgerflow: self.ledgerflow_challenge()?,
+        })
+    }
+
+    /// Encodes the `PAYMENT-REQUIRED` header value.
+    pub fn encode_header(&self) -> Result<String, ProtocolError> {
+        encode_header(self)
+    }
+
+    /// Decodes a `PAYMENT-REQUIRED` header value.
+    pub fn decode_header(value: &str) -> Result<Self, ProtocolError> {
+        decode_header(value)
+    }
+}
+
+/// `extensions.ledgerflow.info` of a payment.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+struct LedgerFlowPaymentInfo {
+    version: String,
+    challenge_id: String,
+    /// base64url(CBOR [`LedgerFlowAuthorizationExtension`]).
+    authorization: String,
+}
+
+/// The x402 v2 `PaymentPayload` document.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct X402PaymentPayload {
+    pub x402_version: u32,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resource: Option<X402ResourceInfo>,
+    pub accepted: X402PaymentRequirements,
+    /// Scheme-specific payment data.
+    pub payload: Value,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub extensions: BTreeMap<String, Value>,
+}
+
+impl X402PaymentPayload {
+    /// Maps a payment payload.
+    pub fn from_payload(payload: &PaymentPayload) -> Result<Self, ProtocolError> {
+        let mut extensions = BTreeMap::new();
+        if let Some(extension) = &payload.ledgerflow {
+            let info = LedgerFlowPaymentInfo {
+                version: extension.version.clone(),
+                challenge_id: extension.challenge_id.clone(),
+                authorization: base64url_encode(&extension.encode_cbor()?),
+            };
+            extensions
+                .insert(LEDGERFLOW_EXTENSION_KEY.to_string(), json!({ "info": to_value(&info)? }));
+        }
+        if let Some(payment_identifier) = &payload.payment_identifier {
+            extensions.insert(
+                PAYMENT_IDENTIFIER_EXTENSION_KEY.to_string(),
+                json!({ "info": { "id": payment_identifier } }),
+            );
+        }
+        let settlement = match serde_json::from_str::<Value>(&payload.settlement_payload) {
+            Ok(object @ Value::Object(_)) => object,
+            _ => Value::String(payload.settlement_payload.clone()),
+        };
+        Ok(Self {
+            x402_version: X402_VERSION,
+            resource: None,
+            accepted: X402PaymentRequirements::from(&payload.accepted),
+            payload: settlement,
+            extensions,
+        })
+    }
+
+    /// Maps back to a payment payload. A payment without
+    /// `extensions.ledgerflow` (a stock x402 client) maps to `ledgerflow:
+    /// None`.
+    pub fn to_payload(&self) -> Result<PaymentPayload, ProtocolError> {
+        let ledgerflow = self
+            .extensions
+            .get(LEDGERFLOW_EXTENSION_KEY)
+            .map(|extension| {
+                let info: LedgerFlowPaymentInfo =
+                    from_value(extension.get("info").cloned().unwrap_or_default())?;
+                let authorization = LedgerFlowAuthorizationExtension::decode_cbor(
+                    &base64url_decode(&info.authorization)?,
+                )?;
+                if authorization.challenge_id != info.challenge_id ||
+                    authorization.version != info.version
+                {
+                    return Err(ProtocolError::Json(
+                        "extensions.ledgerflow.info does not match its authorization".to_string(),
+                    ));
+                }
+                Ok(authorization)
+            })
+            .transpose()?;
+        let payment_identifier = self
+            .extensions
+            .get(PAYMENT_IDENTIFIER_EXTENSION_KEY)
+            .map(|extension| {
+                extension
+                    .pointer("/info/id")
+                    .and_then(Value::as_str)
+                    .map(str::to_string)
+                    .ok_or_else(|| {
+                        ProtocolError::Json("payment-identifier.info.id must be a string".into())
+                    })
+            })
+            .transpose()?;
+        let settlement_payload = match &self.payload {
+            Value::String(payload) => payload.clone(),
+            other => other.to_string(),
+        };
+        Ok(PaymentPayload {
+            accepted: AcceptedQuote::try_from(&self.accepted)?,
+            settlement_payload,
+            payment_identifier,
+            ledgerflow,
+        })
+    }
+
+    /// Encodes the `PAYMENT-SIGNATURE` header value.
+    pub fn encode_header(&self) -> Result<String, ProtocolError> {
+        encode_header(self)
+    }
+
+    /// Decodes a `PAYMENT-SIGNATURE` header value.
+    pub fn decode_header(value: &str) -> Result<Self, ProtocolError> {
+        decode_header(value)
+    }
+}
+
+/// The x402 v2 `SettlementResponse` document.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct X402SettlementResponse {
+    pub success: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_reason: Option<String>,
+    /// Settlement reference on the rail (a transaction hash on chain).
+    #[serde(default)]
+    pub transaction: String,
+    #[serde(default)]
+    pub network: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub payer: Option<String>,
+}
+
+impl X402SettlementResponse {
+    /// Encodes the `PAYMENT-RESPONSE` header value.
+    pub fn encode_header(&self) -> Result<String, ProtocolError> {
+        encode_header(self)
+    }
+
+    /// Decodes a `PAYMENT-RESPONSE` header value.
+    pub fn decode_header(value: &str) -> Result<Self, ProtocolError> {
+        decode_header(value)
+    }
+}
+
+/// Encodes a payment payload as a `PAYMENT-SIGNATURE` header value.
+pub fn encode_payment_signature(payload: &PaymentPayload) -> Result<String, ProtocolError> {
+    X402PaymentPayload::from_payload(payload)?.encode_header()
+}
+
+/// Decodes a `PAYMENT-SIGNATURE` header value into a payment payload.
+pub fn decode_payment_signature(value: &str) -> Result<PaymentPayload, ProtocolError> {
+    X402PaymentPayload::decode_header(value)?.to_payload()
+}
+
+// ---------------------------------------------------------------------------
+// Schema
+// ---------------------------------------------------------------------------
+
+/// JSON Schema of `extensions.ledgerflow.info` in a 402 (a serialized
+/// [`LedgerFlowChallenge`]).
+#[must_use]
+pub fn ledgerflow_challenge_schema() -> Value {
+    json!({
+        "$schema": "https://json-schema.org/draft/2020-12/schema",
+        "type": "object",
+        "required": [
+            "version", "challenge_id", "merchant_id", "resource", "proof_freshness_ms",
+            "clock_skew_ms", "challenge_ttl_ms", "required_subject_kinds"
+        ],
+        "properties": {
+            "version": { "const": LEDGERFLOW_EXTENSION_VERSION },
+            "challenge_id": { "type": "string" },
+            "merchant_id": { "type": "string" },
+            "resource": { "type": "string" },
+            "proof_freshness_ms": { "type": "integer", "minimum": 0 },
+            "clock_skew_ms": { "type": "integer", "minimum": 0 },
+            "challenge_ttl_ms": { "type": "integer", "minimum": 0 },
+            "required_subject_kinds": { "type": "array", "items": { "type": "string" } },
+            "ledger": { "type": ["string", "null"] },
+            "human_present": { "type": "boolean" }
+        }
+    })
+}
+
+// ---------------------------------------------------------------------------
+// Helpers
+// ---------------------------------------------------------------------------
+
+fn to_value<T: Serialize>(value: &T) -> Result<Value, ProtocolError> {
+    serde_json::to_value(value).map_err(|error| ProtocolError::Json(error.to_string()))
+}
+
+fn from_value<T: DeserializeOwned>(value: Value) -> Result<T, ProtocolError> {
+    serde_json::from_value(value).map_err(|error| ProtocolError::Json(error.to_string()))
+}
+
+fn encode_header<T: Serialize>(document: &T) -> Result<String, ProtocolError> {
+    serde_json::to_vec(document)
+        .map(|bytes| base64_encode(&bytes))
+        .map_err(|error| ProtocolError::Json(error.to_string()))
+}
+
+fn decode_header<T: DeserializeOwned>(value: &str) -> Result<T, ProtocolError> {
+    serde_json::from_slice(&base64_decode_lenient(value)?)
+        .map_err(|error| ProtocolError::Json(error.to_string()))
+}
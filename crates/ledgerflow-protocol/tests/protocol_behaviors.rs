@@ -3,16 +3,18 @@
 #![allow(clippy::expect_used)]
 
 use ledgerflow_core::{
-    AssetRef, InMemoryRevocationCheck, MerchantConstraint, PaymentConstraint, PaymentRail,
-    PaymentSubjectKind, PaymentSubjectRef, SigningKeyPair, TrustedIssuer, TrustedIssuers, Warrant,
-    WarrantBuilder, WarrantChain,
+    ApprovalGate, AssetRef, DelegatedWarrantBuilder, InMemoryRevocationCheck, MerchantConstraint,
+    PaymentConstraint, PaymentRail, PaymentSubjectKind, PaymentSubjectRef, SigningKeyPair,
+    TrustedIssuer, TrustedIssuers, Warrant, WarrantBuilder, WarrantChain,
 };
 use ledgerflow_protocol::{
     AcceptedQuote, HttpRequest, InMemoryReplayStore, InMemoryWarrantRepository,
-    LedgerFlowAuthorizationExtension, LedgerFlowCarrier, LedgerFlowChallenge, MerchantVerifier,
-    PaymentPayloadSeed, SlimAuthorization, build_payment_payload, canonical_accepted_hash,
-    canonical_request_hash, decode_authorization_param, decode_challenge_param,
-    encode_authorization_param, encode_challenge_param, merchant_payment_required,
+    LedgerFlowAuthorizationExtension, LedgerFlowCarrier, LedgerFlowChallenge, McpToolCall,
+    MerchantVerificationError, MerchantVerifier, PaymentPayloadSeed, SlimAuthorization,
+    VerificationContextBuilder, WarrantRepository, build_payment_payload, build_tool_call_payment,
+    canonical_accepted_hash, canonical_request_hash, decode_authorization_param,
+    decode_challenge_param, encode_authorization_param, encode_challenge_param,
+    merchant_payment_required,
 };
 
 fn issuer_keys() -> SigningKeyPair {
@@ -105,6 +107,23 @@ fn challenge_cbor_round_trip_preserves_fields() {
     assert_eq!(decoded.challenge_id, "challenge-1");
 }
 
+#[test]
+fn challenge_advertises_accounting_point_ledger() {
+    let response = merchant_payment_required(
+        "challenge-1",
+        "merchant-a",
+        "/pay",
+        vec![AcceptedQuote::exact("USDC", 100, "merchant-a", Some("base".to_string()))],
+        60_000,
+    );
+    assert_eq!(response.ledgerflow.as_ref().and_then(|c| c.ledger.as_deref()), None);
+
+    let challenge = response.with_ledger("https://ledger.example").ledgerflow.expect("challenge");
+    let decoded = LedgerFlowChallenge::decode_cbor(&challenge.encode_cbor().expect("encode"))
+        .expect("decode");
+    assert_eq!(decoded.ledger.as_deref(), Some("https://ledger.example"));
+}
+
 #[test]
 fn authorization_extension_cbor_round_trip_preserves_fields() {
     let accepted = AcceptedQuote::exact("USDC", 100, "merchant-a", Some("base".to_string()));
@@ -397,6 +416,159 @@ fn mpp_authorization_param_round_trips_as_slim_payload() {
     assert_eq!(slim.payment_subject, subject_ref());
 }
 
+#[test]
+fn merchant_verifier_resolves_digest_referenced_parents() {
+    let delegate = SigningKeyPair::from_bytes(&[63u8; 32]);
+    let child = DelegatedWarrantBuilder::from(root_warrant()).issue_to(
+        delegate.signer_ref(),
+        &holder_keys(),
+        2_000,
+        [0_u8; 8],
+    );
+    let payload = build_payment_payload(
+        &challenge(),
+        &request(),
+        AcceptedQuote::exact("USDC", 100, "merchant-a", Some("base".to_string())),
+        WarrantChain { warrants: vec![root_warrant(), child.clone()] },
+        PaymentPayloadSeed { signer: delegate, payment_identifier: None, ..seed() },
+    )
+    .expect("build");
+    let extension = payload.ledgerflow.clone().expect("extension");
+
+    // The header-slim form carries the leaf and the parent digests.
+    let slim = decode_authorization_param(
+        &encode_authorization_param(
+            &extension.warrant_chain,
+            &extension.proof,
+            &extension.signer,
+            &extension.payment_subject,
+            &extension.approvals,
+        )
+        .expect("encode"),
+    )
+    .expect("decode");
+    assert_eq!(slim.parent_digests, vec![root_warrant().digest()]);
+    let referenced = slim.into_referenced_extension();
+    assert_eq!(referenced, extension.into_digest_referenced());
+    assert_eq!(referenced.warrant_chain, vec![child]);
+    let payload = ledgerflow_protocol::PaymentPayload { ledgerflow: Some(referenced), ..payload };
+
+    let verify = |repository: InMemoryWarrantRepository| {
+        MerchantVerifier::new(
+            InMemoryReplayStore::default(),
+            repository,
+            InMemoryRevocationCheck::new(),
+        )
+        .verify_payment(
+            &challenge(),
+            &request(),
+            &payload,
+            &trusted(),
+            "web-search",
+            &tool_arguments(),
+            2_000,
+        )
+    };
+    let error = verify(InMemoryWarrantRepository::default()).expect_err("unknown parent");
+    assert!(matches!(error, MerchantVerificationError::UnknownWarrantDigest { .. }));
+
+    let mut repository = InMemoryWarrantRepository::default();
+    repository.store(root_warrant());
+    let outcome = verify(repository).expect("verified");
+    assert_eq!(outcome.authorization.chain_len, 2);
+}
+
+fn mcp_warrant() -> Warrant {
+    let issuer = issuer_keys();
+    WarrantBuilder::new(2_000)
+        .warrant_id(*b"mcp-000000000000")
+        .ttl_secs(60)
+        .max_depth(1)
+        .issuer(issuer.signer_ref())
+        .holder(holder_keys().signer_ref())
+        .merchant(MerchantConstraint::with_ids(vec!["merchant-a".to_string()]))
+        .resource(ledgerflow_core::ResourceConstraint {
+            http_methods: vec![ledgerflow_protocol::MCP_METHOD.to_string()],
+            path_prefixes: vec!["/tools/".to_string()],
+        })
+        .payment(
+            PaymentConstraint::new(1_000)
+                .with_asset(AssetRef::new("USDC", Some("base".to_string())))
+                .with_rails(vec![PaymentRail::Onchain])
+                .with_schemes(vec!["exact".to_string()]),
+        )
+        .approval_gate(
+            "transfer",
+            ApprovalGate {
+                argument_constraints: [("tier".to_string(), "high".to_string())].into(),
+            },
+        )
+        .approver(SigningKeyPair::from_bytes(&[64u8; 32]).signer_ref())
+        .sign_with(&issuer, [0_u8; 8])
+}
+
+#[test]
+fn merchant_verifier_enforces_warrants_per_mcp_tool_call() {
+    let paid_call = |arguments: serde_json::Value, nonce: &str| {
+        let call = McpToolCall::from_params(&serde_json::json!({
+            "name": "transfer",
+            "arguments": arguments,
+        }))
+        .expect("params");
+        let payment = build_tool_call_payment(
+            &challenge(),
+            "tools.example",
+            &call,
+            AcceptedQuote::exact("USDC", 100, "merchant-a", Some("base".to_string())),
+            WarrantChain::single(mcp_warrant()),
+            PaymentPayloadSeed { nonce: nonce.to_string(), payment_identifier: None, ..seed() },
+        )
+        .expect("build");
+        call.with_payment(&payment).expect("attach")
+    };
+    let mut verifier = MerchantVerifier::new(
+        InMemoryReplayStore::default(),
+        InMemoryWarrantRepository::default(),
+        InMemoryRevocationCheck::new(),
+    );
+    let mut verify = |call: &McpToolCall| {
+        verifier.verify_tool_call(
+            &challenge(),
+            "tools.example",
+            call,
+            &trusted(),
+            &VerificationContextBuilder::new(),
+            2_000,
+        )
+    };
+
+    let call = paid_call(serde_json::json!({ "to": "bob", "tier": "low", "amount": 5 }), "n-1");
+    let round_tripped = McpToolCall::from_params(&call.to_params()).expect("round trip");
+    let outcome = verify(&round_tripped).expect("verified");
+    assert_eq!(outcome.authorization.merchant_id, "merchant-a");
+
+    // Arguments swapped after signing no longer match the PoP.
+    let mut tampered = paid_call(serde_json::json!({ "to": "bob", "tier": "low" }), "n-2");
+    tampered.arguments.insert("to".to_string(), "mallory".into());
+    let error = verify(&tampered).expect_err("tampered");
+    assert!(matches!(error, MerchantVerificationError::ToolArgumentsMismatch));
+
+    // The gate fires on the call's own arguments.
+    let gated = paid_call(serde_json::json!({ "to": "bob", "tier": "high" }), "n-3");
+    let error = verify(&gated).expect_err("approval required");
+    assert!(
+        matches!(
+            error,
+            MerchantVerificationError::Core(ledgerflow_core::AuthorizationError::ApprovalRequired)
+        ),
+        "{error:?}"
+    );
+
+    let unpaid = McpToolCall::new("transfer", serde_json::Map::new());
+    let error = verify(&unpaid).expect_err("unpaid");
+    assert!(matches!(error, MerchantVerificationError::MissingLedgerFlowExtension));
+}
+
 #[test]
 fn carrier_size_policy_rejects_oversized_header_payloads() {
     let error = LedgerFlowCarrier::HttpHeader
@@ -0,0 +1,169 @@
+//! x402 v2 JSON wire corpus: every document in `tests/corpus/x402` must
+//! survive JSON and header round trips unchanged, and LedgerFlow values must
+//! map to and from the documents losslessly.
+
+#![allow(clippy::expect_used)]
+
+use std::{collections::BTreeMap, fs, path::PathBuf};
+
+use ledgerflow_core::{
+    MerchantConstraint, PaymentConstraint, PaymentSubjectKind, PaymentSubjectRef, SigningKeyPair,
+    WarrantBuilder, WarrantChain,
+};
+use ledgerflow_protocol::{
+    AcceptedQuote, HttpRequest, PAYMENT_REQUIRED_HEADER, PaymentPayloadSeed, X402PaymentPayload,
+    X402PaymentRequired, X402SettlementResponse, build_payment_payload, decode_payment_signature,
+    encode_payment_signature, merchant_payment_required,
+};
+use serde::{Serialize, de::DeserializeOwned};
+use serde_json::Value;
+
+fn corpus(name: &str) -> Value {
+    let path = PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests/corpus/x402").join(name);
+    serde_json::from_str(&fs::read_to_string(path).expect("corpus file")).expect("corpus json")
+}
+
+/// Parses `name` as `T`, checks it re-serializes to the same JSON and that
+/// `encode`/`decode` (the header codec) round-trip it.
+fn assert_round_trips<T>(name: &str, encode: impl Fn(&T) -> String, decode: impl Fn(&str) -> T) -> T
+where
+    T: Serialize + DeserializeOwned + PartialEq + std::fmt::Debug,
+{
+    let json = corpus(name);
+    let document: T = serde_json::from_value(json.clone()).expect(name);
+    assert_eq!(serde_json::to_value(&document).expect("serialize"), json, "{name}");
+    assert_eq!(decode(&encode(&document)), document, "{name} header");
+    document
+}
+
+fn quote() -> AcceptedQuote {
+    AcceptedQuote::exact("USDC", 100, "merchant-a", Some("eip155:8453".to_string()))
+}
+
+#[test]
+fn stock_documents_round_trip() {
+    let required = assert_round_trips(
+        "payment_required_stock.json",
+        |document: &X402PaymentRequired| document.encode_header().expect("encode"),
+        |value| X402PaymentRequired::decode_header(value).expect("decode"),
+    );
+    assert_eq!(required.ledgerflow_challenge().expect("challenge"), None);
+    let accepted = required.accepted_quotes().expect("quotes");
+    assert_eq!(accepted[0].amount, 10_000);
+    assert_eq!(accepted[0].payee_id, "0x209693Bc6afc0C5328bA36FaF03C514EF312287C");
+
+    let payment = assert_round_trips(
+        "payment_payload_stock.json",
+        |document: &X402PaymentPayload| document.encode_header().expect("encode"),
+        |value| X402PaymentPayload::decode_header(value).expect("decode"),
+    );
+    let payload = payment.to_payload().expect("payload");
+    assert!(payload.ledgerflow.is_none());
+    assert_eq!(
+        serde_json::from_str::<Value>(&payload.settlement_payload).expect("json"),
+        payment.payload
+    );
+    assert_eq!(X402PaymentPayload::from_payload(&payload).expect("map").payload, payment.payload);
+
+    for name in ["settlement_response_success.json", "settlement_response_failure.json"] {
+        assert_round_trips(
+            name,
+            |document: &X402SettlementResponse| document.encode_header().expect("encode"),
+            |value| X402SettlementResponse::decode_header(value).expect("decode"),
+        );
+    }
+}
+
+#[test]
+fn ledgerflow_payment_required_matches_the_corpus() {
+    let response = merchant_payment_required(
+        "challenge-1",
+        "merchant-a",
+        "https://merchant-a.example/search",
+        vec![quote()],
+        60_000,
+    )
+    .with_ledger("facilitator-1");
+    let document = X402PaymentRequired::from_response(&response).expect("map");
+    assert_eq!(
+        serde_json::to_value(&document).expect("json"),
+        corpus("payment_required_ledgerflow.json")
+    );
+
+    let decoded = X402PaymentRequired::decode_header(&document.encode_header().expect("encode"))
+        .expect("decode")
+        .to_response()
+        .expect("response");
+    assert_eq!(decoded.accepted, response.accepted);
+    assert_eq!(decoded.ledgerflow, response.ledgerflow);
+    assert!(decoded.headers.iter().any(|(name, _)| name == PAYMENT_REQUIRED_HEADER));
+}
+
+#[test]
+fn networkless_quotes_and_short_ttls_map_without_placeholders() {
+    let mut response = merchant_payment_required(
+        "challenge-1",
+        "merchant-a",
+        "/search",
+        vec![AcceptedQuote::exact("USDC", 100, "merchant-a", None)],
+        60_000,
+    );
+    response.ledgerflow.as_mut().expect("challenge").challenge_ttl_ms = 1_500;
+    let document = X402PaymentRequired::from_response(&response).expect("map");
+    let accepts = &serde_json::to_value(&document).expect("json")["accepts"][0];
+    assert!(accepts.get("network").is_none(), "{accepts}");
+    assert_eq!(accepts["maxTimeoutSeconds"], 2);
+    assert_eq!(document.accepted_quotes().expect("quotes"), response.accepted);
+}
+
+#[test]
+fn ledgerflow_payment_survives_the_payment_signature_header() {
+    let issuer = SigningKeyPair::from_bytes(&[71_u8; 32]);
+    let holder = SigningKeyPair::from_bytes(&[72_u8; 32]);
+    let warrant = WarrantBuilder::new(2_000)
+        .ttl_secs(60)
+        .issuer(issuer.signer_ref())
+        .holder(holder.signer_ref())
+        .merchant(MerchantConstraint::with_ids(vec!["merchant-a".to_string()]))
+        .payment(PaymentConstraint::new(1_000))
+        .sign_with(&issuer, [0_u8; 8]);
+    let challenge =
+        merchant_payment_required("challenge-1", "merchant-a", "/search", vec![], 60_000)
+            .ledgerflow
+            .expect("challenge");
+    let payload = build_payment_payload(
+        &challenge,
+        &HttpRequest::new("POST", "merchant-a.example", "/search", b"{}".to_vec()),
+        quote(),
+        WarrantChain::single(warrant),
+        PaymentPayloadSeed {
+            payment_subject: PaymentSubjectRef::new(
+                PaymentSubjectKind::Caip10,
+                "caip10:eip155:8453:0xabc123",
+            ),
+            signer: holder,
+            created_at_ms: 2_000,
+            nonce: "nonce-1".to_string(),
+            payment_identifier: Some("pay-1".to_string()),
+            tool_args: BTreeMap::new(),
+            approvals: Vec::new(),
+        },
+    )
+    .expect("payload");
+
+    let header = encode_payment_signature(&payload).expect("encode");
+    assert_eq!(decode_payment_signature(&header).expect("decode"), payload);
+
+    let document = X402PaymentPayload::decode_header(&header).expect("document");
+    assert_eq!(document.x402_version, 2);
+    assert_eq!(document.accepted.max_amount_required, "100");
+    assert_eq!(document.accepted.pay_to, "merchant-a");
+    assert_eq!(document.extensions["ledgerflow"]["info"]["challenge_id"], "challenge-1");
+    assert_eq!(document.extensions["payment-identifier"]["info"]["id"], "pay-1");
+
+    // The echoed info must agree with the authorization it carries.
+    let mut tampered = document;
+    tampered.extensions.get_mut("ledgerflow").expect("ledgerflow")["info"]["challenge_id"] =
+        Value::from("challenge-2");
+    assert!(tampered.to_payload().is_err());
+}
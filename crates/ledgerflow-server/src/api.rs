@@ -3,10 +3,22 @@
 //! Endpoints (v1):
 //!
 //! - `GET  /healthz` — liveness.
-//! - `POST /v1/warrants` — issue a root warrant (demo issuer).
-//! - `POST /v1/revocations` — revoke a warrant or holder.
+//! - `POST /v1/warrants` — issue a root warrant (see [`crate::issuance`]).
+//! - `GET  /v1/warrants/{digest}` — resolve a stored warrant (digest-referenced chains).
+//! - `POST /v1/revocations` — revoke a warrant, holder, payment subject, issuer key or agent id.
+//! - `GET  /v1/srl` — signed revocation list, full or delta (see [`crate::srl`]).
 //! - `GET  /v1/settlements/{transaction_id}` — idempotent settlement query.
 //! - `GET  /v1/audit` — buffered webhook/audit events.
+//! - `POST /v1/verify` / `POST /v1/settle` / `GET /v1/status` — hosted facilitator (see
+//!   [`crate::facilitator`]).
+//! - `GET|POST /v1/admin/issuer-keys`, `POST /v1/admin/issuer-keys/rotate` — per-tenant issuer keys
+//!   (see [`crate::tenant_keys`]).
+//! - `GET|PUT /v1/admin/trust-anchors`, `POST /v1/admin/trust-anchors/reload` — hot-swappable
+//!   process-wide trust anchors (see [`crate::trust_anchors`]).
+//! - `GET /v1/approvals`, `GET|POST /v1/approvals/{request_hash}`, `GET
+//!   /v1/approvals/{request_hash}/wait` — human approval inbox (see [`crate::approvals`]).
+
+use std::sync::PoisonError;
 
 use axum::{
     Json, Router,
@@ -15,6 +27,7 @@ use axum::{
     response::{IntoResponse, Response},
     routing::{get, post},
 };
+use ledgerflow_core::hex_encode_bytes;
 use serde::{Deserialize, Serialize};
 use utoipa::{OpenApi, ToSchema};
 
@@ -23,8 +36,56 @@ use crate::{state::AppState, webhook::WebhookEvent};
 /// OpenAPI document for the LedgerFlow server REST API (design §10.3).
 #[derive(OpenApi)]
 #[openapi(
-    paths(health, issue_warrant, revoke, query_settlement, audit),
-    components(schemas(IssueWarrantRequest, IssueWarrantResponse, RevokeRequest)),
+    paths(
+        health,
+        crate::issuance::issue_warrant,
+        crate::issuance::lookup_warrant,
+        revoke,
+        crate::srl::publish_srl,
+        query_settlement,
+        audit,
+        crate::facilitator::verify,
+        crate::facilitator::settle,
+        crate::facilitator::status,
+        crate::tenant_keys::list_keys,
+        crate::tenant_keys::create_key,
+        crate::tenant_keys::rotate_key,
+        crate::trust_anchors::list_anchors,
+        crate::trust_anchors::replace_anchors,
+        crate::trust_anchors::reload_anchors,
+        crate::approvals::list_approvals,
+        crate::approvals::get_approval,
+        crate::approvals::submit_approval,
+        crate::approvals::wait_approval
+    ),
+    components(schemas(
+        crate::issuance::IssueWarrantRequest,
+        crate::issuance::MerchantBody,
+        crate::issuance::ResourceBody,
+        crate::issuance::AssetBody,
+        crate::issuance::PaymentBody,
+        crate::issuance::ToolBody,
+        crate::issuance::ApprovalGateBody,
+        crate::issuance::SignerBody,
+        crate::issuance::ExtensionsBody,
+        crate::issuance::IssueBoundsBody,
+        crate::issuance::BudgetBody,
+        crate::issuance::IssueWarrantResponse,
+        RevokeRequest,
+        crate::srl::SrlResponse,
+        crate::facilitator::FacilitatorRequest,
+        crate::facilitator::PaymentPayloadBody,
+        crate::facilitator::AcceptedQuoteBody,
+        crate::facilitator::RequestContextBody,
+        crate::facilitator::VerifyResponse,
+        crate::facilitator::SettleResponse,
+        crate::facilitator::SettlementView,
+        crate::tenant_keys::TenantKeyInfo,
+        crate::trust_anchors::TrustAnchorBody,
+        crate::trust_anchors::TrustAnchorsRequest,
+        crate::approvals::ApprovalBody,
+        crate::approvals::ApprovalRequestView
+    )),
     info(
         title = "LedgerFlow Server API",
         version = "0.1.0",
@@ -37,10 +98,31 @@ pub struct ApiDoc;
 pub fn router() -> Router<AppState> {
     Router::new()
         .route("/healthz", get(health))
-        .route("/v1/warrants", post(issue_warrant))
+        .route("/v1/warrants", post(crate::issuance::issue_warrant))
+        .route("/v1/warrants/{digest}", get(crate::issuance::lookup_warrant))
         .route("/v1/revocations", post(revoke))
+        .route("/v1/srl", get(crate::srl::publish_srl))
         .route("/v1/settlements/{transaction_id}", get(query_settlement))
         .route("/v1/audit", get(audit))
+        .route("/v1/verify", post(crate::facilitator::verify))
+        .route("/v1/settle", post(crate::facilitator::settle))
+        .route("/v1/status", get(crate::facilitator::status))
+        .route(
+            "/v1/admin/issuer-keys",
+            get(crate::tenant_keys::list_keys).post(crate::tenant_keys::create_key),
+        )
+        .route("/v1/admin/issuer-keys/rotate", post(crate::tenant_keys::rotate_key))
+        .route(
+            "/v1/admin/trust-anchors",
+            get(crate::trust_anchors::list_anchors).put(crate::trust_anchors::replace_anchors),
+        )
+        .route("/v1/admin/trust-anchors/reload", post(crate::trust_anchors::reload_anchors))
+        .route("/v1/approvals", get(crate::approvals::list_approvals))
+        .route(
+            "/v1/approvals/{request_hash}",
+            get(crate::approvals::get_approval).post(crate::approvals::submit_approval),
+        )
+        .route("/v1/approvals/{request_hash}/wait", get(crate::approvals::wait_approval))
         .merge(
             utoipa_swagger_ui::SwaggerUi::new("/swagger-ui")
                 .url("/openapi.json", ApiDoc::openapi()),
@@ -104,85 +186,35 @@ async fn health() -> Json<ApiResponse<String>> {
     Json(ApiResponse::ok("ok".to_string()))
 }
 
-/// Issue-warrant request body.
-#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
-pub struct IssueWarrantRequest {
-    pub holder_public_key: String,
-    pub merchant_id: String,
-    pub amount_cap: u128,
-    pub ttl_secs: Option<u64>,
-}
-
-/// Issue-warrant response body.
-#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
-pub struct IssueWarrantResponse {
-    pub warrant_id: String,
-    pub digest: String,
-    pub expires_at: u64,
-}
-
-/// Issues a root warrant for a holder.
-#[utoipa::path(
-    post,
-    path = "/v1/warrants",
-    request_body = IssueWarrantRequest,
-    responses(
-        (status = 200, description = "Warrant issued", body = IssueWarrantResponse),
-        (status = 400, description = "Bad request"),
-        (status = 401, description = "Unauthorized")
-    )
-)]
-async fn issue_warrant(
-    State(state): State<AppState>,
-    ctx: crate::saas::SaaSContext,
-    Json(request): Json<IssueWarrantRequest>,
-) -> Result<Json<ApiResponse<IssueWarrantResponse>>, ApiError> {
-    let holder_key_bytes = decode_hex(&request.holder_public_key)
-        .ok_or_else(|| ApiError::BadRequest("holder_public_key must be 32-byte hex".to_string()))?;
-    let holder_key = ledgerflow_core::SigningKeyPair::from_bytes(&holder_key_bytes);
-    // Enforce a sane upper bound on the per-charge cap (fail-closed: a request
-    // for more than the configured ceiling is rejected rather than silently
-    // issued). Design §6.1 hard caps must be enforced server-side.
-    if request.amount_cap > MAX_PER_CHARGE_CAP {
-        return Err(ApiError::BadRequest(format!(
-            "amount_cap exceeds the maximum allowed ({MAX_PER_CHARGE_CAP})"
-        )));
-    }
-    let issuer_key = state.issuer_key.clone();
-    let now_ms = now_ms();
-    let warrant = ledgerflow_core::WarrantBuilder::new(now_ms)
-        .ttl_secs(request.ttl_secs.unwrap_or(ledgerflow_core::DEFAULT_WARRANT_TTL_SECS))
-        .max_depth(ledgerflow_core::DEFAULT_MAX_DEPTH)
-        .issuer(issuer_key.signer_ref())
-        .holder(holder_key.signer_ref())
-        .merchant(ledgerflow_core::MerchantConstraint::with_ids(vec![request.merchant_id]))
-        .resource(ledgerflow_core::ResourceConstraint {
-            http_methods: vec!["POST".to_string()],
-            path_prefixes: vec!["/pay".to_string()],
-        })
-        .payment(ledgerflow_core::PaymentConstraint::new(request.amount_cap))
-        .sign_with(&issuer_key, random_bytes());
-
-    let warrant_id = warrant.id_hex();
-    let digest = warrant.digest();
-    let expires_at = warrant.expires_at;
-    state.webhook.emit(WebhookEvent::WarrantIssued {
-        tenant_id: ctx.tenant_id,
-        warrant_id: warrant_id.clone(),
-    });
-    Ok(Json(ApiResponse::ok(IssueWarrantResponse { warrant_id, digest, expires_at })))
-}
-
 /// Revoke request body.
 #[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
 pub struct RevokeRequest {
     /// Hex-encoded 16-byte warrant id.
     pub warrant_id: Option<String>,
-    /// Hex-encoded 32-byte holder public key.
+    /// Hex-encoded holder public key.
     pub holder_public_key: Option<String>,
+    /// Algorithm of `holder_public_key` / `issuer_public_key` (default
+    /// `ed25519`), which fixes the expected key length.
+    #[serde(default)]
+    pub algorithm: Option<String>,
+    /// The revoked warrant's `expires_at` (Unix seconds), as a cross-check.
+    /// The recorded expiry, after which published revocation lists compact
+    /// the entry away, is always the stored warrant's; a value in the past
+    /// or one the stored warrant contradicts is rejected, and a warrant this
+    /// server does not hold is recorded without an expiry.
+    pub expires_at: Option<u64>,
+    /// Payment subject (payer account) to revoke.
+    #[schema(value_type = Option<Object>)]
+    pub payment_subject: Option<ledgerflow_core::PaymentSubjectRef>,
+    /// Hex-encoded issuer or sub-issuer public key; every chain with a node
+    /// it signed is rejected.
+    pub issuer_public_key: Option<String>,
+    /// `ledgerflow.agent_id` value to revoke.
+    pub agent_id: Option<String>,
 }
 
-/// Revokes a warrant or holder (tenant-scoped).
+/// Revokes a warrant, holder, payment subject, issuer key or agent id
+/// (tenant-scoped), emitting one webhook event per revocation.
 #[utoipa::path(
     post,
     path = "/v1/revocations",
@@ -204,9 +236,10 @@ async fn revoke(
     if let Some(warrant_id) = &request.warrant_id {
         let bytes: [u8; 16] = decode_hex(warrant_id)
             .ok_or_else(|| ApiError::BadRequest("warrant_id must be 16-byte hex".to_string()))?;
+        let expires_at = revoked_warrant_expiry(&state, &bytes, request.expires_at)?;
         state
             .revocation_store
-            .revoke_warrant_for(tenant, &bytes)
+            .revoke_warrant_for_until(tenant, &bytes, expires_at)
             .map_err(|error| ApiError::Internal(error.to_string()))?;
         state.webhook.emit(WebhookEvent::WarrantRevoked {
             tenant_id: tenant.clone(),
@@ -215,20 +248,83 @@ async fn revoke(
         return Ok(Json(ApiResponse::ok(format!("warrant {warrant_id} revoked"))));
     }
     if let Some(holder_key) = &request.holder_public_key {
-        let bytes: [u8; 32] = decode_hex(holder_key).ok_or_else(|| {
-            ApiError::BadRequest("holder_public_key must be 32-byte hex".to_string())
-        })?;
-        let holder = ledgerflow_core::SignerRef::new(
-            ledgerflow_core::SigningAlgorithm::Ed25519,
-            bytes.to_vec(),
-        );
+        let holder = crate::issuance::parse_signer(holder_key, request.algorithm.as_deref(), None)
+            .map_err(|error| ApiError::BadRequest(format!("holder_public_key: {error}")))?;
         state
             .revocation_store
             .revoke_holder_for(tenant, &holder)
             .map_err(|error| ApiError::Internal(error.to_string()))?;
+        state.webhook.emit(WebhookEvent::HolderRevoked {
+            tenant_id: tenant.clone(),
+            public_key: hex_encode_bytes(&holder.public_key),
+        });
         return Ok(Json(ApiResponse::ok(format!("holder {holder_key} revoked"))));
     }
-    Err(ApiError::BadRequest("provide warrant_id or holder_public_key".to_string()))
+    if let Some(subject) = &request.payment_subject {
+        state
+            .revocation_store
+            .revoke_subject_for(tenant, subject)
+            .map_err(|error| ApiError::Internal(error.to_string()))?;
+        state.webhook.emit(WebhookEvent::PaymentSubjectRevoked {
+            tenant_id: tenant.clone(),
+            payment_subject: subject.value.clone(),
+        });
+        return Ok(Json(ApiResponse::ok(format!("payment subject {} revoked", subject.value))));
+    }
+    if let Some(issuer_key) = &request.issuer_public_key {
+        let issuer = crate::issuance::parse_signer(issuer_key, request.algorithm.as_deref(), None)
+            .map_err(|error| ApiError::BadRequest(format!("issuer_public_key: {error}")))?;
+        state
+            .revocation_store
+            .revoke_issuer_for(tenant, &issuer)
+            .map_err(|error| ApiError::Internal(error.to_string()))?;
+        state.webhook.emit(WebhookEvent::IssuerRevoked {
+            tenant_id: tenant.clone(),
+            public_key: hex_encode_bytes(&issuer.public_key),
+        });
+        return Ok(Json(ApiResponse::ok(format!("issuer {issuer_key} revoked"))));
+    }
+    if let Some(agent_id) = &request.agent_id {
+        state
+            .revocation_store
+            .revoke_agent_for(tenant, agent_id)
+            .map_err(|error| ApiError::Internal(error.to_string()))?;
+        state.webhook.emit(WebhookEvent::AgentRevoked {
+            tenant_id: tenant.clone(),
+            agent_id: agent_id.clone(),
+        });
+        return Ok(Json(ApiResponse::ok(format!("agent {agent_id} revoked"))));
+    }
+    Err(ApiError::BadRequest(
+        "provide warrant_id, holder_public_key, payment_subject, issuer_public_key or agent_id"
+            .to_string(),
+    ))
+}
+
+/// The expiry recorded with a warrant revocation: the stored warrant's, so a
+/// caller cannot make published lists compact away a live warrant.
+fn revoked_warrant_expiry(
+    state: &AppState,
+    warrant_id: &[u8],
+    claimed: Option<u64>,
+) -> Result<Option<u64>, ApiError> {
+    if claimed.is_some_and(|expires_at| expires_at < now_ms() / 1_000) {
+        return Err(ApiError::BadRequest("expires_at is in the past".to_string()));
+    }
+    let stored = state
+        .warrants
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .load_by_id(warrant_id)
+        .map(|warrant| warrant.expires_at);
+    if let (Some(claimed), Some(stored)) = (claimed, stored) &&
+        claimed != stored
+    {
+        return Err(ApiError::BadRequest(format!(
+            "expires_at {claimed} does not match the warrant's {stored}"
+        )));
+    }
+    Ok(stored)
 }
 
 /// Queries an idempotent settlement by transaction id.
@@ -243,17 +339,14 @@ async fn revoke(
 )]
 async fn query_settlement(
     State(state): State<AppState>,
+    ctx: crate::saas::SaaSContext,
     axum::extract::Path(transaction_id): axum::extract::Path<String>,
 ) -> Result<Json<ApiResponse<serde_json::Value>>, ApiError> {
-    match state.registry.query(&transaction_id) {
+    match state.registry.query(&ctx.tenant_id, &transaction_id) {
         Some(entry) => {
             let value = serde_json::json!({
                 "transaction_id": entry.receipt.transaction_id,
-                "status": match entry.status {
-                    ledgerflow_facilitator::SettlementStatus::Settled => "settled",
-                    ledgerflow_facilitator::SettlementStatus::Pending => "pending",
-                    ledgerflow_facilitator::SettlementStatus::Failed => "failed",
-                },
+                "status": entry.status.as_str(),
                 "amount": entry.receipt.settled_amount,
                 "asset": entry.receipt.asset,
             });
@@ -287,6 +380,16 @@ async fn audit(
             WebhookEvent::WarrantRevoked { warrant_id, .. } => {
                 format!("warrant_revoked:{warrant_id}")
             }
+            WebhookEvent::HolderRevoked { public_key, .. } => {
+                format!("holder_revoked:{public_key}")
+            }
+            WebhookEvent::PaymentSubjectRevoked { payment_subject, .. } => {
+                format!("payment_subject_revoked:{payment_subject}")
+            }
+            WebhookEvent::IssuerRevoked { public_key, .. } => {
+                format!("issuer_revoked:{public_key}")
+            }
+            WebhookEvent::AgentRevoked { agent_id, .. } => format!("agent_revoked:{agent_id}"),
             WebhookEvent::PaymentSettled { transaction_id, amount, .. } => {
                 format!("payment_settled:{transaction_id}:{amount}")
             }
@@ -298,21 +401,12 @@ async fn audit(
     Json(ApiResponse::ok(events))
 }
 
-fn now_ms() -> u64 {
+pub(crate) fn now_ms() -> u64 {
     std::time::SystemTime::now()
         .duration_since(std::time::UNIX_EPOCH)
         .map_or(0, |d| d.as_millis() as u64)
 }
 
-/// Maximum per-charge cap the server will issue (base units). Requests above
-/// this ceiling are rejected (fail-closed). Tunable per deployment.
-const MAX_PER_CHARGE_CAP: u128 = u128::MAX / 1_000_000;
-
-/// Generates a cryptographically random nonce for warrant issuance.
-fn random_bytes() -> [u8; 8] {
-    rand::random()
-}
-
 /// Decodes a hex string into exactly `N` bytes.
 fn decode_hex<const N: usize>(hex: &str) -> Option<[u8; N]> {
     if hex.len() != N * 2 {
@@ -0,0 +1,730 @@
+//! Human-in-the-loop approval workflow (design §6.5).
+//!
+//! When the hosted facilitator rejects a payment because an approval gate
+//! fired (or the challenge demanded human presence), it opens a **pending
+//! approval request** keyed by the canonical request hash and emits
+//! `approval.requested`. Approvers sign the request hash out of band and post
+//! the [`SignedApproval`] here; the agent polls (or long-polls) until the
+//! warrant's `min_approvals` threshold is met, then retries the payment with
+//! the collected approvals bound into its PoP.
+//!
+//! - `GET  /v1/approvals` — the tenant's open requests.
+//! - `GET  /v1/approvals/{request_hash}` — one request with its approvals.
+//! - `POST /v1/approvals/{request_hash}` — submit a signed approval.
+//! - `GET  /v1/approvals/{request_hash}/wait` — long-poll until approved or expired.
+//!
+//! Requests expire [`DEFAULT_APPROVAL_TTL_SECS`] after they were opened.
+//! Submissions are authenticated by the approval signature itself and must
+//! come from the warrant's effective approvers; a smart-account approver's
+//! signature is checked through the ERC-1271 seam when the server has a
+//! contract verifier. The inbox writes through to an
+//! [`ApprovalStore`] when one is attached (the `sqlite` backend; see
+//! `AppState::with_sqlite`), so pending requests survive a restart; without
+//! one it lives in memory only.
+
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, RwLock},
+    time::Duration,
+};
+
+use axum::{
+    Json,
+    extract::{Path, Query, State},
+};
+use ledgerflow_core::{
+    ContractSignatureVerifier, SignatureEnvelope, SignedApproval, SignerRef, Warrant,
+    approval::DEFAULT_APPROVAL_TTL_SECS, hex_encode_bytes,
+};
+use ledgerflow_protocol::wire::base64url_encode;
+use serde::{Deserialize, Serialize};
+use tokio::sync::Notify;
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    api::{ApiError, ApiResponse, now_ms},
+    saas::SaaSContext,
+    state::AppState,
+};
+
+/// Default long-poll timeout for `/wait`.
+pub const DEFAULT_WAIT_MS: u64 = 30_000;
+
+/// Upper bound on a single long-poll.
+pub const MAX_WAIT_MS: u64 = 60_000;
+
+// ---------------------------------------------------------------------------
+// Pending requests
+// ---------------------------------------------------------------------------
+
+/// An approval request awaiting its threshold.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct PendingApproval {
+    pub tenant_id: String,
+    /// Canonical request hash every approval must bind.
+    pub request_hash: String,
+    /// The leaf warrant whose gate fired.
+    pub warrant: Warrant,
+    pub tool_name: String,
+    pub tool_arguments: BTreeMap<String, String>,
+    pub amount: u128,
+    pub asset: String,
+    pub payee_id: String,
+    /// Keys allowed to approve (the warrant's effective approvers).
+    pub approvers: Vec<SignerRef>,
+    /// Distinct approvals required.
+    pub threshold: u32,
+    /// Unix seconds.
+    pub created_at: u64,
+    /// Unix seconds.
+    pub expires_at: u64,
+    /// Accepted approvals, one per approver.
+    pub approvals: Vec<SignedApproval>,
+}
+
+impl PendingApproval {
+    /// Opens a request for `warrant`, applying the `min_approvals` rule
+    /// (0 = every approver).
+    #[must_use]
+    pub fn new(
+        tenant_id: impl Into<String>,
+        request_hash: impl Into<String>,
+        warrant: Warrant,
+        approvers: Vec<SignerRef>,
+        now_secs: u64,
+    ) -> Self {
+        let threshold = match warrant.min_approvals {
+            0 => u32::try_from(approvers.len()).unwrap_or(u32::MAX),
+            min => min,
+        };
+        Self {
+            tenant_id: tenant_id.into(),
+            request_hash: request_hash.into(),
+            warrant,
+            tool_name: String::new(),
+            tool_arguments: BTreeMap::new(),
+            amount: 0,
+            asset: String::new(),
+            payee_id: String::new(),
+            approvers,
+            threshold,
+            created_at: now_secs,
+            expires_at: now_secs + DEFAULT_APPROVAL_TTL_SECS,
+            approvals: Vec::new(),
+        }
+    }
+
+    /// Records the tool call that triggered the gate.
+    #[must_use]
+    pub fn with_tool(
+        mut self,
+        tool_name: impl Into<String>,
+        tool_arguments: BTreeMap<String, String>,
+    ) -> Self {
+        self.tool_name = tool_name.into();
+        self.tool_arguments = tool_arguments;
+        self
+    }
+
+    /// Records the payment being approved.
+    #[must_use]
+    pub fn with_payment(
+        mut self,
+        amount: u128,
+        asset: impl Into<String>,
+        payee_id: impl Into<String>,
+    ) -> Self {
+        self.amount = amount;
+        self.asset = asset.into();
+        self.payee_id = payee_id.into();
+        self
+    }
+
+    /// `true` once the approvals still live at `now_secs` meet the
+    /// threshold (verification rejects expired ones).
+    #[must_use]
+    pub fn is_approved(&self, now_secs: u64) -> bool {
+        let live = self.approvals.iter().filter(|held| held.expires_at >= now_secs).count();
+        u32::try_from(live).unwrap_or(u32::MAX) >= self.threshold
+    }
+
+    #[must_use]
+    pub const fn is_expired(&self, now_secs: u64) -> bool {
+        now_secs >= self.expires_at
+    }
+
+    /// Wire status: `approved`, `expired` or `pending`.
+    #[must_use]
+    pub fn status(&self, now_secs: u64) -> &'static str {
+        if self.is_approved(now_secs) {
+            "approved"
+        } else if self.is_expired(now_secs) {
+            "expired"
+        } else {
+            "pending"
+        }
+    }
+
+    /// Checks everything about `approval` but its signature.
+    fn check(&self, approval: &SignedApproval, now_secs: u64) -> Result<(), ApprovalError> {
+        if self.is_expired(now_secs) {
+            return Err(ApprovalError::Expired);
+        }
+        if approval.request_hash != self.request_hash {
+            return Err(ApprovalError::RequestMismatch);
+        }
+        if approval.expires_at < now_secs {
+            return Err(ApprovalError::ApprovalExpired);
+        }
+        if !self.approvers.contains(&approval.approver) {
+            return Err(ApprovalError::ApproverNotAllowed);
+        }
+        Ok(())
+    }
+
+    /// Records `approval`, whose signature the caller has verified. A repeat
+    /// approval from the same approver replaces the earlier one, and expired
+    /// approvals are dropped.
+    fn accept(&mut self, approval: SignedApproval, now_secs: u64) -> Result<(), ApprovalError> {
+        self.check(&approval, now_secs)?;
+        self.approvals.retain(|held| {
+            held.expires_at >= now_secs && held.approver.public_key != approval.approver.public_key
+        });
+        self.approvals.push(approval);
+        Ok(())
+    }
+}
+
+/// Approval inbox failures.
+#[derive(Debug, thiserror::Error)]
+pub enum ApprovalError {
+    #[error("no approval request for this request hash")]
+    NotFound,
+    #[error("the approval request has expired")]
+    Expired,
+    #[error("the approval binds a different request hash")]
+    RequestMismatch,
+    #[error("the approval has expired")]
+    ApprovalExpired,
+    #[error("the approver is not allowed to approve this request")]
+    ApproverNotAllowed,
+    #[error("the approval signature is invalid")]
+    InvalidSignature,
+    #[error("approval inbox lock poisoned")]
+    Poisoned,
+    #[error("approval storage failed: {0}")]
+    Storage(String),
+}
+
+impl From<ApprovalError> for ApiError {
+    fn from(error: ApprovalError) -> Self {
+        match error {
+            ApprovalError::NotFound => Self::NotFound,
+            ApprovalError::Poisoned | ApprovalError::Storage(_) => {
+                Self::Internal(error.to_string())
+            }
+            other => Self::BadRequest(other.to_string()),
+        }
+    }
+}
+
+/// Storage seam for pending approval requests.
+pub trait ApprovalStore: std::fmt::Debug + Send + Sync {
+    /// Inserts or replaces the request keyed by its tenant and request hash.
+    fn save_request(&self, pending: &PendingApproval) -> Result<(), ApprovalError>;
+
+    /// Drops every request expired at `now_secs`.
+    fn remove_expired(&self, now_secs: u64) -> Result<(), ApprovalError>;
+
+    /// Every stored request.
+    fn load_requests(&self) -> Result<Vec<PendingApproval>, ApprovalError>;
+}
+
+/// Shared approval store handle.
+pub type SharedApprovalStore = Arc<dyn ApprovalStore>;
+
+/// Pending requests keyed by `(tenant_id, request_hash)`.
+type Requests = BTreeMap<(String, String), PendingApproval>;
+
+/// Tenant-scoped pending approval requests.
+///
+/// Cloning shares the underlying inbox.
+#[derive(Clone, Debug, Default)]
+pub struct ApprovalInbox {
+    requests: Arc<RwLock<Requests>>,
+    /// Wakes long-polls whenever an approval is accepted.
+    changed: Arc<Notify>,
+    /// Write-through persistence; `None` keeps requests in memory only.
+    store: Option<SharedApprovalStore>,
+}
+
+impl ApprovalInbox {
+    /// Creates an empty in-memory inbox (not restart-safe).
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates an inbox persisted in `store`, loading the requests it
+    /// already holds.
+    pub fn with_store(store: SharedApprovalStore) -> Result<Self, ApprovalError> {
+        let requests = store
+            .load_requests()?
+            .into_iter()
+            .map(|held| ((held.tenant_id.clone(), held.request_hash.clone()), held))
+            .collect();
+        Ok(Self {
+            requests: Arc::new(RwLock::new(requests)),
+            changed: Arc::default(),
+            store: Some(store),
+        })
+    }
+
+    /// Opens `pending` unless a live request for the same hash exists (its
+    /// collected approvals are kept). Returns `true` when a request was
+    /// opened. Expired requests are dropped first.
+    pub fn open(&self, pending: PendingApproval, now_secs: u64) -> Result<bool, ApprovalError> {
+        let mut requests = self.requests.write().map_err(|_| ApprovalError::Poisoned)?;
+        if let Some(store) = &self.store {
+            store.remove_expired(now_secs)?;
+        }
+        requests.retain(|_, held| !held.is_expired(now_secs));
+        let key = (pending.tenant_id.clone(), pending.request_hash.clone());
+        if requests.contains_key(&key) {
+            return Ok(false);
+        }
+        self.persist(&pending)?;
+        requests.insert(key, pending);
+        Ok(true)
+    }
+
+    fn persist(&self, pending: &PendingApproval) -> Result<(), ApprovalError> {
+        self.store.as_ref().map_or(Ok(()), |store| store.save_request(pending))
+    }
+
+    /// The tenant's unexpired requests, oldest first.
+    pub fn list(
+        &self,
+        tenant_id: &str,
+        now_secs: u64,
+    ) -> Result<Vec<PendingApproval>, ApprovalError> {
+        let requests = self.requests.read().map_err(|_| ApprovalError::Poisoned)?;
+        let mut open: Vec<_> = requests
+            .iter()
+            .filter(|((tenant, _), held)| tenant == tenant_id && !held.is_expired(now_secs))
+            .map(|(_, held)| held.clone())
+            .collect();
+        open.sort_by_key(|held| held.created_at);
+        Ok(open)
+    }
+
+    /// One request (including an expired one not yet dropped).
+    pub fn get(
+        &self,
+        tenant_id: &str,
+        request_hash: &str,
+    ) -> Result<PendingApproval, ApprovalError> {
+        let requests = self.requests.read().map_err(|_| ApprovalError::Poisoned)?;
+        requests
+            .get(&(tenant_id.to_string(), request_hash.to_string()))
+            .cloned()
+            .ok_or(ApprovalError::NotFound)
+    }
+
+    /// Validates and records a signed approval, waking long-polls.
+    ///
+    /// A contract-account approver is verified through `contract_verifier`
+    /// (ERC-1271). That may call a node, so the signature is checked with
+    /// the inbox unlocked, once the request and approver are known to match.
+    pub fn submit(
+        &self,
+        tenant_id: &str,
+        approval: SignedApproval,
+        contract_verifier: Option<&dyn ContractSignatureVerifier>,
+        now_secs: u64,
+    ) -> Result<PendingApproval, ApprovalError> {
+        let key = (tenant_id.to_string(), approval.request_hash.clone());
+        self.requests
+            .read()
+            .map_err(|_| ApprovalError::Poisoned)?
+            .get(&key)
+            .ok_or(ApprovalError::NotFound)?
+            .check(&approval, now_secs)?;
+        if !approval.verify_signature_with(contract_verifier) {
+            return Err(ApprovalError::InvalidSignature);
+        }
+        let mut requests = self.requests.write().map_err(|_| ApprovalError::Poisoned)?;
+        let pending = requests.get_mut(&key).ok_or(ApprovalError::NotFound)?;
+        let mut updated = pending.clone();
+        updated.accept(approval, now_secs)?;
+        self.persist(&updated)?;
+        pending.clone_from(&updated);
+        drop(requests);
+        self.changed.notify_waiters();
+        Ok(updated)
+    }
+
+    /// Waits until the request is approved or expired, or `timeout` elapses,
+    /// and returns its state at that point.
+    pub async fn wait(
+        &self,
+        tenant_id: &str,
+        request_hash: &str,
+        timeout: Duration,
+    ) -> Result<PendingApproval, ApprovalError> {
+        let deadline = tokio::time::Instant::now() + timeout;
+        loop {
+            // Register before reading so an approval accepted in between
+            // still wakes this poll.
+            let changed = self.changed.notified();
+            tokio::pin!(changed);
+            changed.as_mut().enable();
+
+            let pending = self.get(tenant_id, request_hash)?;
+            let now_secs = now_ms() / 1_000;
+            if pending.is_approved(now_secs) || pending.is_expired(now_secs) {
+                return Ok(pending);
+            }
+            let expiry = tokio::time::Instant::now() +
+                Duration::from_secs(pending.expires_at.saturating_sub(now_secs));
+            if tokio::time::timeout_at(deadline.min(expiry), changed).await.is_err() {
+                return self.get(tenant_id, request_hash);
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Wire types
+// ---------------------------------------------------------------------------
+
+/// A signed approval on the wire.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct ApprovalBody {
+    /// Hex-encoded approver public key.
+    pub approver_public_key: String,
+    /// Approver key algorithm (default `ed25519`).
+    #[serde(default)]
+    pub algorithm: Option<String>,
+    /// Unix seconds when the approval expires.
+    pub expires_at: u64,
+    /// Hex-encoded signature over the approval preimage.
+    pub signature: String,
+}
+
+impl ApprovalBody {
+    fn from_approval(approval: &SignedApproval) -> Self {
+        Self {
+            approver_public_key: hex_encode_bytes(&approval.approver.public_key),
+            algorithm: Some(approval.approver.alg.as_str().to_string()),
+            expires_at: approval.expires_at,
+            signature: hex_encode_bytes(&approval.signature.value),
+        }
+    }
+
+    fn into_approval(self, request_hash: &str) -> Result<SignedApproval, ApiError> {
+        let approver = crate::issuance::parse_signer(
+            &self.approver_public_key,
+            self.algorithm.as_deref(),
+            None,
+        )
+        .map_err(|error| ApiError::BadRequest(format!("approver_public_key: {error}")))?;
+        let signature =
+            crate::issuance::decode_hex_vec(self.signature.trim().trim_start_matches("0x"))
+                .ok_or_else(|| ApiError::BadRequest("signature: invalid hex".to_string()))?;
+        Ok(SignedApproval {
+            request_hash: request_hash.to_string(),
+            signature: SignatureEnvelope { alg: approver.alg, value: signature },
+            approver,
+            expires_at: self.expires_at,
+        })
+    }
+}
+
+/// An approval request as shown to approvers and agents.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct ApprovalRequestView {
+    pub request_hash: String,
+    /// `pending`, `approved` or `expired`.
+    pub status: String,
+    pub warrant_id: String,
+    pub warrant_digest: String,
+    /// base64url (unpadded) CBOR of the leaf warrant.
+    pub warrant: String,
+    pub tool_name: String,
+    pub tool_arguments: BTreeMap<String, String>,
+    /// Decimal amount of the payment being approved.
+    pub amount: String,
+    pub asset: String,
+    pub payee_id: String,
+    /// Hex-encoded public keys allowed to approve.
+    pub approvers: Vec<String>,
+    pub min_approvals: u32,
+    pub created_at: u64,
+    pub expires_at: u64,
+    pub approvals: Vec<ApprovalBody>,
+}
+
+impl ApprovalRequestView {
+    fn from_pending(pending: &PendingApproval, now_secs: u64) -> Result<Self, ApiError> {
+        let warrant =
+            pending.warrant.encode_cbor().map_err(|error| ApiError::Internal(error.to_string()))?;
+        Ok(Self {
+            request_hash: pending.request_hash.clone(),
+            status: pending.status(now_secs).to_string(),
+            warrant_id: hex_encode_bytes(&pending.warrant.id),
+            warrant_digest: pending.warrant.digest(),
+            warrant: base64url_encode(&warrant),
+            tool_name: pending.tool_name.clone(),
+            tool_arguments: pending.tool_arguments.clone(),
+            amount: pending.amount.to_string(),
+            asset: pending.asset.clone(),
+            payee_id: pending.payee_id.clone(),
+            approvers: pending
+                .approvers
+                .iter()
+                .map(|approver| hex_encode_bytes(&approver.public_key))
+                .collect(),
+            min_approvals: pending.threshold,
+            created_at: pending.created_at,
+            expires_at: pending.expires_at,
+            approvals: pending.approvals.iter().map(ApprovalBody::from_approval).collect(),
+        })
+    }
+}
+
+/// Query parameters of `GET /v1/approvals/{request_hash}/wait`.
+#[derive(Clone, Debug, Deserialize, IntoParams)]
+pub struct WaitQuery {
+    /// Long-poll timeout (default 30 s, capped at 60 s).
+    pub timeout_ms: Option<u64>,
+}
+
+// ---------------------------------------------------------------------------
+// Handlers
+// ---------------------------------------------------------------------------
+
+/// Lists the tenant's open approval requests.
+#[utoipa::path(
+    get,
+    path = "/v1/approvals",
+    responses(
+        (status = 200, description = "Open approval requests", body = Vec<ApprovalRequestView>),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub(crate) async fn list_approvals(
+    State(state): State<AppState>,
+    ctx: SaaSContext,
+) -> Result<Json<ApiResponse<Vec<ApprovalRequestView>>>, ApiError> {
+    let now_secs = now_ms() / 1_000;
+    let views = state
+        .approvals
+        .list(&ctx.tenant_id, now_secs)?
+        .iter()
+        .map(|pending| ApprovalRequestView::from_pending(pending, now_secs))
+        .collect::<Result<Vec<_>, _>>()?;
+    Ok(Json(ApiResponse::ok(views)))
+}
+
+/// Returns one approval request.
+#[utoipa::path(
+    get,
+    path = "/v1/approvals/{request_hash}",
+    params(("request_hash" = String, Path, description = "Canonical request hash")),
+    responses(
+        (status = 200, description = "Approval request", body = ApprovalRequestView),
+        (status = 404, description = "Not found")
+    )
+)]
+pub(crate) async fn get_approval(
+    State(state): State<AppState>,
+    ctx: SaaSContext,
+    Path(request_hash): Path<String>,
+) -> Result<Json<ApiResponse<ApprovalRequestView>>, ApiError> {
+    let pending = state.approvals.get(&ctx.tenant_id, &request_hash)?;
+    Ok(Json(ApiResponse::ok(ApprovalRequestView::from_pending(&pending, now_ms() / 1_000)?)))
+}
+
+/// Submits a signed approval for a request.
+#[utoipa::path(
+    post,
+    path = "/v1/approvals/{request_hash}",
+    params(("request_hash" = String, Path, description = "Canonical request hash")),
+    request_body = ApprovalBody,
+    responses(
+        (status = 200, description = "Updated approval request", body = ApprovalRequestView),
+        (status = 400, description = "Invalid or expired approval"),
+        (status = 404, description = "Not found")
+    )
+)]
+pub(crate) async fn submit_approval(
+    State(state): State<AppState>,
+    ctx: SaaSContext,
+    Path(request_hash): Path<String>,
+    Json(body): Json<ApprovalBody>,
+) -> Result<Json<ApiResponse<ApprovalRequestView>>, ApiError> {
+    let now_secs = now_ms() / 1_000;
+    let approval = body.into_approval(&request_hash)?;
+    // An ERC-1271 check blocks on the node, so submission runs on the
+    // blocking pool.
+    let contract_verifier = state.verification.contract_verifier.clone();
+    let pending = tokio::task::spawn_blocking(move || {
+        state.approvals.submit(&ctx.tenant_id, approval, contract_verifier.as_deref(), now_secs)
+    })
+    .await
+    .map_err(|error| ApiError::Internal(format!("approval task failed: {error}")))??;
+    Ok(Json(ApiResponse::ok(ApprovalRequestView::from_pending(&pending, now_secs)?)))
+}
+
+/// Long-polls until a request is approved or expired.
+#[utoipa::path(
+    get,
+    path = "/v1/approvals/{request_hash}/wait",
+    params(
+        ("request_hash" = String, Path, description = "Canonical request hash"),
+        WaitQuery
+    ),
+    responses(
+        (status = 200, description = "Approval request when approved, expired or timed out", body = ApprovalRequestView),
+        (status = 404, description = "Not found")
+    )
+)]
+pub(crate) async fn wait_approval(
+    State(state): State<AppState>,
+    ctx: SaaSContext,
+    Path(request_hash): Path<String>,
+    Query(query): Query<WaitQuery>,
+) -> Result<Json<ApiResponse<ApprovalRequestView>>, ApiError> {
+    let timeout = query.timeout_ms.unwrap_or(DEFAULT_WAIT_MS).min(MAX_WAIT_MS);
+    let pending =
+        state.approvals.wait(&ctx.tenant_id, &request_hash, Duration::from_millis(timeout)).await?;
+    Ok(Json(ApiResponse::ok(ApprovalRequestView::from_pending(&pending, now_ms() / 1_000)?)))
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::expect_used)]
+
+    use ledgerflow_core::{PaymentConstraint, SigningKeyPair, WarrantBuilder};
+
+    use super::*;
+
+    const HASH: &str = "sha256:request";
+
+    fn keys(tag: u8) -> SigningKeyPair {
+        SigningKeyPair::from_bytes(&[tag; 32])
+    }
+
+    fn pending(now_secs: u64) -> PendingApproval {
+        let issuer = keys(1);
+        let warrant = WarrantBuilder::new(now_secs * 1_000)
+            .ttl_secs(300)
+            .issuer(issuer.signer_ref())
+            .holder(keys(2).signer_ref())
+            .payment(PaymentConstraint::new(1_000))
+            .approver(keys(3).signer_ref())
+            .approver(keys(4).signer_ref())
+            .min_approvals(2)
+            .sign_with(&issuer, [0_u8; 8]);
+        let approvers = warrant.required_approvers.clone();
+        PendingApproval::new("tenant-a", HASH, warrant, approvers, now_secs).with_payment(
+            100,
+            "USDC",
+            "merchant-a",
+        )
+    }
+
+    fn approval(tag: u8, expires_at: u64) -> SignedApproval {
+        SignedApproval::sign(HASH, &keys(tag).signer_ref(), expires_at, &keys(tag))
+    }
+
+    #[test]
+    fn submissions_are_validated_until_the_threshold_is_met() {
+        let inbox = ApprovalInbox::new();
+        assert!(inbox.open(pending(1_000), 1_000).expect("open"));
+
+        let outsider = approval(5, 2_000);
+        assert!(matches!(
+            inbox.submit("tenant-a", outsider, None, 1_000),
+            Err(ApprovalError::ApproverNotAllowed)
+        ));
+        let mut forged = approval(3, 2_000);
+        forged.expires_at += 1;
+        assert!(matches!(
+            inbox.submit("tenant-a", forged, None, 1_000),
+            Err(ApprovalError::InvalidSignature)
+        ));
+        assert!(matches!(
+            inbox.submit("tenant-b", approval(3, 2_000), None, 1_000),
+            Err(ApprovalError::NotFound)
+        ));
+
+        // A repeated approver counts once.
+        inbox.submit("tenant-a", approval(3, 2_000), None, 1_000).expect("first");
+        let held = inbox.submit("tenant-a", approval(3, 2_000), None, 1_000).expect("repeat");
+        assert_eq!(held.status(1_000), "pending");
+        let held = inbox.submit("tenant-a", approval(4, 2_000), None, 1_000).expect("second");
+        assert_eq!(held.status(1_000), "approved");
+        assert_eq!(held.approvals.len(), 2);
+    }
+
+    #[test]
+    fn expired_approvals_stop_counting_and_are_dropped() {
+        let inbox = ApprovalInbox::new();
+        inbox.open(pending(1_000), 1_000).expect("open");
+        inbox.submit("tenant-a", approval(3, 1_100), None, 1_000).expect("first");
+        let held = inbox.submit("tenant-a", approval(4, 2_000), None, 1_000).expect("second");
+        assert_eq!(held.status(1_100), "approved");
+        assert_eq!(held.status(1_101), "pending");
+
+        // Re-approving after the first approval lapsed restores the threshold.
+        let held = inbox.submit("tenant-a", approval(4, 2_000), None, 1_200).expect("repeat");
+        assert_eq!(held.approvals.len(), 1);
+        assert_eq!(held.status(1_200), "pending");
+        let held = inbox.submit("tenant-a", approval(3, 2_000), None, 1_200).expect("renewed");
+        assert_eq!(held.status(1_200), "approved");
+    }
+
+    #[test]
+    fn reopening_keeps_approvals_and_expired_requests_drop() {
+        let inbox = ApprovalInbox::new();
+        inbox.open(pending(1_000), 1_000).expect("open");
+        inbox.submit("tenant-a", approval(3, 2_000), None, 1_000).expect("approve");
+        assert!(!inbox.open(pending(1_010), 1_010).expect("reopen"));
+        assert_eq!(inbox.get("tenant-a", HASH).expect("get").approvals.len(), 1);
+
+        let expiry = 1_000 + DEFAULT_APPROVAL_TTL_SECS;
+        assert!(matches!(
+            inbox.submit("tenant-a", approval(4, expiry + 60), None, expiry),
+            Err(ApprovalError::Expired)
+        ));
+        assert!(inbox.list("tenant-a", expiry).expect("list").is_empty());
+        assert!(inbox.open(pending(expiry), expiry).expect("fresh request"));
+        assert!(inbox.get("tenant-a", HASH).expect("get").approvals.is_empty());
+    }
+
+    #[test]
+    fn wait_returns_once_the_threshold_is_met() {
+        let now_secs = now_ms() / 1_000;
+        let inbox = ApprovalInbox::new();
+        inbox.open(pending(now_secs), now_secs).expect("open");
+        let runtime = tokio::runtime::Runtime::new().expect("runtime");
+        let held = runtime.block_on(async {
+            let waiter = inbox.clone();
+            let poll = tokio::spawn(async move {
+                waiter.wait("tenant-a", HASH, Duration::from_secs(10)).await
+            });
+            tokio::task::yield_now().await;
+            inbox.submit("tenant-a", approval(3, now_secs + 60), None, now_secs).expect("first");
+            inbox.submit("tenant-a", approval(4, now_secs + 60), None, now_secs).expect("second");
+            poll.await.expect("join").expect("wait")
+        });
+        assert!(held.is_approved(now_secs));
+
+        let timed_out = runtime
+            .block_on(inbox.wait("tenant-a", "sha256:other", Duration::from_millis(1)))
+            .map(|_| ());
+        assert!(matches!(timed_out, Err(ApprovalError::NotFound)));
+    }
+}
@@ -23,6 +23,17 @@ pub struct SaasConfig {
     pub tenant_id: String,
 }
 
+/// A wallet daemon holding the process issuer key (local JSON-RPC).
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct IssuerWalletConfig {
+    /// JSON-RPC endpoint of the wallet daemon.
+    pub url: String,
+    /// Hex-encoded public key of the issuer key held by the wallet.
+    pub public_key_hex: String,
+    /// Key algorithm (`ed25519` | `secp256k1`; default `ed25519`).
+    pub algorithm: Option<String>,
+}
+
 /// Full server configuration.
 #[derive(Clone, Debug, Eq, PartialEq)]
 pub struct ServerConfig {
@@ -30,13 +41,27 @@ pub struct ServerConfig {
     pub saas: SaasConfig,
     /// Hex-encoded Ed25519 issuer signing key used to issue warrants.
     ///
-    /// **Required.** A real, secret key must be supplied (e.g.
-    /// `LEDGERFLOW_ISSUER_KEY`). Absence is a startup failure: the server must
-    /// never fall back to a predictable demo key in production (design §6.8).
+    /// **Required** unless [`Self::issuer_wallet`] is set. A real, secret key
+    /// must be supplied (e.g. `LEDGERFLOW_ISSUER_KEY`). Absence is a startup
+    /// failure: the server must never fall back to a predictable demo key in
+    /// production (design §6.8).
     pub issuer_key_hex: Option<String>,
+    /// Wallet daemon that signs warrants instead of an in-memory key.
+    pub issuer_wallet: Option<IssuerWalletConfig>,
     /// Optional webhook delivery URL (design §10.3). When set, events are
     /// delivered to this endpoint with bounded retry.
     pub webhook_url: Option<String>,
+    /// Accounting-point identifier of this server (design §14 P2). When set,
+    /// settlements of warrants whose budget policy names this ledger are
+    /// reserved against an in-process budget ledger; budgeted warrants are
+    /// rejected when unset.
+    pub ledger_id: Option<String>,
+    /// JSON file holding the process-wide trusted-issuer set. When set it is
+    /// authoritative and can be reloaded at runtime (design §6.8).
+    pub trusted_issuers_file: Option<std::path::PathBuf>,
+    /// Hex-encoded Ed25519 key signing the revocation lists served by
+    /// `GET /v1/srl`. SRL publication is disabled when unset.
+    pub srl_key_hex: Option<String>,
 }
 
 impl ServerConfig {
@@ -48,11 +73,19 @@ impl ServerConfig {
     /// - `LEDGERFLOW_SAAS_MODE` (`standalone` | `saas`; absent = standalone)
     /// - `LEDGERFLOW_SERVICE_TOKEN` (required when mode is `saas`)
     /// - `LEDGERFLOW_TENANT_ID` (default `default`)
-    /// - `LEDGERFLOW_ISSUER_KEY` (hex Ed25519 key; required to issue warrants)
+    /// - `LEDGERFLOW_ISSUER_KEY` (hex Ed25519 key; required unless a wallet is configured)
+    /// - `LEDGERFLOW_ISSUER_WALLET_URL` (local JSON-RPC wallet holding the issuer key)
+    /// - `LEDGERFLOW_ISSUER_WALLET_KEY` (hex public key of that key; required with the URL)
+    /// - `LEDGERFLOW_ISSUER_WALLET_ALG` (`ed25519` | `secp256k1`; default `ed25519`)
+    /// - `LEDGERFLOW_WEBHOOK_URL` (optional webhook endpoint)
+    /// - `LEDGERFLOW_LEDGER_ID` (optional accounting-point identifier)
+    /// - `LEDGERFLOW_TRUSTED_ISSUERS_FILE` (optional JSON trusted-issuer set)
+    /// - `LEDGERFLOW_SRL_KEY` (optional hex Ed25519 key signing published revocation lists)
     ///
     /// Invalid `saas` mode or a missing service token in `saas` mode is a
     /// hard error (fail-fast). A missing issuer key is also a hard error: the
-    /// server must never default to a predictable demo key.
+    /// server must never default to a predictable demo key. Exactly one of
+    /// the in-memory key and the issuer wallet may be configured.
     pub fn from_env() -> Result<Self, ConfigError> {
         let bind_addr =
             std::env::var("LEDGERFLOW_BIND").unwrap_or_else(|_| "127.0.0.1:8080".to_string());
@@ -73,16 +106,40 @@ impl ServerConfig {
         }
         let tenant_id =
             std::env::var("LEDGERFLOW_TENANT_ID").unwrap_or_else(|_| "default".to_string());
-        let issuer_key_hex = std::env::var("LEDGERFLOW_ISSUER_KEY").ok();
-        if issuer_key_hex.as_deref().is_none_or(|s| s.is_empty()) {
-            return Err(ConfigError::MissingIssuerKey);
+        let issuer_key_hex =
+            std::env::var("LEDGERFLOW_ISSUER_KEY").ok().filter(|key| !key.is_empty());
+        let issuer_wallet = match std::env::var("LEDGERFLOW_ISSUER_WALLET_URL") {
+            Ok(url) if !url.is_empty() => {
+                let public_key_hex = std::env::var("LEDGERFLOW_ISSUER_WALLET_KEY")
+                    .ok()
+                    .filter(|key| !key.is_empty())
+                    .ok_or(ConfigError::MissingIssuerWalletKey)?;
+                let algorithm = std::env::var("LEDGERFLOW_ISSUER_WALLET_ALG").ok();
+                Some(IssuerWalletConfig { url, public_key_hex, algorithm })
+            }
+            _ => None,
+        };
+        match (&issuer_key_hex, &issuer_wallet) {
+            (None, None) => return Err(ConfigError::MissingIssuerKey),
+            (Some(_), Some(_)) => return Err(ConfigError::ConflictingIssuerKeys),
+            _ => {}
         }
         let webhook_url = std::env::var("LEDGERFLOW_WEBHOOK_URL").ok();
+        let ledger_id = std::env::var("LEDGERFLOW_LEDGER_ID").ok().filter(|id| !id.is_empty());
+        let trusted_issuers_file = std::env::var("LEDGERFLOW_TRUSTED_ISSUERS_FILE")
+            .ok()
+            .filter(|path| !path.is_empty())
+            .map(std::path::PathBuf::from);
+        let srl_key_hex = std::env::var("LEDGERFLOW_SRL_KEY").ok().filter(|key| !key.is_empty());
         Ok(Self {
             bind_addr,
             saas: SaasConfig { mode, service_token, tenant_id },
             issuer_key_hex,
+            issuer_wallet,
             webhook_url,
+            ledger_id,
+            trusted_issuers_file,
+            srl_key_hex,
         })
     }
 }
@@ -94,6 +151,13 @@ pub enum ConfigError {
     InvalidMode { mode: String, valid: String },
     #[error("LEDGERFLOW_SERVICE_TOKEN is required when LEDGERFLOW_SAAS_MODE=saas")]
     MissingServiceToken,
-    #[error("LEDGERFLOW_ISSUER_KEY is required to issue warrants (never defaults to a demo key)")]
+    #[error(
+        "LEDGERFLOW_ISSUER_KEY or LEDGERFLOW_ISSUER_WALLET_URL is required to issue warrants \
+         (never defaults to a demo key)"
+    )]
     MissingIssuerKey,
+    #[error("LEDGERFLOW_ISSUER_WALLET_KEY is required when LEDGERFLOW_ISSUER_WALLET_URL is set")]
+    MissingIssuerWalletKey,
+    #[error("set either LEDGERFLOW_ISSUER_KEY or LEDGERFLOW_ISSUER_WALLET_URL, not both")]
+    ConflictingIssuerKeys,
 }
@@ -0,0 +1,610 @@
+//! Hosted facilitator endpoints (x402-facilitator compatible).
+//!
+//! Merchants that cannot link the Rust crates post the x402 payment they
+//! received, together with the challenge they issued and the request being
+//! paid for, and receive a structured verdict:
+//!
+//! - `POST /v1/verify` — full authorization pre-check (chain, PoP, payload binding, approvals,
+//!   tenant-scoped revocation). Non-consuming. A payment held back by an approval gate opens a
+//!   pending request in the approval inbox ([`crate::approvals`]).
+//! - `POST /v1/settle` — verify, claim the PoP nonce, then settle through the
+//!   [`SettlementService`](ledgerflow_facilitator::SettlementService) atomic re-verify (design
+//!   §8.1) and record the receipt. A failed settlement releases the nonce for a retry.
+//! - `GET  /v1/status` — idempotent settlement lookup by transaction id or warrant digest, scoped
+//!   to the caller's tenant.
+//!
+//! The request body is the protocol crate's [`FacilitatorRequest`]: the
+//! payment payload is accepted either as a JSON object or as a base64 string
+//! of that object (the x402 `X-PAYMENT` header form); the LedgerFlow
+//! extension inside it is base64url CBOR, exactly as carried on the wire.
+//! Structurally invalid payments are verdicts (`invalid_payment`), not HTTP
+//! errors, matching x402 facilitator semantics.
+
+use std::{collections::BTreeMap, sync::PoisonError};
+
+use axum::{
+    Json,
+    extract::{Query, State},
+};
+use ledgerflow_core::{AuthorizationContext, WarrantChain, effective_approvers, sha256_prefixed};
+use ledgerflow_facilitator::{
+    RegistryEntry, SettleRequest, SettlementStatus, VerificationService, VerifyOutcome,
+    VerifyRequest, VerifyStatus,
+};
+pub use ledgerflow_protocol::facilitator_json::{
+    AcceptedQuoteBody, ChallengeWire, FacilitatorRequest, PaymentPayloadBody, PaymentPayloadWire,
+    RequestContextBody,
+};
+use ledgerflow_protocol::{
+    AcceptedQuote, HttpRequest, LedgerFlowAuthorizationExtension, LedgerFlowChallenge,
+    PaymentPayload, ReplayFingerprint, VerificationContextBuilder, canonical_accepted_hash,
+    canonical_request_hash, decode_challenge_param, wire::base64url_decode,
+};
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    api::{ApiError, ApiResponse},
+    approvals::PendingApproval,
+    state::AppState,
+    webhook::WebhookEvent,
+};
+
+// ---------------------------------------------------------------------------
+// Wire types
+// ---------------------------------------------------------------------------
+
+/// Verify verdict.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct VerifyResponse {
+    pub is_valid: bool,
+    /// [`VerifyStatus`] wire code.
+    pub status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invalid_reason: Option<String>,
+    /// Payment subject that will be charged.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warrant_digest: Option<String>,
+}
+
+/// Settle verdict.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct SettleResponse {
+    pub success: bool,
+    /// [`SettlementStatus`] wire code.
+    pub status: String,
+    /// [`VerifyStatus`] wire code of the pre-settlement verification.
+    pub verify_status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_reason: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transaction_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rail: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub asset: Option<String>,
+}
+
+/// Query parameters of `GET /v1/status` (exactly one is required).
+#[derive(Clone, Debug, Deserialize, IntoParams)]
+pub struct StatusQuery {
+    pub transaction_id: Option<String>,
+    pub warrant_digest: Option<String>,
+}
+
+/// One recorded settlement.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct SettlementView {
+    pub transaction_id: String,
+    /// [`SettlementStatus`] wire code.
+    pub status: String,
+    pub rail: String,
+    pub amount: String,
+    pub asset: String,
+}
+
+impl From<RegistryEntry> for SettlementView {
+    fn from(entry: RegistryEntry) -> Self {
+        Self {
+            transaction_id: entry.receipt.transaction_id,
+            status: entry.status.as_str().to_string(),
+            rail: entry.receipt.rail.as_str().to_string(),
+            amount: entry.receipt.settled_amount.to_string(),
+            asset: entry.receipt.asset,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Handlers
+// ---------------------------------------------------------------------------
+
+/// Verifies an x402 payment carrying a LedgerFlow authorization.
+#[utoipa::path(
+    post,
+    path = "/v1/verify",
+    request_body = FacilitatorRequest,
+    responses(
+        (status = 200, description = "Verification verdict", body = VerifyResponse),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub(crate) async fn verify(
+    State(state): State<AppState>,
+    ctx: crate::saas::SaaSContext,
+    Json(request): Json<FacilitatorRequest>,
+) -> Json<ApiResponse<VerifyResponse>> {
+    let now_ms = crate::api::now_ms();
+    let outcome = match DecodedPayment::verify_blocking(request, state, ctx, now_ms).await {
+        Ok((_, outcome)) | Err(outcome) => outcome,
+    };
+    Json(ApiResponse::ok(VerifyResponse {
+        is_valid: outcome.status.is_verified(),
+        status: outcome.status.as_str().to_string(),
+        invalid_reason: outcome.reason,
+        payer: outcome.authorization.as_ref().map(|a| a.payment_subject.value.clone()),
+        warrant_digest: outcome.authorization.map(|a| a.warrant_digest),
+    }))
+}
+
+/// Verifies and settles an x402 payment carrying a LedgerFlow authorization.
+#[utoipa::path(
+    post,
+    path = "/v1/settle",
+    request_body = FacilitatorRequest,
+    responses(
+        (status = 200, description = "Settlement verdict", body = SettleResponse),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub(crate) async fn settle(
+    State(state): State<AppState>,
+    ctx: crate::saas::SaaSContext,
+    Json(request): Json<FacilitatorRequest>,
+) -> Json<ApiResponse<SettleResponse>> {
+    let now_ms = crate::api::now_ms();
+    let (decoded, outcome) =
+        match DecodedPayment::verify_blocking(request, state.clone(), ctx.clone(), now_ms).await {
+            Ok(verified) => verified,
+            Err(rejection) => return Json(ApiResponse::ok(SettleResponse::rejected(rejection))),
+        };
+    let Some(authorization) = outcome.authorization else {
+        return Json(ApiResponse::ok(SettleResponse::rejected(outcome)));
+    };
+
+    // Verification is non-consuming; settlement burns the PoP nonce so the
+    // same proof can never be settled twice. A failed settlement releases it
+    // again, so the payment can be retried.
+    let fingerprint = decoded.fingerprint();
+    if !claim_nonce(&state, fingerprint.clone(), now_ms) {
+        return Json(ApiResponse::ok(SettleResponse::rejected(VerifyOutcome::error(
+            VerifyStatus::Replayed,
+            "the proof nonce was already settled".to_string(),
+        ))));
+    }
+
+    // Rails block on their node (an EVM rail polls for confirmations), so
+    // the settlement runs on the blocking pool.
+    let service =
+        state.settlement.with_revocation(state.revocation_store.for_tenant(&ctx.tenant_id));
+    let (settlement, authorization) = match tokio::task::spawn_blocking(move || {
+        let settlement = service.settle(&SettleRequest {
+            authorization: &authorization,
+            chain: &decoded.chain,
+            proof: &decoded.extension.proof,
+            context: &decoded.context,
+            settlement_payload: &decoded.settlement_payload,
+            now_ms,
+        });
+        (settlement, authorization)
+    })
+    .await
+    {
+        Ok(settled) => settled,
+        Err(error) => {
+            release_nonce(&state, &fingerprint);
+            return Json(ApiResponse::ok(SettleResponse {
+                success: false,
+                status: SettlementStatus::Failed.as_str().to_string(),
+                verify_status: VerifyStatus::Verified.as_str().to_string(),
+                error_reason: Some(format!("settlement task failed: {error}")),
+                payer: None,
+                transaction_id: None,
+                rail: None,
+                amount: None,
+                asset: None,
+            }));
+        }
+    };
+    // Nothing moved on a failed settlement; a pending one may still land, so
+    // its nonce stays claimed and the outcome is tracked through `/v1/status`.
+    if settlement.status == SettlementStatus::Failed {
+        release_nonce(&state, &fingerprint);
+    }
+    let payer = Some(authorization.payment_subject.value.clone());
+    let Some(receipt) = settlement.receipt else {
+        return Json(ApiResponse::ok(SettleResponse {
+            success: false,
+            status: settlement.status.as_str().to_string(),
+            verify_status: VerifyStatus::Verified.as_str().to_string(),
+            error_reason: settlement.reason,
+            payer,
+            transaction_id: None,
+            rail: None,
+            amount: None,
+            asset: None,
+        }));
+    };
+
+    state.registry.record(
+        &ctx.tenant_id,
+        &authorization.warrant_digest,
+        receipt.clone(),
+        settlement.status,
+    );
+    state.webhook.emit(WebhookEvent::PaymentSettled {
+        tenant_id: ctx.tenant_id,
+        transaction_id: receipt.transaction_id.clone(),
+        amount: receipt.settled_amount,
+    });
+    Json(ApiResponse::ok(SettleResponse {
+        success: settlement.status == SettlementStatus::Settled,
+        status: settlement.status.as_str().to_string(),
+        verify_status: VerifyStatus::Verified.as_str().to_string(),
+        error_reason: settlement.reason,
+        payer,
+        transaction_id: Some(receipt.transaction_id),
+        rail: Some(receipt.rail.as_str().to_string()),
+        amount: Some(receipt.settled_amount.to_string()),
+        asset: Some(receipt.asset),
+    }))
+}
+
+/// Looks up recorded settlements by transaction id or warrant digest.
+#[utoipa::path(
+    get,
+    path = "/v1/status",
+    params(StatusQuery),
+    responses(
+        (status = 200, description = "Recorded settlements", body = [SettlementView]),
+        (status = 400, description = "Missing query parameter"),
+        (status = 401, description = "Unauthorized"),
+        (status = 404, description = "Not found")
+    )
+)]
+pub(crate) async fn status(
+    State(state): State<AppState>,
+    ctx: crate::saas::SaaSContext,
+    Query(query): Query<StatusQuery>,
+) -> Result<Json<ApiResponse<Vec<SettlementView>>>, ApiError> {
+    let entries = match (&query.transaction_id, &query.warrant_digest) {
+        (Some(transaction_id), None) => {
+            state.registry.query(&ctx.tenant_id, transaction_id).into_iter().collect()
+        }
+        (None, Some(digest)) => state.registry.query_by_warrant(&ctx.tenant_id, digest),
+        _ => {
+            return Err(ApiError::BadRequest(
+                "provide exactly one of transaction_id or warrant_digest".to_string(),
+            ));
+        }
+    };
+    if entries.is_empty() {
+        return Err(ApiError::NotFound);
+    }
+    Ok(Json(ApiResponse::ok(entries.into_iter().map(SettlementView::from).collect())))
+}
+
+// ---------------------------------------------------------------------------
+// Decoding and verification
+// ---------------------------------------------------------------------------
+
+/// A fully decoded facilitator request, ready for verification.
+struct DecodedPayment {
+    challenge: LedgerFlowChallenge,
+    extension: LedgerFlowAuthorizationExtension,
+    chain: WarrantChain,
+    context: AuthorizationContext,
+    payment_payload_digest: String,
+    settlement_payload: String,
+    tool_arguments: BTreeMap<String, String>,
+}
+
+impl DecodedPayment {
+    /// Decodes, verifies and opens any approval request on the blocking
+    /// pool: injected contract verifiers and identity resolvers block on
+    /// their node, and the warrant repository may be SQLite-backed.
+    async fn verify_blocking(
+        request: FacilitatorRequest,
+        state: AppState,
+        ctx: crate::saas::SaaSContext,
+        now_ms: u64,
+    ) -> Result<(Self, VerifyOutcome), VerifyOutcome> {
+        tokio::task::spawn_blocking(move || {
+            let decoded = Self::decode(request, &state, now_ms)?;
+            let outcome = decoded.verify(&state, &ctx);
+            decoded.request_approval(&state, &ctx, &outcome, now_ms);
+            Ok((decoded, outcome))
+        })
+        .await
+        .unwrap_or_else(|error| {
+            Err(VerifyOutcome::error(
+                VerifyStatus::Unauthorized,
+                format!("verification task failed: {error}"),
+            ))
+        })
+    }
+
+    /// Decodes the wire request and applies the structural checks the
+    /// in-process `MerchantVerifier` performs (challenge echo, signer
+    /// consistency, non-empty chain). Digest-referenced parents are resolved
+    /// from the server's warrant repository.
+    fn decode(
+        request: FacilitatorRequest,
+        state: &AppState,
+        now_ms: u64,
+    ) -> Result<Self, VerifyOutcome> {
+        let invalid = |reason: String| VerifyOutcome::error(VerifyStatus::InvalidPayment, reason);
+
+        let challenge = match request.challenge {
+            ChallengeWire::Json(challenge) => challenge,
+            ChallengeWire::Encoded(value) => decode_challenge_param(&value)
+                .map_err(|error| invalid(format!("invalid challenge: {error}")))?,
+        };
+        let body = match request.payment_payload {
+            PaymentPayloadWire::Json(body) => body,
+            PaymentPayloadWire::Encoded(value) => {
+                let json = decode_base64(&value)
+                    .map_err(|error| invalid(format!("invalid payment payload: {error}")))?;
+                serde_json::from_slice(&json)
+                    .map_err(|error| invalid(format!("invalid payment payload: {error}")))?
+            }
+        };
+        let amount = body
+            .accepted
+            .amount
+            .parse::<u128>()
+            .map_err(|_| invalid("accepted.amount must be a decimal integer".to_string()))?;
+        let extension = decode_base64(&body.ledgerflow)
+            .map_err(|error| error.to_string())
+            .and_then(|bytes| {
+                LedgerFlowAuthorizationExtension::decode_cbor(&bytes)
+                    .map_err(|error| error.to_string())
+            })
+            .map_err(|error| invalid(format!("invalid LedgerFlow extension: {error}")))?;
+        let http_body = decode_base64(&request.request.body)
+            .map_err(|error| invalid(format!("invalid request body: {error}")))?;
+
+        if extension.challenge_id != challenge.challenge_id {
+            return Err(VerifyOutcome::error(
+                VerifyStatus::Replayed,
+                "the payload did not echo the active challenge id".to_string(),
+            ));
+        }
+        if extension.signer.public_key != extension.proof.signer_key {
+            return Err(VerifyOutcome::error(
+                VerifyStatus::Unauthorized,
+                "the payload signer did not match the proof signer".to_string(),
+            ));
+        }
+        if extension.warrant_chain.is_empty() {
+            return Err(invalid("the warrant chain is empty".to_string()));
+        }
+        let mut chain = WarrantChain::default();
+        {
+            let warrants = state.warrants.lock().unwrap_or_else(PoisonError::into_inner);
+            for digest in &extension.parent_digests {
+                let parent = warrants
+                    .load(digest)
+                    .ok_or_else(|| invalid(format!("unknown warrant digest {digest}")))?;
+                chain.push(parent);
+            }
+        }
+        for warrant in &extension.warrant_chain {
+            chain.push(warrant.clone());
+        }
+
+        let accepted = AcceptedQuote {
+            scheme: body.accepted.scheme,
+            asset: body.accepted.asset,
+            amount,
+            payee_id: body.accepted.payee_id,
+            network: body.accepted.network,
+        };
+        let http_request = HttpRequest::new(
+            request.request.method,
+            request.request.authority,
+            request.request.path_and_query,
+            http_body,
+        );
+        let payload = PaymentPayload {
+            accepted,
+            settlement_payload: body.settlement_payload,
+            payment_identifier: body.payment_identifier,
+            ledgerflow: None,
+        };
+        let context = VerificationContextBuilder::new()
+            .with_tool_name(request.tool_name)
+            .with_model_provider(request.model_provider)
+            .with_action_label(request.action_label)
+            .build(
+                &challenge,
+                &http_request,
+                &payload,
+                &extension,
+                &canonical_request_hash(&http_request),
+                &canonical_accepted_hash(&payload.accepted),
+                now_ms,
+            );
+        Ok(Self {
+            challenge,
+            chain,
+            extension,
+            context,
+            // Bind the PoP to the concrete accepted quote (design §6.3).
+            payment_payload_digest: sha256_prefixed(payload.accepted.canonical()),
+            settlement_payload: payload.settlement_payload,
+            tool_arguments: request.tool_arguments,
+        })
+    }
+
+    /// Runs the full authorization pipeline against the tenant's trust
+    /// anchors and revocation view. A verified chain is stored so later
+    /// submissions (and remote verifiers) can reference it by digest.
+    fn verify(&self, state: &AppState, ctx: &crate::saas::SaaSContext) -> VerifyOutcome {
+        let trusted = match state.trusted_for(ctx) {
+            Ok(trusted) => trusted,
+            Err(error) => {
+                return VerifyOutcome::error(VerifyStatus::Unauthorized, error.to_string())
+            }
+        };
+        // The tenant's revocation view, with the server's resolvers.
+        let service = VerificationService {
+            revocation: state.revocation_store.for_tenant(&ctx.tenant_id),
+            approver_sets: state.verification.approver_sets.clone(),
+            contract_verifier: state.verification.contract_verifier.clone(),
+            identity_resolver: state.verification.identity_resolver.clone(),
+        };
+        let outcome = service.verify_payment(
+            &VerifyRequest {
+                chain: &self.chain,
+                trusted: &trusted,
+                proof: &self.extension.proof,
+                context: &self.context,
+                approvals: &self.extension.approvals,
+                tool_arguments: &self.tool_arguments,
+            },
+            self.payment_payload_digest.clone(),
+        );
+        if outcome.status.is_verified() {
+            let mut warrants = state.warrants.lock().unwrap_or_else(PoisonError::into_inner);
+            for warrant in &self.chain.warrants {
+                warrants.store(warrant.clone());
+            }
+        }
+        outcome
+    }
+
+    /// Opens a pending approval request when `outcome` failed on a gate that
+    /// fired for this call (or on a human-presence challenge), emitting
+    /// `approval.requested` the first time the request hash is seen.
+    fn request_approval(
+        &self,
+        state: &AppState,
+        ctx: &crate::saas::SaaSContext,
+        outcome: &VerifyOutcome,
+        now_ms: u64,
+    ) {
+        if outcome.status != VerifyStatus::InsufficientApproval {
+            return;
+        }
+        let Some(leaf) = self.chain.leaf() else {
+            return;
+        };
+        let gated = leaf
+            .approval_gates
+            .get(&self.context.tool_name)
+            .is_some_and(|gate| gate.fires(&self.tool_arguments));
+        if !gated && !self.context.human_present {
+            return;
+        }
+        // Nobody could ever satisfy a request without approvers.
+        let approvers = match effective_approvers(leaf, state.verification.approver_sets.as_deref())
+        {
+            Ok(approvers) if !approvers.is_empty() => approvers,
+            _ => return,
+        };
+        let now_secs = now_ms / 1_000;
+        let pending = PendingApproval::new(
+            ctx.tenant_id.clone(),
+            self.context.request_hash.clone(),
+            leaf.clone(),
+            approvers,
+            now_secs,
+        )
+        .with_tool(self.context.tool_name.clone(), self.tool_arguments.clone())
+        .with_payment(
+            self.context.selected_amount,
+            self.context.asset.clone(),
+            self.context.payee_id.clone(),
+        );
+        if state.approvals.open(pending, now_secs).unwrap_or(false) {
+            state.webhook.emit(WebhookEvent::ApprovalRequested {
+                tenant_id: ctx.tenant_id.clone(),
+                request_hash: self.context.request_hash.clone(),
+            });
+        }
+    }
+
+    /// The replay fingerprint of this payment's PoP nonce.
+    fn fingerprint(&self) -> ReplayFingerprint {
+        ReplayFingerprint {
+            challenge_id: self.challenge.challenge_id.clone(),
+            nonce: self.extension.proof.tuple.nonce.clone(),
+            request_hash: self.context.request_hash.clone(),
+            accepted_hash: self.context.accepted_hash.clone(),
+        }
+    }
+}
+
+/// Claims a PoP nonce; `false` when it was already claimed.
+fn claim_nonce(state: &AppState, fingerprint: ReplayFingerprint, now_ms: u64) -> bool {
+    state.settle_replay.lock().is_ok_and(|mut store| store.claim_nonce(fingerprint, now_ms).is_ok())
+}
+
+/// Releases a nonce claimed by [`claim_nonce`].
+fn release_nonce(state: &AppState, fingerprint: &ReplayFingerprint) {
+    state.settle_replay.lock().unwrap_or_else(PoisonError::into_inner).release_nonce(fingerprint);
+}
+
+impl SettleResponse {
+    fn rejected(outcome: VerifyOutcome) -> Self {
+        Self {
+            success: false,
+            status: SettlementStatus::Failed.as_str().to_string(),
+            verify_status: outcome.status.as_str().to_string(),
+            error_reason: outcome.reason,
+            payer: None,
+            transaction_id: None,
+            rail: None,
+            amount: None,
+            asset: None,
+        }
+    }
+}
+
+/// Decodes standard or URL-safe base64, padded or not.
+fn decode_base64(value: &str) -> Result<Vec<u8>, ledgerflow_protocol::ProtocolError> {
+    let normalized: String = value
+        .trim()
+        .trim_end_matches('=')
+        .chars()
+        .map(|c| match c {
+            '+' => '-',
+            '/' => '_',
+            other => other,
+        })
+        .collect();
+    base64url_decode(&normalized)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::expect_used)]
+
+    use super::*;
+
+    #[test]
+    fn decode_base64_accepts_standard_and_url_safe_forms() {
+        let bytes = [0xFB, 0xFF, 0xBF];
+        assert_eq!(decode_base64("+/+/").expect("standard"), bytes);
+        assert_eq!(decode_base64("-_-_").expect("url-safe"), bytes);
+        assert_eq!(decode_base64("aGk=").expect("padded"), b"hi");
+        assert!(decode_base64("!!").is_err());
+    }
+}
@@ -0,0 +1,645 @@
+//! Root-warrant issuance (`POST /v1/warrants`) and warrant resolution
+//! (`GET /v1/warrants/{digest}`).
+//!
+//! The request mirrors the full warrant constraint surface (design §4):
+//! merchant ids / host suffixes, resource methods / path prefixes, the payment
+//! constraint (assets, rails, schemes, payees, per-charge cap), an optional
+//! tool constraint, approval gates with their m-of-n approver set, delegation
+//! depth, and the reserved extensions (`IssueBounds`, budget policy, agent id,
+//! and the audit hints). Everything is validated before signing: a request
+//! that would produce an unusable or unbounded warrant is a 400, never a
+//! silently narrowed warrant.
+//!
+//! The response carries the signed warrant as base64url CBOR — the exact form
+//! agents embed in a LedgerFlow authorization extension.
+//!
+//! Every issued warrant is stored by digest, alongside the chains the hosted
+//! facilitator verified, so digest-referenced authorizations (the leaf
+//! inline, parents by digest) resolve against this server. The lookup is
+//! unauthenticated: a warrant is a signed, content-addressed document that
+//! agents already present in the clear to every merchant they pay.
+
+use std::{collections::BTreeMap, sync::PoisonError};
+
+use axum::{
+    Json,
+    extract::{Path, State},
+};
+use ledgerflow_core::{
+    AGENT_ID_EXTENSION_KEY, AgentIdRef, ApprovalGate, AssetRef, BUDGET_EXTENSION, BudgetPolicy,
+    DEFAULT_MAX_DEPTH, DEFAULT_WARRANT_TTL_SECS, ISSUE_BOUNDS_EXTENSION, IssueBounds,
+    MAX_DELEGATION_DEPTH, MAX_WARRANT_TTL_SECS, MerchantConstraint, PaymentConstraint, PaymentRail,
+    ResourceConstraint, SignerRef, SigningAlgorithm, ToolConstraint, Warrant, WarrantBuilder,
+};
+use ledgerflow_protocol::wire::base64url_encode;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    api::{ApiError, ApiResponse, now_ms},
+    state::AppState,
+    webhook::WebhookEvent,
+};
+
+/// Maximum per-charge cap the server will issue (base units). Requests above
+/// this ceiling are rejected (fail-closed). Tunable per deployment.
+const MAX_PER_CHARGE_CAP: u128 = u128::MAX / 1_000_000;
+
+// ---------------------------------------------------------------------------
+// Request / response bodies
+// ---------------------------------------------------------------------------
+
+/// Issue-warrant request body.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct IssueWarrantRequest {
+    /// Hex-encoded holder public key (the agent's key, never a secret).
+    pub holder_public_key: String,
+    /// Holder key algorithm: `ed25519` (default, 32 bytes), `secp256k1`
+    /// (33-byte compressed), `eth_personal_sign` / `eth_typed_data` (33-byte
+    /// compressed or 20-byte address).
+    #[serde(default)]
+    pub holder_algorithm: Option<String>,
+    /// Merchant allowlist; at least one id or host suffix is required.
+    pub merchant: MerchantBody,
+    /// Resource allowlist (empty = any method / path).
+    #[serde(default)]
+    pub resource: ResourceBody,
+    pub payment: PaymentBody,
+    #[serde(default)]
+    pub tool: Option<ToolBody>,
+    /// Approval gates keyed by tool name.
+    #[serde(default)]
+    pub approval_gates: BTreeMap<String, ApprovalGateBody>,
+    /// Keys that may approve gated executions.
+    #[serde(default)]
+    pub required_approvers: Vec<SignerBody>,
+    /// m-of-n threshold (0 = all required approvers).
+    #[serde(default)]
+    pub min_approvals: u32,
+    /// Maximum delegation depth (default 4, at most 8).
+    #[serde(default)]
+    pub max_depth: Option<u8>,
+    /// Lifetime in seconds (default 7 days, at most 90 days).
+    #[serde(default)]
+    pub ttl_secs: Option<u64>,
+    #[serde(default)]
+    pub extensions: ExtensionsBody,
+}
+
+/// Merchant allowlist.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct MerchantBody {
+    #[serde(default)]
+    pub merchant_ids: Vec<String>,
+    #[serde(default)]
+    pub host_suffixes: Vec<String>,
+}
+
+/// Resource allowlist.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct ResourceBody {
+    #[serde(default)]
+    pub http_methods: Vec<String>,
+    #[serde(default)]
+    pub path_prefixes: Vec<String>,
+}
+
+/// An allowed asset (CAIP-19 preferred).
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct AssetBody {
+    pub asset: String,
+    #[serde(default)]
+    pub network: Option<String>,
+}
+
+/// Payment constraint.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct PaymentBody {
+    /// Per-charge cap in base units.
+    pub max_per_charge: u128,
+    #[serde(default)]
+    pub allowed_assets: Vec<AssetBody>,
+    /// `onchain` | `exchange` | `custodial` | `traditional_gateway`.
+    #[serde(default)]
+    pub allowed_rails: Vec<String>,
+    #[serde(default)]
+    pub allowed_schemes: Vec<String>,
+    #[serde(default)]
+    pub payee_ids: Vec<String>,
+}
+
+/// AI tool constraint.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct ToolBody {
+    #[serde(default)]
+    pub tool_names: Vec<String>,
+    #[serde(default)]
+    pub model_providers: Vec<String>,
+    #[serde(default)]
+    pub action_labels: Vec<String>,
+}
+
+/// Approval gate (empty constraints = every invocation needs approval).
+#[derive(Clone, Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct ApprovalGateBody {
+    #[serde(default)]
+    pub argument_constraints: BTreeMap<String, String>,
+}
+
+/// A public signer key.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct SignerBody {
+    /// Hex-encoded public key.
+    pub public_key: String,
+    /// Key algorithm (see [`IssueWarrantRequest::holder_algorithm`]).
+    #[serde(default)]
+    pub algorithm: Option<String>,
+    #[serde(default)]
+    pub key_id: Option<String>,
+}
+
+/// Reserved warrant extensions.
+#[derive(Clone, Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct ExtensionsBody {
+    /// EIP-8004 agent reference (`eip155:<chain>:<registry>/<id>`).
+    #[serde(default)]
+    pub agent_id: Option<String>,
+    #[serde(default)]
+    pub session_id: Option<String>,
+    #[serde(default)]
+    pub client_id: Option<String>,
+    #[serde(default)]
+    pub merchant_display_name: Option<String>,
+    /// Limits on what the holder may delegate.
+    #[serde(default)]
+    pub issue_bounds: Option<IssueBoundsBody>,
+    /// Accounting-point budget policy.
+    #[serde(default)]
+    pub budget: Option<BudgetBody>,
+}
+
+/// Issuance bounds (every list is a ceiling; empty = unrestricted).
+#[derive(Clone, Debug, Default, Serialize, Deserialize, ToSchema)]
+pub struct IssueBoundsBody {
+    #[serde(default)]
+    pub merchant_ids: Vec<String>,
+    #[serde(default)]
+    pub host_suffixes: Vec<String>,
+    #[serde(default)]
+    pub http_methods: Vec<String>,
+    #[serde(default)]
+    pub path_prefixes: Vec<String>,
+    #[serde(default)]
+    pub assets: Vec<AssetBody>,
+    #[serde(default)]
+    pub rails: Vec<String>,
+    #[serde(default)]
+    pub schemes: Vec<String>,
+    #[serde(default)]
+    pub payee_ids: Vec<String>,
+    #[serde(default)]
+    pub max_per_charge: Option<u128>,
+    #[serde(default)]
+    pub max_issue_depth: Option<u8>,
+}
+
+/// Budget policy enforced by an accounting point.
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct BudgetBody {
+    pub ledger: String,
+    #[serde(default)]
+    pub per_hour: Option<u128>,
+    #[serde(default)]
+    pub per_day: Option<u128>,
+    #[serde(default)]
+    pub per_month: Option<u128>,
+    #[serde(default)]
+    pub lifetime: Option<u128>,
+}
+
+/// Signed-warrant response body (issuance and `GET /v1/warrants/{digest}`).
+#[derive(Clone, Debug, Serialize, Deserialize, ToSchema)]
+pub struct IssueWarrantResponse {
+    pub warrant_id: String,
+    pub digest: String,
+    pub issued_at: u64,
+    pub expires_at: u64,
+    /// base64url (unpadded) CBOR of the signed warrant.
+    pub warrant: String,
+}
+
+// ---------------------------------------------------------------------------
+// Handler
+// ---------------------------------------------------------------------------
+
+/// Issues a root warrant for a holder.
+#[utoipa::path(
+    post,
+    path = "/v1/warrants",
+    request_body = IssueWarrantRequest,
+    responses(
+        (status = 200, description = "Warrant issued", body = IssueWarrantResponse),
+        (status = 400, description = "Bad request"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub(crate) async fn issue_warrant(
+    State(state): State<AppState>,
+    ctx: crate::saas::SaaSContext,
+    Json(request): Json<IssueWarrantRequest>,
+) -> Result<Json<ApiResponse<IssueWarrantResponse>>, ApiError> {
+    // Each tenant signs with its own root (design §6.8).
+    let (signer, issuer) = state.issuer_for(&ctx)?;
+    let draft = request.into_builder(now_ms())?.issuer(issuer).build_unsigned(random_bytes());
+    let warrant = signer.sign(draft).await?;
+    let response = IssueWarrantResponse::from_warrant(&warrant)?;
+    state.warrants.lock().unwrap_or_else(PoisonError::into_inner).store(warrant);
+    state.webhook.emit(WebhookEvent::WarrantIssued {
+        tenant_id: ctx.tenant_id,
+        warrant_id: response.warrant_id.clone(),
+    });
+    Ok(Json(ApiResponse::ok(response)))
+}
+
+/// Resolves a stored warrant by digest (digest-referenced chains).
+#[utoipa::path(
+    get,
+    path = "/v1/warrants/{digest}",
+    params(("digest" = String, Path, description = "Warrant digest (`sha256:<hex>`)")),
+    responses(
+        (status = 200, description = "Stored warrant", body = IssueWarrantResponse),
+        (status = 404, description = "Unknown digest")
+    )
+)]
+pub(crate) async fn lookup_warrant(
+    State(state): State<AppState>,
+    Path(digest): Path<String>,
+) -> Result<Json<ApiResponse<IssueWarrantResponse>>, ApiError> {
+    let warrant = state
+        .warrants
+        .lock()
+        .unwrap_or_else(PoisonError::into_inner)
+        .load(&digest)
+        .ok_or(ApiError::NotFound)?;
+    Ok(Json(ApiResponse::ok(IssueWarrantResponse::from_warrant(&warrant)?)))
+}
+
+impl IssueWarrantResponse {
+    fn from_warrant(warrant: &Warrant) -> Result<Self, ApiError> {
+        let encoded =
+            warrant.encode_cbor().map_err(|error| ApiError::Internal(error.to_string()))?;
+        Ok(Self {
+            warrant_id: warrant.id_hex(),
+            digest: warrant.digest(),
+            issued_at: warrant.issued_at,
+            expires_at: warrant.expires_at,
+            warrant: base64url_encode(&encoded),
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Validation
+// ---------------------------------------------------------------------------
+
+type IssuerlessBuilder = WarrantBuilder<
+    ledgerflow_core::typestate::NoIssuer,
+    ledgerflow_core::typestate::HasHolder,
+    ledgerflow_core::typestate::Unsigned,
+>;
+
+impl IssueWarrantRequest {
+    /// Validates the request and returns a builder holding every constraint
+    /// (the issuer is chosen by the caller).
+    fn into_builder(self, now_ms: u64) -> Result<IssuerlessBuilder, ApiError> {
+        let holder = parse_signer(&self.holder_public_key, self.holder_algorithm.as_deref(), None)
+            .map_err(|reason| bad_request(format!("holder_public_key: {reason}")))?;
+
+        if self.merchant.merchant_ids.is_empty() && self.merchant.host_suffixes.is_empty() {
+            return Err(bad_request("merchant must list at least one id or host suffix"));
+        }
+        if self.payment.max_per_charge == 0 {
+            return Err(bad_request("payment.max_per_charge must be positive"));
+        }
+        // Design §6.1 hard caps are enforced server-side.
+        if self.payment.max_per_charge > MAX_PER_CHARGE_CAP {
+            return Err(bad_request(format!(
+                "payment.max_per_charge exceeds the maximum allowed ({MAX_PER_CHARGE_CAP})"
+            )));
+        }
+        let ttl_secs = self.ttl_secs.unwrap_or(DEFAULT_WARRANT_TTL_SECS);
+        if ttl_secs == 0 || ttl_secs > MAX_WARRANT_TTL_SECS {
+            return Err(bad_request(format!("ttl_secs must be in 1..={MAX_WARRANT_TTL_SECS}")));
+        }
+        let max_depth = self.max_depth.unwrap_or(DEFAULT_MAX_DEPTH);
+        if max_depth > MAX_DELEGATION_DEPTH {
+            return Err(bad_request(format!("max_depth must be at most {MAX_DELEGATION_DEPTH}")));
+        }
+
+        let approvers = self
+            .required_approvers
+            .iter()
+            .enumerate()
+            .map(|(index, approver)| {
+                parse_signer(
+                    &approver.public_key,
+                    approver.algorithm.as_deref(),
+                    approver.key_id.clone(),
+                )
+                .map_err(|reason| bad_request(format!("required_approvers[{index}]: {reason}")))
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        if !self.approval_gates.is_empty() && approvers.is_empty() {
+            return Err(bad_request("approval_gates require at least one required approver"));
+        }
+        if self.min_approvals as usize > approvers.len() {
+            return Err(bad_request("min_approvals exceeds the number of required approvers"));
+        }
+
+        let payment = PaymentConstraint {
+            allowed_assets: self
+                .payment
+                .allowed_assets
+                .into_iter()
+                .map(AssetBody::into_asset)
+                .collect(),
+            max_per_charge: self.payment.max_per_charge,
+            allowed_rails: parse_rails(&self.payment.allowed_rails, "payment.allowed_rails")?,
+            allowed_schemes: self.payment.allowed_schemes,
+            payee_ids: self.payment.payee_ids,
+        };
+
+        let mut builder = WarrantBuilder::new(now_ms)
+            .ttl_secs(ttl_secs)
+            .max_depth(max_depth)
+            .holder(holder)
+            .merchant(MerchantConstraint {
+                merchant_ids: self.merchant.merchant_ids,
+                host_suffixes: self.merchant.host_suffixes,
+            })
+            .resource(ResourceConstraint {
+                http_methods: self.resource.http_methods,
+                path_prefixes: self.resource.path_prefixes,
+            })
+            .payment(payment)
+            .min_approvals(self.min_approvals);
+        if let Some(tool) = self.tool {
+            builder = builder.tool(ToolConstraint {
+                tool_names: tool.tool_names,
+                model_providers: tool.model_providers,
+                action_labels: tool.action_labels,
+            });
+        }
+        for (tool, gate) in self.approval_gates {
+            builder = builder.approval_gate(
+                tool,
+                ApprovalGate { argument_constraints: gate.argument_constraints },
+            );
+        }
+        for approver in approvers {
+            builder = builder.approver(approver);
+        }
+        for (key, value) in self.extensions.into_entries()? {
+            builder = builder.extension(key, value);
+        }
+        Ok(builder)
+    }
+}
+
+impl AssetBody {
+    fn into_asset(self) -> AssetRef {
+        AssetRef::new(self.asset, self.network)
+    }
+}
+
+impl ExtensionsBody {
+    /// Encodes the populated extensions under their reserved keys.
+    fn into_entries(self) -> Result<Vec<(&'static str, Vec<u8>)>, ApiError> {
+        let mut entries = Vec::new();
+        if let Some(agent_id) = self.agent_id {
+            let agent = AgentIdRef::parse(&agent_id)
+                .map_err(|error| bad_request(format!("extensions.agent_id: {error}")))?;
+            entries.push((AGENT_ID_EXTENSION_KEY, agent.to_string().into_bytes()));
+        }
+        for (key, value) in [
+            ("ledgerflow.session_id", self.session_id),
+            ("ledgerflow.client_id", self.client_id),
+            ("ledgerflow.merchant_display_name", self.merchant_display_name),
+        ] {
+            if let Some(value) = value {
+                entries.push((key, value.into_bytes()));
+            }
+        }
+        if let Some(bounds) = self.issue_bounds {
+            let bounds = IssueBounds {
+                merchant_ids: bounds.merchant_ids,
+                host_suffixes: bounds.host_suffixes,
+                http_methods: bounds.http_methods,
+                path_prefixes: bounds.path_prefixes,
+                assets: bounds.assets.into_iter().map(AssetBody::into_asset).collect(),
+                rails: parse_rails(&bounds.rails, "extensions.issue_bounds.rails")?,
+                schemes: bounds.schemes,
+                payee_ids: bounds.payee_ids,
+                max_per_charge: bounds.max_per_charge,
+                max_issue_depth: bounds.max_issue_depth,
+            };
+            let encoded =
+                bounds.encode_cbor().map_err(|error| ApiError::Internal(error.to_string()))?;
+            entries.push((ISSUE_BOUNDS_EXTENSION, encoded));
+        }
+        if let Some(budget) = self.budget {
+            if budget.ledger.is_empty() {
+                return Err(bad_request("extensions.budget.ledger must not be empty"));
+            }
+            let policy = BudgetPolicy {
+                ledger: budget.ledger,
+                per_hour: budget.per_hour,
+                per_day: budget.per_day,
+                per_month: budget.per_month,
+                lifetime: budget.lifetime,
+            };
+            let encoded =
+                policy.encode_cbor().map_err(|error| ApiError::Internal(error.to_string()))?;
+            entries.push((BUDGET_EXTENSION, encoded));
+        }
+        Ok(entries)
+    }
+}
+
+/// Parses a hex public key for `algorithm` (default `ed25519`), checking the
+/// key length the algorithm expects.
+pub(crate) fn parse_signer(
+    public_key_hex: &str,
+    algorithm: Option<&str>,
+    key_id: Option<String>,
+) -> Result<SignerRef, String> {
+    let (alg, lengths): (SigningAlgorithm, &[usize]) = match algorithm.unwrap_or("ed25519") {
+        "ed25519" => (SigningAlgorithm::Ed25519, &[32]),
+        "secp256k1" => (SigningAlgorithm::Secp256k1, &[33]),
+        "eth_personal_sign" => (SigningAlgorithm::EthPersonalSign, &[33, 20]),
+        "eth_typed_data" => (SigningAlgorithm::EthTypedData, &[33, 20]),
+        other => return Err(format!("unsupported algorithm `{other}`")),
+    };
+    let hex = public_key_hex.trim().trim_start_matches("0x");
+    let bytes = decode_hex_vec(hex).ok_or_else(|| "invalid hex".to_string())?;
+    if !lengths.contains(&bytes.len()) {
+        return Err(format!("{} keys must be {lengths:?} bytes, got {}", alg.as_str(), bytes.len()));
+    }
+    let signer = SignerRef::new(alg, bytes);
+    Ok(match key_id {
+        Some(key_id) => signer.with_key_id(key_id),
+        None => signer,
+    })
+}
+
+fn parse_rails(rails: &[String], field: &str) -> Result<Vec<PaymentRail>, ApiError> {
+    rails
+        .iter()
+        .map(|rail| match rail.as_str() {
+            "onchain" => Ok(PaymentRail::Onchain),
+            "exchange" => Ok(PaymentRail::Exchange),
+            "custodial" => Ok(PaymentRail::Custodial),
+            "traditional_gateway" => Ok(PaymentRail::TraditionalGateway),
+            other => Err(bad_request(format!("{field}: unknown rail `{other}`"))),
+        })
+        .collect()
+}
+
+pub(crate) fn decode_hex_vec(hex: &str) -> Option<Vec<u8>> {
+    if !hex.len().is_multiple_of(2) {
+        return None;
+    }
+    (0..hex.len()).step_by(2).map(|i| u8::from_str_radix(hex.get(i..i + 2)?, 16).ok()).collect()
+}
+
+/// Generates a cryptographically random nonce for warrant issuance.
+fn random_bytes() -> [u8; 8] {
+    rand::random()
+}
+
+fn bad_request(message: impl Into<String>) -> ApiError {
+    ApiError::BadRequest(message.into())
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::expect_used)]
+
+    use ledgerflow_core::{SigningKeyPair, agent_id_from_warrant};
+
+    use super::*;
+
+    fn request(value: serde_json::Value) -> IssueWarrantRequest {
+        serde_json::from_value(value).expect("request")
+    }
+
+    fn holder_hex() -> String {
+        ledgerflow_core::hex_encode_bytes(
+            &SigningKeyPair::from_bytes(&[2_u8; 32]).public_key_bytes(),
+        )
+    }
+
+    #[test]
+    fn full_constraint_surface_is_carried_into_the_warrant() {
+        let issuer = SigningKeyPair::from_bytes(&[1_u8; 32]);
+        let approver = ledgerflow_core::hex_encode_bytes(
+            &SigningKeyPair::from_bytes(&[3_u8; 32]).public_key_bytes(),
+        );
+        let warrant = request(serde_json::json!({
+            "holder_public_key": holder_hex(),
+            "merchant": { "merchant_ids": ["merchant-a"], "host_suffixes": [".example.com"] },
+            "resource": { "http_methods": ["GET"], "path_prefixes": ["/search"] },
+            "payment": {
+                "max_per_charge": 500,
+                "allowed_assets": [{ "asset": "USDC", "network": "eip155:8453" }],
+                "allowed_rails": ["onchain"],
+                "allowed_schemes": ["exact"],
+                "payee_ids": ["merchant-a"]
+            },
+            "tool": { "tool_names": ["web-search"] },
+            "approval_gates": { "web-search": { "argument_constraints": { "tier": "premium" } } },
+            "required_approvers": [{ "public_key": approver }],
+            "min_approvals": 1,
+            "max_depth": 2,
+            "ttl_secs": 3600,
+            "extensions": {
+                "agent_id": "eip155:1:0x8004a169fb4a3325136eb29fa0ceb6d2e539a432/22",
+                "issue_bounds": { "max_per_charge": 100, "rails": ["onchain"] },
+                "budget": { "ledger": "ledger-1", "per_day": 1000 }
+            }
+        }))
+        .into_builder(1_000_000)
+        .expect("valid")
+        .issuer(issuer.signer_ref())
+        .sign_with(&issuer, [0_u8; 8]);
+
+        assert!(warrant.verify_signature());
+        assert_eq!(warrant.holder, SigningKeyPair::from_bytes(&[2_u8; 32]).signer_ref());
+        assert_eq!(warrant.merchant.host_suffixes, vec![".example.com".to_string()]);
+        assert_eq!(warrant.resource.path_prefixes, vec!["/search".to_string()]);
+        assert_eq!(warrant.payment.allowed_rails, vec![PaymentRail::Onchain]);
+        assert_eq!(
+            warrant.payment.allowed_assets,
+            vec![AssetRef::new("USDC", Some("eip155:8453".to_string()))]
+        );
+        assert_eq!(warrant.tool.as_ref().expect("tool").tool_names, vec!["web-search".to_string()]);
+        assert!(warrant.approval_gates.contains_key("web-search"));
+        assert_eq!((warrant.required_approvers.len(), warrant.min_approvals), (1, 1));
+        assert_eq!(warrant.max_depth, 2);
+        assert_eq!(warrant.expires_at - warrant.issued_at, 3600);
+        assert!(agent_id_from_warrant(&warrant).expect("agent id").is_some());
+        assert_eq!(warrant.issue_bounds().expect("bounds").max_per_charge, Some(100));
+        assert_eq!(warrant.budget_policy().expect("budget").expect("policy").per_day, Some(1000));
+
+        let response = IssueWarrantResponse::from_warrant(&warrant).expect("response");
+        let decoded = ledgerflow_protocol::wire::base64url_decode(&response.warrant).expect("b64");
+        assert_eq!(Warrant::decode_cbor(&decoded).expect("cbor"), warrant);
+    }
+
+    #[test]
+    fn holder_key_is_a_public_key_not_a_seed() {
+        let issuer = SigningKeyPair::from_bytes(&[1_u8; 32]);
+        let public_key = SigningKeyPair::from_bytes(&[2_u8; 32]).public_key_bytes();
+        let warrant = request(serde_json::json!({
+            "holder_public_key": ledgerflow_core::hex_encode_bytes(&public_key),
+            "merchant": { "merchant_ids": ["merchant-a"] },
+            "payment": { "max_per_charge": 1 }
+        }))
+        .into_builder(1_000_000)
+        .expect("valid")
+        .issuer(issuer.signer_ref())
+        .sign_with(&issuer, [0_u8; 8]);
+        assert_eq!(warrant.holder.public_key, public_key.to_vec());
+    }
+
+    #[test]
+    fn unusable_or_unbounded_requests_are_rejected() {
+        let base = serde_json::json!({
+            "holder_public_key": holder_hex(),
+            "merchant": { "merchant_ids": ["merchant-a"] },
+            "payment": { "max_per_charge": 1 }
+        });
+        let invalid = [
+            ("/merchant", serde_json::json!({})),
+            ("/payment", serde_json::json!({ "max_per_charge": 0 })),
+            ("/payment", serde_json::json!({ "max_per_charge": 1, "allowed_rails": ["wire"] })),
+            ("/holder_public_key", serde_json::json!("abcd")),
+            ("/holder_algorithm", serde_json::json!("rsa")),
+            ("/max_depth", serde_json::json!(9)),
+            ("/ttl_secs", serde_json::json!(0)),
+            ("/min_approvals", serde_json::json!(1)),
+            ("/approval_gates", serde_json::json!({ "web-search": {} })),
+            ("/extensions", serde_json::json!({ "agent_id": "not-an-agent" })),
+        ];
+        for (pointer, value) in invalid {
+            let mut body = base.clone();
+            let field = pointer.trim_start_matches('/');
+            body[field] = value;
+            assert!(
+                matches!(request(body).into_builder(1_000_000), Err(ApiError::BadRequest(_))),
+                "{pointer} should be rejected"
+            );
+        }
+        assert!(request(base).into_builder(1_000_000).is_ok());
+    }
+}
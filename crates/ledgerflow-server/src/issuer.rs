@@ -0,0 +1,62 @@
+//! Warrant issuer signing (design §6.8).
+//!
+//! The process issuer either holds its root key in memory
+//! (`LEDGERFLOW_ISSUER_KEY`) or delegates every signature to a wallet daemon
+//! over local JSON-RPC (`LEDGERFLOW_ISSUER_WALLET_URL`), so the root key can
+//! live in a hardened signer and never enters the server. Tenant keys
+//! ([`crate::tenant_keys`]) are always in-memory.
+
+use std::sync::Arc;
+
+use ledgerflow_core::{SignerRef, SigningKeyPair, Warrant};
+use ledgerflow_wallet::{WalletError, WalletSigner, sign_warrant};
+
+use crate::api::ApiError;
+
+/// Signs the warrants this server issues.
+#[derive(Clone)]
+pub enum IssuerSigner {
+    /// A key held in process memory.
+    Local(SigningKeyPair),
+    /// A wallet holding the private key of `issuer`.
+    Wallet { wallet: Arc<dyn WalletSigner>, issuer: SignerRef },
+}
+
+impl IssuerSigner {
+    /// Signs with a wallet key; `issuer` is the public key the wallet holds.
+    #[must_use]
+    pub fn wallet(wallet: Arc<dyn WalletSigner>, issuer: SignerRef) -> Self {
+        Self::Wallet { wallet, issuer }
+    }
+
+    /// The `SignerRef` warrants issued by this signer name as issuer.
+    #[must_use]
+    pub fn signer_ref(&self) -> SignerRef {
+        match self {
+            Self::Local(keys) => keys.signer_ref(),
+            Self::Wallet { issuer, .. } => issuer.clone(),
+        }
+    }
+
+    /// Signs an unsigned warrant.
+    ///
+    /// Wallet calls block on the wallet transport, so they run on the blocking
+    /// pool rather than on the request's worker thread.
+    pub async fn sign(&self, draft: Warrant) -> Result<Warrant, WalletError> {
+        match self {
+            Self::Local(keys) => Ok(draft.sign_with(keys)),
+            Self::Wallet { wallet, .. } => {
+                let wallet = Arc::clone(wallet);
+                tokio::task::spawn_blocking(move || sign_warrant(wallet.as_ref(), draft))
+                    .await
+                    .map_err(|error| WalletError::Transport(error.to_string()))?
+            }
+        }
+    }
+}
+
+impl From<WalletError> for ApiError {
+    fn from(error: WalletError) -> Self {
+        Self::Internal(format!("issuer wallet: {error}"))
+    }
+}
@@ -6,22 +6,59 @@
 //!
 //! - `[saas]` mode (`standalone` | `saas`) with fail-fast configuration.
 //! - REST endpoints for warrant issuance / revocation / audit / settlement.
+//! - Signed Revocation List publication for facilitator replicas.
+//! - Hosted facilitator endpoints (`/v1/verify`, `/v1/settle`, `/v1/status`).
 //! - SaaS internal-header protocol (trusts only gateway-injected headers).
+//! - Per-tenant issuer keys and trust anchors.
+//! - Warrant issuance through a local RPC wallet holding the root key.
+//! - Human approval inbox for gated payments.
 //! - Webhook event emission.
+//! - Optional SQLite persistence (feature `sqlite`).
 
 #![allow(missing_docs)]
 #![allow(missing_debug_implementations)]
 
 pub mod api;
+pub mod approvals;
 pub mod config;
+pub mod facilitator;
+pub mod issuance;
+pub mod issuer;
 pub mod saas;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+pub mod srl;
 pub mod state;
+pub mod tenant_keys;
+pub mod trust_anchors;
 pub mod webhook;
 
+#[cfg(feature = "sqlite")]
+pub use crate::sqlite::{
+    SqliteReplayStore, SqliteStore, SqliteStoreError, SqliteTenantRevocationView,
+};
 pub use crate::{
     api::{ApiError, ApiResponse, router},
-    config::{SaasMode, ServerConfig},
+    approvals::{
+        ApprovalBody, ApprovalError, ApprovalInbox, ApprovalRequestView, ApprovalStore,
+        PendingApproval, SharedApprovalStore,
+    },
+    config::{IssuerWalletConfig, SaasMode, ServerConfig},
+    facilitator::{
+        FacilitatorRequest, PaymentPayloadBody, SettleResponse, SettlementView, VerifyResponse,
+    },
+    issuance::{IssueWarrantRequest, IssueWarrantResponse},
+    issuer::IssuerSigner,
     saas::{SaaSContext, SaasAuthError, SaasAuthExtractor, saas_auth_middleware},
-    state::{AppState, NewAppState, ServerStateError},
+    srl::{SRL_COMPACTION_GRACE_SECS, SRL_REPUBLISH_SECS, SrlCache, SrlQuery, SrlResponse},
+    state::{
+        AppState, NewAppState, ServerStateError, SharedReplayStore, SharedWarrantRepository,
+        load_issuer, load_trusted_issuers, store_trusted_issuers,
+    },
+    tenant_keys::{
+        SharedTenantKeyStore, TenantIssuerKey, TenantKeyError, TenantKeyInfo, TenantKeyRegistry,
+        TenantKeyStore,
+    },
+    trust_anchors::{TrustAnchorBody, TrustAnchorsRequest},
     webhook::{WebhookEvent, WebhookSender},
 };
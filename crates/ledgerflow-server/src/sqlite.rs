@@ -0,0 +1,1513 @@
+//! SQLite persistence (feature `sqlite`; design §11.3).
+//!
+//! [`SqliteStore`] keeps every piece of facilitator state that must survive a
+//! restart in one database file:
+//!
+//! - revocations of every scope, global and tenant-scoped (design §6.6 / §10.2) —
+//!   [`RevocationStore`], [`RevocationCheck`] and [`SqliteStore::for_tenant`];
+//! - settlement outcomes for idempotent `/status` queries — [`SettlementStore`];
+//! - nonce claims and payment-id idempotency — [`SqliteReplayStore`];
+//! - issued / cached warrants keyed by digest — [`WarrantRepository`];
+//! - per-tenant issuer keyrings, secret keys included — [`TenantKeyStore`];
+//! - pending human approval requests — [`ApprovalStore`].
+//!
+//! The replay store and warrant repository also implement the async seams
+//! (`AsyncReplayStore`, `AsyncWarrantRepository`) for a shared
+//! `AsyncMerchantVerifier`; those run each query on the blocking pool.
+//!
+//! The schema is versioned through `PRAGMA user_version`; [`MIGRATIONS`] are
+//! applied in order, each in its own transaction, when the store is opened.
+//! A database written by a newer schema is refused rather than downgraded.
+//!
+//! The storage seams are infallible, so database failures are logged and the
+//! security-relevant answers fail closed: an unreadable revocation row reads
+//! as revoked and an unwritable nonce claim reads as a replay.
+
+use std::{
+    collections::BTreeMap,
+    path::Path,
+    sync::{Arc, Mutex},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use ledgerflow_core::{
+    CborCodec, PaymentSubjectRef, RevocationCheck, RevocationDecision, SignerRef, SigningKeyPair,
+    SrlEntry, VerifiedAuthorization, Warrant, hex_encode_bytes, subject_revocation_key,
+};
+use ledgerflow_facilitator::{
+    RailKind, RegistryEntry, RevocationStore, RevocationStoreError, SettlementReceipt,
+    SettlementStatus, SettlementStore, SharedRevocationCheck, tenant_scoped_key,
+};
+use ledgerflow_protocol::{ReplayConflict, ReplayFingerprint, ReplayStore, WarrantRepository};
+use rusqlite::{Connection, OptionalExtension, params};
+
+use crate::{
+    approvals::{ApprovalError, ApprovalStore, PendingApproval},
+    tenant_keys::{TenantIssuerKey, TenantKeyError, TenantKeyStore},
+};
+
+/// Schema migrations; entry `n` upgrades `user_version` from `n` to `n + 1`.
+pub const MIGRATIONS: &[&str] = &[
+    // 1: revocations, settlements, replay state, warrants.
+    "CREATE TABLE revocations (
+        scope TEXT NOT NULL CHECK (scope IN ('warrant', 'holder')),
+        tenant_id TEXT NOT NULL,
+        key BLOB NOT NULL,
+        revoked_at_ms INTEGER NOT NULL,
+        PRIMARY KEY (scope, tenant_id, key)
+    );
+    CREATE TABLE settlements (
+        transaction_id TEXT PRIMARY KEY,
+        rail TEXT NOT NULL,
+        settled_amount TEXT NOT NULL,
+        asset TEXT NOT NULL,
+        status TEXT NOT NULL
+    );
+    CREATE TABLE settlement_warrants (
+        seq INTEGER PRIMARY KEY AUTOINCREMENT,
+        warrant_digest TEXT NOT NULL,
+        transaction_id TEXT NOT NULL REFERENCES settlements (transaction_id),
+        UNIQUE (warrant_digest, transaction_id)
+    );
+    CREATE TABLE nonce_claims (
+        challenge_id TEXT NOT NULL,
+        nonce TEXT NOT NULL,
+        request_hash TEXT NOT NULL,
+        accepted_hash TEXT NOT NULL,
+        created_at_ms INTEGER NOT NULL,
+        PRIMARY KEY (challenge_id, nonce)
+    );
+    CREATE INDEX nonce_claims_created_at ON nonce_claims (created_at_ms);
+    CREATE TABLE payment_results (
+        payment_identifier TEXT PRIMARY KEY,
+        request_hash TEXT NOT NULL,
+        accepted_hash TEXT NOT NULL,
+        authorization_cbor BLOB NOT NULL
+    );
+    CREATE TABLE warrants (
+        digest TEXT PRIMARY KEY,
+        warrant_cbor BLOB NOT NULL,
+        stored_at_ms INTEGER NOT NULL
+    );",
+    // 2: tenant issuer keyrings.
+    "CREATE TABLE tenant_issuer_keys (
+        tenant_id TEXT NOT NULL,
+        position INTEGER NOT NULL,
+        key_id TEXT NOT NULL,
+        secret_key BLOB NOT NULL,
+        created_at_ms INTEGER NOT NULL,
+        active INTEGER NOT NULL,
+        not_after INTEGER,
+        PRIMARY KEY (tenant_id, position)
+    );",
+    // 3: pending approval requests.
+    "CREATE TABLE pending_approvals (
+        tenant_id TEXT NOT NULL,
+        request_hash TEXT NOT NULL,
+        expires_at INTEGER NOT NULL,
+        request_json TEXT NOT NULL,
+        PRIMARY KEY (tenant_id, request_hash)
+    );
+    CREATE INDEX pending_approvals_expires_at ON pending_approvals (expires_at);",
+    // 4: settlement outcomes keyed by tenant; earlier rows belong to no tenant.
+    "CREATE TABLE settlements_v2 (
+        tenant_id TEXT NOT NULL,
+        transaction_id TEXT NOT NULL,
+        rail TEXT NOT NULL,
+        settled_amount TEXT NOT NULL,
+        asset TEXT NOT NULL,
+        status TEXT NOT NULL,
+        PRIMARY KEY (tenant_id, transaction_id)
+    );
+    CREATE TABLE settlement_warrants_v2 (
+        seq INTEGER PRIMARY KEY AUTOINCREMENT,
+        tenant_id TEXT NOT NULL,
+        warrant_digest TEXT NOT NULL,
+        transaction_id TEXT NOT NULL,
+        UNIQUE (tenant_id, warrant_digest, transaction_id),
+        FOREIGN KEY (tenant_id, transaction_id)
+            REFERENCES settlements_v2 (tenant_id, transaction_id)
+    );
+    INSERT INTO settlements_v2 (tenant_id, transaction_id, rail, settled_amount, asset, status)
+        SELECT '', transaction_id, rail, settled_amount, asset, status FROM settlements;
+    INSERT INTO settlement_warrants_v2 (tenant_id, warrant_digest, transaction_id)
+        SELECT '', warrant_digest, transaction_id FROM settlement_warrants ORDER BY seq;
+    DROP TABLE settlement_warrants;
+    DROP TABLE settlements;
+    ALTER TABLE settlements_v2 RENAME TO settlements;
+    ALTER TABLE settlement_warrants_v2 RENAME TO settlement_warrants;",
+    // 5: an append order for SRLs; its `sqlite_sequence` row is the SRL version.
+    "CREATE TABLE revocations_v2 (
+        seq INTEGER PRIMARY KEY AUTOINCREMENT,
+        scope TEXT NOT NULL CHECK (scope IN ('warrant', 'holder')),
+        tenant_id TEXT NOT NULL,
+        key BLOB NOT NULL,
+        revoked_at_ms INTEGER NOT NULL,
+        UNIQUE (scope, tenant_id, key)
+    );
+    INSERT INTO revocations_v2 (scope, tenant_id, key, revoked_at_ms)
+        SELECT scope, tenant_id, key, revoked_at_ms FROM revocations ORDER BY rowid;
+    DROP TABLE revocations;
+    ALTER TABLE revocations_v2 RENAME TO revocations;",
+    // 6: warrant expiries, so compacted SRLs can drop expired warrants.
+    "ALTER TABLE revocations ADD COLUMN expires_at INTEGER;",
+    // 7: subject, issuer and agent revocation scopes.
+    "CREATE TABLE revocations_v2 (
+        seq INTEGER PRIMARY KEY AUTOINCREMENT,
+        scope TEXT NOT NULL
+            CHECK (scope IN ('warrant', 'holder', 'subject', 'issuer', 'agent')),
+        tenant_id TEXT NOT NULL,
+        key BLOB NOT NULL,
+        revoked_at_ms INTEGER NOT NULL,
+        expires_at INTEGER,
+        UNIQUE (scope, tenant_id, key)
+    );
+    INSERT INTO revocations_v2 (seq, scope, tenant_id, key, revoked_at_ms, expires_at)
+        SELECT seq, scope, tenant_id, key, revoked_at_ms, expires_at FROM revocations;
+    DROP TABLE revocations;
+    ALTER TABLE revocations_v2 RENAME TO revocations;",
+    // 8: warrant ids, so revocations can take a stored warrant's expiry;
+    // earlier rows have none.
+    "ALTER TABLE warrants ADD COLUMN warrant_id BLOB;
+    CREATE INDEX warrants_warrant_id ON warrants (warrant_id);",
+];
+
+/// Tenant id recorded for global (unscoped) revocations.
+const GLOBAL_TENANT: &str = "";
+
+/// The revocation scopes of design §6.6, as stored in `revocations.scope`.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+enum Scope {
+    Warrant,
+    Holder,
+    Subject,
+    Issuer,
+    Agent,
+}
+
+impl Scope {
+    const fn as_str(self) -> &'static str {
+        match self {
+            Self::Warrant => "warrant",
+            Self::Holder => "holder",
+            Self::Subject => "subject",
+            Self::Issuer => "issuer",
+            Self::Agent => "agent",
+        }
+    }
+
+    fn from_str(value: &str) -> Option<Self> {
+        match value {
+            "warrant" => Some(Self::Warrant),
+            "holder" => Some(Self::Holder),
+            "subject" => Some(Self::Subject),
+            "issuer" => Some(Self::Issuer),
+            "agent" => Some(Self::Agent),
+            _ => None,
+        }
+    }
+
+    const fn revoked(self) -> RevocationDecision {
+        match self {
+            Self::Warrant => RevocationDecision::RevokedWarrant,
+            Self::Holder => RevocationDecision::RevokedHolder,
+            Self::Subject => RevocationDecision::RevokedSubject,
+            Self::Issuer => RevocationDecision::RevokedIssuer,
+            Self::Agent => RevocationDecision::RevokedAgent,
+        }
+    }
+
+    /// The SRL entry publishing `key` under this scope.
+    fn srl_entry(self, key: &[u8], expires_at: Option<u64>) -> SrlEntry {
+        let key_hex = hex_encode_bytes(key);
+        match self {
+            Self::Warrant => SrlEntry::Warrant { id_hex: key_hex, expires_at },
+            Self::Holder => SrlEntry::Holder { key_hex },
+            Self::Subject => SrlEntry::Subject { key_hex },
+            Self::Issuer => SrlEntry::Issuer { key_hex },
+            Self::Agent => SrlEntry::Agent { key_hex },
+        }
+    }
+}
+
+/// Default nonce-claim TTL, matching `InMemoryReplayStore`.
+const DEFAULT_REPLAY_TTL_MS: u64 = 300_000;
+
+/// SQLite store failures.
+#[derive(Debug, thiserror::Error)]
+pub enum SqliteStoreError {
+    #[error("SQLite error: {0}")]
+    Sqlite(#[from] rusqlite::Error),
+    #[error("database schema version {found} is newer than supported version {supported}")]
+    UnsupportedSchema { found: i64, supported: i64 },
+    #[error("the SQLite connection lock was poisoned")]
+    Poisoned,
+}
+
+/// SQLite-backed, restart-safe facilitator storage.
+///
+/// Cheap to clone: clones share one connection.
+#[derive(Clone, Debug)]
+pub struct SqliteStore {
+    connection: Arc<Mutex<Connection>>,
+}
+
+impl SqliteStore {
+    /// Opens (creating if needed) and migrates the database at `path`.
+    pub fn open(path: impl AsRef<Path>) -> Result<Self, SqliteStoreError> {
+        let connection = Connection::open(path)?;
+        connection.pragma_update(None, "journal_mode", "WAL")?;
+        connection.pragma_update(None, "synchronous", "FULL")?;
+        Self::with_connection(connection)
+    }
+
+    /// Opens a private in-memory database (tests and demos; not restart-safe).
+    pub fn open_in_memory() -> Result<Self, SqliteStoreError> {
+        Self::with_connection(Connection::open_in_memory()?)
+    }
+
+    fn with_connection(mut connection: Connection) -> Result<Self, SqliteStoreError> {
+        connection.pragma_update(None, "foreign_keys", true)?;
+        migrate(&mut connection)?;
+        Ok(Self { connection: Arc::new(Mutex::new(connection)) })
+    }
+
+    /// Current schema version (`PRAGMA user_version`).
+    pub fn schema_version(&self) -> Result<i64, SqliteStoreError> {
+        self.with(|connection| {
+            connection.pragma_query_value(None, "user_version", |row| row.get(0))
+        })
+    }
+
+    fn with<T>(
+        &self,
+        operation: impl FnOnce(&mut Connection) -> rusqlite::Result<T>,
+    ) -> Result<T, SqliteStoreError> {
+        let mut connection = self.connection.lock().map_err(|_| SqliteStoreError::Poisoned)?;
+        Ok(operation(&mut connection)?)
+    }
+
+    // -----------------------------------------------------------------------
+    // Revocation
+    // -----------------------------------------------------------------------
+
+    /// Revokes a warrant by id.
+    pub fn revoke_warrant(&self, warrant_id: &[u8]) -> Result<(), SqliteStoreError> {
+        self.revoke(Scope::Warrant, GLOBAL_TENANT, warrant_id, None)
+    }
+
+    /// Revokes a holder key.
+    pub fn revoke_holder(&self, holder: &SignerRef) -> Result<(), SqliteStoreError> {
+        self.revoke(Scope::Holder, GLOBAL_TENANT, &holder.public_key, None)
+    }
+
+    /// Revokes a payment subject.
+    pub fn revoke_subject(&self, subject: &PaymentSubjectRef) -> Result<(), SqliteStoreError> {
+        self.revoke(Scope::Subject, GLOBAL_TENANT, &subject_revocation_key(subject), None)
+    }
+
+    /// Revokes an issuer or sub-issuer key.
+    pub fn revoke_issuer(&self, issuer: &SignerRef) -> Result<(), SqliteStoreError> {
+        self.revoke(Scope::Issuer, GLOBAL_TENANT, &issuer.public_key, None)
+    }
+
+    /// Revokes a `ledgerflow.agent_id` value.
+    pub fn revoke_agent(&self, agent_id: &str) -> Result<(), SqliteStoreError> {
+        self.revoke(Scope::Agent, GLOBAL_TENANT, agent_id.as_bytes(), None)
+    }
+
+    /// Tenant-scoped revocation of a warrant (design §10.2).
+    pub fn revoke_warrant_for(
+        &self,
+        tenant_id: &str,
+        warrant_id: &[u8],
+    ) -> Result<(), SqliteStoreError> {
+        self.revoke_warrant_for_until(tenant_id, warrant_id, None)
+    }
+
+    /// Tenant-scoped revocation of a warrant, recording its `expires_at` so
+    /// a published SRL can compact the entry away once it has passed.
+    pub fn revoke_warrant_for_until(
+        &self,
+        tenant_id: &str,
+        warrant_id: &[u8],
+        expires_at: Option<u64>,
+    ) -> Result<(), SqliteStoreError> {
+        self.revoke(Scope::Warrant, tenant_scope(tenant_id), warrant_id, expires_at)
+    }
+
+    /// Tenant-scoped revocation of a holder key (design §10.2).
+    pub fn revoke_holder_for(
+        &self,
+        tenant_id: &str,
+        holder: &SignerRef,
+    ) -> Result<(), SqliteStoreError> {
+        self.revoke(Scope::Holder, tenant_scope(tenant_id), &holder.public_key, None)
+    }
+
+    /// Tenant-scoped revocation of a payment subject (design §10.2).
+    pub fn revoke_subject_for(
+        &self,
+        tenant_id: &str,
+        subject: &PaymentSubjectRef,
+    ) -> Result<(), SqliteStoreError> {
+        self.revoke(Scope::Subject, tenant_scope(tenant_id), &subject_revocation_key(subject), None)
+    }
+
+    /// Tenant-scoped revocation of an issuer key (design §10.2).
+    pub fn revoke_issuer_for(
+        &self,
+        tenant_id: &str,
+        issuer: &SignerRef,
+    ) -> Result<(), SqliteStoreError> {
+        self.revoke(Scope::Issuer, tenant_scope(tenant_id), &issuer.public_key, None)
+    }
+
+    /// Tenant-scoped revocation of an agent id (design §10.2).
+    pub fn revoke_agent_for(
+        &self,
+        tenant_id: &str,
+        agent_id: &str,
+    ) -> Result<(), SqliteStoreError> {
+        self.revoke(Scope::Agent, tenant_scope(tenant_id), agent_id.as_bytes(), None)
+    }
+
+    /// Every revocation in append order, tenant-scoped rows under
+    /// [`tenant_scoped_key`]s, as [`FileRevocationStore::srl_entries`]
+    /// publishes them.
+    ///
+    /// [`FileRevocationStore::srl_entries`]: ledgerflow_facilitator::FileRevocationStore::srl_entries
+    pub fn srl_entries(&self) -> Result<Vec<SrlEntry>, SqliteStoreError> {
+        let rows = self.with(|connection| {
+            let mut statement = connection.prepare_cached(
+                "SELECT scope, tenant_id, key, expires_at FROM revocations ORDER BY seq",
+            )?;
+            statement
+                .query_map([], |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, Vec<u8>>(2)?,
+                        row.get::<_, Option<i64>>(3)?,
+                    ))
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()
+        })?;
+        rows.into_iter()
+            .map(|(scope, tenant_id, key, expires_at)| {
+                let scope = Scope::from_str(&scope).ok_or_else(|| {
+                    SqliteStoreError::Sqlite(rusqlite::Error::InvalidColumnType(
+                        0,
+                        "scope".to_string(),
+                        rusqlite::types::Type::Text,
+                    ))
+                })?;
+                let key = if tenant_id == GLOBAL_TENANT {
+                    key
+                } else {
+                    tenant_scoped_key(tenant_unscope(&tenant_id), &key)
+                };
+                let expires_at = expires_at.and_then(|secs| u64::try_from(secs).ok());
+                Ok(scope.srl_entry(&key, expires_at))
+            })
+            .collect()
+    }
+
+    /// The published SRL version: the highest append sequence ever handed
+    /// out, which never decreases (unlike a row count).
+    pub fn srl_version(&self) -> Result<u64, SqliteStoreError> {
+        let seq = self.with(|connection| {
+            connection
+                .query_row(
+                    "SELECT seq FROM sqlite_sequence WHERE name = 'revocations'",
+                    [],
+                    |row| row.get::<_, i64>(0),
+                )
+                .optional()
+        })?;
+        Ok(seq.and_then(|seq| u64::try_from(seq).ok()).unwrap_or_default())
+    }
+
+    /// Returns a [`RevocationCheck`] view honoring both global and
+    /// `tenant_id`-scoped revocations.
+    #[must_use]
+    pub fn for_tenant(&self, tenant_id: impl Into<String>) -> SqliteTenantRevocationView {
+        SqliteTenantRevocationView { store: self.clone(), tenant_id: tenant_id.into() }
+    }
+
+    /// Returns a replay store over this database with the default TTL.
+    #[must_use]
+    pub fn replay_store(&self) -> SqliteReplayStore {
+        SqliteReplayStore { store: self.clone(), ttl_ms: DEFAULT_REPLAY_TTL_MS }
+    }
+
+    fn revoke(
+        &self,
+        scope: Scope,
+        tenant_id: &str,
+        key: &[u8],
+        expires_at: Option<u64>,
+    ) -> Result<(), SqliteStoreError> {
+        let expires_at = expires_at.map(|secs| i64::try_from(secs).unwrap_or(i64::MAX));
+        self.with(|connection| {
+            connection.execute(
+                "INSERT OR IGNORE INTO revocations (scope, tenant_id, key, expires_at, revoked_at_ms)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![scope.as_str(), tenant_id, key, expires_at, now_ms()],
+            )
+        })?;
+        Ok(())
+    }
+
+    /// Whether `key` is revoked under `scope` globally or for `tenant_id`.
+    /// Fails closed (revoked) when the database cannot answer.
+    fn decision(&self, scope: Scope, tenant_id: Option<&str>, key: &[u8]) -> RevocationDecision {
+        let tenant = tenant_id.map_or(GLOBAL_TENANT, tenant_scope);
+        let revoked = self
+            .with(|connection| {
+                connection
+                    .query_row(
+                        "SELECT 1 FROM revocations
+                         WHERE scope = ?1 AND key = ?2 AND tenant_id IN (?3, ?4)",
+                        params![scope.as_str(), key, GLOBAL_TENANT, tenant],
+                        |_| Ok(()),
+                    )
+                    .optional()
+            })
+            .map_or_else(
+                |error| {
+                    tracing::error!(%error, "revocation lookup failed; failing closed");
+                    true
+                },
+                |row| row.is_some(),
+            );
+        if revoked { scope.revoked() } else { RevocationDecision::Ok }
+    }
+
+    // -----------------------------------------------------------------------
+    // Settlements
+    // -----------------------------------------------------------------------
+
+    fn settlement_entries(
+        &self,
+        sql: &str,
+        tenant_id: &str,
+        key: &str,
+    ) -> Result<Vec<RegistryEntry>, SqliteStoreError> {
+        let rows = self.with(|connection| {
+            let mut statement = connection.prepare_cached(sql)?;
+            statement
+                .query_map([tenant_id, key], |row| {
+                    Ok((
+                        row.get::<_, String>(0)?,
+                        row.get::<_, String>(1)?,
+                        row.get::<_, String>(2)?,
+                        row.get::<_, String>(3)?,
+                        row.get::<_, String>(4)?,
+                    ))
+                })?
+                .collect::<rusqlite::Result<Vec<_>>>()
+        })?;
+        rows.into_iter()
+            .map(|(transaction_id, rail, amount, asset, status)| {
+                let corrupt = |column: &str| {
+                    SqliteStoreError::Sqlite(rusqlite::Error::InvalidColumnType(
+                        0,
+                        column.to_string(),
+                        rusqlite::types::Type::Text,
+                    ))
+                };
+                Ok(RegistryEntry {
+                    receipt: SettlementReceipt {
+                        rail: RailKind::from_wire(&rail).ok_or_else(|| corrupt("rail"))?,
+                        transaction_id,
+                        settled_amount: amount.parse().map_err(|_| corrupt("settled_amount"))?,
+                        asset,
+                    },
+                    status: SettlementStatus::from_wire(&status)
+                        .ok_or_else(|| corrupt("status"))?,
+                })
+            })
+            .collect()
+    }
+}
+
+/// Applies pending [`MIGRATIONS`].
+fn migrate(connection: &mut Connection) -> Result<(), SqliteStoreError> {
+    let supported = MIGRATIONS.len() as i64;
+    let mut version: i64 = connection.pragma_query_value(None, "user_version", |row| row.get(0))?;
+    if version > supported {
+        return Err(SqliteStoreError::UnsupportedSchema { found: version, supported });
+    }
+    for migration in &MIGRATIONS[version as usize..] {
+        let transaction = connection.transaction()?;
+        transaction.execute_batch(migration)?;
+        version += 1;
+        transaction.pragma_update(None, "user_version", version)?;
+        transaction.commit()?;
+    }
+    Ok(())
+}
+
+/// Tenant ids are stored verbatim; the empty id is reserved for global rows.
+const fn tenant_scope(tenant_id: &str) -> &str {
+    if tenant_id.is_empty() { "\u{0}" } else { tenant_id }
+}
+
+/// Inverse of [`tenant_scope`].
+fn tenant_unscope(stored: &str) -> &str {
+    if stored == "\u{0}" { "" } else { stored }
+}
+
+fn now_ms() -> i64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| i64::try_from(elapsed.as_millis()).unwrap_or(i64::MAX))
+}
+
+impl RevocationCheck for SqliteStore {
+    fn check_warrant(&self, warrant_id: &[u8]) -> RevocationDecision {
+        self.decision(Scope::Warrant, None, warrant_id)
+    }
+
+    fn check_holder(&self, holder: &SignerRef) -> RevocationDecision {
+        self.decision(Scope::Holder, None, &holder.public_key)
+    }
+
+    fn check_subject(&self, subject: &PaymentSubjectRef) -> RevocationDecision {
+        self.decision(Scope::Subject, None, &subject_revocation_key(subject))
+    }
+
+    fn check_issuer(&self, issuer: &SignerRef) -> RevocationDecision {
+        self.decision(Scope::Issuer, None, &issuer.public_key)
+    }
+
+    fn check_agent(&self, agent_id: &str) -> RevocationDecision {
+        self.decision(Scope::Agent, None, agent_id.as_bytes())
+    }
+}
+
+/// Lets the server route `POST /v1/revocations`, hosted verification and
+/// `GET /v1/srl` to the database (see `AppState::with_sqlite`).
+impl RevocationStore for SqliteStore {
+    fn revoke_warrant_for_until(
+        &self,
+        tenant_id: &str,
+        warrant_id: &[u8],
+        expires_at: Option<u64>,
+    ) -> Result<(), RevocationStoreError> {
+        Self::revoke_warrant_for_until(self, tenant_id, warrant_id, expires_at).map_err(backend)
+    }
+
+    fn revoke_holder_for(
+        &self,
+        tenant_id: &str,
+        holder: &SignerRef,
+    ) -> Result<(), RevocationStoreError> {
+        Self::revoke_holder_for(self, tenant_id, holder).map_err(backend)
+    }
+
+    fn revoke_subject_for(
+        &self,
+        tenant_id: &str,
+        subject: &PaymentSubjectRef,
+    ) -> Result<(), RevocationStoreError> {
+        Self::revoke_subject_for(self, tenant_id, subject).map_err(backend)
+    }
+
+    fn revoke_issuer_for(
+        &self,
+        tenant_id: &str,
+        issuer: &SignerRef,
+    ) -> Result<(), RevocationStoreError> {
+        Self::revoke_issuer_for(self, tenant_id, issuer).map_err(backend)
+    }
+
+    fn revoke_agent_for(
+        &self,
+        tenant_id: &str,
+        agent_id: &str,
+    ) -> Result<(), RevocationStoreError> {
+        Self::revoke_agent_for(self, tenant_id, agent_id).map_err(backend)
+    }
+
+    fn for_tenant(&self, tenant_id: &str) -> SharedRevocationCheck {
+        Arc::new(Self::for_tenant(self, tenant_id))
+    }
+
+    /// An unreadable log publishes an empty list, which replicas reject as a
+    /// rollback (they go stale and fail closed) rather than miss revocations.
+    fn srl_entries(&self) -> Vec<SrlEntry> {
+        Self::srl_entries(self).unwrap_or_else(|error| {
+            tracing::error!(%error, "revocation log unreadable; publishing an empty SRL");
+            Vec::new()
+        })
+    }
+
+    fn srl_version(&self) -> u64 {
+        Self::srl_version(self).unwrap_or_else(|error| {
+            tracing::error!(%error, "revocation log unreadable; publishing an empty SRL");
+            0
+        })
+    }
+}
+
+fn backend(error: SqliteStoreError) -> RevocationStoreError {
+    RevocationStoreError::Backend(error.to_string())
+}
+
+/// Tenant-scoped view over a [`SqliteStore`] (global + tenant revocations).
+#[derive(Clone, Debug)]
+pub struct SqliteTenantRevocationView {
+    store: SqliteStore,
+    tenant_id: String,
+}
+
+impl RevocationCheck for SqliteTenantRevocationView {
+    fn check_warrant(&self, warrant_id: &[u8]) -> RevocationDecision {
+        self.store.decision(Scope::Warrant, Some(&self.tenant_id), warrant_id)
+    }
+
+    fn check_holder(&self, holder: &SignerRef) -> RevocationDecision {
+        self.store.decision(Scope::Holder, Some(&self.tenant_id), &holder.public_key)
+    }
+
+    fn check_subject(&self, subject: &PaymentSubjectRef) -> RevocationDecision {
+        self.store.decision(Scope::Subject, Some(&self.tenant_id), &subject_revocation_key(subject))
+    }
+
+    fn check_issuer(&self, issuer: &SignerRef) -> RevocationDecision {
+        self.store.decision(Scope::Issuer, Some(&self.tenant_id), &issuer.public_key)
+    }
+
+    fn check_agent(&self, agent_id: &str) -> RevocationDecision {
+        self.store.decision(Scope::Agent, Some(&self.tenant_id), agent_id.as_bytes())
+    }
+}
+
+impl TenantKeyStore for SqliteStore {
+    fn save_keyring(
+        &self,
+        tenant_id: &str,
+        keyring: &[TenantIssuerKey],
+    ) -> Result<(), TenantKeyError> {
+        let storage = |error: SqliteStoreError| TenantKeyError::Storage(error.to_string());
+        self.with(|connection| {
+            let transaction = connection.transaction()?;
+            transaction
+                .execute("DELETE FROM tenant_issuer_keys WHERE tenant_id = ?1", [tenant_id])?;
+            for (position, key) in keyring.iter().enumerate() {
+                transaction.execute(
+                    "INSERT INTO tenant_issuer_keys
+                     (tenant_id, position, key_id, secret_key, created_at_ms, active, not_after)
+                     VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+                    params![
+                        tenant_id,
+                        i64::try_from(position).unwrap_or(i64::MAX),
+                        key.key_id,
+                        key.signing.secret_key_bytes().as_slice(),
+                        i64::try_from(key.created_at_ms).unwrap_or(i64::MAX),
+                        key.active,
+                        key.not_after.map(|secs| i64::try_from(secs).unwrap_or(i64::MAX)),
+                    ],
+                )?;
+            }
+            transaction.commit()
+        })
+        .map_err(storage)
+    }
+
+    fn load_keyrings(&self) -> Result<BTreeMap<String, Vec<TenantIssuerKey>>, TenantKeyError> {
+        let rows = self
+            .with(|connection| {
+                let mut statement = connection.prepare_cached(
+                    "SELECT tenant_id, key_id, secret_key, created_at_ms, active, not_after
+                     FROM tenant_issuer_keys ORDER BY tenant_id, position",
+                )?;
+                statement
+                    .query_map([], |row| {
+                        Ok((
+                            row.get::<_, String>(0)?,
+                            row.get::<_, String>(1)?,
+                            row.get::<_, Vec<u8>>(2)?,
+                            row.get::<_, i64>(3)?,
+                            row.get::<_, bool>(4)?,
+                            row.get::<_, Option<i64>>(5)?,
+                        ))
+                    })?
+                    .collect::<rusqlite::Result<Vec<_>>>()
+            })
+            .map_err(|error| TenantKeyError::Storage(error.to_string()))?;
+        let mut keyrings: BTreeMap<String, Vec<TenantIssuerKey>> = BTreeMap::new();
+        for (tenant_id, key_id, secret_key, created_at_ms, active, not_after) in rows {
+            let secret_key = <[u8; 32]>::try_from(secret_key).map_err(|_| {
+                TenantKeyError::Storage(format!("secret key of `{key_id}` is not 32 bytes"))
+            })?;
+            keyrings.entry(tenant_id).or_default().push(TenantIssuerKey {
+                key_id,
+                signing: SigningKeyPair::from_bytes(&secret_key),
+                created_at_ms: u64::try_from(created_at_ms).unwrap_or_default(),
+                active,
+                not_after: not_after.and_then(|secs| u64::try_from(secs).ok()),
+            });
+        }
+        Ok(keyrings)
+    }
+}
+
+impl ApprovalStore for SqliteStore {
+    fn save_request(&self, pending: &PendingApproval) -> Result<(), ApprovalError> {
+        let request = serde_json::to_string(pending)
+            .map_err(|error| ApprovalError::Storage(error.to_string()))?;
+        self.with(|connection| {
+            connection.execute(
+                "INSERT OR REPLACE INTO pending_approvals
+                 (tenant_id, request_hash, expires_at, request_json) VALUES (?1, ?2, ?3, ?4)",
+                params![
+                    pending.tenant_id,
+                    pending.request_hash,
+                    i64::try_from(pending.expires_at).unwrap_or(i64::MAX),
+                    request,
+                ],
+            )
+        })
+        .map(|_| ())
+        .map_err(|error| ApprovalError::Storage(error.to_string()))
+    }
+
+    fn remove_expired(&self, now_secs: u64) -> Result<(), ApprovalError> {
+        self.with(|connection| {
+            connection.execute(
+                "DELETE FROM pending_approvals WHERE expires_at <= ?1",
+                [i64::try_from(now_secs).unwrap_or(i64::MAX)],
+            )
+        })
+        .map(|_| ())
+        .map_err(|error| ApprovalError::Storage(error.to_string()))
+    }
+
+    fn load_requests(&self) -> Result<Vec<PendingApproval>, ApprovalError> {
+        let rows = self
+            .with(|connection| {
+                let mut statement = connection
+                    .prepare_cached("SELECT request_json FROM pending_approvals ORDER BY rowid")?;
+                statement
+                    .query_map([], |row| row.get::<_, String>(0))?
+                    .collect::<rusqlite::Result<Vec<_>>>()
+            })
+            .map_err(|error| ApprovalError::Storage(error.to_string()))?;
+        rows.iter()
+            .map(|request| {
+                serde_json::from_str(request)
+                    .map_err(|error| ApprovalError::Storage(error.to_string()))
+            })
+            .collect()
+    }
+}
+
+impl SettlementStore for SqliteStore {
+    fn record(
+        &self,
+        tenant_id: &str,
+        warrant_digest: &str,
+        receipt: SettlementReceipt,
+        status: SettlementStatus,
+    ) {
+        let result = self.with(|connection| {
+            let transaction = connection.transaction()?;
+            transaction.execute(
+                "INSERT INTO settlements
+                     (tenant_id, transaction_id, rail, settled_amount, asset, status)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6)
+                 ON CONFLICT (tenant_id, transaction_id) DO UPDATE SET
+                     rail = excluded.rail,
+                     settled_amount = excluded.settled_amount,
+                     asset = excluded.asset,
+                     status = excluded.status",
+                params![
+                    tenant_id,
+                    receipt.transaction_id,
+                    receipt.rail.as_str(),
+                    receipt.settled_amount.to_string(),
+                    receipt.asset,
+                    status.as_str(),
+                ],
+            )?;
+            transaction.execute(
+                "INSERT OR IGNORE INTO settlement_warrants
+                     (tenant_id, warrant_digest, transaction_id)
+                 VALUES (?1, ?2, ?3)",
+                params![tenant_id, warrant_digest, receipt.transaction_id],
+            )?;
+            transaction.commit()
+        });
+        if let Err(error) = result {
+            tracing::error!(%error, transaction_id = %receipt.transaction_id, "failed to record settlement");
+        }
+    }
+
+    fn query(&self, tenant_id: &str, transaction_id: &str) -> Option<RegistryEntry> {
+        self.settlement_entries(
+            "SELECT transaction_id, rail, settled_amount, asset, status
+             FROM settlements WHERE tenant_id = ?1 AND transaction_id = ?2",
+            tenant_id,
+            transaction_id,
+        )
+        .inspect_err(|error| tracing::error!(%error, "settlement query failed"))
+        .ok()
+        .and_then(|entries| entries.into_iter().next())
+    }
+
+    fn query_by_warrant(&self, tenant_id: &str, warrant_digest: &str) -> Vec<RegistryEntry> {
+        self.settlement_entries(
+            "SELECT s.transaction_id, s.rail, s.settled_amount, s.asset, s.status
+             FROM settlement_warrants w JOIN settlements s USING (tenant_id, transaction_id)
+             WHERE w.tenant_id = ?1 AND w.warrant_digest = ?2 ORDER BY w.seq",
+            tenant_id,
+            warrant_digest,
+        )
+        .inspect_err(|error| tracing::error!(%error, "settlement query failed"))
+        .unwrap_or_default()
+    }
+}
+
+impl WarrantRepository for SqliteStore {
+    fn load(&self, digest: &str) -> Option<Warrant> {
+        let bytes = self
+            .with(|connection| {
+                connection
+                    .query_row(
+                        "SELECT warrant_cbor FROM warrants WHERE digest = ?1",
+                        [digest],
+                        |row| row.get::<_, Vec<u8>>(0),
+                    )
+                    .optional()
+            })
+            .inspect_err(|error| tracing::error!(%error, "warrant lookup failed"))
+            .ok()??;
+        // Re-check the content address so a tampered row is never served.
+        Warrant::decode_cbor(&bytes).ok().filter(|warrant| warrant.digest() == digest)
+    }
+
+    fn store(&mut self, warrant: Warrant) {
+        let encoded = match warrant.encode_cbor() {
+            Ok(encoded) => encoded,
+            Err(error) => {
+                tracing::error!(%error, "failed to encode warrant");
+                return;
+            }
+        };
+        let result = self.with(|connection| {
+            connection.execute(
+                "INSERT OR IGNORE INTO warrants (digest, warrant_cbor, stored_at_ms, warrant_id)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![warrant.digest(), encoded, now_ms(), warrant.id],
+            )
+        });
+        if let Err(error) = result {
+            tracing::error!(%error, "failed to store warrant");
+        }
+    }
+
+    fn load_by_id(&self, warrant_id: &[u8]) -> Option<Warrant> {
+        let (digest, bytes) = self
+            .with(|connection| {
+                connection
+                    .query_row(
+                        "SELECT digest, warrant_cbor FROM warrants WHERE warrant_id = ?1 LIMIT 1",
+                        [warrant_id],
+                        |row| Ok((row.get::<_, String>(0)?, row.get::<_, Vec<u8>>(1)?)),
+                    )
+                    .optional()
+            })
+            .inspect_err(|error| tracing::error!(%error, "warrant lookup failed"))
+            .ok()??;
+        Warrant::decode_cbor(&bytes)
+            .ok()
+            .filter(|warrant| warrant.digest() == digest && warrant.id == warrant_id)
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Replay
+// ---------------------------------------------------------------------------
+
+/// SQLite-backed [`ReplayStore`]: nonce claims and payment-id idempotency
+/// survive restarts.
+#[derive(Clone, Debug)]
+pub struct SqliteReplayStore {
+    store: SqliteStore,
+    ttl_ms: u64,
+}
+
+impl SqliteReplayStore {
+    /// Sets the nonce-claim TTL in milliseconds.
+    #[must_use]
+    pub const fn with_ttl(mut self, ttl_ms: u64) -> Self {
+        self.ttl_ms = ttl_ms;
+        self
+    }
+}
+
+impl ReplayStore for SqliteReplayStore {
+    fn claim_nonce(
+        &mut self,
+        fingerprint: ReplayFingerprint,
+        now_ms: u64,
+    ) -> Result<(), ReplayConflict> {
+        let now = i64::try_from(now_ms).unwrap_or(i64::MAX);
+        let cutoff = i64::try_from(now_ms.saturating_sub(self.ttl_ms)).unwrap_or(i64::MAX);
+        let result = self.store.with(|connection| {
+            let transaction = connection.transaction()?;
+            // Same expiry rule as the in-memory store: live while age < TTL.
+            transaction.execute("DELETE FROM nonce_claims WHERE created_at_ms <= ?1", [cutoff])?;
+            let existing = transaction
+                .query_row(
+                    "SELECT challenge_id, nonce, request_hash, accepted_hash FROM nonce_claims
+                     WHERE challenge_id = ?1 AND nonce = ?2",
+                    params![fingerprint.challenge_id, fingerprint.nonce],
+                    |row| {
+                        Ok(ReplayFingerprint {
+                            challenge_id: row.get(0)?,
+                            nonce: row.get(1)?,
+                            request_hash: row.get(2)?,
+                            accepted_hash: row.get(3)?,
+                        })
+                    },
+                )
+                .optional()?;
+            if existing.is_none() {
+                transaction.execute(
+                    "INSERT INTO nonce_claims
+                         (challenge_id, nonce, request_hash, accepted_hash, created_at_ms)
+                     VALUES (?1, ?2, ?3, ?4, ?5)",
+                    params![
+                        fingerprint.challenge_id,
+                        fingerprint.nonce,
+                        fingerprint.request_hash,
+                        fingerprint.accepted_hash,
+                        now,
+                    ],
+                )?;
+            }
+            transaction.commit()?;
+            Ok(existing)
+        });
+        match result {
+            Ok(None) => Ok(()),
+            Ok(Some(existing)) => Err(ReplayConflict { existing }),
+            Err(error) => {
+                tracing::error!(%error, "nonce claim failed; treating as replay");
+                Err(ReplayConflict { existing: fingerprint })
+            }
+        }
+    }
+
+    fn release_nonce(&mut self, fingerprint: &ReplayFingerprint) {
+        let result = self.store.with(|connection| {
+            connection.execute(
+                "DELETE FROM nonce_claims
+                 WHERE challenge_id = ?1 AND nonce = ?2 AND request_hash = ?3
+                     AND accepted_hash = ?4",
+                params![
+                    fingerprint.challenge_id,
+                    fingerprint.nonce,
+                    fingerprint.request_hash,
+                    fingerprint.accepted_hash,
+                ],
+            )
+        });
+        if let Err(error) = result {
+            tracing::error!(%error, "failed to release nonce claim");
+        }
+    }
+
+    fn cached_payment(
+        &self,
+        payment_identifier: &str,
+        request_hash: &str,
+        accepted_hash: &str,
+    ) -> Option<VerifiedAuthorization> {
+        let bytes = self
+            .store
+            .with(|connection| {
+                connection
+                    .query_row(
+                        "SELECT authorization_cbor FROM payment_results
+                         WHERE payment_identifier = ?1 AND request_hash = ?2
+                             AND accepted_hash = ?3",
+                        params![payment_identifier, request_hash, accepted_hash],
+                        |row| row.get::<_, Vec<u8>>(0),
+                    )
+                    .optional()
+            })
+            .inspect_err(|error| tracing::error!(%error, "payment cache lookup failed"))
+            .ok()??;
+        VerifiedAuthorization::decode_cbor(&bytes).ok()
+    }
+
+    fn cache_payment(
+        &mut self,
+        payment_identifier: String,
+        authorization: VerifiedAuthorization,
+        request_hash: String,
+        accepted_hash: String,
+    ) {
+        let encoded = match authorization.encode_cbor() {
+            Ok(encoded) => encoded,
+            Err(error) => {
+                tracing::error!(%error, "failed to encode cached payment");
+                return;
+            }
+        };
+        let result = self.store.with(|connection| {
+            connection.execute(
+                "INSERT INTO payment_results
+                     (payment_identifier, request_hash, accepted_hash, authorization_cbor)
+                 VALUES (?1, ?2, ?3, ?4)
+                 ON CONFLICT (payment_identifier) DO UPDATE SET
+                     request_hash = excluded.request_hash,
+                     accepted_hash = excluded.accepted_hash,
+                     authorization_cbor = excluded.authorization_cbor",
+                params![payment_identifier, request_hash, accepted_hash, encoded],
+            )
+        });
+        if let Err(error) = result {
+            tracing::error!(%error, "failed to cache payment");
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Async seams
+// ---------------------------------------------------------------------------
+
+// The connection is already behind a mutex, so the async impls work on a
+// clone of the handle; a failed blocking task fails closed like a failed
+// query.
+
+impl ledgerflow_protocol::AsyncReplayStore for SqliteReplayStore {
+    async fn claim_nonce(
+        &self,
+        fingerprint: ReplayFingerprint,
+        now_ms: u64,
+    ) -> Result<(), ReplayConflict> {
+        let mut store = self.clone();
+        let claimed = fingerprint.clone();
+        tokio::task::spawn_blocking(move || ReplayStore::claim_nonce(&mut store, claimed, now_ms))
+            .await
+            .unwrap_or_else(|error| {
+                tracing::error!(%error, "nonce claim task failed; treating as replay");
+                Err(ReplayConflict { existing: fingerprint })
+            })
+    }
+
+    async fn cached_payment(
+        &self,
+        payment_identifier: &str,
+        request_hash: &str,
+        accepted_hash: &str,
+    ) -> Option<VerifiedAuthorization> {
+        let store = self.clone();
+        let (payment_identifier, request_hash, accepted_hash) =
+            (payment_identifier.to_string(), request_hash.to_string(), accepted_hash.to_string());
+        tokio::task::spawn_blocking(move || {
+            ReplayStore::cached_payment(&store, &payment_identifier, &request_hash, &accepted_hash)
+        })
+        .await
+        .inspect_err(|error| tracing::error!(%error, "payment cache lookup task failed"))
+        .ok()
+        .flatten()
+    }
+
+    async fn cache_payment(
+        &self,
+        payment_identifier: String,
+        authorization: VerifiedAuthorization,
+        request_hash: String,
+        accepted_hash: String,
+    ) {
+        let mut store = self.clone();
+        let result = tokio::task::spawn_blocking(move || {
+            ReplayStore::cache_payment(
+                &mut store,
+                payment_identifier,
+                authorization,
+                request_hash,
+                accepted_hash,
+            );
+        })
+        .await;
+        if let Err(error) = result {
+            tracing::error!(%error, "payment cache task failed");
+        }
+    }
+}
+
+impl ledgerflow_protocol::AsyncWarrantRepository for SqliteStore {
+    async fn load(&self, digest: &str) -> Option<Warrant> {
+        let store = self.clone();
+        let digest = digest.to_string();
+        tokio::task::spawn_blocking(move || WarrantRepository::load(&store, &digest))
+            .await
+            .inspect_err(|error| tracing::error!(%error, "warrant lookup task failed"))
+            .ok()
+            .flatten()
+    }
+
+    async fn store(&self, warrant: Warrant) {
+        let mut store = self.clone();
+        let result =
+            tokio::task::spawn_blocking(move || WarrantRepository::store(&mut store, warrant))
+                .await;
+        if let Err(error) = result {
+            tracing::error!(%error, "warrant store task failed");
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::expect_used)]
+
+    use ledgerflow_core::{
+        MerchantConstraint, PaymentConstraint, PaymentRail, PaymentSubjectKind, PaymentSubjectRef,
+        ResourceConstraint, SigningKeyPair, WarrantBuilder,
+    };
+
+    use super::*;
+
+    fn temp_db(name: &str) -> std::path::PathBuf {
+        let dir =
+            std::env::temp_dir().join(format!("ledgerflow-sqlite-{name}-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).expect("create dir");
+        let path = dir.join("ledgerflow.db");
+        let _ = std::fs::remove_file(&path);
+        path
+    }
+
+    fn warrant() -> Warrant {
+        let issuer = SigningKeyPair::from_bytes(&[1_u8; 32]);
+        WarrantBuilder::new(1_000)
+            .warrant_id(*b"sqlite-warrant-1")
+            .ttl_secs(60)
+            .issuer(issuer.signer_ref())
+            .holder(SigningKeyPair::from_bytes(&[2_u8; 32]).signer_ref())
+            .merchant(MerchantConstraint::with_ids(vec!["merchant-a".to_string()]))
+            .resource(ResourceConstraint::default())
+            .payment(PaymentConstraint::new(1_000))
+            .sign_with(&issuer, [0_u8; 8])
+    }
+
+    fn authorization(warrant: &Warrant) -> VerifiedAuthorization {
+        VerifiedAuthorization {
+            merchant_id: "merchant-a".to_string(),
+            tool_name: "web-search".to_string(),
+            payment_subject: PaymentSubjectRef::new(
+                PaymentSubjectKind::Caip10,
+                "caip10:eip155:1:0xabc",
+            ),
+            holder: warrant.holder.clone(),
+            leaf_warrant: warrant.clone(),
+            root_warrant: warrant.clone(),
+            chain_len: 1,
+            amount: 100,
+            asset: "USDC".to_string(),
+            scheme: "exact".to_string(),
+            payee_id: "merchant-a".to_string(),
+            rail: PaymentRail::Onchain,
+            challenge_id: "challenge-1".to_string(),
+            request_hash: "sha256:req".to_string(),
+            accepted_hash: "sha256:acc".to_string(),
+            warrant_digest: warrant.digest(),
+        }
+    }
+
+    fn fingerprint(nonce: &str) -> ReplayFingerprint {
+        ReplayFingerprint {
+            challenge_id: "challenge-1".to_string(),
+            nonce: nonce.to_string(),
+            request_hash: "sha256:req".to_string(),
+            accepted_hash: "sha256:acc".to_string(),
+        }
+    }
+
+    #[test]
+    fn state_survives_reopening_the_database() {
+        let path = temp_db("reopen");
+        let holder = SigningKeyPair::from_bytes(&[7_u8; 32]).signer_ref();
+        let warrant = warrant();
+        let receipt = SettlementReceipt {
+            rail: RailKind::Solana,
+            transaction_id: "tx-1".to_string(),
+            settled_amount: u128::from(u64::MAX) + 1,
+            asset: "USDC".to_string(),
+        };
+        {
+            let mut store = SqliteStore::open(&path).expect("open");
+            store.revoke_warrant(&[1_u8; 16]).expect("revoke");
+            store.revoke_holder_for("tenant-a", &holder).expect("revoke holder");
+            store.record("tenant-a", "sha256:w", receipt.clone(), SettlementStatus::Settled);
+            store.store(warrant.clone());
+            let mut replay = store.replay_store();
+            replay.claim_nonce(fingerprint("n-1"), 1_000).expect("claim");
+            replay.cache_payment(
+                "pay-1".to_string(),
+                authorization(&warrant),
+                "sha256:req".to_string(),
+                "sha256:acc".to_string(),
+            );
+        }
+
+        let store = SqliteStore::open(&path).expect("reopen");
+        assert_eq!(store.schema_version().expect("version"), MIGRATIONS.len() as i64);
+        assert_eq!(store.check_warrant(&[1_u8; 16]), RevocationDecision::RevokedWarrant);
+        assert_eq!(
+            store.for_tenant("tenant-a").check_holder(&holder),
+            RevocationDecision::RevokedHolder
+        );
+        let entry = store.query("tenant-a", "tx-1").expect("settlement");
+        assert_eq!(entry, RegistryEntry { receipt, status: SettlementStatus::Settled });
+        assert_eq!(store.query_by_warrant("tenant-a", "sha256:w"), vec![entry]);
+        assert_eq!(store.query("tenant-b", "tx-1"), None);
+        assert!(store.query_by_warrant("tenant-b", "sha256:w").is_empty());
+        assert_eq!(store.load(&warrant.digest()), Some(warrant.clone()));
+        assert_eq!(store.load_by_id(&warrant.id), Some(warrant.clone()));
+        assert_eq!(store.load_by_id(&[0xEE; 16]), None);
+
+        let mut replay = store.replay_store();
+        assert!(replay.claim_nonce(fingerprint("n-1"), 2_000).is_err(), "nonce remembered");
+        assert_eq!(
+            replay.cached_payment("pay-1", "sha256:req", "sha256:acc"),
+            Some(authorization(&warrant))
+        );
+        assert_eq!(replay.cached_payment("pay-1", "sha256:other", "sha256:acc"), None);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn tenant_revocations_are_isolated() {
+        let store = SqliteStore::open_in_memory().expect("open");
+        let holder = SigningKeyPair::from_bytes(&[7_u8; 32]).signer_ref();
+        store.revoke_warrant(&[1_u8; 16]).expect("global");
+        store.revoke_warrant_for("tenant-a", &[2_u8; 16]).expect("tenant");
+        store.revoke_holder_for("tenant-a", &holder).expect("tenant holder");
+
+        let tenant_a = store.for_tenant("tenant-a");
+        let tenant_b = store.for_tenant("tenant-b");
+        assert_eq!(tenant_b.check_warrant(&[1_u8; 16]), RevocationDecision::RevokedWarrant);
+        assert_eq!(tenant_a.check_warrant(&[2_u8; 16]), RevocationDecision::RevokedWarrant);
+        assert_eq!(tenant_b.check_warrant(&[2_u8; 16]), RevocationDecision::Ok);
+        assert_eq!(tenant_b.check_holder(&holder), RevocationDecision::Ok);
+        // Tenant-scoped rows never leak into the global view.
+        assert_eq!(store.check_warrant(&[2_u8; 16]), RevocationDecision::Ok);
+        assert_eq!(store.check_holder(&holder), RevocationDecision::Ok);
+        // An empty tenant id does not alias the global scope.
+        store.revoke_warrant_for("", &[3_u8; 16]).expect("empty tenant");
+        assert_eq!(store.check_warrant(&[3_u8; 16]), RevocationDecision::Ok);
+    }
+
+    #[test]
+    fn app_state_routes_every_revocation_scope_to_the_database() {
+        let store = SqliteStore::open_in_memory().expect("open");
+        let state =
+            crate::NewAppState::demo().expect("demo state").with_sqlite(&store).expect("attach");
+        let issuer = SigningKeyPair::from_bytes(&[0x5D; 32]).signer_ref();
+        let subject = PaymentSubjectRef::new(PaymentSubjectKind::Opaque, "sqlite-payer");
+        let revocations = &state.revocation_store;
+        revocations.revoke_warrant_for_until("tenant-a", &[4_u8; 16], Some(99)).expect("warrant");
+        revocations.revoke_subject_for("tenant-a", &subject).expect("subject");
+        revocations.revoke_issuer_for("tenant-a", &issuer).expect("issuer");
+        revocations.revoke_agent_for("", "agent-9").expect("agent");
+
+        assert_eq!(
+            store.for_tenant("tenant-a").check_issuer(&issuer),
+            RevocationDecision::RevokedIssuer
+        );
+        assert_eq!(
+            state.verification.revocation.for_tenant("tenant-a").check_subject(&subject),
+            RevocationDecision::RevokedSubject
+        );
+        assert_eq!(
+            state.settlement.revocation.for_tenant("tenant-b").check_issuer(&issuer),
+            RevocationDecision::Ok
+        );
+        assert_eq!(
+            revocations.for_tenant("").check_agent("agent-9"),
+            RevocationDecision::RevokedAgent
+        );
+
+        // Published under the same keys as the file store, in append order.
+        let path = temp_db("srl").with_extension("jsonl");
+        let file = ledgerflow_facilitator::FileRevocationStore::open(&path).expect("file store");
+        file.revoke_warrant_for_until("tenant-a", &[4_u8; 16], Some(99)).expect("warrant");
+        file.revoke_subject_for("tenant-a", &subject).expect("subject");
+        file.revoke_issuer_for("tenant-a", &issuer).expect("issuer");
+        file.revoke_agent_for("", "agent-9").expect("agent");
+        assert_eq!(revocations.srl_entries(), file.srl_entries());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn tenant_keyrings_survive_a_restart_with_their_windows() {
+        let path = temp_db("tenant-keys");
+        let (first, second) = {
+            let store = SqliteStore::open(&path).expect("open");
+            let state = crate::NewAppState::demo()
+                .expect("demo state")
+                .with_sqlite(&store)
+                .expect("attach");
+            let first = state.tenant_keys.create("tenant-a", 1_000).expect("create");
+            let second = state.tenant_keys.rotate("tenant-a", 5_000).expect("rotate");
+            (first, second)
+        };
+
+        let store = SqliteStore::open(&path).expect("reopen");
+        let state =
+            crate::NewAppState::demo().expect("demo state").with_sqlite(&store).expect("attach");
+        let active = state.tenant_keys.active_key("tenant-a").expect("lock").expect("active");
+        assert_eq!(active.key_id, second.key_id);
+        assert_eq!(hex_encode_bytes(&active.signing.public_key_bytes()), second.public_key);
+        let listed = state.tenant_keys.list("tenant-a").expect("list");
+        assert_eq!(listed[0].public_key, first.public_key);
+        assert_eq!(listed[0].not_after, Some(5));
+        assert!(state.tenant_keys.list("tenant-b").expect("list").is_empty());
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn pending_approvals_survive_a_restart_with_their_approvals() {
+        let path = temp_db("approvals");
+        let approver = SigningKeyPair::from_bytes(&[3_u8; 32]);
+        // Opened after the stale request's TTL ran out.
+        let now = ledgerflow_core::approval::DEFAULT_APPROVAL_TTL_SECS + 100;
+        let approvers =
+            vec![approver.signer_ref(), SigningKeyPair::from_bytes(&[4_u8; 32]).signer_ref()];
+        {
+            let store = SqliteStore::open(&path).expect("open");
+            let state = crate::NewAppState::demo()
+                .expect("demo state")
+                .with_sqlite(&store)
+                .expect("attach");
+            let live =
+                PendingApproval::new("tenant-a", "sha256:live", warrant(), approvers.clone(), now)
+                    .with_payment(u128::from(u64::MAX) + 1, "USDC", "merchant-a");
+            let stale = PendingApproval::new("tenant-a", "sha256:stale", warrant(), approvers, 0);
+            assert!(state.approvals.open(stale, 0).expect("open stale"));
+            assert!(state.approvals.open(live, now).expect("open live"));
+            let approval = ledgerflow_core::SignedApproval::sign(
+                "sha256:live",
+                &approver.signer_ref(),
+                now + 60,
+                &approver,
+            );
+            state.approvals.submit("tenant-a", approval, None, now).expect("submit");
+        }
+
+        let store = SqliteStore::open(&path).expect("reopen");
+        let state =
+            crate::NewAppState::demo().expect("demo state").with_sqlite(&store).expect("attach");
+        let held = state.approvals.get("tenant-a", "sha256:live").expect("live request");
+        assert_eq!(held.amount, u128::from(u64::MAX) + 1);
+        assert_eq!(held.approvals.len(), 1);
+        assert_eq!(held.status(now), "pending");
+        // The stale request was dropped when the live one was opened.
+        assert!(matches!(
+            state.approvals.get("tenant-a", "sha256:stale"),
+            Err(ApprovalError::NotFound)
+        ));
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn version_one_revocations_survive_the_scope_migration() {
+        let path = temp_db("migrate");
+        {
+            let mut connection = Connection::open(&path).expect("open");
+            let transaction = connection.transaction().expect("transaction");
+            transaction.execute_batch(MIGRATIONS[0]).expect("v1 schema");
+            transaction.pragma_update(None, "user_version", 1).expect("version");
+            transaction
+                .execute(
+                    "INSERT INTO revocations (scope, tenant_id, key, revoked_at_ms)
+                     VALUES ('warrant', '', ?1, 0)",
+                    [vec![1_u8; 16]],
+                )
+                .expect("v1 row");
+            transaction.commit().expect("commit");
+        }
+        let store = SqliteStore::open(&path).expect("migrate");
+        assert_eq!(store.schema_version().expect("version"), MIGRATIONS.len() as i64);
+        assert_eq!(store.check_warrant(&[1_u8; 16]), RevocationDecision::RevokedWarrant);
+        assert_eq!(
+            store.srl_entries().expect("entries"),
+            vec![SrlEntry::Warrant { id_hex: hex_encode_bytes(&[1_u8; 16]), expires_at: None }]
+        );
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn the_srl_version_only_moves_forward() {
+        let path = temp_db("srl-version");
+        let holder = SigningKeyPair::from_bytes(&[7_u8; 32]).signer_ref();
+        let version = {
+            let store = SqliteStore::open(&path).expect("open");
+            assert_eq!(store.srl_version().expect("version"), 0);
+            store.revoke_warrant(&[1_u8; 16]).expect("revoke");
+            let first = store.srl_version().expect("version");
+            assert!(first > 0);
+            store.revoke_warrant(&[1_u8; 16]).expect("duplicate is ignored");
+            assert!(store.srl_version().expect("version") >= first);
+            store.revoke_holder(&holder).expect("revoke holder");
+            let version = store.srl_version().expect("version");
+            assert!(version > first);
+            version
+        };
+        let store = SqliteStore::open(&path).expect("reopen");
+        assert_eq!(store.srl_version().expect("version"), version);
+        store.revoke_warrant(&[2_u8; 16]).expect("revoke");
+        assert!(store.srl_version().expect("version") > version);
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn nonce_claims_expire_after_the_ttl() {
+        let store = SqliteStore::open_in_memory().expect("open");
+        let mut replay = store.replay_store().with_ttl(1_000);
+        replay.claim_nonce(fingerprint("n-1"), 10_000).expect("claim");
+        let conflict = replay.claim_nonce(fingerprint("n-1"), 10_999).expect_err("replay");
+        assert_eq!(conflict.existing, fingerprint("n-1"));
+        replay.claim_nonce(fingerprint("n-1"), 11_000).expect("expired claim is reusable");
+    }
+
+    #[tokio::test]
+    async fn async_seams_share_the_database_with_the_sync_ones() {
+        use ledgerflow_protocol::{AsyncReplayStore, AsyncWarrantRepository};
+
+        let store = SqliteStore::open_in_memory().expect("open");
+        let replay = store.replay_store();
+        AsyncReplayStore::claim_nonce(&replay, fingerprint("n-1"), 1_000).await.expect("claim");
+        let conflict =
+            ReplayStore::claim_nonce(&mut store.replay_store(), fingerprint("n-1"), 1_500)
+                .expect_err("claimed through the async seam");
+        assert_eq!(conflict.existing, fingerprint("n-1"));
+
+        let warrant = warrant();
+        AsyncWarrantRepository::store(&store, warrant.clone()).await;
+        assert_eq!(WarrantRepository::load(&store, &warrant.digest()), Some(warrant.clone()));
+        assert_eq!(AsyncWarrantRepository::load(&store, &warrant.digest()).await, Some(warrant));
+    }
+
+    #[test]
+    fn newer_schemas_are_refused() {
+        let path = temp_db("schema");
+        {
+            let connection = Connection::open(&path).expect("open");
+            connection.pragma_update(None, "user_version", 99).expect("bump");
+        }
+        let error = SqliteStore::open(&path).expect_err("newer schema");
+        assert!(matches!(error, SqliteStoreError::UnsupportedSchema { found: 99, .. }));
+        let _ = std::fs::remove_file(&path);
+    }
+}
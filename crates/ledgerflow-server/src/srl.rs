@@ -0,0 +1,291 @@
+//! Signed Revocation List publication (design §6.6).
+//!
+//! The control plane publishes its revocation store as an SRL signed with
+//! `LEDGERFLOW_SRL_KEY`; facilitators poll it (`ledgerflow_facilitator::
+//! SrlSync::spawn_poller`) and replay the entries into their own stores.
+//!
+//! - `GET /v1/srl` — a snapshot of the whole list.
+//! - `GET /v1/srl?since={version}` — a delta: the entries revoked after `version`, signed as
+//!   applying on top of `version`; no list when the caller is up to date.
+//!
+//! The store is an append-only log, so its record count is the list version
+//! and a delta is a suffix of the log. Both forms are compacted (warrants
+//! that expired more than [`SRL_COMPACTION_GRACE_SECS`] ago are dropped) and
+//! carry the Merkle root of the compacted state, which a replica matches
+//! after applying. Tenant-scoped records are published with their scoped
+//! keys, so a replica enforces them for the same tenant only.
+//!
+//! Signed lists are cached per version ([`SrlCache`]): polls between
+//! revocations are answered without re-reading the log, recomputing the root
+//! or re-signing. A cached version is re-signed after
+//! [`SRL_REPUBLISH_SECS`] so compaction keeps advancing. Reading the store
+//! and signing block, so a poll is answered on the blocking pool.
+
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, Mutex, PoisonError},
+};
+
+use axum::{
+    Json,
+    extract::{Query, State},
+};
+use ledgerflow_core::{
+    CborCodec as _, SignedRevocationList, SigningKeyPair, SrlEntry, srl_state_root,
+};
+use ledgerflow_protocol::wire::base64url_encode;
+use serde::{Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+
+use crate::{
+    api::{ApiError, ApiResponse},
+    state::AppState,
+};
+
+/// How long past its expiry a revoked warrant stays in published lists, so
+/// verifiers whose clocks lag the control plane still see it.
+pub const SRL_COMPACTION_GRACE_SECS: u64 = 300;
+
+/// How long a version's signed lists are served from the cache before they
+/// are compacted and signed again.
+pub const SRL_REPUBLISH_SECS: u64 = 60;
+
+/// Most lists (the snapshot and deltas) cached per version; other `since`
+/// values are signed per request.
+const MAX_CACHED_DELTAS: usize = 64;
+
+/// The signed lists published at the current version.
+#[derive(Clone, Debug, Default)]
+pub struct SrlCache {
+    inner: Arc<Mutex<Option<Publication>>>,
+}
+
+#[derive(Debug)]
+struct Publication {
+    version: u64,
+    compacted_before: u64,
+    state_root: String,
+    /// The snapshot (key `None`) and deltas (key `Some(since)`) signed so far.
+    lists: BTreeMap<Option<u64>, SignedList>,
+}
+
+/// An encoded signed list and its digest.
+#[derive(Clone, Debug)]
+struct SignedList {
+    srl: String,
+    digest: String,
+}
+
+/// Query parameters of `GET /v1/srl`.
+#[derive(Clone, Debug, Deserialize, IntoParams)]
+pub struct SrlQuery {
+    /// Version the caller has applied; omit for the full list.
+    pub since: Option<u64>,
+}
+
+/// A published revocation list.
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct SrlResponse {
+    /// Current list version.
+    pub version: u64,
+    /// Version `srl` is a delta from; absent for a snapshot.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub since: Option<u64>,
+    /// base64url (unpadded) CBOR of the signed list; absent when the caller
+    /// is at `version`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub srl: Option<String>,
+    /// Digest of the signed list (for audit records).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub digest: Option<String>,
+    /// Merkle root of the compacted list at `version`.
+    pub state_root: String,
+}
+
+/// Serves the latest signed revocation list, or the delta since a version.
+#[utoipa::path(
+    get,
+    path = "/v1/srl",
+    params(SrlQuery),
+    responses(
+        (status = 200, description = "Signed revocation list", body = SrlResponse),
+        (status = 404, description = "SRL publication is not configured")
+    )
+)]
+pub(crate) async fn publish_srl(
+    State(state): State<AppState>,
+    Query(query): Query<SrlQuery>,
+) -> Result<Json<ApiResponse<SrlResponse>>, ApiError> {
+    if state.srl_signer.is_none() {
+        return Err(ApiError::NotFound);
+    }
+    let now_secs = crate::api::now_ms() / 1_000;
+    let response = tokio::task::spawn_blocking(move || srl_response(&state, query.since, now_secs))
+        .await
+        .map_err(|error| ApiError::Internal(format!("SRL task failed: {error}")))??;
+    Ok(Json(ApiResponse::ok(response)))
+}
+
+/// The snapshot, or the delta `since`, at the store's current version,
+/// served from and recorded in the [`SrlCache`].
+fn srl_response(
+    state: &AppState,
+    since: Option<u64>,
+    now_secs: u64,
+) -> Result<SrlResponse, ApiError> {
+    let keys = state.srl_signer.as_ref().ok_or(ApiError::NotFound)?;
+    let mut cache = state.srl_cache.inner.lock().unwrap_or_else(PoisonError::into_inner);
+    let version = state.revocation_store.srl_version();
+    let current =
+        cache.as_ref().is_some_and(|publication| publication.is_current(version, now_secs));
+    let mut log = None;
+    let publication = match &mut *cache {
+        Some(publication) if current => publication,
+        slot => {
+            let entries = state.revocation_store.srl_entries();
+            let publication = slot.insert(Publication::compact(&entries, now_secs));
+            log = Some(entries);
+            publication
+        }
+    };
+    let version = publication.version;
+    let state_root = publication.state_root.clone();
+    // A caller ahead of this log (e.g. one fed by another control plane)
+    // gets the full list and rejects it as a rollback.
+    let since = since.filter(|since| *since > 0 && *since <= version);
+    if since.unwrap_or(0) == version {
+        return Ok(SrlResponse { version, since, srl: None, digest: None, state_root });
+    }
+    let signed = if let Some(signed) = publication.lists.get(&since) {
+        signed.clone()
+    } else {
+        let entries = log.unwrap_or_else(|| state.revocation_store.srl_entries());
+        let signed = sign_list(publication, since, entries, keys)?;
+        if publication.lists.len() < MAX_CACHED_DELTAS {
+            publication.lists.insert(since, signed.clone());
+        }
+        signed
+    };
+    Ok(SrlResponse {
+        version,
+        since,
+        srl: Some(signed.srl),
+        digest: Some(signed.digest),
+        state_root,
+    })
+}
+
+impl Publication {
+    /// The compacted state of `entries` as of `now_secs`, with nothing
+    /// signed yet.
+    fn compact(entries: &[SrlEntry], now_secs: u64) -> Self {
+        let compacted_before = now_secs.saturating_sub(SRL_COMPACTION_GRACE_SECS);
+        let live: Vec<_> =
+            entries.iter().filter(|entry| !entry.is_expired(compacted_before)).cloned().collect();
+        Self {
+            version: entries.len() as u64,
+            compacted_before,
+            state_root: srl_state_root(&live),
+            lists: BTreeMap::new(),
+        }
+    }
+
+    /// Whether this publication still answers for `version` at `now_secs`.
+    const fn is_current(&self, version: u64, now_secs: u64) -> bool {
+        self.version == version &&
+            now_secs.saturating_sub(SRL_COMPACTION_GRACE_SECS) <
+                self.compacted_before.saturating_add(SRL_REPUBLISH_SECS)
+    }
+}
+
+/// Signs the snapshot, or the delta `since`, of `publication` over `entries`
+/// (the log, read at or after the publication's version).
+fn sign_list(
+    publication: &Publication,
+    since: Option<u64>,
+    mut entries: Vec<SrlEntry>,
+    keys: &SigningKeyPair,
+) -> Result<SignedList, ApiError> {
+    entries.truncate(usize::try_from(publication.version).unwrap_or(usize::MAX));
+    let list = if let Some(since) = since {
+        let skip = usize::try_from(since).unwrap_or(entries.len()).min(entries.len());
+        SignedRevocationList::sign_delta(
+            since,
+            publication.version,
+            entries.split_off(skip),
+            publication.compacted_before,
+            publication.state_root.clone(),
+            keys,
+        )
+    } else {
+        entries.retain(|entry| !entry.is_expired(publication.compacted_before));
+        SignedRevocationList::sign_snapshot(
+            publication.version,
+            entries,
+            publication.compacted_before,
+            keys,
+        )
+    };
+    let encoded = list.encode_cbor().map_err(|error| ApiError::Internal(error.to_string()))?;
+    Ok(SignedList { srl: base64url_encode(&encoded), digest: list.digest() })
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::expect_used)]
+
+    use super::*;
+
+    fn publish(state: &AppState, since: Option<u64>) -> SrlResponse {
+        let response = tokio::runtime::Builder::new_current_thread()
+            .build()
+            .expect("runtime")
+            .block_on(publish_srl(State(state.clone()), Query(SrlQuery { since })))
+            .expect("published");
+        response.0.data.expect("data")
+    }
+
+    fn cached_lists(state: &AppState) -> (u64, usize) {
+        let cache = state.srl_cache.inner.lock().expect("cache");
+        let publication = cache.as_ref().expect("publication");
+        (publication.version, publication.lists.len())
+    }
+
+    #[test]
+    fn signed_lists_are_cached_until_the_version_moves() {
+        let state = crate::state::NewAppState::demo().expect("demo state");
+        for id in [0xC1, 0xC2] {
+            state.revocation_store.revoke_warrant_for_until("default", &[id; 16], None).expect("r");
+        }
+        let snapshot = publish(&state, None);
+        let version = snapshot.version;
+        let delta = publish(&state, Some(version - 1));
+        assert_eq!(cached_lists(&state), (version, 2));
+
+        // Repeated polls are served from the cache.
+        assert_eq!(publish(&state, None).digest, snapshot.digest);
+        assert_eq!(publish(&state, Some(version - 1)).digest, delta.digest);
+        assert!(publish(&state, Some(version)).srl.is_none());
+        assert_eq!(cached_lists(&state), (version, 2));
+
+        // A revocation starts a new publication.
+        state.revocation_store.revoke_warrant_for_until("default", &[0xC3; 16], None).expect("r");
+        let next = publish(&state, None);
+        assert_eq!(next.version, version + 1);
+        assert_ne!(next.state_root, snapshot.state_root);
+        assert_eq!(cached_lists(&state), (version + 1, 1));
+    }
+
+    #[test]
+    fn at_most_the_cap_of_lists_is_cached_per_version() {
+        let state = crate::state::NewAppState::demo().expect("demo state");
+        for id in 0..=MAX_CACHED_DELTAS as u8 {
+            state.revocation_store.revoke_warrant_for_until("default", &[id; 16], None).expect("r");
+        }
+        let version = publish(&state, None).version;
+        for since in 1..version {
+            assert!(publish(&state, Some(since)).srl.is_some());
+        }
+        assert_eq!(cached_lists(&state), (version, MAX_CACHED_DELTAS));
+    }
+}
@@ -1,30 +1,67 @@
 //! Application state shared by handlers.
 
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
-use ledgerflow_core::{RevocationCheck, SignerRef, SigningKeyPair, TrustedIssuers};
+use ledgerflow_core::{
+    RevocationCheck, SignerRef, SigningAlgorithm, SigningKeyPair, TrustAnchorSet, TrustedIssuers,
+};
 use ledgerflow_facilitator::{
-    DefaultSubjectResolver, EvmRailAdapter, FileRevocationStore, SettlementRegistry,
-    SettlementService, SharedRailAdapter, SolanaRailAdapter, VerificationService,
+    DefaultSubjectResolver, EvmRailAdapter, FileRevocationStore, InMemoryBudgetLedger,
+    SettlementRegistry, SettlementService, SharedRailAdapter, SharedRevocationStore,
+    SharedSettlementStore, SolanaRailAdapter, VerificationService,
+};
+use ledgerflow_protocol::{
+    InMemoryReplayStore, InMemoryWarrantRepository, ReplayStore, WarrantRepository,
 };
+use ledgerflow_wallet::{LocalRpcConfig, LocalRpcSigner};
+
+/// Shared replay store handle (nonce claims for hosted settlement).
+pub type SharedReplayStore = Arc<Mutex<dyn ReplayStore + Send>>;
+
+/// Shared warrant repository handle (issued and presented warrants, served
+/// by `GET /v1/warrants/{digest}`).
+pub type SharedWarrantRepository = Arc<Mutex<dyn WarrantRepository + Send>>;
 
 /// Application state.
 #[derive(Clone)]
 pub struct AppState {
     pub config: crate::config::ServerConfig,
     pub saas: crate::saas::SaasAuthExtractor,
-    pub verification: VerificationService<FileRevocationStore>,
+    pub verification: VerificationService<SharedRevocationStore>,
     pub settlement:
-        SettlementService<FileRevocationStore, DefaultSubjectResolver, SharedRailAdapter>,
-    pub registry: SettlementRegistry,
-    pub trusted: TrustedIssuers,
-    /// The issuer signing key used to issue warrants. Never a predictable demo
-    /// key: it is loaded from configuration (fail-fast when absent).
-    pub issuer_key: SigningKeyPair,
+        SettlementService<SharedRevocationStore, DefaultSubjectResolver, SharedRailAdapter>,
+    /// Settlement outcomes for `/status` queries (in-memory unless a
+    /// persistent store is attached, e.g. [`AppState::with_sqlite`]).
+    pub registry: SharedSettlementStore,
+    /// Process-wide trust anchors (`standalone` mode; see
+    /// [`AppState::trusted_for`]). Hot-swappable: see
+    /// [`AppState::reload_trusted_issuers`].
+    pub trusted: TrustAnchorSet,
+    /// The process issuer used to sign warrants: an in-memory key or a wallet
+    /// daemon. Never a predictable demo key: it is loaded from configuration
+    /// (fail-fast when absent). `saas` tenants never fall back to it (see
+    /// [`AppState::issuer_for`]).
+    pub issuer: crate::issuer::IssuerSigner,
+    /// Per-tenant issuer keys and trust anchors (design §6.8).
+    pub tenant_keys: crate::tenant_keys::TenantKeyRegistry,
     /// The revocation store, exposed for tenant-scoped admin operations
-    /// (design §10.2).
-    pub revocation_store: FileRevocationStore,
+    /// (design §10.2) and SRL publication: a [`FileRevocationStore`] unless a
+    /// persistent backend is attached (e.g. [`AppState::with_sqlite`]).
+    pub revocation_store: SharedRevocationStore,
     pub webhook: crate::webhook::WebhookSender,
+    /// Nonce claims for hosted `/v1/settle` (a proof settles at most once).
+    pub settle_replay: SharedReplayStore,
+    /// Pending human approvals opened by the hosted facilitator.
+    pub approvals: crate::approvals::ApprovalInbox,
+    /// Every issued warrant and every warrant of a verified chain, keyed by
+    /// digest: resolves digest-referenced parents for the hosted facilitator
+    /// and remote verifiers.
+    pub warrants: SharedWarrantRepository,
+    /// Control-plane key signing the revocation lists served by
+    /// `GET /v1/srl` (see [`crate::srl`]); `None` disables publication.
+    pub srl_signer: Option<SigningKeyPair>,
+    /// The signed lists published at the current revocation version.
+    pub srl_cache: crate::srl::SrlCache,
 }
 
 impl AppState {
@@ -34,18 +71,32 @@ impl AppState {
         revocation_path: &std::path::Path,
         trusted: TrustedIssuers,
     ) -> Result<Self, ServerStateError> {
-        let revocation = FileRevocationStore::open(revocation_path)?;
-        let settlement = SettlementService::new(
-            revocation.clone(),
+        let revocation: SharedRevocationStore =
+            Arc::new(FileRevocationStore::open(revocation_path)?);
+        let mut settlement = SettlementService::new(
+            Arc::clone(&revocation),
             DefaultSubjectResolver,
             vec![
                 Arc::new(EvmRailAdapter) as SharedRailAdapter,
                 Arc::new(SolanaRailAdapter) as SharedRailAdapter,
             ],
         );
+        if let Some(ledger_id) = &config.ledger_id {
+            settlement = settlement
+                .with_budget_ledger(Arc::new(InMemoryBudgetLedger::new(ledger_id.clone())));
+        }
         // The issuer key is mandatory; `NewAppState::demo` supplies a test key,
         // but production construction must provide a real key via config.
-        let issuer_key = load_issuer_key(&config)?;
+        let issuer = load_issuer(&config)?;
+        let srl_signer = config
+            .srl_key_hex
+            .as_deref()
+            .map(|hex| {
+                decode_hex::<32>(hex).map(|bytes| SigningKeyPair::from_bytes(&bytes)).ok_or_else(
+                    || ServerStateError::Srl("the SRL key must be 32-byte hex".to_string()),
+                )
+            })
+            .transpose()?;
         let webhook = match &config.webhook_url {
             Some(url) => crate::webhook::WebhookSender::with_delivery(url.clone()),
             None => crate::webhook::WebhookSender::disabled(),
@@ -56,32 +107,142 @@ impl AppState {
                 service_token: config.saas.service_token.clone(),
                 standalone_tenant: config.saas.tenant_id.clone(),
             },
-            verification: VerificationService::new(revocation.clone()),
+            verification: VerificationService::new(Arc::clone(&revocation)),
             settlement,
-            registry: SettlementRegistry::new(),
-            trusted,
-            issuer_key,
+            registry: Arc::new(SettlementRegistry::new()),
+            trusted: TrustAnchorSet::new(trusted),
+            issuer,
+            tenant_keys: crate::tenant_keys::TenantKeyRegistry::new(),
             revocation_store: revocation,
             webhook,
+            settle_replay: Arc::new(Mutex::new(InMemoryReplayStore::default())),
+            approvals: crate::approvals::ApprovalInbox::new(),
+            warrants: Arc::new(Mutex::new(InMemoryWarrantRepository::default())),
+            srl_signer,
+            srl_cache: crate::srl::SrlCache::default(),
             config,
         })
     }
+
+    /// Re-reads the configured trusted-issuers file and swaps it in, dropping
+    /// entries retired long enough ago that no warrant they signed is live.
+    /// Returns the number of anchors now in effect.
+    pub fn reload_trusted_issuers(&self, now_secs: u64) -> Result<usize, ServerStateError> {
+        let path = self.config.trusted_issuers_file.as_deref().ok_or_else(|| {
+            ServerStateError::TrustAnchors("no trusted-issuers file configured".to_string())
+        })?;
+        let mut trusted = load_trusted_issuers(path)?;
+        trusted.prune_retired(now_secs);
+        let count = trusted.issuers.len();
+        self.trusted.store(trusted);
+        Ok(count)
+    }
+
+    /// Swaps in `trusted`, first writing it back to the configured
+    /// trusted-issuers file (when there is one) so a later reload or restart
+    /// keeps it. Without a file the change lasts until the process exits.
+    pub fn replace_trusted_issuers(&self, trusted: TrustedIssuers) -> Result<(), ServerStateError> {
+        if let Some(path) = &self.config.trusted_issuers_file {
+            store_trusted_issuers(path, &trusted)?;
+        }
+        self.trusted.store(trusted);
+        Ok(())
+    }
+
+    /// Persists revocations, settlement outcomes, hosted-settlement nonce
+    /// claims, stored warrants and tenant issuer keyrings in `store`, so a
+    /// restarted node neither forgets a revocation or a settled transaction
+    /// nor accepts a used nonce again, and keeps serving (and verifying) the
+    /// warrants it issued. Verification, settlement and SRL publication all
+    /// read the database's revocations.
+    ///
+    /// Fails when the stored keyrings cannot be loaded.
+    #[cfg(feature = "sqlite")]
+    pub fn with_sqlite(
+        mut self,
+        store: &crate::sqlite::SqliteStore,
+    ) -> Result<Self, ServerStateError> {
+        let revocation: SharedRevocationStore = Arc::new(store.clone());
+        self.verification.revocation = Arc::clone(&revocation);
+        self.settlement.revocation = Arc::clone(&revocation);
+        self.revocation_store = revocation;
+        self.srl_cache = crate::srl::SrlCache::default();
+        self.registry = Arc::new(store.clone());
+        self.settle_replay = Arc::new(Mutex::new(store.replay_store()));
+        self.warrants = Arc::new(Mutex::new(store.clone()));
+        self.tenant_keys =
+            crate::tenant_keys::TenantKeyRegistry::with_store(Arc::new(store.clone()))?;
+        self.approvals = crate::approvals::ApprovalInbox::with_store(Arc::new(store.clone()))?;
+        Ok(self)
+    }
 }
 
-/// Loads the issuer signing key from configuration.
+/// Loads the process issuer from configuration: the configured issuer wallet,
+/// else `LEDGERFLOW_ISSUER_KEY`.
 ///
-/// Fails when `LEDGERFLOW_ISSUER_KEY` was not configured (the server must never
-/// default to a predictable demo key — design §6.8).
-fn load_issuer_key(
+/// Fails when neither was configured (the server must never default to a
+/// predictable demo key — design §6.8). No wallet call is made here; the
+/// wallet is first contacted when a warrant is issued.
+pub fn load_issuer(
     config: &crate::config::ServerConfig,
-) -> Result<SigningKeyPair, ServerStateError> {
+) -> Result<crate::issuer::IssuerSigner, ServerStateError> {
+    if let Some(wallet) = &config.issuer_wallet {
+        let issuer = crate::issuance::parse_signer(
+            &wallet.public_key_hex,
+            wallet.algorithm.as_deref(),
+            None,
+        )
+        .map_err(|reason| ServerStateError::Issuer(format!("issuer wallet key: {reason}")))?;
+        if !matches!(issuer.alg, SigningAlgorithm::Ed25519 | SigningAlgorithm::Secp256k1) {
+            return Err(ServerStateError::Issuer(format!(
+                "issuer wallets sign with ed25519 or secp256k1, not {}",
+                issuer.alg.as_str()
+            )));
+        }
+        let signer = LocalRpcSigner::new_http(LocalRpcConfig {
+            url: wallet.url.clone(),
+            ..LocalRpcConfig::default()
+        });
+        return Ok(crate::issuer::IssuerSigner::wallet(Arc::new(signer), issuer));
+    }
     let hex = config
         .issuer_key_hex
         .as_deref()
         .ok_or_else(|| ServerStateError::Issuer("issuer key not configured".to_string()))?;
     let bytes = decode_hex::<32>(hex)
         .ok_or_else(|| ServerStateError::Issuer("issuer key must be 32-byte hex".to_string()))?;
-    Ok(SigningKeyPair::from_bytes(&bytes))
+    Ok(crate::issuer::IssuerSigner::Local(SigningKeyPair::from_bytes(&bytes)))
+}
+
+/// Loads a trusted-issuer set from a JSON file (a serialized
+/// [`TrustedIssuers`]).
+pub fn load_trusted_issuers(path: &std::path::Path) -> Result<TrustedIssuers, ServerStateError> {
+    let raw = std::fs::read(path).map_err(|error| {
+        ServerStateError::TrustAnchors(format!("cannot read {}: {error}", path.display()))
+    })?;
+    serde_json::from_slice(&raw).map_err(|error| {
+        ServerStateError::TrustAnchors(format!("invalid {}: {error}", path.display()))
+    })
+}
+
+/// Writes a trusted-issuer set to `path` in the format
+/// [`load_trusted_issuers`] reads, replacing the file atomically.
+pub fn store_trusted_issuers(
+    path: &std::path::Path,
+    trusted: &TrustedIssuers,
+) -> Result<(), ServerStateError> {
+    let failed = |error: std::io::Error| {
+        ServerStateError::TrustAnchors(format!("cannot write {}: {error}", path.display()))
+    };
+    let json = serde_json::to_vec_pretty(trusted)
+        .map_err(|error| ServerStateError::TrustAnchors(error.to_string()))?;
+    let mut staging = path.as_os_str().to_owned();
+    staging.push(".tmp");
+    let staging = std::path::PathBuf::from(staging);
+    let mut file = std::fs::File::create(&staging).map_err(failed)?;
+    std::io::Write::write_all(&mut file, &json).map_err(failed)?;
+    file.sync_all().map_err(failed)?;
+    std::fs::rename(&staging, path).map_err(failed)
 }
 
 /// State construction failures.
@@ -91,6 +252,14 @@ pub enum ServerStateError {
     Revocation(#[from] ledgerflow_facilitator::RevocationStoreError),
     #[error("invalid issuer configuration: {0}")]
     Issuer(String),
+    #[error("invalid trust-anchor configuration: {0}")]
+    TrustAnchors(String),
+    #[error("invalid SRL configuration: {0}")]
+    Srl(String),
+    #[error("failed to load tenant issuer keys: {0}")]
+    TenantKeys(#[from] crate::tenant_keys::TenantKeyError),
+    #[error("failed to load pending approvals: {0}")]
+    Approvals(#[from] crate::approvals::ApprovalError),
 }
 
 /// Demo state builder used by tests and the CLI.
@@ -112,7 +281,12 @@ impl NewAppState {
             },
             // Demo issuer key (hex of 32 `0x01` bytes). Test-only.
             issuer_key_hex: Some(hex_encode(&[1_u8; 32])),
+            issuer_wallet: None,
             webhook_url: None,
+            ledger_id: None,
+            trusted_issuers_file: None,
+            // Demo SRL key (hex of 32 `0x03` bytes). Test-only.
+            srl_key_hex: Some(hex_encode(&[3_u8; 32])),
         };
         let issuer = SigningKeyPair::from_bytes(&[1_u8; 32]);
         let mut trusted = TrustedIssuers::new();
@@ -250,7 +424,7 @@ mod tests {
         let tool_arguments = std::collections::BTreeMap::new();
         let outcome = state.verification.verify(&ledgerflow_facilitator::VerifyRequest {
             chain: &chain,
-            trusted: &state.trusted,
+            trusted: &state.trusted.load(),
             proof: &proof,
             context: &context,
             approvals: &[],
@@ -263,6 +437,7 @@ mod tests {
             chain: &chain,
             proof: &proof,
             context: &context,
+            settlement_payload: "",
             now_ms,
         });
 
@@ -0,0 +1,451 @@
+//! Per-tenant issuer keys and trust anchors (design §6.8 / §10.2).
+//!
+//! In `saas` mode every tenant issues with its own root key and verifies
+//! against its own trust anchors; tenant A's keys never sign or verify for
+//! tenant B. A tenant's keyring holds one **active** key (used for issuance)
+//! plus every key it rotated away from. Rotation closes the old key's
+//! validity window (`not_after`, design §6.8): warrants it signed before the
+//! rotation keep verifying until they expire, but it anchors no later root.
+//!
+//! Keyrings hold secret keys, so a restart-safe deployment attaches a
+//! [`TenantKeyStore`] (the `sqlite` backend; see `AppState::with_sqlite`);
+//! without one they live in memory only.
+//!
+//! `standalone` mode keeps the process-wide issuer key and trust set; keys
+//! registered for the standalone tenant take precedence for issuance and are
+//! added to its anchors.
+//!
+//! Admin endpoints (tenant-scoped, `admin` role required in `saas` mode):
+//!
+//! - `GET  /v1/admin/issuer-keys` — list the tenant's keys (public halves only).
+//! - `POST /v1/admin/issuer-keys` — create the tenant's first key.
+//! - `POST /v1/admin/issuer-keys/rotate` — generate a new active key.
+
+use std::{
+    collections::BTreeMap,
+    sync::{Arc, RwLock},
+};
+
+use axum::{Json, extract::State};
+use ledgerflow_core::{SignerRef, SigningKeyPair, TrustedIssuer, TrustedIssuers, hex_encode_bytes};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    api::{ApiError, ApiResponse, now_ms},
+    config::SaasMode,
+    issuer::IssuerSigner,
+    saas::SaaSContext,
+    state::AppState,
+};
+
+/// Role required (in `saas` mode) to manage a tenant's issuer keys.
+pub const ADMIN_ROLE: &str = "admin";
+
+// ---------------------------------------------------------------------------
+// Registry
+// ---------------------------------------------------------------------------
+
+/// One issuer key in a tenant keyring.
+#[derive(Clone, Debug)]
+pub struct TenantIssuerKey {
+    /// Key id carried in the issuer `SignerRef` of warrants it signs.
+    pub key_id: String,
+    pub signing: SigningKeyPair,
+    pub created_at_ms: u64,
+    /// `true` for the key used for new issuance.
+    pub active: bool,
+    /// Latest root `issued_at` (Unix seconds) this key anchors; set when it
+    /// is rotated out.
+    pub not_after: Option<u64>,
+}
+
+impl TenantIssuerKey {
+    /// The issuer reference (with key id) this key signs as.
+    #[must_use]
+    pub fn signer_ref(&self) -> SignerRef {
+        self.signing.signer_ref().with_key_id(self.key_id.clone())
+    }
+
+    fn info(&self) -> TenantKeyInfo {
+        TenantKeyInfo {
+            key_id: self.key_id.clone(),
+            algorithm: self.signing.signer_ref().alg.as_str().to_string(),
+            public_key: hex_encode_bytes(&self.signing.public_key_bytes()),
+            created_at_ms: self.created_at_ms,
+            active: self.active,
+            not_after: self.not_after,
+        }
+    }
+}
+
+/// Public view of a tenant issuer key.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, ToSchema)]
+pub struct TenantKeyInfo {
+    pub key_id: String,
+    pub algorithm: String,
+    /// Hex-encoded public key.
+    pub public_key: String,
+    pub created_at_ms: u64,
+    pub active: bool,
+    /// End of the key's validity window (Unix seconds) once rotated out.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub not_after: Option<u64>,
+}
+
+/// Tenant key registry failures.
+#[derive(Debug, thiserror::Error)]
+pub enum TenantKeyError {
+    #[error("tenant `{0}` already has issuer keys; rotate instead")]
+    AlreadyExists(String),
+    #[error("tenant `{0}` has no issuer keys")]
+    NotFound(String),
+    #[error("tenant key registry lock poisoned")]
+    Poisoned,
+    #[error("tenant key storage failed: {0}")]
+    Storage(String),
+}
+
+/// Storage seam for tenant keyrings. Stores hold secret keys and must be
+/// protected like the process issuer key.
+pub trait TenantKeyStore: std::fmt::Debug + Send + Sync {
+    /// Replaces the stored keyring of `tenant_id` with `keyring`, atomically.
+    fn save_keyring(
+        &self,
+        tenant_id: &str,
+        keyring: &[TenantIssuerKey],
+    ) -> Result<(), TenantKeyError>;
+
+    /// Every stored keyring, each oldest first.
+    fn load_keyrings(&self) -> Result<BTreeMap<String, Vec<TenantIssuerKey>>, TenantKeyError>;
+}
+
+/// Shared tenant key store handle.
+pub type SharedTenantKeyStore = Arc<dyn TenantKeyStore>;
+
+/// Per-tenant issuer keyrings (cheap to clone; clones share state).
+#[derive(Clone, Debug, Default)]
+pub struct TenantKeyRegistry {
+    keyrings: Arc<RwLock<BTreeMap<String, Vec<TenantIssuerKey>>>>,
+    /// Write-through persistence; `None` keeps keyrings in memory only.
+    store: Option<SharedTenantKeyStore>,
+}
+
+impl TenantKeyRegistry {
+    /// Creates an empty in-memory registry (not restart-safe).
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a registry persisted in `store`, loading the keyrings it
+    /// already holds.
+    pub fn with_store(store: SharedTenantKeyStore) -> Result<Self, TenantKeyError> {
+        let keyrings = store.load_keyrings()?;
+        Ok(Self { keyrings: Arc::new(RwLock::new(keyrings)), store: Some(store) })
+    }
+
+    /// Creates the tenant's first (active) key from a random seed.
+    pub fn create(&self, tenant_id: &str, now_ms: u64) -> Result<TenantKeyInfo, TenantKeyError> {
+        self.create_with(tenant_id, SigningKeyPair::from_bytes(&rand::random()), now_ms)
+    }
+
+    /// Creates the tenant's first (active) key from `signing` (imported or
+    /// loaded from configuration).
+    pub fn create_with(
+        &self,
+        tenant_id: &str,
+        signing: SigningKeyPair,
+        now_ms: u64,
+    ) -> Result<TenantKeyInfo, TenantKeyError> {
+        let mut keyrings = self.keyrings.write().map_err(|_| TenantKeyError::Poisoned)?;
+        if keyrings.get(tenant_id).is_some_and(|keyring| !keyring.is_empty()) {
+            return Err(TenantKeyError::AlreadyExists(tenant_id.to_string()));
+        }
+        let mut keyring = Vec::new();
+        let info = push_active(&mut keyring, tenant_id, signing, now_ms);
+        self.persist(tenant_id, &keyring)?;
+        keyrings.insert(tenant_id.to_string(), keyring);
+        Ok(info)
+    }
+
+    /// Generates a new active key; see [`rotate_to`](Self::rotate_to).
+    pub fn rotate(&self, tenant_id: &str, now_ms: u64) -> Result<TenantKeyInfo, TenantKeyError> {
+        self.rotate_to(tenant_id, SigningKeyPair::from_bytes(&rand::random()), now_ms)
+    }
+
+    /// Makes `signing` the active key. The previous keys stay trusted for
+    /// roots issued up to now (their `not_after` closes at `now_ms`).
+    pub fn rotate_to(
+        &self,
+        tenant_id: &str,
+        signing: SigningKeyPair,
+        now_ms: u64,
+    ) -> Result<TenantKeyInfo, TenantKeyError> {
+        let mut keyrings = self.keyrings.write().map_err(|_| TenantKeyError::Poisoned)?;
+        let mut keyring = keyrings
+            .get(tenant_id)
+            .filter(|keyring| !keyring.is_empty())
+            .cloned()
+            .ok_or_else(|| TenantKeyError::NotFound(tenant_id.to_string()))?;
+        let now_secs = now_ms / 1_000;
+        for key in &mut keyring {
+            key.active = false;
+            key.not_after = Some(key.not_after.map_or(now_secs, |end| end.min(now_secs)));
+        }
+        let info = push_active(&mut keyring, tenant_id, signing, now_ms);
+        self.persist(tenant_id, &keyring)?;
+        keyrings.insert(tenant_id.to_string(), keyring);
+        Ok(info)
+    }
+
+    /// Writes `keyring` through to the store (before it becomes visible, so
+    /// a failed write changes nothing).
+    fn persist(&self, tenant_id: &str, keyring: &[TenantIssuerKey]) -> Result<(), TenantKeyError> {
+        self.store.as_ref().map_or(Ok(()), |store| store.save_keyring(tenant_id, keyring))
+    }
+
+    /// Lists the tenant's keys, oldest first.
+    pub fn list(&self, tenant_id: &str) -> Result<Vec<TenantKeyInfo>, TenantKeyError> {
+        let keyrings = self.keyrings.read().map_err(|_| TenantKeyError::Poisoned)?;
+        Ok(keyrings
+            .get(tenant_id)
+            .map(|keyring| keyring.iter().map(TenantIssuerKey::info).collect())
+            .unwrap_or_default())
+    }
+
+    /// The tenant's active issuance key, if any.
+    pub fn active_key(&self, tenant_id: &str) -> Result<Option<TenantIssuerKey>, TenantKeyError> {
+        let keyrings = self.keyrings.read().map_err(|_| TenantKeyError::Poisoned)?;
+        Ok(keyrings
+            .get(tenant_id)
+            .and_then(|keyring| keyring.iter().find(|key| key.active))
+            .cloned())
+    }
+
+    /// The tenant's trust anchors: every key in its keyring, within its
+    /// validity window (empty when the tenant has none, which is
+    /// fail-closed).
+    pub fn trusted_issuers(&self, tenant_id: &str) -> Result<TrustedIssuers, TenantKeyError> {
+        let keyrings = self.keyrings.read().map_err(|_| TenantKeyError::Poisoned)?;
+        let mut trusted = TrustedIssuers::new();
+        for key in keyrings.get(tenant_id).into_iter().flatten() {
+            trusted.add(
+                TrustedIssuer::new(key.key_id.clone(), key.signer_ref())
+                    .with_validity(None, key.not_after),
+            );
+        }
+        Ok(trusted)
+    }
+}
+
+fn push_active(
+    keyring: &mut Vec<TenantIssuerKey>,
+    tenant_id: &str,
+    signing: SigningKeyPair,
+    now_ms: u64,
+) -> TenantKeyInfo {
+    let key = TenantIssuerKey {
+        key_id: format!("{tenant_id}#{}", keyring.len() + 1),
+        signing,
+        created_at_ms: now_ms,
+        active: true,
+        not_after: None,
+    };
+    let info = key.info();
+    keyring.push(key);
+    info
+}
+
+// ---------------------------------------------------------------------------
+// Tenant selection
+// ---------------------------------------------------------------------------
+
+impl AppState {
+    /// Selects the issuer signer (and the `SignerRef` it signs as) for the
+    /// request's tenant.
+    ///
+    /// A registered tenant key always wins. Without one, `standalone` mode
+    /// falls back to the process issuer key; `saas` mode fails closed rather
+    /// than signing with a key shared across tenants.
+    pub fn issuer_for(
+        &self,
+        ctx: &SaaSContext,
+    ) -> Result<(IssuerSigner, SignerRef), TenantKeyError> {
+        if let Some(key) = self.tenant_keys.active_key(&ctx.tenant_id)? {
+            let signer = key.signer_ref();
+            return Ok((IssuerSigner::Local(key.signing), signer));
+        }
+        match self.config.saas.mode {
+            SaasMode::Standalone => Ok((self.issuer.clone(), self.issuer.signer_ref())),
+            SaasMode::Saas => Err(TenantKeyError::NotFound(ctx.tenant_id.clone())),
+        }
+    }
+
+    /// Selects the trust anchors for the request's tenant.
+    ///
+    /// `saas` mode uses only the tenant's keyring; `standalone` mode adds the
+    /// tenant's keys to the process-wide set.
+    pub fn trusted_for(&self, ctx: &SaaSContext) -> Result<TrustedIssuers, TenantKeyError> {
+        let tenant = self.tenant_keys.trusted_issuers(&ctx.tenant_id)?;
+        Ok(match self.config.saas.mode {
+            SaasMode::Saas => tenant,
+            SaasMode::Standalone => {
+                let mut trusted = TrustedIssuers::clone(&self.trusted.load());
+                trusted.issuers.extend(tenant.issuers);
+                trusted
+            }
+        })
+    }
+}
+
+impl From<TenantKeyError> for ApiError {
+    fn from(error: TenantKeyError) -> Self {
+        match error {
+            TenantKeyError::AlreadyExists(_) | TenantKeyError::NotFound(_) => {
+                Self::BadRequest(error.to_string())
+            }
+            TenantKeyError::Poisoned | TenantKeyError::Storage(_) => {
+                Self::Internal(error.to_string())
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Admin endpoints
+// ---------------------------------------------------------------------------
+
+/// Lists the tenant's issuer keys.
+#[utoipa::path(
+    get,
+    path = "/v1/admin/issuer-keys",
+    responses(
+        (status = 200, description = "Tenant issuer keys", body = Vec<TenantKeyInfo>),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub(crate) async fn list_keys(
+    State(state): State<AppState>,
+    ctx: SaaSContext,
+) -> Result<Json<ApiResponse<Vec<TenantKeyInfo>>>, ApiError> {
+    require_admin(&state, &ctx)?;
+    Ok(Json(ApiResponse::ok(state.tenant_keys.list(&ctx.tenant_id)?)))
+}
+
+/// Creates the tenant's first issuer key.
+#[utoipa::path(
+    post,
+    path = "/v1/admin/issuer-keys",
+    responses(
+        (status = 200, description = "Key created", body = TenantKeyInfo),
+        (status = 400, description = "Tenant already has keys"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub(crate) async fn create_key(
+    State(state): State<AppState>,
+    ctx: SaaSContext,
+) -> Result<Json<ApiResponse<TenantKeyInfo>>, ApiError> {
+    require_admin(&state, &ctx)?;
+    Ok(Json(ApiResponse::ok(state.tenant_keys.create(&ctx.tenant_id, now_ms())?)))
+}
+
+/// Rotates the tenant's active issuer key.
+#[utoipa::path(
+    post,
+    path = "/v1/admin/issuer-keys/rotate",
+    responses(
+        (status = 200, description = "New active key", body = TenantKeyInfo),
+        (status = 400, description = "Tenant has no keys"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub(crate) async fn rotate_key(
+    State(state): State<AppState>,
+    ctx: SaaSContext,
+) -> Result<Json<ApiResponse<TenantKeyInfo>>, ApiError> {
+    require_admin(&state, &ctx)?;
+    Ok(Json(ApiResponse::ok(state.tenant_keys.rotate(&ctx.tenant_id, now_ms())?)))
+}
+
+/// In `saas` mode only gateway-asserted admins manage keys; `standalone` has
+/// a single trusted operator.
+pub(crate) fn require_admin(state: &AppState, ctx: &SaaSContext) -> Result<(), ApiError> {
+    match state.config.saas.mode {
+        SaasMode::Standalone => Ok(()),
+        SaasMode::Saas if ctx.roles.iter().any(|role| role == ADMIN_ROLE) => Ok(()),
+        SaasMode::Saas => Err(ApiError::Unauthorized),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::expect_used)]
+
+    use super::*;
+
+    #[test]
+    fn rotation_closes_the_old_key_window_and_issues_with_the_new_one() {
+        let registry = TenantKeyRegistry::new();
+        let first = registry
+            .create_with("tenant-a", SigningKeyPair::from_bytes(&[7_u8; 32]), 1_000)
+            .expect("create");
+        assert!(matches!(
+            registry.create("tenant-a", 2_000),
+            Err(TenantKeyError::AlreadyExists(_))
+        ));
+
+        let second = registry
+            .rotate_to("tenant-a", SigningKeyPair::from_bytes(&[8_u8; 32]), 3_000)
+            .expect("rotate");
+        assert_ne!(first.key_id, second.key_id);
+        let active = registry.active_key("tenant-a").expect("lock").expect("active");
+        assert_eq!(active.key_id, second.key_id);
+        assert_eq!(active.signer_ref().key_id.as_deref(), Some(second.key_id.as_str()));
+
+        // The retired key anchors roots issued up to the rotation only.
+        let retired = SigningKeyPair::from_bytes(&[7_u8; 32]).signer_ref();
+        let trusted = registry.trusted_issuers("tenant-a").expect("trusted");
+        assert!(trusted.contains_at(&retired, 3));
+        assert!(!trusted.contains_at(&retired, 4));
+        assert!(trusted.contains_at(&SigningKeyPair::from_bytes(&[8_u8; 32]).signer_ref(), 4));
+        let listed = registry.list("tenant-a").expect("list");
+        assert_eq!(listed.iter().map(|key| key.active).collect::<Vec<_>>(), vec![false, true]);
+        assert_eq!(listed.iter().map(|key| key.not_after).collect::<Vec<_>>(), vec![Some(3), None]);
+    }
+
+    #[test]
+    fn tenants_are_isolated() {
+        let registry = TenantKeyRegistry::new();
+        registry.create("tenant-a", 1).expect("create");
+        assert!(registry.active_key("tenant-b").expect("lock").is_none());
+        assert!(registry.trusted_issuers("tenant-b").expect("trusted").is_empty());
+        assert!(matches!(registry.rotate("tenant-b", 2), Err(TenantKeyError::NotFound(_))));
+    }
+
+    #[test]
+    fn saas_tenants_never_fall_back_to_the_process_key() {
+        let mut state = crate::state::NewAppState::demo().expect("demo state");
+        state.config.saas.mode = SaasMode::Saas;
+        let tenant_a = SaaSContext::standalone("tenant-a");
+        let tenant_b = SaaSContext::standalone("tenant-b");
+        assert!(matches!(state.issuer_for(&tenant_a), Err(TenantKeyError::NotFound(_))));
+        assert!(state.trusted_for(&tenant_a).expect("trusted").is_empty());
+
+        state.tenant_keys.create("tenant-a", 1).expect("create");
+        let (_, issuer) = state.issuer_for(&tenant_a).expect("tenant key");
+        assert!(!state.trusted.load().contains(&issuer));
+        assert!(state.trusted_for(&tenant_a).expect("trusted").contains(&issuer));
+        assert!(!state.trusted_for(&tenant_b).expect("trusted").contains(&issuer));
+    }
+
+    #[test]
+    fn standalone_falls_back_to_the_process_key() {
+        let state = crate::state::NewAppState::demo().expect("demo state");
+        let ctx = SaaSContext::standalone("default");
+        let (_, issuer) = state.issuer_for(&ctx).expect("process key");
+        assert_eq!(issuer, state.issuer.signer_ref());
+        assert!(state.trusted_for(&ctx).expect("trusted").contains(&issuer));
+    }
+}
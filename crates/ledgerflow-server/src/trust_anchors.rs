@@ -0,0 +1,259 @@
+//! Process-wide trust-anchor administration (design §6.8 key rotation).
+//!
+//! The `standalone` trust set lives in a hot-swappable
+//! [`TrustAnchorSet`](ledgerflow_core::TrustAnchorSet); these endpoints
+//! replace it or reload it from `LEDGERFLOW_TRUSTED_ISSUERS_FILE` without a
+//! restart. A replacement is written back to that file before it takes
+//! effect, so a later reload or restart keeps it (a server without the file
+//! holds it in memory only). A rotation is expressed with validity windows: keep the old root
+//! with a `not_after` that closes the overlap and add the new root.
+//!
+//! - `GET  /v1/admin/trust-anchors` — current anchors.
+//! - `PUT  /v1/admin/trust-anchors` — replace the anchor set (and the configured file).
+//! - `POST /v1/admin/trust-anchors/reload` — reload from the configured file.
+//!
+//! `saas` tenants verify against their own keyrings
+//! ([`crate::tenant_keys`]), so the process-wide set is not administrable in
+//! `saas` mode.
+
+use axum::{Json, extract::State};
+use ledgerflow_core::{AgentIdRef, TrustedIssuer, TrustedIssuers, hex_encode_bytes};
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+use crate::{
+    api::{ApiError, ApiResponse, now_ms},
+    config::SaasMode,
+    saas::SaaSContext,
+    state::AppState,
+    tenant_keys::require_admin,
+};
+
+/// A trusted issuer entry.
+#[derive(Clone, Debug, Deserialize, Eq, PartialEq, Serialize, ToSchema)]
+pub struct TrustAnchorBody {
+    pub key_id: String,
+    /// Hex-encoded issuer public key.
+    pub public_key: String,
+    /// Key algorithm (default `ed25519`).
+    #[serde(default)]
+    pub algorithm: Option<String>,
+    /// Optional EIP-8004 agent identity anchoring this issuer.
+    #[serde(default)]
+    pub anchor: Option<String>,
+    /// Earliest root `issued_at` (Unix seconds) accepted.
+    #[serde(default)]
+    pub not_before: Option<u64>,
+    /// Latest root `issued_at` (Unix seconds) accepted.
+    #[serde(default)]
+    pub not_after: Option<u64>,
+}
+
+impl TrustAnchorBody {
+    fn from_entry(entry: &TrustedIssuer) -> Self {
+        Self {
+            key_id: entry.key_id.clone(),
+            public_key: hex_encode_bytes(&entry.issuer.public_key),
+            algorithm: Some(entry.issuer.alg.as_str().to_string()),
+            anchor: entry.anchor.as_ref().map(ToString::to_string),
+            not_before: entry.not_before,
+            not_after: entry.not_after,
+        }
+    }
+
+    fn into_entry(self, index: usize) -> Result<TrustedIssuer, ApiError> {
+        let field = |reason: String| ApiError::BadRequest(format!("anchors[{index}]: {reason}"));
+        if self.key_id.is_empty() {
+            return Err(field("key_id must not be empty".to_string()));
+        }
+        if let (Some(not_before), Some(not_after)) = (self.not_before, self.not_after) &&
+            not_before > not_after
+        {
+            return Err(field("not_before is after not_after".to_string()));
+        }
+        let issuer = crate::issuance::parse_signer(
+            &self.public_key,
+            self.algorithm.as_deref(),
+            Some(self.key_id.clone()),
+        )
+        .map_err(field)?;
+        let entry = match self.anchor {
+            Some(anchor) => {
+                let anchor =
+                    AgentIdRef::parse(&anchor).map_err(|error| field(error.to_string()))?;
+                TrustedIssuer::anchored(self.key_id, issuer, anchor)
+            }
+            None => TrustedIssuer::new(self.key_id, issuer),
+        };
+        Ok(entry.with_validity(self.not_before, self.not_after))
+    }
+}
+
+/// Replace-anchors request body.
+#[derive(Clone, Debug, Deserialize, Serialize, ToSchema)]
+pub struct TrustAnchorsRequest {
+    pub anchors: Vec<TrustAnchorBody>,
+}
+
+/// Lists the process-wide trust anchors.
+#[utoipa::path(
+    get,
+    path = "/v1/admin/trust-anchors",
+    responses(
+        (status = 200, description = "Current trust anchors", body = Vec<TrustAnchorBody>),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub(crate) async fn list_anchors(
+    State(state): State<AppState>,
+    ctx: SaaSContext,
+) -> Result<Json<ApiResponse<Vec<TrustAnchorBody>>>, ApiError> {
+    require_operator(&state, &ctx)?;
+    Ok(Json(ApiResponse::ok(anchor_bodies(&state.trusted.load()))))
+}
+
+/// Replaces the process-wide trust anchors.
+///
+/// The new set is written to `LEDGERFLOW_TRUSTED_ISSUERS_FILE` before it is
+/// swapped in, so a removed root stays removed across a reload or restart.
+/// Without a configured file the change is in-memory only and is lost on
+/// restart.
+#[utoipa::path(
+    put,
+    path = "/v1/admin/trust-anchors",
+    request_body = TrustAnchorsRequest,
+    responses(
+        (status = 200, description = "Anchors now in effect", body = Vec<TrustAnchorBody>),
+        (status = 400, description = "Invalid anchor"),
+        (status = 401, description = "Unauthorized"),
+        (status = 500, description = "The anchors file could not be written")
+    )
+)]
+pub(crate) async fn replace_anchors(
+    State(state): State<AppState>,
+    ctx: SaaSContext,
+    Json(request): Json<TrustAnchorsRequest>,
+) -> Result<Json<ApiResponse<Vec<TrustAnchorBody>>>, ApiError> {
+    require_operator(&state, &ctx)?;
+    // An empty set would reject every chain; require an explicit anchor.
+    if request.anchors.is_empty() {
+        return Err(ApiError::BadRequest("at least one trust anchor is required".to_string()));
+    }
+    let issuers = request
+        .anchors
+        .into_iter()
+        .enumerate()
+        .map(|(index, body)| body.into_entry(index))
+        .collect::<Result<Vec<_>, _>>()?;
+    state
+        .replace_trusted_issuers(TrustedIssuers { issuers })
+        .map_err(|error| ApiError::Internal(error.to_string()))?;
+    Ok(Json(ApiResponse::ok(anchor_bodies(&state.trusted.load()))))
+}
+
+/// Reloads the process-wide trust anchors from the configured file.
+#[utoipa::path(
+    post,
+    path = "/v1/admin/trust-anchors/reload",
+    responses(
+        (status = 200, description = "Anchors now in effect", body = Vec<TrustAnchorBody>),
+        (status = 400, description = "No file configured or invalid file"),
+        (status = 401, description = "Unauthorized")
+    )
+)]
+pub(crate) async fn reload_anchors(
+    State(state): State<AppState>,
+    ctx: SaaSContext,
+) -> Result<Json<ApiResponse<Vec<TrustAnchorBody>>>, ApiError> {
+    require_operator(&state, &ctx)?;
+    state
+        .reload_trusted_issuers(now_ms() / 1_000)
+        .map_err(|error| ApiError::BadRequest(error.to_string()))?;
+    Ok(Json(ApiResponse::ok(anchor_bodies(&state.trusted.load()))))
+}
+
+fn anchor_bodies(trusted: &TrustedIssuers) -> Vec<TrustAnchorBody> {
+    trusted.issuers.iter().map(TrustAnchorBody::from_entry).collect()
+}
+
+fn require_operator(state: &AppState, ctx: &SaaSContext) -> Result<(), ApiError> {
+    require_admin(state, ctx)?;
+    match state.config.saas.mode {
+        SaasMode::Standalone => Ok(()),
+        SaasMode::Saas => Err(ApiError::BadRequest(
+            "process-wide trust anchors are not used in saas mode; manage tenant issuer keys"
+                .to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::expect_used)]
+
+    use ledgerflow_core::SigningKeyPair;
+
+    use super::*;
+
+    #[test]
+    fn reload_swaps_anchors_from_the_configured_file() {
+        let mut state = crate::state::NewAppState::demo().expect("demo state");
+        let rotated = SigningKeyPair::from_bytes(&[9_u8; 32]).signer_ref();
+        let mut file_set = TrustedIssuers::new();
+        file_set.add(TrustedIssuer::new("issuer-2".to_string(), rotated.clone()));
+        file_set.add(
+            TrustedIssuer::new("retired".to_string(), rotated.clone()).with_validity(None, Some(0)),
+        );
+        let path = std::env::temp_dir()
+            .join(format!("ledgerflow-trust-anchors-{}.json", std::process::id()));
+        std::fs::write(&path, serde_json::to_vec(&file_set).expect("json")).expect("write");
+
+        let reader = state.clone();
+        state.config.trusted_issuers_file = Some(path.clone());
+        let now_secs = ledgerflow_core::MAX_WARRANT_TTL_SECS + 1;
+        assert_eq!(state.reload_trusted_issuers(now_secs).expect("reload"), 1);
+        assert!(reader.trusted.load().contains(&rotated));
+        assert!(!reader.trusted.load().contains(&state.issuer.signer_ref()));
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn replacements_are_written_back_to_the_configured_file() {
+        let mut state = crate::state::NewAppState::demo().expect("demo state");
+        let path = std::env::temp_dir()
+            .join(format!("ledgerflow-trust-anchors-replace-{}.json", std::process::id()));
+        state.config.trusted_issuers_file = Some(path.clone());
+        let rotated = SigningKeyPair::from_bytes(&[9_u8; 32]).signer_ref();
+        let mut replacement = TrustedIssuers::new();
+        replacement.add(TrustedIssuer::new("issuer-2".to_string(), rotated.clone()));
+
+        state.replace_trusted_issuers(replacement).expect("replace");
+        assert!(state.trusted.load().contains(&rotated));
+        // A reload keeps the replacement instead of reverting to the old set.
+        state.reload_trusted_issuers(0).expect("reload");
+        assert!(state.trusted.load().contains(&rotated));
+        assert!(!state.trusted.load().contains(&state.issuer.signer_ref()));
+        std::fs::remove_file(path).ok();
+    }
+
+    #[test]
+    fn anchor_bodies_round_trip_validity_windows() {
+        let body = TrustAnchorBody {
+            key_id: "issuer-2".to_string(),
+            public_key: hex_encode_bytes(
+                &SigningKeyPair::from_bytes(&[9_u8; 32]).public_key_bytes(),
+            ),
+            algorithm: None,
+            anchor: None,
+            not_before: Some(10),
+            not_after: Some(20),
+        };
+        let entry = body.clone().into_entry(0).expect("entry");
+        assert!(entry.is_valid_at(15) && !entry.is_valid_at(21));
+        let echoed = TrustAnchorBody::from_entry(&entry);
+        assert_eq!((echoed.not_before, echoed.not_after), (Some(10), Some(20)));
+
+        let inverted = TrustAnchorBody { not_before: Some(30), ..body };
+        assert!(matches!(inverted.into_entry(0), Err(ApiError::BadRequest(_))));
+    }
+}
@@ -23,6 +23,14 @@ pub enum WebhookEvent {
     WarrantIssued { tenant_id: String, warrant_id: String },
     /// A warrant was revoked.
     WarrantRevoked { tenant_id: String, warrant_id: String },
+    /// A holder key was revoked (hex public key).
+    HolderRevoked { tenant_id: String, public_key: String },
+    /// A payment subject was revoked.
+    PaymentSubjectRevoked { tenant_id: String, payment_subject: String },
+    /// An issuer or sub-issuer key was revoked (hex public key).
+    IssuerRevoked { tenant_id: String, public_key: String },
+    /// A `ledgerflow.agent_id` was revoked.
+    AgentRevoked { tenant_id: String, agent_id: String },
     /// A payment was settled.
     PaymentSettled { tenant_id: String, transaction_id: String, amount: u128 },
     /// An approval was requested.
@@ -36,6 +44,10 @@ impl WebhookEvent {
         match self {
             Self::WarrantIssued { tenant_id, .. } |
             Self::WarrantRevoked { tenant_id, .. } |
+            Self::HolderRevoked { tenant_id, .. } |
+            Self::PaymentSubjectRevoked { tenant_id, .. } |
+            Self::IssuerRevoked { tenant_id, .. } |
+            Self::AgentRevoked { tenant_id, .. } |
             Self::PaymentSettled { tenant_id, .. } |
             Self::ApprovalRequested { tenant_id, .. } => tenant_id,
         }
@@ -47,6 +59,10 @@ impl WebhookEvent {
         match self {
             Self::WarrantIssued { .. } => "warrant.issued",
             Self::WarrantRevoked { .. } => "warrant.revoked",
+            Self::HolderRevoked { .. } => "holder.revoked",
+            Self::PaymentSubjectRevoked { .. } => "payment_subject.revoked",
+            Self::IssuerRevoked { .. } => "issuer.revoked",
+            Self::AgentRevoked { .. } => "agent.revoked",
             Self::PaymentSettled { .. } => "payment.settled",
             Self::ApprovalRequested { .. } => "approval.requested",
         }
@@ -136,6 +136,39 @@ fn config_defaults_to_standalone_without_saas_env() {
     }
 }
 
+#[test]
+fn config_accepts_an_issuer_wallet_instead_of_a_key() {
+    let _guard = ENV_LOCK.lock().expect("env lock");
+    unsafe {
+        std::env::remove_var("LEDGERFLOW_SAAS_MODE");
+        std::env::remove_var("LEDGERFLOW_ISSUER_KEY");
+        std::env::set_var("LEDGERFLOW_ISSUER_WALLET_URL", "http://127.0.0.1:18080");
+    }
+    let error = ServerConfig::from_env().expect_err("wallet key is required");
+    assert!(error.to_string().contains("LEDGERFLOW_ISSUER_WALLET_KEY is required"));
+
+    unsafe {
+        std::env::set_var("LEDGERFLOW_ISSUER_WALLET_KEY", "8a".repeat(32));
+    }
+    let config = ServerConfig::from_env().expect("wallet issuer");
+    let wallet = config.issuer_wallet.clone().expect("wallet config");
+    assert_eq!(wallet.url, "http://127.0.0.1:18080");
+    assert!(config.issuer_key_hex.is_none());
+    let issuer = ledgerflow_server::load_issuer(&config).expect("issuer");
+    assert_eq!(issuer.signer_ref().public_key, vec![0x8a; 32]);
+
+    unsafe {
+        std::env::set_var("LEDGERFLOW_ISSUER_KEY", "01".repeat(32));
+    }
+    let error = ServerConfig::from_env().expect_err("ambiguous issuer");
+    assert!(error.to_string().contains("not both"));
+    unsafe {
+        std::env::remove_var("LEDGERFLOW_ISSUER_KEY");
+        std::env::remove_var("LEDGERFLOW_ISSUER_WALLET_URL");
+        std::env::remove_var("LEDGERFLOW_ISSUER_WALLET_KEY");
+    }
+}
+
 #[test]
 fn api_health_endpoint_responds() {
     let state = ledgerflow_server::NewAppState::demo().expect("demo state");
@@ -181,3 +214,828 @@ fn api_revocation_endpoint_works_end_to_end() {
     assert_eq!(result.0, axum::http::StatusCode::OK);
     assert!(result.1.contains("\"ok\":true"));
 }
+
+#[test]
+fn api_revokes_subjects_issuers_and_agents_per_tenant() {
+    use ledgerflow_core::{
+        PaymentSubjectKind, PaymentSubjectRef, RevocationCheck, RevocationDecision,
+        Secp256k1KeyPair, SignerRef, SigningAlgorithm, SigningKeyPair, hex_encode_bytes,
+    };
+
+    let state = ledgerflow_server::NewAppState::demo().expect("demo state");
+    let store = state.revocation_store.clone();
+    let app = ledgerflow_server::api::router().with_state(state);
+    // Keys unrelated to the demo issuer: the demo revocation file is shared
+    // by every test in this process.
+    let issuer = SigningKeyPair::from_bytes(&[0x5C; 32]).signer_ref();
+    let subject = PaymentSubjectRef::new(PaymentSubjectKind::Opaque, "scoped-revocation-payer");
+    let evm_key = Secp256k1KeyPair::from_bytes(&[0x5D; 32]).expect("secp256k1 key");
+    let secp256k1_issuer = evm_key.signer_ref(SigningAlgorithm::Secp256k1);
+    let evm_issuer =
+        SignerRef::new(SigningAlgorithm::EthTypedData, evm_key.ethereum_address().to_vec());
+    for body in [
+        serde_json::json!({ "issuer_public_key": hex_encode_bytes(&issuer.public_key) }),
+        serde_json::json!({
+            "issuer_public_key": hex_encode_bytes(&secp256k1_issuer.public_key),
+            "algorithm": "secp256k1",
+        }),
+        serde_json::json!({
+            "issuer_public_key": format!("0x{}", hex_encode_bytes(&evm_issuer.public_key)),
+            "algorithm": "eth_typed_data",
+        }),
+        serde_json::json!({ "agent_id": "scoped-revocation-agent" }),
+        serde_json::json!({ "payment_subject": subject }),
+    ] {
+        let (status, response) = call(&app, "POST", "/v1/revocations", Some(&body));
+        assert_eq!(status, axum::http::StatusCode::OK, "{response}");
+    }
+    let (status, _) = call(
+        &app,
+        "POST",
+        "/v1/revocations",
+        Some(&serde_json::json!({ "issuer_public_key": "zz" })),
+    );
+    assert_eq!(status, axum::http::StatusCode::BAD_REQUEST);
+    // A compressed secp256k1 key is not an Ed25519 key.
+    let (status, _) = call(
+        &app,
+        "POST",
+        "/v1/revocations",
+        Some(&serde_json::json!({
+            "issuer_public_key": hex_encode_bytes(&secp256k1_issuer.public_key),
+        })),
+    );
+    assert_eq!(status, axum::http::StatusCode::BAD_REQUEST);
+
+    let tenant = store.for_tenant("default");
+    assert_eq!(tenant.check_issuer(&issuer), RevocationDecision::RevokedIssuer);
+    assert_eq!(tenant.check_issuer(&secp256k1_issuer), RevocationDecision::RevokedIssuer);
+    assert_eq!(tenant.check_issuer(&evm_issuer), RevocationDecision::RevokedIssuer);
+    assert_eq!(tenant.check_agent("scoped-revocation-agent"), RevocationDecision::RevokedAgent);
+    assert_eq!(tenant.check_subject(&subject), RevocationDecision::RevokedSubject);
+    assert_eq!(store.for_tenant("other").check_issuer(&issuer), RevocationDecision::Ok);
+
+    // Every scope emits its own event.
+    let (_, audit) = call(&app, "GET", "/v1/audit", None);
+    let events: Vec<&str> = audit["data"]
+        .as_array()
+        .expect("events")
+        .iter()
+        .map(|event| event.as_str().expect("event"))
+        .collect();
+    assert_eq!(
+        events,
+        [
+            format!("issuer_revoked:{}", hex_encode_bytes(&issuer.public_key)),
+            format!("issuer_revoked:{}", hex_encode_bytes(&secp256k1_issuer.public_key)),
+            format!("issuer_revoked:{}", hex_encode_bytes(&evm_issuer.public_key)),
+            "agent_revoked:scoped-revocation-agent".to_string(),
+            "payment_subject_revoked:scoped-revocation-payer".to_string(),
+        ]
+    );
+}
+
+// ---------------------------------------------------------------------------
+// Hosted facilitator (/v1/verify, /v1/settle, /v1/status)
+// ---------------------------------------------------------------------------
+
+fn wall_clock_ms() -> u64 {
+    std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map_or(0, |d| u64::try_from(d.as_millis()).unwrap_or(u64::MAX))
+}
+
+/// A facilitator request body signed under the demo issuer key (`[1u8; 32]`).
+fn facilitator_body(nonce: &str) -> serde_json::Value {
+    gated_facilitator_body(nonce, None, Vec::new())
+}
+
+/// Like [`facilitator_body`]; with an approver, the warrant gates the
+/// `transfer` tool on that approver and the call carries `approvals`.
+fn gated_facilitator_body(
+    nonce: &str,
+    approver: Option<&ledgerflow_core::SigningKeyPair>,
+    approvals: Vec<ledgerflow_core::SignedApproval>,
+) -> serde_json::Value {
+    use ledgerflow_core::ApprovalGate;
+
+    facilitator_body_with(
+        nonce,
+        if approver.is_some() { "transfer" } else { "" },
+        |builder| match approver {
+            Some(approver) => builder
+                .approval_gate("transfer", ApprovalGate::unconditional())
+                .approver(approver.signer_ref()),
+            None => builder,
+        },
+        approvals,
+    )
+}
+
+/// A warrant builder before its issuer and holder are set.
+type FreshWarrantBuilder = ledgerflow_core::WarrantBuilder<
+    ledgerflow_core::typestate::NoIssuer,
+    ledgerflow_core::typestate::NoHolder,
+    ledgerflow_core::typestate::Unsigned,
+>;
+
+/// Like [`facilitator_body`], calling `tool_name` under a warrant shaped by
+/// `configure`.
+fn facilitator_body_with(
+    nonce: &str,
+    tool_name: &str,
+    configure: impl FnOnce(FreshWarrantBuilder) -> FreshWarrantBuilder,
+    approvals: Vec<ledgerflow_core::SignedApproval>,
+) -> serde_json::Value {
+    use ledgerflow_core::{
+        AssetRef, MerchantConstraint, PaymentConstraint, PaymentRail, PaymentSubjectKind,
+        PaymentSubjectRef, ResourceConstraint, SigningKeyPair, WarrantBuilder, WarrantChain,
+    };
+    use ledgerflow_protocol::{
+        AcceptedQuote, HttpRequest, PaymentPayloadSeed, build_payment_payload,
+        encode_challenge_param, merchant_payment_required, wire::base64url_encode,
+    };
+
+    let now_ms = wall_clock_ms();
+    let issuer = SigningKeyPair::from_bytes(&[1_u8; 32]);
+    let holder = SigningKeyPair::from_bytes(&[2_u8; 32]);
+    let warrant = configure(WarrantBuilder::new(now_ms))
+        .warrant_id(*b"hosted-facilitat")
+        .ttl_secs(300)
+        .max_depth(1)
+        .issuer(issuer.signer_ref())
+        .holder(holder.signer_ref())
+        .merchant(MerchantConstraint::with_ids(vec!["merchant-a".to_string()]))
+        .resource(ResourceConstraint {
+            http_methods: vec!["POST".to_string()],
+            path_prefixes: vec!["/pay".to_string()],
+        })
+        .payment(
+            PaymentConstraint::new(1_000)
+                .with_asset(AssetRef::new("USDC", Some("base".to_string())))
+                .with_rails(vec![PaymentRail::Onchain])
+                .with_schemes(vec!["exact".to_string()]),
+        )
+        .sign_with(&issuer, [0_u8; 8]);
+    let accepted = AcceptedQuote::exact("USDC", 100, "merchant-a", Some("base".to_string()));
+    let challenge = merchant_payment_required(
+        "challenge-1",
+        "merchant-a",
+        "/pay",
+        vec![accepted.clone()],
+        60_000,
+    )
+    .ledgerflow
+    .expect("challenge");
+    let request = HttpRequest::new("POST", "merchant-a.example", "/pay", b"{}".to_vec());
+    let payload = build_payment_payload(
+        &challenge,
+        &request,
+        accepted,
+        WarrantChain::single(warrant),
+        PaymentPayloadSeed {
+            payment_subject: PaymentSubjectRef::new(
+                PaymentSubjectKind::Caip10,
+                "caip10:eip155:8453:0xabc123",
+            ),
+            signer: holder,
+            created_at_ms: now_ms,
+            nonce: nonce.to_string(),
+            payment_identifier: None,
+            tool_args: std::collections::BTreeMap::new(),
+            approvals,
+        },
+    )
+    .expect("payload");
+    let extension = payload.ledgerflow.as_ref().expect("extension").encode_cbor().expect("cbor");
+    serde_json::json!({
+        "payment_payload": {
+            "accepted": {
+                "scheme": payload.accepted.scheme,
+                "asset": payload.accepted.asset,
+                "amount": payload.accepted.amount.to_string(),
+                "payee_id": payload.accepted.payee_id,
+                "network": payload.accepted.network,
+            },
+            "settlement_payload": payload.settlement_payload,
+            "ledgerflow": base64url_encode(&extension),
+        },
+        "challenge": encode_challenge_param(&challenge).expect("challenge param"),
+        "request": {
+            "method": "POST",
+            "authority": "merchant-a.example",
+            "path_and_query": "/pay",
+            "body": base64url_encode(b"{}"),
+        },
+        "tool_name": tool_name,
+    })
+}
+
+fn call(
+    app: &axum::Router,
+    method: &str,
+    uri: &str,
+    body: Option<&serde_json::Value>,
+) -> (axum::http::StatusCode, serde_json::Value) {
+    tokio::runtime::Runtime::new().expect("runtime").block_on(async {
+        use tower::ServiceExt as _;
+        let request =
+            axum::http::Request::builder()
+                .method(method)
+                .uri(uri)
+                .header("content-type", "application/json")
+                .body(body.map_or_else(axum::body::Body::empty, |b| {
+                    axum::body::Body::from(b.to_string())
+                }))
+                .expect("request");
+        let response = app.clone().oneshot(request).await.expect("response");
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX).await.expect("body");
+        (status, serde_json::from_slice(&body).unwrap_or(serde_json::Value::Null))
+    })
+}
+
+#[test]
+fn api_hosted_facilitator_verifies_settles_and_reports_status() {
+    let state = ledgerflow_server::NewAppState::demo().expect("demo state");
+    let app = ledgerflow_server::api::router().with_state(state);
+    let body = facilitator_body("hosted-nonce-1");
+
+    let (status, verify) = call(&app, "POST", "/v1/verify", Some(&body));
+    assert_eq!(status, axum::http::StatusCode::OK);
+    assert_eq!(verify["data"]["status"], "verified", "{verify}");
+    assert_eq!(verify["data"]["is_valid"], true);
+    assert_eq!(verify["data"]["payer"], "caip10:eip155:8453:0xabc123");
+
+    let (_, settle) = call(&app, "POST", "/v1/settle", Some(&body));
+    assert_eq!(settle["data"]["status"], "settled", "{settle}");
+    assert_eq!(settle["data"]["rail"], "evm");
+    assert_eq!(settle["data"]["amount"], "100");
+    let transaction_id = settle["data"]["transaction_id"].as_str().expect("transaction id");
+
+    let (status, lookup) =
+        call(&app, "GET", &format!("/v1/status?transaction_id={transaction_id}"), None);
+    assert_eq!(status, axum::http::StatusCode::OK);
+    assert_eq!(lookup["data"][0]["status"], "settled");
+    let digest = verify["data"]["warrant_digest"].as_str().expect("digest").replace(':', "%3A");
+    let (_, by_warrant) = call(&app, "GET", &format!("/v1/status?warrant_digest={digest}"), None);
+    assert_eq!(by_warrant["data"][0]["transaction_id"], transaction_id);
+
+    // The same proof cannot settle twice.
+    let (_, replay) = call(&app, "POST", "/v1/settle", Some(&body));
+    assert_eq!(replay["data"]["success"], false);
+    assert_eq!(replay["data"]["verify_status"], "replayed");
+}
+
+/// An EVM rail whose first settlement fails.
+struct FlakyRail {
+    failed: std::sync::atomic::AtomicBool,
+}
+
+impl ledgerflow_facilitator::RailAdapter for FlakyRail {
+    fn kind(&self) -> ledgerflow_facilitator::RailKind {
+        ledgerflow_facilitator::RailKind::Evm
+    }
+
+    fn supports(&self, subject: &ledgerflow_facilitator::ResolvedSubject) -> bool {
+        ledgerflow_facilitator::EvmRailAdapter.supports(subject)
+    }
+
+    fn quote(
+        &self,
+        authorization: &ledgerflow_core::VerifiedAuthorization,
+    ) -> Result<ledgerflow_facilitator::RailQuote, ledgerflow_facilitator::RailError> {
+        ledgerflow_facilitator::EvmRailAdapter.quote(authorization)
+    }
+
+    fn settle(
+        &self,
+        authorization: &ledgerflow_core::VerifiedAuthorization,
+    ) -> Result<ledgerflow_facilitator::SettlementReceipt, ledgerflow_facilitator::RailError> {
+        if self.failed.swap(true, std::sync::atomic::Ordering::SeqCst) {
+            ledgerflow_facilitator::EvmRailAdapter.settle(authorization)
+        } else {
+            Err(ledgerflow_facilitator::RailError::SettlementFailed("node unavailable".to_string()))
+        }
+    }
+
+    fn verify(
+        &self,
+        receipt: &ledgerflow_facilitator::SettlementReceipt,
+    ) -> Result<ledgerflow_facilitator::VerificationResult, ledgerflow_facilitator::RailError> {
+        ledgerflow_facilitator::EvmRailAdapter.verify(receipt)
+    }
+}
+
+#[test]
+fn api_hosted_facilitator_releases_the_nonce_when_the_rail_fails() {
+    let mut state = ledgerflow_server::NewAppState::demo().expect("demo state");
+    state.settlement.adapters =
+        vec![std::sync::Arc::new(FlakyRail { failed: std::sync::atomic::AtomicBool::new(false) })];
+    let app = ledgerflow_server::api::router().with_state(state);
+    let body = facilitator_body("hosted-nonce-retry");
+
+    let (_, failed) = call(&app, "POST", "/v1/settle", Some(&body));
+    assert_eq!(failed["data"]["success"], false, "{failed}");
+    assert_eq!(failed["data"]["status"], "failed");
+    assert_eq!(failed["data"]["verify_status"], "verified");
+
+    // The same proof settles once the rail recovers, and only once.
+    let (_, settled) = call(&app, "POST", "/v1/settle", Some(&body));
+    assert_eq!(settled["data"]["status"], "settled", "{settled}");
+    let (_, replay) = call(&app, "POST", "/v1/settle", Some(&body));
+    assert_eq!(replay["data"]["verify_status"], "replayed");
+}
+
+#[test]
+fn api_hosted_facilitator_rejects_malformed_payments_and_lookups() {
+    let state = ledgerflow_server::NewAppState::demo().expect("demo state");
+    let app = ledgerflow_server::api::router().with_state(state);
+
+    let mut body = facilitator_body("hosted-nonce-2");
+    body["payment_payload"]["ledgerflow"] = serde_json::json!("not-cbor");
+    let (status, verify) = call(&app, "POST", "/v1/verify", Some(&body));
+    assert_eq!(status, axum::http::StatusCode::OK);
+    assert_eq!(verify["data"]["status"], "invalid_payment");
+    assert_eq!(verify["data"]["is_valid"], false);
+
+    let mut body = facilitator_body("hosted-nonce-3");
+    body["request"]["path_and_query"] = serde_json::json!("/pay?tampered=1");
+    let (_, verify) = call(&app, "POST", "/v1/verify", Some(&body));
+    assert_eq!(verify["data"]["status"], "unauthorized", "{verify}");
+
+    let (status, _) = call(&app, "GET", "/v1/status", None);
+    assert_eq!(status, axum::http::StatusCode::BAD_REQUEST);
+    let (status, _) = call(&app, "GET", "/v1/status?transaction_id=missing", None);
+    assert_eq!(status, axum::http::StatusCode::NOT_FOUND);
+}
+
+#[test]
+fn api_approval_inbox_collects_approvals_for_gated_payments() {
+    use ledgerflow_core::{SignedApproval, SigningKeyPair, hex_encode_bytes};
+
+    let state = ledgerflow_server::NewAppState::demo().expect("demo state");
+    let app = ledgerflow_server::api::router().with_state(state.clone());
+    let approver = SigningKeyPair::from_bytes(&[7_u8; 32]);
+
+    let body = gated_facilitator_body("gated-nonce-1", Some(&approver), Vec::new());
+    let (_, verify) = call(&app, "POST", "/v1/verify", Some(&body));
+    assert_eq!(verify["data"]["status"], "insufficient_approval", "{verify}");
+
+    let (status, inbox) = call(&app, "GET", "/v1/approvals", None);
+    assert_eq!(status, axum::http::StatusCode::OK);
+    let request = &inbox["data"][0];
+    assert_eq!(request["status"], "pending");
+    assert_eq!(request["tool_name"], "transfer");
+    assert_eq!(request["amount"], "100");
+    assert_eq!(request["min_approvals"], 1);
+    let request_hash = request["request_hash"].as_str().expect("hash").to_string();
+    assert!(state.webhook.buffered().iter().any(|event| event.kind() == "approval.requested"));
+
+    let uri = format!("/v1/approvals/{}", request_hash.replace(':', "%3A"));
+    let expires_at = wall_clock_ms() / 1_000 + 60;
+    let outsider = SigningKeyPair::from_bytes(&[8_u8; 32]);
+    let rejected =
+        SignedApproval::sign(request_hash.clone(), &outsider.signer_ref(), expires_at, &outsider);
+    let (status, _) = call(
+        &app,
+        "POST",
+        &uri,
+        Some(&serde_json::json!({
+            "approver_public_key": hex_encode_bytes(&outsider.public_key_bytes()),
+            "expires_at": expires_at,
+            "signature": hex_encode_bytes(&rejected.signature.value),
+        })),
+    );
+    assert_eq!(status, axum::http::StatusCode::BAD_REQUEST);
+
+    let approval =
+        SignedApproval::sign(request_hash, &approver.signer_ref(), expires_at, &approver);
+    let (status, submitted) = call(
+        &app,
+        "POST",
+        &uri,
+        Some(&serde_json::json!({
+            "approver_public_key": hex_encode_bytes(&approver.public_key_bytes()),
+            "expires_at": expires_at,
+            "signature": hex_encode_bytes(&approval.signature.value),
+        })),
+    );
+    assert_eq!(status, axum::http::StatusCode::OK, "{submitted}");
+    assert_eq!(submitted["data"]["status"], "approved");
+
+    let (_, waited) = call(&app, "GET", &format!("{uri}/wait?timeout_ms=10"), None);
+    assert_eq!(waited["data"]["status"], "approved");
+    assert_eq!(waited["data"]["approvals"].as_array().map(Vec::len), Some(1));
+
+    // The agent retries with the collected approval bound into its PoP.
+    let retry = gated_facilitator_body("gated-nonce-2", Some(&approver), vec![approval]);
+    let (_, verify) = call(&app, "POST", "/v1/verify", Some(&retry));
+    assert_eq!(verify["data"]["status"], "verified", "{verify}");
+}
+
+/// Vouches for one smart account's signature, as its ERC-1271
+/// `isValidSignature` would over the approval preimage.
+#[derive(Debug)]
+struct StubContractAccount {
+    account: ledgerflow_core::SignerRef,
+    signature: Vec<u8>,
+    hash: std::sync::Mutex<Option<[u8; 32]>>,
+}
+
+impl ledgerflow_core::ContractSignatureVerifier for StubContractAccount {
+    fn is_valid_signature(
+        &self,
+        account: &ledgerflow_core::SignerRef,
+        hash: [u8; 32],
+        signature: &[u8],
+    ) -> bool {
+        *self.hash.lock().expect("lock") = Some(hash);
+        account == &self.account && signature == self.signature
+    }
+}
+
+#[test]
+fn api_approval_inbox_accepts_contract_account_approvers() {
+    use ledgerflow_core::{
+        ApprovalGate, SignatureEnvelope, SignedApproval, SignerRef, SigningAlgorithm,
+        hex_encode_bytes, keccak256,
+    };
+
+    let account = SignerRef::new(SigningAlgorithm::EthPersonalSign, vec![0x5a_u8; 20]);
+    let state = ledgerflow_server::NewAppState::demo().expect("demo state");
+    let contract = std::sync::Arc::new(StubContractAccount {
+        account: account.clone(),
+        signature: vec![0x42_u8; 65],
+        hash: std::sync::Mutex::new(None),
+    });
+    let mut with_verifier = state.clone();
+    with_verifier.verification =
+        state.verification.clone().with_contract_verifier(contract.clone());
+    let app = ledgerflow_server::api::router().with_state(state);
+    let app_with_verifier = ledgerflow_server::api::router().with_state(with_verifier);
+
+    let body = facilitator_body_with(
+        "contract-gated-nonce-1",
+        "transfer",
+        |builder| {
+            builder
+                .approval_gate("transfer", ApprovalGate::unconditional())
+                .approver(account.clone())
+        },
+        Vec::new(),
+    );
+    let (_, verify) = call(&app, "POST", "/v1/verify", Some(&body));
+    assert_eq!(verify["data"]["status"], "insufficient_approval", "{verify}");
+    let (_, inbox) = call(&app, "GET", "/v1/approvals", None);
+    let request_hash = inbox["data"][0]["request_hash"].as_str().expect("hash").to_string();
+
+    let uri = format!("/v1/approvals/{}", request_hash.replace(':', "%3A"));
+    let expires_at = wall_clock_ms() / 1_000 + 60;
+    let submission = serde_json::json!({
+        "approver_public_key": hex_encode_bytes(&account.public_key),
+        "algorithm": "eth_personal_sign",
+        "expires_at": expires_at,
+        "signature": hex_encode_bytes(&[0x42_u8; 65]),
+    });
+    // Without an ERC-1271 verifier the account's signature cannot be checked.
+    let (status, _) = call(&app, "POST", &uri, Some(&submission));
+    assert_eq!(status, axum::http::StatusCode::BAD_REQUEST);
+
+    let (status, submitted) = call(&app_with_verifier, "POST", &uri, Some(&submission));
+    assert_eq!(status, axum::http::StatusCode::OK, "{submitted}");
+    assert_eq!(submitted["data"]["status"], "approved");
+    let approval = SignedApproval {
+        request_hash,
+        approver: account,
+        expires_at,
+        signature: SignatureEnvelope {
+            alg: SigningAlgorithm::EthPersonalSign,
+            value: vec![0x42_u8; 65],
+        },
+    };
+    assert_eq!(*contract.hash.lock().expect("lock"), Some(keccak256(&approval.preimage())));
+}
+
+#[test]
+fn api_approval_inbox_opens_requests_for_approver_set_gates() {
+    use ledgerflow_core::{
+        APPROVER_SET_EXTENSION, ApprovalGate, ApproverSet, InMemoryApproverSetResolver,
+        SigningKeyPair, hex_encode_bytes,
+    };
+
+    let authority = SigningKeyPair::from_bytes(&[0x21_u8; 32]);
+    let member = SigningKeyPair::from_bytes(&[0x22_u8; 32]);
+    let set =
+        ApproverSet::new("ops", vec![member.signer_ref()], 1).sign_with(&authority).expect("set");
+    let reference = set.reference().expect("ref").encode_cbor().expect("cbor");
+    let mut resolver = InMemoryApproverSetResolver::new();
+    resolver.publish(set);
+
+    let mut state = ledgerflow_server::NewAppState::demo().expect("demo state");
+    state.verification =
+        state.verification.clone().with_approver_sets(std::sync::Arc::new(resolver));
+    let app = ledgerflow_server::api::router().with_state(state);
+    let body = facilitator_body_with(
+        "set-gated-nonce-1",
+        "transfer",
+        |builder| {
+            builder
+                .approval_gate("transfer", ApprovalGate::unconditional())
+                .extension(APPROVER_SET_EXTENSION, reference)
+        },
+        Vec::new(),
+    );
+    let (_, verify) = call(&app, "POST", "/v1/verify", Some(&body));
+    assert_eq!(verify["data"]["status"], "insufficient_approval", "{verify}");
+
+    // The request names the set's current members.
+    let (_, inbox) = call(&app, "GET", "/v1/approvals", None);
+    let approvers = inbox["data"][0]["approvers"].as_array().expect("open request");
+    assert_eq!(approvers, &[serde_json::json!(hex_encode_bytes(&member.public_key_bytes()))]);
+}
+
+#[test]
+fn api_serves_issued_warrants_to_remote_repositories() {
+    use ledgerflow_core::Warrant;
+    use ledgerflow_protocol::{AsyncWarrantRepository, remote_warrants::RemoteWarrantRepository};
+
+    let state = ledgerflow_server::NewAppState::demo().expect("demo state");
+    let app = ledgerflow_server::api::router().with_state(state);
+    let (status, issued) = call(
+        &app,
+        "POST",
+        "/v1/warrants",
+        Some(&serde_json::json!({
+            "holder_public_key": "02".repeat(32),
+            "merchant": { "merchant_ids": ["merchant-a"] },
+            "payment": { "max_per_charge": 1_000 },
+        })),
+    );
+    assert_eq!(status, axum::http::StatusCode::OK, "{issued}");
+    let digest = issued["data"]["digest"].as_str().expect("digest").to_string();
+
+    let (status, found) = call(&app, "GET", &format!("/v1/warrants/{digest}"), None);
+    assert_eq!(status, axum::http::StatusCode::OK);
+    assert_eq!(found["data"]["warrant"], issued["data"]["warrant"]);
+    let (status, _) = call(&app, "GET", "/v1/warrants/sha256:00", None);
+    assert_eq!(status, axum::http::StatusCode::NOT_FOUND);
+
+    tokio::runtime::Runtime::new().expect("runtime").block_on(async {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let address = listener.local_addr().expect("address");
+        let server = tokio::spawn(axum::serve(listener, app).into_future());
+
+        let repository = RemoteWarrantRepository::new(format!("http://{address}"));
+        let warrant: Warrant = repository.load(&digest).await.expect("resolved");
+        assert_eq!(warrant.digest(), digest);
+        assert!(repository.load("sha256:00").await.is_none());
+        assert_eq!(repository.cached(), 1);
+        server.abort();
+    });
+}
+
+#[test]
+fn remote_warrant_fetches_time_out_and_misses_are_cached() {
+    use std::{
+        sync::{
+            Arc,
+            atomic::{AtomicUsize, Ordering},
+        },
+        time::{Duration, Instant},
+    };
+
+    use ledgerflow_protocol::{AsyncWarrantRepository, remote_warrants::RemoteWarrantRepository};
+
+    tokio::runtime::Runtime::new().expect("runtime").block_on(async {
+        // Accepts connections and never answers.
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let address = listener.local_addr().expect("address");
+        let connections = Arc::new(AtomicUsize::new(0));
+        let accepted = Arc::clone(&connections);
+        let server = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                accepted.fetch_add(1, Ordering::SeqCst);
+                tokio::spawn(async move {
+                    tokio::time::sleep(Duration::from_secs(30)).await;
+                    drop(stream);
+                });
+            }
+        });
+
+        let repository =
+            RemoteWarrantRepository::new(format!("http://{address}")).with_timeout_ms(200);
+        // Anything but a warrant digest is refused before a request is made.
+        for digest in ["../admin/tenants", "sha256:x?y", "sha256:00"] {
+            assert!(repository.load(digest).await.is_none());
+        }
+        assert_eq!(connections.load(Ordering::SeqCst), 0);
+
+        let digest = format!("sha256:{}", "00".repeat(32));
+        let started = Instant::now();
+        assert!(repository.load(&digest).await.is_none());
+        assert!(started.elapsed() < Duration::from_secs(5));
+        let attempted = connections.load(Ordering::SeqCst);
+        assert!(attempted > 0);
+
+        // The miss is answered locally until its window lapses.
+        assert!(repository.load(&digest).await.is_none());
+        assert_eq!(connections.load(Ordering::SeqCst), attempted);
+        server.abort();
+    });
+}
+
+#[test]
+fn api_publishes_signed_revocation_lists_to_polling_replicas() {
+    use ledgerflow_core::{RevocationCheck, RevocationDecision, SigningKeyPair, hex_encode_bytes};
+    use ledgerflow_facilitator::{FileRevocationStore, SrlSync, srl_sync::HttpSrlSource};
+
+    let state = ledgerflow_server::NewAppState::demo().expect("demo state");
+    let control_plane = SigningKeyPair::from_bytes(&[3_u8; 32]).signer_ref();
+    // A long-expired warrant the server holds, so its revocation carries the
+    // warrant's own expiry.
+    let issuer = SigningKeyPair::from_bytes(&[4_u8; 32]);
+    let expired = ledgerflow_core::WarrantBuilder::new(1_000)
+        .ttl_secs(60)
+        .issuer(issuer.signer_ref())
+        .holder(SigningKeyPair::from_bytes(&[5_u8; 32]).signer_ref())
+        .merchant(ledgerflow_core::MerchantConstraint::with_ids(vec!["merchant-a".to_string()]))
+        .payment(ledgerflow_core::PaymentConstraint::new(1_000))
+        .sign_with(&issuer, [0_u8; 8]);
+    state.warrants.lock().expect("warrants").store(expired.clone());
+    let app = ledgerflow_server::api::router().with_state(state);
+    let revoke = |warrant_id: &str| {
+        let body = serde_json::json!({ "warrant_id": warrant_id });
+        let (status, _) = call(&app, "POST", "/v1/revocations", Some(&body));
+        assert_eq!(status, axum::http::StatusCode::OK);
+    };
+    let (_, initial) = call(&app, "GET", "/v1/srl", None);
+    let base = initial["data"]["version"].as_u64().expect("version");
+    revoke(&"a1".repeat(16));
+
+    let (status, full) = call(&app, "GET", "/v1/srl", None);
+    assert_eq!(status, axum::http::StatusCode::OK);
+    assert_eq!(full["data"]["version"], base + 1);
+    assert!(full["data"]["srl"].is_string());
+    let (_, current) = call(&app, "GET", &format!("/v1/srl?since={}", base + 1), None);
+    assert!(current["data"]["srl"].is_null(), "{current}");
+
+    let dir = std::env::temp_dir().join(format!("ledgerflow-srl-replica-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).expect("create dir");
+    let path = dir.join("revocations.jsonl");
+    let _ = std::fs::remove_file(&path);
+    let replica = FileRevocationStore::open(&path).expect("replica store");
+    let sync = SrlSync::new(replica.clone(), control_plane.clone()).with_fail_closed(true);
+
+    tokio::runtime::Runtime::new().expect("runtime").block_on(async {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.expect("bind");
+        let address = listener.local_addr().expect("address");
+        let server = tokio::spawn(axum::serve(listener, app.clone()).into_future());
+        let source = HttpSrlSource::new(format!("http://{address}"));
+
+        let synced = tokio::task::spawn_blocking({
+            let sync = sync.clone();
+            let source = source.clone();
+            move || sync.sync_once(&source)
+        });
+        assert_eq!(synced.await.expect("join").expect("full sync"), base + 1);
+
+        // A later revocation arrives as a delta.
+        let body = serde_json::json!({ "warrant_id": "b2".repeat(16) });
+        let (status, _) = tokio::task::spawn_blocking({
+            let app = app.clone();
+            move || call(&app, "POST", "/v1/revocations", Some(&body))
+        })
+        .await
+        .expect("join");
+        assert_eq!(status, axum::http::StatusCode::OK);
+        let synced = tokio::task::spawn_blocking({
+            let sync = sync.clone();
+            let source = source.clone();
+            move || sync.sync_once(&source)
+        });
+        assert_eq!(synced.await.expect("join").expect("delta sync"), base + 2);
+
+        // Caller-supplied expiries cannot shorten a revocation's life.
+        let expired_id = hex_encode_bytes(&expired.id);
+        let rejected = tokio::task::spawn_blocking({
+            let app = app.clone();
+            let expired_id = expired_id.clone();
+            move || {
+                [
+                    serde_json::json!({ "warrant_id": "d4".repeat(16), "expires_at": 1 }),
+                    serde_json::json!({ "warrant_id": expired_id, "expires_at": u64::MAX }),
+                ]
+                .map(|body| call(&app, "POST", "/v1/revocations", Some(&body)).0)
+            }
+        })
+        .await
+        .expect("join");
+        assert_eq!(rejected, [axum::http::StatusCode::BAD_REQUEST; 2]);
+
+        // A revocation of a long-expired warrant is compacted out of the
+        // list; the replica still lands on the published checkpoint.
+        let body = serde_json::json!({ "warrant_id": expired_id });
+        let (_, published) = tokio::task::spawn_blocking({
+            let app = app.clone();
+            move || {
+                let (status, _) = call(&app, "POST", "/v1/revocations", Some(&body));
+                assert_eq!(status, axum::http::StatusCode::OK);
+                call(&app, "GET", "/v1/srl", None)
+            }
+        })
+        .await
+        .expect("join");
+        let synced = tokio::task::spawn_blocking({
+            let sync = sync.clone();
+            move || sync.sync_once(&source)
+        });
+        assert_eq!(synced.await.expect("join").expect("compacted sync"), base + 3);
+        assert_eq!(
+            sync.checkpoint(),
+            (base + 3, published["data"]["state_root"].as_str().expect("root").to_string())
+        );
+        server.abort();
+    });
+    // Tenant-scoped revocations replay with their scoped keys and are
+    // enforced through the replica's tenant view.
+    let tenant = sync.for_tenant("default");
+    assert_eq!(tenant.check_warrant(&[0xA1; 16]), RevocationDecision::RevokedWarrant);
+    assert_eq!(tenant.check_warrant(&[0xB2; 16]), RevocationDecision::RevokedWarrant);
+    assert_eq!(sync.for_tenant("other").check_warrant(&[0xB2; 16]), RevocationDecision::Ok);
+    assert_eq!(tenant.check_warrant(&expired.id), RevocationDecision::Ok);
+    assert!(!sync.is_stale());
+
+    // A node that never reached the control plane fails closed per tenant too.
+    let stale = SrlSync::new(replica, control_plane).with_fail_closed(true);
+    assert_eq!(
+        stale.for_tenant("default").check_warrant(&[0xA1; 16]),
+        RevocationDecision::Unavailable
+    );
+
+    let _ = std::fs::remove_file(&path);
+    let _ = std::fs::remove_dir(&dir);
+}
+
+// ---------------------------------------------------------------------------
+// Issuance through a wallet daemon
+// ---------------------------------------------------------------------------
+
+#[test]
+fn api_issues_warrants_through_a_local_rpc_wallet() {
+    use std::sync::Arc;
+
+    use ledgerflow_core::{SigningKeyPair, Warrant};
+    use ledgerflow_wallet::{
+        EmbeddedSigner, LocalRpcConfig, LocalRpcSigner, LoopbackJsonRpcServer, WalletSigner,
+    };
+
+    let issue = |state: ledgerflow_server::AppState| {
+        let app = ledgerflow_server::api::router().with_state(state);
+        call(
+            &app,
+            "POST",
+            "/v1/warrants",
+            Some(&serde_json::json!({
+                "holder_public_key": "02".repeat(32),
+                "merchant": { "merchant_ids": ["merchant-a"] },
+                "payment": { "max_per_charge": 1_000 },
+            })),
+        )
+    };
+    // The demo state trusts the `[1u8; 32]` root; point its issuer at a wallet.
+    let with_wallet = |daemon: &LoopbackJsonRpcServer| {
+        let mut state = ledgerflow_server::NewAppState::demo().expect("demo state");
+        let wallet = LocalRpcSigner::new_http(LocalRpcConfig {
+            url: daemon.url(),
+            ..LocalRpcConfig::default()
+        });
+        state.issuer = ledgerflow_server::IssuerSigner::wallet(
+            Arc::new(wallet),
+            SigningKeyPair::from_bytes(&[1_u8; 32]).signer_ref(),
+        );
+        state
+    };
+
+    // The wallet daemon holds the demo root key; the server never sees it.
+    let root = SigningKeyPair::from_bytes(&[1_u8; 32]);
+    let daemon = LoopbackJsonRpcServer::start(
+        Arc::new(EmbeddedSigner::new(root.clone())) as Arc<dyn WalletSigner>
+    )
+    .expect("wallet daemon");
+    let (status, issued) = issue(with_wallet(&daemon));
+    assert_eq!(status, axum::http::StatusCode::OK, "{issued}");
+    let encoded = issued["data"]["warrant"].as_str().expect("warrant");
+    let warrant = Warrant::decode_cbor(
+        &ledgerflow_protocol::wire::base64url_decode(encoded).expect("base64url"),
+    )
+    .expect("cbor");
+    assert_eq!(warrant.issuer, root.signer_ref());
+    assert!(warrant.verify_signature());
+
+    // A daemon that does not hold the configured issuer key fails the request.
+    let other = LoopbackJsonRpcServer::start(Arc::new(EmbeddedSigner::from_bytes(&[9_u8; 32])))
+        .expect("wallet daemon");
+    let (status, _) = issue(with_wallet(&other));
+    assert_eq!(status, axum::http::StatusCode::INTERNAL_SERVER_ERROR);
+}
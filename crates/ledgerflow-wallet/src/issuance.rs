@@ -0,0 +1,86 @@
+//! Warrant issuance through a [`WalletSigner`].
+//!
+//! Issuers whose root key lives in a wallet (a hardened local daemon, a
+//! hardware-backed signer, ...) never hand the key to LedgerFlow. The caller
+//! builds the warrant unsigned
+//! ([`WarrantBuilder::build_unsigned`](ledgerflow_core::WarrantBuilder::build_unsigned)),
+//! the wallet signs its [`Warrant::signed_message`] under
+//! [`SignDomain::Warrant`], and the envelope is attached and checked here.
+
+use ledgerflow_core::Warrant;
+
+use crate::{
+    error::WalletError,
+    signer::{SignDomain, SignRequest, WalletSigner},
+};
+
+/// Signs an unsigned warrant with the wallet key named by its `issuer`.
+///
+/// The returned warrant has been verified against its issuer, so a wallet
+/// that signs with a different key (or the wrong preimage) is reported as
+/// [`WalletError::Rejected`] instead of yielding an unverifiable warrant.
+pub fn sign_warrant(signer: &dyn WalletSigner, draft: Warrant) -> Result<Warrant, WalletError> {
+    let result = signer.sign(&SignRequest {
+        domain: SignDomain::Warrant,
+        message: draft.signed_message(),
+        key: Some(draft.issuer.clone()),
+    })?;
+    if result.signer.public_key != draft.issuer.public_key {
+        return Err(WalletError::NoMatchingKey);
+    }
+    let warrant = draft.with_signature(result.signature);
+    if !warrant.verify_signature() {
+        return Err(WalletError::Rejected(
+            "wallet signature does not verify against the warrant issuer".to_string(),
+        ));
+    }
+    Ok(warrant)
+}
+
+#[cfg(test)]
+mod tests {
+    #![allow(clippy::expect_used)]
+    use ledgerflow_core::{PaymentConstraint, SigningKeyPair, WarrantBuilder};
+
+    use super::*;
+    use crate::{
+        embedded::EmbeddedSigner,
+        local_rpc::{LocalRpcSigner, MockJsonRpcTransport},
+        server::handle_jsonrpc,
+    };
+
+    fn draft(issuer: &SigningKeyPair) -> Warrant {
+        WarrantBuilder::new(1_700_000_000_000)
+            .issuer(issuer.signer_ref())
+            .holder(SigningKeyPair::from_bytes(&[0x52; 32]).signer_ref())
+            .payment(PaymentConstraint::new(1_000))
+            .build_unsigned([0_u8; 8])
+    }
+
+    #[test]
+    fn embedded_wallet_issues_the_same_warrant_as_the_raw_key() {
+        let key = SigningKeyPair::from_bytes(&[0x51; 32]);
+        let issued = sign_warrant(&EmbeddedSigner::new(key.clone()), draft(&key)).expect("issue");
+        assert!(issued.verify_signature());
+        assert_eq!(issued, draft(&key).sign_with(&key));
+    }
+
+    #[test]
+    fn local_rpc_wallet_issues_verifiable_warrants() {
+        let key = SigningKeyPair::from_bytes(&[0x51; 32]);
+        let daemon = EmbeddedSigner::new(key.clone());
+        let wallet = LocalRpcSigner::new(MockJsonRpcTransport::new(move |method, params| {
+            handle_jsonrpc(&daemon, method, &params)
+        }));
+        let issued = sign_warrant(&wallet, draft(&key)).expect("issue");
+        assert!(issued.verify_signature());
+    }
+
+    #[test]
+    fn a_wallet_without_the_issuer_key_is_refused() {
+        let wallet = EmbeddedSigner::new(SigningKeyPair::from_bytes(&[0x53; 32]));
+        let error = sign_warrant(&wallet, draft(&SigningKeyPair::from_bytes(&[0x51; 32])))
+            .expect_err("foreign key");
+        assert!(matches!(error, WalletError::NoMatchingKey));
+    }
+}
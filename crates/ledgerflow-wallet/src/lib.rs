@@ -14,12 +14,16 @@
 //! - [`server::EmbeddedWalletServer`]: an in-memory JSON-RPC 2.0 server over a [`WalletSigner`],
 //!   plus (feature `http`) a loopback HTTP listener for end-to-end use with
 //!   [`local_rpc::HttpJsonRpcTransport`].
+//!
+//! Issuers holding their root key in a wallet sign warrants with
+//! [`issuance::sign_warrant`].
 
 #![allow(missing_docs)]
 
 pub mod approvals;
 pub mod embedded;
 pub mod error;
+pub mod issuance;
 pub mod local_rpc;
 pub mod server;
 pub mod signer;
@@ -32,6 +36,7 @@ pub use crate::{
     approvals::request_approval,
     embedded::EmbeddedSigner,
     error::WalletError,
+    issuance::sign_warrant,
     local_rpc::{
         JsonRpcError, JsonRpcRequest, JsonRpcResponse, LocalRpcConfig, LocalRpcSigner,
         MockJsonRpcTransport, RpcTransport,
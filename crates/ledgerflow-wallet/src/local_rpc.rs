@@ -236,19 +236,18 @@ fn parse_sign_result(value: &serde_json::Value) -> Result<SignResult, WalletErro
         .get("value")
         .and_then(|v| v.as_str())
         .ok_or_else(|| WalletError::InvalidPayload("missing signature.value".to_string()))?;
+    // The envelope is produced by the signer's key, so it shares its algorithm.
+    let alg = match alg {
+        "secp256k1" => SigningAlgorithm::Secp256k1,
+        _ => SigningAlgorithm::Ed25519,
+    };
     Ok(SignResult {
         signer: SignerRef {
-            alg: match alg {
-                "secp256k1" => SigningAlgorithm::Secp256k1,
-                _ => SigningAlgorithm::Ed25519,
-            },
+            alg,
             public_key: base64_decode(public_key)?,
             key_id: signer.get("key_id").and_then(|v| v.as_str()).map(str::to_string),
         },
-        signature: SignatureEnvelope {
-            alg: SigningAlgorithm::Ed25519,
-            value: base64_decode(sig_value)?,
-        },
+        signature: SignatureEnvelope { alg, value: base64_decode(sig_value)? },
     })
 }
 